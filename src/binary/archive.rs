@@ -0,0 +1,368 @@
+use std::fmt;
+
+use nom::bytes::complete::{tag, take};
+use nom::combinator::map;
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{be_u32, be_u64, le_u32, le_u64};
+use nom::sequence::tuple;
+
+use super::Object;
+use crate::error::BinDumpResult;
+use crate::parse;
+
+pub(crate) const MAGIC: &[u8] = b"!<arch>\n";
+
+/// The flavour of `ar` archive, which decides how long names and the symbol table are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    /// System V and GNU archives, with `/` and `//` members
+    Gnu,
+    /// BSD and Apple archives, with `#1/` names and `__.SYMDEF` members
+    Bsd,
+    /// No special members, so the flavour can't be told apart
+    Unknown,
+}
+
+/// A static library, made up of a number of object files
+#[derive(Debug)]
+pub struct Archive {
+    pub kind: ArchiveKind,
+    /// Symbols defined by the members, from the archive's symbol table
+    pub symbols: Vec<ArchiveSymbol>,
+    pub members: Vec<ArchiveMember>,
+}
+
+#[derive(Debug)]
+pub struct ArchiveSymbol {
+    pub name: String,
+    /// Offset of the header of the member that defines this symbol
+    pub member_offset: u64,
+}
+
+#[derive(Debug)]
+pub struct ArchiveMember {
+    pub name: String,
+    /// Offset of the member header in the archive
+    pub offset: u64,
+    pub timestamp: u64,
+    pub owner_id: u64,
+    pub group_id: u64,
+    pub mode: u64,
+    pub size: u64,
+    /// The member parsed as an object file
+    pub object: BinDumpResult<Object>,
+}
+
+/// The fields of a member header, before any long name has been resolved
+struct MemberHeader<'a> {
+    name: &'a str,
+    timestamp: u64,
+    owner_id: u64,
+    group_id: u64,
+    mode: u64,
+    size: u64,
+}
+
+impl<'a> MemberHeader<'a> {
+    fn parse(input: parse::Input<'a>) -> parse::ParseResult<'a, Self> {
+        let (input, (name, timestamp, owner_id, group_id, mode, size, _)) = context(
+            "Parse Archive Member Header",
+            tuple((
+                map(take(16usize), |name: parse::Input| {
+                    std::str::from_utf8(name).unwrap_or_default().trim_end()
+                }),
+                ascii_number(12, 10),
+                ascii_number(6, 10),
+                ascii_number(6, 10),
+                ascii_number(8, 8),
+                ascii_number(10, 10),
+                tag(b"`\n"),
+            )),
+        )(input)?;
+        Ok((
+            input,
+            Self {
+                name,
+                timestamp,
+                owner_id,
+                group_id,
+                mode,
+                size,
+            },
+        ))
+    }
+}
+
+/// Parses a space padded ASCII number of `width` characters. Blank fields parse as 0.
+fn ascii_number<'a>(
+    width: usize,
+    radix: u32,
+) -> impl FnMut(parse::Input<'a>) -> parse::ParseResult<'a, u64> {
+    map(take(width), move |field: parse::Input| {
+        std::str::from_utf8(field)
+            .ok()
+            .and_then(|field| u64::from_str_radix(field.trim(), radix).ok())
+            .unwrap_or(0)
+    })
+}
+
+impl Archive {
+    pub(crate) fn parse(data: &[u8]) -> BinDumpResult<Self> {
+        let (mut input, _) = context("Archive Magic", tag(MAGIC))(data)?;
+        let mut kind = ArchiveKind::Unknown;
+        let mut symbols = Vec::new();
+        let mut long_names: &[u8] = &[];
+        let mut members = Vec::new();
+
+        while !input.is_empty() {
+            let offset = (data.len() - input.len()) as u64;
+            let (rest, header) = MemberHeader::parse(input)?;
+            let size = (header.size as usize).min(rest.len());
+            let mut contents = &rest[..size];
+            // Members are aligned to 2 bytes
+            input = &rest[(size + size % 2).min(rest.len())..];
+
+            let name = match header.name {
+                // GNU symbol tables. Windows import libraries have a second `/` member in a
+                // different format, which only the first one is read for.
+                "/" | "/SYM64/" if symbols.is_empty() => {
+                    kind = ArchiveKind::Gnu;
+                    symbols = parse_gnu_symbol_table(contents, header.name == "/SYM64/")?;
+                    continue;
+                }
+                "/" | "/SYM64/" => continue,
+                "//" => {
+                    kind = ArchiveKind::Gnu;
+                    long_names = contents;
+                    continue;
+                }
+                name => {
+                    if let Some(length) = name.strip_prefix("#1/") {
+                        // BSD long names are stored at the start of the member contents
+                        let length = length.parse::<usize>().unwrap_or(0).min(contents.len());
+                        let (name, rest) = contents.split_at(length);
+                        contents = rest;
+                        let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+                        String::from_utf8_lossy(&name[..end]).into_owned()
+                    } else if let Some(long_name_offset) = name
+                        .strip_prefix('/')
+                        .and_then(|offset| offset.parse::<usize>().ok())
+                    {
+                        let name = long_names.get(long_name_offset..).unwrap_or_default();
+                        let end = name
+                            .windows(2)
+                            .position(|window| window == b"/\n")
+                            .or_else(|| name.iter().position(|&b| b == b'\n'))
+                            .unwrap_or(name.len());
+                        String::from_utf8_lossy(&name[..end]).into_owned()
+                    } else {
+                        name.trim_end_matches('/').to_string()
+                    }
+                }
+            };
+
+            if name.starts_with("__.SYMDEF") {
+                kind = ArchiveKind::Bsd;
+                symbols = parse_bsd_symbol_table(contents, name.starts_with("__.SYMDEF_64"))?;
+                continue;
+            }
+
+            members.push(ArchiveMember {
+                name,
+                offset,
+                timestamp: header.timestamp,
+                owner_id: header.owner_id,
+                group_id: header.group_id,
+                mode: header.mode,
+                size: contents.len() as u64,
+                object: Object::parse(contents.to_vec()),
+            });
+        }
+
+        Ok(Self {
+            kind,
+            symbols,
+            members,
+        })
+    }
+
+    /// The member whose header is at `offset`, as referenced by the symbol table
+    pub fn member_at(&self, offset: u64) -> Option<&ArchiveMember> {
+        self.members.iter().find(|member| member.offset == offset)
+    }
+}
+
+/// Reads a run of null terminated strings
+fn strings(input: parse::Input, number: usize) -> Vec<String> {
+    input
+        .split(|&b| b == 0)
+        .take(number)
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+        .collect()
+}
+
+/// Parses the GNU `/` symbol table: a big endian count, then the member offsets, then the names
+fn parse_gnu_symbol_table(
+    input: parse::Input,
+    is_64_bit: bool,
+) -> BinDumpResult<Vec<ArchiveSymbol>> {
+    let (input, offsets) = if is_64_bit {
+        let (input, number) = context("Parse Archive Symbol Count", be_u64)(input)?;
+        count(be_u64, number as usize)(input)?
+    } else {
+        let (input, number) = context("Parse Archive Symbol Count", be_u32)(input)?;
+        let (input, offsets) = count(be_u32, number as usize)(input)?;
+        (input, offsets.into_iter().map(u64::from).collect())
+    };
+    let names = strings(input, offsets.len());
+    Ok(names
+        .into_iter()
+        .zip(offsets)
+        .map(|(name, member_offset)| ArchiveSymbol {
+            name,
+            member_offset,
+        })
+        .collect())
+}
+
+/// Parses a BSD `__.SYMDEF` ranlib table: the size of the ranlib entries, the entries of string
+/// offset and member offset, then the size of the string table and the strings
+fn parse_bsd_symbol_table(
+    input: parse::Input,
+    is_64_bit: bool,
+) -> BinDumpResult<Vec<ArchiveSymbol>> {
+    let (entries, string_table) = if is_64_bit {
+        let (input, size) = context("Parse Ranlib Size", le_u64)(input)?;
+        let (input, entries) = count(tuple((le_u64, le_u64)), size as usize / 16)(input)?;
+        let (input, string_size) = le_u64(input)?;
+        (entries, &input[..(string_size as usize).min(input.len())])
+    } else {
+        let (input, size) = context("Parse Ranlib Size", le_u32)(input)?;
+        let (input, entries) = count(
+            map(tuple((le_u32, le_u32)), |(name, offset)| {
+                (name as u64, offset as u64)
+            }),
+            size as usize / 8,
+        )(input)?;
+        let (input, string_size) = le_u32(input)?;
+        (entries, &input[..(string_size as usize).min(input.len())])
+    };
+    Ok(entries
+        .into_iter()
+        .map(|(name_offset, member_offset)| {
+            let name = string_table.get(name_offset as usize..).unwrap_or_default();
+            ArchiveSymbol {
+                name: strings(name, 1).pop().unwrap_or_default(),
+                member_offset,
+            }
+        })
+        .collect())
+}
+
+impl fmt::Display for Archive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Archive: {} members", self.members.len())?;
+        writeln!(f, "Kind: {:?}", self.kind)?;
+        writeln!(f, "Number of symbols: {}", self.symbols.len())?;
+        for symbol in &self.symbols {
+            let member = self
+                .member_at(symbol.member_offset)
+                .map(|member| member.name.as_str())
+                .unwrap_or("?");
+            writeln!(f, "{} in {}", symbol.name, member)?;
+        }
+        for (i, member) in self.members.iter().enumerate() {
+            writeln!(f, "Member {}: {}", i, member.name)?;
+            writeln!(f, "Offset: {:x}", member.offset)?;
+            writeln!(f, "Timestamp: {}", member.timestamp)?;
+            writeln!(f, "Owner Id: {}", member.owner_id)?;
+            writeln!(f, "Group Id: {}", member.group_id)?;
+            writeln!(f, "Mode: {:o}", member.mode)?;
+            writeln!(f, "Size: {} bytes", member.size)?;
+            match &member.object {
+                Ok(object) => writeln!(f, "{}", object)?,
+                Err(e) => writeln!(f, "Error {}", e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A member with the given header name, padded to 2 bytes
+    fn member(name: &str, contents: &[u8]) -> Vec<u8> {
+        let header = format!(
+            "{:16}{:12}{:6}{:6}{:8}{:10}`\n",
+            name,
+            0,
+            0,
+            0,
+            644,
+            contents.len()
+        );
+        let mut data = header.into_bytes();
+        data.extend_from_slice(contents);
+        if data.len() % 2 != 0 {
+            data.push(b'\n');
+        }
+        data
+    }
+
+    #[test]
+    fn reads_gnu_long_names_and_symbols() {
+        let long_name = "a_very_long_member_name.o";
+        let mut data = MAGIC.to_vec();
+        // The symbol table, long names and first member are 0x50, 0x58 and 0x40 bytes long
+        let first = (MAGIC.len() + 0x50 + 0x58) as u32;
+        let mut symbol_table = 2u32.to_be_bytes().to_vec();
+        symbol_table.extend_from_slice(&first.to_be_bytes());
+        symbol_table.extend_from_slice(&(first + 0x40).to_be_bytes());
+        symbol_table.extend_from_slice(b"foo\0bar\0");
+        data.extend(member("/", &symbol_table));
+        data.extend(member("//", format!("{}/\n", long_name).as_bytes()));
+        data.extend(member("/0", &[0; 4]));
+        data.extend(member("short.o/", &[0; 4]));
+
+        let archive = Archive::parse(&data).unwrap();
+        assert_eq!(archive.kind, ArchiveKind::Gnu);
+        let names: Vec<_> = archive.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, [long_name, "short.o"]);
+        assert_eq!(archive.symbols[0].name, "foo");
+        assert_eq!(archive.symbols[1].name, "bar");
+        let defining = |symbol: &ArchiveSymbol| {
+            archive
+                .member_at(symbol.member_offset)
+                .map(|member| member.name.as_str())
+        };
+        assert_eq!(defining(&archive.symbols[0]), Some(long_name));
+        assert_eq!(defining(&archive.symbols[1]), Some("short.o"));
+    }
+
+    #[test]
+    fn reads_bsd_long_names_and_symdef() {
+        let mut data = MAGIC.to_vec();
+        // One ranlib entry for `_foo`, defined by the member after the 0x64 byte symbol table
+        let first = (MAGIC.len() + 0x64) as u32;
+        let mut symbol_table = b"__.SYMDEF SORTED".to_vec();
+        for field in [8, 0, first, 8] {
+            symbol_table.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        symbol_table.extend_from_slice(b"_foo\0\0\0\0");
+        data.extend(member("#1/16", &symbol_table));
+        data.extend(member("#1/12", b"long_name.o\0\0\0\0\0"));
+
+        let archive = Archive::parse(&data).unwrap();
+        assert_eq!(archive.kind, ArchiveKind::Bsd);
+        assert_eq!(archive.members.len(), 1);
+        assert_eq!(archive.members[0].name, "long_name.o");
+        assert_eq!(archive.members[0].size, 4);
+        assert_eq!(archive.symbols.len(), 1);
+        assert_eq!(archive.symbols[0].name, "_foo");
+        let member = archive.member_at(archive.symbols[0].member_offset).unwrap();
+        assert_eq!(member.name, "long_name.o");
+    }
+}
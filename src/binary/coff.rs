@@ -0,0 +1,471 @@
+pub mod import;
+pub mod relocations;
+pub mod symbols;
+
+use std::fmt;
+
+use import::ImportObject;
+use nom::bytes::complete::{tag, take};
+use nom::combinator::map;
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+use relocations::{Relocation, RELOCATION_SIZE};
+use symbols::{Symbol, BIGOBJ_SYMBOL_SIZE, SYMBOL_SIZE};
+
+use super::pe::header::{flag_names, CoffHeader, Machine, CHARACTERISTICS};
+use super::pe::sections::SectionHeader;
+use super::Object;
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse::{self, string_at, truncated};
+
+/// The start of an `ANON_OBJECT_HEADER`: an unknown machine followed by `0xffff`
+pub(crate) const ANONYMOUS_MAGIC: &[u8] = b"\0\0\xff\xff";
+
+/// The class ID of `/bigobj` objects, `{D1BAA1C7-BAEE-4BA9-AF20-FAF66AA4DCB8}`
+const BIGOBJ_CLASS_ID: [u8; 16] = [
+    0xc7, 0xa1, 0xba, 0xd1, 0xee, 0xba, 0xa9, 0x4b, 0xaf, 0x20, 0xfa, 0xf6, 0x6a, 0xa4, 0xdc, 0xb8,
+];
+
+/// Set on sections with more relocations than fit the 16 bit count
+const IMAGE_SCN_LNK_NRELOC_OVFL: u32 = 0x0100_0000;
+
+/// Whether a file starting with `machine` looks like an object file. Objects have no magic, so
+/// this is all there is to go on.
+pub(crate) fn is_object_machine(machine: u16) -> bool {
+    !matches!(Machine::from(machine), Machine::Unknown | Machine::Other(_))
+}
+
+/// Parses a file with an anonymous object header, which is either a short import object or a
+/// `/bigobj` object
+pub(crate) fn parse_anonymous(data: Vec<u8>) -> BinDumpResult<Object> {
+    let (_, (_, version)) = context(
+        "Parse Anonymous Object Header",
+        tuple((tag(ANONYMOUS_MAGIC), le_u16)),
+    )(data.as_slice())?;
+    if version == 0 {
+        return Ok(Object::CoffImport(ImportObject::parse(&data)?));
+    }
+    if version >= 2 && data.get(12..28) == Some(&BIGOBJ_CLASS_ID) {
+        return Ok(Object::Coff(Coff::parse(data)?));
+    }
+    Err(BinDumpError::Unsupported {
+        format: "anonymous objects other than import objects and /bigobj objects".to_string(),
+    })
+}
+
+/// The file header of an object, from either the regular COFF header or the `/bigobj` one
+#[derive(Debug)]
+pub struct FileHeader {
+    pub machine: Machine,
+    /// The version of the `ANON_OBJECT_HEADER_BIGOBJ`, for `/bigobj` objects
+    pub bigobj_version: Option<u16>,
+    pub number_of_sections: u32,
+    pub time_date_stamp: u32,
+    pub pointer_to_symbol_table: u32,
+    pub number_of_symbols: u32,
+    /// Always 0 for `/bigobj` objects, whose header doesn't have the field
+    pub characteristics: u16,
+}
+
+impl FileHeader {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        if !input.starts_with(ANONYMOUS_MAGIC) {
+            let (input, header) = CoffHeader::parse(input)?;
+            // Objects don't have an optional header, but skip one if it's there
+            let (input, _) = context(
+                "Parse Optional Header",
+                take(header.size_of_optional_header),
+            )(input)?;
+            return Ok((
+                input,
+                Self {
+                    machine: header.machine,
+                    bigobj_version: None,
+                    number_of_sections: header.number_of_sections as u32,
+                    time_date_stamp: header.time_date_stamp,
+                    pointer_to_symbol_table: header.pointer_to_symbol_table,
+                    number_of_symbols: header.number_of_symbols,
+                    characteristics: header.characteristics,
+                },
+            ));
+        }
+        let (
+            input,
+            (
+                _,
+                version,
+                machine,
+                time_date_stamp,
+                _,
+                number_of_sections,
+                pointer_to_symbol_table,
+                number_of_symbols,
+            ),
+        ) = context(
+            "Parse BigObj Header",
+            tuple((
+                tag(ANONYMOUS_MAGIC),
+                le_u16,
+                map(le_u16, Machine::from),
+                le_u32,
+                // The class ID, then the size, flags and metadata fields that are always 0
+                take(32usize),
+                le_u32,
+                le_u32,
+                le_u32,
+            )),
+        )(input)?;
+        Ok((
+            input,
+            Self {
+                machine,
+                bigobj_version: Some(version),
+                number_of_sections,
+                time_date_stamp,
+                pointer_to_symbol_table,
+                number_of_symbols,
+                characteristics: 0,
+            },
+        ))
+    }
+
+    pub fn is_bigobj(&self) -> bool {
+        self.bigobj_version.is_some()
+    }
+
+    pub fn characteristic_names(&self) -> Vec<&'static str> {
+        flag_names(self.characteristics, &CHARACTERISTICS)
+    }
+}
+
+/// A COFF object file, as compilers produce for the MSVC toolchain
+#[derive(Debug)]
+pub struct Coff {
+    header: FileHeader,
+    sections: Vec<SectionHeader>,
+    /// The relocations of each section, in the order of the section table
+    relocations: Vec<BinDumpResult<Vec<Relocation>>>,
+    symbols: BinDumpResult<Vec<Symbol>>,
+    string_table: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl Coff {
+    pub(crate) fn parse(data: Vec<u8>) -> BinDumpResult<Self> {
+        let (input, header) = FileHeader::parse(&data)?;
+        let (_, mut sections) = context(
+            "Parse Section Headers",
+            count(SectionHeader::parse, header.number_of_sections as usize),
+        )(input)?;
+
+        // The string table follows the symbol table, and starts with its size
+        let symbol_size = if header.is_bigobj() {
+            BIGOBJ_SYMBOL_SIZE
+        } else {
+            SYMBOL_SIZE
+        };
+        let string_table = (header.pointer_to_symbol_table as usize)
+            .checked_add(symbol_size * header.number_of_symbols as usize)
+            .filter(|_| header.pointer_to_symbol_table != 0)
+            .and_then(|start| data.get(start..))
+            .and_then(|input| {
+                let size = u32::from_le_bytes(input.get(..4)?.try_into().ok()?) as usize;
+                Some(input[..size.clamp(4, input.len())].to_vec())
+            })
+            .unwrap_or_default();
+        for section in &mut sections {
+            if let Some(name) = long_section_name(&section.name, &string_table) {
+                section.name = name;
+            }
+        }
+
+        let mut coff = Self {
+            header,
+            sections,
+            relocations: Vec::new(),
+            symbols: Ok(Vec::new()),
+            string_table,
+            data,
+        };
+        coff.symbols = coff.parse_symbols();
+        coff.relocations = coff
+            .sections
+            .iter()
+            .map(|section| coff.parse_relocations(section))
+            .collect();
+        Ok(coff)
+    }
+
+    fn parse_symbols(&self) -> BinDumpResult<Vec<Symbol>> {
+        if self.header.pointer_to_symbol_table == 0 {
+            return Ok(Vec::new());
+        }
+        let bigobj = self.header.is_bigobj();
+        let mut input = self
+            .data
+            .get(self.header.pointer_to_symbol_table as usize..)
+            .ok_or_else(|| truncated("symbols"))?;
+        let mut symbols = Vec::new();
+        let mut index = 0;
+        while index < self.header.number_of_symbols {
+            let (rest, symbol) = Symbol::parse(input, index, bigobj, &self.string_table)?;
+            index += 1 + symbol.number_of_aux_symbols as u32;
+            symbols.push(symbol);
+            input = rest;
+        }
+        Ok(symbols)
+    }
+
+    fn parse_relocations(&self, section: &SectionHeader) -> BinDumpResult<Vec<Relocation>> {
+        let machine = self.header.machine;
+        let mut number = section.number_of_relocations as usize;
+        let mut input = self
+            .data
+            .get(section.pointer_to_relocations as usize..)
+            .filter(|_| number != 0)
+            .unwrap_or_default();
+        // When the count overflows, the first relocation holds the real count, itself included
+        if section.characteristics & IMAGE_SCN_LNK_NRELOC_OVFL != 0 && number == 0xffff {
+            let (rest, first) = Relocation::parse(input, machine)?;
+            number = (first.virtual_address as usize).saturating_sub(1);
+            input = rest;
+        }
+        if input.len() < number.saturating_mul(RELOCATION_SIZE) {
+            return Err(truncated("relocations"));
+        }
+        let (_, mut relocations) = context(
+            "Parse Relocations",
+            count(|input| Relocation::parse(input, machine), number),
+        )(input)?;
+        for relocation in &mut relocations {
+            relocation.symbol = self
+                .symbol_at(relocation.symbol_table_index)
+                .map(|symbol| symbol.name.clone());
+        }
+        Ok(relocations)
+    }
+
+    pub fn header(&self) -> &FileHeader {
+        &self.header
+    }
+
+    pub fn sections(&self) -> &[SectionHeader] {
+        &self.sections
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<&SectionHeader> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// The contents of a section as stored in the file
+    pub fn section_data(&self, section: &SectionHeader) -> &[u8] {
+        let start = (section.pointer_to_raw_data as usize).min(self.data.len());
+        let end = start
+            .saturating_add(section.size_of_raw_data as usize)
+            .min(self.data.len());
+        &self.data[start..end]
+    }
+
+    /// The relocations of the section at `index` in the section table
+    pub fn relocations(&self, index: usize) -> &[Relocation] {
+        match self.relocations.get(index) {
+            Some(Ok(relocations)) => relocations,
+            _ => &[],
+        }
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        match &self.symbols {
+            Ok(symbols) => symbols,
+            Err(_) => &[],
+        }
+    }
+
+    /// The symbol at an index of the symbol table, which is how relocations refer to them
+    pub fn symbol_at(&self, index: u32) -> Option<&Symbol> {
+        let symbols = self.symbols();
+        let found = symbols.binary_search_by_key(&index, |symbol| symbol.index);
+        symbols.get(found.ok()?)
+    }
+
+    /// Reads the string at an offset of the string table. The offsets count the size field.
+    pub fn string(&self, offset: u32) -> Option<String> {
+        (offset as usize >= 4 && (offset as usize) < self.string_table.len())
+            .then(|| string_at(&self.string_table, offset as usize))
+    }
+}
+
+/// Resolves a `/offset` section name, with a decimal offset, or a `//offset` one, with a
+/// base64 offset, through the string table
+fn long_section_name(name: &str, string_table: &[u8]) -> Option<String> {
+    let offset = if let Some(digits) = name.strip_prefix("//") {
+        digits.bytes().try_fold(0usize, |offset, digit| {
+            let value = match digit {
+                b'A'..=b'Z' => digit - b'A',
+                b'a'..=b'z' => digit - b'a' + 26,
+                b'0'..=b'9' => digit - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => return None,
+            };
+            offset.checked_mul(64)?.checked_add(value as usize)
+        })?
+    } else {
+        name.strip_prefix('/')?.parse().ok()?
+    };
+    (offset < string_table.len()).then(|| string_at(string_table, offset))
+}
+
+impl fmt::Display for Coff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = &self.header;
+        writeln!(f, "COFF: {} Architecture", header.machine)?;
+        if let Some(version) = header.bigobj_version {
+            writeln!(f, "BigObj Version: {}", version)?;
+        }
+        writeln!(f, "Time Date Stamp: {:#x}", header.time_date_stamp)?;
+        writeln!(
+            f,
+            "Pointer To Symbol Table: {:#x}",
+            header.pointer_to_symbol_table
+        )?;
+        writeln!(f, "Number Of Symbols: {}", header.number_of_symbols)?;
+        if !header.is_bigobj() {
+            writeln!(
+                f,
+                "Characteristics: {:#x} ({})",
+                header.characteristics,
+                header.characteristic_names().join(", ")
+            )?;
+        }
+        writeln!(f, "Sections:")?;
+        for (i, (section, relocations)) in self.sections.iter().zip(&self.relocations).enumerate() {
+            // Section numbers in the symbol table are one based
+            writeln!(f, "Section {}", i + 1)?;
+            write!(f, "{}", section)?;
+            if let Some(alignment) = section.alignment() {
+                writeln!(f, "Alignment: {}", alignment)?;
+            }
+            match relocations {
+                Ok(relocations) if relocations.is_empty() => {}
+                Ok(relocations) => {
+                    writeln!(f, "Relocations:")?;
+                    for relocation in relocations {
+                        writeln!(f, "  {}", relocation)?;
+                    }
+                }
+                Err(e) => writeln!(f, "Relocations: Error {}", e)?,
+            }
+            writeln!(f)?;
+        }
+        match &self.symbols {
+            Ok(symbols) if symbols.is_empty() => {}
+            Ok(symbols) => {
+                writeln!(f, "Symbols:")?;
+                for symbol in symbols {
+                    writeln!(f, "{}", symbol)?;
+                }
+            }
+            Err(e) => writeln!(f, "Symbols: Error {}", e)?,
+        }
+        writeln!(f, "String Table Size: {:#x}", self.string_table.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symbols::StorageClass;
+
+    fn u32s(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    /// An x64 object with one `.text` section and a `main` symbol, using the `/bigobj` header
+    /// and symbol layout when asked to
+    fn object(bigobj: bool) -> Vec<u8> {
+        let mut data = if bigobj {
+            let mut header = ANONYMOUS_MAGIC.to_vec();
+            header.extend_from_slice(&[2, 0, 0x64, 0x86, 0, 0, 0, 0]);
+            header.extend_from_slice(&BIGOBJ_CLASS_ID);
+            header.resize(44, 0);
+            header.extend(u32s(&[1, 0, 1]));
+            header
+        } else {
+            let mut header = vec![0x64, 0x86, 1, 0, 0, 0, 0, 0];
+            header.extend(u32s(&[0, 1]));
+            header.extend_from_slice(&[0; 4]);
+            header
+        };
+        // The symbol table follows the section header and the section contents
+        let symbol_table = data.len() as u32 + 40 + 4;
+        let pointer = if bigobj { 48 } else { 8 };
+        data[pointer..pointer + 4].copy_from_slice(&symbol_table.to_le_bytes());
+
+        data.extend_from_slice(b".text\0\0\0");
+        data.extend(u32s(&[0, 0, 4, symbol_table - 4, 0, 0, 0, 0x6000_0020]));
+        data.extend_from_slice(&[0xc3, 0xcc, 0xcc, 0xcc]);
+        data.extend_from_slice(b"main\0\0\0\0");
+        data.extend(u32s(&[0]));
+        if bigobj {
+            data.extend(u32s(&[1]));
+        } else {
+            data.extend_from_slice(&[1, 0]);
+        }
+        data.extend_from_slice(&[0x20, 0, 2, 0]);
+        data.extend(u32s(&[4]));
+        data
+    }
+
+    fn check_contents(coff: &Coff) {
+        assert_eq!(coff.header().machine, Machine::Amd64);
+        assert_eq!(coff.sections().len(), 1);
+        let text = coff.section_by_name(".text").unwrap();
+        assert_eq!(coff.section_data(text), [0xc3, 0xcc, 0xcc, 0xcc]);
+        let symbol = &coff.symbols()[0];
+        assert_eq!(symbol.name, "main");
+        assert_eq!(symbol.section_number, 1);
+        assert_eq!(symbol.storage_class, StorageClass::External);
+    }
+
+    #[test]
+    fn detects_bigobj_objects() {
+        let coff = match parse_anonymous(object(true)).unwrap() {
+            Object::Coff(coff) => coff,
+            object => panic!("{:?} isn't a COFF object", object),
+        };
+        assert!(coff.header().is_bigobj());
+        assert_eq!(coff.header().bigobj_version, Some(2));
+        check_contents(&coff);
+    }
+
+    #[test]
+    fn parses_regular_objects() {
+        let coff = Coff::parse(object(false)).unwrap();
+        assert!(!coff.header().is_bigobj());
+        check_contents(&coff);
+    }
+
+    #[test]
+    fn rejects_other_anonymous_objects() {
+        // Version 1 anonymous objects have no class ID
+        let mut data = object(true);
+        data[4] = 1;
+        assert!(matches!(
+            parse_anonymous(data),
+            Err(BinDumpError::Unsupported { .. })
+        ));
+
+        let mut data = object(true);
+        data[12] ^= 0xff;
+        assert!(matches!(
+            parse_anonymous(data),
+            Err(BinDumpError::Unsupported { .. })
+        ));
+    }
+}
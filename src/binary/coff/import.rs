@@ -0,0 +1,170 @@
+use std::fmt;
+
+use nom::bytes::complete::tag;
+use nom::combinator::map;
+use nom::error::context;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+
+use super::ANONYMOUS_MAGIC;
+use crate::binary::pe::header::Machine;
+use crate::error::BinDumpResult;
+use crate::parse::string_at;
+
+// From winnt.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportType {
+    Code,
+    Data,
+    Const,
+    Other(u16),
+}
+
+impl From<u16> for ImportType {
+    fn from(typ: u16) -> Self {
+        match typ {
+            0 => ImportType::Code,
+            1 => ImportType::Data,
+            2 => ImportType::Const,
+            typ => ImportType::Other(typ),
+        }
+    }
+}
+
+impl fmt::Display for ImportType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportType::Other(typ) => write!(f, "Unknown ({:#x})", typ),
+            typ => write!(f, "{:?}", typ),
+        }
+    }
+}
+
+/// How the name the DLL exports is derived from the symbol name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportNameType {
+    /// Imported by ordinal, which is in the hint field
+    Ordinal,
+    /// The symbol name as is
+    Name,
+    /// The symbol name without its leading `?`, `@` or `_`
+    NameNoPrefix,
+    /// The symbol name without its leading `?`, `@` or `_`, and cut at the first `@`
+    NameUndecorate,
+    /// A name stored after the DLL name
+    NameExportAs,
+    Other(u16),
+}
+
+impl From<u16> for ImportNameType {
+    fn from(typ: u16) -> Self {
+        match typ {
+            0 => ImportNameType::Ordinal,
+            1 => ImportNameType::Name,
+            2 => ImportNameType::NameNoPrefix,
+            3 => ImportNameType::NameUndecorate,
+            4 => ImportNameType::NameExportAs,
+            typ => ImportNameType::Other(typ),
+        }
+    }
+}
+
+impl fmt::Display for ImportNameType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportNameType::Other(typ) => write!(f, "Unknown ({:#x})", typ),
+            typ => write!(f, "{:?}", typ),
+        }
+    }
+}
+
+/// A short import object, the `IMPORT_OBJECT_HEADER` format MSVC import libraries use for their
+/// members in place of full objects
+#[derive(Debug)]
+pub struct ImportObject {
+    pub version: u16,
+    pub machine: Machine,
+    pub time_date_stamp: u32,
+    /// The size of the strings after the header
+    pub size_of_data: u32,
+    /// The ordinal when importing by ordinal, and a hint into the export name table otherwise
+    pub ordinal_or_hint: u16,
+    pub typ: ImportType,
+    pub name_type: ImportNameType,
+    /// The name of the symbol the import defines
+    pub symbol: String,
+    pub dll: String,
+    /// The exported name, for `IMPORT_OBJECT_NAME_EXPORTAS` imports
+    pub export_name: Option<String>,
+}
+
+impl ImportObject {
+    pub(crate) fn parse(data: &[u8]) -> BinDumpResult<Self> {
+        let (input, (_, version, machine, time_date_stamp, size_of_data, ordinal_or_hint, types)) =
+            context(
+                "Parse Import Object Header",
+                tuple((
+                    tag(ANONYMOUS_MAGIC),
+                    le_u16,
+                    map(le_u16, Machine::from),
+                    le_u32,
+                    le_u32,
+                    le_u16,
+                    le_u16,
+                )),
+            )(data)?;
+        let strings = &input[..(size_of_data as usize).min(input.len())];
+        let symbol = string_at(strings, 0);
+        let dll = string_at(strings, symbol.len() + 1);
+        let name_type = ImportNameType::from(types >> 2 & 0x7);
+        let export_name = (name_type == ImportNameType::NameExportAs)
+            .then(|| string_at(strings, symbol.len() + dll.len() + 2));
+        Ok(Self {
+            version,
+            machine,
+            time_date_stamp,
+            size_of_data,
+            ordinal_or_hint,
+            typ: ImportType::from(types & 0x3),
+            name_type,
+            symbol,
+            dll,
+            export_name,
+        })
+    }
+
+    /// The name the import is looked up by in the DLL's exports, unless it's by ordinal
+    pub fn import_name(&self) -> Option<&str> {
+        let without_prefix = || {
+            self.symbol
+                .strip_prefix(['?', '@', '_'])
+                .unwrap_or(&self.symbol)
+        };
+        match self.name_type {
+            ImportNameType::Ordinal => None,
+            ImportNameType::NameNoPrefix => Some(without_prefix()),
+            ImportNameType::NameUndecorate => without_prefix().split('@').next(),
+            ImportNameType::NameExportAs => self.export_name.as_deref(),
+            ImportNameType::Name | ImportNameType::Other(_) => Some(&self.symbol),
+        }
+    }
+}
+
+impl fmt::Display for ImportObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "COFF Import: {} Architecture", self.machine)?;
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "Time Date Stamp: {:#x}", self.time_date_stamp)?;
+        writeln!(f, "Symbol: {}", self.symbol)?;
+        writeln!(f, "DLL: {}", self.dll)?;
+        writeln!(f, "Type: {}", self.typ)?;
+        writeln!(f, "Name Type: {}", self.name_type)?;
+        match self.import_name() {
+            Some(name) => {
+                writeln!(f, "Hint: {}", self.ordinal_or_hint)?;
+                writeln!(f, "Import Name: {}", name)
+            }
+            None => writeln!(f, "Ordinal: {}", self.ordinal_or_hint),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+
+use crate::binary::pe::header::Machine;
+use crate::parse;
+
+/// The size of a relocation entry
+pub const RELOCATION_SIZE: usize = 10;
+
+// From winnt.h
+const I386_RELOCATIONS: [(u16, &str); 11] = [
+    (0x00, "IMAGE_REL_I386_ABSOLUTE"),
+    (0x01, "IMAGE_REL_I386_DIR16"),
+    (0x02, "IMAGE_REL_I386_REL16"),
+    (0x06, "IMAGE_REL_I386_DIR32"),
+    (0x07, "IMAGE_REL_I386_DIR32NB"),
+    (0x09, "IMAGE_REL_I386_SEG12"),
+    (0x0a, "IMAGE_REL_I386_SECTION"),
+    (0x0b, "IMAGE_REL_I386_SECREL"),
+    (0x0c, "IMAGE_REL_I386_TOKEN"),
+    (0x0d, "IMAGE_REL_I386_SECREL7"),
+    (0x14, "IMAGE_REL_I386_REL32"),
+];
+
+const AMD64_RELOCATIONS: [(u16, &str); 17] = [
+    (0x00, "IMAGE_REL_AMD64_ABSOLUTE"),
+    (0x01, "IMAGE_REL_AMD64_ADDR64"),
+    (0x02, "IMAGE_REL_AMD64_ADDR32"),
+    (0x03, "IMAGE_REL_AMD64_ADDR32NB"),
+    (0x04, "IMAGE_REL_AMD64_REL32"),
+    (0x05, "IMAGE_REL_AMD64_REL32_1"),
+    (0x06, "IMAGE_REL_AMD64_REL32_2"),
+    (0x07, "IMAGE_REL_AMD64_REL32_3"),
+    (0x08, "IMAGE_REL_AMD64_REL32_4"),
+    (0x09, "IMAGE_REL_AMD64_REL32_5"),
+    (0x0a, "IMAGE_REL_AMD64_SECTION"),
+    (0x0b, "IMAGE_REL_AMD64_SECREL"),
+    (0x0c, "IMAGE_REL_AMD64_SECREL7"),
+    (0x0d, "IMAGE_REL_AMD64_TOKEN"),
+    (0x0e, "IMAGE_REL_AMD64_SREL32"),
+    (0x0f, "IMAGE_REL_AMD64_PAIR"),
+    (0x10, "IMAGE_REL_AMD64_SSPAN32"),
+];
+
+const ARM_RELOCATIONS: [(u16, &str); 20] = [
+    (0x00, "IMAGE_REL_ARM_ABSOLUTE"),
+    (0x01, "IMAGE_REL_ARM_ADDR32"),
+    (0x02, "IMAGE_REL_ARM_ADDR32NB"),
+    (0x03, "IMAGE_REL_ARM_BRANCH24"),
+    (0x04, "IMAGE_REL_ARM_BRANCH11"),
+    (0x05, "IMAGE_REL_ARM_TOKEN"),
+    (0x06, "IMAGE_REL_ARM_GPREL12"),
+    (0x07, "IMAGE_REL_ARM_GPREL7"),
+    (0x08, "IMAGE_REL_ARM_BLX24"),
+    (0x09, "IMAGE_REL_ARM_BLX11"),
+    (0x0a, "IMAGE_REL_ARM_REL32"),
+    (0x0e, "IMAGE_REL_ARM_SECTION"),
+    (0x0f, "IMAGE_REL_ARM_SECREL"),
+    (0x10, "IMAGE_REL_ARM_MOV32"),
+    (0x11, "IMAGE_REL_THUMB_MOV32"),
+    (0x12, "IMAGE_REL_THUMB_BRANCH20"),
+    (0x13, "IMAGE_REL_THUMB_UNUSED"),
+    (0x14, "IMAGE_REL_THUMB_BRANCH24"),
+    (0x15, "IMAGE_REL_THUMB_BLX23"),
+    (0x16, "IMAGE_REL_ARM_PAIR"),
+];
+
+const ARM64_RELOCATIONS: [(u16, &str); 18] = [
+    (0x00, "IMAGE_REL_ARM64_ABSOLUTE"),
+    (0x01, "IMAGE_REL_ARM64_ADDR32"),
+    (0x02, "IMAGE_REL_ARM64_ADDR32NB"),
+    (0x03, "IMAGE_REL_ARM64_BRANCH26"),
+    (0x04, "IMAGE_REL_ARM64_PAGEBASE_REL21"),
+    (0x05, "IMAGE_REL_ARM64_REL21"),
+    (0x06, "IMAGE_REL_ARM64_PAGEOFFSET_12A"),
+    (0x07, "IMAGE_REL_ARM64_PAGEOFFSET_12L"),
+    (0x08, "IMAGE_REL_ARM64_SECREL"),
+    (0x09, "IMAGE_REL_ARM64_SECREL_LOW12A"),
+    (0x0a, "IMAGE_REL_ARM64_SECREL_HIGH12A"),
+    (0x0b, "IMAGE_REL_ARM64_SECREL_LOW12L"),
+    (0x0c, "IMAGE_REL_ARM64_TOKEN"),
+    (0x0d, "IMAGE_REL_ARM64_SECTION"),
+    (0x0e, "IMAGE_REL_ARM64_ADDR64"),
+    (0x0f, "IMAGE_REL_ARM64_BRANCH19"),
+    (0x10, "IMAGE_REL_ARM64_BRANCH14"),
+    (0x11, "IMAGE_REL_ARM64_REL32"),
+];
+
+/// The type of a relocation, which is only meaningful together with the machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelocationType {
+    pub machine: Machine,
+    pub typ: u16,
+}
+
+impl RelocationType {
+    /// The `IMAGE_REL_*` name of the type, for the machines it's known for
+    pub fn name(&self) -> Option<&'static str> {
+        let names: &[(u16, &str)] = match self.machine {
+            Machine::I386 => &I386_RELOCATIONS,
+            Machine::Amd64 => &AMD64_RELOCATIONS,
+            Machine::Arm | Machine::Thumb | Machine::ArmNt => &ARM_RELOCATIONS,
+            Machine::Arm64 | Machine::Arm64Ec | Machine::Arm64X => &ARM64_RELOCATIONS,
+            _ => &[],
+        };
+        names
+            .iter()
+            .find(|(typ, _)| *typ == self.typ)
+            .map(|(_, name)| *name)
+    }
+}
+
+impl fmt::Display for RelocationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "Unknown ({:#x})", self.typ),
+        }
+    }
+}
+
+/// A relocation of an object file section
+#[derive(Debug)]
+pub struct Relocation {
+    /// The offset of the relocated location from the start of the section
+    pub virtual_address: u32,
+    pub symbol_table_index: u32,
+    /// The name of the symbol, if the index points at one
+    pub symbol: Option<String>,
+    pub typ: RelocationType,
+}
+
+impl Relocation {
+    pub(super) fn parse(input: parse::Input, machine: Machine) -> parse::ParseResult<Self> {
+        let (input, (virtual_address, symbol_table_index, typ)) =
+            context("Parse Relocation", tuple((le_u32, le_u32, le_u16)))(input)?;
+        Ok((
+            input,
+            Self {
+                virtual_address,
+                symbol_table_index,
+                symbol: None,
+                typ: RelocationType { machine, typ },
+            },
+        ))
+    }
+}
+
+impl fmt::Display for Relocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:08x} {:<32}", self.virtual_address, self.typ)?;
+        match &self.symbol {
+            Some(symbol) => write!(f, " {}", symbol),
+            None => write!(f, " <symbol {}>", self.symbol_table_index),
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::error::context;
+use nom::number::complete::{le_i16, le_i32, le_u16, le_u32, u8};
+use nom::sequence::tuple;
+
+use crate::parse::{self, hex, string_at};
+
+/// The size of a symbol table record in regular objects
+pub const SYMBOL_SIZE: usize = 18;
+/// The size of a symbol table record in `/bigobj` objects, which have 32 bit section numbers
+pub const BIGOBJ_SYMBOL_SIZE: usize = 20;
+
+/// The section number of symbols that aren't defined in the object
+pub const SYM_UNDEFINED: i32 = 0;
+/// The section number of symbols with an absolute value
+pub const SYM_ABSOLUTE: i32 = -1;
+/// The section number of symbols that only carry debug information
+pub const SYM_DEBUG: i32 = -2;
+
+/// The complex type of function symbols, in the high nibble of the type
+const DTYPE_FUNCTION: u16 = 2;
+
+// From winnt.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageClass {
+    EndOfFunction,
+    Null,
+    Automatic,
+    External,
+    Static,
+    Register,
+    ExternalDef,
+    Label,
+    UndefinedLabel,
+    MemberOfStruct,
+    Argument,
+    StructTag,
+    MemberOfUnion,
+    UnionTag,
+    TypeDefinition,
+    UndefinedStatic,
+    EnumTag,
+    MemberOfEnum,
+    RegisterParam,
+    BitField,
+    Block,
+    Function,
+    EndOfStruct,
+    File,
+    Section,
+    WeakExternal,
+    ClrToken,
+    Other(u8),
+}
+
+impl From<u8> for StorageClass {
+    fn from(class: u8) -> Self {
+        match class {
+            0xff => StorageClass::EndOfFunction,
+            0 => StorageClass::Null,
+            1 => StorageClass::Automatic,
+            2 => StorageClass::External,
+            3 => StorageClass::Static,
+            4 => StorageClass::Register,
+            5 => StorageClass::ExternalDef,
+            6 => StorageClass::Label,
+            7 => StorageClass::UndefinedLabel,
+            8 => StorageClass::MemberOfStruct,
+            9 => StorageClass::Argument,
+            10 => StorageClass::StructTag,
+            11 => StorageClass::MemberOfUnion,
+            12 => StorageClass::UnionTag,
+            13 => StorageClass::TypeDefinition,
+            14 => StorageClass::UndefinedStatic,
+            15 => StorageClass::EnumTag,
+            16 => StorageClass::MemberOfEnum,
+            17 => StorageClass::RegisterParam,
+            18 => StorageClass::BitField,
+            100 => StorageClass::Block,
+            101 => StorageClass::Function,
+            102 => StorageClass::EndOfStruct,
+            103 => StorageClass::File,
+            104 => StorageClass::Section,
+            105 => StorageClass::WeakExternal,
+            107 => StorageClass::ClrToken,
+            class => StorageClass::Other(class),
+        }
+    }
+}
+
+impl fmt::Display for StorageClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageClass::Other(class) => write!(f, "Unknown ({:#x})", class),
+            class => write!(f, "{:?}", class),
+        }
+    }
+}
+
+/// How the linker picks between COMDAT sections with the same symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComdatSelection {
+    NoDuplicates,
+    Any,
+    SameSize,
+    ExactMatch,
+    Associative,
+    Largest,
+    Newest,
+    Other(u8),
+}
+
+impl From<u8> for ComdatSelection {
+    fn from(selection: u8) -> Self {
+        match selection {
+            1 => ComdatSelection::NoDuplicates,
+            2 => ComdatSelection::Any,
+            3 => ComdatSelection::SameSize,
+            4 => ComdatSelection::ExactMatch,
+            5 => ComdatSelection::Associative,
+            6 => ComdatSelection::Largest,
+            7 => ComdatSelection::Newest,
+            selection => ComdatSelection::Other(selection),
+        }
+    }
+}
+
+impl fmt::Display for ComdatSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComdatSelection::Other(selection) => write!(f, "Unknown ({:#x})", selection),
+            selection => write!(f, "{:?}", selection),
+        }
+    }
+}
+
+/// How a weak external is resolved when nothing defines the symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeakSearch {
+    NoLibrary,
+    Library,
+    Alias,
+    AntiDependency,
+    Other(u32),
+}
+
+impl From<u32> for WeakSearch {
+    fn from(search: u32) -> Self {
+        match search {
+            1 => WeakSearch::NoLibrary,
+            2 => WeakSearch::Library,
+            3 => WeakSearch::Alias,
+            4 => WeakSearch::AntiDependency,
+            search => WeakSearch::Other(search),
+        }
+    }
+}
+
+impl fmt::Display for WeakSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeakSearch::Other(search) => write!(f, "Unknown ({:#x})", search),
+            search => write!(f, "{:?}", search),
+        }
+    }
+}
+
+/// An auxiliary record following a symbol, whose format depends on the kind of symbol
+#[derive(Debug)]
+pub enum AuxiliaryRecord {
+    FunctionDefinition {
+        /// The symbol table index of the `.bf` symbol of the function
+        tag_index: u32,
+        total_size: u32,
+        pointer_to_line_number: u32,
+        pointer_to_next_function: u32,
+    },
+    /// The record of a `.bf` or `.ef` symbol
+    BeginEndFunction {
+        line_number: u16,
+        pointer_to_next_function: u32,
+    },
+    WeakExternal {
+        /// The symbol table index of the symbol to use instead
+        tag_index: u32,
+        search: WeakSearch,
+    },
+    /// The source file name, which takes up all the records of a `.file` symbol
+    File(String),
+    SectionDefinition {
+        length: u32,
+        number_of_relocations: u16,
+        number_of_line_numbers: u16,
+        checksum: u32,
+        /// The section this one is associated with, for associative COMDAT sections
+        number: u32,
+        selection: Option<ComdatSelection>,
+    },
+    ClrToken {
+        symbol_table_index: u32,
+    },
+    /// A record of a kind this doesn't know about
+    Unknown(Vec<u8>),
+}
+
+impl AuxiliaryRecord {
+    fn parse<'a>(
+        input: parse::Input<'a>,
+        symbol: &Symbol,
+        bigobj: bool,
+    ) -> parse::ParseResult<'a, Self> {
+        let class = symbol.storage_class;
+        if symbol.is_section_definition() {
+            let (rest, (length, number_of_relocations, number_of_line_numbers, checksum)) =
+                context(
+                    "Parse Section Definition",
+                    tuple((le_u32, le_u16, le_u16, le_u32)),
+                )(input)?;
+            let (rest, (number, selection, _, high_number)) =
+                tuple((le_u16, u8, u8, le_u16))(rest)?;
+            // The high half of the section number is only there in `/bigobj` records
+            let high_number = if bigobj { high_number as u32 } else { 0 };
+            return Ok((
+                rest,
+                AuxiliaryRecord::SectionDefinition {
+                    length,
+                    number_of_relocations,
+                    number_of_line_numbers,
+                    checksum,
+                    number: number as u32 | high_number << 16,
+                    selection: (selection != 0).then(|| ComdatSelection::from(selection)),
+                },
+            ));
+        }
+        if symbol.is_function_definition() {
+            let (rest, (tag_index, total_size, pointer_to_line_number, pointer_to_next_function)) =
+                context(
+                    "Parse Function Definition",
+                    tuple((le_u32, le_u32, le_u32, le_u32)),
+                )(input)?;
+            return Ok((
+                rest,
+                AuxiliaryRecord::FunctionDefinition {
+                    tag_index,
+                    total_size,
+                    pointer_to_line_number,
+                    pointer_to_next_function,
+                },
+            ));
+        }
+        match class {
+            StorageClass::Function => {
+                let (rest, (_, line_number, _, pointer_to_next_function)) =
+                    context(
+                        "Parse Function Record",
+                        tuple((le_u32, le_u16, take(6usize), le_u32)),
+                    )(input)?;
+                Ok((
+                    rest,
+                    AuxiliaryRecord::BeginEndFunction {
+                        line_number,
+                        pointer_to_next_function,
+                    },
+                ))
+            }
+            StorageClass::WeakExternal => {
+                let (rest, (tag_index, search)) =
+                    context("Parse Weak External", tuple((le_u32, le_u32)))(input)?;
+                Ok((
+                    rest,
+                    AuxiliaryRecord::WeakExternal {
+                        tag_index,
+                        search: WeakSearch::from(search),
+                    },
+                ))
+            }
+            StorageClass::ClrToken => {
+                let (rest, (_, _, symbol_table_index)) =
+                    context("Parse CLR Token", tuple((u8, u8, le_u32)))(input)?;
+                Ok((rest, AuxiliaryRecord::ClrToken { symbol_table_index }))
+            }
+            _ => Ok((&[], AuxiliaryRecord::Unknown(input.to_vec()))),
+        }
+    }
+}
+
+impl fmt::Display for AuxiliaryRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuxiliaryRecord::FunctionDefinition {
+                tag_index,
+                total_size,
+                pointer_to_line_number,
+                pointer_to_next_function,
+            } => write!(
+                f,
+                "Function Definition: Tag Index {} Size {:#x} Line Numbers {:#x} Next {}",
+                tag_index, total_size, pointer_to_line_number, pointer_to_next_function
+            ),
+            AuxiliaryRecord::BeginEndFunction {
+                line_number,
+                pointer_to_next_function,
+            } => write!(
+                f,
+                "Function: Line {} Next {}",
+                line_number, pointer_to_next_function
+            ),
+            AuxiliaryRecord::WeakExternal { tag_index, search } => {
+                write!(
+                    f,
+                    "Weak External: Tag Index {} Search {}",
+                    tag_index, search
+                )
+            }
+            AuxiliaryRecord::File(name) => write!(f, "File: {}", name),
+            AuxiliaryRecord::SectionDefinition {
+                length,
+                number_of_relocations,
+                number_of_line_numbers,
+                checksum,
+                number,
+                selection,
+            } => {
+                write!(
+                    f,
+                    "Section Definition: Length {:#x} Relocations {} Line Numbers {} \
+                     Checksum {:#010x}",
+                    length, number_of_relocations, number_of_line_numbers, checksum
+                )?;
+                if let Some(selection) = selection {
+                    write!(f, " Selection {}", selection)?;
+                }
+                if *selection == Some(ComdatSelection::Associative) {
+                    write!(f, " Associated Section {}", number)?;
+                }
+                Ok(())
+            }
+            AuxiliaryRecord::ClrToken { symbol_table_index } => {
+                write!(f, "CLR Token: Symbol {}", symbol_table_index)
+            }
+            AuxiliaryRecord::Unknown(data) => write!(f, "Unknown: {}", hex(data)),
+        }
+    }
+}
+
+/// A symbol table entry with its auxiliary records
+#[derive(Debug)]
+pub struct Symbol {
+    /// The index of the entry in the symbol table, where auxiliary records count as entries
+    pub index: u32,
+    pub name: String,
+    pub value: u32,
+    /// The one based section number, or one of the special `SYM_*` numbers
+    pub section_number: i32,
+    pub typ: u16,
+    pub storage_class: StorageClass,
+    pub number_of_aux_symbols: u8,
+    pub auxiliary_records: Vec<AuxiliaryRecord>,
+}
+
+impl Symbol {
+    pub(super) fn parse<'a>(
+        input: parse::Input<'a>,
+        index: u32,
+        bigobj: bool,
+        string_table: &[u8],
+    ) -> parse::ParseResult<'a, Self> {
+        let (input, name) = context("Parse Symbol Name", take(8usize))(input)?;
+        let (input, (value, section_number)) = if bigobj {
+            context("Parse Symbol", tuple((le_u32, le_i32)))(input)?
+        } else {
+            let (input, (value, section_number)) =
+                context("Parse Symbol", tuple((le_u32, le_i16)))(input)?;
+            (input, (value, section_number as i32))
+        };
+        let (input, (typ, storage_class, number_of_aux_symbols)) =
+            context("Parse Symbol", tuple((le_u16, u8, u8)))(input)?;
+        let name = match name {
+            // Long names are an offset into the string table instead
+            [0, 0, 0, 0, offset @ ..] => {
+                let offset = u32::from_le_bytes(offset.try_into().unwrap_or_default());
+                string_at(string_table, offset as usize)
+            }
+            name => string_at(name, 0),
+        };
+        let mut symbol = Self {
+            index,
+            name,
+            value,
+            section_number,
+            typ,
+            storage_class: StorageClass::from(storage_class),
+            number_of_aux_symbols,
+            auxiliary_records: Vec::new(),
+        };
+
+        let size = if bigobj {
+            BIGOBJ_SYMBOL_SIZE
+        } else {
+            SYMBOL_SIZE
+        };
+        let (input, auxiliary) = context(
+            "Parse Auxiliary Symbols",
+            take(size * number_of_aux_symbols as usize),
+        )(input)?;
+        if symbol.storage_class == StorageClass::File {
+            if !auxiliary.is_empty() {
+                symbol
+                    .auxiliary_records
+                    .push(AuxiliaryRecord::File(string_at(auxiliary, 0)));
+            }
+        } else {
+            for (i, record) in auxiliary.chunks(size).enumerate() {
+                // Only the first record has a known format
+                let record = if i == 0 {
+                    AuxiliaryRecord::parse(&record[..SYMBOL_SIZE], &symbol, bigobj)?.1
+                } else {
+                    AuxiliaryRecord::Unknown(record.to_vec())
+                };
+                symbol.auxiliary_records.push(record);
+            }
+        }
+        Ok((input, symbol))
+    }
+
+    /// The symbol of a section, with a record that describes the section
+    pub fn is_section_definition(&self) -> bool {
+        self.storage_class == StorageClass::Static && self.value == 0 && self.section_number > 0
+    }
+
+    pub fn is_function_definition(&self) -> bool {
+        self.storage_class == StorageClass::External
+            && self.typ >> 4 == DTYPE_FUNCTION
+            && self.section_number > 0
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let section = match self.section_number {
+            SYM_UNDEFINED => "UNDEF".to_string(),
+            SYM_ABSOLUTE => "ABS".to_string(),
+            SYM_DEBUG => "DEBUG".to_string(),
+            number => number.to_string(),
+        };
+        write!(
+            f,
+            "[{:>4}] {:08x} {:>5} {:#06x} {:<14} {}",
+            self.index, self.value, section, self.typ, self.storage_class, self.name
+        )?;
+        for record in &self.auxiliary_records {
+            write!(f, "\n       {}", record)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+//! Reader for DWARF 2 to 5 debugging information, shared by ELF files and Mach-O dSYMs
+
+use std::collections::hash_map::{Entry, HashMap};
+use std::fmt;
+
+use nom::bytes::complete::{tag, take_till};
+use nom::combinator::map;
+use nom::error::context;
+use nom::number::{complete, Endianness};
+use nom::sequence::terminated;
+
+pub mod abbreviations;
+pub mod cfi;
+pub mod constants;
+pub mod frames;
+pub mod line;
+pub mod unit;
+
+use abbreviations::Abbreviations;
+use unit::{Die, Unit};
+
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse;
+
+/// The raw contents of the DWARF sections of an object, empty when a section is missing
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DwarfSections<'a> {
+    pub debug_info: &'a [u8],
+    pub debug_abbrev: &'a [u8],
+    pub debug_str: &'a [u8],
+    pub debug_line: &'a [u8],
+    pub debug_line_str: &'a [u8],
+    pub debug_ranges: &'a [u8],
+    pub debug_rnglists: &'a [u8],
+    pub debug_addr: &'a [u8],
+    pub debug_str_offsets: &'a [u8],
+}
+
+impl<'a> DwarfSections<'a> {
+    /// Stores the contents of a section by its ELF (`.debug_info`, or `.debug_info.dwo` in split
+    /// DWARF files) or Mach-O (`__debug_info`) name, returning whether it is one of the sections
+    /// the reader uses
+    pub fn insert(&mut self, name: &str, data: &'a [u8]) -> bool {
+        let name = name.strip_suffix(".dwo").unwrap_or(name);
+        let name = name
+            .strip_prefix("__")
+            .or_else(|| name.strip_prefix('.'))
+            .unwrap_or(name);
+        let section = match name {
+            "debug_info" => &mut self.debug_info,
+            "debug_abbrev" => &mut self.debug_abbrev,
+            "debug_str" => &mut self.debug_str,
+            "debug_line" => &mut self.debug_line,
+            "debug_line_str" => &mut self.debug_line_str,
+            "debug_ranges" => &mut self.debug_ranges,
+            "debug_rnglists" => &mut self.debug_rnglists,
+            "debug_addr" => &mut self.debug_addr,
+            // Mach-O section names are cut off at 16 characters
+            "debug_str_offsets" | "debug_str_offs" => &mut self.debug_str_offsets,
+            _ => return false,
+        };
+        *section = data;
+        true
+    }
+}
+
+/// How the values in a unit are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Format {
+    pub endianness: Endianness,
+    pub version: u16,
+    /// 4 for 32 bit DWARF and 8 for 64 bit DWARF
+    pub offset_size: u8,
+    pub address_size: u8,
+}
+
+impl Format {
+    pub(crate) fn u16(self) -> impl FnMut(parse::Input) -> parse::ParseResult<u16> {
+        move |input| complete::u16(self.endianness)(input)
+    }
+
+    pub(crate) fn u32(self) -> impl FnMut(parse::Input) -> parse::ParseResult<u32> {
+        move |input| complete::u32(self.endianness)(input)
+    }
+
+    pub(crate) fn u64(self) -> impl FnMut(parse::Input) -> parse::ParseResult<u64> {
+        move |input| complete::u64(self.endianness)(input)
+    }
+
+    /// Parses an unsigned number of 1, 2, 3, 4 or 8 bytes
+    pub(crate) fn sized(self, size: u8) -> impl FnMut(parse::Input) -> parse::ParseResult<u64> {
+        move |input| match size {
+            1 => map(complete::u8, u64::from)(input),
+            2 => map(complete::u16(self.endianness), u64::from)(input),
+            3 => map(complete::u24(self.endianness), u64::from)(input),
+            4 => map(complete::u32(self.endianness), u64::from)(input),
+            _ => complete::u64(self.endianness)(input),
+        }
+    }
+
+    /// Parses an offset into another section
+    pub(crate) fn offset(self) -> impl FnMut(parse::Input) -> parse::ParseResult<u64> {
+        self.sized(self.offset_size)
+    }
+
+    pub(crate) fn address(self) -> impl FnMut(parse::Input) -> parse::ParseResult<u64> {
+        self.sized(self.address_size)
+    }
+}
+
+/// Parses the length that starts units and tables, returning it with the offset size it implies
+pub(crate) fn initial_length(
+    endianness: Endianness,
+) -> impl FnMut(parse::Input) -> parse::ParseResult<(u64, u8)> {
+    move |input| {
+        let (input, length) = complete::u32(endianness)(input)?;
+        match length {
+            0xffff_ffff => map(complete::u64(endianness), |length| (length, 8))(input),
+            length => Ok((input, (length as u64, 4))),
+        }
+    }
+}
+
+/// Parses a null terminated string
+pub(crate) fn string(input: parse::Input) -> parse::ParseResult<String> {
+    let (input, bytes) = context(
+        "Parse String",
+        terminated(take_till(|byte| byte == 0), tag([0])),
+    )(input)?;
+    Ok((input, String::from_utf8_lossy(bytes).into_owned()))
+}
+
+/// Reads the offset or address at `index` in a table of values of `size` bytes
+pub(crate) fn table_entry(
+    format: Format,
+    table: &[u8],
+    base: u64,
+    index: u64,
+    size: u8,
+) -> Option<u64> {
+    let start = index.checked_mul(size as u64)?.checked_add(base)?;
+    let input = table.get(usize::try_from(start).ok()?..)?;
+    let result: parse::ParseResult<u64> = format.sized(size)(input);
+    result.ok().map(|(_, value)| value)
+}
+
+/// A half-open range of addresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range {
+    pub begin: u64,
+    pub end: u64,
+}
+
+impl Range {
+    pub fn contains(&self, address: u64) -> bool {
+        self.begin <= address && address < self.end
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:#x}, {:#x})", self.begin, self.end)
+    }
+}
+
+/// The compile units of an object and their line tables
+#[derive(Debug)]
+pub struct Dwarf {
+    units: Vec<Unit>,
+}
+
+impl Dwarf {
+    pub fn parse(sections: &DwarfSections, endianness: Endianness) -> BinDumpResult<Self> {
+        let mut abbreviations: HashMap<u64, Abbreviations> = HashMap::new();
+        let mut units = Vec::new();
+        let mut offset = 0u64;
+        while (offset as usize) < sections.debug_info.len() {
+            let input = &sections.debug_info[offset as usize..];
+            let (unit_length, offset_size) = initial_length(endianness)(input)?.1;
+            // Linkers pad the section with zeros when dropping units
+            if unit_length == 0 {
+                offset += offset_size as u64;
+                continue;
+            }
+            let (mut unit, entries) = Unit::parse_header(sections.debug_info, offset, endianness)?;
+            let abbreviation_offset = unit.abbreviation_offset;
+            if let Entry::Vacant(entry) = abbreviations.entry(abbreviation_offset) {
+                let table = sections
+                    .debug_abbrev
+                    .get(abbreviation_offset as usize..)
+                    .ok_or_else(|| BinDumpError::ParseError {
+                        error: format!(
+                            "Abbreviation offset {:#x} is past the end of .debug_abbrev",
+                            abbreviation_offset
+                        ),
+                    })?;
+                entry.insert(Abbreviations::parse(table)?.1);
+            }
+            unit.parse_entries(
+                sections,
+                endianness,
+                &abbreviations[&abbreviation_offset],
+                entries,
+            )?;
+            offset = unit.next_offset();
+            units.push(unit);
+        }
+        Ok(Self { units })
+    }
+
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+
+    /// The entry at an offset in `.debug_info`, the target of reference attributes
+    pub fn die_at(&self, offset: u64) -> Option<&Die> {
+        self.units
+            .iter()
+            .find(|unit| unit.offset <= offset && offset < unit.next_offset())?
+            .die_at(offset)
+    }
+}
+
+impl fmt::Display for Dwarf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for unit in &self.units {
+            write!(f, "{}", unit)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use nom::error::context;
+use nom::number::complete;
+
+use super::constants::{DW_FORM_implicit_const, DwAt, DwForm, DwTag};
+use crate::parse::{self, sleb128, uleb128};
+
+/// The name and form of one attribute of an abbreviation
+#[derive(Debug, Clone, Copy)]
+pub struct AttributeSpecification {
+    pub name: DwAt,
+    pub form: DwForm,
+    /// The value of a `DW_FORM_implicit_const` attribute, which is stored here rather than in
+    /// the entries
+    pub implicit_const: Option<i64>,
+}
+
+/// The layout shared by the debugging information entries that use the same code
+#[derive(Debug, Clone)]
+pub struct Abbreviation {
+    pub code: u64,
+    pub tag: DwTag,
+    pub has_children: bool,
+    pub attributes: Vec<AttributeSpecification>,
+}
+
+/// An abbreviation table from `.debug_abbrev`, which may be shared by several units
+#[derive(Debug, Clone, Default)]
+pub struct Abbreviations {
+    abbreviations: HashMap<u64, Abbreviation>,
+}
+
+impl Abbreviations {
+    pub fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let mut abbreviations = HashMap::new();
+        let mut input = input;
+        loop {
+            let (rest, code) = context("Parse Abbreviation Code", uleb128)(input)?;
+            input = rest;
+            if code == 0 {
+                break;
+            }
+            let (rest, (tag, has_children)) = context(
+                "Parse Abbreviation",
+                nom::sequence::tuple((uleb128, complete::u8)),
+            )(input)?;
+            input = rest;
+            let mut attributes = Vec::new();
+            loop {
+                let (rest, (name, form)) = context(
+                    "Parse Attribute Specification",
+                    nom::sequence::tuple((uleb128, uleb128)),
+                )(input)?;
+                input = rest;
+                if name == 0 && form == 0 {
+                    break;
+                }
+                let form = DwForm(form as u16);
+                let implicit_const = if form == DW_FORM_implicit_const {
+                    let (rest, value) = sleb128(input)?;
+                    input = rest;
+                    Some(value)
+                } else {
+                    None
+                };
+                attributes.push(AttributeSpecification {
+                    name: DwAt(name as u16),
+                    form,
+                    implicit_const,
+                });
+            }
+            abbreviations.insert(
+                code,
+                Abbreviation {
+                    code,
+                    tag: DwTag(tag as u16),
+                    has_children: has_children != 0,
+                    attributes,
+                },
+            );
+        }
+        Ok((input, Self { abbreviations }))
+    }
+
+    pub fn get(&self, code: u64) -> Option<&Abbreviation> {
+        self.abbreviations.get(&code)
+    }
+}
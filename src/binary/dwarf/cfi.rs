@@ -0,0 +1,1149 @@
+// Instructions and pointer encodings are matched by their DW_* names
+#![allow(non_upper_case_globals)]
+
+use std::collections::{hash_map, BTreeMap, HashMap};
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::combinator::map;
+use nom::error::context;
+use nom::number::{complete, Endianness};
+
+use super::constants::*;
+use super::{initial_length, string, Format};
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse::{self, failure, sleb128, uleb128};
+
+/// The section call frame information was read from, which decides how CIEs are told apart from
+/// FDEs and how FDEs refer to their CIE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameSection {
+    /// `.eh_frame` or `__eh_frame`, used for exception handling and kept in stripped binaries
+    EhFrame,
+    /// `.debug_frame`
+    DebugFrame,
+}
+
+/// The addresses that relative pointer encodings are measured from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PointerBases {
+    /// The address of the section being read, which `DW_EH_PE_pcrel` pointers are relative to
+    pub section: u64,
+    /// The address `DW_EH_PE_textrel` pointers are relative to
+    pub text: u64,
+    /// The address `DW_EH_PE_datarel` pointers are relative to
+    pub data: u64,
+}
+
+/// A pointer read with a `DW_EH_PE_*` encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pointer {
+    pub address: u64,
+    /// Whether the real value is stored at `address` rather than being `address` itself
+    pub indirect: bool,
+}
+
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.indirect {
+            write!(f, "[{:#x}]", self.address)
+        } else {
+            write!(f, "{:#x}", self.address)
+        }
+    }
+}
+
+/// A call frame instruction, with its operands already multiplied by the alignment factors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    AdvanceLoc(u64),
+    SetLoc(u64),
+    DefCfa {
+        register: u64,
+        offset: i64,
+    },
+    DefCfaRegister(u64),
+    DefCfaOffset(i64),
+    DefCfaExpression(Vec<u8>),
+    Undefined(u64),
+    SameValue(u64),
+    Offset {
+        register: u64,
+        offset: i64,
+    },
+    ValOffset {
+        register: u64,
+        offset: i64,
+    },
+    Register {
+        register: u64,
+        source: u64,
+    },
+    Expression {
+        register: u64,
+        expression: Vec<u8>,
+    },
+    ValExpression {
+        register: u64,
+        expression: Vec<u8>,
+    },
+    Restore(u64),
+    RememberState,
+    RestoreState,
+    ArgsSize(u64),
+    /// `DW_CFA_GNU_window_save` on SPARC and `DW_CFA_AARCH64_negate_ra_state` on AArch64
+    WindowSave,
+    Nop,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::AdvanceLoc(delta) => write!(f, "DW_CFA_advance_loc: {}", delta),
+            Instruction::SetLoc(address) => write!(f, "DW_CFA_set_loc: {:#x}", address),
+            Instruction::DefCfa { register, offset } => {
+                write!(f, "DW_CFA_def_cfa: r{}{:+}", register, offset)
+            }
+            Instruction::DefCfaRegister(register) => {
+                write!(f, "DW_CFA_def_cfa_register: r{}", register)
+            }
+            Instruction::DefCfaOffset(offset) => write!(f, "DW_CFA_def_cfa_offset: {}", offset),
+            Instruction::DefCfaExpression(expression) => {
+                write!(f, "DW_CFA_def_cfa_expression: {:02x?}", expression)
+            }
+            Instruction::Undefined(register) => write!(f, "DW_CFA_undefined: r{}", register),
+            Instruction::SameValue(register) => write!(f, "DW_CFA_same_value: r{}", register),
+            Instruction::Offset { register, offset } => {
+                write!(f, "DW_CFA_offset: r{} at CFA{:+}", register, offset)
+            }
+            Instruction::ValOffset { register, offset } => {
+                write!(f, "DW_CFA_val_offset: r{} is CFA{:+}", register, offset)
+            }
+            Instruction::Register { register, source } => {
+                write!(f, "DW_CFA_register: r{} in r{}", register, source)
+            }
+            Instruction::Expression {
+                register,
+                expression,
+            } => write!(f, "DW_CFA_expression: r{} {:02x?}", register, expression),
+            Instruction::ValExpression {
+                register,
+                expression,
+            } => write!(
+                f,
+                "DW_CFA_val_expression: r{} {:02x?}",
+                register, expression
+            ),
+            Instruction::Restore(register) => write!(f, "DW_CFA_restore: r{}", register),
+            Instruction::RememberState => write!(f, "DW_CFA_remember_state"),
+            Instruction::RestoreState => write!(f, "DW_CFA_restore_state"),
+            Instruction::ArgsSize(size) => write!(f, "DW_CFA_GNU_args_size: {}", size),
+            Instruction::WindowSave => write!(f, "DW_CFA_GNU_window_save"),
+            Instruction::Nop => write!(f, "DW_CFA_nop"),
+        }
+    }
+}
+
+/// A Common Information Entry, holding what the FDEs that refer to it share
+#[derive(Debug, Clone)]
+pub struct Cie {
+    pub offset: u64,
+    pub version: u8,
+    pub augmentation: String,
+    pub address_size: u8,
+    pub segment_selector_size: u8,
+    pub code_alignment_factor: u64,
+    pub data_alignment_factor: i64,
+    pub return_address_register: u64,
+    /// How the addresses in the FDEs of this CIE are encoded
+    pub fde_encoding: u8,
+    /// How the language specific data area pointers of the FDEs are encoded
+    pub lsda_encoding: Option<u8>,
+    /// The personality routine that handles exceptions in the functions of this CIE
+    pub personality: Option<Pointer>,
+    /// Whether the FDEs describe signal handlers, whose return address isn't after a call
+    pub signal_frame: bool,
+    pub initial_instructions: Vec<Instruction>,
+}
+
+impl fmt::Display for Cie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "CIE {:#x}:", self.offset)?;
+        writeln!(f, "  Version: {}", self.version)?;
+        writeln!(f, "  Augmentation: {:?}", self.augmentation)?;
+        writeln!(f, "  Code Alignment Factor: {}", self.code_alignment_factor)?;
+        writeln!(f, "  Data Alignment Factor: {}", self.data_alignment_factor)?;
+        writeln!(
+            f,
+            "  Return Address Register: r{}",
+            self.return_address_register
+        )?;
+        writeln!(f, "  FDE Encoding: {:#04x}", self.fde_encoding)?;
+        if let Some(encoding) = self.lsda_encoding {
+            writeln!(f, "  LSDA Encoding: {:#04x}", encoding)?;
+        }
+        if let Some(personality) = self.personality {
+            writeln!(f, "  Personality: {}", personality)?;
+        }
+        if self.signal_frame {
+            writeln!(f, "  Signal Frame")?;
+        }
+        for instruction in &self.initial_instructions {
+            writeln!(f, "  {}", instruction)?;
+        }
+        Ok(())
+    }
+}
+
+/// A Frame Description Entry, which describes how to unwind one function
+#[derive(Debug, Clone)]
+pub struct Fde {
+    pub offset: u64,
+    pub cie_offset: u64,
+    pub initial_location: u64,
+    pub address_range: u64,
+    /// The language specific data area, which holds the exception tables of the function
+    pub lsda: Option<Pointer>,
+    pub instructions: Vec<Instruction>,
+}
+
+impl Fde {
+    pub fn contains(&self, address: u64) -> bool {
+        self.initial_location <= address && address - self.initial_location < self.address_range
+    }
+}
+
+/// How the canonical frame address, the value of the stack pointer at the call site, is found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfaRule {
+    RegisterOffset { register: u64, offset: i64 },
+    Expression(Vec<u8>),
+}
+
+impl fmt::Display for CfaRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CfaRule::RegisterOffset { register, offset } => write!(f, "r{}{:+}", register, offset),
+            CfaRule::Expression(expression) => write!(f, "{:02x?}", expression),
+        }
+    }
+}
+
+/// How the caller's value of a register is recovered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegisterRule {
+    Undefined,
+    SameValue,
+    /// Saved at this offset from the CFA
+    Offset(i64),
+    /// The CFA plus this offset
+    ValOffset(i64),
+    /// Saved in another register
+    Register(u64),
+    /// Saved at the address an expression computes
+    Expression(Vec<u8>),
+    /// The value an expression computes
+    ValExpression(Vec<u8>),
+}
+
+impl fmt::Display for RegisterRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegisterRule::Undefined => write!(f, "undefined"),
+            RegisterRule::SameValue => write!(f, "same"),
+            RegisterRule::Offset(offset) => write!(f, "[CFA{:+}]", offset),
+            RegisterRule::ValOffset(offset) => write!(f, "CFA{:+}", offset),
+            RegisterRule::Register(register) => write!(f, "r{}", register),
+            RegisterRule::Expression(expression) => write!(f, "[{:02x?}]", expression),
+            RegisterRule::ValExpression(expression) => write!(f, "{:02x?}", expression),
+        }
+    }
+}
+
+/// The rules for unwinding from the addresses `start` up to `end`. Registers without a rule keep
+/// the default of the ABI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnwindRow {
+    pub start: u64,
+    pub end: u64,
+    pub cfa: CfaRule,
+    pub registers: BTreeMap<u64, RegisterRule>,
+}
+
+impl UnwindRow {
+    pub fn contains(&self, address: u64) -> bool {
+        self.start <= address && address < self.end
+    }
+}
+
+impl fmt::Display for UnwindRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:#x}, {:#x}): CFA={}", self.start, self.end, self.cfa)?;
+        for (register, rule) in &self.registers {
+            write!(f, " r{}={}", register, rule)?;
+        }
+        Ok(())
+    }
+}
+
+/// The CFA and register rules while the instructions of an entry are executed
+#[derive(Debug, Clone)]
+struct State {
+    cfa: CfaRule,
+    registers: BTreeMap<u64, RegisterRule>,
+}
+
+impl State {
+    fn execute(&mut self, instruction: &Instruction) {
+        match instruction.clone() {
+            Instruction::DefCfa { register, offset } => {
+                self.cfa = CfaRule::RegisterOffset { register, offset }
+            }
+            Instruction::DefCfaRegister(new) => {
+                if let CfaRule::RegisterOffset { register, .. } = &mut self.cfa {
+                    *register = new;
+                }
+            }
+            Instruction::DefCfaOffset(new) => {
+                if let CfaRule::RegisterOffset { offset, .. } = &mut self.cfa {
+                    *offset = new;
+                }
+            }
+            Instruction::DefCfaExpression(expression) => self.cfa = CfaRule::Expression(expression),
+            Instruction::Undefined(register) => self.set(register, RegisterRule::Undefined),
+            Instruction::SameValue(register) => self.set(register, RegisterRule::SameValue),
+            Instruction::Offset { register, offset } => {
+                self.set(register, RegisterRule::Offset(offset))
+            }
+            Instruction::ValOffset { register, offset } => {
+                self.set(register, RegisterRule::ValOffset(offset))
+            }
+            Instruction::Register { register, source } => {
+                self.set(register, RegisterRule::Register(source))
+            }
+            Instruction::Expression {
+                register,
+                expression,
+            } => self.set(register, RegisterRule::Expression(expression)),
+            Instruction::ValExpression {
+                register,
+                expression,
+            } => self.set(register, RegisterRule::ValExpression(expression)),
+            // Locations, state and restores are handled by the caller, and the rest don't change
+            // the rules
+            _ => {}
+        }
+    }
+
+    fn set(&mut self, register: u64, rule: RegisterRule) {
+        self.registers.insert(register, rule);
+    }
+}
+
+/// The CIEs and FDEs of a `.eh_frame` or `.debug_frame` section
+#[derive(Debug)]
+pub struct CallFrameInformation {
+    section: FrameSection,
+    cies: Vec<Cie>,
+    fdes: Vec<Fde>,
+}
+
+impl CallFrameInformation {
+    /// Parses a whole section, where `bases.section` is the address it is loaded at
+    pub fn parse(
+        data: &[u8],
+        section: FrameSection,
+        bases: PointerBases,
+        endianness: Endianness,
+        address_size: u8,
+    ) -> BinDumpResult<Self> {
+        let reader = Reader {
+            data,
+            section,
+            bases,
+            endianness,
+            address_size,
+        };
+        let mut cies: Vec<Cie> = Vec::new();
+        let mut cie_indices = HashMap::new();
+        let mut fdes = Vec::new();
+        let mut offset = 0u64;
+        while (offset as usize) < data.len() {
+            let entry = reader.entry(offset)?;
+            let next = offset + entry.length;
+            if entry.is_terminator {
+                offset = next;
+                continue;
+            }
+            match entry.cie_offset {
+                None => {
+                    if let hash_map::Entry::Vacant(vacant) = cie_indices.entry(offset) {
+                        vacant.insert(cies.len());
+                        cies.push(reader.cie(offset, entry.body)?.1);
+                    }
+                }
+                Some(cie_offset) => {
+                    // A `.debug_frame` FDE may refer to a CIE that comes after it
+                    let index = match cie_indices.get(&cie_offset) {
+                        Some(&index) => index,
+                        None => {
+                            let cie_entry = reader.entry(cie_offset)?;
+                            if cie_entry.cie_offset.is_some() || cie_entry.is_terminator {
+                                return Err(BinDumpError::ParseError {
+                                    error: format!(
+                                        "FDE at {:#x} refers to {:#x}, which is not a CIE",
+                                        offset, cie_offset
+                                    ),
+                                });
+                            }
+                            cies.push(reader.cie(cie_offset, cie_entry.body)?.1);
+                            cie_indices.insert(cie_offset, cies.len() - 1);
+                            cies.len() - 1
+                        }
+                    };
+                    let cie = &cies[index];
+                    fdes.push(reader.fde(offset, cie, entry.body)?.1);
+                }
+            }
+            offset = next;
+        }
+        cies.sort_by_key(|cie| cie.offset);
+        Ok(Self {
+            section,
+            cies,
+            fdes,
+        })
+    }
+
+    pub fn section(&self) -> FrameSection {
+        self.section
+    }
+
+    pub fn cies(&self) -> &[Cie] {
+        &self.cies
+    }
+
+    pub fn fdes(&self) -> &[Fde] {
+        &self.fdes
+    }
+
+    pub fn cie(&self, offset: u64) -> Option<&Cie> {
+        self.cies.iter().find(|cie| cie.offset == offset)
+    }
+
+    /// The FDE at an offset in the section, as found through `.eh_frame_hdr`
+    pub fn fde_at(&self, offset: u64) -> Option<&Fde> {
+        self.fdes.iter().find(|fde| fde.offset == offset)
+    }
+
+    /// The FDE describing the function that contains an address
+    pub fn fde_for(&self, address: u64) -> Option<&Fde> {
+        self.fdes.iter().find(|fde| fde.contains(address))
+    }
+
+    /// Executes the instructions of an FDE into the rows of its unwind table
+    pub fn rows(&self, fde: &Fde) -> Vec<UnwindRow> {
+        let Some(cie) = self.cie(fde.cie_offset) else {
+            return Vec::new();
+        };
+        let mut state = State {
+            cfa: CfaRule::RegisterOffset {
+                register: 0,
+                offset: 0,
+            },
+            registers: BTreeMap::new(),
+        };
+        for instruction in &cie.initial_instructions {
+            state.execute(instruction);
+        }
+        let initial = state.registers.clone();
+        let mut saved = Vec::new();
+        let mut rows = Vec::new();
+        let mut start = fde.initial_location;
+        let end = fde.initial_location.saturating_add(fde.address_range);
+        for instruction in &fde.instructions {
+            let location = match instruction {
+                Instruction::AdvanceLoc(delta) => start.saturating_add(*delta),
+                Instruction::SetLoc(address) => *address,
+                Instruction::RememberState => {
+                    saved.push(state.clone());
+                    continue;
+                }
+                Instruction::RestoreState => {
+                    if let Some(previous) = saved.pop() {
+                        state = previous;
+                    }
+                    continue;
+                }
+                Instruction::Restore(register) => {
+                    match initial.get(register) {
+                        Some(rule) => state.set(*register, rule.clone()),
+                        None => {
+                            state.registers.remove(register);
+                        }
+                    }
+                    continue;
+                }
+                instruction => {
+                    state.execute(instruction);
+                    continue;
+                }
+            };
+            if location > start {
+                rows.push(UnwindRow {
+                    start,
+                    end: location.min(end),
+                    cfa: state.cfa.clone(),
+                    registers: state.registers.clone(),
+                });
+                start = location;
+            }
+        }
+        if start < end {
+            rows.push(UnwindRow {
+                start,
+                end,
+                cfa: state.cfa,
+                registers: state.registers,
+            });
+        }
+        rows
+    }
+
+    /// The rules for unwinding a frame stopped at an address
+    pub fn unwind_row(&self, address: u64) -> Option<UnwindRow> {
+        self.row_in(self.fde_for(address)?, address)
+    }
+
+    /// The row of an FDE's unwind table that covers an address
+    pub fn row_in(&self, fde: &Fde, address: u64) -> Option<UnwindRow> {
+        self.rows(fde).into_iter().find(|row| row.contains(address))
+    }
+}
+
+impl fmt::Display for CallFrameInformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for cie in &self.cies {
+            write!(f, "{}", cie)?;
+        }
+        for fde in &self.fdes {
+            writeln!(
+                f,
+                "FDE {:#x} CIE {:#x} [{:#x}, {:#x}):",
+                fde.offset,
+                fde.cie_offset,
+                fde.initial_location,
+                fde.initial_location.saturating_add(fde.address_range)
+            )?;
+            if let Some(lsda) = fde.lsda {
+                writeln!(f, "  LSDA: {}", lsda)?;
+            }
+            for instruction in &fde.instructions {
+                writeln!(f, "  {}", instruction)?;
+            }
+            for row in self.rows(fde) {
+                writeln!(f, "  {}", row)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The start of a CIE or FDE
+struct Entry<'a> {
+    /// The length of the whole entry, including the length field
+    length: u64,
+    is_terminator: bool,
+    /// The CIE an FDE refers to, or `None` for a CIE
+    cie_offset: Option<u64>,
+    /// What follows the CIE identifier or pointer
+    body: parse::Input<'a>,
+}
+
+/// Reads entries out of a section, knowing where it is loaded to resolve relative pointers
+#[derive(Clone, Copy)]
+struct Reader<'a> {
+    data: &'a [u8],
+    section: FrameSection,
+    bases: PointerBases,
+    endianness: Endianness,
+    address_size: u8,
+}
+
+impl<'a> Reader<'a> {
+    fn format(&self, address_size: u8) -> Format {
+        Format {
+            endianness: self.endianness,
+            version: 0,
+            offset_size: 4,
+            address_size,
+        }
+    }
+
+    /// The offset into the section of input taken from it, which may end before the section
+    fn position(&self, input: parse::Input<'a>) -> u64 {
+        (input.as_ptr() as usize - self.data.as_ptr() as usize) as u64
+    }
+
+    fn entry(&self, offset: u64) -> BinDumpResult<Entry<'a>> {
+        let input = self
+            .data
+            .get(offset as usize..)
+            .ok_or_else(|| BinDumpError::ParseError {
+                error: format!("Call frame entry offset {:#x} is out of bounds", offset),
+            })?;
+        let (rest, (length, offset_size)) = context(
+            "Parse Call Frame Entry Length",
+            initial_length(self.endianness),
+        )(input)?;
+        let header_length = if offset_size == 8 { 12 } else { 4 };
+        if length == 0 {
+            return Ok(Entry {
+                length: header_length,
+                is_terminator: true,
+                cie_offset: None,
+                body: &[],
+            });
+        }
+        let (_, contents) = context("Parse Call Frame Entry", take(length))(rest)?;
+        let format = Format {
+            offset_size,
+            ..self.format(self.address_size)
+        };
+        let (body, id) = context("Parse CIE Pointer", format.offset())(contents)?;
+        let cie_offset = match self.section {
+            FrameSection::EhFrame if id == 0 => None,
+            // `.eh_frame` FDEs point back from the pointer itself
+            FrameSection::EhFrame => Some((offset + header_length).wrapping_sub(id)),
+            FrameSection::DebugFrame if id == 0xffff_ffff || id == u64::MAX => None,
+            FrameSection::DebugFrame => Some(id),
+        };
+        Ok(Entry {
+            length: header_length + length,
+            is_terminator: false,
+            cie_offset,
+            body,
+        })
+    }
+
+    fn cie(&self, offset: u64, input: parse::Input<'a>) -> parse::ParseResult<'a, Cie> {
+        let (input, version) = context("Parse CIE Version", complete::u8)(input)?;
+        let (mut input, augmentation) = context("Parse CIE Augmentation", string)(input)?;
+        // Old GCC versions stored a pointer to exception data here
+        if augmentation.contains("eh") {
+            input = take(self.address_size)(input)?.0;
+        }
+        let (input, (address_size, segment_selector_size)) = if version >= 4 {
+            context(
+                "Parse CIE Address Size",
+                nom::sequence::tuple((complete::u8, complete::u8)),
+            )(input)?
+        } else {
+            (input, (self.address_size, 0))
+        };
+        let (input, (code_alignment_factor, data_alignment_factor)) = context(
+            "Parse CIE Alignment Factors",
+            nom::sequence::tuple((uleb128, sleb128)),
+        )(input)?;
+        let (mut input, return_address_register) = if version == 1 {
+            context(
+                "Parse CIE Return Address Register",
+                map(complete::u8, u64::from),
+            )(input)?
+        } else {
+            context("Parse CIE Return Address Register", uleb128)(input)?
+        };
+        let mut cie = Cie {
+            offset,
+            version,
+            augmentation,
+            address_size,
+            segment_selector_size,
+            code_alignment_factor,
+            data_alignment_factor,
+            return_address_register,
+            fde_encoding: DW_EH_PE_absptr,
+            lsda_encoding: None,
+            personality: None,
+            signal_frame: false,
+            initial_instructions: Vec::new(),
+        };
+        if cie.augmentation.starts_with('z') {
+            let (rest, length) = context("Parse CIE Augmentation Length", uleb128)(input)?;
+            let (rest, mut data) = context("Parse CIE Augmentation Data", take(length))(rest)?;
+            input = rest;
+            for character in cie.augmentation[1..].chars() {
+                match character {
+                    'L' => {
+                        let (rest, encoding) = complete::u8(data)?;
+                        data = rest;
+                        cie.lsda_encoding = Some(encoding);
+                    }
+                    'P' => {
+                        let (rest, encoding) = complete::u8(data)?;
+                        let (rest, personality) = self.pointer(rest, encoding, address_size, 0)?;
+                        data = rest;
+                        cie.personality = personality;
+                    }
+                    'R' => {
+                        let (rest, encoding) = complete::u8(data)?;
+                        data = rest;
+                        cie.fde_encoding = encoding;
+                    }
+                    'S' => cie.signal_frame = true,
+                    // Branch target identification and memory tagging don't change unwinding
+                    'B' | 'G' => {}
+                    _ => break,
+                }
+            }
+        } else if !cie.augmentation.is_empty() && cie.augmentation != "eh" {
+            // Nothing after an unknown augmentation can be read
+            return Ok((&[], cie));
+        }
+        let (input, instructions) = self.instructions(input, &cie)?;
+        cie.initial_instructions = instructions;
+        Ok((input, cie))
+    }
+
+    fn fde(&self, offset: u64, cie: &Cie, input: parse::Input<'a>) -> parse::ParseResult<'a, Fde> {
+        let (input, _segment_selector) = take(cie.segment_selector_size)(input)?;
+        let (input, initial_location) =
+            self.pointer(input, cie.fde_encoding, cie.address_size, 0)?;
+        // The range is a size, so it's read in the format of the encoding without applying it
+        let (mut input, address_range) =
+            self.pointer(input, cie.fde_encoding & 0x0f, cie.address_size, 0)?;
+        let initial_location = initial_location.map_or(0, |pointer| pointer.address);
+        let mut lsda = None;
+        if cie.augmentation.starts_with('z') {
+            let (rest, length) = context("Parse FDE Augmentation Length", uleb128)(input)?;
+            let (rest, data) = context("Parse FDE Augmentation Data", take(length))(rest)?;
+            input = rest;
+            if let Some(encoding) = cie.lsda_encoding {
+                lsda = self
+                    .pointer(data, encoding, cie.address_size, initial_location)?
+                    .1;
+            }
+        }
+        let (input, instructions) = self.instructions(input, cie)?;
+        Ok((
+            input,
+            Fde {
+                offset,
+                cie_offset: cie.offset,
+                initial_location,
+                address_range: address_range.map_or(0, |pointer| pointer.address),
+                lsda,
+                instructions,
+            },
+        ))
+    }
+
+    /// Reads a pointer in a `DW_EH_PE_*` encoding, which is `None` for `DW_EH_PE_omit`.
+    /// `function` is the start of the function that `DW_EH_PE_funcrel` pointers are relative to.
+    fn pointer(
+        &self,
+        input: parse::Input<'a>,
+        encoding: u8,
+        address_size: u8,
+        function: u64,
+    ) -> parse::ParseResult<'a, Option<Pointer>> {
+        if encoding == DW_EH_PE_omit {
+            return Ok((input, None));
+        }
+        let format = self.format(address_size);
+        let mut input = input;
+        if encoding & 0x70 == DW_EH_PE_aligned {
+            let misalignment = (self.bases.section + self.position(input)) % address_size as u64;
+            if misalignment != 0 {
+                input = take(address_size as u64 - misalignment)(input)?.0;
+            }
+        }
+        let position = self.bases.section.wrapping_add(self.position(input));
+        let (input, value) = context("Parse Encoded Pointer", |input| match encoding & 0x0f {
+            DW_EH_PE_absptr => format.address()(input),
+            DW_EH_PE_uleb128 => uleb128(input),
+            DW_EH_PE_udata2 => format.sized(2)(input),
+            DW_EH_PE_udata4 => format.sized(4)(input),
+            DW_EH_PE_udata8 => format.sized(8)(input),
+            DW_EH_PE_sleb128 => map(sleb128, |value| value as u64)(input),
+            DW_EH_PE_sdata2 => map(complete::i16(self.endianness), |value| value as u64)(input),
+            DW_EH_PE_sdata4 => map(complete::i32(self.endianness), |value| value as u64)(input),
+            DW_EH_PE_sdata8 => map(complete::i64(self.endianness), |value| value as u64)(input),
+            _ => Err(failure(input, "Unknown pointer format")),
+        })(input)?;
+        let base = match encoding & 0x70 {
+            DW_EH_PE_absptr | DW_EH_PE_aligned => 0,
+            DW_EH_PE_pcrel => position,
+            DW_EH_PE_textrel => self.bases.text,
+            DW_EH_PE_datarel => self.bases.data,
+            DW_EH_PE_funcrel => function,
+            _ => return Err(failure(input, "Unknown pointer application")),
+        };
+        let mut address = base.wrapping_add(value);
+        if address_size == 4 {
+            address &= 0xffff_ffff;
+        }
+        Ok((
+            input,
+            Some(Pointer {
+                address,
+                indirect: encoding & DW_EH_PE_indirect != 0,
+            }),
+        ))
+    }
+
+    /// Decodes the instructions that fill the rest of an entry
+    fn instructions(
+        &self,
+        input: parse::Input<'a>,
+        cie: &Cie,
+    ) -> parse::ParseResult<'a, Vec<Instruction>> {
+        let code = |delta: u64| delta.wrapping_mul(cie.code_alignment_factor);
+        let data = |offset: i64| offset.wrapping_mul(cie.data_alignment_factor);
+        let format = self.format(cie.address_size);
+        let mut instructions = Vec::new();
+        let mut input = input;
+        while !input.is_empty() {
+            let (rest, opcode) = context("Parse Call Frame Instruction", complete::u8)(input)?;
+            let operand = (opcode & 0x3f) as u64;
+            let (rest, instruction) = match opcode & 0xc0 {
+                DW_CFA_advance_loc => (rest, Instruction::AdvanceLoc(code(operand))),
+                DW_CFA_offset => map(uleb128, |offset| Instruction::Offset {
+                    register: operand,
+                    offset: data(offset as i64),
+                })(rest)?,
+                DW_CFA_restore => (rest, Instruction::Restore(operand)),
+                _ => match opcode {
+                    DW_CFA_nop => (rest, Instruction::Nop),
+                    DW_CFA_set_loc => {
+                        let (rest, address) =
+                            self.pointer(rest, cie.fde_encoding, cie.address_size, 0)?;
+                        let address = address.map_or(0, |pointer| pointer.address);
+                        (rest, Instruction::SetLoc(address))
+                    }
+                    DW_CFA_advance_loc1 => map(format.sized(1), |delta| {
+                        Instruction::AdvanceLoc(code(delta))
+                    })(rest)?,
+                    DW_CFA_advance_loc2 => map(format.sized(2), |delta| {
+                        Instruction::AdvanceLoc(code(delta))
+                    })(rest)?,
+                    DW_CFA_advance_loc4 => map(format.sized(4), |delta| {
+                        Instruction::AdvanceLoc(code(delta))
+                    })(rest)?,
+                    DW_CFA_offset_extended => map(
+                        nom::sequence::tuple((uleb128, uleb128)),
+                        |(register, offset)| Instruction::Offset {
+                            register,
+                            offset: data(offset as i64),
+                        },
+                    )(rest)?,
+                    DW_CFA_restore_extended => map(uleb128, Instruction::Restore)(rest)?,
+                    DW_CFA_undefined => map(uleb128, Instruction::Undefined)(rest)?,
+                    DW_CFA_same_value => map(uleb128, Instruction::SameValue)(rest)?,
+                    DW_CFA_register => map(
+                        nom::sequence::tuple((uleb128, uleb128)),
+                        |(register, source)| Instruction::Register { register, source },
+                    )(rest)?,
+                    DW_CFA_remember_state => (rest, Instruction::RememberState),
+                    DW_CFA_restore_state => (rest, Instruction::RestoreState),
+                    DW_CFA_def_cfa => map(
+                        nom::sequence::tuple((uleb128, uleb128)),
+                        |(register, offset)| Instruction::DefCfa {
+                            register,
+                            offset: offset as i64,
+                        },
+                    )(rest)?,
+                    DW_CFA_def_cfa_register => map(uleb128, Instruction::DefCfaRegister)(rest)?,
+                    DW_CFA_def_cfa_offset => {
+                        map(uleb128, |offset| Instruction::DefCfaOffset(offset as i64))(rest)?
+                    }
+                    DW_CFA_def_cfa_expression => map(block, Instruction::DefCfaExpression)(rest)?,
+                    DW_CFA_expression => map(
+                        nom::sequence::tuple((uleb128, block)),
+                        |(register, expression)| Instruction::Expression {
+                            register,
+                            expression,
+                        },
+                    )(rest)?,
+                    DW_CFA_offset_extended_sf => map(
+                        nom::sequence::tuple((uleb128, sleb128)),
+                        |(register, offset)| Instruction::Offset {
+                            register,
+                            offset: data(offset),
+                        },
+                    )(rest)?,
+                    DW_CFA_def_cfa_sf => map(
+                        nom::sequence::tuple((uleb128, sleb128)),
+                        |(register, offset)| Instruction::DefCfa {
+                            register,
+                            offset: data(offset),
+                        },
+                    )(rest)?,
+                    DW_CFA_def_cfa_offset_sf => {
+                        map(sleb128, |offset| Instruction::DefCfaOffset(data(offset)))(rest)?
+                    }
+                    DW_CFA_val_offset => map(
+                        nom::sequence::tuple((uleb128, uleb128)),
+                        |(register, offset)| Instruction::ValOffset {
+                            register,
+                            offset: data(offset as i64),
+                        },
+                    )(rest)?,
+                    DW_CFA_val_offset_sf => map(
+                        nom::sequence::tuple((uleb128, sleb128)),
+                        |(register, offset)| Instruction::ValOffset {
+                            register,
+                            offset: data(offset),
+                        },
+                    )(rest)?,
+                    DW_CFA_val_expression => map(
+                        nom::sequence::tuple((uleb128, block)),
+                        |(register, expression)| Instruction::ValExpression {
+                            register,
+                            expression,
+                        },
+                    )(rest)?,
+                    DW_CFA_GNU_window_save => (rest, Instruction::WindowSave),
+                    DW_CFA_GNU_args_size => map(uleb128, Instruction::ArgsSize)(rest)?,
+                    DW_CFA_GNU_negative_offset_extended => map(
+                        nom::sequence::tuple((uleb128, uleb128)),
+                        |(register, offset)| Instruction::Offset {
+                            register,
+                            offset: data(-(offset as i64)),
+                        },
+                    )(rest)?,
+                    _ => return Err(failure(input, "Unknown call frame instruction")),
+                },
+            };
+            instructions.push(instruction);
+            input = rest;
+        }
+        Ok((input, instructions))
+    }
+}
+
+/// Parses a DWARF expression preceded by its length
+fn block(input: parse::Input) -> parse::ParseResult<Vec<u8>> {
+    let (input, length) = uleb128(input)?;
+    map(take(length), <[u8]>::to_vec)(input)
+}
+
+/// The `.eh_frame_hdr` section, a sorted table for finding the FDE of an address quickly
+#[derive(Debug)]
+pub struct EhFrameHeader {
+    pub version: u8,
+    /// The address of `.eh_frame`
+    pub eh_frame: u64,
+    /// The start address of each function and the address of its FDE, sorted by start address
+    pub table: Vec<(u64, u64)>,
+}
+
+impl EhFrameHeader {
+    /// Parses the section loaded at `address`, which its table entries are usually relative to
+    pub fn parse(
+        data: &[u8],
+        address: u64,
+        endianness: Endianness,
+        address_size: u8,
+    ) -> BinDumpResult<Self> {
+        let reader = Reader {
+            data,
+            section: FrameSection::EhFrame,
+            bases: PointerBases {
+                section: address,
+                text: 0,
+                data: address,
+            },
+            endianness,
+            address_size,
+        };
+        let (input, (version, eh_frame_encoding, count_encoding, table_encoding)) = context(
+            "Parse Eh Frame Header",
+            nom::sequence::tuple((complete::u8, complete::u8, complete::u8, complete::u8)),
+        )(data)?;
+        let (input, eh_frame) = reader.pointer(input, eh_frame_encoding, address_size, 0)?;
+        let (mut input, count) = reader.pointer(input, count_encoding, address_size, 0)?;
+        let mut table = Vec::new();
+        if table_encoding != DW_EH_PE_omit {
+            for _ in 0..count.map_or(0, |count| count.address) {
+                let (rest, start) = reader.pointer(input, table_encoding, address_size, 0)?;
+                let (rest, fde) = reader.pointer(rest, table_encoding, address_size, 0)?;
+                input = rest;
+                if let (Some(start), Some(fde)) = (start, fde) {
+                    table.push((start.address, fde.address));
+                }
+            }
+        }
+        Ok(Self {
+            version,
+            eh_frame: eh_frame.map_or(0, |pointer| pointer.address),
+            table,
+        })
+    }
+
+    /// The address of the FDE that may contain an address, which still has to be checked
+    /// against the range of the FDE
+    pub fn lookup(&self, address: u64) -> Option<u64> {
+        let index = self.table.partition_point(|&(start, _)| start <= address);
+        Some(self.table.get(index.checked_sub(1)?)?.1)
+    }
+}
+
+impl fmt::Display for EhFrameHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "Eh Frame: {:#x}", self.eh_frame)?;
+        writeln!(f, "Table:")?;
+        for (start, fde) in &self.table {
+            writeln!(f, "  {:#x}: FDE at {:#x}", start, fde)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prefixes an entry body with its 32-bit length
+    fn entry(body: &[u8]) -> Vec<u8> {
+        let mut data = (body.len() as u32).to_le_bytes().to_vec();
+        data.extend_from_slice(body);
+        data
+    }
+
+    /// A `.eh_frame` at 0x2000 with a "zR" CIE and one FDE for [0x1000, 0x1010)
+    fn eh_frame() -> Vec<u8> {
+        let mut data = entry(&[
+            0, 0, 0, 0, // CIE id
+            1, b'z', b'R', 0, // version, augmentation
+            1, 0x78, 16, // code and data alignment, return address register
+            1, 0x1b, // augmentation data: pcrel | sdata4 FDEs
+            0x0c, 7, 8, // DW_CFA_def_cfa r7+8
+            0x90, 1, // DW_CFA_offset r16 at CFA-8
+            0, 0,
+        ]);
+        let mut fde = Vec::new();
+        fde.extend_from_slice(&0x1cu32.to_le_bytes()); // back to the CIE
+        fde.extend_from_slice(&(0x1000i32 - 0x2020).to_le_bytes()); // pcrel initial location
+        fde.extend_from_slice(&0x10u32.to_le_bytes());
+        fde.extend_from_slice(&[
+            0,    // augmentation data length
+            0x41, // DW_CFA_advance_loc 1
+            0x0e, 16, // DW_CFA_def_cfa_offset 16
+            0x86, 2,    // DW_CFA_offset r6 at CFA-16
+            0x43, // DW_CFA_advance_loc 3
+            0x0d, 6, // DW_CFA_def_cfa_register r6
+            0, 0, 0,
+        ]);
+        data.extend(entry(&fde));
+        data.extend_from_slice(&[0; 4]);
+        data
+    }
+
+    fn parse_eh_frame() -> CallFrameInformation {
+        let bases = PointerBases {
+            section: 0x2000,
+            ..Default::default()
+        };
+        CallFrameInformation::parse(
+            &eh_frame(),
+            FrameSection::EhFrame,
+            bases,
+            Endianness::Little,
+            8,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_eh_frame_cie_and_fde() {
+        let cfi = parse_eh_frame();
+        let cie = cfi.cie(0).unwrap();
+        assert_eq!(cie.augmentation, "zR");
+        assert_eq!(cie.data_alignment_factor, -8);
+        assert_eq!(cie.return_address_register, 16);
+        assert_eq!(cie.fde_encoding, 0x1b);
+
+        let fde = cfi.fde_at(0x18).unwrap();
+        assert_eq!(fde.cie_offset, 0);
+        assert_eq!(fde.initial_location, 0x1000);
+        assert_eq!(fde.address_range, 0x10);
+        assert!(cfi.fde_for(0x100f).is_some());
+        assert!(cfi.fde_for(0x1010).is_none());
+    }
+
+    #[test]
+    fn executes_fde_into_rows() {
+        let cfi = parse_eh_frame();
+        let rows: Vec<String> = cfi
+            .rows(&cfi.fdes()[0])
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                "[0x1000, 0x1001): CFA=r7+8 r16=[CFA-8]",
+                "[0x1001, 0x1004): CFA=r7+16 r6=[CFA-16] r16=[CFA-8]",
+                "[0x1004, 0x1010): CFA=r6+16 r6=[CFA-16] r16=[CFA-8]",
+            ]
+        );
+        let row = cfi.unwind_row(0x1002).unwrap();
+        assert_eq!(
+            row.cfa,
+            CfaRule::RegisterOffset {
+                register: 7,
+                offset: 16
+            }
+        );
+        assert!(cfi.unwind_row(0x1010).is_none());
+    }
+
+    #[test]
+    fn debug_frame_fde_may_precede_its_cie() {
+        let mut fde = Vec::new();
+        fde.extend_from_slice(&0x1cu32.to_le_bytes()); // absolute CIE offset
+        fde.extend_from_slice(&0x1000u64.to_le_bytes());
+        fde.extend_from_slice(&0x10u64.to_le_bytes());
+        fde.extend_from_slice(&[0x41, 0x0e, 16, 0]);
+        let mut data = entry(&fde);
+        data.extend(entry(&[
+            0xff, 0xff, 0xff, 0xff, // CIE id
+            1, 0, // version, no augmentation
+            1, 0x78, 16, 0x0c, 7, 8,
+        ]));
+        let cfi = CallFrameInformation::parse(
+            &data,
+            FrameSection::DebugFrame,
+            PointerBases::default(),
+            Endianness::Little,
+            8,
+        )
+        .unwrap();
+        assert_eq!(cfi.cies().len(), 1);
+        assert_eq!(cfi.fdes()[0].cie_offset, 0x1c);
+        assert_eq!(
+            cfi.unwind_row(0x1008).unwrap().to_string(),
+            "[0x1001, 0x1010): CFA=r7+16"
+        );
+    }
+
+    #[test]
+    fn looks_up_fdes_in_eh_frame_header() {
+        // At 0x1f00: pcrel eh_frame pointer, udata4 count and a datarel | sdata4 table
+        let mut data = vec![1, 0x1b, 0x03, 0x3b];
+        data.extend_from_slice(&(0x2000i32 - 0x1f04).to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        for (start, fde) in [(0x1000i32, 0x2018i32), (0x1100, 0x2040)] {
+            data.extend_from_slice(&(start - 0x1f00).to_le_bytes());
+            data.extend_from_slice(&(fde - 0x1f00).to_le_bytes());
+        }
+        let header = EhFrameHeader::parse(&data, 0x1f00, Endianness::Little, 8).unwrap();
+        assert_eq!(header.eh_frame, 0x2000);
+        assert_eq!(header.table, [(0x1000, 0x2018), (0x1100, 0x2040)]);
+        assert_eq!(header.lookup(0xfff), None);
+        assert_eq!(header.lookup(0x1050), Some(0x2018));
+        assert_eq!(header.lookup(0x2000), Some(0x2040));
+    }
+}
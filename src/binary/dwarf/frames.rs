@@ -0,0 +1,156 @@
+// Tags are matched by their DW_* names
+#![allow(non_upper_case_globals)]
+
+use std::fmt;
+
+use super::constants::*;
+use super::line::LineRow;
+use super::unit::{AttributeValue, Die, Unit};
+use super::Dwarf;
+
+/// A function active at an address, with the source position in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub function: Option<String>,
+    pub file: Option<String>,
+    /// 0 when the line is unknown
+    pub line: u64,
+    pub column: u64,
+    /// Whether the function was inlined into the next frame
+    pub inlined: bool,
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {}:{}:{}",
+            self.function.as_deref().unwrap_or("??"),
+            self.file.as_deref().unwrap_or("??"),
+            self.line,
+            self.column
+        )?;
+        if self.inlined {
+            write!(f, " (inlined)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Entries whose children may be functions even though they cover no addresses themselves
+const CONTAINERS: [DwTag; 6] = [
+    DW_TAG_namespace,
+    DW_TAG_module,
+    DW_TAG_class_type,
+    DW_TAG_structure_type,
+    DW_TAG_union_type,
+    DW_TAG_interface_type,
+];
+
+impl Dwarf {
+    /// The functions active at an address, innermost first, so inlined calls come before the
+    /// functions they were inlined into
+    pub fn find_frames(&self, address: u64) -> Vec<Frame> {
+        for unit in &self.units {
+            let Some(root) = &unit.root else {
+                continue;
+            };
+            let ranges = root.ranges();
+            if !ranges.is_empty() && !ranges.iter().any(|range| range.contains(address)) {
+                continue;
+            }
+            let mut scopes = Vec::new();
+            find_scopes(root, address, &mut scopes);
+            let row = unit
+                .line_program
+                .as_ref()
+                .and_then(|program| program.find_row(address));
+            if scopes.is_empty() && row.is_none() {
+                continue;
+            }
+            return self.frames(unit, &scopes, row);
+        }
+        Vec::new()
+    }
+
+    /// Builds the frames for a chain of scopes, from the outermost function to the innermost
+    /// inlined call
+    fn frames(&self, unit: &Unit, scopes: &[&Die], row: Option<&LineRow>) -> Vec<Frame> {
+        let file_path = |index: u64| {
+            unit.line_program
+                .as_ref()
+                .and_then(|program| program.file_path(index))
+        };
+        let mut file = row.and_then(|row| file_path(row.file));
+        let mut line = row.map_or(0, |row| row.line);
+        let mut column = row.map_or(0, |row| row.column);
+        if scopes.is_empty() {
+            return vec![Frame {
+                function: None,
+                file,
+                line,
+                column,
+                inlined: false,
+            }];
+        }
+        let mut frames = Vec::with_capacity(scopes.len());
+        for (i, scope) in scopes.iter().enumerate().rev() {
+            frames.push(Frame {
+                function: self.function_name(scope),
+                file: file.take(),
+                line,
+                column,
+                inlined: i > 0,
+            });
+            // The caller's position is where the call was inlined
+            let attribute = |name| scope.attribute(name).and_then(AttributeValue::as_u64);
+            file = attribute(DW_AT_call_file).and_then(file_path);
+            line = attribute(DW_AT_call_line).unwrap_or(0);
+            column = attribute(DW_AT_call_column).unwrap_or(0);
+        }
+        frames
+    }
+
+    /// The name of a function, looked up through the entries that inlined copies and
+    /// out-of-line definitions refer to
+    pub fn function_name(&self, die: &Die) -> Option<String> {
+        let mut die = die;
+        // Bounded so that a reference cycle can't loop forever
+        for _ in 0..8 {
+            for name in [DW_AT_name, DW_AT_linkage_name, DW_AT_MIPS_linkage_name] {
+                if let Some(name) = die.attribute(name).and_then(AttributeValue::as_str) {
+                    return Some(name.to_string());
+                }
+            }
+            let reference = [DW_AT_abstract_origin, DW_AT_specification]
+                .into_iter()
+                .find_map(|name| match die.attribute(name) {
+                    Some(AttributeValue::Reference(offset)) => Some(*offset),
+                    _ => None,
+                })?;
+            die = self.die_at(reference)?;
+        }
+        None
+    }
+}
+
+/// Collects the functions and inlined calls that contain an address, outermost first
+fn find_scopes<'a>(die: &'a Die, address: u64, scopes: &mut Vec<&'a Die>) {
+    for child in &die.children {
+        let ranges = child.ranges();
+        if ranges.is_empty() {
+            if CONTAINERS.contains(&child.tag) {
+                find_scopes(child, address, scopes);
+            }
+            continue;
+        }
+        if !ranges.iter().any(|range| range.contains(address)) {
+            continue;
+        }
+        if matches!(child.tag, DW_TAG_subprogram | DW_TAG_inlined_subroutine) {
+            scopes.push(child);
+        }
+        find_scopes(child, address, scopes);
+        return;
+    }
+}
@@ -0,0 +1,592 @@
+// Opcodes are matched against the DW_* constants, which keep the names of the standard
+#![allow(non_upper_case_globals)]
+
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::error::context;
+use nom::number::{complete, Endianness};
+use nom::sequence::tuple;
+
+use super::constants::*;
+use super::unit::{parse_value, AttributeValue};
+use super::{initial_length, string, DwarfSections, Format};
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse::{self, failure, sleb128, uleb128};
+
+/// A source file of a line table
+#[derive(Debug, Clone, Default)]
+pub struct FileEntry {
+    pub path: String,
+    pub directory_index: u64,
+    /// Modification time, 0 if unknown
+    pub timestamp: u64,
+    /// Size in bytes, 0 if unknown
+    pub size: u64,
+    pub md5: Option<[u8; 16]>,
+}
+
+/// The source position of a machine instruction, one row of the line table
+#[derive(Debug, Clone, Copy)]
+pub struct LineRow {
+    pub address: u64,
+    pub file: u64,
+    pub line: u64,
+    pub column: u64,
+    pub is_stmt: bool,
+    pub basic_block: bool,
+    /// The first address past the end of a sequence of instructions
+    pub end_sequence: bool,
+    pub prologue_end: bool,
+    pub epilogue_begin: bool,
+    pub isa: u64,
+    pub discriminator: u64,
+}
+
+impl LineRow {
+    fn new(default_is_stmt: bool) -> Self {
+        Self {
+            address: 0,
+            file: 1,
+            line: 1,
+            column: 0,
+            is_stmt: default_is_stmt,
+            basic_block: false,
+            end_sequence: false,
+            prologue_end: false,
+            epilogue_begin: false,
+            isa: 0,
+            discriminator: 0,
+        }
+    }
+}
+
+impl fmt::Display for LineRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:#018x} {:>6} {:>6} {:>4} {:>3} {:>13}",
+            self.address, self.line, self.column, self.file, self.isa, self.discriminator
+        )?;
+        let flags = [
+            (self.is_stmt, "is_stmt"),
+            (self.basic_block, "basic_block"),
+            (self.end_sequence, "end_sequence"),
+            (self.prologue_end, "prologue_end"),
+            (self.epilogue_begin, "epilogue_begin"),
+        ];
+        for (_, name) in flags.iter().filter(|(set, _)| *set) {
+            write!(f, " {}", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// A line number program from `.debug_line` and the rows it produces
+#[derive(Debug, Clone)]
+pub struct LineProgram {
+    pub offset: u64,
+    pub version: u16,
+    pub address_size: u8,
+    pub minimum_instruction_length: u8,
+    pub maximum_operations_per_instruction: u8,
+    pub default_is_stmt: bool,
+    pub line_base: i8,
+    pub line_range: u8,
+    pub opcode_base: u8,
+    /// The unit's `DW_AT_comp_dir`, which is directory 0 before DWARF 5
+    pub compilation_directory: Option<String>,
+    pub include_directories: Vec<String>,
+    pub files: Vec<FileEntry>,
+    pub rows: Vec<LineRow>,
+}
+
+impl LineProgram {
+    pub fn parse(
+        sections: &DwarfSections,
+        endianness: Endianness,
+        address_size: u8,
+        offset: u64,
+        compilation_directory: Option<String>,
+    ) -> BinDumpResult<Self> {
+        let data =
+            sections
+                .debug_line
+                .get(offset as usize..)
+                .ok_or_else(|| BinDumpError::ParseError {
+                    error: format!(
+                        "Line table offset {:#x} is past the end of .debug_line",
+                        offset
+                    ),
+                })?;
+        let (input, (unit_length, offset_size)) = initial_length(endianness)(data)?;
+        let input = &input[..(unit_length as usize).min(input.len())];
+        let (input, version) =
+            context("Parse Line Table Version", complete::u16(endianness))(input)?;
+        if !(2..=5).contains(&version) {
+            return Err(BinDumpError::Unsupported {
+                format: format!("DWARF line table version {}", version),
+            });
+        }
+        let mut format = Format {
+            endianness,
+            version,
+            offset_size,
+            address_size,
+        };
+        let input = if version >= 5 {
+            let (input, (address_size, _segment_selector_size)) =
+                tuple((complete::u8, complete::u8))(input)?;
+            format.address_size = address_size;
+            input
+        } else {
+            input
+        };
+        let (input, header_length) = format.offset()(input)?;
+        let program = input.get(header_length as usize..).unwrap_or_default();
+        let (input, (minimum_instruction_length, maximum_operations_per_instruction)) =
+            if version >= 4 {
+                tuple((complete::u8, complete::u8))(input)?
+            } else {
+                let (input, minimum_instruction_length) = complete::u8(input)?;
+                (input, (minimum_instruction_length, 1))
+            };
+        let (input, (default_is_stmt, line_base, line_range, opcode_base)) = context(
+            "Parse Line Table Header",
+            tuple((complete::u8, complete::i8, complete::u8, complete::u8)),
+        )(input)?;
+        let (input, standard_opcode_lengths) = take(opcode_base.saturating_sub(1))(input)?;
+
+        let mut line_program = Self {
+            offset,
+            version,
+            address_size: format.address_size,
+            minimum_instruction_length,
+            maximum_operations_per_instruction,
+            default_is_stmt: default_is_stmt != 0,
+            line_base,
+            line_range,
+            opcode_base,
+            compilation_directory,
+            include_directories: Vec::new(),
+            files: Vec::new(),
+            rows: Vec::new(),
+        };
+        if version >= 5 {
+            let (input, directories) = parse_entries(input, format, sections)?;
+            line_program.include_directories =
+                directories.into_iter().map(|entry| entry.path).collect();
+            line_program.files = parse_entries(input, format, sections)?.1;
+        } else {
+            let mut input = input;
+            loop {
+                let (rest, directory) = string(input)?;
+                input = rest;
+                if directory.is_empty() {
+                    break;
+                }
+                line_program.include_directories.push(directory);
+            }
+            loop {
+                let (rest, file) = parse_file_entry(input)?;
+                input = rest;
+                match file {
+                    Some(file) => line_program.files.push(file),
+                    None => break,
+                }
+            }
+        }
+        if line_range == 0 {
+            return Err(BinDumpError::ParseError {
+                error: format!("Line table at {:#x} has a line range of 0", offset),
+            });
+        }
+        line_program.run(program, format, standard_opcode_lengths)?;
+        Ok(line_program)
+    }
+
+    /// Runs the line number program, appending a row for every emitted instruction
+    fn run(
+        &mut self,
+        program: parse::Input,
+        format: Format,
+        standard_opcode_lengths: &[u8],
+    ) -> BinDumpResult<()> {
+        let minimum_instruction_length = self.minimum_instruction_length as u64;
+        let mut row = LineRow::new(self.default_is_stmt);
+        let mut input = program;
+        while !input.is_empty() {
+            let (rest, opcode) = complete::u8(input)?;
+            input = rest;
+            if opcode >= self.opcode_base {
+                let adjusted = opcode - self.opcode_base;
+                row.address = row
+                    .address
+                    .wrapping_add((adjusted / self.line_range) as u64 * minimum_instruction_length);
+                row.line = row.line.wrapping_add_signed(
+                    self.line_base as i64 + (adjusted % self.line_range) as i64,
+                );
+                self.emit(&mut row);
+                continue;
+            }
+            match opcode {
+                0 => {
+                    let (rest, length) = uleb128(input)?;
+                    let (rest, body) = take(length)(rest)?;
+                    input = rest;
+                    let Some((&extended, operands)) = body.split_first() else {
+                        continue;
+                    };
+                    match extended {
+                        DW_LNE_end_sequence => {
+                            row.end_sequence = true;
+                            self.emit(&mut row);
+                            row = LineRow::new(self.default_is_stmt);
+                        }
+                        DW_LNE_set_address => {
+                            row.address = format.sized(operands.len() as u8)(operands)?.1;
+                        }
+                        DW_LNE_define_file => {
+                            if let (_, Some(file)) = parse_file_entry(operands)? {
+                                self.files.push(file);
+                            }
+                        }
+                        DW_LNE_set_discriminator => row.discriminator = uleb128(operands)?.1,
+                        _ => {}
+                    }
+                }
+                DW_LNS_copy => self.emit(&mut row),
+                DW_LNS_advance_pc => {
+                    let (rest, advance) = uleb128(input)?;
+                    input = rest;
+                    row.address = row
+                        .address
+                        .wrapping_add(advance.wrapping_mul(minimum_instruction_length));
+                }
+                DW_LNS_advance_line => {
+                    let (rest, advance) = sleb128(input)?;
+                    input = rest;
+                    row.line = row.line.wrapping_add_signed(advance);
+                }
+                DW_LNS_set_file => (input, row.file) = uleb128(input)?,
+                DW_LNS_set_column => (input, row.column) = uleb128(input)?,
+                DW_LNS_negate_stmt => row.is_stmt = !row.is_stmt,
+                DW_LNS_set_basic_block => row.basic_block = true,
+                DW_LNS_const_add_pc => {
+                    let adjusted = 255 - self.opcode_base;
+                    row.address = row.address.wrapping_add(
+                        (adjusted / self.line_range) as u64 * minimum_instruction_length,
+                    );
+                }
+                DW_LNS_fixed_advance_pc => {
+                    let (rest, advance) = format.u16()(input)?;
+                    input = rest;
+                    row.address = row.address.wrapping_add(advance as u64);
+                }
+                DW_LNS_set_prologue_end => row.prologue_end = true,
+                DW_LNS_set_epilogue_begin => row.epilogue_begin = true,
+                DW_LNS_set_isa => (input, row.isa) = uleb128(input)?,
+                // Opcodes this reader doesn't know are skipped using their operand counts
+                opcode => {
+                    let operands = standard_opcode_lengths
+                        .get(opcode as usize - 1)
+                        .copied()
+                        .unwrap_or(0);
+                    for _ in 0..operands {
+                        input = uleb128(input)?.0;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends a row and resets the registers that only apply to one row
+    fn emit(&mut self, row: &mut LineRow) {
+        self.rows.push(*row);
+        row.basic_block = false;
+        row.prologue_end = false;
+        row.epilogue_begin = false;
+        row.discriminator = 0;
+    }
+
+    /// A file by the index rows use, which starts at 1 before DWARF 5 and at 0 since
+    pub fn file(&self, index: u64) -> Option<&FileEntry> {
+        let index = if self.version >= 5 {
+            index
+        } else {
+            index.checked_sub(1)?
+        };
+        self.files.get(index as usize)
+    }
+
+    /// A directory by the index file entries use
+    pub fn directory(&self, index: u64) -> Option<&str> {
+        if self.version >= 5 {
+            return self
+                .include_directories
+                .get(index as usize)
+                .map(String::as_str);
+        }
+        match index {
+            0 => self.compilation_directory.as_deref(),
+            index => self
+                .include_directories
+                .get(index as usize - 1)
+                .map(String::as_str),
+        }
+    }
+
+    /// The full path of a file, joined with its directory and the compilation directory
+    pub fn file_path(&self, index: u64) -> Option<String> {
+        let file = self.file(index)?;
+        if file.path.starts_with('/') {
+            return Some(file.path.clone());
+        }
+        let mut path = String::new();
+        if let Some(directory) = self.directory(file.directory_index) {
+            if !directory.starts_with('/') {
+                if let Some(compilation_directory) = &self.compilation_directory {
+                    path.push_str(compilation_directory);
+                    path.push('/');
+                }
+            }
+            path.push_str(directory);
+            path.push('/');
+        }
+        path.push_str(&file.path);
+        Some(path)
+    }
+
+    /// The row covering an address, which runs from that row up to the next one in its sequence
+    pub fn find_row(&self, address: u64) -> Option<&LineRow> {
+        self.rows
+            .windows(2)
+            .find(|pair| {
+                !pair[0].end_sequence && pair[0].address <= address && address < pair[1].address
+            })
+            .map(|pair| &pair[0])
+    }
+}
+
+/// Parses a DWARF 5 directory or file name table, laid out by its entry format
+fn parse_entries<'a>(
+    input: parse::Input<'a>,
+    format: Format,
+    sections: &DwarfSections,
+) -> parse::ParseResult<'a, Vec<FileEntry>> {
+    let (mut input, format_count) = complete::u8(input)?;
+    let mut entry_format = Vec::with_capacity(format_count as usize);
+    for _ in 0..format_count {
+        let (rest, (content_type, form)) = tuple((uleb128, uleb128))(input)?;
+        input = rest;
+        entry_format.push((content_type, DwForm(form as u16)));
+    }
+    let (mut input, count) = uleb128(input)?;
+    // Every entry takes up at least a byte, which bounds the count by what's left of the table
+    if count > input.len() as u64 || entry_format.is_empty() && count != 0 {
+        return Err(failure(input, "Invalid Line Table Entry Count"));
+    }
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let start = input.len();
+        let mut entry = FileEntry::default();
+        for &(content_type, form) in &entry_format {
+            let (rest, value) = parse_value(input, form, None, format, 0, sections)?;
+            input = rest;
+            match (content_type, value) {
+                (DW_LNCT_path, AttributeValue::String(path)) => entry.path = path,
+                (DW_LNCT_MD5, AttributeValue::Data16(md5)) => entry.md5 = Some(md5),
+                (content_type, value) => {
+                    let value = value.as_u64().unwrap_or(0);
+                    match content_type {
+                        DW_LNCT_directory_index => entry.directory_index = value,
+                        DW_LNCT_timestamp => entry.timestamp = value,
+                        DW_LNCT_size => entry.size = value,
+                        _ => {}
+                    }
+                }
+            }
+        }
+        if input.len() == start {
+            return Err(failure(input, "Empty Line Table Entry"));
+        }
+        entries.push(entry);
+    }
+    Ok((input, entries))
+}
+
+/// Parses a file entry from before DWARF 5, where an empty name ends the table
+fn parse_file_entry(input: parse::Input) -> parse::ParseResult<Option<FileEntry>> {
+    let (input, path) = string(input)?;
+    if path.is_empty() {
+        return Ok((input, None));
+    }
+    let (input, (directory_index, timestamp, size)) =
+        context("Parse File Entry", tuple((uleb128, uleb128, uleb128)))(input)?;
+    Ok((
+        input,
+        Some(FileEntry {
+            path,
+            directory_index,
+            timestamp,
+            size,
+            md5: None,
+        }),
+    ))
+}
+
+impl fmt::Display for LineProgram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Line Table at {:#x}:", self.offset)?;
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(
+            f,
+            "Minimum Instruction Length: {}",
+            self.minimum_instruction_length
+        )?;
+        writeln!(
+            f,
+            "Maximum Operations Per Instruction: {}",
+            self.maximum_operations_per_instruction
+        )?;
+        writeln!(f, "Default Is Stmt: {}", self.default_is_stmt)?;
+        writeln!(f, "Line Base: {}", self.line_base)?;
+        writeln!(f, "Line Range: {}", self.line_range)?;
+        writeln!(f, "Opcode Base: {}", self.opcode_base)?;
+        let first = if self.version >= 5 { 0 } else { 1 };
+        writeln!(f, "Include Directories:")?;
+        for (index, directory) in self.include_directories.iter().enumerate() {
+            writeln!(f, "  [{}] {}", index + first, directory)?;
+        }
+        writeln!(f, "Files:")?;
+        for (index, file) in self.files.iter().enumerate() {
+            write!(
+                f,
+                "  [{}] {} (directory {})",
+                index + first,
+                file.path,
+                file.directory_index
+            )?;
+            if let Some(md5) = file.md5 {
+                write!(f, " md5 ")?;
+                for byte in md5 {
+                    write!(f, "{:02x}", byte)?;
+                }
+            }
+            writeln!(f)?;
+        }
+        writeln!(
+            f,
+            "Address              Line Column File ISA Discriminator Flags"
+        )?;
+        for row in &self.rows {
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A DWARF 4 line table for 64 bit little endian code, with one file and `program`
+    fn line_table(program: &[u8]) -> Vec<u8> {
+        let mut header = vec![
+            1,    // minimum_instruction_length
+            1,    // maximum_operations_per_instruction
+            1,    // default_is_stmt
+            0xfb, // line_base -5
+            14,   // line_range
+            13,   // opcode_base
+            0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1, // standard_opcode_lengths
+            0, // no include directories
+        ];
+        header.extend_from_slice(b"a.c\0\0\0\0\0");
+        let mut unit = 4u16.to_le_bytes().to_vec();
+        unit.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        unit.extend_from_slice(&header);
+        unit.extend_from_slice(program);
+        let mut table = (unit.len() as u32).to_le_bytes().to_vec();
+        table.extend_from_slice(&unit);
+        table
+    }
+
+    fn parse(debug_line: &[u8]) -> BinDumpResult<LineProgram> {
+        let sections = DwarfSections {
+            debug_line,
+            ..Default::default()
+        };
+        LineProgram::parse(&sections, Endianness::Little, 8, 0, None)
+    }
+
+    #[test]
+    fn runs_the_line_program() {
+        let table = line_table(&[
+            // DW_LNE_set_address 0x1000
+            0,
+            9,
+            2,
+            0,
+            0x10,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            // DW_LNS_advance_line 9, then DW_LNS_copy
+            3,
+            9,
+            1,
+            // A special opcode advancing the address by 2 and the line by 1
+            13 + 2 * 14 + 6,
+            // DW_LNS_advance_pc 4, then DW_LNE_end_sequence
+            2,
+            4,
+            0,
+            1,
+            1,
+        ]);
+        let program = parse(&table).unwrap();
+        assert_eq!(program.files.len(), 1);
+        assert_eq!(program.files[0].path, "a.c");
+        let rows: Vec<_> = program
+            .rows
+            .iter()
+            .map(|row| (row.address, row.line, row.end_sequence))
+            .collect();
+        assert_eq!(
+            rows,
+            [(0x1000, 10, false), (0x1002, 11, false), (0x1006, 11, true)]
+        );
+        assert_eq!(program.find_row(0x1003).map(|row| row.line), Some(11));
+        assert!(program.find_row(0x1006).is_none());
+    }
+
+    #[test]
+    fn rejects_entries_without_a_format() {
+        // No entry formats, so entries would take up no bytes however many there are
+        let table = [0, 0xff, 0xff, 0xff, 0xff, 0x0f];
+        assert!(parse_entries(&table, format(), &DwarfSections::default()).is_err());
+        let table = [0, 1, 0xaa];
+        assert!(parse_entries(&table, format(), &DwarfSections::default()).is_err());
+    }
+
+    #[test]
+    fn rejects_counts_past_the_end() {
+        // One DW_LNCT_path entry as DW_FORM_string, and more entries than bytes
+        let table = [1, 1, 8, 0x80, 0x01, b'a', 0];
+        assert!(parse_entries(&table, format(), &DwarfSections::default()).is_err());
+    }
+
+    fn format() -> Format {
+        Format {
+            endianness: Endianness::Little,
+            version: 5,
+            offset_size: 4,
+            address_size: 8,
+        }
+    }
+}
@@ -0,0 +1,742 @@
+// Forms and range list entry kinds are matched by their DW_* names
+#![allow(non_upper_case_globals)]
+
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::combinator::map;
+use nom::error::context;
+use nom::number::{complete, Endianness};
+use nom::sequence::tuple;
+
+use super::abbreviations::Abbreviations;
+use super::constants::*;
+use super::line::LineProgram;
+use super::{initial_length, string, table_entry, DwarfSections, Format, Range};
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse::{self, sleb128, string_at, uleb128};
+
+/// The value of an attribute, with string, address and range list indices resolved where the
+/// sections they refer to are present
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    Address(u64),
+    /// An index into the unit's part of `.debug_addr`
+    AddressIndex(u64),
+    Block(Vec<u8>),
+    /// An unsigned constant, or a constant of unknown signedness
+    Data(u64),
+    Data16([u8; 16]),
+    Signed(i64),
+    Flag(bool),
+    String(String),
+    /// An index into the unit's part of `.debug_str_offsets`
+    StringIndex(u64),
+    /// The offset of an entry in `.debug_info`
+    Reference(u64),
+    /// The offset of an entry or string in the supplementary object file
+    SupplementaryReference(u64),
+    /// The signature of the type unit that holds a type
+    TypeSignature(u64),
+    /// An offset into another section, like a line table or location list
+    SectionOffset(u64),
+    /// A DWARF expression, such as a location
+    Expression(Vec<u8>),
+    LocationListIndex(u64),
+    RangeListIndex(u64),
+    /// The resolved ranges of a `DW_AT_ranges` attribute
+    Ranges(Vec<Range>),
+}
+
+impl AttributeValue {
+    /// The value as an unsigned number, for the forms that hold one
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            AttributeValue::Address(value)
+            | AttributeValue::Data(value)
+            | AttributeValue::Reference(value)
+            | AttributeValue::SectionOffset(value) => Some(value),
+            AttributeValue::Signed(value) => u64::try_from(value).ok(),
+            AttributeValue::Flag(value) => Some(value as u64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttributeValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeValue::Address(address) => write!(f, "{:#018x}", address),
+            AttributeValue::AddressIndex(index) => write!(f, "indexed address {}", index),
+            AttributeValue::Block(bytes) | AttributeValue::Expression(bytes) => {
+                write!(f, "{:02x?}", bytes)
+            }
+            AttributeValue::Data(value) => write!(f, "{:#x}", value),
+            AttributeValue::Data16(bytes) => {
+                for byte in bytes {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            AttributeValue::Signed(value) => write!(f, "{}", value),
+            AttributeValue::Flag(value) => write!(f, "{}", value),
+            AttributeValue::String(value) => write!(f, "\"{}\"", value),
+            AttributeValue::StringIndex(index) => write!(f, "indexed string {}", index),
+            AttributeValue::Reference(offset) => write!(f, "<{:#010x}>", offset),
+            AttributeValue::SupplementaryReference(offset) => {
+                write!(f, "supplementary <{:#010x}>", offset)
+            }
+            AttributeValue::TypeSignature(signature) => write!(f, "signature {:#018x}", signature),
+            AttributeValue::SectionOffset(offset) => write!(f, "offset {:#x}", offset),
+            AttributeValue::LocationListIndex(index) => write!(f, "location list {}", index),
+            AttributeValue::RangeListIndex(index) => write!(f, "range list {}", index),
+            AttributeValue::Ranges(ranges) => {
+                for (i, range) in ranges.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", range)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub name: DwAt,
+    pub form: DwForm,
+    pub value: AttributeValue,
+}
+
+/// A debugging information entry and the entries nested in it
+#[derive(Debug, Clone)]
+pub struct Die {
+    /// Offset in `.debug_info`
+    pub offset: u64,
+    pub tag: DwTag,
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Die>,
+}
+
+impl Die {
+    pub fn attribute(&self, name: DwAt) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| &attribute.value)
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.attribute(DW_AT_name)?.as_str()
+    }
+
+    /// The addresses covered by the entry, from `DW_AT_low_pc` and `DW_AT_high_pc` or from
+    /// `DW_AT_ranges`
+    pub fn ranges(&self) -> Vec<Range> {
+        if let Some(AttributeValue::Ranges(ranges)) = self.attribute(DW_AT_ranges) {
+            return ranges.clone();
+        }
+        let Some(AttributeValue::Address(begin)) = self.attribute(DW_AT_low_pc) else {
+            return Vec::new();
+        };
+        // Since DWARF 4 the high PC may be an offset from the low PC
+        let end = match self.attribute(DW_AT_high_pc) {
+            Some(AttributeValue::Address(end)) => *end,
+            Some(AttributeValue::Data(length)) => begin.wrapping_add(*length),
+            Some(AttributeValue::Signed(length)) => begin.wrapping_add_signed(*length),
+            _ => return Vec::new(),
+        };
+        vec![Range { begin: *begin, end }]
+    }
+
+    /// Finds the entry at an offset in this entry's subtree
+    pub fn die_at(&self, offset: u64) -> Option<&Die> {
+        if self.offset == offset {
+            return Some(self);
+        }
+        // Children are stored in the order of their offsets
+        self.children
+            .iter()
+            .rev()
+            .find(|child| child.offset <= offset)?
+            .die_at(offset)
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = depth * 2;
+        writeln!(f, "{:#010x}: {:indent$}{}", self.offset, "", self.tag)?;
+        for attribute in &self.attributes {
+            writeln!(
+                f,
+                "            {:indent$}  {} [{}] ({})",
+                "", attribute.name, attribute.form, attribute.value
+            )?;
+        }
+        for child in &self.children {
+            child.write(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Die {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// A unit from `.debug_info`, with its tree of entries and its line table
+#[derive(Debug, Clone)]
+pub struct Unit {
+    /// Offset of the unit header in `.debug_info`
+    pub offset: u64,
+    pub unit_length: u64,
+    pub version: u16,
+    pub unit_type: DwUt,
+    /// 4 for 32 bit DWARF and 8 for 64 bit DWARF
+    pub offset_size: u8,
+    pub address_size: u8,
+    pub abbreviation_offset: u64,
+    /// The id connecting a skeleton unit to its split unit
+    pub dwo_id: Option<u64>,
+    pub type_signature: Option<u64>,
+    /// Offset of the type in a type unit, from the start of the unit
+    pub type_offset: Option<u64>,
+    pub root: Option<Die>,
+    pub line_program: Option<LineProgram>,
+}
+
+impl Unit {
+    /// Parses the header of the unit at `offset`, returning it with the offset of its entries
+    pub(super) fn parse_header(
+        debug_info: &[u8],
+        offset: u64,
+        endianness: Endianness,
+    ) -> BinDumpResult<(Self, u64)> {
+        let data = &debug_info[offset as usize..];
+        let (input, (unit_length, offset_size)) = initial_length(endianness)(data)?;
+        let (input, version) = context("Parse Unit Version", complete::u16(endianness))(input)?;
+        if !(2..=5).contains(&version) {
+            return Err(BinDumpError::Unsupported {
+                format: format!("DWARF version {} in the unit at {:#x}", version, offset),
+            });
+        }
+        let mut format = Format {
+            endianness,
+            version,
+            offset_size,
+            address_size: 0,
+        };
+        let mut unit = Self {
+            offset,
+            unit_length,
+            version,
+            unit_type: DW_UT_compile,
+            offset_size,
+            address_size: 0,
+            abbreviation_offset: 0,
+            dwo_id: None,
+            type_signature: None,
+            type_offset: None,
+            root: None,
+            line_program: None,
+        };
+        let input = if version >= 5 {
+            let (input, (unit_type, address_size, abbreviation_offset)) = context(
+                "Parse Unit Header",
+                tuple((complete::u8, complete::u8, format.offset())),
+            )(input)?;
+            format.address_size = address_size;
+            unit.unit_type = DwUt(unit_type);
+            unit.address_size = address_size;
+            unit.abbreviation_offset = abbreviation_offset;
+            match unit.unit_type {
+                DW_UT_skeleton | DW_UT_split_compile => {
+                    let (input, dwo_id) = format.u64()(input)?;
+                    unit.dwo_id = Some(dwo_id);
+                    input
+                }
+                DW_UT_type | DW_UT_split_type => {
+                    let (input, (signature, type_offset)) =
+                        tuple((format.u64(), format.offset()))(input)?;
+                    unit.type_signature = Some(signature);
+                    unit.type_offset = Some(type_offset);
+                    input
+                }
+                _ => input,
+            }
+        } else {
+            let (input, (abbreviation_offset, address_size)) =
+                context("Parse Unit Header", tuple((format.offset(), complete::u8)))(input)?;
+            unit.address_size = address_size;
+            unit.abbreviation_offset = abbreviation_offset;
+            input
+        };
+        let entries = offset + (data.len() - input.len()) as u64;
+        Ok((unit, entries))
+    }
+
+    fn format(&self, endianness: Endianness) -> Format {
+        Format {
+            endianness,
+            version: self.version,
+            offset_size: self.offset_size,
+            address_size: self.address_size,
+        }
+    }
+
+    /// Parses the tree of entries starting at `entries`, then resolves the indexed values in it
+    /// and reads the line table
+    pub(super) fn parse_entries(
+        &mut self,
+        sections: &DwarfSections,
+        endianness: Endianness,
+        abbreviations: &Abbreviations,
+        entries: u64,
+    ) -> BinDumpResult<()> {
+        let format = self.format(endianness);
+        let end = (self.next_offset() as usize).min(sections.debug_info.len());
+        let data = sections
+            .debug_info
+            .get(entries as usize..end)
+            .unwrap_or_default();
+        let mut input = data;
+        // The entries whose children are still being read
+        let mut parents: Vec<Die> = Vec::new();
+        let mut root = None;
+        while !input.is_empty() && root.is_none() {
+            let offset = entries + (data.len() - input.len()) as u64;
+            let (rest, code) = context("Parse Abbreviation Code", uleb128)(input)?;
+            input = rest;
+            if code == 0 {
+                // The end of a list of children
+                if let Some(die) = parents.pop() {
+                    attach(&mut parents, &mut root, die);
+                }
+                continue;
+            }
+            let abbreviation = abbreviations
+                .get(code)
+                .ok_or_else(|| BinDumpError::ParseError {
+                    error: format!("Unknown abbreviation code {} at {:#x}", code, offset),
+                })?;
+            let mut attributes = Vec::with_capacity(abbreviation.attributes.len());
+            for specification in &abbreviation.attributes {
+                let (rest, value) = parse_value(
+                    input,
+                    specification.form,
+                    specification.implicit_const,
+                    format,
+                    self.offset,
+                    sections,
+                )?;
+                input = rest;
+                attributes.push(Attribute {
+                    name: specification.name,
+                    form: specification.form,
+                    value,
+                });
+            }
+            let die = Die {
+                offset,
+                tag: abbreviation.tag,
+                attributes,
+                children: Vec::new(),
+            };
+            if abbreviation.has_children {
+                parents.push(die);
+            } else {
+                attach(&mut parents, &mut root, die);
+            }
+        }
+        // A truncated unit still yields the entries read so far
+        while let Some(die) = parents.pop() {
+            attach(&mut parents, &mut root, die);
+        }
+        let Some(mut root) = root else {
+            return Ok(());
+        };
+
+        let base = |names: &[DwAt]| {
+            names
+                .iter()
+                .find_map(|&name| root.attribute(name))
+                .and_then(AttributeValue::as_u64)
+        };
+        // Split units leave out the bases, which then point just past the header of the only
+        // table in the section
+        let (table_base, rnglists_base) = match self.version {
+            5.. => (2 * self.offset_size as u64, self.offset_size as u64 + 8),
+            _ => (0, 0),
+        };
+        let mut resolver = Resolver {
+            sections,
+            format,
+            str_offsets_base: base(&[DW_AT_str_offsets_base]).unwrap_or(table_base),
+            addr_base: base(&[DW_AT_addr_base, DW_AT_GNU_addr_base]).unwrap_or(table_base),
+            rnglists_base: base(&[DW_AT_rnglists_base]).unwrap_or(rnglists_base),
+            base_address: 0,
+        };
+        resolver.base_address = match root.attribute(DW_AT_low_pc) {
+            Some(AttributeValue::Address(address)) => *address,
+            Some(AttributeValue::AddressIndex(index)) => resolver.address(*index).unwrap_or(0),
+            _ => 0,
+        };
+        resolver.resolve(&mut root);
+
+        if let Some(stmt_list) = root
+            .attribute(DW_AT_stmt_list)
+            .and_then(AttributeValue::as_u64)
+        {
+            let compilation_directory = root
+                .attribute(DW_AT_comp_dir)
+                .and_then(AttributeValue::as_str)
+                .map(str::to_string);
+            self.line_program = Some(LineProgram::parse(
+                sections,
+                endianness,
+                self.address_size,
+                stmt_list,
+                compilation_directory,
+            )?);
+        }
+        self.root = Some(root);
+        Ok(())
+    }
+
+    /// Offset of the next unit in `.debug_info`
+    pub fn next_offset(&self) -> u64 {
+        let length_size = if self.offset_size == 8 { 12 } else { 4 };
+        self.offset
+            .saturating_add(length_size)
+            .saturating_add(self.unit_length)
+    }
+
+    /// The entry at an offset in `.debug_info`, if it is in this unit
+    pub fn die_at(&self, offset: u64) -> Option<&Die> {
+        self.root.as_ref()?.die_at(offset)
+    }
+
+    /// The name of the unit's source file
+    pub fn name(&self) -> Option<&str> {
+        self.root.as_ref()?.name()
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Unit at {:#x}:", self.offset)?;
+        writeln!(f, "Length: {:#x}", self.unit_length)?;
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "Unit Type: {}", self.unit_type)?;
+        writeln!(f, "Format: DWARF{}", self.offset_size as u32 * 8)?;
+        writeln!(f, "Address Size: {}", self.address_size)?;
+        writeln!(f, "Abbreviation Offset: {:#x}", self.abbreviation_offset)?;
+        if let Some(dwo_id) = self.dwo_id {
+            writeln!(f, "DWO ID: {:#018x}", dwo_id)?;
+        }
+        if let Some(signature) = self.type_signature {
+            writeln!(f, "Type Signature: {:#018x}", signature)?;
+        }
+        if let Some(type_offset) = self.type_offset {
+            writeln!(f, "Type Offset: {:#x}", type_offset)?;
+        }
+        if let Some(root) = &self.root {
+            write!(f, "{}", root)?;
+        }
+        if let Some(line_program) = &self.line_program {
+            write!(f, "{}", line_program)?;
+        }
+        Ok(())
+    }
+}
+
+/// Adds a finished entry to its parent, or makes it the root when it has none
+fn attach(parents: &mut [Die], root: &mut Option<Die>, die: Die) {
+    match parents.last_mut() {
+        Some(parent) => parent.children.push(die),
+        None => *root = Some(die),
+    }
+}
+
+/// Replaces the indices in a unit's attributes with the values they refer to
+struct Resolver<'a, 'b> {
+    sections: &'b DwarfSections<'a>,
+    format: Format,
+    str_offsets_base: u64,
+    addr_base: u64,
+    rnglists_base: u64,
+    /// The unit's low PC, which range list entries are relative to
+    base_address: u64,
+}
+
+impl Resolver<'_, '_> {
+    fn resolve(&self, die: &mut Die) {
+        for attribute in &mut die.attributes {
+            let resolved = match (&attribute.value, attribute.name) {
+                (AttributeValue::StringIndex(index), _) => {
+                    self.string(*index).map(AttributeValue::String)
+                }
+                (AttributeValue::AddressIndex(index), _) => {
+                    self.address(*index).map(AttributeValue::Address)
+                }
+                (AttributeValue::SectionOffset(offset), DW_AT_ranges) => {
+                    self.ranges(*offset).map(AttributeValue::Ranges)
+                }
+                (AttributeValue::RangeListIndex(index), DW_AT_ranges) => {
+                    let offset = table_entry(
+                        self.format,
+                        self.sections.debug_rnglists,
+                        self.rnglists_base,
+                        *index,
+                        self.format.offset_size,
+                    );
+                    offset
+                        .and_then(|offset| self.ranges(self.rnglists_base + offset))
+                        .map(AttributeValue::Ranges)
+                }
+                _ => None,
+            };
+            if let Some(value) = resolved {
+                attribute.value = value;
+            }
+        }
+        for child in &mut die.children {
+            self.resolve(child);
+        }
+    }
+
+    fn string(&self, index: u64) -> Option<String> {
+        let offset = table_entry(
+            self.format,
+            self.sections.debug_str_offsets,
+            self.str_offsets_base,
+            index,
+            self.format.offset_size,
+        )?;
+        Some(string_at(self.sections.debug_str, offset as usize))
+    }
+
+    fn address(&self, index: u64) -> Option<u64> {
+        table_entry(
+            self.format,
+            self.sections.debug_addr,
+            self.addr_base,
+            index,
+            self.format.address_size,
+        )
+    }
+
+    /// Reads a range list from `.debug_rnglists` in DWARF 5 or `.debug_ranges` before it
+    fn ranges(&self, offset: u64) -> Option<Vec<Range>> {
+        let result = if self.format.version >= 5 {
+            self.range_list(self.sections.debug_rnglists.get(offset as usize..)?)
+        } else {
+            self.legacy_range_list(self.sections.debug_ranges.get(offset as usize..)?)
+        };
+        result.ok().map(|(_, ranges)| ranges)
+    }
+
+    fn legacy_range_list<'a>(&self, input: parse::Input<'a>) -> parse::ParseResult<'a, Vec<Range>> {
+        let max_address = match self.format.address_size {
+            4 => u32::MAX as u64,
+            _ => u64::MAX,
+        };
+        let mut base = self.base_address;
+        let mut ranges = Vec::new();
+        let mut input = input;
+        loop {
+            let (rest, (begin, end)) =
+                tuple((self.format.address(), self.format.address()))(input)?;
+            input = rest;
+            match (begin, end) {
+                (0, 0) => break,
+                // A base address selection entry
+                (begin, end) if begin == max_address => base = end,
+                (begin, end) => ranges.push(Range {
+                    begin: base.wrapping_add(begin),
+                    end: base.wrapping_add(end),
+                }),
+            }
+        }
+        Ok((input, ranges))
+    }
+
+    fn range_list<'a>(&self, input: parse::Input<'a>) -> parse::ParseResult<'a, Vec<Range>> {
+        let mut base = self.base_address;
+        let mut ranges = Vec::new();
+        let mut input = input;
+        loop {
+            let (rest, kind) = complete::u8(input)?;
+            // Lists of split units refer to addresses in the skeleton's file
+            let indexed = |index: u64| {
+                self.address(index)
+                    .ok_or_else(|| parse::failure(input, "Unresolved address index"))
+            };
+            let (rest, range) = match kind {
+                DW_RLE_end_of_list => {
+                    input = rest;
+                    break;
+                }
+                DW_RLE_base_addressx => {
+                    let (rest, index) = uleb128(rest)?;
+                    base = indexed(index)?;
+                    (rest, None)
+                }
+                DW_RLE_startx_endx => {
+                    let (rest, (begin, end)) = tuple((uleb128, uleb128))(rest)?;
+                    (rest, Some((indexed(begin)?, indexed(end)?)))
+                }
+                DW_RLE_startx_length => {
+                    let (rest, (begin, length)) = tuple((uleb128, uleb128))(rest)?;
+                    let begin = indexed(begin)?;
+                    (rest, Some((begin, begin.wrapping_add(length))))
+                }
+                DW_RLE_offset_pair => {
+                    let (rest, (begin, end)) = tuple((uleb128, uleb128))(rest)?;
+                    (
+                        rest,
+                        Some((base.wrapping_add(begin), base.wrapping_add(end))),
+                    )
+                }
+                DW_RLE_base_address => {
+                    let (rest, address) = self.format.address()(rest)?;
+                    base = address;
+                    (rest, None)
+                }
+                DW_RLE_start_end => {
+                    let (rest, range) =
+                        tuple((self.format.address(), self.format.address()))(rest)?;
+                    (rest, Some(range))
+                }
+                DW_RLE_start_length => {
+                    let (rest, (begin, length)) = tuple((self.format.address(), uleb128))(rest)?;
+                    (rest, Some((begin, begin.wrapping_add(length))))
+                }
+                _ => return Err(parse::failure(input, "Unknown range list entry")),
+            };
+            input = rest;
+            if let Some((begin, end)) = range {
+                ranges.push(Range { begin, end });
+            }
+        }
+        Ok((input, ranges))
+    }
+}
+
+/// Parses an attribute value of the given form
+pub(crate) fn parse_value<'a>(
+    input: parse::Input<'a>,
+    form: DwForm,
+    implicit_const: Option<i64>,
+    format: Format,
+    unit_offset: u64,
+    sections: &DwarfSections,
+) -> parse::ParseResult<'a, AttributeValue> {
+    let block = |length: u64| map(take(length), |bytes: &[u8]| bytes.to_vec());
+    match form {
+        DW_FORM_addr => map(format.address(), AttributeValue::Address)(input),
+        DW_FORM_block1 => {
+            let (input, length) = complete::u8(input)?;
+            map(block(length as u64), AttributeValue::Block)(input)
+        }
+        DW_FORM_block2 => {
+            let (input, length) = format.u16()(input)?;
+            map(block(length as u64), AttributeValue::Block)(input)
+        }
+        DW_FORM_block4 => {
+            let (input, length) = format.u32()(input)?;
+            map(block(length as u64), AttributeValue::Block)(input)
+        }
+        DW_FORM_block => {
+            let (input, length) = uleb128(input)?;
+            map(block(length), AttributeValue::Block)(input)
+        }
+        DW_FORM_exprloc => {
+            let (input, length) = uleb128(input)?;
+            map(block(length), AttributeValue::Expression)(input)
+        }
+        DW_FORM_data1 => map(format.sized(1), AttributeValue::Data)(input),
+        DW_FORM_data2 => map(format.sized(2), AttributeValue::Data)(input),
+        DW_FORM_data4 => map(format.sized(4), AttributeValue::Data)(input),
+        DW_FORM_data8 => map(format.sized(8), AttributeValue::Data)(input),
+        DW_FORM_data16 => {
+            let (input, bytes) = take(16usize)(input)?;
+            let mut data = [0; 16];
+            data.copy_from_slice(bytes);
+            Ok((input, AttributeValue::Data16(data)))
+        }
+        DW_FORM_sdata => map(sleb128, AttributeValue::Signed)(input),
+        DW_FORM_udata => map(uleb128, AttributeValue::Data)(input),
+        DW_FORM_implicit_const => Ok((input, AttributeValue::Signed(implicit_const.unwrap_or(0)))),
+        DW_FORM_string => map(string, AttributeValue::String)(input),
+        DW_FORM_strp => map(format.offset(), |offset| {
+            AttributeValue::String(string_at(sections.debug_str, offset as usize))
+        })(input),
+        DW_FORM_line_strp => map(format.offset(), |offset| {
+            AttributeValue::String(string_at(sections.debug_line_str, offset as usize))
+        })(input),
+        DW_FORM_strp_sup | DW_FORM_GNU_strp_alt => {
+            map(format.offset(), AttributeValue::SupplementaryReference)(input)
+        }
+        DW_FORM_strx | DW_FORM_GNU_str_index => map(uleb128, AttributeValue::StringIndex)(input),
+        DW_FORM_strx1 => map(format.sized(1), AttributeValue::StringIndex)(input),
+        DW_FORM_strx2 => map(format.sized(2), AttributeValue::StringIndex)(input),
+        DW_FORM_strx3 => map(format.sized(3), AttributeValue::StringIndex)(input),
+        DW_FORM_strx4 => map(format.sized(4), AttributeValue::StringIndex)(input),
+        DW_FORM_addrx | DW_FORM_GNU_addr_index => map(uleb128, AttributeValue::AddressIndex)(input),
+        DW_FORM_addrx1 => map(format.sized(1), AttributeValue::AddressIndex)(input),
+        DW_FORM_addrx2 => map(format.sized(2), AttributeValue::AddressIndex)(input),
+        DW_FORM_addrx3 => map(format.sized(3), AttributeValue::AddressIndex)(input),
+        DW_FORM_addrx4 => map(format.sized(4), AttributeValue::AddressIndex)(input),
+        DW_FORM_flag => map(complete::u8, |flag| AttributeValue::Flag(flag != 0))(input),
+        DW_FORM_flag_present => Ok((input, AttributeValue::Flag(true))),
+        // References within the unit are relative to its start
+        DW_FORM_ref1 | DW_FORM_ref2 | DW_FORM_ref4 | DW_FORM_ref8 | DW_FORM_ref_udata => {
+            let (input, offset) = match form {
+                DW_FORM_ref1 => format.sized(1)(input)?,
+                DW_FORM_ref2 => format.sized(2)(input)?,
+                DW_FORM_ref4 => format.sized(4)(input)?,
+                DW_FORM_ref8 => format.sized(8)(input)?,
+                _ => uleb128(input)?,
+            };
+            Ok((
+                input,
+                AttributeValue::Reference(unit_offset.wrapping_add(offset)),
+            ))
+        }
+        // DWARF 2 stored these as addresses
+        DW_FORM_ref_addr if format.version == 2 => {
+            map(format.address(), AttributeValue::Reference)(input)
+        }
+        DW_FORM_ref_addr => map(format.offset(), AttributeValue::Reference)(input),
+        DW_FORM_ref_sig8 => map(format.u64(), AttributeValue::TypeSignature)(input),
+        DW_FORM_ref_sup4 => map(format.sized(4), AttributeValue::SupplementaryReference)(input),
+        DW_FORM_ref_sup8 => map(format.sized(8), AttributeValue::SupplementaryReference)(input),
+        DW_FORM_GNU_ref_alt => map(format.offset(), AttributeValue::SupplementaryReference)(input),
+        DW_FORM_sec_offset => map(format.offset(), AttributeValue::SectionOffset)(input),
+        DW_FORM_loclistx => map(uleb128, AttributeValue::LocationListIndex)(input),
+        DW_FORM_rnglistx => map(uleb128, AttributeValue::RangeListIndex)(input),
+        DW_FORM_indirect => {
+            let (input, form) = uleb128(input)?;
+            parse_value(
+                input,
+                DwForm(form as u16),
+                implicit_const,
+                format,
+                unit_offset,
+                sections,
+            )
+        }
+        _ => Err(parse::failure(input, "Unknown attribute form")),
+    }
+}
@@ -1,16 +1,739 @@
+pub mod call_frames;
+pub mod core_dump;
+pub mod debug_info;
+pub mod dynamic;
+pub mod hash;
+pub mod notes;
+pub mod program_headers;
+pub mod relocations;
+pub mod sections;
+pub mod symbols;
+
 use std::fmt;
 
+use core_dump::Core;
+use dynamic::DynamicEntry;
+use hash::{GnuHashTable, SysvHashTable};
+use nom::bytes::complete::{tag, take};
+use nom::combinator::map;
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::u8;
+use nom::number::{complete, Endianness};
+use nom::sequence::tuple;
+use notes::Note;
+use program_headers::{ProgramHeader, ProgramType};
+use relocations::RelocationTable;
+use sections::{SectionHeader, SectionType};
+use symbols::{Symbol, VersionDefinition, VersionRequirement};
+
+use crate::binary::dwarf::cfi::{CallFrameInformation, EhFrameHeader, FrameSection};
+use crate::binary::dwarf::Dwarf;
+use crate::error::BinDumpResult;
+use crate::parse::{self, string_at, truncated};
+
+/// Section index used in the header when the real index doesn't fit in 16 bits
+const SHN_XINDEX: u16 = 0xffff;
+/// Number of program headers used in the header when the real number doesn't fit in 16 bits
+const PN_XNUM: u16 = 0xffff;
+
+#[derive(Debug)]
+pub struct Elf {
+    header: ElfHeader,
+    program_headers: Vec<ProgramHeader>,
+    sections: Vec<SectionHeader>,
+    symbols: Vec<Symbol>,
+    dynamic_symbols: Vec<Symbol>,
+    version_definitions: Vec<VersionDefinition>,
+    version_requirements: Vec<VersionRequirement>,
+    dynamic: Vec<DynamicEntry>,
+    relocations: Vec<RelocationTable>,
+    notes: Vec<Note>,
+    core: Option<Core>,
+    gnu_hash: Option<GnuHashTable>,
+    hash: Option<SysvHashTable>,
+    dwarf: Option<BinDumpResult<Dwarf>>,
+    eh_frame: Option<BinDumpResult<CallFrameInformation>>,
+    eh_frame_header: Option<BinDumpResult<EhFrameHeader>>,
+    debug_frame: Option<BinDumpResult<CallFrameInformation>>,
+    data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Elf32,
+    Elf64,
+}
+
+/// The class and byte order of a file, which decide how every structure in it is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoding {
+    pub class: Class,
+    pub endianness: Endianness,
+}
+
+impl Encoding {
+    pub(crate) fn u16(self) -> impl FnMut(parse::Input) -> parse::ParseResult<u16> {
+        move |input| complete::u16(self.endianness)(input)
+    }
+
+    pub(crate) fn u32(self) -> impl FnMut(parse::Input) -> parse::ParseResult<u32> {
+        move |input| complete::u32(self.endianness)(input)
+    }
+
+    pub(crate) fn u64(self) -> impl FnMut(parse::Input) -> parse::ParseResult<u64> {
+        move |input| complete::u64(self.endianness)(input)
+    }
+
+    /// Parses an address, offset or size, which is 4 bytes in 32 bit files and 8 in 64 bit ones
+    pub(crate) fn word(self) -> impl FnMut(parse::Input) -> parse::ParseResult<u64> {
+        move |input| match self.class {
+            Class::Elf32 => map(complete::u32(self.endianness), u64::from)(input),
+            Class::Elf64 => complete::u64(self.endianness)(input),
+        }
+    }
+
+    /// Parses a signed word, such as a relocation addend
+    pub(crate) fn sword(self) -> impl FnMut(parse::Input) -> parse::ParseResult<i64> {
+        move |input| match self.class {
+            Class::Elf32 => map(complete::i32(self.endianness), i64::from)(input),
+            Class::Elf64 => complete::i64(self.endianness)(input),
+        }
+    }
+
+    /// The size in bytes of an address
+    pub fn word_size(self) -> u64 {
+        match self.class {
+            Class::Elf32 => 4,
+            Class::Elf64 => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    None,
+    Relocatable,
+    Executable,
+    SharedObject,
+    Core,
+    Other(u16),
+}
+
+impl From<u16> for FileType {
+    fn from(typ: u16) -> Self {
+        match typ {
+            0 => FileType::None,
+            1 => FileType::Relocatable,
+            2 => FileType::Executable,
+            3 => FileType::SharedObject,
+            4 => FileType::Core,
+            typ => FileType::Other(typ),
+        }
+    }
+}
+
+// From elf.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Machine {
+    None,
+    Sparc,
+    I386,
+    M68k,
+    Mips,
+    Parisc,
+    Sparc32Plus,
+    PowerPc,
+    PowerPc64,
+    S390,
+    Arm,
+    SuperH,
+    SparcV9,
+    Ia64,
+    X86_64,
+    Avr,
+    AArch64,
+    RiscV,
+    Bpf,
+    LoongArch,
+    Other(u16),
+}
+
+impl From<u16> for Machine {
+    fn from(machine: u16) -> Self {
+        match machine {
+            0 => Machine::None,
+            2 => Machine::Sparc,
+            3 => Machine::I386,
+            4 => Machine::M68k,
+            8 => Machine::Mips,
+            15 => Machine::Parisc,
+            18 => Machine::Sparc32Plus,
+            20 => Machine::PowerPc,
+            21 => Machine::PowerPc64,
+            22 => Machine::S390,
+            40 => Machine::Arm,
+            42 => Machine::SuperH,
+            43 => Machine::SparcV9,
+            50 => Machine::Ia64,
+            62 => Machine::X86_64,
+            83 => Machine::Avr,
+            183 => Machine::AArch64,
+            243 => Machine::RiscV,
+            247 => Machine::Bpf,
+            258 => Machine::LoongArch,
+            machine => Machine::Other(machine),
+        }
+    }
+}
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Machine::None => write!(f, "None"),
+            Machine::Sparc => write!(f, "Sparc"),
+            Machine::I386 => write!(f, "I386"),
+            Machine::M68k => write!(f, "M68k"),
+            Machine::Mips => write!(f, "Mips"),
+            Machine::Parisc => write!(f, "Parisc"),
+            Machine::Sparc32Plus => write!(f, "Sparc32Plus"),
+            Machine::PowerPc => write!(f, "PowerPc"),
+            Machine::PowerPc64 => write!(f, "PowerPc64"),
+            Machine::S390 => write!(f, "S390"),
+            Machine::Arm => write!(f, "Arm"),
+            Machine::SuperH => write!(f, "SuperH"),
+            Machine::SparcV9 => write!(f, "SparcV9"),
+            Machine::Ia64 => write!(f, "Ia64"),
+            Machine::X86_64 => write!(f, "X86_64"),
+            Machine::Avr => write!(f, "Avr"),
+            Machine::AArch64 => write!(f, "AArch64"),
+            Machine::RiscV => write!(f, "RiscV"),
+            Machine::Bpf => write!(f, "Bpf"),
+            Machine::LoongArch => write!(f, "LoongArch"),
+            Machine::Other(machine) => write!(f, "Unknown ({})", machine),
+        }
+    }
+}
+
 #[derive(Debug)]
-pub struct Elf {}
+pub struct ElfHeader {
+    pub encoding: Encoding,
+    pub version: u8,
+    pub os_abi: u8,
+    pub abi_version: u8,
+    pub file_type: FileType,
+    pub machine: Machine,
+    pub entry: u64,
+    pub program_header_offset: u64,
+    pub section_header_offset: u64,
+    pub flags: u32,
+    pub header_size: u16,
+    pub program_header_size: u16,
+    pub number_of_program_headers: u32,
+    pub section_header_size: u16,
+    pub number_of_sections: u32,
+    pub section_names_index: u32,
+}
+
+impl ElfHeader {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (input, (_, class, data, version, os_abi, abi_version, _)) = context(
+            "Parse ELF Ident",
+            tuple((tag(b"\x7fELF"), u8, u8, u8, u8, u8, take(7usize))),
+        )(input)?;
+        let class = match class {
+            1 => Class::Elf32,
+            2 => Class::Elf64,
+            _ => return Err(parse::failure(input, "Invalid ELF Class")),
+        };
+        let endianness = match data {
+            1 => Endianness::Little,
+            2 => Endianness::Big,
+            _ => return Err(parse::failure(input, "Invalid ELF Data Encoding")),
+        };
+        let encoding = Encoding { class, endianness };
+        let (
+            input,
+            (
+                file_type,
+                machine,
+                _,
+                entry,
+                program_header_offset,
+                section_header_offset,
+                flags,
+                header_size,
+                program_header_size,
+                number_of_program_headers,
+                section_header_size,
+                number_of_sections,
+                section_names_index,
+            ),
+        ) = context(
+            "Parse ELF Header",
+            tuple((
+                map(encoding.u16(), FileType::from),
+                map(encoding.u16(), Machine::from),
+                encoding.u32(),
+                encoding.word(),
+                encoding.word(),
+                encoding.word(),
+                encoding.u32(),
+                encoding.u16(),
+                encoding.u16(),
+                encoding.u16(),
+                encoding.u16(),
+                encoding.u16(),
+                encoding.u16(),
+            )),
+        )(input)?;
+        Ok((
+            input,
+            Self {
+                encoding,
+                version,
+                os_abi,
+                abi_version,
+                file_type,
+                machine,
+                entry,
+                program_header_offset,
+                section_header_offset,
+                flags,
+                header_size,
+                program_header_size,
+                number_of_program_headers: number_of_program_headers as u32,
+                section_header_size,
+                number_of_sections: number_of_sections as u32,
+                section_names_index: section_names_index as u32,
+            },
+        ))
+    }
+
+    pub fn os_abi_name(&self) -> &'static str {
+        match self.os_abi {
+            0 => "System V",
+            1 => "HP-UX",
+            2 => "NetBSD",
+            3 => "Linux",
+            6 => "Solaris",
+            7 => "AIX",
+            8 => "IRIX",
+            9 => "FreeBSD",
+            12 => "OpenBSD",
+            64 => "ARM EABI",
+            97 => "ARM",
+            255 => "Standalone",
+            _ => "Unknown",
+        }
+    }
+}
 
 impl Elf {
-    pub(crate) fn load(data: Vec<u8>) -> Self {
-        todo!("Loading of ELF files is not yet implemented!")
+    pub(crate) fn load(data: Vec<u8>) -> BinDumpResult<Self> {
+        let (_, mut header) = ElfHeader::parse(&data)?;
+        let encoding = header.encoding;
+
+        let mut sections = if header.section_header_offset != 0 {
+            let input = data
+                .get(header.section_header_offset as usize..)
+                .ok_or_else(|| truncated("section headers"))?;
+            // With more than 0xff00 sections the real count is in the first section header
+            let (_, first) = SectionHeader::parse(encoding)(input)?;
+            if header.number_of_sections == 0 {
+                header.number_of_sections = first.size as u32;
+            }
+            if header.section_names_index == SHN_XINDEX as u32 {
+                header.section_names_index = first.link;
+            }
+            if header.number_of_program_headers == PN_XNUM as u32 {
+                header.number_of_program_headers = first.info;
+            }
+            context(
+                "Parse Section Headers",
+                count(
+                    SectionHeader::parse(encoding),
+                    header.number_of_sections as usize,
+                ),
+            )(input)?
+            .1
+        } else {
+            Vec::new()
+        };
+        if let Some(names) = sections.get(header.section_names_index as usize) {
+            let names = section_data(&data, names).to_vec();
+            for section in &mut sections {
+                section.name = string_at(&names, section.name_offset as usize);
+            }
+        }
+
+        let program_headers = if header.program_header_offset != 0 {
+            let input = data
+                .get(header.program_header_offset as usize..)
+                .ok_or_else(|| truncated("program headers"))?;
+            context(
+                "Parse Program Headers",
+                count(
+                    ProgramHeader::parse(encoding),
+                    header.number_of_program_headers as usize,
+                ),
+            )(input)?
+            .1
+        } else {
+            Vec::new()
+        };
+
+        let mut elf = Self {
+            header,
+            program_headers,
+            sections,
+            symbols: Vec::new(),
+            dynamic_symbols: Vec::new(),
+            version_definitions: Vec::new(),
+            version_requirements: Vec::new(),
+            dynamic: Vec::new(),
+            relocations: Vec::new(),
+            notes: Vec::new(),
+            core: None,
+            gnu_hash: None,
+            hash: None,
+            dwarf: None,
+            eh_frame: None,
+            eh_frame_header: None,
+            debug_frame: None,
+            data,
+        };
+        elf.symbols = elf.parse_symbol_table(SectionType::SymbolTable)?;
+        elf.dynamic_symbols = elf.parse_symbol_table(SectionType::DynamicSymbolTable)?;
+        elf.version_definitions = elf.parse_version_definitions()?;
+        elf.version_requirements = elf.parse_version_requirements()?;
+        elf.apply_symbol_versions()?;
+        elf.dynamic = elf.parse_dynamic()?;
+        (elf.gnu_hash, elf.hash) = elf.parse_hash_tables()?;
+        elf.relocations = elf.parse_relocations()?;
+        elf.notes = elf.parse_notes()?;
+        elf.core = elf.parse_core()?;
+        elf.dwarf = elf.parse_dwarf();
+        elf.eh_frame = elf.parse_call_frames(".eh_frame", FrameSection::EhFrame);
+        elf.eh_frame_header = elf.parse_eh_frame_header();
+        elf.debug_frame = elf.parse_call_frames(".debug_frame", FrameSection::DebugFrame);
+        Ok(elf)
+    }
+
+    pub fn header(&self) -> &ElfHeader {
+        &self.header
+    }
+
+    pub fn program_headers(&self) -> &[ProgramHeader] {
+        &self.program_headers
+    }
+
+    pub fn sections(&self) -> &[SectionHeader] {
+        &self.sections
+    }
+
+    /// The symbols from `.symtab`, which is usually removed by stripping
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// The symbols from `.dynsym`, used by the dynamic linker
+    pub fn dynamic_symbols(&self) -> &[Symbol] {
+        &self.dynamic_symbols
+    }
+
+    pub fn version_definitions(&self) -> &[VersionDefinition] {
+        &self.version_definitions
+    }
+
+    pub fn version_requirements(&self) -> &[VersionRequirement] {
+        &self.version_requirements
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<&SectionHeader> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// The contents of a section, which is empty for sections that take up no space in the file
+    pub fn section_data(&self, section: &SectionHeader) -> &[u8] {
+        section_data(&self.data, section)
+    }
+
+    /// Converts a virtual address to a file offset through the `PT_LOAD` segments
+    pub fn virtual_address_to_offset(&self, address: u64) -> Option<u64> {
+        self.program_headers
+            .iter()
+            .filter(|segment| segment.typ == ProgramType::Load)
+            .find(|segment| segment.contains_file_address(address))
+            .map(|segment| segment.offset + (address - segment.virtual_address))
     }
 }
 
+fn section_data<'a>(data: &'a [u8], section: &SectionHeader) -> &'a [u8] {
+    if section.typ == SectionType::NoBits {
+        return &[];
+    }
+    let start = (section.offset as usize).min(data.len());
+    let end = (section.offset.saturating_add(section.size)).min(data.len() as u64) as usize;
+    &data[start..end]
+}
+
 impl fmt::Display for Elf {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        unimplemented!()
+        let header = &self.header;
+        writeln!(
+            f,
+            "ELF: {} Architecture, {}, {:?}",
+            header.machine,
+            match header.encoding.class {
+                Class::Elf32 => "32 bit",
+                Class::Elf64 => "64 bit",
+            },
+            header.encoding.endianness
+        )?;
+        writeln!(f, "Version: {}", header.version)?;
+        writeln!(f, "OS ABI: {} ({})", header.os_abi_name(), header.os_abi)?;
+        writeln!(f, "ABI Version: {}", header.abi_version)?;
+        writeln!(f, "File Type: {:?}", header.file_type)?;
+        writeln!(f, "Entry Point: {:#x}", header.entry)?;
+        writeln!(f, "Flags: {:x}", header.flags)?;
+        writeln!(f, "Program Headers:")?;
+        for (i, program_header) in self.program_headers.iter().enumerate() {
+            writeln!(f, "Program Header {}", i)?;
+            writeln!(f, "{}", program_header)?;
+        }
+        writeln!(f, "Sections:")?;
+        for (i, section) in self.sections.iter().enumerate() {
+            writeln!(f, "Section {}", i)?;
+            writeln!(f, "{}", section)?;
+        }
+        if !self.dynamic.is_empty() {
+            writeln!(f, "Dynamic Section:")?;
+            for entry in &self.dynamic {
+                writeln!(f, "{}", entry)?;
+            }
+            let needed = self.needed_libraries();
+            if !needed.is_empty() {
+                writeln!(f, "Needed Libraries: {}", needed.join(", "))?;
+            }
+            if let Some(soname) = self.soname() {
+                writeln!(f, "Soname: {}", soname)?;
+            }
+            if let Some(runpath) = self.runpath() {
+                writeln!(f, "Runpath: {}", runpath)?;
+            }
+            if let Some(rpath) = self.rpath() {
+                writeln!(f, "Rpath: {}", rpath)?;
+            }
+        }
+        if let Some(table) = &self.gnu_hash {
+            writeln!(f, "GNU Hash Table:")?;
+            writeln!(f, "{}", table)?;
+        }
+        if let Some(table) = &self.hash {
+            writeln!(f, "Hash Table:")?;
+            writeln!(f, "{}", table)?;
+        }
+        let problems = self.check_hash_tables();
+        if !problems.is_empty() {
+            writeln!(f, "Hash Table Problems:")?;
+            for problem in problems {
+                writeln!(f, "{}", problem)?;
+            }
+        }
+        if !self.notes.is_empty() {
+            writeln!(f, "Notes:")?;
+            for note in &self.notes {
+                writeln!(f, "{}", note)?;
+            }
+        }
+        if let Some(core) = &self.core {
+            writeln!(f, "Core:")?;
+            write!(f, "{}", core)?;
+        }
+        match &self.dwarf {
+            Some(Ok(dwarf)) => {
+                writeln!(f, "DWARF:")?;
+                write!(f, "{}", dwarf)?;
+            }
+            Some(Err(e)) => writeln!(f, "DWARF: Error {}", e)?,
+            None => {}
+        }
+        for (name, frames) in [
+            ("Eh Frame", &self.eh_frame),
+            ("Debug Frame", &self.debug_frame),
+        ] {
+            match frames {
+                Some(Ok(frames)) => {
+                    writeln!(f, "{}:", name)?;
+                    write!(f, "{}", frames)?;
+                }
+                Some(Err(e)) => writeln!(f, "{}: Error {}", name, e)?,
+                None => {}
+            }
+        }
+        match &self.eh_frame_header {
+            Some(Ok(header)) => {
+                writeln!(f, "Eh Frame Header:")?;
+                write!(f, "{}", header)?;
+            }
+            Some(Err(e)) => writeln!(f, "Eh Frame Header: Error {}", e)?,
+            None => {}
+        }
+        for table in &self.relocations {
+            writeln!(f, "{}", table)?;
+        }
+        if !self.symbols.is_empty() {
+            writeln!(f, "Symbols:")?;
+            for symbol in &self.symbols {
+                writeln!(f, "{}", symbol)?;
+            }
+        }
+        if !self.dynamic_symbols.is_empty() {
+            writeln!(f, "Dynamic Symbols:")?;
+            for symbol in &self.dynamic_symbols {
+                writeln!(f, "{}", symbol)?;
+            }
+        }
+        if !self.version_definitions.is_empty() {
+            writeln!(f, "Version Definitions:")?;
+            for definition in &self.version_definitions {
+                writeln!(f, "{}", definition)?;
+            }
+        }
+        if !self.version_requirements.is_empty() {
+            writeln!(f, "Version Requirements:")?;
+            for requirement in &self.version_requirements {
+                write!(f, "{}", requirement)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A section of a test file, before it is laid out
+    pub(super) struct TestSection {
+        pub name: &'static str,
+        pub typ: u32,
+        pub link: u32,
+        pub info: u32,
+        pub align: u64,
+        pub entry_size: u64,
+        pub data: Vec<u8>,
+    }
+
+    pub(super) fn section(name: &'static str, typ: u32, data: Vec<u8>) -> TestSection {
+        TestSection {
+            name,
+            typ,
+            link: 0,
+            info: 0,
+            align: 8,
+            entry_size: 0,
+            data,
+        }
+    }
+
+    /// A little endian x86-64 ELF64 file of `file_type` holding `sections`, which get indices from
+    /// 1 and are followed by `.shstrtab`. One `PT_LOAD` segment maps the whole file at address 0,
+    /// so the address of each section is its file offset. The first section is at 0x78.
+    pub(super) fn elf(file_type: u16, mut sections: Vec<TestSection>) -> Elf {
+        let mut names = vec![0];
+        let mut name_offsets = Vec::new();
+        for name in sections
+            .iter()
+            .map(|section| section.name)
+            .chain([".shstrtab"])
+        {
+            name_offsets.push(names.len() as u32);
+            names.extend_from_slice(name.as_bytes());
+            names.push(0);
+        }
+        sections.push(section(".shstrtab", 3, names));
+        let number_of_sections = sections.len() as u16 + 1;
+
+        // The file header and program header come first, then the contents of each section
+        let mut data = vec![0; 0x78];
+        let mut headers = vec![0; 64];
+        for (section, name) in sections.into_iter().zip(name_offsets) {
+            data.resize(data.len().next_multiple_of(8), 0);
+            headers.extend_from_slice(&name.to_le_bytes());
+            headers.extend_from_slice(&section.typ.to_le_bytes());
+            for field in [0, data.len() as u64, data.len() as u64] {
+                headers.extend_from_slice(&field.to_le_bytes());
+            }
+            headers.extend_from_slice(&(section.data.len() as u64).to_le_bytes());
+            headers.extend_from_slice(&section.link.to_le_bytes());
+            headers.extend_from_slice(&section.info.to_le_bytes());
+            headers.extend_from_slice(&section.align.to_le_bytes());
+            headers.extend_from_slice(&section.entry_size.to_le_bytes());
+            data.extend(section.data);
+        }
+        data.resize(data.len().next_multiple_of(8), 0);
+        let section_header_offset = data.len() as u64;
+        data.extend(headers);
+
+        let mut header = vec![0x7f, b'E', b'L', b'F', 2, 1, 1];
+        header.resize(16, 0);
+        for field in [file_type, 62] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        header.extend_from_slice(&1u32.to_le_bytes());
+        for field in [0, 64, section_header_offset] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        header.extend_from_slice(&0u32.to_le_bytes());
+        for field in [64u16, 56, 1, 64, number_of_sections, number_of_sections - 1] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        // PT_LOAD, readable and writable
+        for field in [1u32, 6] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        let size = data.len() as u64;
+        for field in [0, 0, 0, size, size, 0x1000] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        data[..header.len()].copy_from_slice(&header);
+        Elf::load(data).unwrap()
+    }
+
+    /// A note with its name and descriptor padded to `align`
+    pub(super) fn note(owner: &str, typ: u32, descriptor: &[u8], align: usize) -> Vec<u8> {
+        let mut data = Vec::new();
+        for field in [owner.len() as u32 + 1, descriptor.len() as u32, typ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(owner.as_bytes());
+        data.push(0);
+        data.resize(data.len().next_multiple_of(align), 0);
+        data.extend_from_slice(descriptor);
+        data.resize(data.len().next_multiple_of(align), 0);
+        data
+    }
+
+    #[test]
+    fn reads_section_headers() {
+        let mut text = section(".text", 1, vec![0xc3]);
+        text.align = 16;
+        let elf = elf(3, vec![text, section(".bss", 8, vec![0; 4])]);
+        let names: Vec<_> = elf.sections().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["", ".text", ".bss", ".shstrtab"]);
+        let text = elf.section_by_name(".text").unwrap();
+        assert_eq!(text.typ, SectionType::ProgramBits);
+        assert_eq!(text.align, 16);
+        assert_eq!(elf.section_data(text), [0xc3]);
+        // NOBITS sections have a size but no contents
+        let bss = elf.section_by_name(".bss").unwrap();
+        assert_eq!(bss.size, 4);
+        assert!(elf.section_data(bss).is_empty());
+        assert_eq!(
+            elf.virtual_address_to_offset(text.address),
+            Some(text.offset)
+        );
     }
 }
@@ -0,0 +1,85 @@
+use super::{Class, Elf};
+use crate::binary::dwarf::cfi::{
+    CallFrameInformation, EhFrameHeader, FrameSection, PointerBases, UnwindRow,
+};
+use crate::error::BinDumpResult;
+
+impl Elf {
+    fn address_size(&self) -> u8 {
+        match self.header.encoding.class {
+            Class::Elf32 => 4,
+            Class::Elf64 => 8,
+        }
+    }
+
+    /// Reads the call frame information of `.eh_frame` or `.debug_frame`
+    pub(super) fn parse_call_frames(
+        &self,
+        name: &str,
+        section: FrameSection,
+    ) -> Option<BinDumpResult<CallFrameInformation>> {
+        let index = self
+            .sections
+            .iter()
+            .position(|header| header.name == name)?;
+        let address_of = |name| {
+            self.section_by_name(name)
+                .map_or(0, |header| header.address)
+        };
+        let bases = PointerBases {
+            section: self.sections[index].address,
+            text: address_of(".text"),
+            data: address_of(".got"),
+        };
+        Some(CallFrameInformation::parse(
+            &self.relocated_section_data(index),
+            section,
+            bases,
+            self.header.encoding.endianness,
+            self.address_size(),
+        ))
+    }
+
+    pub(super) fn parse_eh_frame_header(&self) -> Option<BinDumpResult<EhFrameHeader>> {
+        let header = self.section_by_name(".eh_frame_hdr")?;
+        Some(EhFrameHeader::parse(
+            self.section_data(header),
+            header.address,
+            self.header.encoding.endianness,
+            self.address_size(),
+        ))
+    }
+
+    pub fn eh_frame(&self) -> Option<&CallFrameInformation> {
+        self.eh_frame.as_ref()?.as_ref().ok()
+    }
+
+    pub fn eh_frame_header(&self) -> Option<&EhFrameHeader> {
+        self.eh_frame_header.as_ref()?.as_ref().ok()
+    }
+
+    pub fn debug_frame(&self) -> Option<&CallFrameInformation> {
+        self.debug_frame.as_ref()?.as_ref().ok()
+    }
+
+    /// The rules for unwinding a frame stopped at an address, from `.eh_frame` through its
+    /// lookup table when there is one, or else from `.debug_frame`
+    pub fn unwind_row(&self, address: u64) -> Option<UnwindRow> {
+        if let Some(eh_frame) = self.eh_frame() {
+            let section = self
+                .section_by_name(".eh_frame")
+                .map_or(0, |header| header.address);
+            let fde = match self.eh_frame_header() {
+                Some(header) => header
+                    .lookup(address)
+                    .and_then(|fde| eh_frame.fde_at(fde.wrapping_sub(section)))
+                    .filter(|fde| fde.contains(address)),
+                None => eh_frame.fde_for(address),
+            };
+            if let Some(row) = fde.and_then(|fde| eh_frame.row_in(fde, address)) {
+                return Some(row);
+            }
+        }
+        self.debug_frame()?.unwind_row(address)
+    }
+}
@@ -0,0 +1,1036 @@
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::combinator::map;
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{i8, u8};
+use nom::sequence::tuple;
+
+use super::notes::NoteContents;
+use super::program_headers::ProgramType;
+use super::{Class, Elf, Encoding, FileType, Machine};
+use crate::error::BinDumpResult;
+use crate::parse::{self, string_at};
+
+// Note types written by the kernel for core dumps, from elf.h
+pub(super) const NT_PRSTATUS: u32 = 1;
+pub(super) const NT_FPREGSET: u32 = 2;
+pub(super) const NT_PRPSINFO: u32 = 3;
+pub(super) const NT_AUXV: u32 = 6;
+pub(super) const NT_SIGINFO: u32 = 0x5349_4749;
+pub(super) const NT_FILE: u32 = 0x4649_4c45;
+pub(super) const NT_PRXFPREG: u32 = 0x46e6_2b7f;
+pub(super) const NT_X86_XSTATE: u32 = 0x202;
+
+/// The general purpose registers of an x86-64 thread, in `user_regs_struct` order
+#[derive(Debug)]
+pub struct X86_64Registers {
+    pub r15: u64,
+    pub r14: u64,
+    pub r13: u64,
+    pub r12: u64,
+    pub rbp: u64,
+    pub rbx: u64,
+    pub r11: u64,
+    pub r10: u64,
+    pub r9: u64,
+    pub r8: u64,
+    pub rax: u64,
+    pub rcx: u64,
+    pub rdx: u64,
+    pub rsi: u64,
+    pub rdi: u64,
+    /// The system call number, if the thread was in a system call
+    pub orig_rax: u64,
+    pub rip: u64,
+    pub cs: u64,
+    pub eflags: u64,
+    pub rsp: u64,
+    pub ss: u64,
+    pub fs_base: u64,
+    pub gs_base: u64,
+    pub ds: u64,
+    pub es: u64,
+    pub fs: u64,
+    pub gs: u64,
+}
+
+/// The general purpose registers of an i386 thread, in `user_regs_struct` order
+#[derive(Debug)]
+pub struct I386Registers {
+    pub ebx: u32,
+    pub ecx: u32,
+    pub edx: u32,
+    pub esi: u32,
+    pub edi: u32,
+    pub ebp: u32,
+    pub eax: u32,
+    pub ds: u32,
+    pub es: u32,
+    pub fs: u32,
+    pub gs: u32,
+    pub orig_eax: u32,
+    pub eip: u32,
+    pub cs: u32,
+    pub eflags: u32,
+    pub esp: u32,
+    pub ss: u32,
+}
+
+#[derive(Debug)]
+pub struct AArch64Registers {
+    /// x0 to x30, where x29 is the frame pointer and x30 the link register
+    pub x: [u64; 31],
+    pub sp: u64,
+    pub pc: u64,
+    pub pstate: u64,
+}
+
+#[derive(Debug)]
+pub struct ArmRegisters {
+    /// r0 to r15, where r13 is the stack pointer, r14 the link register and r15 the pc
+    pub r: [u32; 16],
+    pub cpsr: u32,
+    pub orig_r0: u32,
+}
+
+#[derive(Debug)]
+pub struct RiscVRegisters {
+    pub pc: u64,
+    /// x1 to x31, as x0 is always zero
+    pub x: [u64; 31],
+}
+
+/// The general purpose registers from a `NT_PRSTATUS` note
+#[derive(Debug)]
+pub enum Registers {
+    X86_64(X86_64Registers),
+    I386(I386Registers),
+    AArch64(AArch64Registers),
+    Arm(ArmRegisters),
+    RiscV(RiscVRegisters),
+    /// The raw register set of an architecture whose layout isn't known
+    Other(Vec<u8>),
+}
+
+impl Registers {
+    /// Parses an `elf_gregset_t` for the machine
+    fn parse(encoding: Encoding, machine: Machine, input: parse::Input) -> Registers {
+        let words = |number| -> Option<Vec<u64>> {
+            let parsed: parse::ParseResult<Vec<u64>> =
+                context("Parse Registers", count(encoding.word(), number))(input);
+            parsed.ok().map(|(_, words)| words)
+        };
+        let parsed = match (machine, encoding.class) {
+            (Machine::X86_64, Class::Elf64) => words(27).map(|words| {
+                Registers::X86_64(X86_64Registers {
+                    r15: words[0],
+                    r14: words[1],
+                    r13: words[2],
+                    r12: words[3],
+                    rbp: words[4],
+                    rbx: words[5],
+                    r11: words[6],
+                    r10: words[7],
+                    r9: words[8],
+                    r8: words[9],
+                    rax: words[10],
+                    rcx: words[11],
+                    rdx: words[12],
+                    rsi: words[13],
+                    rdi: words[14],
+                    orig_rax: words[15],
+                    rip: words[16],
+                    cs: words[17],
+                    eflags: words[18],
+                    rsp: words[19],
+                    ss: words[20],
+                    fs_base: words[21],
+                    gs_base: words[22],
+                    ds: words[23],
+                    es: words[24],
+                    fs: words[25],
+                    gs: words[26],
+                })
+            }),
+            (Machine::I386, Class::Elf32) => words(17).map(|words| {
+                let words: Vec<u32> = words.into_iter().map(|word| word as u32).collect();
+                Registers::I386(I386Registers {
+                    ebx: words[0],
+                    ecx: words[1],
+                    edx: words[2],
+                    esi: words[3],
+                    edi: words[4],
+                    ebp: words[5],
+                    eax: words[6],
+                    ds: words[7],
+                    es: words[8],
+                    fs: words[9],
+                    gs: words[10],
+                    orig_eax: words[11],
+                    eip: words[12],
+                    cs: words[13],
+                    eflags: words[14],
+                    esp: words[15],
+                    ss: words[16],
+                })
+            }),
+            (Machine::AArch64, Class::Elf64) => words(34).map(|words| {
+                Registers::AArch64(AArch64Registers {
+                    x: words[..31].try_into().unwrap(),
+                    sp: words[31],
+                    pc: words[32],
+                    pstate: words[33],
+                })
+            }),
+            (Machine::Arm, Class::Elf32) => words(18).map(|words| {
+                let words: Vec<u32> = words.into_iter().map(|word| word as u32).collect();
+                Registers::Arm(ArmRegisters {
+                    r: words[..16].try_into().unwrap(),
+                    cpsr: words[16],
+                    orig_r0: words[17],
+                })
+            }),
+            (Machine::RiscV, Class::Elf64) => words(32).map(|words| {
+                Registers::RiscV(RiscVRegisters {
+                    pc: words[0],
+                    x: words[1..].try_into().unwrap(),
+                })
+            }),
+            _ => None,
+        };
+        parsed.unwrap_or_else(|| Registers::Other(input.to_vec()))
+    }
+
+    /// The number of bytes in an `elf_gregset_t`
+    fn size(encoding: Encoding, machine: Machine) -> Option<usize> {
+        let words = match machine {
+            Machine::X86_64 => 27,
+            Machine::I386 => 17,
+            Machine::AArch64 => 34,
+            Machine::Arm => 18,
+            Machine::RiscV => 32,
+            _ => return None,
+        };
+        Some(words * encoding.word_size() as usize)
+    }
+
+    /// The registers by name, in the order the kernel stores them
+    pub fn named(&self) -> Vec<(String, u64)> {
+        let named = |names: &[&str], values: &[u64]| {
+            names
+                .iter()
+                .map(|name| name.to_string())
+                .zip(values.iter().copied())
+                .collect()
+        };
+        match self {
+            Registers::X86_64(r) => named(
+                &[
+                    "rax", "rbx", "rcx", "rdx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10",
+                    "r11", "r12", "r13", "r14", "r15", "rip", "eflags", "cs", "ss", "ds", "es",
+                    "fs", "gs", "fs_base", "gs_base", "orig_rax",
+                ],
+                &[
+                    r.rax, r.rbx, r.rcx, r.rdx, r.rsi, r.rdi, r.rbp, r.rsp, r.r8, r.r9, r.r10,
+                    r.r11, r.r12, r.r13, r.r14, r.r15, r.rip, r.eflags, r.cs, r.ss, r.ds, r.es,
+                    r.fs, r.gs, r.fs_base, r.gs_base, r.orig_rax,
+                ],
+            ),
+            Registers::I386(r) => named(
+                &[
+                    "eax", "ebx", "ecx", "edx", "esi", "edi", "ebp", "esp", "eip", "eflags", "cs",
+                    "ss", "ds", "es", "fs", "gs", "orig_eax",
+                ],
+                &[
+                    r.eax, r.ebx, r.ecx, r.edx, r.esi, r.edi, r.ebp, r.esp, r.eip, r.eflags, r.cs,
+                    r.ss, r.ds, r.es, r.fs, r.gs, r.orig_eax,
+                ]
+                .map(u64::from),
+            ),
+            Registers::AArch64(r) => (0..31)
+                .map(|i| (format!("x{}", i), r.x[i]))
+                .chain([
+                    ("sp".to_string(), r.sp),
+                    ("pc".to_string(), r.pc),
+                    ("pstate".to_string(), r.pstate),
+                ])
+                .collect(),
+            Registers::Arm(r) => (0..16)
+                .map(|i| (format!("r{}", i), r.r[i] as u64))
+                .chain([
+                    ("cpsr".to_string(), r.cpsr as u64),
+                    ("orig_r0".to_string(), r.orig_r0 as u64),
+                ])
+                .collect(),
+            Registers::RiscV(r) => std::iter::once(("pc".to_string(), r.pc))
+                .chain((0..31).map(|i| (format!("x{}", i + 1), r.x[i])))
+                .collect(),
+            Registers::Other(_) => Vec::new(),
+        }
+    }
+
+    pub fn pc(&self) -> Option<u64> {
+        match self {
+            Registers::X86_64(r) => Some(r.rip),
+            Registers::I386(r) => Some(r.eip as u64),
+            Registers::AArch64(r) => Some(r.pc),
+            Registers::Arm(r) => Some(r.r[15] as u64),
+            Registers::RiscV(r) => Some(r.pc),
+            Registers::Other(_) => None,
+        }
+    }
+
+    pub fn sp(&self) -> Option<u64> {
+        match self {
+            Registers::X86_64(r) => Some(r.rsp),
+            Registers::I386(r) => Some(r.esp as u64),
+            Registers::AArch64(r) => Some(r.sp),
+            Registers::Arm(r) => Some(r.r[13] as u64),
+            Registers::RiscV(r) => Some(r.x[1]),
+            Registers::Other(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Registers::Other(data) = self {
+            return write!(f, "{} bytes of registers", data.len());
+        }
+        for (i, (name, value)) in self.named().iter().enumerate() {
+            if i > 0 {
+                write!(f, "{}", if i % 4 == 0 { "\n" } else { " " })?;
+            }
+            write!(f, "{:>8}: {:016x}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// The floating point and vector registers from a `NT_FPREGSET` or `NT_PRXFPREG` note
+#[derive(Debug)]
+pub enum FloatingPointRegisters {
+    /// The `FXSAVE` layout used on x86-64, and by `NT_PRXFPREG` on i386
+    Fxsave {
+        control: u16,
+        status: u16,
+        tag: u16,
+        opcode: u16,
+        mxcsr: u32,
+        /// The x87 registers, with the 80 bit values in the low bytes
+        st: Vec<u128>,
+        xmm: Vec<u128>,
+    },
+    AArch64 {
+        v: Vec<u128>,
+        fpsr: u32,
+        fpcr: u32,
+    },
+    Other(Vec<u8>),
+}
+
+impl FloatingPointRegisters {
+    fn parse(encoding: Encoding, fxsave: bool, input: parse::Input) -> Self {
+        let u128 = || move |input| nom::number::complete::u128(encoding.endianness)(input);
+        let parsed: parse::ParseResult<Self> = if fxsave {
+            context(
+                "Parse FXSAVE Area",
+                map(
+                    tuple((
+                        encoding.u16(),
+                        encoding.u16(),
+                        encoding.u16(),
+                        encoding.u16(),
+                        take(16usize),
+                        encoding.u32(),
+                        encoding.u32(),
+                        count(u128(), 8),
+                        count(u128(), 16),
+                    )),
+                    |(control, status, tag, opcode, _, mxcsr, _, st, xmm)| {
+                        FloatingPointRegisters::Fxsave {
+                            control,
+                            status,
+                            tag,
+                            opcode,
+                            mxcsr,
+                            st,
+                            xmm,
+                        }
+                    },
+                ),
+            )(input)
+        } else {
+            context(
+                "Parse FPSIMD State",
+                map(
+                    tuple((count(u128(), 32), encoding.u32(), encoding.u32())),
+                    |(v, fpsr, fpcr)| FloatingPointRegisters::AArch64 { v, fpsr, fpcr },
+                ),
+            )(input)
+        };
+        parsed
+            .map(|(_, registers)| registers)
+            .unwrap_or_else(|_| FloatingPointRegisters::Other(input.to_vec()))
+    }
+}
+
+impl fmt::Display for FloatingPointRegisters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FloatingPointRegisters::Fxsave {
+                control,
+                status,
+                tag,
+                opcode,
+                mxcsr,
+                st,
+                xmm,
+            } => {
+                writeln!(
+                    f,
+                    "fcw: {:04x} fsw: {:04x} ftw: {:04x} fop: {:04x} mxcsr: {:08x}",
+                    control, status, tag, opcode, mxcsr
+                )?;
+                for (i, value) in st.iter().enumerate() {
+                    writeln!(f, "st{}: {:020x}", i, value & ((1 << 80) - 1))?;
+                }
+                for (i, value) in xmm.iter().enumerate() {
+                    write!(f, "xmm{}: {:032x}", i, value)?;
+                    if i + 1 < xmm.len() {
+                        writeln!(f)?;
+                    }
+                }
+                Ok(())
+            }
+            FloatingPointRegisters::AArch64 { v, fpsr, fpcr } => {
+                writeln!(f, "fpsr: {:08x} fpcr: {:08x}", fpsr, fpcr)?;
+                for (i, value) in v.iter().enumerate() {
+                    write!(f, "v{}: {:032x}", i, value)?;
+                    if i + 1 < v.len() {
+                        writeln!(f)?;
+                    }
+                }
+                Ok(())
+            }
+            FloatingPointRegisters::Other(data) => {
+                write!(f, "{} bytes of floating point registers", data.len())
+            }
+        }
+    }
+}
+
+/// A thread, from its `NT_PRSTATUS` note and the register notes that follow it
+#[derive(Debug)]
+pub struct Thread {
+    pub pid: i32,
+    pub parent_pid: i32,
+    pub process_group: i32,
+    pub session: i32,
+    /// The signal that the thread is stopped by
+    pub signal: u16,
+    pub pending_signals: u64,
+    pub held_signals: u64,
+    pub registers: Registers,
+    pub floating_point_registers: Option<FloatingPointRegisters>,
+}
+
+impl fmt::Display for Thread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "PID: {}", self.pid)?;
+        writeln!(f, "Signal: {}", self.signal)?;
+        writeln!(f, "Pending Signals: {:#x}", self.pending_signals)?;
+        writeln!(f, "Held Signals: {:#x}", self.held_signals)?;
+        writeln!(f, "Registers:")?;
+        writeln!(f, "{}", self.registers)?;
+        if let Some(registers) = &self.floating_point_registers {
+            writeln!(f, "Floating Point Registers:")?;
+            writeln!(f, "{}", registers)?;
+        }
+        Ok(())
+    }
+}
+
+/// The process information from a `NT_PRPSINFO` note
+#[derive(Debug)]
+pub struct ProcessInfo {
+    pub state: u8,
+    /// The state as shown by `ps`, such as `R` or `S`
+    pub state_name: char,
+    pub zombie: bool,
+    pub nice: i8,
+    pub flags: u64,
+    pub uid: u32,
+    pub gid: u32,
+    pub pid: i32,
+    pub parent_pid: i32,
+    pub process_group: i32,
+    pub session: i32,
+    /// The executable name, truncated to 15 characters
+    pub name: String,
+    /// The start of the command line, truncated to 80 characters
+    pub arguments: String,
+}
+
+impl fmt::Display for ProcessInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Name: {}", self.name)?;
+        writeln!(f, "Command Line: {}", self.arguments)?;
+        writeln!(f, "PID: {}", self.pid)?;
+        writeln!(f, "Parent PID: {}", self.parent_pid)?;
+        writeln!(f, "Process Group: {}", self.process_group)?;
+        writeln!(f, "Session: {}", self.session)?;
+        writeln!(f, "UID: {}", self.uid)?;
+        writeln!(f, "GID: {}", self.gid)?;
+        writeln!(f, "State: {} ({})", self.state_name, self.state)?;
+        writeln!(f, "Nice: {}", self.nice)?;
+        write!(f, "Flags: {:#x}", self.flags)
+    }
+}
+
+/// The `siginfo_t` of the signal that killed the process, from a `NT_SIGINFO` note
+#[derive(Debug)]
+pub struct SignalInfo {
+    pub signal: i32,
+    pub errno: i32,
+    pub code: i32,
+    /// The faulting address, for signals raised by a fault
+    pub address: Option<u64>,
+    /// The PID and UID of the sender, for signals sent by a process
+    pub sender: Option<(i32, u32)>,
+}
+
+// Signals that report a faulting address
+const SIGILL: i32 = 4;
+const SIGTRAP: i32 = 5;
+const SIGBUS: i32 = 7;
+const SIGFPE: i32 = 8;
+const SIGSEGV: i32 = 11;
+
+impl fmt::Display for SignalInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Signal: {}, Code: {}, Errno: {}",
+            self.signal, self.code, self.errno
+        )?;
+        if let Some(address) = self.address {
+            write!(f, ", Address: {:#x}", address)?;
+        }
+        if let Some((pid, uid)) = self.sender {
+            write!(f, ", Sender PID: {}, Sender UID: {}", pid, uid)?;
+        }
+        Ok(())
+    }
+}
+
+/// A file mapped into the process, from a `NT_FILE` note
+#[derive(Debug)]
+pub struct MappedFile {
+    pub start: u64,
+    pub end: u64,
+    /// The offset in the file, in bytes
+    pub file_offset: u64,
+    pub path: String,
+}
+
+impl fmt::Display for MappedFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:016x}-{:016x} {:08x} {}",
+            self.start, self.end, self.file_offset, self.path
+        )
+    }
+}
+
+// From linux/auxvec.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuxiliaryType {
+    Null,
+    Ignore,
+    ExecutableFd,
+    ProgramHeaders,
+    ProgramHeaderSize,
+    ProgramHeaderCount,
+    PageSize,
+    InterpreterBase,
+    Flags,
+    Entry,
+    NotElf,
+    Uid,
+    EffectiveUid,
+    Gid,
+    EffectiveGid,
+    Platform,
+    HardwareCapabilities,
+    ClockTick,
+    Secure,
+    BasePlatform,
+    Random,
+    HardwareCapabilities2,
+    RseqFeatureSize,
+    RseqAlign,
+    HardwareCapabilities3,
+    HardwareCapabilities4,
+    ExecutableName,
+    Sysinfo,
+    SysinfoElfHeader,
+    MinimumSignalStackSize,
+    Other(u64),
+}
+
+impl From<u64> for AuxiliaryType {
+    fn from(typ: u64) -> Self {
+        match typ {
+            0 => AuxiliaryType::Null,
+            1 => AuxiliaryType::Ignore,
+            2 => AuxiliaryType::ExecutableFd,
+            3 => AuxiliaryType::ProgramHeaders,
+            4 => AuxiliaryType::ProgramHeaderSize,
+            5 => AuxiliaryType::ProgramHeaderCount,
+            6 => AuxiliaryType::PageSize,
+            7 => AuxiliaryType::InterpreterBase,
+            8 => AuxiliaryType::Flags,
+            9 => AuxiliaryType::Entry,
+            10 => AuxiliaryType::NotElf,
+            11 => AuxiliaryType::Uid,
+            12 => AuxiliaryType::EffectiveUid,
+            13 => AuxiliaryType::Gid,
+            14 => AuxiliaryType::EffectiveGid,
+            15 => AuxiliaryType::Platform,
+            16 => AuxiliaryType::HardwareCapabilities,
+            17 => AuxiliaryType::ClockTick,
+            23 => AuxiliaryType::Secure,
+            24 => AuxiliaryType::BasePlatform,
+            25 => AuxiliaryType::Random,
+            26 => AuxiliaryType::HardwareCapabilities2,
+            27 => AuxiliaryType::RseqFeatureSize,
+            28 => AuxiliaryType::RseqAlign,
+            29 => AuxiliaryType::HardwareCapabilities3,
+            30 => AuxiliaryType::HardwareCapabilities4,
+            31 => AuxiliaryType::ExecutableName,
+            32 => AuxiliaryType::Sysinfo,
+            33 => AuxiliaryType::SysinfoElfHeader,
+            51 => AuxiliaryType::MinimumSignalStackSize,
+            typ => AuxiliaryType::Other(typ),
+        }
+    }
+}
+
+impl AuxiliaryType {
+    /// Whether the value is the address of a string
+    fn is_string(self) -> bool {
+        matches!(
+            self,
+            AuxiliaryType::Platform | AuxiliaryType::BasePlatform | AuxiliaryType::ExecutableName
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct AuxiliaryEntry {
+    pub typ: AuxiliaryType,
+    pub value: u64,
+    /// The string that the value points to, if it was dumped
+    pub string: Option<String>,
+}
+
+impl fmt::Display for AuxiliaryEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {:#x}", self.typ, self.value)?;
+        if let Some(string) = &self.string {
+            write!(f, " ({})", string)?;
+        }
+        Ok(())
+    }
+}
+
+/// The process state saved in a core dump
+#[derive(Debug, Default)]
+pub struct Core {
+    pub process: Option<ProcessInfo>,
+    pub signal: Option<SignalInfo>,
+    pub threads: Vec<Thread>,
+    pub mapped_files: Vec<MappedFile>,
+    pub auxiliary_vector: Vec<AuxiliaryEntry>,
+}
+
+impl fmt::Display for Core {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(process) = &self.process {
+            writeln!(f, "Process:")?;
+            writeln!(f, "{}", process)?;
+        }
+        if let Some(signal) = &self.signal {
+            writeln!(f, "Signal Info: {}", signal)?;
+        }
+        for (i, thread) in self.threads.iter().enumerate() {
+            writeln!(f, "Thread {}", i)?;
+            writeln!(f, "{}", thread)?;
+        }
+        if !self.mapped_files.is_empty() {
+            writeln!(f, "Mapped Files:")?;
+            for file in &self.mapped_files {
+                writeln!(f, "{}", file)?;
+            }
+        }
+        if !self.auxiliary_vector.is_empty() {
+            writeln!(f, "Auxiliary Vector:")?;
+            for entry in &self.auxiliary_vector {
+                writeln!(f, "{}", entry)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Elf {
+    /// Collects the process state from the notes of a core dump
+    pub(super) fn parse_core(&self) -> BinDumpResult<Option<Core>> {
+        if self.header.file_type != FileType::Core {
+            return Ok(None);
+        }
+        let encoding = self.header.encoding;
+        let mut core = Core::default();
+        for note in &self.notes {
+            let NoteContents::Other(descriptor) = &note.contents else {
+                continue;
+            };
+            match (note.owner.as_str(), note.typ) {
+                ("CORE", NT_PRSTATUS) => core.threads.push(self.parse_prstatus(descriptor)?),
+                ("CORE", NT_PRPSINFO) => {
+                    core.process = Some(parse_prpsinfo(encoding, descriptor)?.1)
+                }
+                ("CORE", NT_SIGINFO) => core.signal = Some(parse_siginfo(encoding, descriptor)?.1),
+                ("CORE", NT_FILE) => {
+                    core.mapped_files = parse_mapped_files(encoding, descriptor)?.1
+                }
+                ("CORE", NT_AUXV) => core.auxiliary_vector = self.parse_auxv(descriptor)?,
+                // The register notes belong to the thread whose status precedes them
+                ("CORE", NT_FPREGSET) | ("LINUX", NT_PRXFPREG) => {
+                    let fxsave = match self.header.machine {
+                        Machine::X86_64 => true,
+                        Machine::I386 => note.typ == NT_PRXFPREG,
+                        Machine::AArch64 => false,
+                        _ => continue,
+                    };
+                    if let Some(thread) = core.threads.last_mut() {
+                        thread.floating_point_registers =
+                            Some(FloatingPointRegisters::parse(encoding, fxsave, descriptor));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(Some(core))
+    }
+
+    fn parse_prstatus(&self, input: &[u8]) -> BinDumpResult<Thread> {
+        let encoding = self.header.encoding;
+        let (rest, (_, signal, _, pending_signals, held_signals)) = context(
+            "Parse Thread Status",
+            tuple((
+                take(12usize),
+                encoding.u16(),
+                take(2usize),
+                encoding.word(),
+                encoding.word(),
+            )),
+        )(input)?;
+        let (rest, (pid, parent_pid, process_group, session, _)) = context(
+            "Parse Thread Status",
+            tuple((
+                encoding.u32(),
+                encoding.u32(),
+                encoding.u32(),
+                encoding.u32(),
+                // The user, system and children's times as four timevals
+                take(8 * encoding.word_size()),
+            )),
+        )(rest)?;
+        let machine = self.header.machine;
+        let size = Registers::size(encoding, machine).unwrap_or(rest.len());
+        let (_, registers) = context("Parse Registers", take(size))(rest)?;
+        Ok(Thread {
+            pid: pid as i32,
+            parent_pid: parent_pid as i32,
+            process_group: process_group as i32,
+            session: session as i32,
+            signal,
+            pending_signals,
+            held_signals,
+            registers: Registers::parse(encoding, machine, registers),
+            floating_point_registers: None,
+        })
+    }
+
+    fn parse_auxv(&self, input: &[u8]) -> BinDumpResult<Vec<AuxiliaryEntry>> {
+        let encoding = self.header.encoding;
+        let mut input = input;
+        let mut entries = Vec::new();
+        while !input.is_empty() {
+            let (rest, (typ, value)) = context(
+                "Parse Auxiliary Vector Entry",
+                tuple((encoding.word(), encoding.word())),
+            )(input)?;
+            input = rest;
+            let typ = AuxiliaryType::from(typ);
+            if typ == AuxiliaryType::Null {
+                break;
+            }
+            let string = if typ.is_string() {
+                self.read_string(value)
+            } else {
+                None
+            };
+            entries.push(AuxiliaryEntry { typ, value, string });
+        }
+        Ok(entries)
+    }
+
+    pub fn core(&self) -> Option<&Core> {
+        self.core.as_ref()
+    }
+
+    /// Reads process memory from the `PT_LOAD` segments of a core dump, or the file image of any
+    /// other ELF file. Memory that wasn't dumped, such as unmodified file mappings, can't be read
+    pub fn read_memory(&self, address: u64, size: u64) -> Option<&[u8]> {
+        self.dumped_memory(address)?
+            .get(..usize::try_from(size).ok()?)
+    }
+
+    /// The memory from `address` to the end of the segment containing it
+    fn dumped_memory(&self, address: u64) -> Option<&[u8]> {
+        let segment = self
+            .program_headers
+            .iter()
+            .filter(|segment| segment.typ == ProgramType::Load)
+            .find(|segment| segment.contains_file_address(address))?;
+        let start = segment.offset + (address - segment.virtual_address);
+        let end = segment.offset + segment.file_size;
+        self.data.get(start as usize..end as usize)
+    }
+
+    /// Reads a null terminated string from process memory
+    fn read_string(&self, address: u64) -> Option<String> {
+        self.dumped_memory(address)
+            .map(|memory| string_at(memory, 0))
+    }
+}
+
+fn parse_prpsinfo(encoding: Encoding, input: parse::Input) -> parse::ParseResult<ProcessInfo> {
+    let (input, (state, state_name, zombie, nice)) =
+        context("Parse Process Info", tuple((u8, u8, u8, i8)))(input)?;
+    let (input, flags) = match encoding.class {
+        Class::Elf32 => encoding.word()(input)?,
+        Class::Elf64 => {
+            let (input, _) = take(4usize)(input)?;
+            encoding.word()(input)?
+        }
+    };
+    // 32 bit kernels use 16 bit IDs here, which leaves the structure 4 bytes shorter
+    let (input, (uid, gid)) = if encoding.class == Class::Elf32 && input.len() < 120 {
+        map(tuple((encoding.u16(), encoding.u16())), |(uid, gid)| {
+            (uid as u32, gid as u32)
+        })(input)?
+    } else {
+        tuple((encoding.u32(), encoding.u32()))(input)?
+    };
+    let (input, (pid, parent_pid, process_group, session, name, arguments)) = context(
+        "Parse Process Info",
+        tuple((
+            encoding.u32(),
+            encoding.u32(),
+            encoding.u32(),
+            encoding.u32(),
+            take(16usize),
+            take(80usize),
+        )),
+    )(input)?;
+    Ok((
+        input,
+        ProcessInfo {
+            state,
+            state_name: state_name as char,
+            zombie: zombie != 0,
+            nice,
+            flags,
+            uid,
+            gid,
+            pid: pid as i32,
+            parent_pid: parent_pid as i32,
+            process_group: process_group as i32,
+            session: session as i32,
+            name: string_at(name, 0),
+            arguments: string_at(arguments, 0).trim_end().to_string(),
+        },
+    ))
+}
+
+fn parse_siginfo(encoding: Encoding, input: parse::Input) -> parse::ParseResult<SignalInfo> {
+    let (input, (signal, errno, code)) = context(
+        "Parse Signal Info",
+        tuple((encoding.u32(), encoding.u32(), encoding.u32())),
+    )(input)?;
+    let (signal, errno, code) = (signal as i32, errno as i32, code as i32);
+    // The union of signal details is aligned like a pointer
+    let (input, _) = take(encoding.word_size() as usize - 4)(input)?;
+    let (_, (address, (pid, uid))) = context(
+        "Parse Signal Info",
+        tuple((
+            nom::combinator::peek(encoding.word()),
+            tuple((encoding.u32(), encoding.u32())),
+        )),
+    )(input)?;
+    let faulted = matches!(signal, SIGILL | SIGTRAP | SIGBUS | SIGFPE | SIGSEGV) && code > 0;
+    Ok((
+        input,
+        SignalInfo {
+            signal,
+            errno,
+            code,
+            address: faulted.then_some(address),
+            // Codes of 0 or less are signals sent from user space
+            sender: (code <= 0).then_some((pid as i32, uid)),
+        },
+    ))
+}
+
+fn parse_mapped_files(
+    encoding: Encoding,
+    input: parse::Input,
+) -> parse::ParseResult<Vec<MappedFile>> {
+    let (input, (number, page_size)) = context(
+        "Parse Mapped Files",
+        tuple((encoding.word(), encoding.word())),
+    )(input)?;
+    let (input, ranges) = context(
+        "Parse Mapped Files",
+        count(
+            tuple((encoding.word(), encoding.word(), encoding.word())),
+            number as usize,
+        ),
+    )(input)?;
+    let mut paths = input.split(|&b| b == 0);
+    let files = ranges
+        .into_iter()
+        .map(|(start, end, page_offset)| MappedFile {
+            start,
+            end,
+            file_offset: page_offset.wrapping_mul(page_size),
+            path: String::from_utf8_lossy(paths.next().unwrap_or_default()).into_owned(),
+        })
+        .collect();
+    Ok((&[], files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{elf, note, section};
+    use super::*;
+
+    fn words(words: &[u64]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    /// An x86-64 core dump of `/bin/true`, whose name is at 0x78, stopped by a segfault at 0xdead
+    fn core_dump() -> Elf {
+        // The signal, then the pending and held signals and the IDs, then rip and rsp
+        let mut status = vec![0; 12];
+        status.extend_from_slice(&[11, 0, 0, 0]);
+        status.extend(words(&[0x100, 0x200]));
+        for id in [42u32, 1, 42, 42] {
+            status.extend_from_slice(&id.to_le_bytes());
+        }
+        status.resize(112, 0);
+        let mut registers = [0; 27];
+        (registers[16], registers[19]) = (0x40_1000, 0x7ffe_0000);
+        status.extend(words(&registers));
+
+        let mut process = vec![0, b'R', 0, 0, 0, 0, 0, 0];
+        process.extend(words(&[0x40_0100]));
+        for id in [1000u32, 1000, 42, 1, 42, 42] {
+            process.extend_from_slice(&id.to_le_bytes());
+        }
+        process.extend_from_slice(b"true\0\0\0\0\0\0\0\0\0\0\0\0");
+        let mut arguments = b"/bin/true --help ".to_vec();
+        arguments.resize(80, 0);
+        process.extend(arguments);
+
+        let mut signal = Vec::new();
+        for field in [11u32, 0, 1, 0] {
+            signal.extend_from_slice(&field.to_le_bytes());
+        }
+        signal.extend(words(&[0xdead]));
+
+        let mut files = words(&[1, 0x1000, 0x40_0000, 0x40_2000, 2]);
+        files.extend_from_slice(b"/bin/true\0");
+
+        let mut notes = note("CORE", NT_PRSTATUS, &status, 4);
+        notes.extend(note("CORE", NT_PRPSINFO, &process, 4));
+        notes.extend(note("CORE", NT_SIGINFO, &signal, 4));
+        notes.extend(note("CORE", NT_FILE, &files, 4));
+        notes.extend(note(
+            "CORE",
+            NT_AUXV,
+            &words(&[6, 0x1000, 31, 0x78, 0, 0]),
+            4,
+        ));
+        let mut notes = section(".note", 7, notes);
+        notes.align = 4;
+        elf(4, vec![section(".data", 1, b"/bin/true\0".to_vec()), notes])
+    }
+
+    #[test]
+    fn reads_the_process_state() {
+        let elf = core_dump();
+        let core = elf.core().unwrap();
+
+        assert_eq!(core.threads.len(), 1);
+        let thread = &core.threads[0];
+        assert_eq!((thread.pid, thread.parent_pid, thread.signal), (42, 1, 11));
+        assert_eq!(
+            (thread.pending_signals, thread.held_signals),
+            (0x100, 0x200)
+        );
+        assert_eq!(thread.registers.pc(), Some(0x40_1000));
+        assert_eq!(thread.registers.sp(), Some(0x7ffe_0000));
+
+        let process = core.process.as_ref().unwrap();
+        assert_eq!((process.name.as_str(), process.state_name), ("true", 'R'));
+        assert_eq!(process.arguments, "/bin/true --help");
+        assert_eq!((process.uid, process.pid), (1000, 42));
+
+        let signal = core.signal.as_ref().unwrap();
+        assert_eq!(
+            signal.to_string(),
+            "Signal: 11, Code: 1, Errno: 0, Address: 0xdead"
+        );
+
+        assert_eq!(core.mapped_files.len(), 1);
+        assert_eq!(
+            core.mapped_files[0].to_string(),
+            "0000000000400000-0000000000402000 00002000 /bin/true"
+        );
+
+        let auxv: Vec<_> = core
+            .auxiliary_vector
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            auxv,
+            ["PageSize: 0x1000", "ExecutableName: 0x78 (/bin/true)"]
+        );
+    }
+
+    #[test]
+    fn reads_dumped_memory() {
+        let elf = core_dump();
+        assert_eq!(elf.read_memory(0x78, 4), Some(&b"/bin"[..]));
+        assert_eq!(elf.read_memory(0x1000_0000, 4), None);
+    }
+}
@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+
+use nom::number::Endianness;
+
+use super::relocations::kinds::DataOperation;
+use super::sections::SectionType;
+use super::{Elf, FileType};
+use crate::binary::dwarf::{Dwarf, DwarfSections};
+use crate::error::{BinDumpError, BinDumpResult};
+
+/// Section flag of sections stored compressed, with a header before the data
+const SHF_COMPRESSED: u64 = 0x800;
+
+impl Elf {
+    /// Reads the DWARF debugging information, if the file has a `.debug_info` section
+    pub(super) fn parse_dwarf(&self) -> Option<BinDumpResult<Dwarf>> {
+        self.section_by_name(".debug_info")
+            .or_else(|| self.section_by_name(".debug_info.dwo"))?;
+        let mut contents = Vec::new();
+        for (index, section) in self.sections.iter().enumerate() {
+            if !section.name.starts_with(".debug_") {
+                continue;
+            }
+            if section.flags & SHF_COMPRESSED != 0 {
+                return Some(Err(BinDumpError::Unsupported {
+                    format: format!("compressed debug section {}", section.name),
+                }));
+            }
+            contents.push((section.name.as_str(), self.relocated_section_data(index)));
+        }
+        let mut sections = DwarfSections::default();
+        for (name, data) in &contents {
+            sections.insert(name, data);
+        }
+        Some(Dwarf::parse(&sections, self.header.encoding.endianness))
+    }
+
+    /// The contents of a section with the data relocations of an object file applied, which is
+    /// how its debug sections refer to each other and to code
+    pub(super) fn relocated_section_data(&self, index: usize) -> Cow<'_, [u8]> {
+        let data = self.section_data(&self.sections[index]);
+        if self.header.file_type != FileType::Relocatable {
+            return Cow::Borrowed(data);
+        }
+        let tables = self
+            .sections
+            .iter()
+            .filter(|section| {
+                matches!(section.typ, SectionType::Rel | SectionType::Rela)
+                    && section.info as usize == index
+            })
+            .filter_map(|section| {
+                self.relocations
+                    .iter()
+                    .find(|table| table.offset == section.offset)
+            });
+        let endianness = self.header.encoding.endianness;
+        let mut data = data.to_vec();
+        for table in tables {
+            for relocation in &table.relocations {
+                let Some((operation, size)) = relocation.typ.data_operation() else {
+                    continue;
+                };
+                let Some(target) = usize::try_from(relocation.offset)
+                    .ok()
+                    .and_then(|offset| data.get_mut(offset..offset.checked_add(size)?))
+                else {
+                    continue;
+                };
+                let current = read_unsigned(target, endianness);
+                // REL relocations keep their addend at the relocated location
+                let addend = relocation.addend.map_or(current, |addend| addend as u64);
+                let value = self
+                    .symbols
+                    .get(relocation.symbol_index as usize)
+                    .map_or(0, |symbol| symbol.value)
+                    .wrapping_add(addend);
+                let value = match operation {
+                    DataOperation::Set => value,
+                    DataOperation::Add => current.wrapping_add(value),
+                    DataOperation::Subtract => current.wrapping_sub(value),
+                };
+                write_unsigned(target, value, endianness);
+            }
+        }
+        Cow::Owned(data)
+    }
+
+    /// The DWARF debugging information
+    pub fn dwarf(&self) -> Option<&Dwarf> {
+        self.dwarf.as_ref()?.as_ref().ok()
+    }
+}
+
+fn read_unsigned(bytes: &[u8], endianness: Endianness) -> u64 {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().enumerate() {
+        let shift = match endianness {
+            Endianness::Big => 8 * (bytes.len() - 1 - i),
+            _ => 8 * i,
+        };
+        value |= (byte as u64) << shift;
+    }
+    value
+}
+
+fn write_unsigned(bytes: &mut [u8], value: u64, endianness: Endianness) {
+    let length = bytes.len();
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        let shift = match endianness {
+            Endianness::Big => 8 * (length - 1 - i),
+            _ => 8 * i,
+        };
+        *byte = (value >> shift) as u8;
+    }
+}
@@ -0,0 +1,432 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::sequence::tuple;
+
+use super::program_headers::ProgramType;
+use super::sections::SectionType;
+use super::Elf;
+use crate::error::BinDumpResult;
+use crate::parse::string_at;
+
+// From elf.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicTag {
+    Null,
+    Needed,
+    PltRelSize,
+    PltGot,
+    Hash,
+    StringTable,
+    SymbolTable,
+    Rela,
+    RelaSize,
+    RelaEntrySize,
+    StringTableSize,
+    SymbolEntrySize,
+    Init,
+    Fini,
+    SoName,
+    RPath,
+    Symbolic,
+    Rel,
+    RelSize,
+    RelEntrySize,
+    PltRel,
+    Debug,
+    TextRel,
+    JumpRel,
+    BindNow,
+    InitArray,
+    FiniArray,
+    InitArraySize,
+    FiniArraySize,
+    RunPath,
+    Flags,
+    PreinitArray,
+    PreinitArraySize,
+    SymbolTableIndices,
+    RelrSize,
+    Relr,
+    RelrEntrySize,
+    AndroidRel,
+    AndroidRelSize,
+    AndroidRela,
+    AndroidRelaSize,
+    AndroidRelr,
+    AndroidRelrSize,
+    AndroidRelrEntrySize,
+    GnuPrelinked,
+    Checksum,
+    GnuHash,
+    TlsDescPlt,
+    TlsDescGot,
+    GnuLibList,
+    Config,
+    DependencyAudit,
+    Audit,
+    VersionSymbols,
+    RelaCount,
+    RelCount,
+    Flags1,
+    VersionDefinitions,
+    VersionDefinitionsNumber,
+    VersionRequirements,
+    VersionRequirementsNumber,
+    Auxiliary,
+    Filter,
+    Other(u64),
+}
+
+impl From<u64> for DynamicTag {
+    fn from(tag: u64) -> Self {
+        match tag {
+            0 => DynamicTag::Null,
+            1 => DynamicTag::Needed,
+            2 => DynamicTag::PltRelSize,
+            3 => DynamicTag::PltGot,
+            4 => DynamicTag::Hash,
+            5 => DynamicTag::StringTable,
+            6 => DynamicTag::SymbolTable,
+            7 => DynamicTag::Rela,
+            8 => DynamicTag::RelaSize,
+            9 => DynamicTag::RelaEntrySize,
+            10 => DynamicTag::StringTableSize,
+            11 => DynamicTag::SymbolEntrySize,
+            12 => DynamicTag::Init,
+            13 => DynamicTag::Fini,
+            14 => DynamicTag::SoName,
+            15 => DynamicTag::RPath,
+            16 => DynamicTag::Symbolic,
+            17 => DynamicTag::Rel,
+            18 => DynamicTag::RelSize,
+            19 => DynamicTag::RelEntrySize,
+            20 => DynamicTag::PltRel,
+            21 => DynamicTag::Debug,
+            22 => DynamicTag::TextRel,
+            23 => DynamicTag::JumpRel,
+            24 => DynamicTag::BindNow,
+            25 => DynamicTag::InitArray,
+            26 => DynamicTag::FiniArray,
+            27 => DynamicTag::InitArraySize,
+            28 => DynamicTag::FiniArraySize,
+            29 => DynamicTag::RunPath,
+            30 => DynamicTag::Flags,
+            32 => DynamicTag::PreinitArray,
+            33 => DynamicTag::PreinitArraySize,
+            34 => DynamicTag::SymbolTableIndices,
+            35 => DynamicTag::RelrSize,
+            36 => DynamicTag::Relr,
+            37 => DynamicTag::RelrEntrySize,
+            0x6000_000f => DynamicTag::AndroidRel,
+            0x6000_0010 => DynamicTag::AndroidRelSize,
+            0x6000_0011 => DynamicTag::AndroidRela,
+            0x6000_0012 => DynamicTag::AndroidRelaSize,
+            0x6fff_e000 => DynamicTag::AndroidRelr,
+            0x6fff_e001 => DynamicTag::AndroidRelrSize,
+            0x6fff_e003 => DynamicTag::AndroidRelrEntrySize,
+            0x6fff_fdf5 => DynamicTag::GnuPrelinked,
+            0x6fff_fdf8 => DynamicTag::Checksum,
+            0x6fff_fef5 => DynamicTag::GnuHash,
+            0x6fff_fef6 => DynamicTag::TlsDescPlt,
+            0x6fff_fef7 => DynamicTag::TlsDescGot,
+            0x6fff_fef9 => DynamicTag::GnuLibList,
+            0x6fff_fefa => DynamicTag::Config,
+            0x6fff_fefb => DynamicTag::DependencyAudit,
+            0x6fff_fefc => DynamicTag::Audit,
+            0x6fff_fff0 => DynamicTag::VersionSymbols,
+            0x6fff_fff9 => DynamicTag::RelaCount,
+            0x6fff_fffa => DynamicTag::RelCount,
+            0x6fff_fffb => DynamicTag::Flags1,
+            0x6fff_fffc => DynamicTag::VersionDefinitions,
+            0x6fff_fffd => DynamicTag::VersionDefinitionsNumber,
+            0x6fff_fffe => DynamicTag::VersionRequirements,
+            0x6fff_ffff => DynamicTag::VersionRequirementsNumber,
+            0x7fff_fffd => DynamicTag::Auxiliary,
+            0x7fff_ffff => DynamicTag::Filter,
+            tag => DynamicTag::Other(tag),
+        }
+    }
+}
+
+impl DynamicTag {
+    /// Whether the value is an offset into the dynamic string table
+    fn is_string(self) -> bool {
+        matches!(
+            self,
+            DynamicTag::Needed
+                | DynamicTag::SoName
+                | DynamicTag::RPath
+                | DynamicTag::RunPath
+                | DynamicTag::Config
+                | DynamicTag::DependencyAudit
+                | DynamicTag::Audit
+                | DynamicTag::Auxiliary
+                | DynamicTag::Filter
+        )
+    }
+
+    /// Whether the value is a size or a count rather than an address
+    fn is_size(self) -> bool {
+        matches!(
+            self,
+            DynamicTag::PltRelSize
+                | DynamicTag::RelaSize
+                | DynamicTag::RelaEntrySize
+                | DynamicTag::StringTableSize
+                | DynamicTag::SymbolEntrySize
+                | DynamicTag::RelSize
+                | DynamicTag::RelEntrySize
+                | DynamicTag::InitArraySize
+                | DynamicTag::FiniArraySize
+                | DynamicTag::PreinitArraySize
+                | DynamicTag::RelrSize
+                | DynamicTag::RelrEntrySize
+                | DynamicTag::AndroidRelSize
+                | DynamicTag::AndroidRelaSize
+                | DynamicTag::AndroidRelrSize
+                | DynamicTag::AndroidRelrEntrySize
+                | DynamicTag::RelaCount
+                | DynamicTag::RelCount
+                | DynamicTag::VersionDefinitionsNumber
+                | DynamicTag::VersionRequirementsNumber
+        )
+    }
+}
+
+const FLAGS: [(u64, &str); 5] = [
+    (0x1, "ORIGIN"),
+    (0x2, "SYMBOLIC"),
+    (0x4, "TEXTREL"),
+    (0x8, "BIND_NOW"),
+    (0x10, "STATIC_TLS"),
+];
+
+const FLAGS_1: [(u64, &str); 28] = [
+    (0x1, "NOW"),
+    (0x2, "GLOBAL"),
+    (0x4, "GROUP"),
+    (0x8, "NODELETE"),
+    (0x10, "LOADFLTR"),
+    (0x20, "INITFIRST"),
+    (0x40, "NOOPEN"),
+    (0x80, "ORIGIN"),
+    (0x100, "DIRECT"),
+    (0x200, "TRANS"),
+    (0x400, "INTERPOSE"),
+    (0x800, "NODEFLIB"),
+    (0x1000, "NODUMP"),
+    (0x2000, "CONFALT"),
+    (0x4000, "ENDFILTEE"),
+    (0x8000, "DISPRELDNE"),
+    (0x1_0000, "DISPRELPND"),
+    (0x2_0000, "NODIRECT"),
+    (0x4_0000, "IGNMULDEF"),
+    (0x8_0000, "NOKSYMS"),
+    (0x10_0000, "NOHDR"),
+    (0x20_0000, "EDITED"),
+    (0x40_0000, "NORELOC"),
+    (0x80_0000, "SYMINTPOSE"),
+    (0x100_0000, "GLOBAUDIT"),
+    (0x200_0000, "SINGLETON"),
+    (0x400_0000, "STUB"),
+    (0x800_0000, "PIE"),
+];
+
+#[derive(Debug)]
+pub struct DynamicEntry {
+    pub tag: DynamicTag,
+    pub value: u64,
+    /// The value resolved through the dynamic string table, for tags that name a string
+    pub string: Option<String>,
+}
+
+impl fmt::Display for DynamicEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: ", self.tag)?;
+        if let Some(string) = &self.string {
+            return write!(f, "{}", string);
+        }
+        let flag_names = |flags: &[(u64, &str)]| {
+            flags
+                .iter()
+                .filter(|(bit, _)| self.value & bit != 0)
+                .map(|(_, name)| *name)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        match self.tag {
+            DynamicTag::Flags => write!(f, "{}", flag_names(&FLAGS)),
+            DynamicTag::Flags1 => write!(f, "{}", flag_names(&FLAGS_1)),
+            DynamicTag::PltRel => match self.value {
+                7 => write!(f, "RELA"),
+                17 => write!(f, "REL"),
+                value => write!(f, "{}", value),
+            },
+            tag if tag.is_size() => write!(f, "{}", self.value),
+            _ => write!(f, "{:#x}", self.value),
+        }
+    }
+}
+
+impl Elf {
+    /// Parses the dynamic section, from `PT_DYNAMIC` if there is one so that it can still be found
+    /// without section headers
+    pub(super) fn parse_dynamic(&self) -> BinDumpResult<Vec<DynamicEntry>> {
+        let data = if let Some(segment) = self
+            .program_headers
+            .iter()
+            .find(|segment| segment.typ == ProgramType::Dynamic)
+        {
+            let start = (segment.offset as usize).min(self.data.len());
+            let end = segment
+                .offset
+                .saturating_add(segment.file_size)
+                .min(self.data.len() as u64) as usize;
+            &self.data[start..end]
+        } else if let Some(section) = self
+            .sections
+            .iter()
+            .find(|section| section.typ == SectionType::Dynamic)
+        {
+            self.section_data(section)
+        } else {
+            return Ok(Vec::new());
+        };
+
+        let encoding = self.header.encoding;
+        let mut input = data;
+        let mut entries = Vec::new();
+        while !input.is_empty() {
+            let (rest, (tag, value)) = context(
+                "Parse Dynamic Entry",
+                tuple((encoding.word(), encoding.word())),
+            )(input)?;
+            input = rest;
+            let tag = DynamicTag::from(tag);
+            if tag == DynamicTag::Null {
+                break;
+            }
+            entries.push(DynamicEntry {
+                tag,
+                value,
+                string: None,
+            });
+        }
+
+        // Strings are looked up through the table that the dynamic linker uses, rather than
+        // through section headers that may have been stripped
+        let string_table = entries
+            .iter()
+            .find(|entry| entry.tag == DynamicTag::StringTable)
+            .and_then(|entry| self.virtual_address_to_offset(entry.value));
+        let string_table_size = entries
+            .iter()
+            .find(|entry| entry.tag == DynamicTag::StringTableSize)
+            .map(|entry| entry.value)
+            .unwrap_or(u64::MAX);
+        if let Some(offset) = string_table {
+            let start = (offset as usize).min(self.data.len());
+            let end = offset
+                .saturating_add(string_table_size)
+                .min(self.data.len() as u64) as usize;
+            let strings = &self.data[start..end];
+            for entry in &mut entries {
+                if entry.tag.is_string() {
+                    entry.string = Some(string_at(strings, entry.value as usize));
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    pub fn dynamic(&self) -> &[DynamicEntry] {
+        &self.dynamic
+    }
+
+    /// The value of the first dynamic entry with the given tag
+    pub fn dynamic_value(&self, tag: DynamicTag) -> Option<u64> {
+        self.dynamic
+            .iter()
+            .find(|entry| entry.tag == tag)
+            .map(|entry| entry.value)
+    }
+
+    fn dynamic_string(&self, tag: DynamicTag) -> Option<&str> {
+        self.dynamic
+            .iter()
+            .find(|entry| entry.tag == tag)
+            .and_then(|entry| entry.string.as_deref())
+    }
+
+    /// The libraries this object depends on, in load order
+    pub fn needed_libraries(&self) -> Vec<&str> {
+        self.dynamic
+            .iter()
+            .filter(|entry| entry.tag == DynamicTag::Needed)
+            .filter_map(|entry| entry.string.as_deref())
+            .collect()
+    }
+
+    pub fn soname(&self) -> Option<&str> {
+        self.dynamic_string(DynamicTag::SoName)
+    }
+
+    pub fn runpath(&self) -> Option<&str> {
+        self.dynamic_string(DynamicTag::RunPath)
+    }
+
+    /// The deprecated search path, which unlike the runpath is searched before `LD_LIBRARY_PATH`
+    pub fn rpath(&self) -> Option<&str> {
+        self.dynamic_string(DynamicTag::RPath)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{elf, section};
+    use super::*;
+
+    #[test]
+    fn resolves_strings_through_the_dynamic_string_table() {
+        // libc.so.6, libm.so.6, libtest.so.1 and $ORIGIN/lib are at 1, 11, 21 and 34
+        let strings = b"\0libc.so.6\0libm.so.6\0libtest.so.1\0$ORIGIN/lib\0".to_vec();
+        let mut dynamic = Vec::new();
+        for (tag, value) in [
+            (1u64, 1u64),
+            (1, 11),
+            (14, 21),
+            (29, 34),
+            (5, 0x78),
+            (10, strings.len() as u64),
+            (30, 0x8),
+            (0x6fff_fffb, 0x800_0001),
+            (20, 7),
+            (0, 0),
+            // Anything after DT_NULL isn't part of the table
+            (1, 1),
+        ] {
+            dynamic.extend_from_slice(&tag.to_le_bytes());
+            dynamic.extend_from_slice(&value.to_le_bytes());
+        }
+        let elf = elf(
+            3,
+            vec![
+                section(".dynstr", 3, strings),
+                section(".dynamic", 6, dynamic),
+            ],
+        );
+
+        assert_eq!(elf.dynamic().len(), 9);
+        assert_eq!(elf.needed_libraries(), ["libc.so.6", "libm.so.6"]);
+        assert_eq!(elf.soname(), Some("libtest.so.1"));
+        assert_eq!(elf.runpath(), Some("$ORIGIN/lib"));
+        assert_eq!(elf.rpath(), None);
+        assert_eq!(elf.dynamic_value(DynamicTag::StringTable), Some(0x78));
+        let shown: Vec<_> = elf.dynamic()[6..].iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            shown,
+            ["Flags: BIND_NOW", "Flags1: NOW PIE", "PltRel: RELA"]
+        );
+    }
+}
@@ -0,0 +1,522 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::multi::count;
+use nom::sequence::tuple;
+
+use super::dynamic::DynamicTag;
+use super::sections::SectionType;
+use super::symbols::{SectionIndex, Symbol, SymbolVersion};
+use super::{Elf, Encoding};
+use crate::error::BinDumpResult;
+use crate::parse;
+
+/// The hash function of `.gnu.hash`, from Bernstein's string hash
+pub fn gnu_hash(name: &[u8]) -> u32 {
+    name.iter().fold(5381u32, |hash, &c| {
+        hash.wrapping_mul(33).wrapping_add(c as u32)
+    })
+}
+
+/// The hash function of the SysV `.hash` table
+pub fn sysv_hash(name: &[u8]) -> u32 {
+    name.iter().fold(0u32, |hash, &c| {
+        let hash = (hash << 4).wrapping_add(c as u32);
+        let high = hash & 0xf000_0000;
+        (hash ^ (high >> 24)) & !high
+    })
+}
+
+/// A `DT_GNU_HASH` table, which hashes the dynamic symbols from `symbol_offset` on
+#[derive(Debug)]
+pub struct GnuHashTable {
+    /// The index of the first dynamic symbol in the table
+    pub symbol_offset: u32,
+    pub bloom_shift: u32,
+    /// Bloom filter words, which are as wide as an address
+    pub bloom: Vec<u64>,
+    /// The index of the first symbol of each bucket, or 0 for an empty bucket
+    pub buckets: Vec<u32>,
+    /// The hash of each symbol, with the low bit set on the last symbol of a bucket
+    pub chains: Vec<u32>,
+    /// The number of bits in a bloom filter word
+    bloom_bits: u32,
+}
+
+impl GnuHashTable {
+    fn parse(encoding: Encoding, input: parse::Input) -> parse::ParseResult<Self> {
+        let (input, (bucket_count, symbol_offset, bloom_size, bloom_shift)) = context(
+            "Parse GNU Hash Table",
+            tuple((
+                encoding.u32(),
+                encoding.u32(),
+                encoding.u32(),
+                encoding.u32(),
+            )),
+        )(input)?;
+        let (input, (bloom, buckets)) = context(
+            "Parse GNU Hash Table",
+            tuple((
+                count(encoding.word(), bloom_size as usize),
+                count(encoding.u32(), bucket_count as usize),
+            )),
+        )(input)?;
+        // The number of chain entries isn't stored, so the chains are followed from the highest
+        // bucket to the end of its last chain
+        let mut chain_count = 0;
+        if let Some(&last) = buckets.iter().max() {
+            if last >= symbol_offset {
+                let mut index = (last - symbol_offset) as usize;
+                while let Some(entry) = input.get(index * 4..) {
+                    let Ok((_, hash)) = encoding.u32()(entry) else {
+                        break;
+                    };
+                    index += 1;
+                    if hash & 1 != 0 {
+                        break;
+                    }
+                }
+                chain_count = index;
+            }
+        }
+        let (input, chains) =
+            context("Parse GNU Hash Chains", count(encoding.u32(), chain_count))(input)?;
+        Ok((
+            input,
+            Self {
+                symbol_offset,
+                bloom_shift,
+                bloom,
+                buckets,
+                chains,
+                bloom_bits: encoding.word_size() as u32 * 8,
+            },
+        ))
+    }
+
+    /// Whether the bloom filter allows that a symbol with this hash is in the table
+    pub fn bloom_matches(&self, hash: u32) -> bool {
+        if self.bloom.is_empty() {
+            return false;
+        }
+        let word = self.bloom[((hash / self.bloom_bits) as usize) % self.bloom.len()];
+        let mask = (1u64 << (hash % self.bloom_bits))
+            | (1u64 << (hash.checked_shr(self.bloom_shift).unwrap_or(0) % self.bloom_bits));
+        word & mask == mask
+    }
+
+    /// The indices of the symbols in the chain of the bucket for `hash`, along with their hashes
+    fn chain(&self, hash: u32) -> impl Iterator<Item = (u32, u32)> + '_ {
+        let start = match self
+            .buckets
+            .get((hash as usize) % self.buckets.len().max(1))
+        {
+            Some(&start) if start >= self.symbol_offset => Some(start),
+            _ => None,
+        };
+        let mut index = start;
+        std::iter::from_fn(move || {
+            let current = index?;
+            let chain_hash = *self.chains.get((current - self.symbol_offset) as usize)?;
+            index = if chain_hash & 1 == 0 {
+                Some(current + 1)
+            } else {
+                None
+            };
+            Some((current, chain_hash))
+        })
+    }
+}
+
+impl fmt::Display for GnuHashTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Buckets: {}", self.buckets.len())?;
+        writeln!(f, "Symbol Offset: {}", self.symbol_offset)?;
+        writeln!(f, "Bloom Filter Words: {}", self.bloom.len())?;
+        writeln!(f, "Bloom Shift: {}", self.bloom_shift)?;
+        write!(f, "Chain Entries: {}", self.chains.len())
+    }
+}
+
+/// A SysV `DT_HASH` table, which hashes every dynamic symbol
+#[derive(Debug)]
+pub struct SysvHashTable {
+    pub buckets: Vec<u32>,
+    /// The next symbol in the chain of each symbol, or 0 at the end of a chain
+    pub chains: Vec<u32>,
+}
+
+impl SysvHashTable {
+    fn parse(encoding: Encoding, input: parse::Input) -> parse::ParseResult<Self> {
+        let (input, (bucket_count, chain_count)) =
+            context("Parse Hash Table", tuple((encoding.u32(), encoding.u32())))(input)?;
+        let (input, (buckets, chains)) = context(
+            "Parse Hash Table",
+            tuple((
+                count(encoding.u32(), bucket_count as usize),
+                count(encoding.u32(), chain_count as usize),
+            )),
+        )(input)?;
+        Ok((input, Self { buckets, chains }))
+    }
+
+    /// The indices of the symbols in the chain of the bucket for `hash`, stopping at a loop
+    fn chain(&self, hash: u32) -> impl Iterator<Item = u32> + '_ {
+        let mut index = self
+            .buckets
+            .get((hash as usize) % self.buckets.len().max(1))
+            .copied()
+            .unwrap_or(0);
+        let mut steps = 0;
+        std::iter::from_fn(move || {
+            if index == 0 || steps > self.chains.len() {
+                return None;
+            }
+            let current = index;
+            index = self.chains.get(current as usize).copied().unwrap_or(0);
+            steps += 1;
+            Some(current)
+        })
+    }
+}
+
+impl fmt::Display for SysvHashTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Buckets: {}", self.buckets.len())?;
+        write!(f, "Chain Entries: {}", self.chains.len())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashTableKind {
+    Gnu,
+    Sysv,
+}
+
+/// An inconsistency between a hash table and the dynamic symbols
+#[derive(Debug)]
+pub enum HashTableProblem {
+    /// A defined symbol that can't be found through the table
+    MissingSymbol {
+        table: HashTableKind,
+        index: u32,
+        name: String,
+    },
+    /// A symbol that the bloom filter rejects, so lookups fail before reaching the chains
+    BloomFilterMiss { index: u32, name: String },
+    /// A chain entry whose hash doesn't match the hash of its symbol's name
+    HashMismatch {
+        index: u32,
+        name: String,
+        stored: u32,
+        computed: u32,
+    },
+    /// A symbol in the chain of a bucket that its hash doesn't select
+    WrongBucket {
+        index: u32,
+        name: String,
+        bucket: u32,
+    },
+    /// A chain that points outside the symbol table or never ends
+    BrokenChain { table: HashTableKind, bucket: u32 },
+}
+
+impl fmt::Display for HashTableProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashTableProblem::MissingSymbol { table, index, name } => write!(
+                f,
+                "{:?}: symbol {} ({}) can't be found through the hash table",
+                table, index, name
+            ),
+            HashTableProblem::BloomFilterMiss { index, name } => write!(
+                f,
+                "Gnu: symbol {} ({}) is rejected by the bloom filter",
+                index, name
+            ),
+            HashTableProblem::HashMismatch {
+                index,
+                name,
+                stored,
+                computed,
+            } => write!(
+                f,
+                "Gnu: symbol {} ({}) has hash {:#x} in the chain but {:#x} by name",
+                index, name, stored, computed
+            ),
+            HashTableProblem::WrongBucket {
+                index,
+                name,
+                bucket,
+            } => write!(
+                f,
+                "Gnu: symbol {} ({}) is in the chain of bucket {} that its hash doesn't select",
+                index, name, bucket
+            ),
+            HashTableProblem::BrokenChain { table, bucket } => {
+                write!(f, "{:?}: the chain of bucket {} is broken", table, bucket)
+            }
+        }
+    }
+}
+
+impl Elf {
+    /// Parses the hash tables through the dynamic section, or through their sections if there
+    /// is no dynamic section
+    pub(super) fn parse_hash_tables(
+        &self,
+    ) -> BinDumpResult<(Option<GnuHashTable>, Option<SysvHashTable>)> {
+        let encoding = self.header.encoding;
+        let gnu = match self.hash_table_data(DynamicTag::GnuHash, SectionType::GnuHash) {
+            Some(data) => Some(GnuHashTable::parse(encoding, data)?.1),
+            None => None,
+        };
+        let sysv = match self.hash_table_data(DynamicTag::Hash, SectionType::Hash) {
+            Some(data) => Some(SysvHashTable::parse(encoding, data)?.1),
+            None => None,
+        };
+        Ok((gnu, sysv))
+    }
+
+    /// The data from the start of a hash table to the end of the file, as the size of a table
+    /// is only known once its header is read
+    fn hash_table_data(&self, tag: DynamicTag, typ: SectionType) -> Option<&[u8]> {
+        let offset = match self.dynamic_value(tag) {
+            Some(address) => self.virtual_address_to_offset(address)?,
+            None => {
+                self.sections
+                    .iter()
+                    .find(|section| section.typ == typ)?
+                    .offset
+            }
+        };
+        self.data.get(offset as usize..)
+    }
+
+    pub fn gnu_hash_table(&self) -> Option<&GnuHashTable> {
+        self.gnu_hash.as_ref()
+    }
+
+    pub fn hash_table(&self) -> Option<&SysvHashTable> {
+        self.hash.as_ref()
+    }
+
+    /// Looks up a defined dynamic symbol the way the dynamic loader does, through `.gnu.hash`
+    /// if there is one and `.hash` otherwise. `name` may carry a version, as in
+    /// `memcpy@GLIBC_2.2.5`; without one the default version is found
+    pub fn lookup_dynamic_symbol(&self, name: &str) -> Option<&Symbol> {
+        let (base, version) = match name.split_once('@') {
+            Some((base, version)) => (base, Some(version.trim_start_matches('@'))),
+            None => (name, None),
+        };
+        let matches = |index: u32| {
+            let symbol = self.dynamic_symbols.get(index as usize)?;
+            if symbol.name != base || symbol.section_index == SectionIndex::Undefined {
+                return None;
+            }
+            let accepted = match (&symbol.version, version) {
+                (Some(SymbolVersion::Defined { name, .. }), Some(version)) => name == version,
+                (Some(SymbolVersion::Defined { hidden, .. }), None) => !hidden,
+                (_, Some(_)) => false,
+                (_, None) => true,
+            };
+            accepted.then_some(symbol)
+        };
+
+        if let Some(table) = &self.gnu_hash {
+            let hash = gnu_hash(base.as_bytes());
+            if !table.bloom_matches(hash) {
+                return None;
+            }
+            return table
+                .chain(hash)
+                .filter(|(_, chain_hash)| chain_hash | 1 == hash | 1)
+                .find_map(|(index, _)| matches(index));
+        }
+        let table = self.hash.as_ref()?;
+        table.chain(sysv_hash(base.as_bytes())).find_map(matches)
+    }
+
+    /// Checks that every defined dynamic symbol can be found through the hash tables, and that
+    /// their chains are well formed
+    pub fn check_hash_tables(&self) -> Vec<HashTableProblem> {
+        let mut problems = Vec::new();
+        let symbols = &self.dynamic_symbols;
+        let defined = |symbol: &Symbol| {
+            !symbol.name.is_empty() && symbol.section_index != SectionIndex::Undefined
+        };
+
+        if let Some(table) = &self.gnu_hash {
+            let mut reachable = vec![false; symbols.len()];
+            for (bucket, &start) in table.buckets.iter().enumerate() {
+                if start == 0 {
+                    continue;
+                }
+                if start < table.symbol_offset
+                    || (start - table.symbol_offset) as usize >= table.chains.len()
+                {
+                    problems.push(HashTableProblem::BrokenChain {
+                        table: HashTableKind::Gnu,
+                        bucket: bucket as u32,
+                    });
+                    continue;
+                }
+                let mut index = start;
+                loop {
+                    let Some(&chain_hash) =
+                        table.chains.get((index - table.symbol_offset) as usize)
+                    else {
+                        problems.push(HashTableProblem::BrokenChain {
+                            table: HashTableKind::Gnu,
+                            bucket: bucket as u32,
+                        });
+                        break;
+                    };
+                    if let Some(symbol) = symbols.get(index as usize) {
+                        reachable[index as usize] = true;
+                        let computed = gnu_hash(symbol.name.as_bytes());
+                        if computed | 1 != chain_hash | 1 {
+                            problems.push(HashTableProblem::HashMismatch {
+                                index,
+                                name: symbol.name.clone(),
+                                stored: chain_hash,
+                                computed,
+                            });
+                        } else if computed as usize % table.buckets.len() != bucket {
+                            problems.push(HashTableProblem::WrongBucket {
+                                index,
+                                name: symbol.name.clone(),
+                                bucket: bucket as u32,
+                            });
+                        }
+                    }
+                    if chain_hash & 1 != 0 {
+                        break;
+                    }
+                    index += 1;
+                }
+            }
+            for (index, symbol) in symbols.iter().enumerate() {
+                if !defined(symbol) {
+                    continue;
+                }
+                if !reachable[index] {
+                    problems.push(HashTableProblem::MissingSymbol {
+                        table: HashTableKind::Gnu,
+                        index: index as u32,
+                        name: symbol.name.clone(),
+                    });
+                } else if !table.bloom_matches(gnu_hash(symbol.name.as_bytes())) {
+                    problems.push(HashTableProblem::BloomFilterMiss {
+                        index: index as u32,
+                        name: symbol.name.clone(),
+                    });
+                }
+            }
+        }
+
+        if let Some(table) = &self.hash {
+            for (bucket, &start) in table.buckets.iter().enumerate() {
+                let mut index = start;
+                let mut steps = 0;
+                while index != 0 {
+                    steps += 1;
+                    match table.chains.get(index as usize) {
+                        Some(&next) if steps <= table.chains.len() => index = next,
+                        _ => {
+                            problems.push(HashTableProblem::BrokenChain {
+                                table: HashTableKind::Sysv,
+                                bucket: bucket as u32,
+                            });
+                            break;
+                        }
+                    }
+                }
+            }
+            for (index, symbol) in symbols.iter().enumerate() {
+                if defined(symbol)
+                    && !table
+                        .chain(sysv_hash(symbol.name.as_bytes()))
+                        .any(|found| found as usize == index)
+                {
+                    problems.push(HashTableProblem::MissingSymbol {
+                        table: HashTableKind::Sysv,
+                        index: index as u32,
+                        name: symbol.name.clone(),
+                    });
+                }
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Class;
+    use super::*;
+    use nom::number::Endianness;
+
+    const ENCODING: Encoding = Encoding {
+        class: Class::Elf64,
+        endianness: Endianness::Little,
+    };
+
+    fn words(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn hashes_names() {
+        assert_eq!(gnu_hash(b""), 5381);
+        assert_eq!(gnu_hash(b"printf"), 0x156b_2bb8);
+        assert_eq!(gnu_hash(b"exit"), 0x7c96_7e3f);
+        assert_eq!(sysv_hash(b"printf"), 0x0779_05a6);
+        assert_eq!(sysv_hash(b"exit"), 0x0006_cf04);
+    }
+
+    #[test]
+    fn looks_up_gnu_hash_chains() {
+        // printf in the first bucket, and exit and puts in the second, after the null symbol
+        let mut data = words(&[2, 1, 1, 6]);
+        // The two bits each of the names sets with a shift of 6
+        let bloom = [56, 46, 63, 56, 17, 44]
+            .iter()
+            .fold(0u64, |bloom, bit| bloom | 1 << bit);
+        data.extend_from_slice(&bloom.to_le_bytes());
+        data.extend(words(&[1, 2, 0x156b_2bb9, 0x7c96_7e3e, 0x7c9c_7b11]));
+        // Whatever follows the last chain isn't part of the table
+        data.extend(words(&[0xdead_beef]));
+        let (_, table) = GnuHashTable::parse(ENCODING, &data).unwrap();
+        assert_eq!(table.chains.len(), 3);
+
+        for name in ["printf", "exit", "puts"] {
+            assert!(table.bloom_matches(gnu_hash(name.as_bytes())));
+        }
+        assert!(!table.bloom_matches(gnu_hash(b"")));
+        let indices = |name: &str| -> Vec<u32> {
+            table
+                .chain(gnu_hash(name.as_bytes()))
+                .map(|(index, _)| index)
+                .collect()
+        };
+        assert_eq!(indices("printf"), [1]);
+        assert_eq!(indices("puts"), [2, 3]);
+    }
+
+    #[test]
+    fn looks_up_sysv_hash_chains() {
+        // printf and exit in the first bucket, and puts in the second
+        let data = words(&[2, 4, 1, 3, 0, 2, 0, 0]);
+        let (_, table) = SysvHashTable::parse(ENCODING, &data).unwrap();
+        let indices =
+            |name: &str| -> Vec<u32> { table.chain(sysv_hash(name.as_bytes())).collect() };
+        assert_eq!(indices("exit"), [1, 2]);
+        assert_eq!(indices("puts"), [3]);
+
+        // A chain that loops back on itself still ends
+        let data = words(&[1, 2, 1, 0, 1]);
+        let (_, table) = SysvHashTable::parse(ENCODING, &data).unwrap();
+        assert!(table.chain(0).count() <= table.chains.len() + 1);
+    }
+}
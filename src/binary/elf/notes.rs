@@ -0,0 +1,464 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::error::context;
+use nom::sequence::tuple;
+
+use super::core_dump::{
+    NT_AUXV, NT_FILE, NT_FPREGSET, NT_PRPSINFO, NT_PRSTATUS, NT_PRXFPREG, NT_SIGINFO, NT_X86_XSTATE,
+};
+use super::program_headers::ProgramType;
+use super::sections::SectionType;
+use super::{Elf, Encoding, Machine};
+use crate::error::BinDumpResult;
+use crate::parse::{self, hex, string_at};
+
+// Note types for the "GNU" owner, from elf.h
+const NT_GNU_ABI_TAG: u32 = 1;
+const NT_GNU_HWCAP: u32 = 2;
+const NT_GNU_BUILD_ID: u32 = 3;
+const NT_GNU_GOLD_VERSION: u32 = 4;
+const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+/// The type of `.note.package` notes, owned by "FDO"
+const NT_FDO_PACKAGING_METADATA: u32 = 0xcafe_1a7e;
+const NT_STAPSDT: u32 = 3;
+
+// GNU property types
+const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc000_0000;
+const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc000_0002;
+const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc000_8002;
+const GNU_PROPERTY_X86_FEATURE_2_NEEDED: u32 = 0xc000_8001;
+const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xc001_0001;
+const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc001_0002;
+
+const X86_FEATURE_1: [(u32, &str); 4] = [(1, "IBT"), (2, "SHSTK"), (4, "LAM_U48"), (8, "LAM_U57")];
+
+const X86_FEATURE_2: [(u32, &str); 10] = [
+    (1, "x86"),
+    (2, "x87"),
+    (4, "MMX"),
+    (8, "XMM"),
+    (0x10, "YMM"),
+    (0x20, "ZMM"),
+    (0x40, "FXSR"),
+    (0x80, "XSAVE"),
+    (0x100, "XSAVEOPT"),
+    (0x200, "XSAVEC"),
+];
+
+const X86_ISA_1: [(u32, &str); 4] = [
+    (1, "x86-64-baseline"),
+    (2, "x86-64-v2"),
+    (4, "x86-64-v3"),
+    (8, "x86-64-v4"),
+];
+
+const AARCH64_FEATURE_1: [(u32, &str); 3] = [(1, "BTI"), (2, "PAC"), (4, "GCS")];
+
+/// Writes the names of the set bits in `value`, and any unknown bits in hex
+fn write_bits(f: &mut fmt::Formatter<'_>, value: u32, names: &[(u32, &str)]) -> fmt::Result {
+    let mut parts: Vec<String> = names
+        .iter()
+        .filter(|(bit, _)| value & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect();
+    let known = names.iter().fold(0, |known, (bit, _)| known | bit);
+    if value & !known != 0 {
+        parts.push(format!("{:#x}", value & !known));
+    }
+    if parts.is_empty() {
+        write!(f, "<None>")
+    } else {
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// A property from a `NT_GNU_PROPERTY_TYPE_0` note
+#[derive(Debug)]
+pub enum GnuProperty {
+    StackSize(u64),
+    NoCopyOnProtected,
+    /// Control flow protection that every object in the link supports
+    X86Feature1And(u32),
+    X86Feature2Needed(u32),
+    X86Feature2Used(u32),
+    /// The x86-64 microarchitecture levels needed to run the code
+    X86Isa1Needed(u32),
+    X86Isa1Used(u32),
+    /// Branch target identification and pointer authentication that every object supports
+    AArch64Feature1And(u32),
+    Other {
+        typ: u32,
+        data: Vec<u8>,
+    },
+}
+
+impl fmt::Display for GnuProperty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GnuProperty::StackSize(size) => write!(f, "stack size: {:#x}", size),
+            GnuProperty::NoCopyOnProtected => write!(f, "no copy on protected"),
+            GnuProperty::X86Feature1And(value) => {
+                write!(f, "x86 feature: ")?;
+                write_bits(f, *value, &X86_FEATURE_1)
+            }
+            GnuProperty::X86Feature2Needed(value) => {
+                write!(f, "x86 feature needed: ")?;
+                write_bits(f, *value, &X86_FEATURE_2)
+            }
+            GnuProperty::X86Feature2Used(value) => {
+                write!(f, "x86 feature used: ")?;
+                write_bits(f, *value, &X86_FEATURE_2)
+            }
+            GnuProperty::X86Isa1Needed(value) => {
+                write!(f, "x86 ISA needed: ")?;
+                write_bits(f, *value, &X86_ISA_1)
+            }
+            GnuProperty::X86Isa1Used(value) => {
+                write!(f, "x86 ISA used: ")?;
+                write_bits(f, *value, &X86_ISA_1)
+            }
+            GnuProperty::AArch64Feature1And(value) => {
+                write!(f, "AArch64 feature: ")?;
+                write_bits(f, *value, &AARCH64_FEATURE_1)
+            }
+            GnuProperty::Other { typ, data } => {
+                write!(f, "<unknown {:#x}> {} bytes", typ, data.len())
+            }
+        }
+    }
+}
+
+/// A SystemTap statically defined tracing probe
+#[derive(Debug)]
+pub struct SdtProbe {
+    /// The address of the probe point
+    pub pc: u64,
+    /// The link time address of `.stapsdt.base`, used to adjust `pc` for prelinking
+    pub base: u64,
+    /// The address of the counter that enables the probe, or 0
+    pub semaphore: u64,
+    pub provider: String,
+    pub name: String,
+    /// The argument locations, in the assembler syntax of the architecture
+    pub arguments: String,
+}
+
+#[derive(Debug)]
+pub enum NoteContents {
+    /// The unique identifier of the build, which debug files are keyed by
+    BuildId(Vec<u8>),
+    /// The earliest kernel that the file runs on
+    AbiTag {
+        os: u32,
+        major: u32,
+        minor: u32,
+        patch: u32,
+    },
+    GoldVersion(String),
+    GnuProperties(Vec<GnuProperty>),
+    /// The JSON description of the package that the file was built for
+    Package(String),
+    Stapsdt(SdtProbe),
+    Other(Vec<u8>),
+}
+
+#[derive(Debug)]
+pub struct Note {
+    pub owner: String,
+    pub typ: u32,
+    pub contents: NoteContents,
+}
+
+impl Note {
+    pub fn type_name(&self) -> &'static str {
+        match (self.owner.as_str(), self.typ) {
+            ("GNU", NT_GNU_ABI_TAG) => "NT_GNU_ABI_TAG",
+            ("GNU", NT_GNU_HWCAP) => "NT_GNU_HWCAP",
+            ("GNU", NT_GNU_BUILD_ID) => "NT_GNU_BUILD_ID",
+            ("GNU", NT_GNU_GOLD_VERSION) => "NT_GNU_GOLD_VERSION",
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => "NT_GNU_PROPERTY_TYPE_0",
+            ("FDO", NT_FDO_PACKAGING_METADATA) => "NT_FDO_PACKAGING_METADATA",
+            ("stapsdt", NT_STAPSDT) => "NT_STAPSDT",
+            ("CORE", NT_PRSTATUS) => "NT_PRSTATUS",
+            ("CORE", NT_FPREGSET) => "NT_FPREGSET",
+            ("CORE", NT_PRPSINFO) => "NT_PRPSINFO",
+            ("CORE", NT_AUXV) => "NT_AUXV",
+            ("CORE", NT_SIGINFO) => "NT_SIGINFO",
+            ("CORE", NT_FILE) => "NT_FILE",
+            ("LINUX", NT_PRXFPREG) => "NT_PRXFPREG",
+            ("LINUX", NT_X86_XSTATE) => "NT_X86_XSTATE",
+            _ => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ({:#x}): ", self.owner, self.type_name(), self.typ)?;
+        match &self.contents {
+            NoteContents::BuildId(id) => write!(f, "Build ID: {}", hex(id)),
+            NoteContents::AbiTag {
+                os,
+                major,
+                minor,
+                patch,
+            } => {
+                let os = match os {
+                    0 => "Linux",
+                    1 => "Hurd",
+                    2 => "Solaris",
+                    3 => "FreeBSD",
+                    4 => "NetBSD",
+                    5 => "Syllable",
+                    _ => "Unknown",
+                };
+                write!(f, "OS: {}, ABI: {}.{}.{}", os, major, minor, patch)
+            }
+            NoteContents::GoldVersion(version) => write!(f, "Version: {}", version),
+            NoteContents::GnuProperties(properties) => {
+                let properties: Vec<String> = properties.iter().map(|p| p.to_string()).collect();
+                write!(f, "Properties: {}", properties.join("; "))
+            }
+            NoteContents::Package(json) => write!(f, "Package: {}", json),
+            NoteContents::Stapsdt(probe) => {
+                write!(f, "Provider: {}, Name: {}, ", probe.provider, probe.name)?;
+                write!(
+                    f,
+                    "Location: {:#x}, Base: {:#x}, Semaphore: {:#x}, Arguments: {}",
+                    probe.pc, probe.base, probe.semaphore, probe.arguments
+                )
+            }
+            NoteContents::Other(data) => write!(f, "{} bytes", data.len()),
+        }
+    }
+}
+
+/// Rounds `value` up to a multiple of `align`, which is a power of two
+fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+impl Elf {
+    /// Parses the notes in the note sections, and in the `PT_NOTE` segments for notes that no
+    /// section covers
+    pub(super) fn parse_notes(&self) -> BinDumpResult<Vec<Note>> {
+        let mut blocks: Vec<(u64, &[u8], u64)> = self
+            .sections
+            .iter()
+            .filter(|section| section.typ == SectionType::Note)
+            .map(|section| (section.offset, self.section_data(section), section.align))
+            .collect();
+        for segment in &self.program_headers {
+            if segment.typ != ProgramType::Note {
+                continue;
+            }
+            let start = (segment.offset as usize).min(self.data.len());
+            let end = segment
+                .offset
+                .saturating_add(segment.file_size)
+                .min(self.data.len() as u64) as usize;
+            blocks.push((segment.offset, &self.data[start..end], segment.align));
+        }
+
+        let mut seen = HashSet::new();
+        let mut notes = Vec::new();
+        for (offset, data, align) in blocks {
+            // Notes are 4 byte aligned, except GNU properties in 64 bit files which are 8
+            let align = if align == 8 { 8 } else { 4 };
+            let mut position = 0;
+            while position + 12 <= data.len() {
+                let input = &data[position..];
+                let (_, (name_size, descriptor_size, typ)) = context(
+                    "Parse Note Header",
+                    tuple((
+                        self.header.encoding.u32(),
+                        self.header.encoding.u32(),
+                        self.header.encoding.u32(),
+                    )),
+                )(input)?;
+                let name_start = 12;
+                let descriptor_start = align_up(name_start + name_size as usize, align);
+                let end = align_up(descriptor_start + descriptor_size as usize, align);
+                let (_, descriptor) = context("Parse Note", take(descriptor_size))(
+                    input.get(descriptor_start..).unwrap_or_default(),
+                )?;
+                let name = &input[name_start..(name_start + name_size as usize).min(input.len())];
+                let note_offset = offset + position as u64;
+                position += end;
+                if !seen.insert(note_offset) {
+                    continue;
+                }
+                let owner = string_at(name, 0);
+                let contents = self.note_contents(&owner, typ, descriptor);
+                notes.push(Note {
+                    owner,
+                    typ,
+                    contents,
+                });
+            }
+        }
+        Ok(notes)
+    }
+
+    /// Decodes a note descriptor, keeping the raw bytes of notes that are unknown or malformed
+    fn note_contents(&self, owner: &str, typ: u32, descriptor: &[u8]) -> NoteContents {
+        let encoding = self.header.encoding;
+        let parsed = match (owner, typ) {
+            ("GNU", NT_GNU_BUILD_ID) => Some(NoteContents::BuildId(descriptor.to_vec())),
+            ("GNU", NT_GNU_ABI_TAG) => tuple((
+                encoding.u32(),
+                encoding.u32(),
+                encoding.u32(),
+                encoding.u32(),
+            ))(descriptor)
+            .ok()
+            .map(|(_, (os, major, minor, patch))| NoteContents::AbiTag {
+                os,
+                major,
+                minor,
+                patch,
+            }),
+            ("GNU", NT_GNU_GOLD_VERSION) => {
+                Some(NoteContents::GoldVersion(string_at(descriptor, 0)))
+            }
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => self
+                .parse_gnu_properties(descriptor)
+                .map(NoteContents::GnuProperties),
+            ("FDO", NT_FDO_PACKAGING_METADATA) => {
+                Some(NoteContents::Package(string_at(descriptor, 0)))
+            }
+            ("stapsdt", NT_STAPSDT) => parse_sdt_probe(encoding, descriptor)
+                .ok()
+                .map(|(_, probe)| NoteContents::Stapsdt(probe)),
+            _ => None,
+        };
+        parsed.unwrap_or_else(|| NoteContents::Other(descriptor.to_vec()))
+    }
+
+    fn parse_gnu_properties(&self, mut input: &[u8]) -> Option<Vec<GnuProperty>> {
+        let encoding = self.header.encoding;
+        let align = encoding.word_size() as usize;
+        let mut properties = Vec::new();
+        while !input.is_empty() {
+            let parsed: parse::ParseResult<(u32, u32)> =
+                tuple((encoding.u32(), encoding.u32()))(input);
+            let (rest, (typ, size)) = parsed.ok()?;
+            let data = rest.get(..size as usize)?;
+            let value = || encoding.u32()(data).ok().map(|(_, value)| value);
+            let machine = self.header.machine;
+            let x86 = matches!(machine, Machine::X86_64 | Machine::I386);
+            let property = match typ {
+                GNU_PROPERTY_STACK_SIZE => GnuProperty::StackSize(encoding.word()(data).ok()?.1),
+                GNU_PROPERTY_NO_COPY_ON_PROTECTED => GnuProperty::NoCopyOnProtected,
+                GNU_PROPERTY_X86_FEATURE_1_AND if x86 => GnuProperty::X86Feature1And(value()?),
+                GNU_PROPERTY_X86_FEATURE_2_NEEDED if x86 => {
+                    GnuProperty::X86Feature2Needed(value()?)
+                }
+                GNU_PROPERTY_X86_FEATURE_2_USED if x86 => GnuProperty::X86Feature2Used(value()?),
+                GNU_PROPERTY_X86_ISA_1_NEEDED if x86 => GnuProperty::X86Isa1Needed(value()?),
+                GNU_PROPERTY_X86_ISA_1_USED if x86 => GnuProperty::X86Isa1Used(value()?),
+                GNU_PROPERTY_AARCH64_FEATURE_1_AND if machine == Machine::AArch64 => {
+                    GnuProperty::AArch64Feature1And(value()?)
+                }
+                typ => GnuProperty::Other {
+                    typ,
+                    data: data.to_vec(),
+                },
+            };
+            properties.push(property);
+            input = rest
+                .get(align_up(size as usize, align)..)
+                .unwrap_or_default();
+        }
+        Some(properties)
+    }
+
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    /// The GNU build ID, which identifies the build for finding its separate debug file
+    pub fn build_id(&self) -> Option<&[u8]> {
+        self.notes.iter().find_map(|note| match &note.contents {
+            NoteContents::BuildId(id) => Some(&id[..]),
+            _ => None,
+        })
+    }
+}
+
+fn parse_sdt_probe(encoding: Encoding, input: parse::Input) -> parse::ParseResult<SdtProbe> {
+    let (strings, (pc, base, semaphore)) = context(
+        "Parse SDT Probe",
+        tuple((encoding.word(), encoding.word(), encoding.word())),
+    )(input)?;
+    let mut parts = strings.split(|&b| b == 0);
+    let mut next = || String::from_utf8_lossy(parts.next().unwrap_or_default()).into_owned();
+    let (provider, name, arguments) = (next(), next(), next());
+    Ok((
+        &[],
+        SdtProbe {
+            pc,
+            base,
+            semaphore,
+            provider,
+            name,
+            arguments,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{elf, note, section};
+    use super::*;
+
+    #[test]
+    fn reads_the_build_id_and_abi_tag() {
+        let mut notes = note("GNU", NT_GNU_BUILD_ID, &[0xde, 0xad, 0xbe, 0xef, 0x01], 4);
+        let abi_tag: Vec<u8> = [0u32, 3, 2, 0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        notes.extend(note("GNU", NT_GNU_ABI_TAG, &abi_tag, 4));
+        let mut notes = section(".note", 7, notes);
+        notes.align = 4;
+        let elf = elf(3, vec![notes]);
+
+        assert_eq!(elf.build_id(), Some(&[0xde, 0xad, 0xbe, 0xef, 0x01][..]));
+        let shown: Vec<_> = elf.notes().iter().map(|note| note.to_string()).collect();
+        assert_eq!(
+            shown,
+            [
+                "GNU NT_GNU_BUILD_ID (0x3): Build ID: deadbeef01",
+                "GNU NT_GNU_ABI_TAG (0x1): OS: Linux, ABI: 3.2.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_gnu_properties() {
+        let mut properties = Vec::new();
+        // IBT, SHSTK and an unknown bit, then the x86-64-v2 level padded to 8 bytes
+        for (typ, value) in [
+            (GNU_PROPERTY_X86_FEATURE_1_AND, 0x13),
+            (GNU_PROPERTY_X86_ISA_1_NEEDED, 2),
+        ] {
+            for field in [typ, 4, value, 0] {
+                properties.extend_from_slice(&u32::to_le_bytes(field));
+            }
+        }
+        let notes = note("GNU", NT_GNU_PROPERTY_TYPE_0, &properties, 8);
+        let elf = elf(3, vec![section(".note.gnu.property", 7, notes)]);
+
+        assert_eq!(elf.notes().len(), 1);
+        assert_eq!(
+            elf.notes()[0].to_string(),
+            "GNU NT_GNU_PROPERTY_TYPE_0 (0x5): Properties: x86 feature: IBT, SHSTK, 0x10; \
+             x86 ISA needed: x86-64-v2"
+        );
+    }
+}
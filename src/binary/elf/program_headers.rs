@@ -0,0 +1,155 @@
+use std::fmt;
+
+use nom::combinator::map;
+use nom::error::context;
+use nom::sequence::tuple;
+
+use super::{Class, Encoding};
+use crate::parse;
+
+// From elf.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgramType {
+    Null,
+    Load,
+    Dynamic,
+    Interpreter,
+    Note,
+    Shlib,
+    ProgramHeaders,
+    Tls,
+    GnuEhFrame,
+    GnuStack,
+    GnuRelro,
+    GnuProperty,
+    Other(u32),
+}
+
+impl From<u32> for ProgramType {
+    fn from(typ: u32) -> Self {
+        match typ {
+            0 => ProgramType::Null,
+            1 => ProgramType::Load,
+            2 => ProgramType::Dynamic,
+            3 => ProgramType::Interpreter,
+            4 => ProgramType::Note,
+            5 => ProgramType::Shlib,
+            6 => ProgramType::ProgramHeaders,
+            7 => ProgramType::Tls,
+            0x6474_e550 => ProgramType::GnuEhFrame,
+            0x6474_e551 => ProgramType::GnuStack,
+            0x6474_e552 => ProgramType::GnuRelro,
+            0x6474_e553 => ProgramType::GnuProperty,
+            typ => ProgramType::Other(typ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ProgramHeader {
+    pub typ: ProgramType,
+    pub flags: u32,
+    pub offset: u64,
+    pub virtual_address: u64,
+    pub physical_address: u64,
+    pub file_size: u64,
+    pub memory_size: u64,
+    pub align: u64,
+}
+
+impl ProgramHeader {
+    pub(super) fn parse(
+        encoding: Encoding,
+    ) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input| {
+            let word = || encoding.word();
+            // The flags moved to keep the 64 bit fields aligned
+            let (
+                input,
+                (
+                    typ,
+                    flags,
+                    offset,
+                    virtual_address,
+                    physical_address,
+                    file_size,
+                    memory_size,
+                    align,
+                ),
+            ) = match encoding.class {
+                Class::Elf32 => context(
+                    "Parse Program Header",
+                    map(
+                        tuple((
+                            encoding.u32(),
+                            word(),
+                            word(),
+                            word(),
+                            word(),
+                            word(),
+                            encoding.u32(),
+                            word(),
+                        )),
+                        |(typ, offset, vaddr, paddr, file_size, memory_size, flags, align)| {
+                            (
+                                typ,
+                                flags,
+                                offset,
+                                vaddr,
+                                paddr,
+                                file_size,
+                                memory_size,
+                                align,
+                            )
+                        },
+                    ),
+                )(input)?,
+                Class::Elf64 => context(
+                    "Parse Program Header",
+                    tuple((
+                        encoding.u32(),
+                        encoding.u32(),
+                        word(),
+                        word(),
+                        word(),
+                        word(),
+                        word(),
+                        word(),
+                    )),
+                )(input)?,
+            };
+            Ok((
+                input,
+                Self {
+                    typ: ProgramType::from(typ),
+                    flags,
+                    offset,
+                    virtual_address,
+                    physical_address,
+                    file_size,
+                    memory_size,
+                    align,
+                },
+            ))
+        }
+    }
+
+    /// Whether `address` is in the part of this segment that is backed by the file
+    pub fn contains_file_address(&self, address: u64) -> bool {
+        address >= self.virtual_address && address - self.virtual_address < self.file_size
+    }
+}
+
+impl fmt::Display for ProgramHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = |bit: u32, c: char| if self.flags & bit != 0 { c } else { '-' };
+        writeln!(f, "Type: {:?}", self.typ)?;
+        writeln!(f, "Flags: {}{}{}", flag(4, 'r'), flag(2, 'w'), flag(1, 'x'))?;
+        writeln!(f, "Offset: {:x}", self.offset)?;
+        writeln!(f, "Virtual Address: {:x}", self.virtual_address)?;
+        writeln!(f, "Physical Address: {:x}", self.physical_address)?;
+        writeln!(f, "File Size: {} bytes", self.file_size)?;
+        writeln!(f, "Memory Size: {} bytes", self.memory_size)?;
+        writeln!(f, "Align: {}", self.align)
+    }
+}
@@ -0,0 +1,523 @@
+pub mod kinds;
+
+use std::fmt;
+
+use kinds::RelocationType;
+use nom::bytes::complete::tag;
+use nom::error::context;
+use nom::sequence::tuple;
+
+use super::dynamic::DynamicTag;
+use super::sections::SectionType;
+use super::symbols::{SectionIndex, Symbol, SymbolType};
+use super::{Class, Elf, Encoding, Machine};
+use crate::error::BinDumpResult;
+use crate::parse;
+
+/// How the entries of a relocation table are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationFormat {
+    /// `Elf_Rel` entries, with the addend stored at the relocated location
+    Rel,
+    /// `Elf_Rela` entries, with an explicit addend
+    Rela,
+    /// A bitmap of relative relocations
+    Relr,
+    /// Android's packed `APS2` encoding of `Elf_Rel` entries
+    AndroidRel,
+    /// Android's packed `APS2` encoding of `Elf_Rela` entries
+    AndroidRela,
+}
+
+impl RelocationFormat {
+    fn has_addends(self) -> bool {
+        matches!(self, RelocationFormat::Rela | RelocationFormat::AndroidRela)
+    }
+}
+
+#[derive(Debug)]
+pub struct Relocation {
+    pub offset: u64,
+    pub info: u64,
+    pub symbol_index: u32,
+    /// The versioned name of the symbol, if the relocation has one
+    pub symbol: Option<String>,
+    pub addend: Option<i64>,
+    pub typ: RelocationType,
+    /// The second and third types of a MIPS64 relocation, which are applied to the result of the
+    /// first
+    pub extra_types: Vec<RelocationType>,
+}
+
+impl fmt::Display for Relocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:016x} {:016x} {:<24}",
+            self.offset, self.info, self.typ
+        )?;
+        match (&self.symbol, self.addend) {
+            (Some(symbol), Some(addend)) if addend < 0 => {
+                write!(f, " {} - {:x}", symbol, addend.unsigned_abs())?
+            }
+            (Some(symbol), Some(addend)) => write!(f, " {} + {:x}", symbol, addend)?,
+            (Some(symbol), None) => write!(f, " {}", symbol)?,
+            (None, Some(addend)) => write!(f, " {:x}", addend)?,
+            (None, None) => {}
+        }
+        for typ in &self.extra_types {
+            write!(f, "\n{:>34}{}", "Type: ", typ)?;
+        }
+        Ok(())
+    }
+}
+
+/// The relocations from a relocation section or from one of the tables in the dynamic section
+#[derive(Debug)]
+pub struct RelocationTable {
+    /// The section name, or the dynamic tag for tables found without section headers
+    pub name: String,
+    pub format: RelocationFormat,
+    pub offset: u64,
+    pub relocations: Vec<Relocation>,
+}
+
+impl fmt::Display for RelocationTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Relocation Table '{}' at offset {:#x} contains {} entries ({:?}):",
+            self.name,
+            self.offset,
+            self.relocations.len(),
+            self.format
+        )?;
+        for relocation in &self.relocations {
+            writeln!(f, "{}", relocation)?;
+        }
+        Ok(())
+    }
+}
+
+/// The dynamic tags that locate relocation tables, with the tag for their size
+const DYNAMIC_TABLES: [(DynamicTag, DynamicTag, RelocationFormat); 5] = [
+    (DynamicTag::Rel, DynamicTag::RelSize, RelocationFormat::Rel),
+    (
+        DynamicTag::Rela,
+        DynamicTag::RelaSize,
+        RelocationFormat::Rela,
+    ),
+    (
+        DynamicTag::Relr,
+        DynamicTag::RelrSize,
+        RelocationFormat::Relr,
+    ),
+    (
+        DynamicTag::AndroidRel,
+        DynamicTag::AndroidRelSize,
+        RelocationFormat::AndroidRel,
+    ),
+    (
+        DynamicTag::AndroidRela,
+        DynamicTag::AndroidRelaSize,
+        RelocationFormat::AndroidRela,
+    ),
+];
+
+/// `DT_PLTREL` value for PLT relocations with addends
+const DT_RELA: u64 = 7;
+
+// Group flags of the Android packed relocation format
+const RELOCATION_GROUPED_BY_INFO_FLAG: i64 = 1;
+const RELOCATION_GROUPED_BY_OFFSET_DELTA_FLAG: i64 = 2;
+const RELOCATION_GROUPED_BY_ADDEND_FLAG: i64 = 4;
+const RELOCATION_GROUP_HAS_ADDEND_FLAG: i64 = 8;
+
+impl Elf {
+    /// Parses the relocation sections, and the tables referenced by the dynamic section that no
+    /// section covers, so that relocations are found even without section headers
+    pub(super) fn parse_relocations(&self) -> BinDumpResult<Vec<RelocationTable>> {
+        let mut tables = Vec::new();
+        for section in &self.sections {
+            let format = match section.typ {
+                SectionType::Rel => RelocationFormat::Rel,
+                SectionType::Rela => RelocationFormat::Rela,
+                SectionType::Relr | SectionType::AndroidRelr => RelocationFormat::Relr,
+                SectionType::AndroidRel => RelocationFormat::AndroidRel,
+                SectionType::AndroidRela => RelocationFormat::AndroidRela,
+                _ => continue,
+            };
+            let symbols = match self.sections.get(section.link as usize) {
+                Some(linked) if linked.typ == SectionType::SymbolTable => &self.symbols[..],
+                Some(linked) if linked.typ == SectionType::DynamicSymbolTable => {
+                    &self.dynamic_symbols[..]
+                }
+                _ => &[],
+            };
+            tables.push(RelocationTable {
+                name: section.name.clone(),
+                format,
+                offset: section.offset,
+                relocations: self.parse_relocation_table(
+                    self.section_data(section),
+                    format,
+                    symbols,
+                )?,
+            });
+        }
+
+        let mut dynamic_tables = DYNAMIC_TABLES.to_vec();
+        let plt_format = match self.dynamic_value(DynamicTag::PltRel) {
+            Some(DT_RELA) => RelocationFormat::Rela,
+            _ => RelocationFormat::Rel,
+        };
+        dynamic_tables.push((DynamicTag::JumpRel, DynamicTag::PltRelSize, plt_format));
+        for (address_tag, size_tag, format) in dynamic_tables {
+            let (Some(address), Some(size)) = (
+                self.dynamic_value(address_tag),
+                self.dynamic_value(size_tag),
+            ) else {
+                continue;
+            };
+            let Some(offset) = self.virtual_address_to_offset(address) else {
+                continue;
+            };
+            if tables.iter().any(|table| table.offset == offset) {
+                continue;
+            }
+            let start = (offset as usize).min(self.data.len());
+            let end = offset.saturating_add(size).min(self.data.len() as u64) as usize;
+            tables.push(RelocationTable {
+                name: format!("DT_{:?}", address_tag),
+                format,
+                offset,
+                relocations: self.parse_relocation_table(
+                    &self.data[start..end],
+                    format,
+                    &self.dynamic_symbols,
+                )?,
+            });
+        }
+        Ok(tables)
+    }
+
+    fn parse_relocation_table(
+        &self,
+        data: &[u8],
+        format: RelocationFormat,
+        symbols: &[Symbol],
+    ) -> BinDumpResult<Vec<Relocation>> {
+        let encoding = self.header.encoding;
+        match format {
+            RelocationFormat::Rel | RelocationFormat::Rela => {
+                let entry_size = encoding.word_size() * if format.has_addends() { 3 } else { 2 };
+                data.chunks_exact(entry_size as usize)
+                    .map(|entry| {
+                        let (entry, (offset, info)) = context(
+                            "Parse Relocation",
+                            tuple((encoding.word(), encoding.word())),
+                        )(entry)?;
+                        let addend = if format.has_addends() {
+                            Some(encoding.sword()(entry)?.1)
+                        } else {
+                            None
+                        };
+                        Ok(self.relocation(offset, info, addend, symbols))
+                    })
+                    .collect()
+            }
+            RelocationFormat::Relr => self.parse_relr(data),
+            RelocationFormat::AndroidRel | RelocationFormat::AndroidRela => {
+                // Each relocation patches a different pointer, and those are all in the file
+                let max_count = self.data.len() as u64 / encoding.word_size();
+                let (_, entries) = parse_android_packed(data, format.has_addends(), max_count)?;
+                Ok(entries
+                    .into_iter()
+                    .map(|(offset, info, addend)| self.relocation(offset, info, addend, symbols))
+                    .collect())
+            }
+        }
+    }
+
+    /// Expands a RELR table, where an even entry is the address of a relative relocation and
+    /// an odd entry is a bitmap of the words that follow the last address
+    fn parse_relr(&self, data: &[u8]) -> BinDumpResult<Vec<Relocation>> {
+        let encoding = self.header.encoding;
+        let word_size = encoding.word_size();
+        let bits = word_size * 8;
+        let relative = kinds::relative_type(self.header.machine);
+        let typ = RelocationType::new(self.header.machine, relative);
+        let mut relocations = Vec::new();
+        let mut next = 0u64;
+        for entry in data.chunks_exact(word_size as usize) {
+            let (_, entry) = context("Parse RELR Entry", encoding.word())(entry)?;
+            let mut push = |offset| {
+                relocations.push(Relocation {
+                    offset,
+                    info: relative as u64,
+                    symbol_index: 0,
+                    symbol: None,
+                    addend: None,
+                    typ,
+                    extra_types: Vec::new(),
+                })
+            };
+            if entry & 1 == 0 {
+                push(entry);
+                next = entry.wrapping_add(word_size);
+            } else {
+                for bit in 1..bits {
+                    if (entry >> bit) & 1 != 0 {
+                        push(next.wrapping_add((bit - 1) * word_size));
+                    }
+                }
+                next = next.wrapping_add((bits - 1) * word_size);
+            }
+        }
+        Ok(relocations)
+    }
+
+    /// Splits `r_info` into its symbol and types and resolves the symbol name
+    fn relocation(
+        &self,
+        offset: u64,
+        info: u64,
+        addend: Option<i64>,
+        symbols: &[Symbol],
+    ) -> Relocation {
+        let Encoding { class, endianness } = self.header.encoding;
+        let machine = self.header.machine;
+        let (symbol_index, typ, extra_types) = match class {
+            // MIPS64 packs three types and a special symbol after a 32 bit symbol index, stored
+            // byte by byte rather than as one number
+            Class::Elf64 if machine == Machine::Mips => {
+                let (symbol, types) = match endianness {
+                    nom::number::Endianness::Big => (
+                        (info >> 32) as u32,
+                        [info & 0xff, (info >> 8) & 0xff, (info >> 16) & 0xff],
+                    ),
+                    _ => (
+                        info as u32,
+                        [info >> 56, (info >> 48) & 0xff, (info >> 40) & 0xff],
+                    ),
+                };
+                let extra_types = types[1..]
+                    .iter()
+                    .map(|&typ| RelocationType::new(machine, typ as u32))
+                    .filter(|typ| !typ.is_none())
+                    .collect();
+                (
+                    symbol,
+                    RelocationType::new(machine, types[0] as u32),
+                    extra_types,
+                )
+            }
+            Class::Elf64 => (
+                (info >> 32) as u32,
+                RelocationType::new(machine, info as u32),
+                Vec::new(),
+            ),
+            Class::Elf32 => (
+                (info >> 8) as u32,
+                RelocationType::new(machine, (info & 0xff) as u32),
+                Vec::new(),
+            ),
+        };
+        let symbol = match symbol_index {
+            0 => None,
+            index => symbols.get(index as usize).map(|symbol| {
+                // Section symbols are unnamed, and stand for the section they refer to
+                match (symbol.typ, symbol.section_index) {
+                    (SymbolType::Section, SectionIndex::Index(section))
+                        if symbol.name.is_empty() =>
+                    {
+                        self.sections
+                            .get(section as usize)
+                            .map(|section| section.name.clone())
+                            .unwrap_or_default()
+                    }
+                    _ => symbol.versioned_name(),
+                }
+            }),
+        };
+        Relocation {
+            offset,
+            info,
+            symbol_index,
+            symbol,
+            addend,
+            typ,
+            extra_types,
+        }
+    }
+
+    /// The relocation sections and dynamic relocation tables
+    pub fn relocation_tables(&self) -> &[RelocationTable] {
+        &self.relocations
+    }
+}
+
+/// Decodes Android's `APS2` packed relocations into offset, info and addend triples
+#[allow(clippy::type_complexity)]
+fn parse_android_packed(
+    input: parse::Input,
+    has_addends: bool,
+    max_count: u64,
+) -> parse::ParseResult<Vec<(u64, u64, Option<i64>)>> {
+    let (mut input, (_, count, mut offset)) = context(
+        "Parse Android Packed Relocations",
+        tuple((tag(b"APS2"), parse::sleb128, parse::sleb128)),
+    )(input)?;
+    // Grouped relocations don't use any input, so the count is all that bounds the table
+    if count as u64 > max_count {
+        return Err(parse::failure(input, "Too Many Android Packed Relocations"));
+    }
+    let mut info = 0i64;
+    let mut addend = 0i64;
+    let mut relocations = Vec::new();
+    while (relocations.len() as i64) < count {
+        let (rest, (group_size, flags)) = tuple((parse::sleb128, parse::sleb128))(input)?;
+        if group_size <= 0 || group_size > count - relocations.len() as i64 {
+            return Err(parse::failure(
+                input,
+                "Invalid Android Packed Relocation Group Size",
+            ));
+        }
+        input = rest;
+        let grouped_by_info = flags & RELOCATION_GROUPED_BY_INFO_FLAG != 0;
+        let grouped_by_offset_delta = flags & RELOCATION_GROUPED_BY_OFFSET_DELTA_FLAG != 0;
+        let grouped_by_addend = flags & RELOCATION_GROUPED_BY_ADDEND_FLAG != 0;
+        let group_has_addend = flags & RELOCATION_GROUP_HAS_ADDEND_FLAG != 0;
+
+        let mut offset_delta = 0;
+        if grouped_by_offset_delta {
+            (input, offset_delta) = parse::sleb128(input)?;
+        }
+        if grouped_by_info {
+            (input, info) = parse::sleb128(input)?;
+        }
+        if group_has_addend && grouped_by_addend {
+            let (rest, delta) = parse::sleb128(input)?;
+            input = rest;
+            addend = addend.wrapping_add(delta);
+        } else if !group_has_addend {
+            addend = 0;
+        }
+        for _ in 0..group_size {
+            if grouped_by_offset_delta {
+                offset = offset.wrapping_add(offset_delta);
+            } else {
+                let (rest, delta) = parse::sleb128(input)?;
+                input = rest;
+                offset = offset.wrapping_add(delta);
+            }
+            if !grouped_by_info {
+                (input, info) = parse::sleb128(input)?;
+            }
+            if group_has_addend && !grouped_by_addend {
+                let (rest, delta) = parse::sleb128(input)?;
+                input = rest;
+                addend = addend.wrapping_add(delta);
+            }
+            relocations.push((offset as u64, info as u64, has_addends.then_some(addend)));
+        }
+    }
+    Ok((input, relocations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kinds::{MipsRelocation, X86_64Relocation};
+
+    /// An ELF64 file that is only a header, for `machine` and the given byte order
+    fn header_only(machine: u16, endianness: nom::number::Endianness) -> Elf {
+        let big = endianness == nom::number::Endianness::Big;
+        let mut data = vec![0x7f, b'E', b'L', b'F', 2, if big { 2 } else { 1 }, 1];
+        data.resize(16, 0);
+        let mut push = |bytes: &[u8]| match big {
+            true => data.extend(bytes.iter().rev()),
+            false => data.extend_from_slice(bytes),
+        };
+        push(&1u16.to_le_bytes());
+        push(&machine.to_le_bytes());
+        push(&1u32.to_le_bytes());
+        for _ in 0..3 {
+            push(&0u64.to_le_bytes());
+        }
+        push(&0u32.to_le_bytes());
+        for field in [64u16, 0, 0, 0, 0, 0] {
+            push(&field.to_le_bytes());
+        }
+        Elf::load(data).unwrap()
+    }
+
+    #[test]
+    fn expands_relr_tables() {
+        let elf = header_only(62, nom::number::Endianness::Little);
+        let mut data = Vec::new();
+        // An address, then bitmaps of the words after it
+        for entry in [0x1000u64, 0b1011, 1 << 63 | 1] {
+            data.extend_from_slice(&entry.to_le_bytes());
+        }
+        let relocations = elf.parse_relr(&data).unwrap();
+        let offsets: Vec<_> = relocations
+            .iter()
+            .map(|relocation| relocation.offset)
+            .collect();
+        assert_eq!(offsets, [0x1000, 0x1008, 0x1018, 0x13f0]);
+        assert!(relocations.iter().all(|relocation| relocation.typ
+            == RelocationType::X86_64(X86_64Relocation::R_X86_64_RELATIVE)));
+    }
+
+    #[test]
+    fn splits_mips64_info() {
+        for (endianness, info) in [
+            (nom::number::Endianness::Little, 18 << 56 | 24 << 48 | 5),
+            (nom::number::Endianness::Big, 5 << 32 | 24 << 8 | 18),
+        ] {
+            let elf = header_only(8, endianness);
+            let relocation = elf.relocation(0, info, None, &[]);
+            assert_eq!(relocation.symbol_index, 5);
+            assert_eq!(
+                relocation.typ,
+                RelocationType::Mips(MipsRelocation::R_MIPS_64)
+            );
+            assert_eq!(
+                relocation.extra_types,
+                [RelocationType::Mips(MipsRelocation::R_MIPS_SUB)]
+            );
+        }
+    }
+
+    #[test]
+    fn decodes_android_packed_relocations() {
+        // Three relocations from 0x10, grouped by an offset delta of 8 and an info of 8, with
+        // their own addends
+        let data = b"APS2\x03\x10\x03\x0b\x08\x08\x04\x7c\x00";
+        let (_, relocations) = parse_android_packed(data, true, 16).unwrap();
+        assert_eq!(
+            relocations,
+            [(0x18, 8, Some(4)), (0x20, 8, Some(0)), (0x28, 8, Some(0))]
+        );
+        let (_, relocations) =
+            parse_android_packed(b"APS2\x02\x10\x02\x03\x08\x08", false, 16).unwrap();
+        assert_eq!(relocations, [(0x18, 8, None), (0x20, 8, None)]);
+    }
+
+    #[test]
+    fn rejects_invalid_android_packed_group_sizes() {
+        // An empty group would never finish the table
+        assert!(parse_android_packed(b"APS2\x02\x10\x00\x03\x08\x08", false, 16).is_err());
+        assert!(parse_android_packed(b"APS2\x02\x10\x7f\x03\x08\x08", false, 16).is_err());
+        // More relocations than the table declares
+        assert!(parse_android_packed(b"APS2\x02\x10\x03\x03\x08\x08", false, 16).is_err());
+    }
+
+    #[test]
+    fn rejects_android_packed_counts_past_the_file() {
+        // 2^40 relocations in one group that is grouped by everything, so it reads no more input
+        let data = b"APS2\x80\x80\x80\x80\x80\x20\x10\x80\x80\x80\x80\x80\x20\x0f\x08\x08\x00";
+        assert!(parse_android_packed(data, true, 1 << 20).is_err());
+    }
+}
@@ -0,0 +1,627 @@
+//! The relocation types of each architecture, named as in their psABI documents
+
+use std::fmt;
+
+use crate::binary::elf::Machine;
+
+/// Declares an enum of relocation types, with conversions from the raw type and a `Display` that
+/// prints the `R_*` name
+macro_rules! relocation_kinds {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident = $value:literal,)* }) => {
+        $(#[$meta])*
+        #[allow(non_camel_case_types)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($variant,)*
+            Other(u32),
+        }
+
+        impl From<u32> for $name {
+            fn from(typ: u32) -> Self {
+                match typ {
+                    $($value => $name::$variant,)*
+                    typ => $name::Other(typ),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $($name::$variant => write!(f, stringify!($variant)),)*
+                    $name::Other(typ) => write!(f, "<unknown {}>", typ),
+                }
+            }
+        }
+    };
+}
+
+relocation_kinds!(X86_64Relocation {
+    R_X86_64_NONE = 0,
+    R_X86_64_64 = 1,
+    R_X86_64_PC32 = 2,
+    R_X86_64_GOT32 = 3,
+    R_X86_64_PLT32 = 4,
+    R_X86_64_COPY = 5,
+    R_X86_64_GLOB_DAT = 6,
+    R_X86_64_JUMP_SLOT = 7,
+    R_X86_64_RELATIVE = 8,
+    R_X86_64_GOTPCREL = 9,
+    R_X86_64_32 = 10,
+    R_X86_64_32S = 11,
+    R_X86_64_16 = 12,
+    R_X86_64_PC16 = 13,
+    R_X86_64_8 = 14,
+    R_X86_64_PC8 = 15,
+    R_X86_64_DTPMOD64 = 16,
+    R_X86_64_DTPOFF64 = 17,
+    R_X86_64_TPOFF64 = 18,
+    R_X86_64_TLSGD = 19,
+    R_X86_64_TLSLD = 20,
+    R_X86_64_DTPOFF32 = 21,
+    R_X86_64_GOTTPOFF = 22,
+    R_X86_64_TPOFF32 = 23,
+    R_X86_64_PC64 = 24,
+    R_X86_64_GOTOFF64 = 25,
+    R_X86_64_GOTPC32 = 26,
+    R_X86_64_GOT64 = 27,
+    R_X86_64_GOTPCREL64 = 28,
+    R_X86_64_GOTPC64 = 29,
+    R_X86_64_GOTPLT64 = 30,
+    R_X86_64_PLTOFF64 = 31,
+    R_X86_64_SIZE32 = 32,
+    R_X86_64_SIZE64 = 33,
+    R_X86_64_GOTPC32_TLSDESC = 34,
+    R_X86_64_TLSDESC_CALL = 35,
+    R_X86_64_TLSDESC = 36,
+    R_X86_64_IRELATIVE = 37,
+    R_X86_64_RELATIVE64 = 38,
+    R_X86_64_GOTPCRELX = 41,
+    R_X86_64_REX_GOTPCRELX = 42,
+});
+
+relocation_kinds!(I386Relocation {
+    R_386_NONE = 0,
+    R_386_32 = 1,
+    R_386_PC32 = 2,
+    R_386_GOT32 = 3,
+    R_386_PLT32 = 4,
+    R_386_COPY = 5,
+    R_386_GLOB_DAT = 6,
+    R_386_JMP_SLOT = 7,
+    R_386_RELATIVE = 8,
+    R_386_GOTOFF = 9,
+    R_386_GOTPC = 10,
+    R_386_32PLT = 11,
+    R_386_TLS_TPOFF = 14,
+    R_386_TLS_IE = 15,
+    R_386_TLS_GOTIE = 16,
+    R_386_TLS_LE = 17,
+    R_386_TLS_GD = 18,
+    R_386_TLS_LDM = 19,
+    R_386_16 = 20,
+    R_386_PC16 = 21,
+    R_386_8 = 22,
+    R_386_PC8 = 23,
+    R_386_TLS_GD_32 = 24,
+    R_386_TLS_GD_PUSH = 25,
+    R_386_TLS_GD_CALL = 26,
+    R_386_TLS_GD_POP = 27,
+    R_386_TLS_LDM_32 = 28,
+    R_386_TLS_LDM_PUSH = 29,
+    R_386_TLS_LDM_CALL = 30,
+    R_386_TLS_LDM_POP = 31,
+    R_386_TLS_LDO_32 = 32,
+    R_386_TLS_IE_32 = 33,
+    R_386_TLS_LE_32 = 34,
+    R_386_TLS_DTPMOD32 = 35,
+    R_386_TLS_DTPOFF32 = 36,
+    R_386_TLS_TPOFF32 = 37,
+    R_386_SIZE32 = 38,
+    R_386_TLS_GOTDESC = 39,
+    R_386_TLS_DESC_CALL = 40,
+    R_386_TLS_DESC = 41,
+    R_386_IRELATIVE = 42,
+    R_386_GOT32X = 43,
+});
+
+relocation_kinds!(AArch64Relocation {
+    R_AARCH64_NONE = 0,
+    R_AARCH64_ABS64 = 257,
+    R_AARCH64_ABS32 = 258,
+    R_AARCH64_ABS16 = 259,
+    R_AARCH64_PREL64 = 260,
+    R_AARCH64_PREL32 = 261,
+    R_AARCH64_PREL16 = 262,
+    R_AARCH64_MOVW_UABS_G0 = 263,
+    R_AARCH64_MOVW_UABS_G0_NC = 264,
+    R_AARCH64_MOVW_UABS_G1 = 265,
+    R_AARCH64_MOVW_UABS_G1_NC = 266,
+    R_AARCH64_MOVW_UABS_G2 = 267,
+    R_AARCH64_MOVW_UABS_G2_NC = 268,
+    R_AARCH64_MOVW_UABS_G3 = 269,
+    R_AARCH64_MOVW_SABS_G0 = 270,
+    R_AARCH64_MOVW_SABS_G1 = 271,
+    R_AARCH64_MOVW_SABS_G2 = 272,
+    R_AARCH64_LD_PREL_LO19 = 273,
+    R_AARCH64_ADR_PREL_LO21 = 274,
+    R_AARCH64_ADR_PREL_PG_HI21 = 275,
+    R_AARCH64_ADR_PREL_PG_HI21_NC = 276,
+    R_AARCH64_ADD_ABS_LO12_NC = 277,
+    R_AARCH64_LDST8_ABS_LO12_NC = 278,
+    R_AARCH64_TSTBR14 = 279,
+    R_AARCH64_CONDBR19 = 280,
+    R_AARCH64_JUMP26 = 282,
+    R_AARCH64_CALL26 = 283,
+    R_AARCH64_LDST16_ABS_LO12_NC = 284,
+    R_AARCH64_LDST32_ABS_LO12_NC = 285,
+    R_AARCH64_LDST64_ABS_LO12_NC = 286,
+    R_AARCH64_MOVW_PREL_G0 = 287,
+    R_AARCH64_MOVW_PREL_G0_NC = 288,
+    R_AARCH64_MOVW_PREL_G1 = 289,
+    R_AARCH64_MOVW_PREL_G1_NC = 290,
+    R_AARCH64_MOVW_PREL_G2 = 291,
+    R_AARCH64_MOVW_PREL_G2_NC = 292,
+    R_AARCH64_MOVW_PREL_G3 = 293,
+    R_AARCH64_LDST128_ABS_LO12_NC = 299,
+    R_AARCH64_ADR_GOT_PAGE = 311,
+    R_AARCH64_LD64_GOT_LO12_NC = 312,
+    R_AARCH64_LD64_GOTPAGE_LO15 = 313,
+    R_AARCH64_TLSGD_ADR_PREL21 = 512,
+    R_AARCH64_TLSGD_ADR_PAGE21 = 513,
+    R_AARCH64_TLSGD_ADD_LO12_NC = 514,
+    R_AARCH64_TLSLD_ADR_PAGE21 = 518,
+    R_AARCH64_TLSLD_ADD_LO12_NC = 519,
+    R_AARCH64_TLSIE_MOVW_GOTTPREL_G1 = 539,
+    R_AARCH64_TLSIE_MOVW_GOTTPREL_G0_NC = 540,
+    R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21 = 541,
+    R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC = 542,
+    R_AARCH64_TLSIE_LD_GOTTPREL_PREL19 = 543,
+    R_AARCH64_TLSLE_MOVW_TPREL_G2 = 544,
+    R_AARCH64_TLSLE_MOVW_TPREL_G1 = 545,
+    R_AARCH64_TLSLE_MOVW_TPREL_G1_NC = 546,
+    R_AARCH64_TLSLE_MOVW_TPREL_G0 = 547,
+    R_AARCH64_TLSLE_MOVW_TPREL_G0_NC = 548,
+    R_AARCH64_TLSLE_ADD_TPREL_HI12 = 549,
+    R_AARCH64_TLSLE_ADD_TPREL_LO12 = 550,
+    R_AARCH64_TLSLE_ADD_TPREL_LO12_NC = 551,
+    R_AARCH64_TLSDESC_LD_PREL19 = 560,
+    R_AARCH64_TLSDESC_ADR_PREL21 = 561,
+    R_AARCH64_TLSDESC_ADR_PAGE21 = 562,
+    R_AARCH64_TLSDESC_LD64_LO12 = 563,
+    R_AARCH64_TLSDESC_ADD_LO12 = 564,
+    R_AARCH64_TLSDESC_OFF_G1 = 565,
+    R_AARCH64_TLSDESC_OFF_G0_NC = 566,
+    R_AARCH64_TLSDESC_LDR = 567,
+    R_AARCH64_TLSDESC_ADD = 568,
+    R_AARCH64_TLSDESC_CALL = 569,
+    R_AARCH64_COPY = 1024,
+    R_AARCH64_GLOB_DAT = 1025,
+    R_AARCH64_JUMP_SLOT = 1026,
+    R_AARCH64_RELATIVE = 1027,
+    R_AARCH64_TLS_DTPMOD = 1028,
+    R_AARCH64_TLS_DTPREL = 1029,
+    R_AARCH64_TLS_TPREL = 1030,
+    R_AARCH64_TLSDESC = 1031,
+    R_AARCH64_IRELATIVE = 1032,
+});
+
+relocation_kinds!(ArmRelocation {
+    R_ARM_NONE = 0,
+    R_ARM_PC24 = 1,
+    R_ARM_ABS32 = 2,
+    R_ARM_REL32 = 3,
+    R_ARM_LDR_PC_G0 = 4,
+    R_ARM_ABS16 = 5,
+    R_ARM_ABS12 = 6,
+    R_ARM_THM_ABS5 = 7,
+    R_ARM_ABS8 = 8,
+    R_ARM_SBREL32 = 9,
+    R_ARM_THM_CALL = 10,
+    R_ARM_THM_PC8 = 11,
+    R_ARM_BREL_ADJ = 12,
+    R_ARM_TLS_DESC = 13,
+    R_ARM_TLS_DTPMOD32 = 17,
+    R_ARM_TLS_DTPOFF32 = 18,
+    R_ARM_TLS_TPOFF32 = 19,
+    R_ARM_COPY = 20,
+    R_ARM_GLOB_DAT = 21,
+    R_ARM_JUMP_SLOT = 22,
+    R_ARM_RELATIVE = 23,
+    R_ARM_GOTOFF32 = 24,
+    R_ARM_BASE_PREL = 25,
+    R_ARM_GOT_BREL = 26,
+    R_ARM_PLT32 = 27,
+    R_ARM_CALL = 28,
+    R_ARM_JUMP24 = 29,
+    R_ARM_THM_JUMP24 = 30,
+    R_ARM_BASE_ABS = 31,
+    R_ARM_TARGET1 = 38,
+    R_ARM_V4BX = 40,
+    R_ARM_TARGET2 = 41,
+    R_ARM_PREL31 = 42,
+    R_ARM_MOVW_ABS_NC = 43,
+    R_ARM_MOVT_ABS = 44,
+    R_ARM_MOVW_PREL_NC = 45,
+    R_ARM_MOVT_PREL = 46,
+    R_ARM_THM_MOVW_ABS_NC = 47,
+    R_ARM_THM_MOVT_ABS = 48,
+    R_ARM_THM_MOVW_PREL_NC = 49,
+    R_ARM_THM_MOVT_PREL = 50,
+    R_ARM_THM_JUMP19 = 51,
+    R_ARM_GOT_ABS = 95,
+    R_ARM_GOT_PREL = 96,
+    R_ARM_THM_JUMP11 = 102,
+    R_ARM_THM_JUMP8 = 103,
+    R_ARM_TLS_GD32 = 104,
+    R_ARM_TLS_LDM32 = 105,
+    R_ARM_TLS_LDO32 = 106,
+    R_ARM_TLS_IE32 = 107,
+    R_ARM_TLS_LE32 = 108,
+    R_ARM_IRELATIVE = 160,
+});
+
+relocation_kinds!(RiscVRelocation {
+    R_RISCV_NONE = 0,
+    R_RISCV_32 = 1,
+    R_RISCV_64 = 2,
+    R_RISCV_RELATIVE = 3,
+    R_RISCV_COPY = 4,
+    R_RISCV_JUMP_SLOT = 5,
+    R_RISCV_TLS_DTPMOD32 = 6,
+    R_RISCV_TLS_DTPMOD64 = 7,
+    R_RISCV_TLS_DTPREL32 = 8,
+    R_RISCV_TLS_DTPREL64 = 9,
+    R_RISCV_TLS_TPREL32 = 10,
+    R_RISCV_TLS_TPREL64 = 11,
+    R_RISCV_TLSDESC = 12,
+    R_RISCV_BRANCH = 16,
+    R_RISCV_JAL = 17,
+    R_RISCV_CALL = 18,
+    R_RISCV_CALL_PLT = 19,
+    R_RISCV_GOT_HI20 = 20,
+    R_RISCV_TLS_GOT_HI20 = 21,
+    R_RISCV_TLS_GD_HI20 = 22,
+    R_RISCV_PCREL_HI20 = 23,
+    R_RISCV_PCREL_LO12_I = 24,
+    R_RISCV_PCREL_LO12_S = 25,
+    R_RISCV_HI20 = 26,
+    R_RISCV_LO12_I = 27,
+    R_RISCV_LO12_S = 28,
+    R_RISCV_TPREL_HI20 = 29,
+    R_RISCV_TPREL_LO12_I = 30,
+    R_RISCV_TPREL_LO12_S = 31,
+    R_RISCV_TPREL_ADD = 32,
+    R_RISCV_ADD8 = 33,
+    R_RISCV_ADD16 = 34,
+    R_RISCV_ADD32 = 35,
+    R_RISCV_ADD64 = 36,
+    R_RISCV_SUB8 = 37,
+    R_RISCV_SUB16 = 38,
+    R_RISCV_SUB32 = 39,
+    R_RISCV_SUB64 = 40,
+    R_RISCV_GOT32_PCREL = 41,
+    R_RISCV_ALIGN = 43,
+    R_RISCV_RVC_BRANCH = 44,
+    R_RISCV_RVC_JUMP = 45,
+    R_RISCV_RELAX = 51,
+    R_RISCV_SUB6 = 52,
+    R_RISCV_SET6 = 53,
+    R_RISCV_SET8 = 54,
+    R_RISCV_SET16 = 55,
+    R_RISCV_SET32 = 56,
+    R_RISCV_32_PCREL = 57,
+    R_RISCV_IRELATIVE = 58,
+    R_RISCV_PLT32 = 59,
+    R_RISCV_SET_ULEB128 = 60,
+    R_RISCV_SUB_ULEB128 = 61,
+    R_RISCV_TLSDESC_HI20 = 62,
+    R_RISCV_TLSDESC_LOAD_LO12 = 63,
+    R_RISCV_TLSDESC_ADD_LO12 = 64,
+    R_RISCV_TLSDESC_CALL = 65,
+});
+
+relocation_kinds!(PowerPc64Relocation {
+    R_PPC64_NONE = 0,
+    R_PPC64_ADDR32 = 1,
+    R_PPC64_ADDR24 = 2,
+    R_PPC64_ADDR16 = 3,
+    R_PPC64_ADDR16_LO = 4,
+    R_PPC64_ADDR16_HI = 5,
+    R_PPC64_ADDR16_HA = 6,
+    R_PPC64_ADDR14 = 7,
+    R_PPC64_ADDR14_BRTAKEN = 8,
+    R_PPC64_ADDR14_BRNTAKEN = 9,
+    R_PPC64_REL24 = 10,
+    R_PPC64_REL14 = 11,
+    R_PPC64_REL14_BRTAKEN = 12,
+    R_PPC64_REL14_BRNTAKEN = 13,
+    R_PPC64_GOT16 = 14,
+    R_PPC64_GOT16_LO = 15,
+    R_PPC64_GOT16_HI = 16,
+    R_PPC64_GOT16_HA = 17,
+    R_PPC64_COPY = 19,
+    R_PPC64_GLOB_DAT = 20,
+    R_PPC64_JMP_SLOT = 21,
+    R_PPC64_RELATIVE = 22,
+    R_PPC64_UADDR32 = 24,
+    R_PPC64_UADDR16 = 25,
+    R_PPC64_REL32 = 26,
+    R_PPC64_PLT32 = 27,
+    R_PPC64_PLTREL32 = 28,
+    R_PPC64_PLT16_LO = 29,
+    R_PPC64_PLT16_HI = 30,
+    R_PPC64_PLT16_HA = 31,
+    R_PPC64_SECTOFF = 33,
+    R_PPC64_SECTOFF_LO = 34,
+    R_PPC64_SECTOFF_HI = 35,
+    R_PPC64_SECTOFF_HA = 36,
+    R_PPC64_ADDR30 = 37,
+    R_PPC64_ADDR64 = 38,
+    R_PPC64_ADDR16_HIGHER = 39,
+    R_PPC64_ADDR16_HIGHERA = 40,
+    R_PPC64_ADDR16_HIGHEST = 41,
+    R_PPC64_ADDR16_HIGHESTA = 42,
+    R_PPC64_UADDR64 = 43,
+    R_PPC64_REL64 = 44,
+    R_PPC64_PLT64 = 45,
+    R_PPC64_PLTREL64 = 46,
+    R_PPC64_TOC16 = 47,
+    R_PPC64_TOC16_LO = 48,
+    R_PPC64_TOC16_HI = 49,
+    R_PPC64_TOC16_HA = 50,
+    R_PPC64_TOC = 51,
+    R_PPC64_PLTGOT16 = 52,
+    R_PPC64_PLTGOT16_LO = 53,
+    R_PPC64_PLTGOT16_HI = 54,
+    R_PPC64_PLTGOT16_HA = 55,
+    R_PPC64_ADDR16_DS = 56,
+    R_PPC64_ADDR16_LO_DS = 57,
+    R_PPC64_GOT16_DS = 58,
+    R_PPC64_GOT16_LO_DS = 59,
+    R_PPC64_PLT16_LO_DS = 60,
+    R_PPC64_SECTOFF_DS = 61,
+    R_PPC64_SECTOFF_LO_DS = 62,
+    R_PPC64_TOC16_DS = 63,
+    R_PPC64_TOC16_LO_DS = 64,
+    R_PPC64_PLTGOT16_DS = 65,
+    R_PPC64_PLTGOT16_LO_DS = 66,
+    R_PPC64_TLS = 67,
+    R_PPC64_DTPMOD64 = 68,
+    R_PPC64_TPREL16 = 69,
+    R_PPC64_TPREL16_LO = 70,
+    R_PPC64_TPREL16_HI = 71,
+    R_PPC64_TPREL16_HA = 72,
+    R_PPC64_TPREL64 = 73,
+    R_PPC64_DTPREL16 = 74,
+    R_PPC64_DTPREL16_LO = 75,
+    R_PPC64_DTPREL16_HI = 76,
+    R_PPC64_DTPREL16_HA = 77,
+    R_PPC64_DTPREL64 = 78,
+    R_PPC64_GOT_TLSGD16 = 79,
+    R_PPC64_GOT_TLSGD16_LO = 80,
+    R_PPC64_GOT_TLSGD16_HI = 81,
+    R_PPC64_GOT_TLSGD16_HA = 82,
+    R_PPC64_GOT_TLSLD16 = 83,
+    R_PPC64_GOT_TLSLD16_LO = 84,
+    R_PPC64_GOT_TLSLD16_HI = 85,
+    R_PPC64_GOT_TLSLD16_HA = 86,
+    R_PPC64_GOT_TPREL16_DS = 87,
+    R_PPC64_GOT_TPREL16_LO_DS = 88,
+    R_PPC64_GOT_TPREL16_HI = 89,
+    R_PPC64_GOT_TPREL16_HA = 90,
+    R_PPC64_GOT_DTPREL16_DS = 91,
+    R_PPC64_GOT_DTPREL16_LO_DS = 92,
+    R_PPC64_GOT_DTPREL16_HI = 93,
+    R_PPC64_GOT_DTPREL16_HA = 94,
+    R_PPC64_TPREL16_DS = 95,
+    R_PPC64_TPREL16_LO_DS = 96,
+    R_PPC64_TPREL16_HIGHER = 97,
+    R_PPC64_TPREL16_HIGHERA = 98,
+    R_PPC64_TPREL16_HIGHEST = 99,
+    R_PPC64_TPREL16_HIGHESTA = 100,
+    R_PPC64_DTPREL16_DS = 101,
+    R_PPC64_DTPREL16_LO_DS = 102,
+    R_PPC64_DTPREL16_HIGHER = 103,
+    R_PPC64_DTPREL16_HIGHERA = 104,
+    R_PPC64_DTPREL16_HIGHEST = 105,
+    R_PPC64_DTPREL16_HIGHESTA = 106,
+    R_PPC64_TLSGD = 107,
+    R_PPC64_TLSLD = 108,
+    R_PPC64_TOCSAVE = 109,
+    R_PPC64_ADDR16_HIGH = 110,
+    R_PPC64_ADDR16_HIGHA = 111,
+    R_PPC64_TPREL16_HIGH = 112,
+    R_PPC64_TPREL16_HIGHA = 113,
+    R_PPC64_DTPREL16_HIGH = 114,
+    R_PPC64_DTPREL16_HIGHA = 115,
+    R_PPC64_REL24_NOTOC = 116,
+    R_PPC64_ADDR64_LOCAL = 117,
+    R_PPC64_ENTRY = 118,
+    R_PPC64_PLTSEQ = 119,
+    R_PPC64_PLTCALL = 120,
+    R_PPC64_IRELATIVE = 248,
+    R_PPC64_REL16 = 249,
+    R_PPC64_REL16_LO = 250,
+    R_PPC64_REL16_HI = 251,
+    R_PPC64_REL16_HA = 252,
+});
+
+relocation_kinds!(MipsRelocation {
+    R_MIPS_NONE = 0,
+    R_MIPS_16 = 1,
+    R_MIPS_32 = 2,
+    R_MIPS_REL32 = 3,
+    R_MIPS_26 = 4,
+    R_MIPS_HI16 = 5,
+    R_MIPS_LO16 = 6,
+    R_MIPS_GPREL16 = 7,
+    R_MIPS_LITERAL = 8,
+    R_MIPS_GOT16 = 9,
+    R_MIPS_PC16 = 10,
+    R_MIPS_CALL16 = 11,
+    R_MIPS_GPREL32 = 12,
+    R_MIPS_SHIFT5 = 16,
+    R_MIPS_SHIFT6 = 17,
+    R_MIPS_64 = 18,
+    R_MIPS_GOT_DISP = 19,
+    R_MIPS_GOT_PAGE = 20,
+    R_MIPS_GOT_OFST = 21,
+    R_MIPS_GOT_HI16 = 22,
+    R_MIPS_GOT_LO16 = 23,
+    R_MIPS_SUB = 24,
+    R_MIPS_INSERT_A = 25,
+    R_MIPS_INSERT_B = 26,
+    R_MIPS_DELETE = 27,
+    R_MIPS_HIGHER = 28,
+    R_MIPS_HIGHEST = 29,
+    R_MIPS_CALL_HI16 = 30,
+    R_MIPS_CALL_LO16 = 31,
+    R_MIPS_SCN_DISP = 32,
+    R_MIPS_REL16 = 33,
+    R_MIPS_ADD_IMMEDIATE = 34,
+    R_MIPS_PJUMP = 35,
+    R_MIPS_RELGOT = 36,
+    R_MIPS_JALR = 37,
+    R_MIPS_TLS_DTPMOD32 = 38,
+    R_MIPS_TLS_DTPREL32 = 39,
+    R_MIPS_TLS_DTPMOD64 = 40,
+    R_MIPS_TLS_DTPREL64 = 41,
+    R_MIPS_TLS_GD = 42,
+    R_MIPS_TLS_LDM = 43,
+    R_MIPS_TLS_DTPREL_HI16 = 44,
+    R_MIPS_TLS_DTPREL_LO16 = 45,
+    R_MIPS_TLS_GOTTPREL = 46,
+    R_MIPS_TLS_TPREL32 = 47,
+    R_MIPS_TLS_TPREL64 = 48,
+    R_MIPS_TLS_TPREL_HI16 = 49,
+    R_MIPS_TLS_TPREL_LO16 = 50,
+    R_MIPS_GLOB_DAT = 51,
+    R_MIPS_PC21_S2 = 60,
+    R_MIPS_PC26_S2 = 61,
+    R_MIPS_PC18_S3 = 62,
+    R_MIPS_PC19_S2 = 63,
+    R_MIPS_PCHI16 = 64,
+    R_MIPS_PCLO16 = 65,
+    R_MIPS_COPY = 126,
+    R_MIPS_JUMP_SLOT = 127,
+});
+
+/// The type of a relocation, interpreted for the architecture of the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationType {
+    X86_64(X86_64Relocation),
+    I386(I386Relocation),
+    AArch64(AArch64Relocation),
+    Arm(ArmRelocation),
+    RiscV(RiscVRelocation),
+    PowerPc64(PowerPc64Relocation),
+    Mips(MipsRelocation),
+    /// A relocation for an architecture whose types aren't known
+    Unknown(u32),
+}
+
+impl RelocationType {
+    pub fn new(machine: Machine, typ: u32) -> Self {
+        match machine {
+            Machine::X86_64 => RelocationType::X86_64(typ.into()),
+            Machine::I386 => RelocationType::I386(typ.into()),
+            Machine::AArch64 => RelocationType::AArch64(typ.into()),
+            Machine::Arm => RelocationType::Arm(typ.into()),
+            Machine::RiscV => RelocationType::RiscV(typ.into()),
+            Machine::PowerPc64 => RelocationType::PowerPc64(typ.into()),
+            Machine::Mips => RelocationType::Mips(typ.into()),
+            _ => RelocationType::Unknown(typ),
+        }
+    }
+
+    /// Whether this is the architecture's "no relocation" type
+    pub fn is_none(&self) -> bool {
+        match self {
+            RelocationType::X86_64(typ) => *typ == X86_64Relocation::R_X86_64_NONE,
+            RelocationType::I386(typ) => *typ == I386Relocation::R_386_NONE,
+            RelocationType::AArch64(typ) => *typ == AArch64Relocation::R_AARCH64_NONE,
+            RelocationType::Arm(typ) => *typ == ArmRelocation::R_ARM_NONE,
+            RelocationType::RiscV(typ) => *typ == RiscVRelocation::R_RISCV_NONE,
+            RelocationType::PowerPc64(typ) => *typ == PowerPc64Relocation::R_PPC64_NONE,
+            RelocationType::Mips(typ) => *typ == MipsRelocation::R_MIPS_NONE,
+            RelocationType::Unknown(typ) => *typ == 0,
+        }
+    }
+
+    /// How a relocation that works on plain data changes the bytes it applies to, with their
+    /// width. These are the relocations of the debug sections of object files.
+    pub fn data_operation(&self) -> Option<(DataOperation, usize)> {
+        use AArch64Relocation::*;
+        use MipsRelocation::*;
+        use PowerPc64Relocation::*;
+        use RiscVRelocation::*;
+        use X86_64Relocation::*;
+        let operation = match self {
+            RelocationType::X86_64(R_X86_64_32)
+            | RelocationType::I386(I386Relocation::R_386_32)
+            | RelocationType::AArch64(R_AARCH64_ABS32)
+            | RelocationType::Arm(ArmRelocation::R_ARM_ABS32)
+            | RelocationType::RiscV(R_RISCV_32 | R_RISCV_SET32)
+            | RelocationType::PowerPc64(R_PPC64_ADDR32)
+            | RelocationType::Mips(R_MIPS_32) => (DataOperation::Set, 4),
+            RelocationType::X86_64(R_X86_64_64)
+            | RelocationType::AArch64(R_AARCH64_ABS64)
+            | RelocationType::RiscV(R_RISCV_64)
+            | RelocationType::PowerPc64(R_PPC64_ADDR64)
+            | RelocationType::Mips(R_MIPS_64) => (DataOperation::Set, 8),
+            RelocationType::RiscV(R_RISCV_SET8) => (DataOperation::Set, 1),
+            RelocationType::RiscV(R_RISCV_SET16) => (DataOperation::Set, 2),
+            // RISC-V linkers relax code, so distances are stored as pairs of relocations
+            RelocationType::RiscV(R_RISCV_ADD8) => (DataOperation::Add, 1),
+            RelocationType::RiscV(R_RISCV_ADD16) => (DataOperation::Add, 2),
+            RelocationType::RiscV(R_RISCV_ADD32) => (DataOperation::Add, 4),
+            RelocationType::RiscV(R_RISCV_ADD64) => (DataOperation::Add, 8),
+            RelocationType::RiscV(R_RISCV_SUB8) => (DataOperation::Subtract, 1),
+            RelocationType::RiscV(R_RISCV_SUB16) => (DataOperation::Subtract, 2),
+            RelocationType::RiscV(R_RISCV_SUB32) => (DataOperation::Subtract, 4),
+            RelocationType::RiscV(R_RISCV_SUB64) => (DataOperation::Subtract, 8),
+            _ => return None,
+        };
+        Some(operation)
+    }
+}
+
+/// What a data relocation does with the symbol value plus addend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataOperation {
+    /// Stores it
+    Set,
+    /// Adds it to the value already there
+    Add,
+    /// Subtracts it from the value already there
+    Subtract,
+}
+
+/// The raw type of the relative relocations that RELR tables encode
+pub(crate) fn relative_type(machine: Machine) -> u32 {
+    match machine {
+        Machine::X86_64 | Machine::I386 => 8,
+        Machine::AArch64 => 1027,
+        Machine::Arm => 23,
+        Machine::RiscV => 3,
+        Machine::PowerPc64 => 22,
+        Machine::Mips => 3,
+        _ => 0,
+    }
+}
+
+impl fmt::Display for RelocationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelocationType::X86_64(typ) => write!(f, "{}", typ),
+            RelocationType::I386(typ) => write!(f, "{}", typ),
+            RelocationType::AArch64(typ) => write!(f, "{}", typ),
+            RelocationType::Arm(typ) => write!(f, "{}", typ),
+            RelocationType::RiscV(typ) => write!(f, "{}", typ),
+            RelocationType::PowerPc64(typ) => write!(f, "{}", typ),
+            RelocationType::Mips(typ) => write!(f, "{}", typ),
+            RelocationType::Unknown(typ) => write!(f, "<unknown {}>", typ),
+        }
+    }
+}
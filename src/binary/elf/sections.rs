@@ -0,0 +1,172 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::sequence::tuple;
+
+use super::Encoding;
+use crate::parse;
+
+// From elf.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionType {
+    Null,
+    ProgramBits,
+    SymbolTable,
+    StringTable,
+    Rela,
+    Hash,
+    Dynamic,
+    Note,
+    NoBits,
+    Rel,
+    Shlib,
+    DynamicSymbolTable,
+    InitArray,
+    FiniArray,
+    PreinitArray,
+    Group,
+    SymbolTableIndices,
+    Relr,
+    AndroidRel,
+    AndroidRela,
+    AndroidRelr,
+    GnuAttributes,
+    GnuHash,
+    GnuLibList,
+    GnuVersionDefinitions,
+    GnuVersionRequirements,
+    GnuVersionSymbols,
+    Other(u32),
+}
+
+impl From<u32> for SectionType {
+    fn from(typ: u32) -> Self {
+        match typ {
+            0 => SectionType::Null,
+            1 => SectionType::ProgramBits,
+            2 => SectionType::SymbolTable,
+            3 => SectionType::StringTable,
+            4 => SectionType::Rela,
+            5 => SectionType::Hash,
+            6 => SectionType::Dynamic,
+            7 => SectionType::Note,
+            8 => SectionType::NoBits,
+            9 => SectionType::Rel,
+            10 => SectionType::Shlib,
+            11 => SectionType::DynamicSymbolTable,
+            14 => SectionType::InitArray,
+            15 => SectionType::FiniArray,
+            16 => SectionType::PreinitArray,
+            17 => SectionType::Group,
+            18 => SectionType::SymbolTableIndices,
+            19 => SectionType::Relr,
+            0x6000_0001 => SectionType::AndroidRel,
+            0x6000_0002 => SectionType::AndroidRela,
+            0x6fff_ff00 => SectionType::AndroidRelr,
+            0x6fff_fff5 => SectionType::GnuAttributes,
+            0x6fff_fff6 => SectionType::GnuHash,
+            0x6fff_fff7 => SectionType::GnuLibList,
+            0x6fff_fffd => SectionType::GnuVersionDefinitions,
+            0x6fff_fffe => SectionType::GnuVersionRequirements,
+            0x6fff_ffff => SectionType::GnuVersionSymbols,
+            typ => SectionType::Other(typ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SectionHeader {
+    pub name: String,
+    /// Offset of the name in the section name string table
+    pub name_offset: u32,
+    pub typ: SectionType,
+    pub flags: u64,
+    pub address: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub link: u32,
+    pub info: u32,
+    pub align: u64,
+    pub entry_size: u64,
+}
+
+impl SectionHeader {
+    pub(super) fn parse(
+        encoding: Encoding,
+    ) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input| {
+            let (
+                input,
+                (name_offset, typ, flags, address, offset, size, link, info, align, entry_size),
+            ) = context(
+                "Parse Section Header",
+                tuple((
+                    encoding.u32(),
+                    encoding.u32(),
+                    encoding.word(),
+                    encoding.word(),
+                    encoding.word(),
+                    encoding.word(),
+                    encoding.u32(),
+                    encoding.u32(),
+                    encoding.word(),
+                    encoding.word(),
+                )),
+            )(input)?;
+            Ok((
+                input,
+                Self {
+                    name: String::new(),
+                    name_offset,
+                    typ: SectionType::from(typ),
+                    flags,
+                    address,
+                    offset,
+                    size,
+                    link,
+                    info,
+                    align,
+                    entry_size,
+                },
+            ))
+        }
+    }
+
+    /// The flags in the letters `readelf` uses for them
+    pub fn flag_letters(&self) -> String {
+        const FLAGS: [(u64, char); 12] = [
+            (0x1, 'W'),
+            (0x2, 'A'),
+            (0x4, 'X'),
+            (0x10, 'M'),
+            (0x20, 'S'),
+            (0x40, 'I'),
+            (0x80, 'L'),
+            (0x100, 'O'),
+            (0x200, 'G'),
+            (0x400, 'T'),
+            (0x800, 'C'),
+            (0x8000_0000, 'E'),
+        ];
+        FLAGS
+            .iter()
+            .filter(|(bit, _)| self.flags & bit != 0)
+            .map(|(_, letter)| letter)
+            .collect()
+    }
+}
+
+impl fmt::Display for SectionHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Name: {}", self.name)?;
+        writeln!(f, "Type: {:?}", self.typ)?;
+        writeln!(f, "Flags: {:x} ({})", self.flags, self.flag_letters())?;
+        writeln!(f, "Address: {:x}", self.address)?;
+        writeln!(f, "Offset: {:x}", self.offset)?;
+        writeln!(f, "Size: {} bytes", self.size)?;
+        writeln!(f, "Link: {}", self.link)?;
+        writeln!(f, "Info: {}", self.info)?;
+        writeln!(f, "Align: {}", self.align)?;
+        writeln!(f, "Entry Size: {}", self.entry_size)
+    }
+}
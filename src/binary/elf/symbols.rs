@@ -0,0 +1,728 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::u8;
+use nom::sequence::tuple;
+
+use super::sections::SectionType;
+use super::{Class, Elf, Encoding, SHN_XINDEX};
+use crate::error::BinDumpResult;
+use crate::parse::{self, string_at};
+
+const SHN_UNDEF: u16 = 0;
+const SHN_LORESERVE: u16 = 0xff00;
+const SHN_ABS: u16 = 0xfff1;
+const SHN_COMMON: u16 = 0xfff2;
+/// Bit of a `.gnu.version` entry marking the version as hidden, so the symbol can only be bound to
+/// by naming the version explicitly
+const VERSYM_HIDDEN: u16 = 0x8000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolBinding {
+    Local,
+    Global,
+    Weak,
+    GnuUnique,
+    Other(u8),
+}
+
+impl From<u8> for SymbolBinding {
+    fn from(binding: u8) -> Self {
+        match binding {
+            0 => SymbolBinding::Local,
+            1 => SymbolBinding::Global,
+            2 => SymbolBinding::Weak,
+            10 => SymbolBinding::GnuUnique,
+            binding => SymbolBinding::Other(binding),
+        }
+    }
+}
+
+impl fmt::Display for SymbolBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolBinding::Local => write!(f, "LOCAL"),
+            SymbolBinding::Global => write!(f, "GLOBAL"),
+            SymbolBinding::Weak => write!(f, "WEAK"),
+            SymbolBinding::GnuUnique => write!(f, "UNIQUE"),
+            SymbolBinding::Other(binding) => write!(f, "<{}>", binding),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolType {
+    NoType,
+    Object,
+    Function,
+    Section,
+    File,
+    Common,
+    Tls,
+    GnuIndirectFunction,
+    Other(u8),
+}
+
+impl From<u8> for SymbolType {
+    fn from(typ: u8) -> Self {
+        match typ {
+            0 => SymbolType::NoType,
+            1 => SymbolType::Object,
+            2 => SymbolType::Function,
+            3 => SymbolType::Section,
+            4 => SymbolType::File,
+            5 => SymbolType::Common,
+            6 => SymbolType::Tls,
+            10 => SymbolType::GnuIndirectFunction,
+            typ => SymbolType::Other(typ),
+        }
+    }
+}
+
+impl fmt::Display for SymbolType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolType::NoType => write!(f, "NOTYPE"),
+            SymbolType::Object => write!(f, "OBJECT"),
+            SymbolType::Function => write!(f, "FUNC"),
+            SymbolType::Section => write!(f, "SECTION"),
+            SymbolType::File => write!(f, "FILE"),
+            SymbolType::Common => write!(f, "COMMON"),
+            SymbolType::Tls => write!(f, "TLS"),
+            SymbolType::GnuIndirectFunction => write!(f, "IFUNC"),
+            SymbolType::Other(typ) => write!(f, "<{}>", typ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolVisibility {
+    Default,
+    Internal,
+    Hidden,
+    Protected,
+}
+
+impl From<u8> for SymbolVisibility {
+    fn from(other: u8) -> Self {
+        match other & 0x3 {
+            0 => SymbolVisibility::Default,
+            1 => SymbolVisibility::Internal,
+            2 => SymbolVisibility::Hidden,
+            _ => SymbolVisibility::Protected,
+        }
+    }
+}
+
+impl fmt::Display for SymbolVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolVisibility::Default => write!(f, "DEFAULT"),
+            SymbolVisibility::Internal => write!(f, "INTERNAL"),
+            SymbolVisibility::Hidden => write!(f, "HIDDEN"),
+            SymbolVisibility::Protected => write!(f, "PROTECTED"),
+        }
+    }
+}
+
+/// The section a symbol is defined in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionIndex {
+    Undefined,
+    Absolute,
+    Common,
+    Index(u32),
+    /// An index in the reserved range that has no generic meaning
+    Reserved(u16),
+}
+
+impl fmt::Display for SectionIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectionIndex::Undefined => write!(f, "UND"),
+            SectionIndex::Absolute => write!(f, "ABS"),
+            SectionIndex::Common => write!(f, "COM"),
+            SectionIndex::Index(index) => write!(f, "{}", index),
+            SectionIndex::Reserved(index) => write!(f, "{:#x}", index),
+        }
+    }
+}
+
+/// The GNU version attached to a dynamic symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolVersion {
+    /// The symbol is local to the object
+    Local,
+    /// The symbol is in the unversioned base definition
+    Global,
+    /// A version defined by this object
+    Defined { name: String, hidden: bool },
+    /// A version required from the dependency `file`
+    Required {
+        name: String,
+        file: String,
+        hidden: bool,
+    },
+    /// An index with no matching definition or requirement
+    Unknown(u16),
+}
+
+#[derive(Debug)]
+pub struct Symbol {
+    pub name: String,
+    pub value: u64,
+    pub size: u64,
+    pub binding: SymbolBinding,
+    pub typ: SymbolType,
+    pub visibility: SymbolVisibility,
+    pub section_index: SectionIndex,
+    pub version: Option<SymbolVersion>,
+}
+
+impl Symbol {
+    /// The name with its version appended, as in `memcpy@GLIBC_2.14`
+    pub fn versioned_name(&self) -> String {
+        match &self.version {
+            Some(SymbolVersion::Defined { name, hidden }) => {
+                format!("{}{}{}", self.name, if *hidden { "@" } else { "@@" }, name)
+            }
+            Some(SymbolVersion::Required { name, .. }) => format!("{}@{}", self.name, name),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:016x} {:>6} {:<7} {:<6} {:<9} {:>4} {}",
+            self.value,
+            self.size,
+            self.typ.to_string(),
+            self.binding.to_string(),
+            self.visibility.to_string(),
+            self.section_index.to_string(),
+            self.versioned_name()
+        )?;
+        if let Some(SymbolVersion::Required { file, .. }) = &self.version {
+            write!(f, " ({})", file)?;
+        }
+        Ok(())
+    }
+}
+
+/// A version defined in `.gnu.version_d`
+#[derive(Debug)]
+pub struct VersionDefinition {
+    pub index: u16,
+    pub flags: u16,
+    pub hash: u32,
+    pub name: String,
+    /// The versions this one inherits from
+    pub parents: Vec<String>,
+}
+
+impl fmt::Display for VersionDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Version {}: {}", self.index, self.name)?;
+        if self.flags & 0x1 != 0 {
+            write!(f, " (base)")?;
+        }
+        if self.flags & 0x2 != 0 {
+            write!(f, " (weak)")?;
+        }
+        if !self.parents.is_empty() {
+            write!(f, ", parents {}", self.parents.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The versions needed from one dependency, from `.gnu.version_r`
+#[derive(Debug)]
+pub struct VersionRequirement {
+    pub file: String,
+    pub versions: Vec<VersionNeeded>,
+}
+
+#[derive(Debug)]
+pub struct VersionNeeded {
+    pub index: u16,
+    pub flags: u16,
+    pub hash: u32,
+    pub name: String,
+}
+
+impl fmt::Display for VersionRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "File: {}", self.file)?;
+        for version in &self.versions {
+            write!(f, "Version {}: {}", version.index, version.name)?;
+            if version.flags & 0x2 != 0 {
+                write!(f, " (weak)")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Elf {
+    /// Parses the first section of the given symbol table type, resolving names through its
+    /// linked string table
+    pub(super) fn parse_symbol_table(&self, typ: SectionType) -> BinDumpResult<Vec<Symbol>> {
+        let Some((table_index, table)) = self
+            .sections
+            .iter()
+            .enumerate()
+            .find(|(_, section)| section.typ == typ)
+        else {
+            return Ok(Vec::new());
+        };
+        let encoding = self.header.encoding;
+        let strings = self
+            .sections
+            .get(table.link as usize)
+            .map(|strings| self.section_data(strings))
+            .unwrap_or_default();
+        // Section indices that don't fit in 16 bits are stored in a parallel table
+        let extended_indices = self
+            .sections
+            .iter()
+            .find(|section| {
+                section.typ == SectionType::SymbolTableIndices
+                    && section.link as usize == table_index
+            })
+            .map(|section| self.section_data(section))
+            .unwrap_or_default();
+
+        let data = self.section_data(table);
+        let entry_size = match table.entry_size {
+            0 => symbol_size(encoding),
+            entry_size => entry_size,
+        };
+        let number = data.len() as u64 / entry_size;
+        (0..number)
+            .map(|i| {
+                let entry = &data[(i * entry_size) as usize..];
+                let (_, (name, value, size, info, other, index)) = parse_symbol(encoding)(entry)?;
+                let section_index = match index {
+                    SHN_UNDEF => SectionIndex::Undefined,
+                    SHN_ABS => SectionIndex::Absolute,
+                    SHN_COMMON => SectionIndex::Common,
+                    SHN_XINDEX => {
+                        let extended = extended_indices
+                            .get(i as usize * 4..)
+                            .map(|extended| encoding.u32()(extended))
+                            .transpose()?
+                            .map(|(_, index)| index)
+                            .unwrap_or(0);
+                        SectionIndex::Index(extended)
+                    }
+                    index if index >= SHN_LORESERVE => SectionIndex::Reserved(index),
+                    index => SectionIndex::Index(index as u32),
+                };
+                Ok(Symbol {
+                    name: string_at(strings, name as usize),
+                    value,
+                    size,
+                    binding: SymbolBinding::from(info >> 4),
+                    typ: SymbolType::from(info & 0xf),
+                    visibility: SymbolVisibility::from(other),
+                    section_index,
+                    version: None,
+                })
+            })
+            .collect()
+    }
+
+    pub(super) fn parse_version_definitions(&self) -> BinDumpResult<Vec<VersionDefinition>> {
+        let Some(section) = self
+            .sections
+            .iter()
+            .find(|section| section.typ == SectionType::GnuVersionDefinitions)
+        else {
+            return Ok(Vec::new());
+        };
+        let encoding = self.header.encoding;
+        let data = self.section_data(section);
+        let strings = self.linked_strings(section.link);
+
+        let mut definitions = Vec::new();
+        let mut offset = 0usize;
+        // `sh_info` holds the number of definitions
+        for _ in 0..section.info {
+            let Some(input) = data.get(offset..) else {
+                break;
+            };
+            let (_, (_, flags, index, number_names, hash, aux, next)) = context(
+                "Parse Version Definition",
+                tuple((
+                    encoding.u16(),
+                    encoding.u16(),
+                    encoding.u16(),
+                    encoding.u16(),
+                    encoding.u32(),
+                    encoding.u32(),
+                    encoding.u32(),
+                )),
+            )(input)?;
+            let mut names = Vec::new();
+            let mut aux_offset = offset + aux as usize;
+            for _ in 0..number_names {
+                let Some(input) = data.get(aux_offset..) else {
+                    break;
+                };
+                let (_, (name, next)) = context(
+                    "Parse Version Definition Name",
+                    tuple((encoding.u32(), encoding.u32())),
+                )(input)?;
+                names.push(string_at(strings, name as usize));
+                if next == 0 {
+                    break;
+                }
+                aux_offset += next as usize;
+            }
+            let mut names = names.into_iter();
+            definitions.push(VersionDefinition {
+                index,
+                flags,
+                hash,
+                name: names.next().unwrap_or_default(),
+                parents: names.collect(),
+            });
+            if next == 0 {
+                break;
+            }
+            offset += next as usize;
+        }
+        Ok(definitions)
+    }
+
+    pub(super) fn parse_version_requirements(&self) -> BinDumpResult<Vec<VersionRequirement>> {
+        let Some(section) = self
+            .sections
+            .iter()
+            .find(|section| section.typ == SectionType::GnuVersionRequirements)
+        else {
+            return Ok(Vec::new());
+        };
+        let encoding = self.header.encoding;
+        let data = self.section_data(section);
+        let strings = self.linked_strings(section.link);
+
+        let mut requirements = Vec::new();
+        let mut offset = 0usize;
+        // `sh_info` holds the number of dependencies
+        for _ in 0..section.info {
+            let Some(input) = data.get(offset..) else {
+                break;
+            };
+            let (_, (_, number_versions, file, aux, next)) = context(
+                "Parse Version Requirement",
+                tuple((
+                    encoding.u16(),
+                    encoding.u16(),
+                    encoding.u32(),
+                    encoding.u32(),
+                    encoding.u32(),
+                )),
+            )(input)?;
+            let mut versions = Vec::new();
+            let mut aux_offset = offset + aux as usize;
+            for _ in 0..number_versions {
+                let Some(input) = data.get(aux_offset..) else {
+                    break;
+                };
+                let (_, (hash, flags, index, name, next)) = context(
+                    "Parse Version Requirement Entry",
+                    tuple((
+                        encoding.u32(),
+                        encoding.u16(),
+                        encoding.u16(),
+                        encoding.u32(),
+                        encoding.u32(),
+                    )),
+                )(input)?;
+                versions.push(VersionNeeded {
+                    index,
+                    flags,
+                    hash,
+                    name: string_at(strings, name as usize),
+                });
+                if next == 0 {
+                    break;
+                }
+                aux_offset += next as usize;
+            }
+            requirements.push(VersionRequirement {
+                file: string_at(strings, file as usize),
+                versions,
+            });
+            if next == 0 {
+                break;
+            }
+            offset += next as usize;
+        }
+        Ok(requirements)
+    }
+
+    /// Attaches the versions from `.gnu.version` to the dynamic symbols
+    pub(super) fn apply_symbol_versions(&mut self) -> BinDumpResult<()> {
+        let Some(section) = self
+            .sections
+            .iter()
+            .find(|section| section.typ == SectionType::GnuVersionSymbols)
+        else {
+            return Ok(());
+        };
+        let encoding = self.header.encoding;
+        let data = self.section_data(section);
+        let (_, indices) = context(
+            "Parse Symbol Versions",
+            count(encoding.u16(), data.len() / 2),
+        )(data)?;
+
+        let versions: Vec<_> = indices
+            .into_iter()
+            .map(|index| self.symbol_version(index))
+            .collect();
+        for (symbol, version) in self.dynamic_symbols.iter_mut().zip(versions) {
+            symbol.version = Some(version);
+        }
+        Ok(())
+    }
+
+    fn symbol_version(&self, index: u16) -> SymbolVersion {
+        let hidden = index & VERSYM_HIDDEN != 0;
+        match index & !VERSYM_HIDDEN {
+            0 => SymbolVersion::Local,
+            1 => SymbolVersion::Global,
+            index => {
+                if let Some(definition) = self
+                    .version_definitions
+                    .iter()
+                    .find(|definition| definition.index == index)
+                {
+                    return SymbolVersion::Defined {
+                        name: definition.name.clone(),
+                        hidden,
+                    };
+                }
+                self.version_requirements
+                    .iter()
+                    .find_map(|requirement| {
+                        let version = requirement
+                            .versions
+                            .iter()
+                            .find(|version| version.index == index)?;
+                        Some(SymbolVersion::Required {
+                            name: version.name.clone(),
+                            file: requirement.file.clone(),
+                            hidden,
+                        })
+                    })
+                    .unwrap_or(SymbolVersion::Unknown(index))
+            }
+        }
+    }
+
+    fn linked_strings(&self, link: u32) -> &[u8] {
+        self.sections
+            .get(link as usize)
+            .map(|strings| self.section_data(strings))
+            .unwrap_or_default()
+    }
+}
+
+/// The size of an `Elf32_Sym` or `Elf64_Sym`
+fn symbol_size(encoding: Encoding) -> u64 {
+    match encoding.class {
+        Class::Elf32 => 16,
+        Class::Elf64 => 24,
+    }
+}
+
+/// Parses the name offset, value, size, info, other and section index of a symbol
+#[allow(clippy::type_complexity)]
+fn parse_symbol(
+    encoding: Encoding,
+) -> impl FnMut(parse::Input) -> parse::ParseResult<(u32, u64, u64, u8, u8, u16)> {
+    move |input| match encoding.class {
+        Class::Elf32 => {
+            let (input, (name, value, size, info, other, index)) = context(
+                "Parse Symbol",
+                tuple((
+                    encoding.u32(),
+                    encoding.u32(),
+                    encoding.u32(),
+                    u8,
+                    u8,
+                    encoding.u16(),
+                )),
+            )(input)?;
+            Ok((input, (name, value as u64, size as u64, info, other, index)))
+        }
+        Class::Elf64 => {
+            let (input, (name, info, other, index, value, size)) = context(
+                "Parse Symbol",
+                tuple((
+                    encoding.u32(),
+                    u8,
+                    u8,
+                    encoding.u16(),
+                    encoding.u64(),
+                    encoding.u64(),
+                )),
+            )(input)?;
+            Ok((input, (name, value, size, info, other, index)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{elf, section};
+    use super::*;
+
+    /// An `Elf64_Sym`
+    fn symbol(name: u32, info: u8, other: u8, index: u16, value: u64, size: u64) -> Vec<u8> {
+        let mut data = name.to_le_bytes().to_vec();
+        data.extend_from_slice(&[info, other]);
+        data.extend_from_slice(&index.to_le_bytes());
+        data.extend_from_slice(&value.to_le_bytes());
+        data.extend_from_slice(&size.to_le_bytes());
+        data
+    }
+
+    /// An `Elf64_Verdef` followed by `Elf64_Verdaux` entries for `names`
+    fn version_definition(flags: u16, index: u16, names: &[u32], next: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        for field in [1, flags, index, names.len() as u16] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [0, 20, next] {
+            data.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        for (i, &name) in names.iter().enumerate() {
+            let next = if i + 1 < names.len() { 8 } else { 0 };
+            for field in [name, next] {
+                data.extend_from_slice(&field.to_le_bytes());
+            }
+        }
+        data
+    }
+
+    /// An `Elf64_Verneed` with one `Elf64_Vernaux` for the version `name` with the given index
+    fn version_requirement(file: u32, index: u16, name: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&[1, 0, 1, 0]);
+        for field in [file, 16, 0, 0] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(&[0, 0]);
+        data.extend_from_slice(&index.to_le_bytes());
+        for field in [name, 0] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn reads_symbols_and_their_sections() {
+        let mut symbols = vec![0; 24];
+        symbols.extend(symbol(1, 0x12, 2, 1, 0x10, 4));
+        symbols.extend(symbol(5, 0x11, 0, SHN_XINDEX, 0x20, 8));
+        symbols.extend(symbol(9, 0x11, 0, SHN_COMMON, 8, 8));
+        symbols.extend(symbol(16, 0x21, 0, SHN_ABS, 0x1234, 0));
+        let mut symtab = section(".symtab", 2, symbols);
+        symtab.link = 1;
+        symtab.entry_size = 24;
+        let indices = [0u32, 0, 0x12345]
+            .iter()
+            .flat_map(|i| i.to_le_bytes())
+            .collect();
+        let mut indices = section(".symtab_shndx", 18, indices);
+        indices.link = 2;
+        let strtab = section(".strtab", 3, b"\0foo\0bar\0common\0abs\0".to_vec());
+        let elf = elf(1, vec![strtab, symtab, indices]);
+
+        let symbols = elf.symbols();
+        assert_eq!(symbols.len(), 5);
+        assert_eq!(symbols[0].section_index, SectionIndex::Undefined);
+        let foo = &symbols[1];
+        assert_eq!((foo.name.as_str(), foo.value, foo.size), ("foo", 0x10, 4));
+        assert_eq!(foo.binding, SymbolBinding::Global);
+        assert_eq!(foo.typ, SymbolType::Function);
+        assert_eq!(foo.visibility, SymbolVisibility::Hidden);
+        assert_eq!(foo.section_index, SectionIndex::Index(1));
+        assert_eq!(symbols[2].section_index, SectionIndex::Index(0x12345));
+        assert_eq!(symbols[3].section_index, SectionIndex::Common);
+        assert_eq!(symbols[4].binding, SymbolBinding::Weak);
+        assert_eq!(symbols[4].section_index, SectionIndex::Absolute);
+    }
+
+    #[test]
+    fn attaches_gnu_versions_to_dynamic_symbols() {
+        // memcpy, foo, libtest.so, V1, V0, libc.so.6 and GLIBC_2.14 are at 1, 8, 12, 23, 26, 29
+        // and 39
+        let dynstr = b"\0memcpy\0foo\0libtest.so\0V1\0V0\0libc.so.6\0GLIBC_2.14\0".to_vec();
+        let mut symbols = vec![0; 24];
+        symbols.extend(symbol(8, 0x12, 0, 1, 0x10, 4));
+        symbols.extend(symbol(1, 0x12, 0, SHN_UNDEF, 0, 0));
+        let mut dynsym = section(".dynsym", 11, symbols);
+        dynsym.link = 1;
+        let versions = [0, 2, VERSYM_HIDDEN | 3]
+            .iter()
+            .flat_map(|index| index.to_le_bytes())
+            .collect();
+        let mut versions = section(".gnu.version", 0x6fff_ffff, versions);
+        versions.link = 2;
+        // The base version, then V1 which inherits from V0
+        let mut data = version_definition(1, 1, &[12], 28);
+        data.extend(version_definition(0, 2, &[23, 26], 0));
+        let mut definitions = section(".gnu.version_d", 0x6fff_fffd, data);
+        definitions.link = 1;
+        definitions.info = 2;
+        let data = version_requirement(29, 3, 39);
+        let mut requirements = section(".gnu.version_r", 0x6fff_fffe, data);
+        requirements.link = 1;
+        requirements.info = 1;
+        let elf = elf(
+            3,
+            vec![
+                section(".dynstr", 3, dynstr),
+                dynsym,
+                versions,
+                definitions,
+                requirements,
+            ],
+        );
+
+        let definitions = elf.version_definitions();
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(definitions[0].to_string(), "Version 1: libtest.so (base)");
+        assert_eq!(definitions[1].to_string(), "Version 2: V1, parents V0");
+        let requirements = elf.version_requirements();
+        assert_eq!(requirements.len(), 1);
+        assert_eq!(requirements[0].file, "libc.so.6");
+
+        let symbols = elf.dynamic_symbols();
+        assert_eq!(symbols[0].version, Some(SymbolVersion::Local));
+        assert_eq!(symbols[1].versioned_name(), "foo@@V1");
+        assert_eq!(symbols[2].versioned_name(), "memcpy@GLIBC_2.14");
+        assert_eq!(
+            symbols[2].version,
+            Some(SymbolVersion::Required {
+                name: "GLIBC_2.14".to_string(),
+                file: "libc.so.6".to_string(),
+                hidden: true,
+            })
+        );
+        assert!(symbols[2]
+            .to_string()
+            .ends_with("memcpy@GLIBC_2.14 (libc.so.6)"));
+    }
+}
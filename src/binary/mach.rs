@@ -1,10 +1,19 @@
+pub mod address_space;
+pub mod chained_fixups;
+pub mod dyld_cache;
 pub mod load_commands;
 pub mod machine;
+pub mod objc;
+pub mod swift;
+pub mod symbols;
+pub mod unwind_info;
 
 use std::fmt;
 
+use address_space::AddressSpace;
+use chained_fixups::ChainedFixups;
 use derive_try_from_primitive::TryFromPrimitive;
-use load_commands::LoadCommand;
+use load_commands::{Command, LoadCommand, Section64};
 use machine::CpuType;
 use nom::{
     branch::alt,
@@ -18,10 +27,18 @@ use nom::{
     },
     sequence::tuple,
 };
+use objc::ObjcMetadata;
+use swift::SwiftMetadata;
+use symbols::Symbol;
+use unwind_info::UnwindInfo;
 
+use crate::binary::dwarf::cfi::{CallFrameInformation, FrameSection, PointerBases, UnwindRow};
+use crate::binary::dwarf::{Dwarf, DwarfSections};
+use crate::error::{BinDumpError, BinDumpResult};
 use crate::parse::{self, ParseResult};
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum Mach {
     Universal(Vec<MachArch>),
     MachO(MachODetails),
@@ -134,6 +151,185 @@ impl fmt::Display for MachArchDetails {
 pub struct MachODetails {
     header: MachHeader,
     load_commands: Vec<LoadCommand>,
+    chained_fixups: Option<ChainedFixups>,
+    objc: Option<ObjcMetadata>,
+    swift: Option<SwiftMetadata>,
+    symbols: Vec<Symbol>,
+    dwarf: Option<BinDumpResult<Dwarf>>,
+    eh_frame: Option<BinDumpResult<CallFrameInformation>>,
+    unwind_info: Option<BinDumpResult<UnwindInfo>>,
+}
+
+impl MachODetails {
+    /// Reads the runtime metadata of an image from its sections, following pointers through `space`
+    fn new(
+        header: MachHeader,
+        load_commands: Vec<LoadCommand>,
+        chained_fixups: Option<ChainedFixups>,
+        space: &AddressSpace,
+    ) -> Self {
+        let sections: Vec<_> = load_commands
+            .iter()
+            .filter_map(|command| match &command.command {
+                Command::Segment64(segment) => Some(segment.sections.iter()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        let objc = ObjcMetadata::parse(&sections, space);
+        let swift = SwiftMetadata::parse(&sections, space);
+        Self {
+            header,
+            load_commands,
+            chained_fixups,
+            objc,
+            swift,
+            symbols: Vec::new(),
+            dwarf: None,
+            eh_frame: None,
+            unwind_info: None,
+        }
+    }
+
+    /// Reads the `LC_SYMTAB` symbol table
+    fn parse_symbols<'a>(&self, full_input: parse::Input<'a>) -> ParseResult<'a, Vec<Symbol>> {
+        let Some(table) = self
+            .load_commands
+            .iter()
+            .find_map(|command| match &command.command {
+                Command::SymbolTable(table) => Some(table),
+                _ => None,
+            })
+        else {
+            return Ok((full_input, Vec::new()));
+        };
+        let strings = full_input
+            .get(table.string_offset as usize..)
+            .and_then(|strings| strings.get(..table.string_size as usize))
+            .unwrap_or_default();
+        let input = full_input
+            .get(table.symbol_offset as usize..)
+            .unwrap_or_default();
+        context("Parse Symbol Table", |input| {
+            symbols::parse_symbols(input, table.number_symbols as usize, strings)
+        })(input)
+    }
+
+    /// Reads the DWARF debugging information from the `__DWARF` sections, which dSYM bundles and
+    /// object files have
+    fn parse_dwarf(&self, full_input: parse::Input) -> Option<BinDumpResult<Dwarf>> {
+        let mut sections = DwarfSections::default();
+        for command in &self.load_commands {
+            let Command::Segment64(segment) = &command.command else {
+                continue;
+            };
+            // Object files put every section in one unnamed segment
+            for section in &segment.sections {
+                if section.segment_name != "__DWARF" {
+                    continue;
+                }
+                let start = (section.offset as usize).min(full_input.len());
+                let end = (section.offset as u64)
+                    .saturating_add(section.size)
+                    .min(full_input.len() as u64) as usize;
+                sections.insert(&section.name, &full_input[start..end]);
+            }
+        }
+        if sections.debug_info.is_empty() {
+            return None;
+        }
+        Some(Dwarf::parse(&sections, Endianness::Little))
+    }
+
+    /// A section by its segment and section name, looked up by the segment name recorded in the
+    /// section as object files put every section in one unnamed segment
+    pub fn section(&self, segment_name: &str, name: &str) -> Option<&Section64> {
+        self.load_commands
+            .iter()
+            .filter_map(|command| match &command.command {
+                Command::Segment64(segment) => Some(segment),
+                _ => None,
+            })
+            .flat_map(|segment| &segment.sections)
+            .find(|section| section.segment_name == segment_name && section.name == name)
+    }
+
+    /// Reads the call frame information of `__TEXT,__eh_frame`
+    fn parse_eh_frame(
+        &self,
+        full_input: parse::Input,
+    ) -> Option<BinDumpResult<CallFrameInformation>> {
+        let section = self.section("__TEXT", "__eh_frame")?;
+        let start = (section.offset as usize).min(full_input.len());
+        let end = (section.offset as u64)
+            .saturating_add(section.size)
+            .min(full_input.len() as u64) as usize;
+        let bases = PointerBases {
+            section: section.addr,
+            text: self.section("__TEXT", "__text").map_or(0, |text| text.addr),
+            data: 0,
+        };
+        Some(CallFrameInformation::parse(
+            &full_input[start..end],
+            FrameSection::EhFrame,
+            bases,
+            Endianness::Little,
+            8,
+        ))
+    }
+
+    /// Reads the compact unwind information of `__TEXT,__unwind_info`
+    fn parse_unwind_info(&self, full_input: parse::Input) -> Option<BinDumpResult<UnwindInfo>> {
+        let section = self.section("__TEXT", "__unwind_info")?;
+        let start = (section.offset as usize).min(full_input.len());
+        let end = (section.offset as u64)
+            .saturating_add(section.size)
+            .min(full_input.len() as u64) as usize;
+        Some(
+            UnwindInfo::parse(&full_input[start..end], self.header.cpu_type)
+                .map(|(_, unwind_info)| unwind_info)
+                .map_err(BinDumpError::from),
+        )
+    }
+
+    pub fn load_commands(&self) -> &[LoadCommand] {
+        &self.load_commands
+    }
+
+    /// The Objective-C runtime metadata, if this image has any
+    pub fn objc(&self) -> Option<&ObjcMetadata> {
+        self.objc.as_ref()
+    }
+
+    /// The Swift reflection metadata, if this image has any
+    pub fn swift(&self) -> Option<&SwiftMetadata> {
+        self.swift.as_ref()
+    }
+
+    /// The entries of the `LC_SYMTAB` symbol table
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// The DWARF debugging information, if this is a dSYM or an object file
+    pub fn dwarf(&self) -> Option<&Dwarf> {
+        self.dwarf.as_ref()?.as_ref().ok()
+    }
+
+    /// The call frame information of `__TEXT,__eh_frame`
+    pub fn eh_frame(&self) -> Option<&CallFrameInformation> {
+        self.eh_frame.as_ref()?.as_ref().ok()
+    }
+
+    /// The compact unwind information of `__TEXT,__unwind_info`
+    pub fn unwind_info(&self) -> Option<&UnwindInfo> {
+        self.unwind_info.as_ref()?.as_ref().ok()
+    }
+
+    /// The rules for unwinding a frame stopped at an address, for functions that have an FDE
+    pub fn unwind_row(&self, address: u64) -> Option<UnwindRow> {
+        self.eh_frame()?.unwind_row(address)
+    }
 }
 
 #[derive(Debug)]
@@ -233,7 +429,7 @@ impl Mach {
 
     fn parse_64_bit_little_endian<'a>(
         input: parse::Input<'a>,
-        _full_input: parse::Input<'a>,
+        full_input: parse::Input<'a>,
     ) -> parse::ParseResult<'a, Self> {
         let (input, header) = MachHeader::parse(input, Endianness::Little)?;
         let (input, load_commands) = context(
@@ -243,13 +439,90 @@ impl Mach {
                 header.number_of_load_commands as usize,
             ),
         )(input)?;
-        Ok((
-            input,
-            Mach::MachO(MachODetails {
-                header,
-                load_commands,
-            }),
-        ))
+
+        let chained_fixups = load_commands
+            .iter()
+            .find_map(|command| match &command.command {
+                Command::DynamicLinkerChainedFixups(details) => full_input
+                    .get(details.data_offset as usize..)
+                    .and_then(|blob| blob.get(..details.data_size as usize)),
+                _ => None,
+            })
+            .map(ChainedFixups::parse)
+            .transpose()?
+            .map(|(_, fixups)| fixups);
+        let mut space = AddressSpace::from_load_commands(full_input, &load_commands);
+        if let Some(chained_fixups) = &chained_fixups {
+            space.set_chained_fixups(chained_fixups);
+        }
+
+        let mut details = MachODetails::new(header, load_commands, chained_fixups, &space);
+        details.symbols = details.parse_symbols(full_input)?.1;
+        details.dwarf = details.parse_dwarf(full_input);
+        details.eh_frame = details.parse_eh_frame(full_input);
+        details.unwind_info = details.parse_unwind_info(full_input);
+        Ok((input, Mach::MachO(details)))
+    }
+
+    /// Parses the 64 bit image whose mach header is at `address` in `space`, such as a dylib
+    /// inside a dyld shared cache. Its segments and pointers are read from `space` rather than
+    /// from a file of its own.
+    pub(crate) fn parse_in_address_space(
+        address: u64,
+        space: &AddressSpace,
+    ) -> BinDumpResult<Self> {
+        let image = space.read_to_end(address).ok_or(BinDumpError::ParseError {
+            error: format!("No Mach-O image is mapped at {:#x}", address),
+        })?;
+        let (input, _) = context("Magic", tag(&[0xcf, 0xfa, 0xed, 0xfe]))(image)?;
+        let (input, header) = MachHeader::parse(input, Endianness::Little)?;
+        let (_, load_commands) = context(
+            "Load Load Commands",
+            count(
+                LoadCommand::parse(Endianness::Little),
+                header.number_of_load_commands as usize,
+            ),
+        )(input)?;
+        Ok(Mach::MachO(MachODetails::new(
+            header,
+            load_commands,
+            None,
+            space,
+        )))
+    }
+}
+
+impl Mach {
+    /// The image for a CPU type: the slice of a universal binary for it, defaulting to the host's,
+    /// or a thin image if it is for that CPU type or none is asked for
+    pub fn image(&self, cpu_type: Option<CpuType>) -> Option<&MachODetails> {
+        match self {
+            Mach::Universal(arches) => {
+                let cpu_type = cpu_type.or_else(CpuType::host)?;
+                arches.iter().find_map(|arch| match arch {
+                    MachArch::Arch32(arch) if arch.cpu_type == cpu_type => {
+                        arch.mach_object.image(Some(cpu_type))
+                    }
+                    MachArch::Arch32(_) => None,
+                })
+            }
+            Mach::MachO(details) => cpu_type
+                .is_none_or(|cpu_type| details.header.cpu_type == cpu_type)
+                .then_some(details),
+        }
+    }
+
+    /// The Objective-C metadata of every image, including each arch of a universal binary
+    pub fn objc_metadata(&self) -> Vec<&ObjcMetadata> {
+        match self {
+            Mach::Universal(arches) => arches
+                .iter()
+                .flat_map(|arch| match arch {
+                    MachArch::Arch32(arch) => arch.mach_object.objc_metadata(),
+                })
+                .collect(),
+            Mach::MachO(details) => details.objc().into_iter().collect(),
+        }
     }
 }
 
@@ -267,13 +540,108 @@ impl fmt::Display for Mach {
             Mach::MachO(details) => {
                 let header = &details.header;
                 writeln!(f, "Mach-O: {} Architecture", header.cpu_type)?;
+                writeln!(f, "Cpu Subtype: {:x}", header.cpu_subtype)?;
+                writeln!(f, "File Type: {:?}", header.file_type)?;
+                writeln!(f, "Total Command Size: {} bytes", header.total_command_size)?;
+                writeln!(f, "Flags: {:x}", header.flags)?;
+                writeln!(f, "Reserved: {:x}", header.reserved)?;
                 writeln!(f, "Load Commands:")?;
                 for (i, command) in details.load_commands.iter().enumerate() {
                     writeln!(f, "Load Command {}", i)?;
                     writeln!(f, "{}", command)?;
                 }
+                if let Some(chained_fixups) = &details.chained_fixups {
+                    writeln!(f, "Chained Fixups:")?;
+                    writeln!(f, "{}", chained_fixups)?;
+                }
+                if let Some(objc) = &details.objc {
+                    writeln!(f, "Objective-C:")?;
+                    writeln!(f, "{}", objc)?;
+                }
+                if let Some(swift) = &details.swift {
+                    writeln!(f, "Swift:")?;
+                    writeln!(f, "{}", swift)?;
+                }
+                if !details.symbols.is_empty() {
+                    writeln!(f, "Symbols:")?;
+                    for symbol in &details.symbols {
+                        writeln!(
+                            f,
+                            "{:#018x} {:02x} {:>3} {:04x} {}",
+                            symbol.value,
+                            symbol.typ,
+                            symbol.section,
+                            symbol.description,
+                            symbol.name
+                        )?;
+                    }
+                }
+                match &details.dwarf {
+                    Some(Ok(dwarf)) => {
+                        writeln!(f, "DWARF:")?;
+                        write!(f, "{}", dwarf)?;
+                    }
+                    Some(Err(e)) => writeln!(f, "DWARF: Error {}", e)?,
+                    None => {}
+                }
+                match &details.eh_frame {
+                    Some(Ok(eh_frame)) => {
+                        writeln!(f, "Eh Frame:")?;
+                        write!(f, "{}", eh_frame)?;
+                    }
+                    Some(Err(e)) => writeln!(f, "Eh Frame: Error {}", e)?,
+                    None => {}
+                }
+                match &details.unwind_info {
+                    Some(Ok(unwind_info)) => {
+                        writeln!(f, "Unwind Info:")?;
+                        write!(f, "{}", unwind_info)?;
+                    }
+                    Some(Err(e)) => writeln!(f, "Unwind Info: Error {}", e)?,
+                    None => {}
+                }
                 Ok(())
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A mach header for `cpu_type` with no load commands
+    fn thin_image(cpu_type: CpuType) -> Vec<u8> {
+        let mut image = vec![0xcf, 0xfa, 0xed, 0xfe];
+        for field in [cpu_type as i32, 0, 1, 0, 0, 0, 0] {
+            image.extend_from_slice(&field.to_le_bytes());
+        }
+        image
+    }
+
+    #[test]
+    fn picks_universal_slices() {
+        let mut data = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 2];
+        for (i, cpu_type) in [CpuType::X86_64, CpuType::Arm64].into_iter().enumerate() {
+            for field in [cpu_type as i32, 0, 0x30 + i as i32 * 0x20, 0x20, 0] {
+                data.extend_from_slice(&field.to_be_bytes());
+            }
+        }
+        data.extend_from_slice(&thin_image(CpuType::X86_64));
+        data.extend_from_slice(&thin_image(CpuType::Arm64));
+        let (_, mach) = Mach::parse(&data).unwrap();
+
+        let image = mach.image(Some(CpuType::Arm64)).unwrap();
+        assert_eq!(image.header.cpu_type, CpuType::Arm64);
+        assert!(mach.image(Some(CpuType::PowerPc)).is_none());
+        if let Some(host) =
+            CpuType::host().filter(|host| [CpuType::X86_64, CpuType::Arm64].contains(host))
+        {
+            assert_eq!(mach.image(None).unwrap().header.cpu_type, host);
+        }
+
+        let (_, thin) = Mach::parse(&thin_image(CpuType::Arm64)).unwrap();
+        assert!(thin.image(None).is_some());
+        assert!(thin.image(Some(CpuType::X86_64)).is_none());
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+
+use super::chained_fixups::{decode_chained_pointer, ChainedFixups, ChainedPointer};
+use super::load_commands::{Command, LoadCommand};
+use crate::parse;
+
+/// A contiguous range of virtual memory and the file bytes backing it
+#[derive(Debug)]
+struct Region<'a> {
+    address: u64,
+    size: u64,
+    data: &'a [u8],
+}
+
+/// A pointer read out of an image, with any fixups resolved as far as possible without loading it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pointer {
+    Null,
+    /// A pointer to an address within the image
+    Address(u64),
+    /// A pointer to a symbol in another image, bound by dyld when loading
+    Symbol {
+        name: String,
+        addend: i64,
+    },
+}
+
+impl Pointer {
+    pub fn address(&self) -> Option<u64> {
+        match self {
+            Pointer::Address(address) => Some(*address),
+            _ => None,
+        }
+    }
+}
+
+/// How pointers stored in an image are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PointerEncoding {
+    /// Plain addresses, possibly bound by `LC_DYLD_INFO` opcodes
+    Plain,
+    /// Chained fixups in one of the `DYLD_CHAINED_PTR_*` formats
+    Chained(u16),
+    /// A dyld shared cache with version 2 slide info, where `delta_mask` covers the bits linking
+    /// each pointer to the next one on its page
+    CacheSlideV2 { delta_mask: u64, value_add: u64 },
+    /// A dyld shared cache with version 3 slide info, used by arm64e caches
+    CacheSlideV3 { auth_value_add: u64 },
+    /// A dyld shared cache with version 5 slide info, used by arm64e caches since macOS 14
+    CacheSlideV5 { value_add: u64 },
+}
+
+/// The virtual memory layout of an image, used to follow pointers between structures without
+/// needing to know which file offset they live at
+#[derive(Debug)]
+pub(crate) struct AddressSpace<'a> {
+    regions: Vec<Region<'a>>,
+    /// Preferred load address of the image, which offset based fixups are relative to
+    base_address: u64,
+    pointer_encoding: PointerEncoding,
+    imports: Vec<String>,
+    /// Symbols bound by the `LC_DYLD_INFO` bind opcodes, keyed by the address that is bound
+    binds: HashMap<u64, String>,
+}
+
+impl<'a> AddressSpace<'a> {
+    pub(crate) fn new(base_address: u64) -> Self {
+        Self {
+            regions: Vec::new(),
+            base_address,
+            pointer_encoding: PointerEncoding::Plain,
+            imports: Vec::new(),
+            binds: HashMap::new(),
+        }
+    }
+
+    /// Builds the address space of a Mach-O image from its segments, where `image` is the whole
+    /// image starting at its mach header. Symbols bound by `LC_DYLD_INFO` are recorded, but chained
+    /// fixups have to be added with [`AddressSpace::set_chained_fixups`].
+    pub(crate) fn from_load_commands(
+        image: parse::Input<'a>,
+        load_commands: &[LoadCommand],
+    ) -> Self {
+        let segments: Vec<_> = load_commands
+            .iter()
+            .filter_map(|command| match &command.command {
+                Command::Segment64(segment) => Some(segment),
+                _ => None,
+            })
+            .collect();
+        let base_address = segments
+            .iter()
+            .find(|segment| segment.file_offset == 0 && segment.file_size != 0)
+            .map(|segment| segment.vm_addr)
+            .unwrap_or(0);
+        let mut space = Self::new(base_address);
+        for segment in &segments {
+            let start = (segment.file_offset as usize).min(image.len());
+            let end = segment
+                .file_offset
+                .saturating_add(segment.file_size)
+                .min(image.len() as u64) as usize;
+            space.add_region(segment.vm_addr, segment.vm_size, &image[start..end]);
+        }
+
+        for command in load_commands {
+            match &command.command {
+                Command::DynamicLinkerInfo(details) | Command::DynamicLinkerInfoOnly(details) => {
+                    let streams = [
+                        (details.bind_offset, details.bind_size, false),
+                        (details.weak_bind_offset, details.weak_bind_size, false),
+                        (details.lazy_bind_offset, details.lazy_bind_size, true),
+                    ];
+                    for (offset, size, lazy) in streams {
+                        let start = (offset as usize).min(image.len());
+                        let end = (offset as usize + size as usize).min(image.len());
+                        space.apply_bind_opcodes(&image[start..end], &segments, lazy);
+                    }
+                }
+                _ => {}
+            }
+        }
+        space
+    }
+
+    pub(crate) fn add_region(&mut self, address: u64, size: u64, data: &'a [u8]) {
+        self.regions.push(Region {
+            address,
+            size,
+            data,
+        });
+    }
+
+    pub(crate) fn set_chained_fixups(&mut self, fixups: &ChainedFixups) {
+        if let Some(pointer_format) = fixups.pointer_format() {
+            self.pointer_encoding = PointerEncoding::Chained(pointer_format);
+        }
+        self.imports = fixups
+            .imports
+            .iter()
+            .map(|import| import.name.clone())
+            .collect();
+    }
+
+    pub(crate) fn set_pointer_encoding(&mut self, pointer_encoding: PointerEncoding) {
+        self.pointer_encoding = pointer_encoding;
+    }
+
+    /// Reads `length` bytes at `address`, if they are all backed by the file
+    pub(crate) fn read(&self, address: u64, length: usize) -> Option<&'a [u8]> {
+        let region = self
+            .regions
+            .iter()
+            .find(|region| address >= region.address && address - region.address < region.size)?;
+        let start = (address - region.address) as usize;
+        region.data.get(start..start.checked_add(length)?)
+    }
+
+    /// All of the bytes from `address` to the end of its region that are backed by the file
+    pub(crate) fn read_to_end(&self, address: u64) -> Option<&'a [u8]> {
+        let region = self
+            .regions
+            .iter()
+            .find(|region| address >= region.address && address - region.address < region.size)?;
+        region.data.get((address - region.address) as usize..)
+    }
+
+    pub(crate) fn read_u32(&self, address: u64) -> Option<u32> {
+        Some(u32::from_le_bytes(self.read(address, 4)?.try_into().ok()?))
+    }
+
+    pub(crate) fn read_i32(&self, address: u64) -> Option<i32> {
+        Some(i32::from_le_bytes(self.read(address, 4)?.try_into().ok()?))
+    }
+
+    pub(crate) fn read_u64(&self, address: u64) -> Option<u64> {
+        Some(u64::from_le_bytes(self.read(address, 8)?.try_into().ok()?))
+    }
+
+    /// Reads a null terminated string at `address`
+    pub(crate) fn read_string(&self, address: u64) -> Option<String> {
+        let bytes = self.read_to_end(address)?;
+        let end = bytes.iter().position(|&b| b == 0)?;
+        Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+    }
+
+    /// Reads a pointer stored at `address`, resolving any fixup applied to it
+    pub(crate) fn read_pointer(&self, address: u64) -> Option<Pointer> {
+        if let Some(name) = self.binds.get(&address) {
+            return Some(Pointer::Symbol {
+                name: name.clone(),
+                addend: 0,
+            });
+        }
+        let raw = self.read_u64(address)?;
+        Some(self.resolve_pointer(raw))
+    }
+
+    /// Resolves a raw pointer value, removing any chained fixup encoding from it
+    pub(crate) fn resolve_pointer(&self, raw: u64) -> Pointer {
+        let decoded = match self.pointer_encoding {
+            PointerEncoding::Plain => ChainedPointer::Rebase(raw),
+            PointerEncoding::Chained(pointer_format) => {
+                decode_chained_pointer(pointer_format, raw, self.base_address)
+            }
+            PointerEncoding::CacheSlideV2 {
+                delta_mask,
+                value_add,
+            } => match raw & !delta_mask {
+                0 => ChainedPointer::Rebase(0),
+                value => ChainedPointer::Rebase(value.wrapping_add(value_add)),
+            },
+            PointerEncoding::CacheSlideV3 { auth_value_add } => {
+                if raw >> 63 != 0 {
+                    ChainedPointer::Rebase(auth_value_add.wrapping_add(raw & 0xffff_ffff))
+                } else {
+                    let high8 = (raw >> 43) & 0xff;
+                    ChainedPointer::Rebase(high8 << 56 | (raw & 0x7ff_ffff_ffff))
+                }
+            }
+            PointerEncoding::CacheSlideV5 { value_add } => {
+                // Authenticated pointers use the bits of `high8` for their diversity data
+                let high8 = if raw >> 63 != 0 {
+                    0
+                } else {
+                    (raw >> 34) & 0xff
+                };
+                ChainedPointer::Rebase(high8 << 56 | value_add.wrapping_add(raw & 0x3_ffff_ffff))
+            }
+        };
+        match decoded {
+            ChainedPointer::Rebase(0) => Pointer::Null,
+            // Drop the top byte, which may hold a tag
+            ChainedPointer::Rebase(address) => Pointer::Address(address & 0x00ff_ffff_ffff_ffff),
+            ChainedPointer::Bind { ordinal, addend } => Pointer::Symbol {
+                name: self
+                    .imports
+                    .get(ordinal as usize)
+                    .cloned()
+                    .unwrap_or_else(|| format!("<import {}>", ordinal)),
+                addend,
+            },
+        }
+    }
+
+    /// Runs a stream of bind opcodes, recording which symbol is bound at each address. Binding
+    /// stops at the first malformed opcode.
+    fn apply_bind_opcodes(
+        &mut self,
+        mut input: parse::Input,
+        segments: &[&super::load_commands::Segment64Details],
+        lazy: bool,
+    ) {
+        const POINTER_SIZE: u64 = 8;
+        let mut symbol = String::new();
+        let mut segment_address = 0u64;
+        let mut segment_size = 0u64;
+        let mut offset = 0u64;
+        while let Some((&byte, rest)) = input.split_first() {
+            input = rest;
+            let immediate = byte & 0x0f;
+            let mut bind = |offset: u64| {
+                self.binds.insert(segment_address + offset, symbol.clone());
+            };
+            match byte & 0xf0 {
+                // BIND_OPCODE_DONE, which separates the entries of the lazy bind stream
+                0x00 => {
+                    if !lazy {
+                        break;
+                    }
+                }
+                // BIND_OPCODE_SET_DYLIB_ORDINAL_IMM, BIND_OPCODE_SET_DYLIB_SPECIAL_IMM
+                0x10 | 0x30 => {}
+                // BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB
+                0x20 => match parse::uleb128(input) {
+                    Ok((rest, _)) => input = rest,
+                    Err(_) => break,
+                },
+                // BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM
+                0x40 => {
+                    let end = input.iter().position(|&b| b == 0).unwrap_or(input.len());
+                    symbol = String::from_utf8_lossy(&input[..end]).into_owned();
+                    input = &input[(end + 1).min(input.len())..];
+                }
+                // BIND_OPCODE_SET_TYPE_IMM
+                0x50 => {}
+                // BIND_OPCODE_SET_ADDEND_SLEB
+                0x60 => match parse::sleb128(input) {
+                    Ok((rest, _)) => input = rest,
+                    Err(_) => break,
+                },
+                // BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB
+                0x70 => match parse::uleb128(input) {
+                    Ok((rest, segment_offset)) => {
+                        input = rest;
+                        (segment_address, segment_size) = segments
+                            .get(immediate as usize)
+                            .map(|segment| (segment.vm_addr, segment.vm_size))
+                            .unwrap_or((0, 0));
+                        offset = segment_offset;
+                    }
+                    Err(_) => break,
+                },
+                // BIND_OPCODE_ADD_ADDR_ULEB
+                0x80 => match parse::uleb128(input) {
+                    Ok((rest, delta)) => {
+                        input = rest;
+                        offset = offset.wrapping_add(delta);
+                    }
+                    Err(_) => break,
+                },
+                // BIND_OPCODE_DO_BIND
+                0x90 => {
+                    bind(offset);
+                    offset = offset.wrapping_add(POINTER_SIZE);
+                }
+                // BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB
+                0xa0 => match parse::uleb128(input) {
+                    Ok((rest, delta)) => {
+                        input = rest;
+                        bind(offset);
+                        offset = offset.wrapping_add(delta).wrapping_add(POINTER_SIZE);
+                    }
+                    Err(_) => break,
+                },
+                // BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED
+                0xb0 => {
+                    bind(offset);
+                    offset = offset.wrapping_add(immediate as u64 * POINTER_SIZE + POINTER_SIZE);
+                }
+                // BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB
+                0xc0 => {
+                    let Ok((rest, times)) = parse::uleb128(input) else {
+                        break;
+                    };
+                    let Ok((rest, skip)) = parse::uleb128(rest) else {
+                        break;
+                    };
+                    input = rest;
+                    // Every bind fills a pointer of the segment, so more than fit means the
+                    // stream is corrupt
+                    if times > segment_size.saturating_sub(offset) / POINTER_SIZE {
+                        break;
+                    }
+                    for _ in 0..times {
+                        bind(offset);
+                        offset = offset.wrapping_add(skip).wrapping_add(POINTER_SIZE);
+                    }
+                }
+                // BIND_OPCODE_THREADED is only used by old arm64e binaries, which we don't support
+                _ => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::load_commands::Segment64Details;
+    use super::*;
+
+    fn data_segment() -> Segment64Details {
+        Segment64Details {
+            name: "__DATA".to_string(),
+            vm_addr: 0x4000,
+            vm_size: 0x30,
+            file_offset: 0,
+            file_size: 0,
+            max_protection: 3,
+            initial_protection: 3,
+            flags: 0,
+            sections: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn binds_repeated_pointers() {
+        let segment = data_segment();
+        let mut space = AddressSpace::new(0);
+        // Bind _foo in segment 0 at offset 8, twice with a gap of one pointer, then once more
+        let opcodes = b"\x40_foo\0\x70\x08\xc0\x02\x08\x90\x00";
+        space.apply_bind_opcodes(opcodes, &[&segment], false);
+        let mut binds: Vec<_> = space.binds.into_iter().collect();
+        binds.sort();
+        assert_eq!(
+            binds,
+            [
+                (0x4008, "_foo".to_string()),
+                (0x4018, "_foo".to_string()),
+                (0x4028, "_foo".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_bind_counts_past_the_segment() {
+        let segment = data_segment();
+        let mut space = AddressSpace::new(0);
+        // BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB with a count of 2^63
+        let opcodes = b"\x40_foo\0\x70\x00\xc0\x80\x80\x80\x80\x80\x80\x80\x80\x80\x01\x00";
+        space.apply_bind_opcodes(opcodes, &[&segment], false);
+        assert!(space.binds.is_empty());
+    }
+
+    #[test]
+    fn ignores_segments_past_the_end_of_the_image() {
+        let segment = Segment64Details {
+            file_offset: u64::MAX,
+            file_size: 0x30,
+            ..data_segment()
+        };
+        let load_commands = [LoadCommand {
+            size: 72,
+            command: Command::Segment64(segment),
+        }];
+        let space = AddressSpace::from_load_commands(&[0; 0x30], &load_commands);
+        assert_eq!(space.read(0x4000, 1), None);
+    }
+
+    #[test]
+    fn wraps_shared_cache_slide_values() {
+        let mut space = AddressSpace::new(0);
+        space.set_pointer_encoding(PointerEncoding::CacheSlideV2 {
+            delta_mask: 0,
+            value_add: u64::MAX,
+        });
+        assert_eq!(space.resolve_pointer(2), Pointer::Address(1));
+        space.set_pointer_encoding(PointerEncoding::CacheSlideV3 {
+            auth_value_add: u64::MAX,
+        });
+        assert_eq!(space.resolve_pointer(1 << 63 | 2), Pointer::Address(1));
+        space.set_pointer_encoding(PointerEncoding::CacheSlideV5 {
+            value_add: u64::MAX,
+        });
+        assert_eq!(space.resolve_pointer(2), Pointer::Address(1));
+    }
+}
@@ -0,0 +1,411 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_i32, le_u16, le_u32, le_u64};
+use nom::sequence::tuple;
+
+use crate::parse;
+
+/// The contents of the `LC_DYLD_CHAINED_FIXUPS` blob, from fixup-chains.h
+#[derive(Debug)]
+pub struct ChainedFixups {
+    pub fixups_version: u32,
+    pub imports_format: u32,
+    pub symbols_format: u32,
+    /// The fixup chain starts of each segment, indexed like the segment load commands
+    pub segments: Vec<Option<ChainedStartsInSegment>>,
+    pub imports: Vec<ChainedImport>,
+}
+
+#[derive(Debug)]
+pub struct ChainedStartsInSegment {
+    pub page_size: u16,
+    pub pointer_format: u16,
+    /// Offset of the segment from the mach header
+    pub segment_offset: u64,
+    pub max_valid_pointer: u32,
+    pub page_starts: Vec<u16>,
+}
+
+#[derive(Debug)]
+pub struct ChainedImport {
+    pub library_ordinal: i32,
+    pub weak_import: bool,
+    pub name: String,
+    pub addend: i64,
+}
+
+impl ChainedFixups {
+    pub(crate) fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let blob = input;
+        let (
+            input,
+            (
+                fixups_version,
+                starts_offset,
+                imports_offset,
+                symbols_offset,
+                imports_count,
+                imports_format,
+                symbols_format,
+            ),
+        ) = context(
+            "Parse Chained Fixups Header",
+            tuple((le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32)),
+        )(input)?;
+
+        let starts = &blob[(starts_offset as usize).min(blob.len())..];
+        let (offsets, segment_count) = context("Parse Chained Starts In Image", le_u32)(starts)?;
+        let (_, segment_offsets) = count(le_u32, segment_count as usize)(offsets)?;
+        let mut segments = Vec::with_capacity(segment_offsets.len());
+        for offset in segment_offsets {
+            if offset == 0 {
+                segments.push(None);
+                continue;
+            }
+            let (_, segment) =
+                ChainedStartsInSegment::parse(&starts[(offset as usize).min(starts.len())..])?;
+            segments.push(Some(segment));
+        }
+
+        let symbols = &blob[(symbols_offset as usize).min(blob.len())..];
+        let mut imports_input = &blob[(imports_offset as usize).min(blob.len())..];
+        // The count comes from the file, so the vector grows as imports are actually read
+        let mut imports = Vec::new();
+        for _ in 0..imports_count {
+            let (next_input, import) =
+                ChainedImport::parse(imports_input, imports_format, symbols)?;
+            imports_input = next_input;
+            imports.push(import);
+        }
+
+        Ok((
+            input,
+            Self {
+                fixups_version,
+                imports_format,
+                symbols_format,
+                segments,
+                imports,
+            },
+        ))
+    }
+
+    /// The pointer format used by the fixup chains. Every segment of an image uses the same format
+    /// in practice, so the first one found is used.
+    pub fn pointer_format(&self) -> Option<u16> {
+        self.segments
+            .iter()
+            .flatten()
+            .map(|segment| segment.pointer_format)
+            .next()
+    }
+}
+
+impl ChainedStartsInSegment {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (
+            input,
+            (_size, page_size, pointer_format, segment_offset, max_valid_pointer, page_count),
+        ) = context(
+            "Parse Chained Starts In Segment",
+            tuple((le_u32, le_u16, le_u16, le_u64, le_u32, le_u16)),
+        )(input)?;
+        let (input, page_starts) = count(le_u16, page_count as usize)(input)?;
+        Ok((
+            input,
+            Self {
+                page_size,
+                pointer_format,
+                segment_offset,
+                max_valid_pointer,
+                page_starts,
+            },
+        ))
+    }
+}
+
+impl ChainedImport {
+    fn parse<'a>(
+        input: parse::Input<'a>,
+        imports_format: u32,
+        symbols: parse::Input<'a>,
+    ) -> parse::ParseResult<'a, Self> {
+        let (input, (library_ordinal, weak_import, name_offset, addend)) = match imports_format {
+            // DYLD_CHAINED_IMPORT
+            1 => {
+                let (input, raw) = le_u32(input)?;
+                (
+                    input,
+                    (
+                        (raw & 0xff) as u8 as i8 as i32,
+                        raw & 0x100 != 0,
+                        raw >> 9,
+                        0,
+                    ),
+                )
+            }
+            // DYLD_CHAINED_IMPORT_ADDEND
+            2 => {
+                let (input, (raw, addend)) = tuple((le_u32, le_i32))(input)?;
+                (
+                    input,
+                    (
+                        (raw & 0xff) as u8 as i8 as i32,
+                        raw & 0x100 != 0,
+                        raw >> 9,
+                        addend as i64,
+                    ),
+                )
+            }
+            // DYLD_CHAINED_IMPORT_ADDEND64
+            3 => {
+                let (input, (raw, addend)) = tuple((le_u64, le_u64))(input)?;
+                (
+                    input,
+                    (
+                        (raw & 0xffff) as u16 as i16 as i32,
+                        raw & 0x10000 != 0,
+                        (raw >> 32) as u32,
+                        addend as i64,
+                    ),
+                )
+            }
+            _ => return Err(parse::failure(input, "Unknown chained imports format")),
+        };
+        let name_bytes = &symbols[(name_offset as usize).min(symbols.len())..];
+        let end = name_bytes
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(name_bytes.len());
+        Ok((
+            input,
+            Self {
+                library_ordinal,
+                weak_import,
+                name: String::from_utf8_lossy(&name_bytes[..end]).into_owned(),
+                addend,
+            },
+        ))
+    }
+}
+
+/// A pointer stored in the image, after any chained fixup encoding has been removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainedPointer {
+    /// A pointer to an address within the image
+    Rebase(u64),
+    /// A pointer that is bound to the import at this index when the image is loaded
+    Bind { ordinal: u32, addend: i64 },
+}
+
+/// Decodes a raw pointer from a fixup chain.
+///
+/// `base_address` is the preferred load address of the image, which some formats store their
+/// rebase targets relative to.
+pub fn decode_chained_pointer(pointer_format: u16, raw: u64, base_address: u64) -> ChainedPointer {
+    match pointer_format {
+        // DYLD_CHAINED_PTR_ARM64E, DYLD_CHAINED_PTR_ARM64E_KERNEL,
+        // DYLD_CHAINED_PTR_ARM64E_USERLAND, DYLD_CHAINED_PTR_ARM64E_USERLAND24
+        1 | 7 | 9 | 12 => {
+            let is_auth = raw >> 63 != 0;
+            let is_bind = (raw >> 62) & 1 != 0;
+            match (is_bind, is_auth) {
+                (true, _) => {
+                    let ordinal = if pointer_format == 12 {
+                        raw & 0xff_ffff
+                    } else {
+                        raw & 0xffff
+                    } as u32;
+                    // Authenticated binds have no addend
+                    let addend = if is_auth {
+                        0
+                    } else {
+                        // 19 bit signed addend
+                        (((raw >> 32) & 0x7_ffff) as i64) << 45 >> 45
+                    };
+                    ChainedPointer::Bind { ordinal, addend }
+                }
+                // Authenticated rebases are always relative to the image base
+                (false, true) => ChainedPointer::Rebase(base_address + (raw & 0xffff_ffff)),
+                (false, false) => {
+                    let target = raw & 0x7ff_ffff_ffff;
+                    let high8 = (raw >> 43) & 0xff;
+                    let target = if pointer_format == 1 {
+                        target
+                    } else {
+                        base_address + target
+                    };
+                    ChainedPointer::Rebase(high8 << 56 | target)
+                }
+            }
+        }
+        // DYLD_CHAINED_PTR_64, DYLD_CHAINED_PTR_64_OFFSET
+        2 | 6 => {
+            if raw >> 63 != 0 {
+                ChainedPointer::Bind {
+                    ordinal: (raw & 0xff_ffff) as u32,
+                    addend: ((raw >> 24) & 0xff) as i64,
+                }
+            } else {
+                let target = raw & 0xf_ffff_ffff;
+                let high8 = (raw >> 36) & 0xff;
+                let target = if pointer_format == 2 {
+                    target
+                } else {
+                    base_address + target
+                };
+                ChainedPointer::Rebase(high8 << 56 | target)
+            }
+        }
+        _ => ChainedPointer::Rebase(raw),
+    }
+}
+
+fn pointer_format_name(pointer_format: u16) -> &'static str {
+    match pointer_format {
+        1 => "DYLD_CHAINED_PTR_ARM64E",
+        2 => "DYLD_CHAINED_PTR_64",
+        3 => "DYLD_CHAINED_PTR_32",
+        4 => "DYLD_CHAINED_PTR_32_CACHE",
+        5 => "DYLD_CHAINED_PTR_32_FIRMWARE",
+        6 => "DYLD_CHAINED_PTR_64_OFFSET",
+        7 => "DYLD_CHAINED_PTR_ARM64E_KERNEL",
+        8 => "DYLD_CHAINED_PTR_64_KERNEL_CACHE",
+        9 => "DYLD_CHAINED_PTR_ARM64E_USERLAND",
+        10 => "DYLD_CHAINED_PTR_ARM64E_FIRMWARE",
+        11 => "DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE",
+        12 => "DYLD_CHAINED_PTR_ARM64E_USERLAND24",
+        _ => "unknown",
+    }
+}
+
+impl fmt::Display for ChainedFixups {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Fixups Version: {}", self.fixups_version)?;
+        writeln!(f, "Imports Format: {}", self.imports_format)?;
+        writeln!(f, "Symbols Format: {}", self.symbols_format)?;
+        for (i, segment) in self.segments.iter().enumerate() {
+            if let Some(segment) = segment {
+                writeln!(
+                    f,
+                    "Segment {}: {} ({}), offset {:x}, {} pages of {} bytes",
+                    i,
+                    pointer_format_name(segment.pointer_format),
+                    segment.pointer_format,
+                    segment.segment_offset,
+                    segment.page_starts.len(),
+                    segment.page_size
+                )?;
+            }
+        }
+        writeln!(f, "Number of imports: {}", self.imports.len())?;
+        for (i, import) in self.imports.iter().enumerate() {
+            write!(
+                f,
+                "Import {}: {} (library {})",
+                i, import.name, import.library_ordinal
+            )?;
+            if import.weak_import {
+                write!(f, " weak")?;
+            }
+            if import.addend != 0 {
+                write!(f, " + {:#x}", import.addend)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_64_bit_pointers() {
+        // A rebase with a high byte of 0x80 and 5 as the stride to the next pointer
+        let raw = 0x80 << 36 | 5 << 51 | 0x1_0000_4000;
+        assert_eq!(
+            decode_chained_pointer(2, raw, 0),
+            ChainedPointer::Rebase(0x8000_0001_0000_4000)
+        );
+        assert_eq!(
+            decode_chained_pointer(6, 0x4000, 0x1_0000_0000),
+            ChainedPointer::Rebase(0x1_0000_4000)
+        );
+        assert_eq!(
+            decode_chained_pointer(2, 1 << 63 | 0x12 << 24 | 7, 0),
+            ChainedPointer::Bind {
+                ordinal: 7,
+                addend: 0x12
+            }
+        );
+    }
+
+    #[test]
+    fn decodes_arm64e_pointers() {
+        // An authenticated rebase, with key and diversity bits set
+        let raw = 1 << 63 | 0x1234 << 32 | 0x4000;
+        assert_eq!(
+            decode_chained_pointer(1, raw, 0x1_0000_0000),
+            ChainedPointer::Rebase(0x1_0000_4000)
+        );
+        // A bind with a 19 bit addend of -8
+        let raw = 1 << 62 | (-8i64 as u64 & 0x7_ffff) << 32 | 3;
+        assert_eq!(
+            decode_chained_pointer(1, raw, 0),
+            ChainedPointer::Bind {
+                ordinal: 3,
+                addend: -8
+            }
+        );
+        // DYLD_CHAINED_PTR_ARM64E_USERLAND24 has 24 bit ordinals
+        assert_eq!(
+            decode_chained_pointer(12, 1 << 62 | 0x12_3456, 0),
+            ChainedPointer::Bind {
+                ordinal: 0x12_3456,
+                addend: 0
+            }
+        );
+    }
+
+    #[test]
+    fn parses_imports() {
+        let mut blob = Vec::new();
+        // The header: version, starts, imports and symbols offsets, import count and formats
+        for field in [0, 28, 32, 40, 2, 1, 0] {
+            blob.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        // No segments
+        blob.extend_from_slice(&0u32.to_le_bytes());
+        // _foo from library 1, then a weak _bar from library 2
+        blob.extend_from_slice(&(1u32 | 1 << 9).to_le_bytes());
+        blob.extend_from_slice(&(2u32 | 1 << 8 | 6 << 9).to_le_bytes());
+        blob.extend_from_slice(b"\0_foo\0_bar\0");
+        let (_, fixups) = ChainedFixups::parse(&blob).unwrap();
+        let imports: Vec<_> = fixups
+            .imports
+            .iter()
+            .map(|import| {
+                (
+                    import.name.as_str(),
+                    import.library_ordinal,
+                    import.weak_import,
+                )
+            })
+            .collect();
+        assert_eq!(imports, [("_foo", 1, false), ("_bar", 2, true)]);
+    }
+
+    #[test]
+    fn rejects_import_counts_past_the_end() {
+        let mut blob = Vec::new();
+        for field in [0, 28, 32, 32, u32::MAX, 1, 0] {
+            blob.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        blob.extend_from_slice(&0u32.to_le_bytes());
+        assert!(ChainedFixups::parse(&blob).is_err());
+    }
+}
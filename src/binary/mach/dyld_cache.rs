@@ -0,0 +1,807 @@
+//! The dyld shared cache, which holds the system libraries of macOS and iOS prelinked together
+
+use std::fmt;
+use std::path::Path;
+
+use nom::bytes::complete::{tag, take};
+use nom::combinator::map;
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_u32, le_u64};
+use nom::sequence::tuple;
+
+use super::address_space::{AddressSpace, PointerEncoding};
+use super::symbols::{parse_symbols, Symbol};
+use super::Mach;
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse;
+
+pub(crate) const MAGIC: &[u8] = b"dyld_v1";
+
+/// Offsets of the header fields that decide which layout the arrays it points to use
+const SYMBOL_FILE_UUID_OFFSET: u32 = 400;
+const CACHE_SUB_TYPE_OFFSET: u32 = 456;
+
+/// A dyld shared cache, made up of the main cache file and any sub-caches next to it
+#[derive(Debug)]
+pub struct DyldCache {
+    pub main: DyldCacheFile,
+    pub sub_caches: Vec<SubCache>,
+    pub images: Vec<DyldCacheImage>,
+    pub local_symbols: Option<LocalSymbols>,
+    /// The `.symbols` file, if the local symbols are stored there rather than in the main cache
+    symbols_file: Option<Vec<u8>>,
+}
+
+/// One file of a shared cache, with the mappings of the file into the shared region
+#[derive(Debug)]
+pub struct DyldCacheFile {
+    pub header: DyldCacheHeader,
+    pub mappings: Vec<DyldCacheMapping>,
+    data: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct DyldCacheHeader {
+    /// `dyld_v1` followed by the architecture, e.g. `dyld_v1  arm64e`
+    pub magic: String,
+    pub mapping_offset: u32,
+    pub mapping_count: u32,
+    pub images_offset: u32,
+    pub images_count: u32,
+    pub dyld_base_address: u64,
+    pub code_signature_offset: u64,
+    pub code_signature_size: u64,
+    pub local_symbols_offset: u64,
+    pub local_symbols_size: u64,
+    pub uuid: [u8; 16],
+    pub cache_type: u64,
+    pub platform: u32,
+    pub format_version: u32,
+    pub shared_region_start: u64,
+    pub shared_region_size: u64,
+    pub max_slide: u64,
+    pub mapping_with_slide_offset: u32,
+    pub mapping_with_slide_count: u32,
+    pub os_version: u32,
+    pub sub_cache_array_offset: u32,
+    pub sub_cache_array_count: u32,
+    pub symbol_file_uuid: [u8; 16],
+    pub cache_sub_type: u32,
+}
+
+#[derive(Debug)]
+pub struct DyldCacheMapping {
+    pub address: u64,
+    pub size: u64,
+    pub file_offset: u64,
+    pub max_protection: u32,
+    pub initial_protection: u32,
+    pub flags: u64,
+    pub slide_info: Option<SlideInfo>,
+}
+
+/// The header of the slide info of a mapping, which describes how the pointers in it are encoded
+#[derive(Debug)]
+pub struct SlideInfo {
+    pub version: u32,
+    pub page_size: u32,
+    pub page_starts_count: u32,
+    pub delta_mask: u64,
+    pub value_add: u64,
+}
+
+/// A sub-cache file, which continues the shared region of the main cache
+#[derive(Debug)]
+pub struct SubCache {
+    pub uuid: [u8; 16],
+    /// Offset of the sub-cache's mappings from the start of the shared region
+    pub vm_offset: u64,
+    /// Appended to the path of the main cache to get the path of the sub-cache
+    pub suffix: String,
+    pub cache: BinDumpResult<DyldCacheFile>,
+}
+
+#[derive(Debug)]
+pub struct DyldCacheImage {
+    /// Address of the image's mach header
+    pub address: u64,
+    pub modification_time: u64,
+    pub inode: u64,
+    pub path: String,
+}
+
+/// The symbols stripped from the images in the cache, kept to symbolicate them
+#[derive(Debug)]
+pub struct LocalSymbols {
+    nlist_offset: u64,
+    pub nlist_count: u32,
+    strings_offset: u64,
+    strings_size: u32,
+    pub entries: Vec<LocalSymbolsEntry>,
+}
+
+#[derive(Debug)]
+pub struct LocalSymbolsEntry {
+    /// Address of the mach header of the image these symbols belong to
+    pub dylib_address: u64,
+    pub nlist_start_index: u32,
+    pub nlist_count: u32,
+}
+
+/// A symbol stripped from an image of the cache
+pub type LocalSymbol = Symbol;
+
+impl DyldCacheHeader {
+    fn parse(input: parse::Input) -> BinDumpResult<Self> {
+        let (_, (magic, mapping_offset)) =
+            context("Parse Dyld Cache Header", tuple((take(16usize), le_u32)))(input)?;
+        // The header has grown over time and the mappings directly follow it, so any field past
+        // `mapping_offset` is missing from older caches and reads as 0
+        let mut header = [0u8; 0x200];
+        let length = (mapping_offset as usize).min(header.len()).min(input.len());
+        header[..length].copy_from_slice(&input[..length]);
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(header[offset..offset + 8].try_into().unwrap());
+        let uuid_at =
+            |offset: usize| -> [u8; 16] { header[offset..offset + 16].try_into().unwrap() };
+
+        let (images_offset, images_count) = match u32_at(448) {
+            0 => (u32_at(24), u32_at(28)),
+            images_offset => (images_offset, u32_at(452)),
+        };
+        let end = magic.iter().position(|&b| b == 0).unwrap_or(magic.len());
+        Ok(Self {
+            magic: String::from_utf8_lossy(&magic[..end]).into_owned(),
+            mapping_offset,
+            mapping_count: u32_at(20),
+            images_offset,
+            images_count,
+            dyld_base_address: u64_at(32),
+            code_signature_offset: u64_at(40),
+            code_signature_size: u64_at(48),
+            local_symbols_offset: u64_at(72),
+            local_symbols_size: u64_at(80),
+            uuid: uuid_at(88),
+            cache_type: u64_at(104),
+            platform: u32_at(216),
+            format_version: u32_at(220) & 0xff,
+            shared_region_start: u64_at(224),
+            shared_region_size: u64_at(232),
+            max_slide: u64_at(240),
+            mapping_with_slide_offset: u32_at(312),
+            mapping_with_slide_count: u32_at(316),
+            os_version: u32_at(364),
+            sub_cache_array_offset: u32_at(392),
+            sub_cache_array_count: u32_at(396),
+            symbol_file_uuid: uuid_at(400),
+            cache_sub_type: u32_at(456),
+        })
+    }
+
+    /// Whether the header is new enough to have the field at `offset`
+    fn has_field(&self, offset: u32) -> bool {
+        self.mapping_offset > offset
+    }
+}
+
+impl DyldCacheMapping {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        context(
+            "Parse Dyld Cache Mapping",
+            map(
+                tuple((le_u64, le_u64, le_u64, le_u32, le_u32)),
+                |(address, size, file_offset, max_protection, initial_protection)| Self {
+                    address,
+                    size,
+                    file_offset,
+                    max_protection,
+                    initial_protection,
+                    flags: 0,
+                    slide_info: None,
+                },
+            ),
+        )(input)
+    }
+
+    /// Parses a `dyld_cache_mapping_and_slide_info`, reading the slide info it points to out of
+    /// `data`
+    fn parse_with_slide<'a>(
+        data: &'a [u8],
+    ) -> impl FnMut(parse::Input<'a>) -> parse::ParseResult<'a, Self> {
+        move |input| {
+            let (
+                input,
+                (
+                    address,
+                    size,
+                    file_offset,
+                    slide_info_offset,
+                    slide_info_size,
+                    flags,
+                    max_protection,
+                    initial_protection,
+                ),
+            ) = context(
+                "Parse Dyld Cache Mapping With Slide",
+                tuple((
+                    le_u64, le_u64, le_u64, le_u64, le_u64, le_u64, le_u32, le_u32,
+                )),
+            )(input)?;
+            let slide_info = if slide_info_size == 0 {
+                None
+            } else {
+                data.get(slide_info_offset as usize..)
+                    .and_then(|slide_info| SlideInfo::parse(slide_info).ok())
+                    .map(|(_, slide_info)| slide_info)
+            };
+            Ok((
+                input,
+                Self {
+                    address,
+                    size,
+                    file_offset,
+                    max_protection,
+                    initial_protection,
+                    flags,
+                    slide_info,
+                },
+            ))
+        }
+    }
+}
+
+impl SlideInfo {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (_, (version, page_size)) =
+            context("Parse Slide Info", tuple((le_u32, le_u32)))(input)?;
+        let (delta_mask, value_add, page_starts_count) = match version {
+            2 | 4 => {
+                let (_, (_, page_starts_count, _, _, delta_mask, value_add)) =
+                    tuple((le_u32, le_u32, le_u32, le_u32, le_u64, le_u64))(&input[8..])?;
+                (delta_mask, value_add, page_starts_count)
+            }
+            3 | 5 => {
+                let (_, (page_starts_count, _, value_add)) =
+                    tuple((le_u32, le_u32, le_u64))(&input[8..])?;
+                (0, value_add, page_starts_count)
+            }
+            _ => (0, 0, 0),
+        };
+        Ok((
+            input,
+            Self {
+                version,
+                page_size,
+                page_starts_count,
+                delta_mask,
+                value_add,
+            },
+        ))
+    }
+
+    /// How the pointers in the mapping are encoded, if this is a 64 bit slide info version
+    fn pointer_encoding(&self) -> Option<PointerEncoding> {
+        match self.version {
+            2 => Some(PointerEncoding::CacheSlideV2 {
+                delta_mask: self.delta_mask,
+                value_add: self.value_add,
+            }),
+            3 => Some(PointerEncoding::CacheSlideV3 {
+                auth_value_add: self.value_add,
+            }),
+            5 => Some(PointerEncoding::CacheSlideV5 {
+                value_add: self.value_add,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl DyldCacheFile {
+    fn parse(data: Vec<u8>) -> BinDumpResult<Self> {
+        context("Dyld Cache Magic", tag(MAGIC))(data.as_slice())?;
+        let header = DyldCacheHeader::parse(&data)?;
+        let mappings = if header.mapping_with_slide_count != 0 {
+            let input = data
+                .get(header.mapping_with_slide_offset as usize..)
+                .unwrap_or_default();
+            count(
+                DyldCacheMapping::parse_with_slide(&data),
+                header.mapping_with_slide_count as usize,
+            )(input)?
+            .1
+        } else {
+            let input = data
+                .get(header.mapping_offset as usize..)
+                .unwrap_or_default();
+            count(DyldCacheMapping::parse, header.mapping_count as usize)(input)?.1
+        };
+        Ok(Self {
+            header,
+            mappings,
+            data,
+        })
+    }
+
+    fn string_at(&self, offset: u64) -> String {
+        let bytes = self.data.get(offset as usize..).unwrap_or_default();
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).into_owned()
+    }
+
+    /// Converts a file offset into an address in the shared region
+    fn address_of_offset(&self, offset: u64) -> Option<u64> {
+        self.mappings
+            .iter()
+            .find(|mapping| {
+                offset >= mapping.file_offset && offset - mapping.file_offset < mapping.size
+            })
+            .map(|mapping| mapping.address.wrapping_add(offset - mapping.file_offset))
+    }
+}
+
+impl DyldCache {
+    /// Loads a cache from the path of its main file, along with the sub-caches and the `.symbols`
+    /// file next to it
+    pub(crate) fn load(path: &Path, data: Vec<u8>) -> BinDumpResult<Self> {
+        Self::parse_with_files(data, |suffix| {
+            let mut sub_cache_path = path.as_os_str().to_owned();
+            sub_cache_path.push(suffix);
+            Ok(std::fs::read(sub_cache_path)?)
+        })
+    }
+
+    /// Parses the main file of a cache on its own, without any of its sub-caches
+    pub(crate) fn parse(data: Vec<u8>) -> BinDumpResult<Self> {
+        Self::parse_with_files(data, |_| {
+            Err(BinDumpError::Unsupported {
+                format: "Sub-caches can only be loaded alongside the main cache file".to_string(),
+            })
+        })
+    }
+
+    /// Parses the main file of a cache, using `open` to read the file with the given suffix
+    fn parse_with_files(
+        data: Vec<u8>,
+        open: impl Fn(&str) -> BinDumpResult<Vec<u8>>,
+    ) -> BinDumpResult<Self> {
+        let main = DyldCacheFile::parse(data)?;
+        let header = &main.header;
+
+        let images = {
+            let input = main
+                .data
+                .get(header.images_offset as usize..)
+                .unwrap_or_default();
+            let (_, images) = context(
+                "Parse Dyld Cache Images",
+                count(
+                    tuple((le_u64, le_u64, le_u64, le_u32, le_u32)),
+                    header.images_count as usize,
+                ),
+            )(input)?;
+            images
+                .into_iter()
+                .map(
+                    |(address, modification_time, inode, path_offset, _)| DyldCacheImage {
+                        address,
+                        modification_time,
+                        inode,
+                        path: main.string_at(path_offset as u64),
+                    },
+                )
+                .collect()
+        };
+
+        let sub_caches = {
+            let input = main
+                .data
+                .get(header.sub_cache_array_offset as usize..)
+                .unwrap_or_default();
+            // Older caches name their sub-caches `.1`, `.2` and so on, while newer ones store the
+            // suffix in each entry
+            let entries = if header.has_field(CACHE_SUB_TYPE_OFFSET) {
+                count(
+                    tuple((take(16usize), le_u64, take(32usize))),
+                    header.sub_cache_array_count as usize,
+                )(input)?
+                .1
+                .into_iter()
+                .map(|(uuid, vm_offset, suffix): (&[u8], u64, &[u8])| {
+                    let end = suffix.iter().position(|&b| b == 0).unwrap_or(suffix.len());
+                    (
+                        uuid,
+                        vm_offset,
+                        String::from_utf8_lossy(&suffix[..end]).into_owned(),
+                    )
+                })
+                .collect::<Vec<_>>()
+            } else {
+                count(
+                    tuple((take(16usize), le_u64)),
+                    header.sub_cache_array_count as usize,
+                )(input)?
+                .1
+                .into_iter()
+                .enumerate()
+                .map(|(i, (uuid, vm_offset))| (uuid, vm_offset, format!(".{}", i + 1)))
+                .collect()
+            };
+            entries
+                .into_iter()
+                .map(|(uuid, vm_offset, suffix)| {
+                    let uuid: [u8; 16] = uuid.try_into().unwrap();
+                    let cache = open(&suffix)
+                        .and_then(DyldCacheFile::parse)
+                        .and_then(|cache| {
+                            if cache.header.uuid == uuid {
+                                Ok(cache)
+                            } else {
+                                Err(BinDumpError::ParseError {
+                                    error: format!(
+                                        "The UUID of sub-cache {} doesn't match the main cache",
+                                        suffix
+                                    ),
+                                })
+                            }
+                        });
+                    SubCache {
+                        uuid,
+                        vm_offset,
+                        suffix,
+                        cache,
+                    }
+                })
+                .collect()
+        };
+
+        // Newer caches move the local symbols into a separate `.symbols` file
+        let symbols_file = if header.symbol_file_uuid != [0; 16] {
+            open(".symbols").ok().filter(|symbols| {
+                DyldCacheHeader::parse(symbols)
+                    .map(|symbols| symbols.uuid == main.header.symbol_file_uuid)
+                    .unwrap_or(false)
+            })
+        } else {
+            None
+        };
+        let local_symbols = match &symbols_file {
+            Some(symbols) => LocalSymbols::parse(symbols, &main)?,
+            None => LocalSymbols::parse(&main.data, &main)?,
+        };
+
+        Ok(Self {
+            main,
+            sub_caches,
+            images,
+            local_symbols,
+            symbols_file,
+        })
+    }
+
+    /// All of the cache files that were loaded, starting with the main one
+    pub fn files(&self) -> impl Iterator<Item = &DyldCacheFile> {
+        std::iter::once(&self.main).chain(
+            self.sub_caches
+                .iter()
+                .filter_map(|sub_cache| sub_cache.cache.as_ref().ok()),
+        )
+    }
+
+    /// Finds an image by its install name, or just by its file name
+    pub fn find_image(&self, path: &str) -> Option<&DyldCacheImage> {
+        self.images
+            .iter()
+            .find(|image| image.path == path)
+            .or_else(|| {
+                self.images
+                    .iter()
+                    .find(|image| image.path.rsplit('/').next() == Some(path))
+            })
+    }
+
+    /// The shared region as it is mapped by dyld, before it is slid
+    pub(crate) fn address_space(&self) -> AddressSpace<'_> {
+        let base_address = self
+            .main
+            .mappings
+            .first()
+            .map(|mapping| mapping.address)
+            .unwrap_or(self.main.header.shared_region_start);
+        let mut space = AddressSpace::new(base_address);
+        for file in self.files() {
+            for mapping in &file.mappings {
+                let start = (mapping.file_offset as usize).min(file.data.len());
+                let end = mapping
+                    .file_offset
+                    .saturating_add(mapping.size)
+                    .min(file.data.len() as u64) as usize;
+                space.add_region(mapping.address, mapping.size, &file.data[start..end]);
+            }
+        }
+        let pointer_encoding = self
+            .files()
+            .flat_map(|file| &file.mappings)
+            .find_map(|mapping| mapping.slide_info.as_ref()?.pointer_encoding());
+        if let Some(pointer_encoding) = pointer_encoding {
+            space.set_pointer_encoding(pointer_encoding);
+        }
+        space
+    }
+
+    /// Parses an image in the cache, reading it out of the cache's address space
+    pub fn image(&self, image: &DyldCacheImage) -> BinDumpResult<Mach> {
+        Mach::parse_in_address_space(image.address, &self.address_space())
+    }
+
+    /// The local symbols that were stripped from an image when it was added to the cache
+    pub fn image_local_symbols(&self, image: &DyldCacheImage) -> Vec<LocalSymbol> {
+        let Some(local_symbols) = &self.local_symbols else {
+            return Vec::new();
+        };
+        let Some(entry) = local_symbols
+            .entries
+            .iter()
+            .find(|entry| entry.dylib_address == image.address)
+        else {
+            return Vec::new();
+        };
+        let data = self.symbols_file.as_deref().unwrap_or(&self.main.data);
+        let strings = data
+            .get(local_symbols.strings_offset as usize..)
+            .and_then(|strings| strings.get(..local_symbols.strings_size as usize))
+            .unwrap_or_default();
+        let start = local_symbols.nlist_offset as usize + entry.nlist_start_index as usize * 16;
+        let input = data.get(start..).unwrap_or_default();
+        let symbols = parse_symbols(input, entry.nlist_count as usize, strings);
+        symbols.map(|(_, symbols)| symbols).unwrap_or_default()
+    }
+}
+
+impl LocalSymbols {
+    /// Parses the `dyld_cache_local_symbols_info` of a cache. `data` is the file holding the
+    /// symbols, which is either the main cache or its `.symbols` file.
+    fn parse(data: &[u8], main: &DyldCacheFile) -> BinDumpResult<Option<Self>> {
+        let header = DyldCacheHeader::parse(data)?;
+        if header.local_symbols_offset == 0 {
+            return Ok(None);
+        }
+        let info_offset = header.local_symbols_offset;
+        let input = data.get(info_offset as usize..).unwrap_or_default();
+        let (
+            _,
+            (
+                nlist_offset,
+                nlist_count,
+                strings_offset,
+                strings_size,
+                entries_offset,
+                entries_count,
+            ),
+        ) = context(
+            "Parse Local Symbols Info",
+            tuple((le_u32, le_u32, le_u32, le_u32, le_u32, le_u32)),
+        )(input)?;
+
+        let input = input.get(entries_offset as usize..).unwrap_or_default();
+        // Newer caches identify each dylib by its offset in the shared region, while older ones
+        // use its file offset in the main cache
+        let entries = if main.header.has_field(SYMBOL_FILE_UUID_OFFSET) {
+            let base_address = main
+                .mappings
+                .first()
+                .map(|mapping| mapping.address)
+                .unwrap_or(0);
+            count(tuple((le_u64, le_u32, le_u32)), entries_count as usize)(input)?
+                .1
+                .into_iter()
+                .map(
+                    |(dylib_offset, nlist_start_index, nlist_count)| LocalSymbolsEntry {
+                        dylib_address: base_address.wrapping_add(dylib_offset),
+                        nlist_start_index,
+                        nlist_count,
+                    },
+                )
+                .collect()
+        } else {
+            count(tuple((le_u32, le_u32, le_u32)), entries_count as usize)(input)?
+                .1
+                .into_iter()
+                .map(
+                    |(dylib_offset, nlist_start_index, nlist_count)| LocalSymbolsEntry {
+                        dylib_address: main.address_of_offset(dylib_offset as u64).unwrap_or(0),
+                        nlist_start_index,
+                        nlist_count,
+                    },
+                )
+                .collect()
+        };
+
+        Ok(Some(Self {
+            nlist_offset: info_offset + nlist_offset as u64,
+            nlist_count,
+            strings_offset: info_offset + strings_offset as u64,
+            strings_size,
+            entries,
+        }))
+    }
+}
+
+fn write_uuid(f: &mut fmt::Formatter<'_>, uuid: &[u8; 16]) -> fmt::Result {
+    for (i, byte) in uuid.iter().enumerate() {
+        if matches!(i, 4 | 6 | 8 | 10) {
+            write!(f, "-")?;
+        }
+        write!(f, "{:02X}", byte)?;
+    }
+    Ok(())
+}
+
+fn protection(protection: u32) -> String {
+    let flag = |bit: u32, c: char| if protection & bit != 0 { c } else { '-' };
+    [flag(1, 'r'), flag(2, 'w'), flag(4, 'x')].iter().collect()
+}
+
+impl fmt::Display for DyldCacheFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = &self.header;
+        writeln!(f, "Magic: {}", header.magic)?;
+        write!(f, "Uuid: ")?;
+        write_uuid(f, &header.uuid)?;
+        writeln!(f)?;
+        writeln!(f, "Cache Type: {}", header.cache_type)?;
+        writeln!(f, "Platform: {}", header.platform)?;
+        writeln!(f, "Format Version: {}", header.format_version)?;
+        writeln!(f, "OS Version: {:#x}", header.os_version)?;
+        writeln!(f, "Dyld Base Address: {:#x}", header.dyld_base_address)?;
+        writeln!(
+            f,
+            "Shared Region: {:#x}, {:#x} bytes",
+            header.shared_region_start, header.shared_region_size
+        )?;
+        writeln!(f, "Max Slide: {:#x}", header.max_slide)?;
+        writeln!(
+            f,
+            "Code Signature: offset {:#x}, {} bytes",
+            header.code_signature_offset, header.code_signature_size
+        )?;
+        for (i, mapping) in self.mappings.iter().enumerate() {
+            write!(
+                f,
+                "Mapping {}: {:#x}-{:#x}, file offset {:#x}, {}/{}",
+                i,
+                mapping.address,
+                mapping.address + mapping.size,
+                mapping.file_offset,
+                protection(mapping.initial_protection),
+                protection(mapping.max_protection),
+            )?;
+            if mapping.flags != 0 {
+                write!(f, ", flags {:#x}", mapping.flags)?;
+            }
+            if let Some(slide_info) = &mapping.slide_info {
+                write!(
+                    f,
+                    ", slide info v{} ({} pages of {} bytes)",
+                    slide_info.version, slide_info.page_starts_count, slide_info.page_size
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DyldCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Dyld Shared Cache:")?;
+        write!(f, "{}", self.main)?;
+        writeln!(f, "Number of sub-caches: {}", self.sub_caches.len())?;
+        for (i, sub_cache) in self.sub_caches.iter().enumerate() {
+            write!(
+                f,
+                "Sub-cache {}: {}, vm offset {:#x}, uuid ",
+                i, sub_cache.suffix, sub_cache.vm_offset
+            )?;
+            write_uuid(f, &sub_cache.uuid)?;
+            writeln!(f)?;
+            match &sub_cache.cache {
+                Ok(cache) => write!(f, "{}", cache)?,
+                Err(e) => writeln!(f, "Error {}", e)?,
+            }
+        }
+        writeln!(f, "Number of images: {}", self.images.len())?;
+        for image in &self.images {
+            writeln!(f, "{:#x} {}", image.address, image.path)?;
+        }
+        if let Some(local_symbols) = &self.local_symbols {
+            writeln!(
+                f,
+                "Local Symbols: {} symbols for {} images{}",
+                local_symbols.nlist_count,
+                local_symbols.entries.len(),
+                if self.symbols_file.is_some() {
+                    ", in the .symbols file"
+                } else {
+                    ""
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An old style cache with a header that ends at the mappings, one mapping of the whole file
+    /// at 0x1_8000_0000 and one image, `/usr/lib/libfoo.dylib`
+    fn cache(mapping_file_offset: u64) -> Vec<u8> {
+        let mut data = b"dyld_v1  arm64e\0".to_vec();
+        for field in [0x20u32, 1, 0x40, 1] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.resize(0x20, 0);
+        for field in [0x1_8000_0000u64, 0x80, mapping_file_offset] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(&[5, 0, 0, 0, 5, 0, 0, 0]);
+        for field in [0x1_8000_0100u64, 1, 2, 0x60] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(b"/usr/lib/libfoo.dylib\0");
+        data.resize(0x80, 0);
+        data
+    }
+
+    #[test]
+    fn reads_mappings_and_images() {
+        let cache = DyldCache::parse(cache(0)).unwrap();
+        assert_eq!(cache.main.header.magic, "dyld_v1  arm64e");
+        assert_eq!(cache.main.mappings.len(), 1);
+        assert!(cache.sub_caches.is_empty());
+        assert!(cache.local_symbols.is_none());
+
+        let image = cache.find_image("libfoo.dylib").unwrap();
+        assert_eq!(image.path, "/usr/lib/libfoo.dylib");
+        assert_eq!(image.address, 0x1_8000_0100);
+        assert!(cache.find_image("/usr/lib/libbar.dylib").is_none());
+
+        assert_eq!(cache.main.address_of_offset(0x60), Some(0x1_8000_0060));
+        assert_eq!(cache.main.address_of_offset(0x80), None);
+        assert_eq!(
+            cache.address_space().read_string(0x1_8000_0060).unwrap(),
+            "/usr/lib/libfoo.dylib"
+        );
+    }
+
+    #[test]
+    fn ignores_mappings_past_the_end_of_the_file() {
+        let cache = DyldCache::parse(cache(u64::MAX)).unwrap();
+        assert_eq!(cache.address_space().read(0x1_8000_0000, 1), None);
+    }
+
+    #[test]
+    fn decodes_slide_info_headers() {
+        let mut data = Vec::new();
+        for field in [2u32, 0x4000, 0, 3, 0, 0] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(&0x00ff_ff00_0000_0000u64.to_le_bytes());
+        data.extend_from_slice(&0x1_8000_0000u64.to_le_bytes());
+        let (_, slide_info) = SlideInfo::parse(&data).unwrap();
+        assert_eq!(slide_info.page_size, 0x4000);
+        assert_eq!(slide_info.page_starts_count, 3);
+        assert!(matches!(
+            slide_info.pointer_encoding(),
+            Some(PointerEncoding::CacheSlideV2 {
+                delta_mask: 0x00ff_ff00_0000_0000,
+                value_add: 0x1_8000_0000,
+            })
+        ));
+    }
+}
@@ -1,4 +1,3 @@
-use std::ffi::CStr;
 use std::fmt;
 
 use nom::bytes::complete::take;
@@ -28,8 +27,9 @@ impl LoadCommand {
                 tuple((complete::u32(endianness), complete::u32(endianness))),
             )(input)?;
             let (_input, command) = Command::parse(command_type, command_size, input, endianness)?;
+            let (rest, _) = take(command_size)(start)?;
             Ok((
-                &start[command_size as usize..],
+                rest,
                 LoadCommand {
                     size: command_size,
                     command,
@@ -49,59 +49,59 @@ impl fmt::Display for LoadCommand {
 #[derive(Debug)]
 pub enum Command {
     Segment(SegmentDetails),
-    SymbolTable,
-    SymbolSegment,
-    Thread,
-    UnixThread,
-    LoadFixedVmLibrary,
-    IdentifyFixedVmLibrary,
-    Identify,
-    IncludeFixedVmLibrary,
+    SymbolTable(SymbolTableDetails),
+    SymbolSegment(SymbolSegmentDetails),
+    Thread(ThreadDetails),
+    UnixThread(ThreadDetails),
+    LoadFixedVmLibrary(FixedVmLibraryDetails),
+    IdentifyFixedVmLibrary(FixedVmLibraryDetails),
+    Identify(IdentifyDetails),
+    IncludeFixedVmLibrary(FixedVmFileDetails),
     Prepage,
-    DynamicSymbolTable,
-    LoadDynamicLibrary,
-    IdentifyDynamicLibrary,
-    LoadDynamicLinker,
-    IdentifyDynamicLinker,
-    PreboundDynamicLibrary,
-    Routines,
-    SubFramework,
-    SubUmbrella,
-    SubClient,
-    SubLibrary,
-    TwoLevelHints,
-    PrebindChecksum,
-    LoadWeakDynamicLibrary,
+    DynamicSymbolTable(DynamicSymbolTableDetails),
+    LoadDynamicLibrary(DynamicLibraryDetails),
+    IdentifyDynamicLibrary(DynamicLibraryDetails),
+    LoadDynamicLinker(DynamicLinkerDetails),
+    IdentifyDynamicLinker(DynamicLinkerDetails),
+    PreboundDynamicLibrary(PreboundDynamicLibraryDetails),
+    Routines(RoutinesDetails),
+    SubFramework(SubFrameworkDetails),
+    SubUmbrella(SubUmbrellaDetails),
+    SubClient(SubClientDetails),
+    SubLibrary(SubLibraryDetails),
+    TwoLevelHints(TwoLevelHintsDetails),
+    PrebindChecksum(PrebindChecksumDetails),
+    LoadWeakDynamicLibrary(DynamicLibraryDetails),
     Segment64(Segment64Details),
-    Routines64,
-    Uuid,
-    RPath,
-    CodeSignature,
-    SegmentSplitInfo,
-    ReexportDynamicLibrary,
-    LazyLoadDynamicLibrary,
-    EncryptionInfo,
-    DynamicLinkerInfo,
-    DynamicLinkerInfoOnly,
-    LoadUpwardDynamicLibrary,
-    VersionMinMacOsx,
-    VersionMinIphoneOs,
-    FunctionStarts,
-    DynamicLinkerEnvironment,
-    Main,
-    DataInCode,
-    SourceVersion,
-    DynamicLibraryCodeSignDrs,
-    EncryptionInfo64,
-    LinkerOption,
-    LinkerOptimizationHint,
-    VersionMinTvOs,
-    VersionMinWatchOs,
-    Note,
-    BuildVersion,
-    DynamicLinkerExportsTrie,
-    DynamicLinkerChainedFixups,
-    FileSetEntry,
+    Routines64(Routines64Details),
+    Uuid(UuidDetails),
+    RPath(RPathDetails),
+    CodeSignature(LinkEditDataDetails),
+    SegmentSplitInfo(LinkEditDataDetails),
+    ReexportDynamicLibrary(DynamicLibraryDetails),
+    LazyLoadDynamicLibrary(DynamicLibraryDetails),
+    EncryptionInfo(EncryptionInfoDetails),
+    DynamicLinkerInfo(DynamicLinkerInfoDetails),
+    DynamicLinkerInfoOnly(DynamicLinkerInfoDetails),
+    LoadUpwardDynamicLibrary(DynamicLibraryDetails),
+    VersionMinMacOsx(VersionMinDetails),
+    VersionMinIphoneOs(VersionMinDetails),
+    FunctionStarts(LinkEditDataDetails),
+    DynamicLinkerEnvironment(DynamicLinkerDetails),
+    Main(EntryPointDetails),
+    DataInCode(LinkEditDataDetails),
+    SourceVersion(SourceVersionDetails),
+    DynamicLibraryCodeSignDrs(LinkEditDataDetails),
+    EncryptionInfo64(EncryptionInfoDetails),
+    LinkerOption(LinkerOptionDetails),
+    LinkerOptimizationHint(LinkEditDataDetails),
+    VersionMinTvOs(VersionMinDetails),
+    VersionMinWatchOs(VersionMinDetails),
+    Note(NoteDetails),
+    BuildVersion(BuildVersionDetails),
+    DynamicLinkerExportsTrie(LinkEditDataDetails),
+    DynamicLinkerChainedFixups(LinkEditDataDetails),
+    FileSetEntry(FileSetEntryDetails),
 }
 
 impl Command {
@@ -111,149 +111,377 @@ impl Command {
         input: parse::Input,
         endianness: Endianness,
     ) -> parse::ParseResult<Self> {
+        // Everything after the command type and size, used to resolve `lc_str` offsets
+        let body_size = command_size
+            .checked_sub(8)
+            .ok_or_else(|| parse::failure(input, "Load command is smaller than its header"))?;
+        let (_, body) = context("Parse Load Command body", take(body_size))(input)?;
         match command_type {
             1 => context(
                 "Parse Segment",
                 map(SegmentDetails::parse(endianness), Self::Segment),
             )(input),
-            2 => Ok((&input[command_size as usize - 8..], Self::SymbolTable)),
-            3 => Ok((&input[command_size as usize - 8..], Self::SymbolSegment)),
-            4 => Ok((&input[command_size as usize - 8..], Self::Thread)),
-            5 => Ok((&input[command_size as usize - 8..], Self::UnixThread)),
-            6 => Ok((
-                &input[command_size as usize - 8..],
-                Self::LoadFixedVmLibrary,
-            )),
-            7 => Ok((
-                &input[command_size as usize - 8..],
-                Self::IdentifyFixedVmLibrary,
-            )),
-            8 => Ok((&input[command_size as usize - 8..], Self::Identify)),
-            9 => Ok((
-                &input[command_size as usize - 8..],
-                Self::IncludeFixedVmLibrary,
-            )),
+            2 => context(
+                "Parse Symbol Table",
+                map(SymbolTableDetails::parse(endianness), Self::SymbolTable),
+            )(body),
+            3 => context(
+                "Parse Symbol Segment",
+                map(SymbolSegmentDetails::parse(endianness), Self::SymbolSegment),
+            )(body),
+            4 => context(
+                "Parse Thread",
+                map(ThreadDetails::parse(endianness), Self::Thread),
+            )(body),
+            5 => context(
+                "Parse Unix Thread",
+                map(ThreadDetails::parse(endianness), Self::UnixThread),
+            )(body),
+            6 => context(
+                "Parse Load Fixed VM Library",
+                map(
+                    FixedVmLibraryDetails::parse(endianness),
+                    Self::LoadFixedVmLibrary,
+                ),
+            )(body),
+            7 => context(
+                "Parse Identify Fixed VM Library",
+                map(
+                    FixedVmLibraryDetails::parse(endianness),
+                    Self::IdentifyFixedVmLibrary,
+                ),
+            )(body),
+            8 => context(
+                "Parse Identify",
+                map(IdentifyDetails::parse, Self::Identify),
+            )(body),
+            9 => context(
+                "Parse Include Fixed VM Library",
+                map(
+                    FixedVmFileDetails::parse(endianness),
+                    Self::IncludeFixedVmLibrary,
+                ),
+            )(body),
             10 => Ok((&input[command_size as usize - 8..], Self::Prepage)),
-            11 => Ok((
-                &input[command_size as usize - 8..],
-                Self::DynamicSymbolTable,
-            )),
-            12 => Ok((
-                &input[command_size as usize - 8..],
-                Self::LoadDynamicLibrary,
-            )),
-            13 => Ok((
-                &input[command_size as usize - 8..],
-                Self::IdentifyDynamicLibrary,
-            )),
-            14 => Ok((&input[command_size as usize - 8..], Self::LoadDynamicLinker)),
-            15 => Ok((
-                &input[command_size as usize - 8..],
-                Self::IdentifyDynamicLinker,
-            )),
-            16 => Ok((
-                &input[command_size as usize - 8..],
-                Self::PreboundDynamicLibrary,
-            )),
-            17 => Ok((&input[command_size as usize - 8..], Self::Routines)),
-            18 => Ok((&input[command_size as usize - 8..], Self::SubFramework)),
-            19 => Ok((&input[command_size as usize - 8..], Self::SubUmbrella)),
-            20 => Ok((&input[command_size as usize - 8..], Self::SubClient)),
-            21 => Ok((&input[command_size as usize - 8..], Self::SubLibrary)),
-            22 => Ok((&input[command_size as usize - 8..], Self::TwoLevelHints)),
-            23 => Ok((&input[command_size as usize - 8..], Self::PrebindChecksum)),
+            11 => context(
+                "Parse Dynamic Symbol Table",
+                map(
+                    DynamicSymbolTableDetails::parse(endianness),
+                    Self::DynamicSymbolTable,
+                ),
+            )(body),
+            12 => context(
+                "Parse Load Dynamic Library",
+                map(
+                    DynamicLibraryDetails::parse(endianness),
+                    Self::LoadDynamicLibrary,
+                ),
+            )(body),
+            13 => context(
+                "Parse Identify Dynamic Library",
+                map(
+                    DynamicLibraryDetails::parse(endianness),
+                    Self::IdentifyDynamicLibrary,
+                ),
+            )(body),
+            14 => context(
+                "Parse Load Dynamic Linker",
+                map(
+                    DynamicLinkerDetails::parse(endianness),
+                    Self::LoadDynamicLinker,
+                ),
+            )(body),
+            15 => context(
+                "Parse Identify Dynamic Linker",
+                map(
+                    DynamicLinkerDetails::parse(endianness),
+                    Self::IdentifyDynamicLinker,
+                ),
+            )(body),
+            16 => context(
+                "Parse Prebound Dynamic Library",
+                map(
+                    PreboundDynamicLibraryDetails::parse(endianness),
+                    Self::PreboundDynamicLibrary,
+                ),
+            )(body),
+            17 => context(
+                "Parse Routines",
+                map(RoutinesDetails::parse(endianness), Self::Routines),
+            )(body),
+            18 => context(
+                "Parse Sub Framework",
+                map(SubFrameworkDetails::parse(endianness), Self::SubFramework),
+            )(body),
+            19 => context(
+                "Parse Sub Umbrella",
+                map(SubUmbrellaDetails::parse(endianness), Self::SubUmbrella),
+            )(body),
+            20 => context(
+                "Parse Sub Client",
+                map(SubClientDetails::parse(endianness), Self::SubClient),
+            )(body),
+            21 => context(
+                "Parse Sub Library",
+                map(SubLibraryDetails::parse(endianness), Self::SubLibrary),
+            )(body),
+            22 => context(
+                "Parse Two Level Hints",
+                map(TwoLevelHintsDetails::parse(endianness), Self::TwoLevelHints),
+            )(body),
+            23 => context(
+                "Parse Prebind Checksum",
+                map(
+                    PrebindChecksumDetails::parse(endianness),
+                    Self::PrebindChecksum,
+                ),
+            )(body),
             /* 24 */
-            0x80000018 => Ok((
-                &input[command_size as usize - 8..],
-                Self::LoadWeakDynamicLibrary,
-            )),
+            0x80000018 => context(
+                "Parse Load Weak Dynamic Library",
+                map(
+                    DynamicLibraryDetails::parse(endianness),
+                    Self::LoadWeakDynamicLibrary,
+                ),
+            )(body),
             25 => context(
                 "Parse Segment64",
                 map(Segment64Details::parse(endianness), Self::Segment64),
             )(input),
-            26 => Ok((&input[command_size as usize - 8..], Self::Routines64)),
-            27 => Ok((&input[command_size as usize - 8..], Self::Uuid)),
-            /* 28 */ 0x8000001c => Ok((&input[command_size as usize - 8..], Self::RPath)),
-            29 => Ok((&input[command_size as usize - 8..], Self::CodeSignature)),
-            30 => Ok((&input[command_size as usize - 8..], Self::SegmentSplitInfo)),
+            26 => context(
+                "Parse Routines64",
+                map(Routines64Details::parse(endianness), Self::Routines64),
+            )(body),
+            27 => context("Parse Uuid", map(UuidDetails::parse, Self::Uuid))(body),
+            /* 28 */
+            0x8000001c => context(
+                "Parse RPath",
+                map(RPathDetails::parse(endianness), Self::RPath),
+            )(body),
+            29 => context(
+                "Parse Code Signature",
+                map(LinkEditDataDetails::parse(endianness), Self::CodeSignature),
+            )(body),
+            30 => context(
+                "Parse Segment Split Info",
+                map(
+                    LinkEditDataDetails::parse(endianness),
+                    Self::SegmentSplitInfo,
+                ),
+            )(body),
             /* 31 */
-            0x8000001f => Ok((
-                &input[command_size as usize - 8..],
-                Self::ReexportDynamicLibrary,
-            )),
-            32 => Ok((
-                &input[command_size as usize - 8..],
-                Self::LazyLoadDynamicLibrary,
-            )),
-            33 => Ok((&input[command_size as usize - 8..], Self::EncryptionInfo)),
-            34 => Ok((&input[command_size as usize - 8..], Self::DynamicLinkerInfo)),
+            0x8000001f => context(
+                "Parse Reexport Dynamic Library",
+                map(
+                    DynamicLibraryDetails::parse(endianness),
+                    Self::ReexportDynamicLibrary,
+                ),
+            )(body),
+            32 => context(
+                "Parse Lazy Load Dynamic Library",
+                map(
+                    DynamicLibraryDetails::parse(endianness),
+                    Self::LazyLoadDynamicLibrary,
+                ),
+            )(body),
+            33 => context(
+                "Parse Encryption Info",
+                map(
+                    EncryptionInfoDetails::parse(endianness),
+                    Self::EncryptionInfo,
+                ),
+            )(body),
+            34 => context(
+                "Parse Dynamic Linker Info",
+                map(
+                    DynamicLinkerInfoDetails::parse(endianness),
+                    Self::DynamicLinkerInfo,
+                ),
+            )(body),
             /* 34 */
-            0x80000022 => Ok((
-                &input[command_size as usize - 8..],
-                Self::DynamicLinkerInfoOnly,
-            )),
+            0x80000022 => context(
+                "Parse Dynamic Linker Info Only",
+                map(
+                    DynamicLinkerInfoDetails::parse(endianness),
+                    Self::DynamicLinkerInfoOnly,
+                ),
+            )(body),
             /* 35 */
-            0x80000023 => Ok((
-                &input[command_size as usize - 8..],
-                Self::LoadUpwardDynamicLibrary,
-            )),
-            36 => Ok((&input[command_size as usize - 8..], Self::VersionMinMacOsx)),
-            37 => Ok((
-                &input[command_size as usize - 8..],
-                Self::VersionMinIphoneOs,
-            )),
-            38 => Ok((&input[command_size as usize - 8..], Self::FunctionStarts)),
-            39 => Ok((
-                &input[command_size as usize - 8..],
-                Self::DynamicLinkerEnvironment,
-            )),
-            /* 40 */ 0x80000028 => Ok((&input[command_size as usize - 8..], Self::Main)),
-            41 => Ok((&input[command_size as usize - 8..], Self::DataInCode)),
-            42 => Ok((&input[command_size as usize - 8..], Self::SourceVersion)),
-            43 => Ok((
-                &input[command_size as usize - 8..],
-                Self::DynamicLibraryCodeSignDrs,
-            )),
-            44 => Ok((&input[command_size as usize - 8..], Self::EncryptionInfo64)),
-            45 => Ok((&input[command_size as usize - 8..], Self::LinkerOption)),
-            46 => Ok((
-                &input[command_size as usize - 8..],
-                Self::LinkerOptimizationHint,
-            )),
-            47 => Ok((&input[command_size as usize - 8..], Self::VersionMinTvOs)),
-            48 => Ok((&input[command_size as usize - 8..], Self::VersionMinWatchOs)),
-            49 => Ok((&input[command_size as usize - 8..], Self::Note)),
-            50 => Ok((&input[command_size as usize - 8..], Self::BuildVersion)),
+            0x80000023 => context(
+                "Parse Load Upward Dynamic Library",
+                map(
+                    DynamicLibraryDetails::parse(endianness),
+                    Self::LoadUpwardDynamicLibrary,
+                ),
+            )(body),
+            36 => context(
+                "Parse Version Min MacOSX",
+                map(VersionMinDetails::parse(endianness), Self::VersionMinMacOsx),
+            )(body),
+            37 => context(
+                "Parse Version Min iPhoneOS",
+                map(
+                    VersionMinDetails::parse(endianness),
+                    Self::VersionMinIphoneOs,
+                ),
+            )(body),
+            38 => context(
+                "Parse Function Starts",
+                map(LinkEditDataDetails::parse(endianness), Self::FunctionStarts),
+            )(body),
+            39 => context(
+                "Parse Dynamic Linker Environment",
+                map(
+                    DynamicLinkerDetails::parse(endianness),
+                    Self::DynamicLinkerEnvironment,
+                ),
+            )(body),
+            /* 40 */
+            0x80000028 => context(
+                "Parse Main",
+                map(EntryPointDetails::parse(endianness), Self::Main),
+            )(body),
+            41 => context(
+                "Parse Data In Code",
+                map(LinkEditDataDetails::parse(endianness), Self::DataInCode),
+            )(body),
+            42 => context(
+                "Parse Source Version",
+                map(SourceVersionDetails::parse(endianness), Self::SourceVersion),
+            )(body),
+            43 => context(
+                "Parse Dynamic Library Code Signing DRs",
+                map(
+                    LinkEditDataDetails::parse(endianness),
+                    Self::DynamicLibraryCodeSignDrs,
+                ),
+            )(body),
+            44 => context(
+                "Parse Encryption Info 64",
+                map(
+                    EncryptionInfoDetails::parse(endianness),
+                    Self::EncryptionInfo64,
+                ),
+            )(body),
+            45 => context(
+                "Parse Linker Option",
+                map(LinkerOptionDetails::parse(endianness), Self::LinkerOption),
+            )(body),
+            46 => context(
+                "Parse Linker Optimization Hint",
+                map(
+                    LinkEditDataDetails::parse(endianness),
+                    Self::LinkerOptimizationHint,
+                ),
+            )(body),
+            47 => context(
+                "Parse Version Min tvOS",
+                map(VersionMinDetails::parse(endianness), Self::VersionMinTvOs),
+            )(body),
+            48 => context(
+                "Parse Version Min watchOS",
+                map(
+                    VersionMinDetails::parse(endianness),
+                    Self::VersionMinWatchOs,
+                ),
+            )(body),
+            49 => context(
+                "Parse Note",
+                map(NoteDetails::parse(endianness), Self::Note),
+            )(body),
+            50 => context(
+                "Parse Build Version",
+                map(BuildVersionDetails::parse(endianness), Self::BuildVersion),
+            )(body),
             /* 51 */
-            0x80000033 => Ok((
-                &input[command_size as usize - 8..],
-                Self::DynamicLinkerExportsTrie,
-            )),
+            0x80000033 => context(
+                "Parse Dynamic Linker Exports Trie",
+                map(
+                    LinkEditDataDetails::parse(endianness),
+                    Self::DynamicLinkerExportsTrie,
+                ),
+            )(body),
             /* 52 */
-            0x80000034 => Ok((
-                &input[command_size as usize - 8..],
-                Self::DynamicLinkerChainedFixups,
-            )),
+            0x80000034 => context(
+                "Parse Dynamic Linker Chained Fixups",
+                map(
+                    LinkEditDataDetails::parse(endianness),
+                    Self::DynamicLinkerChainedFixups,
+                ),
+            )(body),
             /* 53 */
-            0x80000035 => Ok((&input[command_size as usize - 8..], Self::FileSetEntry)),
+            0x80000035 => context(
+                "Parse File Set Entry",
+                map(FileSetEntryDetails::parse(endianness), Self::FileSetEntry),
+            )(body),
             _ => panic!("Unknown command type {}", command_type),
         }
     }
 }
 
+/// Parses an `lc_str` offset and reads the string it points to out of the load command body.
+///
+/// The offset is relative to the start of the load command, but `body` starts after the
+/// command type and size, so the offset is adjusted by 8 bytes.
+fn lc_str<'a>(
+    body: parse::Input<'a>,
+    endianness: Endianness,
+) -> impl FnMut(parse::Input<'a>) -> parse::ParseResult<'a, String> {
+    move |input: parse::Input<'a>| {
+        map(complete::u32(endianness), |offset| {
+            let start = (offset as usize).saturating_sub(8).min(body.len());
+            let bytes = &body[start..];
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            String::from_utf8_lossy(&bytes[..end]).into_owned()
+        })(input)
+    }
+}
+
+/// Splits a run of null terminated strings, dropping the padding at the end
+fn null_terminated_strings(input: parse::Input) -> Vec<String> {
+    input
+        .split(|&b| b == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+        .collect()
+}
+
+/// Reads a name stored in a fixed length buffer, which is only null terminated when it is shorter
+/// than the buffer
+fn fixed_length_string(buf: parse::Input) -> String {
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..end]).into_owned()
+}
+
+/// A version packed as `xxxx.yy.zz` into a u32
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedVersion(pub u32);
+
+impl fmt::Display for PackedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}",
+            self.0 >> 16,
+            (self.0 >> 8) & 0xff,
+            self.0 & 0xff
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct Section {
-    name: String,
-    segment_name: String,
-    addr: u32,
-    size: u32,
-    offset: u32,
-    align: u32,
-    relocation_offset: u32,
-    number_relocations: u32,
-    flags: u32,
-    reserved_1: u32,
-    reserved_2: u32,
+    pub name: String,
+    pub segment_name: String,
+    pub addr: u32,
+    pub size: u32,
+    pub offset: u32,
+    pub align: u32,
+    pub relocation_offset: u32,
+    pub number_relocations: u32,
+    pub flags: u32,
+    pub reserved_1: u32,
+    pub reserved_2: u32,
 }
 
 impl Section {
@@ -277,20 +505,8 @@ impl Section {
             ) = context(
                 "Parse Section",
                 tuple((
-                    map(take(16usize), |name_buf| {
-                        CStr::from_bytes_until_nul(name_buf)
-                            .expect("Invalid Section name")
-                            .to_str()
-                            .expect("Invalid Section name")
-                            .to_string()
-                    }),
-                    map(take(16usize), |name_buf| {
-                        CStr::from_bytes_until_nul(name_buf)
-                            .expect("Invalid Segment name")
-                            .to_str()
-                            .expect("Invalid Segment name")
-                            .to_string()
-                    }),
+                    map(take(16usize), fixed_length_string),
+                    map(take(16usize), fixed_length_string),
                     complete::u32(endianness),
                     complete::u32(endianness),
                     complete::u32(endianness),
@@ -342,15 +558,15 @@ impl fmt::Display for Section {
 
 #[derive(Debug)]
 pub struct SegmentDetails {
-    name: String,
-    vm_addr: u32,
-    vm_size: u32,
-    file_offset: u32,
-    file_size: u32,
-    max_protection: i32,
-    initial_protection: i32,
-    flags: u32,
-    sections: Vec<Section>,
+    pub name: String,
+    pub vm_addr: u32,
+    pub vm_size: u32,
+    pub file_offset: u32,
+    pub file_size: u32,
+    pub max_protection: i32,
+    pub initial_protection: i32,
+    pub flags: u32,
+    pub sections: Vec<Section>,
 }
 
 impl SegmentDetails {
@@ -372,13 +588,7 @@ impl SegmentDetails {
             ) = context(
                 "Parse Segment",
                 tuple((
-                    map(take(16usize), |name_buf| {
-                        CStr::from_bytes_until_nul(name_buf)
-                            .expect("Invalid Segment name")
-                            .to_str()
-                            .expect("Invalid Segment name")
-                            .to_string()
-                    }),
+                    map(take(16usize), fixed_length_string),
                     complete::u32(endianness),
                     complete::u32(endianness),
                     complete::u32(endianness),
@@ -434,18 +644,18 @@ impl fmt::Display for SegmentDetails {
 }
 #[derive(Debug)]
 pub struct Section64 {
-    name: String,
-    segment_name: String,
-    addr: u64,
-    size: u64,
-    offset: u32,
-    align: u32,
-    relocation_offset: u32,
-    number_relocations: u32,
-    flags: u32,
-    reserved_1: u32,
-    reserved_2: u32,
-    reserved_3: u32,
+    pub name: String,
+    pub segment_name: String,
+    pub addr: u64,
+    pub size: u64,
+    pub offset: u32,
+    pub align: u32,
+    pub relocation_offset: u32,
+    pub number_relocations: u32,
+    pub flags: u32,
+    pub reserved_1: u32,
+    pub reserved_2: u32,
+    pub reserved_3: u32,
 }
 
 impl Section64 {
@@ -470,20 +680,8 @@ impl Section64 {
             ) = context(
                 "Parse Section64",
                 tuple((
-                    map(take(16usize), |name_buf| {
-                        CStr::from_bytes_until_nul(name_buf)
-                            .expect("Invalid Section name")
-                            .to_str()
-                            .expect("Invalid Section name")
-                            .to_string()
-                    }),
-                    map(take(16usize), |name_buf| {
-                        CStr::from_bytes_until_nul(name_buf)
-                            .expect("Invalid Segment name")
-                            .to_str()
-                            .expect("Invalid Segment name")
-                            .to_string()
-                    }),
+                    map(take(16usize), fixed_length_string),
+                    map(take(16usize), fixed_length_string),
                     complete::u64(endianness),
                     complete::u64(endianness),
                     complete::u32(endianness),
@@ -538,15 +736,15 @@ impl fmt::Display for Section64 {
 
 #[derive(Debug)]
 pub struct Segment64Details {
-    name: String,
-    vm_addr: u64,
-    vm_size: u64,
-    file_offset: u64,
-    file_size: u64,
-    max_protection: i32,
-    initial_protection: i32,
-    flags: u32,
-    sections: Vec<Section64>,
+    pub name: String,
+    pub vm_addr: u64,
+    pub vm_size: u64,
+    pub file_offset: u64,
+    pub file_size: u64,
+    pub max_protection: i32,
+    pub initial_protection: i32,
+    pub flags: u32,
+    pub sections: Vec<Section64>,
 }
 
 impl Segment64Details {
@@ -568,13 +766,7 @@ impl Segment64Details {
             ) = context(
                 "Parse Segment64",
                 tuple((
-                    map(take(16usize), |name_buf| {
-                        CStr::from_bytes_until_nul(name_buf)
-                            .expect("Invalid Segment name")
-                            .to_str()
-                            .expect("Invalid Segment name")
-                            .to_string()
-                    }),
+                    map(take(16usize), fixed_length_string),
                     complete::u64(endianness),
                     complete::u64(endianness),
                     complete::u64(endianness),
@@ -629,69 +821,1194 @@ impl fmt::Display for Segment64Details {
     }
 }
 
-impl fmt::Display for Command {
+#[derive(Debug)]
+pub struct SymbolTableDetails {
+    /// File offset of the symbol table entries
+    pub symbol_offset: u32,
+    /// Number of symbol table entries
+    pub number_symbols: u32,
+    /// File offset of the string table
+    pub string_offset: u32,
+    /// Size of the string table
+    pub string_size: u32,
+}
+
+impl SymbolTableDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            let (input, (symbol_offset, number_symbols, string_offset, string_size)) =
+                tuple((
+                    complete::u32(endianness),
+                    complete::u32(endianness),
+                    complete::u32(endianness),
+                    complete::u32(endianness),
+                ))(input)?;
+            Ok((
+                input,
+                Self {
+                    symbol_offset,
+                    number_symbols,
+                    string_offset,
+                    string_size,
+                },
+            ))
+        }
+    }
+}
+
+impl fmt::Display for SymbolTableDetails {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Command::Segment(details) => {
-                writeln!(f, "Segment")?;
-                writeln!(f, "{}", details)
-            }
-            Command::SymbolTable => writeln!(f, "SymbolTable"),
-            Command::SymbolSegment => writeln!(f, "SymbolSegment"),
-            Command::Thread => writeln!(f, "Thread"),
-            Command::UnixThread => writeln!(f, "UnixThread"),
-            Command::LoadFixedVmLibrary => writeln!(f, "LoadFixedVmLibrary"),
-            Command::IdentifyFixedVmLibrary => writeln!(f, "IdentifyFixedVmLibrary"),
-            Command::Identify => writeln!(f, "Identify"),
-            Command::IncludeFixedVmLibrary => writeln!(f, "IncludeFixedVmLibrary"),
-            Command::Prepage => writeln!(f, "Prepage"),
-            Command::DynamicSymbolTable => writeln!(f, "DynamicSymbolTable"),
-            Command::LoadDynamicLibrary => writeln!(f, "LoadDynamicLibrary"),
-            Command::IdentifyDynamicLibrary => writeln!(f, "IdentifyDynamicLibrary"),
-            Command::LoadDynamicLinker => writeln!(f, "LoadDynamicLinker"),
-            Command::IdentifyDynamicLinker => writeln!(f, "IdentifyDynamicLinker"),
-            Command::PreboundDynamicLibrary => writeln!(f, "PreboundDynamicLibrary"),
-            Command::Routines => writeln!(f, "Routines"),
-            Command::SubFramework => writeln!(f, "SubFramework"),
-            Command::SubUmbrella => writeln!(f, "SubUmbrella"),
-            Command::SubClient => writeln!(f, "SubClient"),
-            Command::SubLibrary => writeln!(f, "SubLibrary"),
-            Command::TwoLevelHints => writeln!(f, "TwoLevelHints"),
-            Command::PrebindChecksum => writeln!(f, "PrebindChecksum"),
-            Command::LoadWeakDynamicLibrary => writeln!(f, "LoadWeakDynamicLibrary"),
-            Command::Segment64(details) => {
-                writeln!(f, "Segment64")?;
-                write!(f, "{}", details)
+        writeln!(f, "Symbol Offset: {:x}", self.symbol_offset)?;
+        writeln!(f, "Number of symbols: {}", self.number_symbols)?;
+        writeln!(f, "String Offset: {:x}", self.string_offset)?;
+        writeln!(f, "String Size: {} bytes", self.string_size)
+    }
+}
+
+#[derive(Debug)]
+pub struct SymbolSegmentDetails {
+    pub offset: u32,
+    pub size: u32,
+}
+
+impl SymbolSegmentDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(
+                tuple((complete::u32(endianness), complete::u32(endianness))),
+                |(offset, size)| Self { offset, size },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for SymbolSegmentDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Offset: {:x}", self.offset)?;
+        writeln!(f, "Size: {} bytes", self.size)
+    }
+}
+
+#[derive(Debug)]
+pub struct ThreadState {
+    /// Machine specific flavor of the thread state
+    pub flavor: u32,
+    /// The raw thread state, as `count` 32 bit words
+    pub state: Vec<u32>,
+}
+
+#[derive(Debug)]
+pub struct ThreadDetails {
+    pub states: Vec<ThreadState>,
+}
+
+impl ThreadDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            let mut input = input;
+            let mut states = Vec::new();
+            while !input.is_empty() {
+                let (next_input, (flavor, state_count)) = context(
+                    "Parse Thread State flavor and count",
+                    tuple((complete::u32(endianness), complete::u32(endianness))),
+                )(input)?;
+                let (next_input, state) = context(
+                    "Parse Thread State",
+                    count(complete::u32(endianness), state_count as usize),
+                )(next_input)?;
+                input = next_input;
+                states.push(ThreadState { flavor, state });
             }
-            Command::Routines64 => writeln!(f, "Routines64"),
-            Command::Uuid => writeln!(f, "Uuid"),
-            Command::RPath => writeln!(f, "RPath"),
-            Command::CodeSignature => writeln!(f, "CodeSignature"),
-            Command::SegmentSplitInfo => writeln!(f, "SegmentSplitInfo"),
-            Command::ReexportDynamicLibrary => writeln!(f, "ReexportDynamicLibrary"),
-            Command::LazyLoadDynamicLibrary => writeln!(f, "LazyLoadDynamicLibrary"),
-            Command::EncryptionInfo => writeln!(f, "EncryptionInfo"),
-            Command::DynamicLinkerInfo => writeln!(f, "DynamicLinkerInfo"),
-            Command::DynamicLinkerInfoOnly => writeln!(f, "DynamicLinkerInfoOnly"),
-            Command::LoadUpwardDynamicLibrary => writeln!(f, "LoadUpwardDynamicLibrary"),
-            Command::VersionMinMacOsx => writeln!(f, "VersionMinMacOsx"),
-            Command::VersionMinIphoneOs => writeln!(f, "VersionMinIphoneOs"),
-            Command::FunctionStarts => writeln!(f, "FunctionStarts"),
-            Command::DynamicLinkerEnvironment => writeln!(f, "DynamicLinkerEnvironment"),
-            Command::Main => writeln!(f, "Main"),
-            Command::DataInCode => writeln!(f, "DataInCode"),
-            Command::SourceVersion => writeln!(f, "SourceVersion"),
-            Command::DynamicLibraryCodeSignDrs => writeln!(f, "DynamicLibraryCodeSignDrs"),
-            Command::EncryptionInfo64 => writeln!(f, "EncryptionInfo64"),
-            Command::LinkerOption => writeln!(f, "LinkerOption"),
-            Command::LinkerOptimizationHint => writeln!(f, "LinkerOptimizationHint"),
-            Command::VersionMinTvOs => writeln!(f, "VersionMinTvOs"),
-            Command::VersionMinWatchOs => writeln!(f, "VersionMinWatchOs"),
-            Command::Note => writeln!(f, "Note"),
-            Command::BuildVersion => writeln!(f, "BuildVersion"),
-            Command::DynamicLinkerExportsTrie => writeln!(f, "DynamicLinkerExportsTrie"),
-            Command::DynamicLinkerChainedFixups => writeln!(f, "DynamicLinkerChainedFixups"),
-            Command::FileSetEntry => writeln!(f, "FileSetEntry"),
+            Ok((input, Self { states }))
+        }
+    }
+}
+
+impl fmt::Display for ThreadDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Number of states: {}", self.states.len())?;
+        for (i, state) in self.states.iter().enumerate() {
+            writeln!(f, "State {}:", i)?;
+            writeln!(f, "Flavor: {}", state.flavor)?;
+            writeln!(f, "State: {:x?}", state.state)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct FixedVmLibraryDetails {
+    pub name: String,
+    pub minor_version: u32,
+    pub header_addr: u32,
+}
+
+impl FixedVmLibraryDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(
+                tuple((
+                    lc_str(input, endianness),
+                    complete::u32(endianness),
+                    complete::u32(endianness),
+                )),
+                |(name, minor_version, header_addr)| Self {
+                    name,
+                    minor_version,
+                    header_addr,
+                },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for FixedVmLibraryDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Name: {}", self.name)?;
+        writeln!(f, "Minor Version: {}", self.minor_version)?;
+        writeln!(f, "Header Addr: {:x}", self.header_addr)
+    }
+}
+
+#[derive(Debug)]
+pub struct IdentifyDetails {
+    pub strings: Vec<String>,
+}
+
+impl IdentifyDetails {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        Ok((
+            &input[input.len()..],
+            Self {
+                strings: null_terminated_strings(input),
+            },
+        ))
+    }
+}
+
+impl fmt::Display for IdentifyDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for string in &self.strings {
+            writeln!(f, "{}", string)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct FixedVmFileDetails {
+    pub name: String,
+    pub header_addr: u32,
+}
+
+impl FixedVmFileDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(
+                tuple((lc_str(input, endianness), complete::u32(endianness))),
+                |(name, header_addr)| Self { name, header_addr },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for FixedVmFileDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Name: {}", self.name)?;
+        writeln!(f, "Header Addr: {:x}", self.header_addr)
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicSymbolTableDetails {
+    /// Index of the first local symbol
+    pub local_symbols_index: u32,
+    pub number_local_symbols: u32,
+    /// Index of the first externally defined symbol
+    pub external_symbols_index: u32,
+    pub number_external_symbols: u32,
+    /// Index of the first undefined symbol
+    pub undefined_symbols_index: u32,
+    pub number_undefined_symbols: u32,
+    pub table_of_contents_offset: u32,
+    pub number_table_of_contents: u32,
+    pub module_table_offset: u32,
+    pub number_module_table: u32,
+    pub external_references_offset: u32,
+    pub number_external_references: u32,
+    pub indirect_symbols_offset: u32,
+    pub number_indirect_symbols: u32,
+    pub external_relocations_offset: u32,
+    pub number_external_relocations: u32,
+    pub local_relocations_offset: u32,
+    pub number_local_relocations: u32,
+}
+
+impl DynamicSymbolTableDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            let (input, fields) = count(complete::u32(endianness), 18)(input)?;
+            Ok((
+                input,
+                Self {
+                    local_symbols_index: fields[0],
+                    number_local_symbols: fields[1],
+                    external_symbols_index: fields[2],
+                    number_external_symbols: fields[3],
+                    undefined_symbols_index: fields[4],
+                    number_undefined_symbols: fields[5],
+                    table_of_contents_offset: fields[6],
+                    number_table_of_contents: fields[7],
+                    module_table_offset: fields[8],
+                    number_module_table: fields[9],
+                    external_references_offset: fields[10],
+                    number_external_references: fields[11],
+                    indirect_symbols_offset: fields[12],
+                    number_indirect_symbols: fields[13],
+                    external_relocations_offset: fields[14],
+                    number_external_relocations: fields[15],
+                    local_relocations_offset: fields[16],
+                    number_local_relocations: fields[17],
+                },
+            ))
+        }
+    }
+}
+
+impl fmt::Display for DynamicSymbolTableDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Local Symbols Index: {}", self.local_symbols_index)?;
+        writeln!(f, "Number of local symbols: {}", self.number_local_symbols)?;
+        writeln!(f, "External Symbols Index: {}", self.external_symbols_index)?;
+        writeln!(
+            f,
+            "Number of external symbols: {}",
+            self.number_external_symbols
+        )?;
+        writeln!(
+            f,
+            "Undefined Symbols Index: {}",
+            self.undefined_symbols_index
+        )?;
+        writeln!(
+            f,
+            "Number of undefined symbols: {}",
+            self.number_undefined_symbols
+        )?;
+        writeln!(
+            f,
+            "Table of Contents Offset: {:x}",
+            self.table_of_contents_offset
+        )?;
+        writeln!(
+            f,
+            "Number of table of contents entries: {}",
+            self.number_table_of_contents
+        )?;
+        writeln!(f, "Module Table Offset: {:x}", self.module_table_offset)?;
+        writeln!(
+            f,
+            "Number of module table entries: {}",
+            self.number_module_table
+        )?;
+        writeln!(
+            f,
+            "External References Offset: {:x}",
+            self.external_references_offset
+        )?;
+        writeln!(
+            f,
+            "Number of external references: {}",
+            self.number_external_references
+        )?;
+        writeln!(
+            f,
+            "Indirect Symbols Offset: {:x}",
+            self.indirect_symbols_offset
+        )?;
+        writeln!(
+            f,
+            "Number of indirect symbols: {}",
+            self.number_indirect_symbols
+        )?;
+        writeln!(
+            f,
+            "External Relocations Offset: {:x}",
+            self.external_relocations_offset
+        )?;
+        writeln!(
+            f,
+            "Number of external relocations: {}",
+            self.number_external_relocations
+        )?;
+        writeln!(
+            f,
+            "Local Relocations Offset: {:x}",
+            self.local_relocations_offset
+        )?;
+        writeln!(
+            f,
+            "Number of local relocations: {}",
+            self.number_local_relocations
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicLibraryDetails {
+    pub name: String,
+    pub timestamp: u32,
+    pub current_version: PackedVersion,
+    pub compatibility_version: PackedVersion,
+}
+
+impl DynamicLibraryDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(
+                tuple((
+                    lc_str(input, endianness),
+                    complete::u32(endianness),
+                    map(complete::u32(endianness), PackedVersion),
+                    map(complete::u32(endianness), PackedVersion),
+                )),
+                |(name, timestamp, current_version, compatibility_version)| Self {
+                    name,
+                    timestamp,
+                    current_version,
+                    compatibility_version,
+                },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for DynamicLibraryDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Name: {}", self.name)?;
+        writeln!(f, "Timestamp: {}", self.timestamp)?;
+        writeln!(f, "Current Version: {}", self.current_version)?;
+        writeln!(f, "Compatibility Version: {}", self.compatibility_version)
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicLinkerDetails {
+    pub name: String,
+}
+
+impl DynamicLinkerDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| map(lc_str(input, endianness), |name| Self { name })(input)
+    }
+}
+
+impl fmt::Display for DynamicLinkerDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Name: {}", self.name)
+    }
+}
+
+#[derive(Debug)]
+pub struct PreboundDynamicLibraryDetails {
+    pub name: String,
+    pub number_modules: u32,
+    /// Bit vector of the modules that are linked, one bit per module
+    pub linked_modules: Vec<u8>,
+}
+
+impl PreboundDynamicLibraryDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            let body = input;
+            let (input, (name, number_modules, linked_modules_offset)) = tuple((
+                lc_str(body, endianness),
+                complete::u32(endianness),
+                complete::u32(endianness),
+            ))(input)?;
+            let start = (linked_modules_offset as usize)
+                .saturating_sub(8)
+                .min(body.len());
+            let linked_modules = body[start..]
+                .iter()
+                .take((number_modules as usize).div_ceil(8))
+                .copied()
+                .collect();
+            Ok((
+                input,
+                Self {
+                    name,
+                    number_modules,
+                    linked_modules,
+                },
+            ))
+        }
+    }
+}
+
+impl fmt::Display for PreboundDynamicLibraryDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Name: {}", self.name)?;
+        writeln!(f, "Number of modules: {}", self.number_modules)?;
+        writeln!(f, "Linked Modules: {:02x?}", self.linked_modules)
+    }
+}
+
+#[derive(Debug)]
+pub struct RoutinesDetails {
+    /// Address of the initialization routine
+    pub init_address: u32,
+    /// Index into the module table that the init routine is defined in
+    pub init_module: u32,
+    pub reserved: [u32; 6],
+}
+
+impl RoutinesDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            let (input, (init_address, init_module, reserved)) = tuple((
+                complete::u32(endianness),
+                complete::u32(endianness),
+                count(complete::u32(endianness), 6),
+            ))(input)?;
+            Ok((
+                input,
+                Self {
+                    init_address,
+                    init_module,
+                    reserved: reserved.try_into().expect("Invalid Routines reserved"),
+                },
+            ))
+        }
+    }
+}
+
+impl fmt::Display for RoutinesDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Init Address: {:x}", self.init_address)?;
+        writeln!(f, "Init Module: {}", self.init_module)?;
+        writeln!(f, "Reserved: {:x?}", self.reserved)
+    }
+}
+
+#[derive(Debug)]
+pub struct Routines64Details {
+    /// Address of the initialization routine
+    pub init_address: u64,
+    /// Index into the module table that the init routine is defined in
+    pub init_module: u64,
+    pub reserved: [u64; 6],
+}
+
+impl Routines64Details {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            let (input, (init_address, init_module, reserved)) = tuple((
+                complete::u64(endianness),
+                complete::u64(endianness),
+                count(complete::u64(endianness), 6),
+            ))(input)?;
+            Ok((
+                input,
+                Self {
+                    init_address,
+                    init_module,
+                    reserved: reserved.try_into().expect("Invalid Routines64 reserved"),
+                },
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Routines64Details {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Init Address: {:x}", self.init_address)?;
+        writeln!(f, "Init Module: {}", self.init_module)?;
+        writeln!(f, "Reserved: {:x?}", self.reserved)
+    }
+}
+
+#[derive(Debug)]
+pub struct SubFrameworkDetails {
+    /// The umbrella framework this framework is a part of
+    pub umbrella: String,
+}
+
+impl SubFrameworkDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(lc_str(input, endianness), |umbrella| Self { umbrella })(input)
+        }
+    }
+}
+
+impl fmt::Display for SubFrameworkDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Umbrella: {}", self.umbrella)
+    }
+}
+
+#[derive(Debug)]
+pub struct SubUmbrellaDetails {
+    /// A sub-umbrella framework of this umbrella framework
+    pub sub_umbrella: String,
+}
+
+impl SubUmbrellaDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(lc_str(input, endianness), |sub_umbrella| Self {
+                sub_umbrella,
+            })(input)
+        }
+    }
+}
+
+impl fmt::Display for SubUmbrellaDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Sub Umbrella: {}", self.sub_umbrella)
+    }
+}
+
+#[derive(Debug)]
+pub struct SubClientDetails {
+    /// A client that is allowed to link against this sub framework
+    pub client: String,
+}
+
+impl SubClientDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| map(lc_str(input, endianness), |client| Self { client })(input)
+    }
+}
+
+impl fmt::Display for SubClientDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Client: {}", self.client)
+    }
+}
+
+#[derive(Debug)]
+pub struct SubLibraryDetails {
+    /// A sub-library of this umbrella framework
+    pub sub_library: String,
+}
+
+impl SubLibraryDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(lc_str(input, endianness), |sub_library| Self {
+                sub_library,
+            })(input)
+        }
+    }
+}
+
+impl fmt::Display for SubLibraryDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Sub Library: {}", self.sub_library)
+    }
+}
+
+#[derive(Debug)]
+pub struct TwoLevelHintsDetails {
+    pub offset: u32,
+    pub number_hints: u32,
+}
+
+impl TwoLevelHintsDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(
+                tuple((complete::u32(endianness), complete::u32(endianness))),
+                |(offset, number_hints)| Self {
+                    offset,
+                    number_hints,
+                },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for TwoLevelHintsDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Offset: {:x}", self.offset)?;
+        writeln!(f, "Number of hints: {}", self.number_hints)
+    }
+}
+
+#[derive(Debug)]
+pub struct PrebindChecksumDetails {
+    pub checksum: u32,
+}
+
+impl PrebindChecksumDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(complete::u32(endianness), |checksum| Self { checksum })(input)
+        }
+    }
+}
+
+impl fmt::Display for PrebindChecksumDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Checksum: {:x}", self.checksum)
+    }
+}
+
+#[derive(Debug)]
+pub struct UuidDetails {
+    pub uuid: [u8; 16],
+}
+
+impl UuidDetails {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        map(take(16usize), |uuid: parse::Input| Self {
+            uuid: uuid.try_into().expect("Invalid Uuid"),
+        })(input)
+    }
+}
+
+impl fmt::Display for UuidDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Uuid: ")?;
+        for (i, byte) in self.uuid.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                write!(f, "-")?;
+            }
+            write!(f, "{:02X}", byte)?;
+        }
+        writeln!(f)
+    }
+}
+
+#[derive(Debug)]
+pub struct RPathDetails {
+    pub path: String,
+}
+
+impl RPathDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| map(lc_str(input, endianness), |path| Self { path })(input)
+    }
+}
+
+impl fmt::Display for RPathDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Path: {}", self.path)
+    }
+}
+
+/// A blob of data in the `__LINKEDIT` segment
+#[derive(Debug)]
+pub struct LinkEditDataDetails {
+    pub data_offset: u32,
+    pub data_size: u32,
+}
+
+impl LinkEditDataDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(
+                tuple((complete::u32(endianness), complete::u32(endianness))),
+                |(data_offset, data_size)| Self {
+                    data_offset,
+                    data_size,
+                },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for LinkEditDataDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Data Offset: {:x}", self.data_offset)?;
+        writeln!(f, "Data Size: {} bytes", self.data_size)
+    }
+}
+
+#[derive(Debug)]
+pub struct EncryptionInfoDetails {
+    pub crypt_offset: u32,
+    pub crypt_size: u32,
+    /// Which encryption system is used, 0 means not encrypted yet
+    pub crypt_id: u32,
+}
+
+impl EncryptionInfoDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        // The 64 bit variant has an extra padding word at the end, which we don't need to read
+        move |input: parse::Input| {
+            map(
+                tuple((
+                    complete::u32(endianness),
+                    complete::u32(endianness),
+                    complete::u32(endianness),
+                )),
+                |(crypt_offset, crypt_size, crypt_id)| Self {
+                    crypt_offset,
+                    crypt_size,
+                    crypt_id,
+                },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for EncryptionInfoDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Crypt Offset: {:x}", self.crypt_offset)?;
+        writeln!(f, "Crypt Size: {} bytes", self.crypt_size)?;
+        writeln!(f, "Crypt Id: {}", self.crypt_id)
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicLinkerInfoDetails {
+    pub rebase_offset: u32,
+    pub rebase_size: u32,
+    pub bind_offset: u32,
+    pub bind_size: u32,
+    pub weak_bind_offset: u32,
+    pub weak_bind_size: u32,
+    pub lazy_bind_offset: u32,
+    pub lazy_bind_size: u32,
+    pub export_offset: u32,
+    pub export_size: u32,
+}
+
+impl DynamicLinkerInfoDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            let (input, fields) = count(complete::u32(endianness), 10)(input)?;
+            Ok((
+                input,
+                Self {
+                    rebase_offset: fields[0],
+                    rebase_size: fields[1],
+                    bind_offset: fields[2],
+                    bind_size: fields[3],
+                    weak_bind_offset: fields[4],
+                    weak_bind_size: fields[5],
+                    lazy_bind_offset: fields[6],
+                    lazy_bind_size: fields[7],
+                    export_offset: fields[8],
+                    export_size: fields[9],
+                },
+            ))
+        }
+    }
+}
+
+impl fmt::Display for DynamicLinkerInfoDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Rebase Offset: {:x}", self.rebase_offset)?;
+        writeln!(f, "Rebase Size: {} bytes", self.rebase_size)?;
+        writeln!(f, "Bind Offset: {:x}", self.bind_offset)?;
+        writeln!(f, "Bind Size: {} bytes", self.bind_size)?;
+        writeln!(f, "Weak Bind Offset: {:x}", self.weak_bind_offset)?;
+        writeln!(f, "Weak Bind Size: {} bytes", self.weak_bind_size)?;
+        writeln!(f, "Lazy Bind Offset: {:x}", self.lazy_bind_offset)?;
+        writeln!(f, "Lazy Bind Size: {} bytes", self.lazy_bind_size)?;
+        writeln!(f, "Export Offset: {:x}", self.export_offset)?;
+        writeln!(f, "Export Size: {} bytes", self.export_size)
+    }
+}
+
+#[derive(Debug)]
+pub struct VersionMinDetails {
+    pub version: PackedVersion,
+    pub sdk: PackedVersion,
+}
+
+impl VersionMinDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(
+                tuple((
+                    map(complete::u32(endianness), PackedVersion),
+                    map(complete::u32(endianness), PackedVersion),
+                )),
+                |(version, sdk)| Self { version, sdk },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for VersionMinDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "SDK: {}", self.sdk)
+    }
+}
+
+#[derive(Debug)]
+pub struct EntryPointDetails {
+    /// File offset of the entry point, relative to the `__TEXT` segment
+    pub entry_offset: u64,
+    /// Initial stack size, or 0 for the default
+    pub stack_size: u64,
+}
+
+impl EntryPointDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(
+                tuple((complete::u64(endianness), complete::u64(endianness))),
+                |(entry_offset, stack_size)| Self {
+                    entry_offset,
+                    stack_size,
+                },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for EntryPointDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Entry Offset: {:x}", self.entry_offset)?;
+        writeln!(f, "Stack Size: {} bytes", self.stack_size)
+    }
+}
+
+#[derive(Debug)]
+pub struct SourceVersionDetails {
+    /// Version packed as `A.B.C.D.E` into 24.10.10.10.10 bits
+    pub version: u64,
+}
+
+impl SourceVersionDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| map(complete::u64(endianness), |version| Self { version })(input)
+    }
+}
+
+impl fmt::Display for SourceVersionDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Version: {}.{}.{}.{}.{}",
+            self.version >> 40,
+            (self.version >> 30) & 0x3ff,
+            (self.version >> 20) & 0x3ff,
+            (self.version >> 10) & 0x3ff,
+            self.version & 0x3ff
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct LinkerOptionDetails {
+    /// The number of strings, as recorded in the command
+    pub count: u32,
+    pub options: Vec<String>,
+}
+
+impl LinkerOptionDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            let (input, count) = complete::u32(endianness)(input)?;
+            // Exactly `count` strings, which can be empty, followed by padding
+            let options = input
+                .split(|&b| b == 0)
+                .take(count as usize)
+                .map(|option| String::from_utf8_lossy(option).into_owned())
+                .collect();
+            Ok((&input[input.len()..], Self { count, options }))
+        }
+    }
+}
+
+impl fmt::Display for LinkerOptionDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Count: {}", self.count)?;
+        writeln!(f, "Options: {}", self.options.join(" "))
+    }
+}
+
+#[derive(Debug)]
+pub struct NoteDetails {
+    pub data_owner: String,
+    /// File offset of the note contents
+    pub offset: u64,
+    pub size: u64,
+}
+
+impl NoteDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(
+                tuple((
+                    map(take(16usize), fixed_length_string),
+                    complete::u64(endianness),
+                    complete::u64(endianness),
+                )),
+                |(data_owner, offset, size)| Self {
+                    data_owner,
+                    offset,
+                    size,
+                },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for NoteDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Data Owner: {}", self.data_owner)?;
+        writeln!(f, "Offset: {:x}", self.offset)?;
+        writeln!(f, "Size: {} bytes", self.size)
+    }
+}
+
+#[derive(Debug)]
+pub struct BuildToolVersion {
+    pub tool: u32,
+    pub version: PackedVersion,
+}
+
+impl fmt::Display for BuildToolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tool = match self.tool {
+            1 => "clang",
+            2 => "swift",
+            3 => "ld",
+            4 => "lld",
+            _ => "unknown",
+        };
+        write!(f, "{} ({}) {}", tool, self.tool, self.version)
+    }
+}
+
+#[derive(Debug)]
+pub struct BuildVersionDetails {
+    pub platform: u32,
+    pub min_os: PackedVersion,
+    pub sdk: PackedVersion,
+    pub tools: Vec<BuildToolVersion>,
+}
+
+impl BuildVersionDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            let (input, (platform, min_os, sdk, number_tools)) = tuple((
+                complete::u32(endianness),
+                map(complete::u32(endianness), PackedVersion),
+                map(complete::u32(endianness), PackedVersion),
+                complete::u32(endianness),
+            ))(input)?;
+            let (input, tools) = count(
+                map(
+                    tuple((
+                        complete::u32(endianness),
+                        map(complete::u32(endianness), PackedVersion),
+                    )),
+                    |(tool, version)| BuildToolVersion { tool, version },
+                ),
+                number_tools as usize,
+            )(input)?;
+            Ok((
+                input,
+                Self {
+                    platform,
+                    min_os,
+                    sdk,
+                    tools,
+                },
+            ))
+        }
+    }
+
+    pub fn platform_name(&self) -> &'static str {
+        match self.platform {
+            1 => "macOS",
+            2 => "iOS",
+            3 => "tvOS",
+            4 => "watchOS",
+            5 => "bridgeOS",
+            6 => "Mac Catalyst",
+            7 => "iOS Simulator",
+            8 => "tvOS Simulator",
+            9 => "watchOS Simulator",
+            10 => "DriverKit",
+            11 => "visionOS",
+            12 => "visionOS Simulator",
+            _ => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for BuildVersionDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Platform: {} ({})", self.platform_name(), self.platform)?;
+        writeln!(f, "Minimum OS: {}", self.min_os)?;
+        writeln!(f, "SDK: {}", self.sdk)?;
+        for tool in &self.tools {
+            writeln!(f, "Tool: {}", tool)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct FileSetEntryDetails {
+    pub vm_addr: u64,
+    pub file_offset: u64,
+    pub entry_id: String,
+}
+
+impl FileSetEntryDetails {
+    fn parse(endianness: Endianness) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
+        move |input: parse::Input| {
+            map(
+                tuple((
+                    complete::u64(endianness),
+                    complete::u64(endianness),
+                    lc_str(input, endianness),
+                    complete::u32(endianness),
+                )),
+                |(vm_addr, file_offset, entry_id, _reserved)| Self {
+                    vm_addr,
+                    file_offset,
+                    entry_id,
+                },
+            )(input)
+        }
+    }
+}
+
+impl fmt::Display for FileSetEntryDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "VM Addr: {:x}", self.vm_addr)?;
+        writeln!(f, "File Offset: {:x}", self.file_offset)?;
+        writeln!(f, "Entry Id: {}", self.entry_id)
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Segment(details) => {
+                writeln!(f, "Segment")?;
+                writeln!(f, "{}", details)
+            }
+            Command::SymbolTable(details) => write!(f, "SymbolTable\n{}", details),
+            Command::SymbolSegment(details) => write!(f, "SymbolSegment\n{}", details),
+            Command::Thread(details) => write!(f, "Thread\n{}", details),
+            Command::UnixThread(details) => write!(f, "UnixThread\n{}", details),
+            Command::LoadFixedVmLibrary(details) => {
+                write!(f, "LoadFixedVmLibrary\n{}", details)
+            }
+            Command::IdentifyFixedVmLibrary(details) => {
+                write!(f, "IdentifyFixedVmLibrary\n{}", details)
+            }
+            Command::Identify(details) => write!(f, "Identify\n{}", details),
+            Command::IncludeFixedVmLibrary(details) => {
+                write!(f, "IncludeFixedVmLibrary\n{}", details)
+            }
+            Command::Prepage => writeln!(f, "Prepage"),
+            Command::DynamicSymbolTable(details) => {
+                write!(f, "DynamicSymbolTable\n{}", details)
+            }
+            Command::LoadDynamicLibrary(details) => {
+                write!(f, "LoadDynamicLibrary\n{}", details)
+            }
+            Command::IdentifyDynamicLibrary(details) => {
+                write!(f, "IdentifyDynamicLibrary\n{}", details)
+            }
+            Command::LoadDynamicLinker(details) => write!(f, "LoadDynamicLinker\n{}", details),
+            Command::IdentifyDynamicLinker(details) => {
+                write!(f, "IdentifyDynamicLinker\n{}", details)
+            }
+            Command::PreboundDynamicLibrary(details) => {
+                write!(f, "PreboundDynamicLibrary\n{}", details)
+            }
+            Command::Routines(details) => write!(f, "Routines\n{}", details),
+            Command::SubFramework(details) => write!(f, "SubFramework\n{}", details),
+            Command::SubUmbrella(details) => write!(f, "SubUmbrella\n{}", details),
+            Command::SubClient(details) => write!(f, "SubClient\n{}", details),
+            Command::SubLibrary(details) => write!(f, "SubLibrary\n{}", details),
+            Command::TwoLevelHints(details) => write!(f, "TwoLevelHints\n{}", details),
+            Command::PrebindChecksum(details) => write!(f, "PrebindChecksum\n{}", details),
+            Command::LoadWeakDynamicLibrary(details) => {
+                write!(f, "LoadWeakDynamicLibrary\n{}", details)
+            }
+            Command::Segment64(details) => {
+                writeln!(f, "Segment64")?;
+                write!(f, "{}", details)
+            }
+            Command::Routines64(details) => write!(f, "Routines64\n{}", details),
+            Command::Uuid(details) => write!(f, "Uuid\n{}", details),
+            Command::RPath(details) => write!(f, "RPath\n{}", details),
+            Command::CodeSignature(details) => write!(f, "CodeSignature\n{}", details),
+            Command::SegmentSplitInfo(details) => write!(f, "SegmentSplitInfo\n{}", details),
+            Command::ReexportDynamicLibrary(details) => {
+                write!(f, "ReexportDynamicLibrary\n{}", details)
+            }
+            Command::LazyLoadDynamicLibrary(details) => {
+                write!(f, "LazyLoadDynamicLibrary\n{}", details)
+            }
+            Command::EncryptionInfo(details) => write!(f, "EncryptionInfo\n{}", details),
+            Command::DynamicLinkerInfo(details) => write!(f, "DynamicLinkerInfo\n{}", details),
+            Command::DynamicLinkerInfoOnly(details) => {
+                write!(f, "DynamicLinkerInfoOnly\n{}", details)
+            }
+            Command::LoadUpwardDynamicLibrary(details) => {
+                write!(f, "LoadUpwardDynamicLibrary\n{}", details)
+            }
+            Command::VersionMinMacOsx(details) => write!(f, "VersionMinMacOsx\n{}", details),
+            Command::VersionMinIphoneOs(details) => {
+                write!(f, "VersionMinIphoneOs\n{}", details)
+            }
+            Command::FunctionStarts(details) => write!(f, "FunctionStarts\n{}", details),
+            Command::DynamicLinkerEnvironment(details) => {
+                write!(f, "DynamicLinkerEnvironment\n{}", details)
+            }
+            Command::Main(details) => write!(f, "Main\n{}", details),
+            Command::DataInCode(details) => write!(f, "DataInCode\n{}", details),
+            Command::SourceVersion(details) => write!(f, "SourceVersion\n{}", details),
+            Command::DynamicLibraryCodeSignDrs(details) => {
+                write!(f, "DynamicLibraryCodeSignDrs\n{}", details)
+            }
+            Command::EncryptionInfo64(details) => write!(f, "EncryptionInfo64\n{}", details),
+            Command::LinkerOption(details) => write!(f, "LinkerOption\n{}", details),
+            Command::LinkerOptimizationHint(details) => {
+                write!(f, "LinkerOptimizationHint\n{}", details)
+            }
+            Command::VersionMinTvOs(details) => write!(f, "VersionMinTvOs\n{}", details),
+            Command::VersionMinWatchOs(details) => write!(f, "VersionMinWatchOs\n{}", details),
+            Command::Note(details) => write!(f, "Note\n{}", details),
+            Command::BuildVersion(details) => write!(f, "BuildVersion\n{}", details),
+            Command::DynamicLinkerExportsTrie(details) => {
+                write!(f, "DynamicLinkerExportsTrie\n{}", details)
+            }
+            Command::DynamicLinkerChainedFixups(details) => {
+                write!(f, "DynamicLinkerChainedFixups\n{}", details)
+            }
+            Command::FileSetEntry(details) => write!(f, "FileSetEntry\n{}", details),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_empty_linker_options() {
+        let (_, details) =
+            LinkerOptionDetails::parse(Endianness::Little)(b"\x03\0\0\0-lz\0\0-lc\0\0\0").unwrap();
+        assert_eq!(details.options, ["-lz", "", "-lc"]);
+    }
+
+    #[test]
+    fn rejects_load_command_sizes_outside_the_data() {
+        // LC_UUID claiming to be smaller than its own header, then larger than the data
+        for size in [4u32, 0x100] {
+            let mut data = 0x1bu32.to_le_bytes().to_vec();
+            data.extend_from_slice(&size.to_le_bytes());
+            data.resize(24, 0);
+            assert!(LoadCommand::parse(Endianness::Little)(&data).is_err());
         }
     }
 }
@@ -27,6 +27,33 @@ pub enum CpuType {
 }
 
 impl CpuType {
+    /// The type of the CPU this was built for, if Mach-O has one for it
+    pub fn host() -> Option<Self> {
+        match std::env::consts::ARCH {
+            "x86" => Some(CpuType::X86),
+            "x86_64" => Some(CpuType::X86_64),
+            "arm" => Some(CpuType::Arm),
+            "aarch64" => Some(CpuType::Arm64),
+            "powerpc" => Some(CpuType::PowerPc),
+            "powerpc64" => Some(CpuType::PowerPc64),
+            _ => None,
+        }
+    }
+
+    /// Looks up a CPU type by the arch name that tools like `lipo` use for it
+    pub fn from_arch_name(name: &str) -> Option<Self> {
+        match name {
+            "i386" => Some(CpuType::X86),
+            "x86_64" | "x86_64h" => Some(CpuType::X86_64),
+            "arm" | "armv6" | "armv7" | "armv7s" | "armv7k" => Some(CpuType::Arm),
+            "arm64" | "arm64e" => Some(CpuType::Arm64),
+            "arm64_32" => Some(CpuType::Arm64_32),
+            "ppc" => Some(CpuType::PowerPc),
+            "ppc64" => Some(CpuType::PowerPc64),
+            _ => None,
+        }
+    }
+
     pub(crate) fn parse(
         endianness: Endianness,
     ) -> impl FnMut(parse::Input) -> parse::ParseResult<Self> {
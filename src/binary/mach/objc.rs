@@ -0,0 +1,541 @@
+pub mod class_dump;
+
+use std::fmt;
+
+use super::address_space::{AddressSpace, Pointer};
+use super::load_commands::Section64;
+
+/// `class_ro_t` flag for metaclasses
+const RO_META: u32 = 1 << 0;
+/// `method_list_t` flag for lists of relative method entries
+const METHOD_LIST_IS_RELATIVE: u32 = 0x8000_0000;
+/// `method_list_t` flag for relative lists whose names point directly at the selector strings
+const METHOD_LIST_USES_DIRECT_SELECTORS: u32 = 0x4000_0000;
+/// Mask for the entry size in a `method_list_t`
+const METHOD_LIST_ENTSIZE_MASK: u32 = 0x0000_fffc;
+/// Low bits of the `class_t` data pointer that the runtime uses as flags
+const CLASS_DATA_FLAGS_MASK: u64 = 0x7;
+
+/// Objective-C runtime metadata, read from the `__objc_*` sections
+#[derive(Debug)]
+pub struct ObjcMetadata {
+    pub image_info: Option<ObjcImageInfo>,
+    pub classes: Vec<ObjcClass>,
+    pub categories: Vec<ObjcCategory>,
+    pub protocols: Vec<ObjcProtocol>,
+    pub selector_references: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ObjcImageInfo {
+    pub version: u32,
+    pub flags: u32,
+}
+
+impl ObjcImageInfo {
+    /// The Swift ABI version this image was built with, if it contains Swift code
+    pub fn swift_version(&self) -> Option<u32> {
+        match (self.flags >> 8) & 0xff {
+            0 => None,
+            version => Some(version),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjcClass {
+    /// Address of the `class_t`
+    pub address: u64,
+    pub name: String,
+    pub superclass: Option<String>,
+    /// Flags from `class_ro_t`
+    pub flags: u32,
+    pub instance_start: u32,
+    pub instance_size: u32,
+    /// Whether the runtime data pointer marks this as a Swift class
+    pub is_swift: bool,
+    pub methods: Vec<ObjcMethod>,
+    pub protocols: Vec<String>,
+    pub ivars: Vec<ObjcIvar>,
+    pub properties: Vec<ObjcProperty>,
+    /// The metaclass, which holds the class methods
+    pub metaclass: Option<Box<ObjcClass>>,
+}
+
+impl ObjcClass {
+    pub fn is_metaclass(&self) -> bool {
+        self.flags & RO_META != 0
+    }
+
+    pub fn class_methods(&self) -> &[ObjcMethod] {
+        self.metaclass
+            .as_ref()
+            .map(|metaclass| metaclass.methods.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn class_properties(&self) -> &[ObjcProperty] {
+        self.metaclass
+            .as_ref()
+            .map(|metaclass| metaclass.properties.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjcMethod {
+    pub name: String,
+    pub types: String,
+    /// Address of the implementation
+    pub implementation: u64,
+}
+
+#[derive(Debug)]
+pub struct ObjcIvar {
+    pub name: String,
+    pub types: String,
+    /// Offset of the ivar within an instance
+    pub offset: u32,
+    pub size: u32,
+    pub alignment: u32,
+}
+
+#[derive(Debug)]
+pub struct ObjcProperty {
+    pub name: String,
+    pub attributes: String,
+}
+
+#[derive(Debug)]
+pub struct ObjcCategory {
+    pub name: String,
+    /// The class this category extends
+    pub class_name: Option<String>,
+    pub instance_methods: Vec<ObjcMethod>,
+    pub class_methods: Vec<ObjcMethod>,
+    pub protocols: Vec<String>,
+    pub properties: Vec<ObjcProperty>,
+}
+
+#[derive(Debug)]
+pub struct ObjcProtocol {
+    pub name: String,
+    pub protocols: Vec<String>,
+    pub instance_methods: Vec<ObjcMethod>,
+    pub class_methods: Vec<ObjcMethod>,
+    pub optional_instance_methods: Vec<ObjcMethod>,
+    pub optional_class_methods: Vec<ObjcMethod>,
+    pub properties: Vec<ObjcProperty>,
+}
+
+impl ObjcMetadata {
+    /// Reads the Objective-C metadata of an image, if it has any
+    pub(crate) fn parse(sections: &[&Section64], space: &AddressSpace) -> Option<Self> {
+        let find = |name: &str| sections.iter().find(|section| section.name == name);
+        let class_list = find("__objc_classlist");
+        let category_list = find("__objc_catlist");
+        let protocol_list = find("__objc_protolist");
+        let selector_references = find("__objc_selrefs");
+        let image_info = find("__objc_imageinfo");
+        if class_list.is_none()
+            && category_list.is_none()
+            && protocol_list.is_none()
+            && image_info.is_none()
+        {
+            return None;
+        }
+
+        let pointers = |section: Option<&&Section64>| -> Vec<Pointer> {
+            section
+                .map(|section| {
+                    let size = space
+                        .read_to_end(section.addr)
+                        .map_or(0, |data| section.size.min(data.len() as u64));
+                    (0..size / 8)
+                        .filter_map(|i| space.read_pointer(section.addr + i * 8))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let reader = Reader { space };
+        let classes = pointers(class_list)
+            .iter()
+            .filter_map(Pointer::address)
+            .filter_map(|address| reader.class(address, true))
+            .collect();
+        let categories = pointers(category_list)
+            .iter()
+            .filter_map(Pointer::address)
+            .filter_map(|address| reader.category(address))
+            .collect();
+        let protocols = pointers(protocol_list)
+            .iter()
+            .filter_map(Pointer::address)
+            .filter_map(|address| reader.protocol(address))
+            .collect();
+        let selector_references = pointers(selector_references)
+            .iter()
+            .filter_map(Pointer::address)
+            .filter_map(|address| space.read_string(address))
+            .collect();
+        let image_info = image_info.and_then(|section| {
+            Some(ObjcImageInfo {
+                version: space.read_u32(section.addr)?,
+                flags: space.read_u32(section.addr + 4)?,
+            })
+        });
+
+        Some(Self {
+            image_info,
+            classes,
+            categories,
+            protocols,
+            selector_references,
+        })
+    }
+}
+
+/// Follows pointers through the runtime structures of an image
+struct Reader<'s, 'a> {
+    space: &'s AddressSpace<'a>,
+}
+
+impl Reader<'_, '_> {
+    fn pointer(&self, address: u64) -> Pointer {
+        self.space.read_pointer(address).unwrap_or(Pointer::Null)
+    }
+
+    fn string_at_pointer(&self, address: u64) -> Option<String> {
+        self.pointer(address)
+            .address()
+            .and_then(|target| self.space.read_string(target))
+    }
+
+    /// The addresses of the entries of a list whose header takes up 8 bytes. Entries stop at the
+    /// end of the data backing the list, so a corrupt count can't run past it, and there are none
+    /// if `entsize` is too small for the entries to be read.
+    fn list_entries(
+        &self,
+        address: u64,
+        count: u64,
+        entsize: u64,
+        min_entsize: u64,
+    ) -> impl Iterator<Item = u64> + '_ {
+        let count = if entsize < min_entsize { 0 } else { count };
+        (0..count)
+            .map_while(move |i| address.checked_add(8)?.checked_add(i.checked_mul(entsize)?))
+            .take_while(move |&entry| self.space.read(entry, entsize as usize).is_some())
+    }
+
+    /// The name of the class a pointer refers to, whether it is in this image or bound from
+    /// another one
+    fn class_name(&self, pointer: &Pointer) -> Option<String> {
+        match pointer {
+            Pointer::Null => None,
+            Pointer::Address(address) => {
+                let data = self.pointer(address + 32).address()? & !CLASS_DATA_FLAGS_MASK;
+                self.string_at_pointer(data + 24)
+            }
+            Pointer::Symbol { name, .. } => Some(
+                name.trim_start_matches("_OBJC_CLASS_$_")
+                    .trim_start_matches("_OBJC_METACLASS_$_")
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn class(&self, address: u64, with_metaclass: bool) -> Option<ObjcClass> {
+        let isa = self.pointer(address);
+        let superclass = self.class_name(&self.pointer(address + 8));
+        let data = self.space.read_u64(address + 32)?;
+        let is_swift = data & 0x3 != 0;
+        let data = self.pointer(address + 32).address()? & !CLASS_DATA_FLAGS_MASK;
+
+        let flags = self.space.read_u32(data)?;
+        let instance_start = self.space.read_u32(data + 4)?;
+        let instance_size = self.space.read_u32(data + 8)?;
+        let name = self.string_at_pointer(data + 24)?;
+        let methods = self.method_list(self.pointer(data + 32).address());
+        let protocols = self.protocol_names(self.pointer(data + 40).address());
+        let ivars = self.ivar_list(self.pointer(data + 48).address());
+        let properties = self.property_list(self.pointer(data + 64).address());
+
+        let metaclass = match isa {
+            Pointer::Address(isa) if with_metaclass => self.class(isa, false).map(Box::new),
+            _ => None,
+        };
+
+        Some(ObjcClass {
+            address,
+            name,
+            superclass,
+            flags,
+            instance_start,
+            instance_size,
+            is_swift,
+            methods,
+            protocols,
+            ivars,
+            properties,
+            metaclass,
+        })
+    }
+
+    fn category(&self, address: u64) -> Option<ObjcCategory> {
+        Some(ObjcCategory {
+            name: self.string_at_pointer(address)?,
+            class_name: self.class_name(&self.pointer(address + 8)),
+            instance_methods: self.method_list(self.pointer(address + 16).address()),
+            class_methods: self.method_list(self.pointer(address + 24).address()),
+            protocols: self.protocol_names(self.pointer(address + 32).address()),
+            properties: self.property_list(self.pointer(address + 40).address()),
+        })
+    }
+
+    fn protocol(&self, address: u64) -> Option<ObjcProtocol> {
+        Some(ObjcProtocol {
+            name: self.string_at_pointer(address + 8)?,
+            protocols: self.protocol_names(self.pointer(address + 16).address()),
+            instance_methods: self.method_list(self.pointer(address + 24).address()),
+            class_methods: self.method_list(self.pointer(address + 32).address()),
+            optional_instance_methods: self.method_list(self.pointer(address + 40).address()),
+            optional_class_methods: self.method_list(self.pointer(address + 48).address()),
+            properties: self.property_list(self.pointer(address + 56).address()),
+        })
+    }
+
+    /// Reads the names of the protocols in a `protocol_list_t`
+    fn protocol_names(&self, address: Option<u64>) -> Vec<String> {
+        let Some(address) = address else {
+            return Vec::new();
+        };
+        let count = self.space.read_u64(address).unwrap_or(0);
+        self.list_entries(address, count, 8, 8)
+            .filter_map(|entry| match self.pointer(entry) {
+                Pointer::Address(protocol) => self.string_at_pointer(protocol + 8),
+                Pointer::Symbol { name, .. } => {
+                    Some(name.trim_start_matches("__OBJC_PROTOCOL_$_").to_string())
+                }
+                Pointer::Null => None,
+            })
+            .collect()
+    }
+
+    fn method_list(&self, address: Option<u64>) -> Vec<ObjcMethod> {
+        let Some(address) = address else {
+            return Vec::new();
+        };
+        let (Some(entsize_and_flags), Some(count)) = (
+            self.space.read_u32(address),
+            self.space.read_u32(address + 4),
+        ) else {
+            return Vec::new();
+        };
+
+        if entsize_and_flags & METHOD_LIST_IS_RELATIVE != 0 {
+            let entsize = (entsize_and_flags & METHOD_LIST_ENTSIZE_MASK) as u64;
+            self.list_entries(address, count as u64, entsize, 12)
+                .filter_map(|entry| {
+                    let name_offset = self.space.read_i32(entry)? as i64;
+                    let types_offset = self.space.read_i32(entry + 4)? as i64;
+                    let implementation_offset = self.space.read_i32(entry + 8)? as i64;
+                    let name_address = entry.wrapping_add_signed(name_offset);
+                    let name = if entsize_and_flags & METHOD_LIST_USES_DIRECT_SELECTORS != 0 {
+                        self.space.read_string(name_address)?
+                    } else {
+                        // The name points at the selector reference rather than the string
+                        self.string_at_pointer(name_address)?
+                    };
+                    Some(ObjcMethod {
+                        name,
+                        types: self
+                            .space
+                            .read_string((entry + 4).wrapping_add_signed(types_offset))
+                            .unwrap_or_default(),
+                        implementation: (entry + 8).wrapping_add_signed(implementation_offset),
+                    })
+                })
+                .collect()
+        } else {
+            let entsize = (entsize_and_flags & !0x3) as u64;
+            self.list_entries(address, count as u64, entsize, 24)
+                .filter_map(|entry| {
+                    Some(ObjcMethod {
+                        name: self.string_at_pointer(entry)?,
+                        types: self.string_at_pointer(entry + 8).unwrap_or_default(),
+                        implementation: self.pointer(entry + 16).address().unwrap_or(0),
+                    })
+                })
+                .collect()
+        }
+    }
+
+    fn ivar_list(&self, address: Option<u64>) -> Vec<ObjcIvar> {
+        let Some(address) = address else {
+            return Vec::new();
+        };
+        let (Some(entsize), Some(count)) = (
+            self.space.read_u32(address),
+            self.space.read_u32(address + 4),
+        ) else {
+            return Vec::new();
+        };
+        self.list_entries(address, count as u64, entsize as u64, 32)
+            .filter_map(|entry| {
+                let offset = self
+                    .pointer(entry)
+                    .address()
+                    .and_then(|offset| self.space.read_u32(offset))
+                    .unwrap_or(0);
+                let alignment = self.space.read_u32(entry + 24)?;
+                Some(ObjcIvar {
+                    name: self.string_at_pointer(entry + 8)?,
+                    types: self.string_at_pointer(entry + 16).unwrap_or_default(),
+                    offset,
+                    size: self.space.read_u32(entry + 28)?,
+                    // Stored as a power of two, with all ones meaning pointer alignment
+                    alignment: if alignment == u32::MAX {
+                        8
+                    } else {
+                        1u32.checked_shl(alignment).unwrap_or(0)
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn property_list(&self, address: Option<u64>) -> Vec<ObjcProperty> {
+        let Some(address) = address else {
+            return Vec::new();
+        };
+        let (Some(entsize), Some(count)) = (
+            self.space.read_u32(address),
+            self.space.read_u32(address + 4),
+        ) else {
+            return Vec::new();
+        };
+        self.list_entries(address, count as u64, entsize as u64, 16)
+            .filter_map(|entry| {
+                Some(ObjcProperty {
+                    name: self.string_at_pointer(entry)?,
+                    attributes: self.string_at_pointer(entry + 8).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for ObjcMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(image_info) = &self.image_info {
+            writeln!(f, "Image Info Version: {}", image_info.version)?;
+            writeln!(f, "Image Info Flags: {:x}", image_info.flags)?;
+            if let Some(swift_version) = image_info.swift_version() {
+                writeln!(f, "Swift Version: {}", swift_version)?;
+            }
+        }
+        writeln!(f, "Number of classes: {}", self.classes.len())?;
+        for class in &self.classes {
+            write!(f, "Class {}", class.name)?;
+            if let Some(superclass) = &class.superclass {
+                write!(f, " : {}", superclass)?;
+            }
+            writeln!(f, " ({:x})", class.address)?;
+            for method in class.class_methods() {
+                writeln!(f, "  + {} {:x}", method.name, method.implementation)?;
+            }
+            for method in &class.methods {
+                writeln!(f, "  - {} {:x}", method.name, method.implementation)?;
+            }
+        }
+        writeln!(f, "Number of categories: {}", self.categories.len())?;
+        for category in &self.categories {
+            writeln!(
+                f,
+                "Category {} ({})",
+                category.class_name.as_deref().unwrap_or("?"),
+                category.name
+            )?;
+        }
+        writeln!(f, "Number of protocols: {}", self.protocols.len())?;
+        for protocol in &self.protocols {
+            writeln!(f, "Protocol {}", protocol.name)?;
+        }
+        writeln!(
+            f,
+            "Number of selector references: {}",
+            self.selector_references.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A property list at 0x1010 with the given header, followed by two properties named `foo`
+    fn property_list(entsize: u32, count: u32) -> Vec<u8> {
+        let mut data = b"foo\0T@\0\0\0\0\0\0\0\0\0\0".to_vec();
+        data.extend_from_slice(&entsize.to_le_bytes());
+        data.extend_from_slice(&count.to_le_bytes());
+        for _ in 0..2 {
+            data.extend_from_slice(&0x1000u64.to_le_bytes());
+            data.extend_from_slice(&0x1004u64.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn reads_property_lists() {
+        let data = property_list(16, 2);
+        let mut space = AddressSpace::new(0);
+        space.add_region(0x1000, data.len() as u64, &data);
+        let properties = Reader { space: &space }.property_list(Some(0x1010));
+        let properties: Vec<_> = properties
+            .iter()
+            .map(|property| (property.name.as_str(), property.attributes.as_str()))
+            .collect();
+        assert_eq!(properties, [("foo", "T@"), ("foo", "T@")]);
+    }
+
+    #[test]
+    fn stops_lists_at_the_end_of_the_data() {
+        let data = property_list(16, u32::MAX);
+        let mut space = AddressSpace::new(0);
+        space.add_region(0x1000, u64::MAX - 0x1000, &data);
+        let reader = Reader { space: &space };
+        assert_eq!(reader.property_list(Some(0x1010)).len(), 2);
+
+        // Entries that are too small to hold a property would otherwise all be read at one address
+        let data = property_list(0, u32::MAX);
+        let mut space = AddressSpace::new(0);
+        space.add_region(0x1000, data.len() as u64, &data);
+        assert!(Reader { space: &space }
+            .property_list(Some(0x1010))
+            .is_empty());
+    }
+
+    #[test]
+    fn stops_pointer_lists_at_the_end_of_the_data() {
+        let data = 0u64.to_le_bytes();
+        let mut space = AddressSpace::new(0);
+        space.add_region(0x1000, u64::MAX - 0x1000, &data);
+        let class_list = Section64 {
+            name: "__objc_classlist".to_string(),
+            segment_name: "__DATA".to_string(),
+            addr: 0x1000,
+            size: u64::MAX - 0x1000,
+            offset: 0,
+            align: 3,
+            relocation_offset: 0,
+            number_relocations: 0,
+            flags: 0,
+            reserved_1: 0,
+            reserved_2: 0,
+            reserved_3: 0,
+        };
+        let metadata = ObjcMetadata::parse(&[&class_list], &space).unwrap();
+        assert!(metadata.classes.is_empty());
+    }
+}
@@ -0,0 +1,361 @@
+//! Generates `class-dump` style headers from Objective-C runtime metadata
+
+use std::fmt::Write;
+
+use super::{ObjcCategory, ObjcClass, ObjcMetadata, ObjcMethod, ObjcProperty, ObjcProtocol};
+
+impl ObjcMetadata {
+    /// Renders the metadata as Objective-C declarations
+    pub fn class_dump(&self) -> String {
+        let mut out = String::new();
+        for protocol in &self.protocols {
+            write_protocol(&mut out, protocol);
+        }
+        for class in &self.classes {
+            write_class(&mut out, class);
+        }
+        for category in &self.categories {
+            write_category(&mut out, category);
+        }
+        out
+    }
+}
+
+fn write_protocol(out: &mut String, protocol: &ObjcProtocol) {
+    write!(out, "@protocol {}", protocol.name).unwrap();
+    write_protocol_list(out, &protocol.protocols);
+    out.push('\n');
+    write_properties(out, &protocol.properties, false);
+    write_methods(out, &protocol.class_methods, '+');
+    write_methods(out, &protocol.instance_methods, '-');
+    if !protocol.optional_class_methods.is_empty() || !protocol.optional_instance_methods.is_empty()
+    {
+        out.push_str("\n@optional\n");
+        write_methods(out, &protocol.optional_class_methods, '+');
+        write_methods(out, &protocol.optional_instance_methods, '-');
+    }
+    out.push_str("@end\n\n");
+}
+
+fn write_class(out: &mut String, class: &ObjcClass) {
+    write!(out, "@interface {}", class.name).unwrap();
+    if let Some(superclass) = &class.superclass {
+        write!(out, " : {}", superclass).unwrap();
+    }
+    write_protocol_list(out, &class.protocols);
+    out.push('\n');
+    if !class.ivars.is_empty() {
+        out.push_str("{\n");
+        for ivar in &class.ivars {
+            // Swift ivars don't record their type
+            if ivar.types.is_empty() {
+                writeln!(out, "    // {} has no type encoding", ivar.name).unwrap();
+                continue;
+            }
+            let (typ, _) = parse_type(&ivar.types);
+            writeln!(out, "    {};", typ.declaration(&ivar.name)).unwrap();
+        }
+        out.push_str("}\n");
+    }
+    out.push('\n');
+    write_properties(out, class.class_properties(), true);
+    write_properties(out, &class.properties, false);
+    write_methods(out, class.class_methods(), '+');
+    write_methods(out, &class.methods, '-');
+    out.push_str("@end\n\n");
+}
+
+fn write_category(out: &mut String, category: &ObjcCategory) {
+    write!(
+        out,
+        "@interface {} ({})",
+        category.class_name.as_deref().unwrap_or("?"),
+        category.name
+    )
+    .unwrap();
+    write_protocol_list(out, &category.protocols);
+    out.push('\n');
+    write_properties(out, &category.properties, false);
+    write_methods(out, &category.class_methods, '+');
+    write_methods(out, &category.instance_methods, '-');
+    out.push_str("@end\n\n");
+}
+
+fn write_protocol_list(out: &mut String, protocols: &[String]) {
+    if !protocols.is_empty() {
+        write!(out, " <{}>", protocols.join(", ")).unwrap();
+    }
+}
+
+fn write_properties(out: &mut String, properties: &[ObjcProperty], class_properties: bool) {
+    for property in properties {
+        writeln!(out, "{}", property_declaration(property, class_properties)).unwrap();
+    }
+    if !properties.is_empty() {
+        out.push('\n');
+    }
+}
+
+fn write_methods(out: &mut String, methods: &[ObjcMethod], kind: char) {
+    for method in methods {
+        writeln!(out, "{} {};", kind, method_declaration(method)).unwrap();
+    }
+    if !methods.is_empty() {
+        out.push('\n');
+    }
+}
+
+/// Builds a method declaration like `(id)initWithName:(id)arg1 count:(int)arg2` from its selector
+/// and type encoding
+fn method_declaration(method: &ObjcMethod) -> String {
+    // The encoding is the return type followed by `self`, `_cmd` and then each argument, all with
+    // their stack offsets
+    let mut types = Vec::new();
+    let mut rest = method.types.as_str();
+    while !rest.is_empty() {
+        let (typ, next) = parse_type(rest);
+        types.push(typ);
+        rest = next.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-');
+    }
+    let type_name = |index: usize| {
+        types
+            .get(index)
+            .map(|typ| typ.declaration(""))
+            .unwrap_or_else(|| "id".to_string())
+    };
+
+    let mut declaration = format!("({})", type_name(0));
+    if !method.name.contains(':') {
+        declaration.push_str(&method.name);
+        return declaration;
+    }
+    let parts = method.name.split(':').filter(|part| !part.is_empty());
+    for (i, part) in parts.enumerate() {
+        if i != 0 {
+            declaration.push(' ');
+        }
+        write!(declaration, "{}:({})arg{}", part, type_name(i + 3), i + 1).unwrap();
+    }
+    declaration
+}
+
+fn property_declaration(property: &ObjcProperty, class_property: bool) -> String {
+    let mut typ = ObjcType::Named("id".to_string());
+    let mut attributes = Vec::new();
+    if class_property {
+        attributes.push("class".to_string());
+    }
+    for attribute in property.attributes.split(',') {
+        let mut chars = attribute.chars();
+        let Some(code) = chars.next() else {
+            continue;
+        };
+        let value = chars.as_str();
+        match code {
+            'T' => typ = parse_type(value).0,
+            'R' => attributes.push("readonly".to_string()),
+            'C' => attributes.push("copy".to_string()),
+            '&' => attributes.push("retain".to_string()),
+            'W' => attributes.push("weak".to_string()),
+            'N' => attributes.push("nonatomic".to_string()),
+            'G' => attributes.push(format!("getter={}", value)),
+            'S' => attributes.push(format!("setter={}", value)),
+            // Dynamic properties, the backing ivar and GC related attributes have no syntax
+            _ => {}
+        }
+    }
+    let attributes = if attributes.is_empty() {
+        String::new()
+    } else {
+        format!("({}) ", attributes.join(", "))
+    };
+    format!(
+        "@property {}{};",
+        attributes,
+        typ.declaration(&property.name)
+    )
+}
+
+/// A C type decoded from an Objective-C type encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ObjcType {
+    Named(String),
+    /// An object, optionally with its class name and protocols
+    Object(Option<String>),
+    Block,
+    Pointer(Box<ObjcType>),
+    Array(u64, Box<ObjcType>),
+    Struct(String),
+    Union(String),
+    BitField(u64),
+    /// A type qualifier like `const` applied to a type
+    Qualified(&'static str, Box<ObjcType>),
+}
+
+impl ObjcType {
+    /// A declaration of a variable of this type, or just the type when `name` is empty
+    fn declaration(&self, name: &str) -> String {
+        let separated = |typ: &str| {
+            if name.is_empty() {
+                typ.to_string()
+            } else {
+                format!("{} {}", typ, name)
+            }
+        };
+        match self {
+            ObjcType::Named(typ) => separated(typ),
+            ObjcType::Object(Some(class)) if class.starts_with('<') => {
+                separated(&format!("id {}", class))
+            }
+            ObjcType::Object(Some(class)) => format!("{} *{}", class, name),
+            ObjcType::Object(None) => separated("id"),
+            ObjcType::Block => format!(
+                "CDUnknownBlockType{}",
+                if name.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {}", name)
+                }
+            ),
+            ObjcType::Pointer(inner) => match inner.as_ref() {
+                ObjcType::Pointer(_) | ObjcType::Object(Some(_)) => {
+                    inner.declaration(&format!("*{}", name))
+                }
+                _ => format!("{} *{}", inner.declaration(""), name),
+            },
+            ObjcType::Array(count, inner) => inner.declaration(&format!("{}[{}]", name, count)),
+            ObjcType::Struct(typ) => separated(&format!("struct {}", typ)),
+            ObjcType::Union(typ) => separated(&format!("union {}", typ)),
+            ObjcType::BitField(bits) => format!("unsigned int {}:{}", name, bits),
+            ObjcType::Qualified(qualifier, inner) => {
+                format!("{} {}", qualifier, inner.declaration(name))
+            }
+        }
+    }
+}
+
+/// Parses one type from the start of an encoding, returning it and the rest of the encoding
+fn parse_type(encoding: &str) -> (ObjcType, &str) {
+    let mut chars = encoding.chars();
+    let Some(code) = chars.next() else {
+        return (ObjcType::Named("void".to_string()), encoding);
+    };
+    let rest = chars.as_str();
+    let named = |name: &str| (ObjcType::Named(name.to_string()), rest);
+    match code {
+        'c' => named("char"),
+        'i' => named("int"),
+        's' => named("short"),
+        'l' => named("long"),
+        'q' => named("long long"),
+        'C' => named("unsigned char"),
+        'I' => named("unsigned int"),
+        'S' => named("unsigned short"),
+        'L' => named("unsigned long"),
+        'Q' => named("unsigned long long"),
+        'f' => named("float"),
+        'd' => named("double"),
+        'D' => named("long double"),
+        'B' => named("_Bool"),
+        'v' => named("void"),
+        '*' => named("char *"),
+        '#' => named("Class"),
+        ':' => named("SEL"),
+        '?' => named("void"),
+        't' => named("__int128"),
+        'T' => named("unsigned __int128"),
+        '@' => {
+            if let Some(rest) = rest.strip_prefix('?') {
+                (ObjcType::Block, rest)
+            } else if let Some(quoted) = rest.strip_prefix('"') {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                let class = &quoted[..end];
+                let rest = &quoted[(end + 1).min(quoted.len())..];
+                if class.is_empty() {
+                    (ObjcType::Object(None), rest)
+                } else {
+                    (ObjcType::Object(Some(class.to_string())), rest)
+                }
+            } else {
+                (ObjcType::Object(None), rest)
+            }
+        }
+        '^' => {
+            let (inner, rest) = parse_type(rest);
+            (ObjcType::Pointer(Box::new(inner)), rest)
+        }
+        '[' => {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let count = rest[..digits].parse().unwrap_or(0);
+            let (inner, rest) = parse_type(&rest[digits..]);
+            (
+                ObjcType::Array(count, Box::new(inner)),
+                rest.strip_prefix(']').unwrap_or(rest),
+            )
+        }
+        '{' | '(' => {
+            let close = if code == '{' { '}' } else { ')' };
+            let body_end = matching_bracket(rest, code, close);
+            let body = &rest[..body_end];
+            let name = body.split('=').next().unwrap_or_default();
+            let name = if name.is_empty() || name == "?" {
+                "?".to_string()
+            } else {
+                name.to_string()
+            };
+            let rest = &rest[(body_end + 1).min(rest.len())..];
+            if code == '{' {
+                (ObjcType::Struct(name), rest)
+            } else {
+                (ObjcType::Union(name), rest)
+            }
+        }
+        'b' => {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            (
+                ObjcType::BitField(rest[..digits].parse().unwrap_or(0)),
+                &rest[digits..],
+            )
+        }
+        'r' | 'n' | 'N' | 'o' | 'O' | 'R' | 'V' | 'A' => {
+            let qualifier = match code {
+                'r' => "const",
+                'n' => "in",
+                'N' => "inout",
+                'o' => "out",
+                'O' => "bycopy",
+                'R' => "byref",
+                'V' => "oneway",
+                _ => "_Atomic",
+            };
+            let (inner, rest) = parse_type(rest);
+            (ObjcType::Qualified(qualifier, Box::new(inner)), rest)
+        }
+        _ => named("void"),
+    }
+}
+
+/// Finds the index of the bracket that closes an already opened one
+fn matching_bracket(input: &str, open: char, close: char) -> usize {
+    let mut depth = 1;
+    let mut in_quotes = false;
+    for (i, c) in input.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            _ if in_quotes => {}
+            c if c == open => depth += 1,
+            c if c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    input.len()
+}
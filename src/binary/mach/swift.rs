@@ -0,0 +1,644 @@
+use std::fmt;
+
+use super::address_space::{AddressSpace, Pointer};
+use super::load_commands::Section64;
+
+/// Swift reflection metadata, read from the `__swift5_*` sections
+#[derive(Debug)]
+pub struct SwiftMetadata {
+    /// Nominal types from `__swift5_types`
+    pub types: Vec<SwiftType>,
+    /// Protocols from `__swift5_protos`
+    pub protocols: Vec<SwiftProtocol>,
+    /// Protocol conformances from `__swift5_proto`
+    pub conformances: Vec<SwiftConformance>,
+    /// Associated type witnesses from `__swift5_assocty`
+    pub associated_types: Vec<SwiftAssociatedTypes>,
+    /// Strings from `__swift5_reflstr`
+    pub reflection_strings: Vec<String>,
+}
+
+/// The kind of a context descriptor, from MetadataValues.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextDescriptorKind {
+    Module,
+    Extension,
+    Anonymous,
+    Protocol,
+    OpaqueType,
+    Class,
+    Struct,
+    Enum,
+    Unknown(u8),
+}
+
+impl From<u32> for ContextDescriptorKind {
+    fn from(flags: u32) -> Self {
+        match (flags & 0x1f) as u8 {
+            0 => Self::Module,
+            1 => Self::Extension,
+            2 => Self::Anonymous,
+            3 => Self::Protocol,
+            4 => Self::OpaqueType,
+            16 => Self::Class,
+            17 => Self::Struct,
+            18 => Self::Enum,
+            kind => Self::Unknown(kind),
+        }
+    }
+}
+
+impl fmt::Display for ContextDescriptorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextDescriptorKind::Module => write!(f, "module"),
+            ContextDescriptorKind::Extension => write!(f, "extension"),
+            ContextDescriptorKind::Anonymous => write!(f, "anonymous"),
+            ContextDescriptorKind::Protocol => write!(f, "protocol"),
+            ContextDescriptorKind::OpaqueType => write!(f, "opaque type"),
+            ContextDescriptorKind::Class => write!(f, "class"),
+            ContextDescriptorKind::Struct => write!(f, "struct"),
+            ContextDescriptorKind::Enum => write!(f, "enum"),
+            ContextDescriptorKind::Unknown(kind) => write!(f, "unknown ({})", kind),
+        }
+    }
+}
+
+/// A nominal type descriptor
+#[derive(Debug)]
+pub struct SwiftType {
+    /// Address of the type descriptor
+    pub address: u64,
+    pub kind: ContextDescriptorKind,
+    pub flags: u32,
+    /// The fully qualified name, including the module and any enclosing types
+    pub name: String,
+    /// The mangled name of the superclass, for classes
+    pub superclass: Option<String>,
+    pub fields: Vec<SwiftField>,
+}
+
+impl SwiftType {
+    pub fn is_generic(&self) -> bool {
+        self.flags & 0x80 != 0
+    }
+}
+
+#[derive(Debug)]
+pub struct SwiftField {
+    pub name: String,
+    /// The mangled type of the field, with symbolic references replaced by the names they refer to
+    pub mangled_type: String,
+    pub flags: u32,
+}
+
+impl SwiftField {
+    pub fn is_var(&self) -> bool {
+        self.flags & 0x2 != 0
+    }
+
+    pub fn is_indirect_case(&self) -> bool {
+        self.flags & 0x1 != 0
+    }
+}
+
+#[derive(Debug)]
+pub struct SwiftProtocol {
+    pub address: u64,
+    pub name: String,
+    pub number_requirements: u32,
+    pub number_requirements_in_signature: u32,
+    pub associated_type_names: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct SwiftConformance {
+    pub address: u64,
+    pub protocol: String,
+    pub type_name: String,
+    pub flags: u32,
+}
+
+#[derive(Debug)]
+pub struct SwiftAssociatedTypes {
+    pub conforming_type: String,
+    pub protocol: String,
+    /// Each associated type name and the mangled type substituted for it
+    pub types: Vec<(String, String)>,
+}
+
+impl SwiftMetadata {
+    /// Reads the Swift reflection metadata of an image, if it has any
+    pub(crate) fn parse(sections: &[&Section64], space: &AddressSpace) -> Option<Self> {
+        let find = |name: &str| sections.iter().find(|section| section.name == name);
+        let types = find("__swift5_types");
+        let protocols = find("__swift5_protos");
+        let conformances = find("__swift5_proto");
+        let associated_types = find("__swift5_assocty");
+        let reflection_strings = find("__swift5_reflstr");
+        if types.is_none()
+            && protocols.is_none()
+            && conformances.is_none()
+            && associated_types.is_none()
+        {
+            return None;
+        }
+
+        let reader = Reader { space };
+        // Each of these sections is a list of 32 bit relative pointers
+        let targets = |section: Option<&&Section64>| -> Vec<u64> {
+            section
+                .map(|section| {
+                    let size = space
+                        .read_to_end(section.addr)
+                        .map_or(0, |data| section.size.min(data.len() as u64));
+                    (0..size / 4)
+                        .filter_map(|i| reader.relative(section.addr + i * 4))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let types = targets(types)
+            .into_iter()
+            // The low bits of type records say whether the pointer is indirect
+            .filter_map(|target| match target & 0x3 {
+                0 => Some(target),
+                _ => space.read_pointer(target & !0x3)?.address(),
+            })
+            .filter_map(|address| reader.nominal_type(address))
+            .collect();
+        let protocols = targets(protocols)
+            .into_iter()
+            .filter_map(|address| reader.protocol(address))
+            .collect();
+        let conformances = targets(conformances)
+            .into_iter()
+            .filter_map(|address| reader.conformance(address))
+            .collect();
+        let associated_types = associated_types
+            .map(|section| reader.associated_types(section))
+            .unwrap_or_default();
+        let reflection_strings = reflection_strings
+            .and_then(|section| space.read(section.addr, section.size as usize))
+            .map(|strings| {
+                strings
+                    .split(|&b| b == 0)
+                    .filter(|s| !s.is_empty())
+                    .map(|s| String::from_utf8_lossy(s).into_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            types,
+            protocols,
+            conformances,
+            associated_types,
+            reflection_strings,
+        })
+    }
+}
+
+/// How many symbolic references reading one name may resolve. Names can refer to each other, or
+/// to themselves, so this stops the reader from following them forever.
+const REFERENCE_BUDGET: u32 = 64;
+
+/// Follows the relative pointers between the Swift metadata structures of an image
+struct Reader<'s, 'a> {
+    space: &'s AddressSpace<'a>,
+}
+
+impl Reader<'_, '_> {
+    /// Resolves a 32 bit relative pointer stored at `address`. Null pointers resolve to `None`.
+    fn relative(&self, address: u64) -> Option<u64> {
+        match self.space.read_i32(address)? {
+            0 => None,
+            offset => Some(address.wrapping_add_signed(offset as i64)),
+        }
+    }
+
+    /// Resolves a relative pointer whose low bit says that it points at a pointer to the target
+    /// instead of the target itself
+    fn relative_indirectable(&self, address: u64) -> Option<Pointer> {
+        let offset = self.space.read_i32(address)?;
+        if offset == 0 {
+            return Some(Pointer::Null);
+        }
+        let target = address.wrapping_add_signed((offset & !1) as i64);
+        if offset & 1 == 0 {
+            Some(Pointer::Address(target))
+        } else {
+            self.space.read_pointer(target)
+        }
+    }
+
+    fn relative_string(&self, address: u64) -> Option<String> {
+        self.space.read_string(self.relative(address)?)
+    }
+
+    /// The fully qualified name of the context descriptor at `address`
+    fn context_name(&self, address: u64) -> Option<String> {
+        let mut budget = REFERENCE_BUDGET;
+        self.context_name_within(address, &mut budget)
+    }
+
+    /// Reads the name of a context descriptor, resolving at most `budget` symbolic references in
+    /// the names of the extensions it is nested in
+    fn context_name_within(&self, address: u64, budget: &mut u32) -> Option<String> {
+        let mut components = Vec::new();
+        let mut current = Some(address);
+        // Bound the walk in case the parents form a loop
+        for _ in 0..32 {
+            let Some(address) = current else {
+                break;
+            };
+            let flags = self.space.read_u32(address)?;
+            match ContextDescriptorKind::from(flags) {
+                ContextDescriptorKind::Anonymous => {}
+                ContextDescriptorKind::Extension => components.push(format!(
+                    "(extension {})",
+                    self.relative(address + 8)
+                        .and_then(|name| self.mangled_name_within(name, budget))
+                        .unwrap_or_default()
+                )),
+                _ => components.push(self.relative_string(address + 8)?),
+            }
+            current = match self.relative_indirectable(address + 4)? {
+                Pointer::Address(parent) => Some(parent),
+                Pointer::Symbol { name, .. } => {
+                    components.push(name);
+                    None
+                }
+                Pointer::Null => None,
+            };
+        }
+        components.reverse();
+        Some(components.join("."))
+    }
+
+    /// Reads the mangled name that the relative pointer at `address` points to
+    fn mangled_name_at(&self, address: u64) -> Option<String> {
+        self.mangled_name(self.relative(address)?)
+    }
+
+    /// Reads a mangled name, replacing any symbolic references it contains with the names of the
+    /// context descriptors that they refer to
+    fn mangled_name(&self, address: u64) -> Option<String> {
+        let mut budget = REFERENCE_BUDGET;
+        self.mangled_name_within(address, &mut budget)
+    }
+
+    /// Reads a mangled name, resolving at most `budget` of the symbolic references it and the
+    /// names it refers to contain. The rest are printed as `<?>`.
+    fn mangled_name_within(&self, address: u64, budget: &mut u32) -> Option<String> {
+        let mut name = String::new();
+        let mut current = address;
+        loop {
+            let byte = *self.space.read(current, 1)?.first()?;
+            match byte {
+                0 => break,
+                // Symbolic references with a 32 bit relative offset
+                0x01..=0x17 => {
+                    let target = self.relative(current + 1).filter(|_| *budget > 0);
+                    *budget = budget.saturating_sub(1);
+                    let referenced = match (byte, target) {
+                        // Direct reference to a context descriptor
+                        (0x01, Some(target)) => self.context_name_within(target, budget),
+                        // Indirect reference to a context descriptor
+                        (0x02, Some(target)) => match self.space.read_pointer(target) {
+                            Some(Pointer::Address(target)) => {
+                                self.context_name_within(target, budget)
+                            }
+                            Some(Pointer::Symbol { name, .. }) => Some(name),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    name.push('<');
+                    name.push_str(referenced.as_deref().unwrap_or("?"));
+                    name.push('>');
+                    current += 5;
+                }
+                // Symbolic references with an absolute pointer
+                0x18..=0x1f => {
+                    name.push_str("<?>");
+                    current += 9;
+                }
+                _ => {
+                    name.push(byte as char);
+                    current += 1;
+                }
+            }
+        }
+        Some(name)
+    }
+
+    fn nominal_type(&self, address: u64) -> Option<SwiftType> {
+        let flags = self.space.read_u32(address)?;
+        let kind = ContextDescriptorKind::from(flags);
+        let name = self.context_name(address)?;
+        let (superclass, fields) = match self.relative(address + 16) {
+            Some(field_descriptor) => self.field_descriptor(field_descriptor)?,
+            None => (None, Vec::new()),
+        };
+        Some(SwiftType {
+            address,
+            kind,
+            flags,
+            name,
+            superclass,
+            fields,
+        })
+    }
+
+    /// Reads a field descriptor, returning its superclass and fields
+    fn field_descriptor(&self, address: u64) -> Option<(Option<String>, Vec<SwiftField>)> {
+        let superclass = self.mangled_name_at(address + 4);
+        let record_size = self.space.read(address + 10, 2)?;
+        let record_size = u16::from_le_bytes([record_size[0], record_size[1]]) as u64;
+        let number_fields = self.space.read_u32(address + 12)? as u64;
+        // Records are 12 bytes or more, and stop at the end of the data backing them so that a
+        // corrupt count can't run past it
+        let fields = (0..number_fields)
+            .map_while(|i| {
+                address
+                    .checked_add(16)?
+                    .checked_add(i.checked_mul(record_size)?)
+            })
+            .take_while(|&record| {
+                record_size >= 12 && self.space.read(record, record_size as usize).is_some()
+            })
+            .map_while(|record| {
+                Some(SwiftField {
+                    flags: self.space.read_u32(record)?,
+                    mangled_type: self.mangled_name_at(record + 4).unwrap_or_default(),
+                    name: self.relative_string(record + 8).unwrap_or_default(),
+                })
+            })
+            .collect();
+        Some((superclass, fields))
+    }
+
+    fn protocol(&self, address: u64) -> Option<SwiftProtocol> {
+        let associated_type_names = self
+            .relative_string(address + 20)
+            .map(|names| names.split(' ').map(str::to_string).collect())
+            .unwrap_or_default();
+        Some(SwiftProtocol {
+            address,
+            name: self.context_name(address)?,
+            number_requirements_in_signature: self.space.read_u32(address + 12)?,
+            number_requirements: self.space.read_u32(address + 16)?,
+            associated_type_names,
+        })
+    }
+
+    fn conformance(&self, address: u64) -> Option<SwiftConformance> {
+        let protocol = match self.relative_indirectable(address)? {
+            Pointer::Address(protocol) => self.context_name(protocol)?,
+            Pointer::Symbol { name, .. } => name,
+            Pointer::Null => return None,
+        };
+        let flags = self.space.read_u32(address + 12)?;
+        let type_reference = self.relative(address + 4);
+        let type_name = match ((flags >> 3) & 0x7, type_reference) {
+            // Direct type descriptor
+            (0, Some(target)) => self.context_name(target),
+            // Indirect type descriptor
+            (1, Some(target)) => match self.space.read_pointer(target)? {
+                Pointer::Address(target) => self.context_name(target),
+                Pointer::Symbol { name, .. } => Some(name),
+                Pointer::Null => None,
+            },
+            // Objective-C class name
+            (2, Some(target)) => self.space.read_string(target),
+            // Indirect Objective-C class
+            (3, Some(target)) => match self.space.read_pointer(target)? {
+                Pointer::Symbol { name, .. } => Some(name),
+                Pointer::Address(class) => Some(format!("objc class {:x}", class)),
+                Pointer::Null => None,
+            },
+            _ => None,
+        };
+        Some(SwiftConformance {
+            address,
+            protocol,
+            type_name: type_name.unwrap_or_else(|| "?".to_string()),
+            flags,
+        })
+    }
+
+    fn associated_types(&self, section: &Section64) -> Vec<SwiftAssociatedTypes> {
+        let mut descriptors = Vec::new();
+        let mut address = section.addr;
+        let end = section.addr.saturating_add(section.size);
+        while address + 16 <= end {
+            let (Some(number_types), Some(record_size)) = (
+                self.space.read_u32(address + 8),
+                self.space.read_u32(address + 12),
+            ) else {
+                break;
+            };
+            let (number_types, record_size) = (number_types as u64, record_size as u64);
+            // Each record holds two relative pointers, and all of them have to be in what's left
+            // of the section
+            let records_size = number_types.checked_mul(record_size);
+            let records_end = records_size.and_then(|size| (address + 16).checked_add(size));
+            let (Some(records_size), Some(records_end)) = (records_size, records_end) else {
+                break;
+            };
+            if records_end > end
+                || number_types != 0 && record_size < 8
+                || self
+                    .space
+                    .read(address + 16, records_size as usize)
+                    .is_none()
+            {
+                break;
+            }
+            let types = (0..number_types)
+                .map(|i| {
+                    let record = address + 16 + i * record_size;
+                    (
+                        self.relative_string(record).unwrap_or_default(),
+                        self.mangled_name_at(record + 4).unwrap_or_default(),
+                    )
+                })
+                .collect();
+            descriptors.push(SwiftAssociatedTypes {
+                conforming_type: self.mangled_name_at(address).unwrap_or_default(),
+                protocol: self.mangled_name_at(address + 4).unwrap_or_default(),
+                types,
+            });
+            address = records_end;
+        }
+        descriptors
+    }
+}
+
+impl fmt::Display for SwiftMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Number of types: {}", self.types.len())?;
+        for typ in &self.types {
+            write!(f, "{} {}", typ.kind, typ.name)?;
+            if let Some(superclass) = &typ.superclass {
+                write!(f, " : {}", superclass)?;
+            }
+            writeln!(f, " ({:x})", typ.address)?;
+            for field in &typ.fields {
+                let introducer = match typ.kind {
+                    ContextDescriptorKind::Enum if field.is_indirect_case() => "indirect case",
+                    ContextDescriptorKind::Enum => "case",
+                    _ if field.is_var() => "var",
+                    _ => "let",
+                };
+                writeln!(f, "  {} {}: {}", introducer, field.name, field.mangled_type)?;
+            }
+        }
+        writeln!(f, "Number of protocols: {}", self.protocols.len())?;
+        for protocol in &self.protocols {
+            write!(
+                f,
+                "protocol {} ({} requirements)",
+                protocol.name, protocol.number_requirements
+            )?;
+            if !protocol.associated_type_names.is_empty() {
+                write!(
+                    f,
+                    " associated types: {}",
+                    protocol.associated_type_names.join(", ")
+                )?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "Number of conformances: {}", self.conformances.len())?;
+        for conformance in &self.conformances {
+            writeln!(f, "{} : {}", conformance.type_name, conformance.protocol)?;
+        }
+        writeln!(
+            f,
+            "Number of associated type descriptors: {}",
+            self.associated_types.len()
+        )?;
+        for associated_types in &self.associated_types {
+            writeln!(
+                f,
+                "{} : {}",
+                associated_types.conforming_type, associated_types.protocol
+            )?;
+            for (name, substituted) in &associated_types.types {
+                writeln!(f, "  {} = {}", name, substituted)?;
+            }
+        }
+        writeln!(
+            f,
+            "Number of reflection strings: {}",
+            self.reflection_strings.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An `__swift5_assocty` section at 0x1000 holding one descriptor with the given number of
+    /// types, the first of which maps `Element` to `Si`
+    fn associated_types(number_types: u32) -> (Section64, Vec<u8>) {
+        let mut data = Vec::new();
+        for field in [0x18, 0x14, number_types, 8, 0x0b, 0x04] {
+            data.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        data.extend_from_slice(b"Si\0Element\0");
+        (section("__swift5_assocty", 0x18), data)
+    }
+
+    /// A `__TEXT` section at 0x1000
+    fn section(name: &str, size: u64) -> Section64 {
+        Section64 {
+            name: name.to_string(),
+            segment_name: "__TEXT".to_string(),
+            addr: 0x1000,
+            size,
+            offset: 0,
+            align: 2,
+            relocation_offset: 0,
+            number_relocations: 0,
+            flags: 0,
+            reserved_1: 0,
+            reserved_2: 0,
+            reserved_3: 0,
+        }
+    }
+
+    #[test]
+    fn reads_associated_types() {
+        let (section, data) = associated_types(1);
+        let mut space = AddressSpace::new(0);
+        space.add_region(0x1000, data.len() as u64, &data);
+        let descriptors = Reader { space: &space }.associated_types(&section);
+        assert_eq!(descriptors.len(), 1);
+        assert_eq!(descriptors[0].conforming_type, "Si");
+        assert_eq!(descriptors[0].protocol, "Si");
+        assert_eq!(
+            descriptors[0].types,
+            [("Element".to_string(), "Si".to_string())]
+        );
+    }
+
+    #[test]
+    fn stops_at_associated_type_counts_past_the_section() {
+        let (section, data) = associated_types(u32::MAX);
+        let mut space = AddressSpace::new(0);
+        space.add_region(0x1000, data.len() as u64, &data);
+        assert!(Reader { space: &space }
+            .associated_types(&section)
+            .is_empty());
+    }
+
+    #[test]
+    fn stops_field_records_at_the_end_of_the_data() {
+        // A field descriptor with no superclass, 12 byte records and far more fields than follow
+        let mut data = Vec::new();
+        for field in [0, 0, 12 << 16, u32::MAX, 2, 0, 0] {
+            data.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        let mut space = AddressSpace::new(0);
+        space.add_region(0x1000, u64::MAX - 0x1000, &data);
+        let (superclass, fields) = Reader { space: &space }.field_descriptor(0x1000).unwrap();
+        assert_eq!(superclass, None);
+        assert_eq!(fields.len(), 1);
+        assert!(fields[0].is_var());
+    }
+
+    #[test]
+    fn stops_at_extensions_named_after_themselves() {
+        // A type list pointing at an extension whose mangled name is a symbolic reference to the
+        // extension itself
+        let mut data = Vec::new();
+        for field in [4, 1, 0, 4] {
+            data.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        data.push(0x01);
+        data.extend_from_slice(&i32::to_le_bytes(-13));
+        data.push(0);
+        let mut space = AddressSpace::new(0);
+        space.add_region(0x1000, data.len() as u64, &data);
+        let metadata = SwiftMetadata::parse(&[&section("__swift5_types", 4)], &space).unwrap();
+        assert_eq!(metadata.types.len(), 1);
+        let name = &metadata.types[0].name;
+        assert!(name.starts_with("(extension <(extension <"));
+        assert!(name.contains("<?>"));
+    }
+
+    #[test]
+    fn stops_type_lists_at_the_end_of_the_data() {
+        let data = i32::to_le_bytes(0x10);
+        let mut space = AddressSpace::new(0);
+        space.add_region(0x1000, u64::MAX - 0x1000, &data);
+        let section = section("__swift5_types", u64::MAX - 0x1000);
+        let metadata = SwiftMetadata::parse(&[&section], &space).unwrap();
+        assert!(metadata.types.is_empty());
+    }
+}
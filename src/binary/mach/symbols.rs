@@ -0,0 +1,55 @@
+use nom::multi::count;
+use nom::number::complete::{le_u16, le_u32, le_u64, u8};
+use nom::sequence::tuple;
+
+use crate::parse::{self, string_at};
+
+/// Mask of the `n_type` bits that mark a debugging (stab) entry
+const N_STAB: u8 = 0xe0;
+/// Mask of the `n_type` bits that say where a symbol is defined
+const N_TYPE: u8 = 0x0e;
+/// A symbol defined in the section numbered `n_sect`
+const N_SECT: u8 = 0x0e;
+/// An external symbol
+const N_EXT: u8 = 0x01;
+
+/// An `nlist_64` entry of a symbol table
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub typ: u8,
+    pub section: u8,
+    pub description: u16,
+    pub value: u64,
+}
+
+impl Symbol {
+    /// Whether the symbol is defined in a section, rather than undefined, absolute or a stab
+    pub fn is_defined_in_section(&self) -> bool {
+        self.typ & N_STAB == 0 && self.typ & N_TYPE == N_SECT
+    }
+
+    pub fn is_external(&self) -> bool {
+        self.typ & N_EXT != 0
+    }
+}
+
+/// Parses `number` 64 bit little endian `nlist` entries, naming them from `strings`
+pub(crate) fn parse_symbols<'a>(
+    input: parse::Input<'a>,
+    number: usize,
+    strings: &[u8],
+) -> parse::ParseResult<'a, Vec<Symbol>> {
+    let (input, nlists) = count(tuple((le_u32, u8, u8, le_u16, le_u64)), number)(input)?;
+    let symbols = nlists
+        .into_iter()
+        .map(|(string_index, typ, section, description, value)| Symbol {
+            name: string_at(strings, string_index as usize),
+            typ,
+            section,
+            description,
+            value,
+        })
+        .collect();
+    Ok((input, symbols))
+}
@@ -0,0 +1,677 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+
+use super::machine::CpuType;
+use crate::parse;
+
+// Encoding bits shared by every architecture, from compact_unwind_encoding.h
+const UNWIND_IS_NOT_FUNCTION_START: u32 = 0x8000_0000;
+const UNWIND_HAS_LSDA: u32 = 0x4000_0000;
+const UNWIND_PERSONALITY_MASK: u32 = 0x3000_0000;
+const UNWIND_MODE_MASK: u32 = 0x0f00_0000;
+/// The offset of the FDE in `__eh_frame` for the DWARF modes
+const UNWIND_DWARF_SECTION_OFFSET: u32 = 0x00ff_ffff;
+
+const UNWIND_X86_64_MODE_RBP_FRAME: u32 = 1;
+const UNWIND_X86_64_MODE_STACK_IMMD: u32 = 2;
+const UNWIND_X86_64_MODE_STACK_IND: u32 = 3;
+const UNWIND_X86_64_MODE_DWARF: u32 = 4;
+const UNWIND_X86_64_RBP_FRAME_REGISTERS: u32 = 0x0000_7fff;
+const UNWIND_X86_64_RBP_FRAME_OFFSET: u32 = 0x00ff_0000;
+const UNWIND_X86_64_FRAMELESS_STACK_SIZE: u32 = 0x00ff_0000;
+const UNWIND_X86_64_FRAMELESS_STACK_ADJUST: u32 = 0x0000_e000;
+const UNWIND_X86_64_FRAMELESS_STACK_REG_COUNT: u32 = 0x0000_1c00;
+const UNWIND_X86_64_FRAMELESS_STACK_REG_PERMUTATION: u32 = 0x0000_03ff;
+/// The callee saved registers, by the 3 bit numbers the encodings use
+const X86_64_REGISTERS: [&str; 7] = ["", "rbx", "r12", "r13", "r14", "r15", "rbp"];
+
+const UNWIND_ARM64_MODE_FRAMELESS: u32 = 2;
+const UNWIND_ARM64_MODE_DWARF: u32 = 3;
+const UNWIND_ARM64_MODE_FRAME: u32 = 4;
+const UNWIND_ARM64_FRAMELESS_STACK_SIZE_MASK: u32 = 0x00ff_f000;
+/// The register pairs a frame saves, by their bit in the encoding
+const ARM64_REGISTER_PAIRS: [(u32, &str); 9] = [
+    (0x001, "x19/x20"),
+    (0x002, "x21/x22"),
+    (0x004, "x23/x24"),
+    (0x008, "x25/x26"),
+    (0x010, "x27/x28"),
+    (0x100, "d8/d9"),
+    (0x200, "d10/d11"),
+    (0x400, "d12/d13"),
+    (0x800, "d14/d15"),
+];
+
+const UNWIND_SECOND_LEVEL_REGULAR: u32 = 2;
+const UNWIND_SECOND_LEVEL_COMPRESSED: u32 = 3;
+
+/// A 32 bit compact unwind encoding, whose low 24 bits depend on the architecture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoding(pub u32);
+
+impl Encoding {
+    /// Whether the entry continues the function of the previous one, as for cold parts
+    pub fn is_function_start(self) -> bool {
+        self.0 & UNWIND_IS_NOT_FUNCTION_START == 0
+    }
+
+    pub fn has_lsda(self) -> bool {
+        self.0 & UNWIND_HAS_LSDA != 0
+    }
+
+    /// The 1-based index of the personality function, or 0 for none
+    pub fn personality_index(self) -> u32 {
+        (self.0 & UNWIND_PERSONALITY_MASK) >> UNWIND_PERSONALITY_MASK.trailing_zeros()
+    }
+
+    /// Interprets the mode bits and their operands for an architecture
+    pub fn mode(self, cpu_type: CpuType) -> UnwindMode {
+        let mode = (self.0 & UNWIND_MODE_MASK) >> UNWIND_MODE_MASK.trailing_zeros();
+        let field = |mask: u32| (self.0 & mask) >> mask.trailing_zeros();
+        if self.0 == 0 {
+            return UnwindMode::None;
+        }
+        match (cpu_type, mode) {
+            (CpuType::X86_64, UNWIND_X86_64_MODE_RBP_FRAME) => {
+                let registers = field(UNWIND_X86_64_RBP_FRAME_REGISTERS);
+                UnwindMode::RbpFrame {
+                    offset: field(UNWIND_X86_64_RBP_FRAME_OFFSET) * 8,
+                    registers: (0..5)
+                        .map(|i| (registers >> (3 * i)) & 0x7)
+                        .filter(|&register| register != 0)
+                        .map(|register| *X86_64_REGISTERS.get(register as usize).unwrap_or(&"?"))
+                        .collect(),
+                }
+            }
+            (CpuType::X86_64, UNWIND_X86_64_MODE_STACK_IMMD | UNWIND_X86_64_MODE_STACK_IND) => {
+                let stack_size = field(UNWIND_X86_64_FRAMELESS_STACK_SIZE);
+                let registers = frameless_registers(
+                    field(UNWIND_X86_64_FRAMELESS_STACK_REG_COUNT),
+                    field(UNWIND_X86_64_FRAMELESS_STACK_REG_PERMUTATION),
+                );
+                if mode == UNWIND_X86_64_MODE_STACK_IMMD {
+                    UnwindMode::FramelessImmediate {
+                        stack_size: stack_size * 8,
+                        registers,
+                    }
+                } else {
+                    UnwindMode::FramelessIndirect {
+                        stack_size_offset: stack_size,
+                        stack_adjust: field(UNWIND_X86_64_FRAMELESS_STACK_ADJUST) * 8,
+                        registers,
+                    }
+                }
+            }
+            (CpuType::X86_64, UNWIND_X86_64_MODE_DWARF)
+            | (CpuType::Arm64, UNWIND_ARM64_MODE_DWARF) => UnwindMode::Dwarf {
+                fde_offset: self.0 & UNWIND_DWARF_SECTION_OFFSET,
+            },
+            (CpuType::Arm64, UNWIND_ARM64_MODE_FRAMELESS) => UnwindMode::Frameless {
+                stack_size: field(UNWIND_ARM64_FRAMELESS_STACK_SIZE_MASK) * 16,
+            },
+            (CpuType::Arm64, UNWIND_ARM64_MODE_FRAME) => UnwindMode::Frame {
+                register_pairs: ARM64_REGISTER_PAIRS
+                    .iter()
+                    .filter(|(bit, _)| self.0 & bit != 0)
+                    .map(|(_, pair)| *pair)
+                    .collect(),
+            },
+            _ => UnwindMode::Unknown(mode),
+        }
+    }
+}
+
+/// Decodes the order in which a frameless x86_64 function pushed its registers, which is stored
+/// as a permutation number
+fn frameless_registers(register_count: u32, permutation: u32) -> Vec<&'static str> {
+    let count = register_count.min(6) as usize;
+    // Each position picks among the registers not picked yet, so the radix shrinks by one
+    let mut permutation = permutation;
+    let mut choices = [0u32; 6];
+    let radixes: &[u32] = match count {
+        6 | 5 => &[120, 24, 6, 2, 1],
+        4 => &[60, 12, 3, 1],
+        3 => &[20, 4, 1],
+        2 => &[5, 1],
+        1 => &[1],
+        _ => &[],
+    };
+    for (choice, radix) in choices.iter_mut().zip(radixes) {
+        *choice = permutation / radix;
+        permutation -= *choice * radix;
+    }
+    let mut used = [false; 7];
+    let mut registers = Vec::with_capacity(count);
+    for &choice in &choices[..count] {
+        let register = (1..7)
+            .filter(|&register| !used[register])
+            .nth(choice as usize);
+        if let Some(register) = register {
+            used[register] = true;
+            registers.push(X86_64_REGISTERS[register]);
+        }
+    }
+    registers
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#010x}", self.0)?;
+        if !self.is_function_start() {
+            write!(f, " not function start")?;
+        }
+        if self.has_lsda() {
+            write!(f, " LSDA")?;
+        }
+        if self.personality_index() != 0 {
+            write!(f, " personality {}", self.personality_index())?;
+        }
+        Ok(())
+    }
+}
+
+/// How to unwind a function, decoded from its encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnwindMode {
+    /// No unwind information, as for functions that can't be unwound through
+    None,
+    /// x86_64: RBP is the frame pointer, and the registers are saved `offset` bytes below it
+    RbpFrame {
+        offset: u32,
+        registers: Vec<&'static str>,
+    },
+    /// x86_64: no frame pointer, with the stack size in the encoding
+    FramelessImmediate {
+        stack_size: u32,
+        registers: Vec<&'static str>,
+    },
+    /// x86_64: no frame pointer, with the stack size in the `sub` instruction at
+    /// `stack_size_offset` bytes into the function, plus `stack_adjust`
+    FramelessIndirect {
+        stack_size_offset: u32,
+        stack_adjust: u32,
+        registers: Vec<&'static str>,
+    },
+    /// arm64: a frame record at FP, with these register pairs saved below it
+    Frame { register_pairs: Vec<&'static str> },
+    /// arm64: a leaf function that moved the stack pointer by `stack_size`
+    Frameless { stack_size: u32 },
+    /// The FDE at this offset in `__eh_frame` describes the function
+    Dwarf { fde_offset: u32 },
+    /// A mode that isn't defined for the architecture
+    Unknown(u32),
+}
+
+impl fmt::Display for UnwindMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let registers = match self {
+            UnwindMode::None => return write!(f, "no unwind information"),
+            UnwindMode::RbpFrame { offset, registers } => {
+                write!(f, "RBP frame, registers at rbp-{}", offset)?;
+                registers
+            }
+            UnwindMode::FramelessImmediate {
+                stack_size,
+                registers,
+            } => {
+                write!(f, "frameless, stack size {}", stack_size)?;
+                registers
+            }
+            UnwindMode::FramelessIndirect {
+                stack_size_offset,
+                stack_adjust,
+                registers,
+            } => {
+                write!(
+                    f,
+                    "frameless, stack size in the instruction at +{} plus {}",
+                    stack_size_offset, stack_adjust
+                )?;
+                registers
+            }
+            UnwindMode::Frame { register_pairs } => {
+                write!(f, "frame")?;
+                register_pairs
+            }
+            UnwindMode::Frameless { stack_size } => {
+                return write!(f, "frameless, stack size {}", stack_size)
+            }
+            UnwindMode::Dwarf { fde_offset } => return write!(f, "DWARF FDE at {:#x}", fde_offset),
+            UnwindMode::Unknown(mode) => return write!(f, "unknown mode {}", mode),
+        };
+        if !registers.is_empty() {
+            write!(f, ", saved: {}", registers.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// An entry of the first level index, which covers functions from `function_offset` up to the
+/// next entry
+#[derive(Debug)]
+pub struct IndexEntry {
+    /// Offset of the first function from the mach header
+    pub function_offset: u32,
+    pub second_level_page_offset: u32,
+    pub lsda_index_offset: u32,
+    /// The second level page, which the last entry marking the end of the functions lacks
+    pub page: Option<SecondLevelPage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageKind {
+    Regular,
+    /// Entries with a 24 bit function offset and an index into the common or page encodings
+    Compressed,
+}
+
+/// A second level page, listing the encoding of each function
+#[derive(Debug)]
+pub struct SecondLevelPage {
+    pub kind: PageKind,
+    /// The encodings local to a compressed page
+    pub encodings: Vec<Encoding>,
+    pub entries: Vec<UnwindEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnwindEntry {
+    /// Offset of the function from the mach header
+    pub function_offset: u32,
+    pub encoding: Encoding,
+}
+
+/// The language specific data area of a function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LsdaEntry {
+    pub function_offset: u32,
+    pub lsda_offset: u32,
+}
+
+/// The `__TEXT,__unwind_info` section
+#[derive(Debug)]
+pub struct UnwindInfo {
+    pub cpu_type: CpuType,
+    pub version: u32,
+    /// Encodings shared by all pages, which compressed pages refer to by index
+    pub common_encodings: Vec<Encoding>,
+    /// Offsets from the mach header of the pointers to the personality functions
+    pub personalities: Vec<u32>,
+    pub index: Vec<IndexEntry>,
+    pub lsdas: Vec<LsdaEntry>,
+}
+
+impl UnwindInfo {
+    pub(crate) fn parse(input: parse::Input, cpu_type: CpuType) -> parse::ParseResult<Self> {
+        let section = input;
+        let at = |offset: u32| &section[(offset as usize).min(section.len())..];
+        let (
+            input,
+            (
+                version,
+                common_encodings_offset,
+                common_encodings_count,
+                personalities_offset,
+                personalities_count,
+                index_offset,
+                index_count,
+            ),
+        ) = context(
+            "Parse Unwind Info Header",
+            tuple((le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32)),
+        )(input)?;
+        let (_, common_encodings) = context(
+            "Parse Common Encodings",
+            count(le_u32, common_encodings_count as usize),
+        )(at(common_encodings_offset))?;
+        let common_encodings: Vec<_> = common_encodings.into_iter().map(Encoding).collect();
+        let (_, personalities) = context(
+            "Parse Personalities",
+            count(le_u32, personalities_count as usize),
+        )(at(personalities_offset))?;
+        let (_, entries) = context(
+            "Parse Unwind Index",
+            count(tuple((le_u32, le_u32, le_u32)), index_count as usize),
+        )(at(index_offset))?;
+        let mut index = Vec::with_capacity(entries.len());
+        for (i, &(function_offset, page_offset, lsda_index_offset)) in entries.iter().enumerate() {
+            let page = if page_offset == 0 || i + 1 == entries.len() {
+                None
+            } else {
+                let page =
+                    SecondLevelPage::parse(at(page_offset), function_offset, &common_encodings)?.1;
+                Some(page)
+            };
+            index.push(IndexEntry {
+                function_offset,
+                second_level_page_offset: page_offset,
+                lsda_index_offset,
+                page,
+            });
+        }
+        // The LSDA entries of all pages are in one array, which the last index entry ends
+        let lsdas = match (entries.first(), entries.last()) {
+            (Some(first), Some(last)) if last.2 > first.2 => {
+                let number = (last.2 - first.2) / 8;
+                context(
+                    "Parse LSDA Index",
+                    count(
+                        nom::combinator::map(
+                            tuple((le_u32, le_u32)),
+                            |(function_offset, lsda_offset)| LsdaEntry {
+                                function_offset,
+                                lsda_offset,
+                            },
+                        ),
+                        number as usize,
+                    ),
+                )(at(first.2))?
+                .1
+            }
+            _ => Vec::new(),
+        };
+        Ok((
+            input,
+            Self {
+                cpu_type,
+                version,
+                common_encodings,
+                personalities,
+                index,
+                lsdas,
+            },
+        ))
+    }
+
+    /// The entry of the function containing an offset from the mach header
+    pub fn lookup(&self, offset: u32) -> Option<UnwindEntry> {
+        let position = self
+            .index
+            .partition_point(|entry| entry.function_offset <= offset);
+        // Offsets past the last entry, which marks the end of the functions, aren't covered
+        if position == self.index.len() {
+            return None;
+        }
+        let page = self.index.get(position.checked_sub(1)?)?.page.as_ref()?;
+        let position = page
+            .entries
+            .partition_point(|entry| entry.function_offset <= offset);
+        page.entries.get(position.checked_sub(1)?).copied()
+    }
+
+    /// The offset of the language specific data area of the function starting at an offset
+    pub fn lsda(&self, function_offset: u32) -> Option<u32> {
+        self.lsdas
+            .iter()
+            .find(|entry| entry.function_offset == function_offset)
+            .map(|entry| entry.lsda_offset)
+    }
+
+    /// The offset of the pointer to the personality function an encoding uses
+    pub fn personality(&self, encoding: Encoding) -> Option<u32> {
+        let index = encoding.personality_index().checked_sub(1)?;
+        self.personalities.get(index as usize).copied()
+    }
+}
+
+impl SecondLevelPage {
+    fn parse<'a>(
+        input: parse::Input<'a>,
+        function_base: u32,
+        common_encodings: &[Encoding],
+    ) -> parse::ParseResult<'a, Self> {
+        let page = input;
+        let at = |offset: u16| &page[(offset as usize).min(page.len())..];
+        let (input, kind) = context("Parse Second Level Page Kind", le_u32)(input)?;
+        match kind {
+            UNWIND_SECOND_LEVEL_REGULAR => {
+                let (input, (entries_offset, entries_count)) =
+                    context("Parse Regular Page Header", tuple((le_u16, le_u16)))(input)?;
+                let (_, entries) = context(
+                    "Parse Regular Page Entries",
+                    count(tuple((le_u32, le_u32)), entries_count as usize),
+                )(at(entries_offset))?;
+                let entries = entries
+                    .into_iter()
+                    .map(|(function_offset, encoding)| UnwindEntry {
+                        function_offset,
+                        encoding: Encoding(encoding),
+                    })
+                    .collect();
+                Ok((
+                    input,
+                    Self {
+                        kind: PageKind::Regular,
+                        encodings: Vec::new(),
+                        entries,
+                    },
+                ))
+            }
+            UNWIND_SECOND_LEVEL_COMPRESSED => {
+                let (input, (entries_offset, entries_count, encodings_offset, encodings_count)) =
+                    context(
+                        "Parse Compressed Page Header",
+                        tuple((le_u16, le_u16, le_u16, le_u16)),
+                    )(input)?;
+                let (_, encodings) = context(
+                    "Parse Compressed Page Encodings",
+                    count(le_u32, encodings_count as usize),
+                )(at(encodings_offset))?;
+                let encodings: Vec<_> = encodings.into_iter().map(Encoding).collect();
+                let (_, entries) = context(
+                    "Parse Compressed Page Entries",
+                    count(le_u32, entries_count as usize),
+                )(at(entries_offset))?;
+                let entries = entries
+                    .into_iter()
+                    .map(|entry| {
+                        let index = (entry >> 24) as usize;
+                        let encoding = common_encodings
+                            .get(index)
+                            .or_else(|| encodings.get(index - common_encodings.len()))
+                            .copied()
+                            .unwrap_or(Encoding(0));
+                        UnwindEntry {
+                            function_offset: function_base.wrapping_add(entry & 0x00ff_ffff),
+                            encoding,
+                        }
+                    })
+                    .collect();
+                Ok((
+                    input,
+                    Self {
+                        kind: PageKind::Compressed,
+                        encodings,
+                        entries,
+                    },
+                ))
+            }
+            _ => Err(parse::failure(page, "Unknown second level page kind")),
+        }
+    }
+}
+
+impl fmt::Display for UnwindInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "Common Encodings:")?;
+        for (i, encoding) in self.common_encodings.iter().enumerate() {
+            writeln!(f, "  {}: {} {}", i, encoding, encoding.mode(self.cpu_type))?;
+        }
+        writeln!(f, "Personalities:")?;
+        for (i, personality) in self.personalities.iter().enumerate() {
+            writeln!(f, "  {}: {:#x}", i + 1, personality)?;
+        }
+        writeln!(f, "Index:")?;
+        for entry in &self.index {
+            writeln!(
+                f,
+                "  Function {:#x} Page {:#x} LSDA Index {:#x}",
+                entry.function_offset, entry.second_level_page_offset, entry.lsda_index_offset
+            )?;
+            let Some(page) = &entry.page else {
+                continue;
+            };
+            writeln!(f, "  {:?} Page:", page.kind)?;
+            for entry in &page.entries {
+                writeln!(
+                    f,
+                    "    {:#x}: {} {}",
+                    entry.function_offset,
+                    entry.encoding,
+                    entry.encoding.mode(self.cpu_type)
+                )?;
+            }
+        }
+        if !self.lsdas.is_empty() {
+            writeln!(f, "LSDAs:")?;
+            for entry in &self.lsdas {
+                writeln!(
+                    f,
+                    "  {:#x}: {:#x}",
+                    entry.function_offset, entry.lsda_offset
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    /// A section with a regular page for 0x1000 and a compressed page for 0x2000
+    fn unwind_info() -> Vec<u8> {
+        // Header, one common encoding and one personality
+        let mut data = words(&[1, 0x1c, 1, 0x20, 1, 0x24, 3, 0x0102_0011, 0x2000]);
+        // Index, with the last entry ending the functions and the LSDA index
+        data.extend(words(&[
+            0x1000, 0x50, 0x48, 0x2000, 0x68, 0x50, 0x3000, 0, 0x50,
+        ]));
+        data.extend(words(&[0x1080, 0x5000]));
+        // Regular page: entries at +8
+        data.extend(words(&[2, 8 | 2 << 16]));
+        data.extend(words(&[0x1000, 0x0204_0400, 0x1080, 0x4102_0011]));
+        // Compressed page: entries at +12, page encodings at +20
+        data.extend(words(&[3, 12 | 2 << 16, 20 | 1 << 16]));
+        data.extend(words(&[0, 1 << 24 | 0x40, 0x0400_0080]));
+        data
+    }
+
+    #[test]
+    fn decodes_x86_64_encodings() {
+        let mode = |encoding| Encoding(encoding).mode(CpuType::X86_64);
+        assert_eq!(
+            mode(0x0102_0011),
+            UnwindMode::RbpFrame {
+                offset: 16,
+                registers: vec!["rbx", "r12"],
+            }
+        );
+        assert_eq!(
+            mode(0x0204_0807).to_string(),
+            "frameless, stack size 32, saved: r12, r14"
+        );
+        assert_eq!(
+            mode(0x0310_2400),
+            UnwindMode::FramelessIndirect {
+                stack_size_offset: 0x10,
+                stack_adjust: 8,
+                registers: vec!["rbx"],
+            }
+        );
+        assert_eq!(mode(0x0400_0123), UnwindMode::Dwarf { fde_offset: 0x123 });
+        assert_eq!(mode(0), UnwindMode::None);
+        assert_eq!(mode(0x0700_0000), UnwindMode::Unknown(7));
+    }
+
+    #[test]
+    fn decodes_arm64_encodings() {
+        let mode = |encoding| Encoding(encoding).mode(CpuType::Arm64);
+        assert_eq!(
+            mode(0x0400_0101).to_string(),
+            "frame, saved: x19/x20, d8/d9"
+        );
+        assert_eq!(mode(0x0200_2000), UnwindMode::Frameless { stack_size: 32 });
+        assert_eq!(mode(0x0300_0040), UnwindMode::Dwarf { fde_offset: 0x40 });
+    }
+
+    #[test]
+    fn decodes_encoding_flags() {
+        let encoding = Encoding(0xd400_0000);
+        assert!(!encoding.is_function_start());
+        assert!(encoding.has_lsda());
+        assert_eq!(encoding.personality_index(), 1);
+        assert_eq!(
+            encoding.to_string(),
+            "0xd4000000 not function start LSDA personality 1"
+        );
+    }
+
+    #[test]
+    fn parses_regular_and_compressed_pages() {
+        let data = unwind_info();
+        let info = UnwindInfo::parse(&data, CpuType::X86_64).unwrap().1;
+        assert_eq!(info.common_encodings, [Encoding(0x0102_0011)]);
+        assert_eq!(info.index.len(), 3);
+        assert!(info.index[2].page.is_none());
+
+        let regular = info.index[0].page.as_ref().unwrap();
+        assert_eq!(regular.kind, PageKind::Regular);
+        assert_eq!(regular.entries.len(), 2);
+        let compressed = info.index[1].page.as_ref().unwrap();
+        assert_eq!(compressed.kind, PageKind::Compressed);
+        assert_eq!(compressed.encodings, [Encoding(0x0400_0080)]);
+        assert_eq!(
+            compressed.entries,
+            [
+                UnwindEntry {
+                    function_offset: 0x2000,
+                    encoding: Encoding(0x0102_0011),
+                },
+                UnwindEntry {
+                    function_offset: 0x2040,
+                    encoding: Encoding(0x0400_0080),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn looks_up_functions_lsdas_and_personalities() {
+        let data = unwind_info();
+        let info = UnwindInfo::parse(&data, CpuType::X86_64).unwrap().1;
+        let lookup = |offset| info.lookup(offset).map(|entry| entry.function_offset);
+        assert_eq!(lookup(0xfff), None);
+        assert_eq!(lookup(0x107f), Some(0x1000));
+        assert_eq!(lookup(0x10ff), Some(0x1080));
+        assert_eq!(lookup(0x2050), Some(0x2040));
+        assert_eq!(lookup(0x3000), None);
+
+        let encoding = info.lookup(0x1080).unwrap().encoding;
+        assert!(encoding.has_lsda());
+        assert_eq!(info.lsda(0x1080), Some(0x5000));
+        assert_eq!(info.lsda(0x1000), None);
+        assert_eq!(info.personality(Encoding(0x1000_0000)), Some(0x2000));
+        assert_eq!(info.personality(Encoding(0x2000_0000)), None);
+    }
+
+    #[test]
+    fn rejects_unknown_page_kind() {
+        let mut data = unwind_info();
+        data[0x50] = 9;
+        assert!(UnwindInfo::parse(&data, CpuType::X86_64).is_err());
+    }
+}
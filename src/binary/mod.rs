@@ -1,46 +1,82 @@
 use std::{fmt, path::Path};
 
+pub mod archive;
+pub mod coff;
+pub mod dwarf;
 pub mod elf;
 pub mod mach;
 pub mod pe;
+pub mod symbolication;
 
+use archive::Archive;
+use coff::import::ImportObject;
+use coff::Coff;
 use elf::Elf;
+use mach::dyld_cache::{self, DyldCache};
 use mach::Mach;
 use pe::Pe;
 
-use crate::error::BinDumpResult;
+use crate::error::{BinDumpError, BinDumpResult};
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum Object {
     Pe(Pe),
     Elf(Elf),
     Mach(Mach),
+    Archive(Archive),
+    DyldCache(DyldCache),
+    Coff(Coff),
+    CoffImport(ImportObject),
 }
 
 impl Object {
     pub fn load<P: AsRef<Path>>(path: P) -> BinDumpResult<Self> {
+        let path = path.as_ref();
         let data = std::fs::read(path)?;
+        // The sub-caches of a shared cache are separate files next to the main one
+        if data.starts_with(dyld_cache::MAGIC) {
+            return Ok(Self::DyldCache(DyldCache::load(path, data)?));
+        }
+        Self::parse(data)
+    }
+
+    /// Parses an object from its contents, detecting the format from the magic bytes
+    pub fn parse(data: Vec<u8>) -> BinDumpResult<Self> {
+        if data.starts_with(archive::MAGIC) {
+            return Ok(Self::Archive(Archive::parse(&data)?));
+        }
+        if data.starts_with(dyld_cache::MAGIC) {
+            return Ok(Self::DyldCache(DyldCache::parse(data)?));
+        }
+        if data.len() < 4 {
+            return Err(BinDumpError::UnknownMagic { magic: data });
+        }
         // Check magic bytes
         // TODO find a better way to match the magic bytes?
         match data[0..4] {
             // PE
-            [0x4d, 0x5a, _, _] => Ok(Self::Pe(Pe::load(data))),
+            [0x4d, 0x5a, _, _] => Ok(Self::Pe(Pe::load(data)?)),
             // ELF
-            [0x7f, 0x45, 0x4c, 0x46] => Ok(Self::Elf(Elf::load(data))),
+            [0x7f, 0x45, 0x4c, 0x46] => Ok(Self::Elf(Elf::load(data)?)),
             // Mach has multiple possible magic byte sequences
             [0xca, 0xfe, 0xba, 0xbe]
             | [0xfe, 0xed, 0xfa, 0xce]
             | [0xfe, 0xed, 0xfa, 0xcf]
             | [0xcf, 0xfa, 0xed, 0xfe]
-            | [0xce, 0xfa, 0xed, 0xfe] => match Mach::parse(&data) {
-                Ok((_input, mach)) => Ok(Self::Mach(mach)),
-                Err(err) => match err.to_owned() {
-                    nom::Err::Incomplete(_x) => todo!(),
-                    nom::Err::Error(e) => Err(e.into()),
-                    nom::Err::Failure(e) => Err(e.into()),
-                },
-            },
-            _ => todo!(),
+            | [0xce, 0xfa, 0xed, 0xfe] => {
+                let (_input, mach) = Mach::parse(&data)?;
+                Ok(Self::Mach(mach))
+            }
+            // Short import objects and `/bigobj` objects start with an anonymous object header
+            [0x00, 0x00, 0xff, 0xff] => coff::parse_anonymous(data),
+            // Other object files have no magic, so go by the machine they start with
+            [low, high, _, _] if coff::is_object_machine(u16::from_le_bytes([low, high])) => {
+                Ok(Self::Coff(Coff::parse(data)?))
+            }
+            _ => Err(BinDumpError::UnknownMagic {
+                magic: data[0..4].to_vec(),
+            }),
         }
     }
 }
@@ -51,6 +87,10 @@ impl fmt::Display for Object {
             Object::Pe(pe) => write!(f, "{}", pe),
             Object::Elf(elf) => write!(f, "{}", elf),
             Object::Mach(macho) => write!(f, "{}", macho),
+            Object::Archive(archive) => write!(f, "{}", archive),
+            Object::DyldCache(cache) => write!(f, "{}", cache),
+            Object::Coff(coff) => write!(f, "{}", coff),
+            Object::CoffImport(import) => write!(f, "{}", import),
         }
     }
 }
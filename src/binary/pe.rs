@@ -1,16 +1,472 @@
+pub mod certificates;
+pub mod clr;
+pub mod debug;
+pub mod exceptions;
+pub mod exports;
+pub mod header;
+pub mod imports;
+pub mod load_config;
+pub mod relocations;
+pub mod resources;
+pub mod rich;
+pub mod sections;
+pub mod tls;
+pub mod version_info;
+
 use std::fmt;
 
+use certificates::WinCertificate;
+use clr::Clr;
+use debug::DebugEntry;
+use exceptions::RuntimeFunction;
+use exports::ExportDirectory;
+use header::{CoffHeader, DataDirectory, DirectoryEntry, DosHeader, OptionalHeader};
+use imports::{BoundImport, DelayImportedLibrary, ImportedLibrary};
+use load_config::LoadConfig;
+use nom::bytes::complete::tag;
+use nom::error::context;
+use nom::multi::count;
+use relocations::BaseRelocationBlock;
+use resources::Resources;
+use rich::RichHeader;
+use sections::SectionHeader;
+use tls::TlsDirectory;
+
+use crate::error::BinDumpResult;
+use crate::parse::{hex, string_at, truncated};
+
+/// The offset of `CheckSum` in the optional header
+const CHECKSUM_OFFSET: usize = 64;
+
 #[derive(Debug)]
-pub struct Pe {}
+pub struct Pe {
+    dos_header: DosHeader,
+    rich_header: Option<BinDumpResult<RichHeader>>,
+    coff_header: CoffHeader,
+    optional_header: OptionalHeader,
+    sections: Vec<SectionHeader>,
+    imports: Vec<ImportedLibrary>,
+    delay_imports: Vec<DelayImportedLibrary>,
+    bound_imports: Vec<BoundImport>,
+    exports: Option<BinDumpResult<ExportDirectory>>,
+    resources: Option<BinDumpResult<Resources>>,
+    base_relocations: Option<BinDumpResult<Vec<BaseRelocationBlock>>>,
+    tls: Option<BinDumpResult<TlsDirectory>>,
+    load_config: Option<BinDumpResult<LoadConfig>>,
+    exceptions: Option<BinDumpResult<Vec<RuntimeFunction>>>,
+    debug: Option<BinDumpResult<Vec<DebugEntry>>>,
+    certificates: Option<BinDumpResult<Vec<WinCertificate>>>,
+    clr: Option<BinDumpResult<Clr>>,
+    data: Vec<u8>,
+}
 
 impl Pe {
-    pub(crate) fn load(data: Vec<u8>) -> Self {
-        todo!("Loading of PE files is not yet implemented!")
+    pub(crate) fn load(data: Vec<u8>) -> BinDumpResult<Self> {
+        let (_, dos_header) = DosHeader::parse(&data)?;
+        let input = data
+            .get(dos_header.pe_header_offset as usize..)
+            .ok_or_else(|| truncated("PE headers"))?;
+        let (input, _) = context("Parse PE Signature", tag(b"PE\0\0"))(input)?;
+        let (input, coff_header) = CoffHeader::parse(input)?;
+        let size = coff_header.size_of_optional_header as usize;
+        let optional = input
+            .get(..size)
+            .ok_or_else(|| truncated("optional header"))?;
+        let (_, optional_header) = OptionalHeader::parse(optional)?;
+        let (_, sections) = context(
+            "Parse Section Headers",
+            count(
+                SectionHeader::parse,
+                coff_header.number_of_sections as usize,
+            ),
+        )(&input[size..])?;
+
+        let mut pe = Self {
+            dos_header,
+            rich_header: None,
+            coff_header,
+            optional_header,
+            sections,
+            imports: Vec::new(),
+            delay_imports: Vec::new(),
+            bound_imports: Vec::new(),
+            exports: None,
+            resources: None,
+            base_relocations: None,
+            tls: None,
+            load_config: None,
+            exceptions: None,
+            debug: None,
+            certificates: None,
+            clr: None,
+            data,
+        };
+        pe.rich_header = pe.parse_rich_header();
+        pe.imports = pe.parse_imports()?;
+        pe.delay_imports = pe.parse_delay_imports()?;
+        pe.bound_imports = pe.parse_bound_imports()?;
+        pe.exports = pe.parse_exports();
+        pe.resources = pe.parse_resources();
+        pe.base_relocations = pe.parse_base_relocations();
+        pe.tls = pe.parse_tls();
+        pe.load_config = pe.parse_load_config();
+        pe.exceptions = pe.parse_exceptions();
+        pe.debug = pe.parse_debug_directory();
+        pe.certificates = pe.parse_certificates();
+        pe.clr = pe.parse_clr();
+        Ok(pe)
+    }
+
+    pub fn dos_header(&self) -> &DosHeader {
+        &self.dos_header
+    }
+
+    pub fn coff_header(&self) -> &CoffHeader {
+        &self.coff_header
+    }
+
+    pub fn optional_header(&self) -> &OptionalHeader {
+        &self.optional_header
+    }
+
+    pub fn sections(&self) -> &[SectionHeader] {
+        &self.sections
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<&SectionHeader> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// The section an RVA falls in
+    pub fn section_for_rva(&self, rva: u32) -> Option<&SectionHeader> {
+        self.sections
+            .iter()
+            .find(|section| section.contains_rva(rva))
+    }
+
+    /// The contents of a section as stored in the file
+    pub fn section_data(&self, section: &SectionHeader) -> &[u8] {
+        let start = (section.pointer_to_raw_data as usize).min(self.data.len());
+        let end = start
+            .saturating_add(section.size_of_raw_data as usize)
+            .min(self.data.len());
+        &self.data[start..end]
+    }
+
+    /// Converts an RVA to a file offset through the section table. The headers are mapped
+    /// at the start of the image, so RVAs below the end of the headers are offsets already
+    pub fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        if rva < self.optional_header.size_of_headers {
+            return Some(rva as usize);
+        }
+        let section = self.section_for_rva(rva)?;
+        let delta = rva - section.virtual_address;
+        if delta >= section.size_of_raw_data {
+            return None;
+        }
+        Some(section.pointer_to_raw_data as usize + delta as usize)
+    }
+
+    /// The file contents from an RVA to the end of the headers or section it's in
+    pub fn data_at_rva(&self, rva: u32) -> Option<&[u8]> {
+        let offset = self.rva_to_offset(rva)?;
+        let end = match self.section_for_rva(rva) {
+            Some(section) if rva >= self.optional_header.size_of_headers => {
+                section.pointer_to_raw_data as usize + section.size_of_raw_data as usize
+            }
+            _ => self.optional_header.size_of_headers as usize,
+        };
+        self.data.get(offset..end.min(self.data.len()))
+    }
+
+    /// Converts a virtual address at the preferred image base to an RVA
+    pub fn va_to_rva(&self, address: u64) -> Option<u32> {
+        let rva = address.checked_sub(self.optional_header.image_base)?;
+        u32::try_from(rva).ok()
+    }
+
+    /// The file contents from a virtual address to the end of the headers or section it's in
+    pub fn data_at_va(&self, address: u64) -> Option<&[u8]> {
+        self.data_at_rva(self.va_to_rva(address)?)
+    }
+
+    /// Reads the null terminated string at an RVA
+    pub fn string_at_rva(&self, rva: u32) -> Option<String> {
+        Some(string_at(self.data_at_rva(rva)?, 0))
+    }
+
+    /// The contents of a data directory, unless the image doesn't have it
+    pub fn directory_data(&self, entry: DirectoryEntry) -> Option<&[u8]> {
+        let DataDirectory {
+            virtual_address,
+            size,
+        } = self.optional_header.data_directory(entry)?;
+        let data = self.data_at_rva(virtual_address)?;
+        Some(&data[..(size as usize).min(data.len())])
+    }
+
+    /// The file contents from the start of a data directory, for tables that end with a null
+    /// entry or carry their own size, whatever the directory size says
+    fn directory_start(&self, entry: DirectoryEntry) -> Option<&[u8]> {
+        self.data_at_rva(self.optional_header.data_directory(entry)?.virtual_address)
+    }
+
+    /// The file offset of the `CheckSum` field
+    fn checksum_offset(&self) -> usize {
+        self.dos_header.pe_header_offset as usize + 24 + CHECKSUM_OFFSET
+    }
+
+    /// The checksum of the image the way `CheckSumMappedFile` computes it, which is the one's
+    /// complement sum of its 16 bit words without the `CheckSum` field, plus the file size
+    pub fn computed_checksum(&self) -> u32 {
+        let checksum = self.checksum_offset();
+        let mut sum = 0u32;
+        for (i, word) in self.data.chunks(2).enumerate() {
+            if (checksum..checksum + 4).contains(&(i * 2)) {
+                continue;
+            }
+            sum += u16::from_le_bytes([word[0], word.get(1).copied().unwrap_or(0)]) as u32;
+            sum = (sum & 0xffff) + (sum >> 16);
+        }
+        sum.wrapping_add(self.data.len() as u32)
+    }
+
+    pub fn imports(&self) -> &[ImportedLibrary] {
+        &self.imports
+    }
+
+    pub fn delay_imports(&self) -> &[DelayImportedLibrary] {
+        &self.delay_imports
+    }
+
+    pub fn bound_imports(&self) -> &[BoundImport] {
+        &self.bound_imports
     }
 }
 
 impl fmt::Display for Pe {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        unimplemented!()
+        let header = &self.coff_header;
+        writeln!(
+            f,
+            "PE: {} Architecture, {}",
+            header.machine,
+            if self.optional_header.is_64_bit() {
+                "64 bit"
+            } else {
+                "32 bit"
+            }
+        )?;
+        writeln!(
+            f,
+            "PE Header Offset: {:#x}",
+            self.dos_header.pe_header_offset
+        )?;
+        writeln!(f, "Time Date Stamp: {:#x}", header.time_date_stamp)?;
+        writeln!(
+            f,
+            "Pointer To Symbol Table: {:#x}",
+            header.pointer_to_symbol_table
+        )?;
+        writeln!(f, "Number Of Symbols: {}", header.number_of_symbols)?;
+        writeln!(
+            f,
+            "Characteristics: {:#x} ({})",
+            header.characteristics,
+            header.characteristic_names().join(", ")
+        )?;
+        match &self.rich_header {
+            Some(Ok(rich_header)) => {
+                writeln!(f, "Rich Header:")?;
+                write!(f, "{}", rich_header)?;
+            }
+            Some(Err(e)) => writeln!(f, "Rich Header: Error {}", e)?,
+            None => {}
+        }
+        writeln!(f, "Optional Header:")?;
+        write!(f, "{}", self.optional_header)?;
+        let checksum = self.computed_checksum();
+        writeln!(
+            f,
+            "Computed Checksum: {:#x} ({})",
+            checksum,
+            match self.optional_header.checksum {
+                // Only drivers and some system DLLs need a checksum, so most images leave it out
+                0 => "Not Set",
+                stored if stored == checksum => "Matches",
+                _ => "Mismatch",
+            }
+        )?;
+        writeln!(f, "Sections:")?;
+        for (i, section) in self.sections.iter().enumerate() {
+            writeln!(f, "Section {}", i)?;
+            writeln!(f, "{}", section)?;
+        }
+        match &self.exports {
+            Some(Ok(exports)) => {
+                writeln!(f, "Exports:")?;
+                write!(f, "{}", exports)?;
+            }
+            Some(Err(e)) => writeln!(f, "Exports: Error {}", e)?,
+            None => {}
+        }
+        if !self.imports.is_empty() {
+            writeln!(f, "Imports:")?;
+            for library in &self.imports {
+                write!(f, "{}", library)?;
+            }
+        }
+        if !self.delay_imports.is_empty() {
+            writeln!(f, "Delay Imports:")?;
+            for library in &self.delay_imports {
+                write!(f, "{}", library)?;
+            }
+        }
+        if !self.bound_imports.is_empty() {
+            writeln!(f, "Bound Imports:")?;
+            for import in &self.bound_imports {
+                write!(f, "{}", import)?;
+            }
+        }
+        match &self.base_relocations {
+            Some(Ok(blocks)) => {
+                writeln!(f, "Base Relocations:")?;
+                for block in blocks {
+                    write!(f, "{}", block)?;
+                }
+            }
+            Some(Err(e)) => writeln!(f, "Base Relocations: Error {}", e)?,
+            None => {}
+        }
+        match &self.tls {
+            Some(Ok(tls)) => {
+                writeln!(f, "TLS Directory:")?;
+                write!(f, "{}", tls)?;
+            }
+            Some(Err(e)) => writeln!(f, "TLS Directory: Error {}", e)?,
+            None => {}
+        }
+        match &self.load_config {
+            Some(Ok(load_config)) => {
+                writeln!(f, "Load Config:")?;
+                write!(f, "{}", load_config)?;
+            }
+            Some(Err(e)) => writeln!(f, "Load Config: Error {}", e)?,
+            None => {}
+        }
+        match &self.debug {
+            Some(Ok(entries)) => {
+                writeln!(f, "Debug Directory:")?;
+                for (i, entry) in entries.iter().enumerate() {
+                    writeln!(f, "Entry {}", i)?;
+                    write!(f, "{}", entry)?;
+                }
+                if let Some(identifier) = self.pdb_identifier() {
+                    writeln!(f, "PDB Identifier: {}", identifier)?;
+                }
+            }
+            Some(Err(e)) => writeln!(f, "Debug Directory: Error {}", e)?,
+            None => {}
+        }
+        match &self.exceptions {
+            Some(Ok(functions)) => {
+                writeln!(f, "Runtime Functions:")?;
+                for function in functions {
+                    write!(f, "{}", function)?;
+                }
+            }
+            Some(Err(e)) => writeln!(f, "Runtime Functions: Error {}", e)?,
+            None => {}
+        }
+        match &self.clr {
+            Some(Ok(clr)) => {
+                writeln!(f, "CLR Header:")?;
+                write!(f, "{}", clr)?;
+            }
+            Some(Err(e)) => writeln!(f, "CLR Header: Error {}", e)?,
+            None => {}
+        }
+        match &self.resources {
+            Some(Ok(resources)) => {
+                writeln!(f, "Resources:")?;
+                write!(f, "{}", resources)?;
+            }
+            Some(Err(e)) => writeln!(f, "Resources: Error {}", e)?,
+            None => {}
+        }
+        match &self.certificates {
+            Some(Ok(certificates)) => {
+                writeln!(f, "Certificates:")?;
+                for (i, certificate) in certificates.iter().enumerate() {
+                    writeln!(f, "Certificate {}", i)?;
+                    write!(f, "{}", certificate)?;
+                }
+                for (algorithm, digest) in self.image_digests() {
+                    writeln!(f, "Image Digest ({}): {}", algorithm, hex(digest))?;
+                    match self.authenticode_hash(algorithm) {
+                        Some(hash) => writeln!(
+                            f,
+                            "Authenticode Hash ({}): {} ({})",
+                            algorithm,
+                            hex(&hash),
+                            if hash == digest {
+                                "Matches"
+                            } else {
+                                "Mismatch"
+                            }
+                        )?,
+                        None => writeln!(f, "Authenticode Hash ({}): Unsupported", algorithm)?,
+                    }
+                }
+            }
+            Some(Err(e)) => writeln!(f, "Certificates: Error {}", e)?,
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A PE32+ image without sections, which ends with a 16 byte certificate table
+    pub(super) fn image() -> Vec<u8> {
+        let mut data = vec![0; 0x40];
+        data[..2].copy_from_slice(b"MZ");
+        data[0x3c] = 0x40;
+        data.extend_from_slice(b"PE\0\0");
+        for field in [0x8664u16, 0, 0, 0, 0, 0, 0, 0, 240, 0x22] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        let mut optional = vec![0; 240];
+        let mut set = |offset: usize, bytes: &[u8]| {
+            optional[offset..offset + bytes.len()].copy_from_slice(bytes)
+        };
+        set(0, &[0x0b, 0x02, 14]);
+        set(24, &0x1_4000_0000u64.to_le_bytes());
+        for (offset, field) in [(32, 0x1000u32), (36, 0x200), (56, 0x1000), (60, 0x200)] {
+            set(offset, &field.to_le_bytes());
+        }
+        set(CHECKSUM_OFFSET, &u32::MAX.to_le_bytes());
+        set(68, &[3, 0, 0x60, 0x81]);
+        set(108, &16u32.to_le_bytes());
+        // The certificate table, whose address is a file offset
+        set(144, &[0x48, 0x01, 0, 0, 0x10, 0, 0, 0]);
+        data.extend_from_slice(&optional);
+        data.extend_from_slice(&[0x10, 0, 0, 0, 0, 0x02, 0x02, 0]);
+        data.extend_from_slice(&[0xaa; 8]);
+        data
+    }
+
+    #[test]
+    fn computes_the_checksum() {
+        let pe = Pe::load(image()).unwrap();
+        assert_eq!(pe.computed_checksum(), 0xbe4e);
+
+        // An odd byte at the end counts as a word of its own
+        let mut data = image();
+        data.push(1);
+        assert_eq!(Pe::load(data).unwrap().computed_checksum(), 0xbe50);
     }
 }
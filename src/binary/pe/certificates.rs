@@ -0,0 +1,266 @@
+pub mod der;
+pub mod pkcs7;
+
+use std::fmt;
+
+use md5::Md5;
+use nom::bytes::complete::take;
+use nom::error::context;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use super::header::DirectoryEntry;
+use super::{Pe, CHECKSUM_OFFSET};
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse::{self, failure, truncated};
+use pkcs7::{Certificate, DigestAlgorithm, SignedData};
+
+// From wintrust.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateType {
+    X509,
+    PkcsSignedData,
+    Reserved1,
+    TsStackSigned,
+    Other(u16),
+}
+
+impl From<u16> for CertificateType {
+    fn from(typ: u16) -> Self {
+        match typ {
+            1 => CertificateType::X509,
+            2 => CertificateType::PkcsSignedData,
+            3 => CertificateType::Reserved1,
+            4 => CertificateType::TsStackSigned,
+            typ => CertificateType::Other(typ),
+        }
+    }
+}
+
+impl fmt::Display for CertificateType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CertificateType::Other(typ) => write!(f, "Unknown ({:#x})", typ),
+            typ => write!(f, "{:?}", typ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CertificateContents {
+    X509(Certificate),
+    /// An Authenticode signature
+    SignedData(SignedData),
+}
+
+impl fmt::Display for CertificateContents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CertificateContents::X509(certificate) => write!(f, "{}", certificate),
+            CertificateContents::SignedData(signed_data) => write!(f, "{}", signed_data),
+        }
+    }
+}
+
+/// A `WIN_CERTIFICATE` entry of the certificate table
+#[derive(Debug)]
+pub struct WinCertificate {
+    /// The file offset of the entry
+    pub offset: usize,
+    pub length: u32,
+    pub revision: u16,
+    pub certificate_type: CertificateType,
+    pub contents: BinDumpResult<CertificateContents>,
+}
+
+impl WinCertificate {
+    fn parse(input: parse::Input, offset: usize) -> parse::ParseResult<Self> {
+        let (rest, (length, revision, typ)) =
+            context("Parse Certificate", tuple((le_u32, le_u16, le_u16)))(input)?;
+        let size = (length as usize)
+            .checked_sub(8)
+            .ok_or_else(|| failure(input, "Invalid Certificate Length"))?;
+        let (_, data) = context("Parse Certificate Data", take(size))(rest)?;
+        let certificate_type = CertificateType::from(typ);
+        let contents = match certificate_type {
+            CertificateType::X509 => Certificate::parse(data)
+                .map(|(_, certificate)| CertificateContents::X509(certificate))
+                .map_err(Into::into),
+            CertificateType::PkcsSignedData => SignedData::parse(data)
+                .map(|(_, signed_data)| CertificateContents::SignedData(signed_data))
+                .map_err(Into::into),
+            typ => Err(BinDumpError::Unsupported {
+                format: format!("certificates of type {}", typ),
+            }),
+        };
+        // Entries start on 8 byte boundaries
+        let next = (length as usize + 7) & !7;
+        Ok((
+            input.get(next..).unwrap_or_default(),
+            Self {
+                offset,
+                length,
+                revision,
+                certificate_type,
+                contents,
+            },
+        ))
+    }
+
+    /// The image digests the entry signs
+    pub fn image_digests(&self) -> Vec<(&DigestAlgorithm, &[u8])> {
+        match &self.contents {
+            Ok(CertificateContents::SignedData(signed_data)) => signed_data.image_digests(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for WinCertificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Offset: {:#x}", self.offset)?;
+        writeln!(f, "Length: {:#x}", self.length)?;
+        writeln!(f, "Revision: {:#x}", self.revision)?;
+        writeln!(f, "Type: {}", self.certificate_type)?;
+        match &self.contents {
+            Ok(contents) => write!(f, "{}", contents),
+            Err(e) => writeln!(f, "Error {}", e),
+        }
+    }
+}
+
+impl Pe {
+    pub(super) fn parse_certificates(&self) -> Option<BinDumpResult<Vec<WinCertificate>>> {
+        let directory = self
+            .optional_header
+            .data_directory(DirectoryEntry::Security)?;
+        // Certificates aren't loaded, so the directory has their file offset instead of an RVA
+        let start = directory.virtual_address as usize;
+        let Some(mut input) = self.data.get(start..start + directory.size as usize) else {
+            return Some(Err(truncated("certificates")));
+        };
+        let mut certificates = Vec::new();
+        while input.len() >= 8 {
+            let offset = start + (directory.size as usize - input.len());
+            match WinCertificate::parse(input, offset) {
+                Ok((rest, certificate)) => {
+                    certificates.push(certificate);
+                    input = rest;
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+        Some(Ok(certificates))
+    }
+
+    pub fn certificates(&self) -> &[WinCertificate] {
+        match &self.certificates {
+            Some(Ok(certificates)) => certificates,
+            _ => &[],
+        }
+    }
+
+    /// The image digests the signatures sign, to compare with
+    /// [`authenticode_hash`](Self::authenticode_hash)
+    pub fn image_digests(&self) -> Vec<(&DigestAlgorithm, &[u8])> {
+        self.certificates()
+            .iter()
+            .flat_map(WinCertificate::image_digests)
+            .collect()
+    }
+
+    /// The Authenticode hash of the image, unless the algorithm isn't supported or the headers
+    /// are cut short
+    ///
+    /// That's the hash of the whole file except for the checksum, the certificate table entry
+    /// of the data directory, and the certificate table itself.
+    pub fn authenticode_hash(&self, algorithm: &DigestAlgorithm) -> Option<Vec<u8>> {
+        let ranges = self.authenticode_ranges()?;
+        Some(match algorithm {
+            DigestAlgorithm::Md5 => hash::<Md5>(&ranges),
+            DigestAlgorithm::Sha1 => hash::<Sha1>(&ranges),
+            DigestAlgorithm::Sha256 => hash::<Sha256>(&ranges),
+            DigestAlgorithm::Sha384 => hash::<Sha384>(&ranges),
+            DigestAlgorithm::Sha512 => hash::<Sha512>(&ranges),
+            DigestAlgorithm::Other(_) => return None,
+        })
+    }
+
+    fn authenticode_ranges(&self) -> Option<Vec<&[u8]>> {
+        let checksum = self.checksum_offset();
+        let optional_header = checksum - CHECKSUM_OFFSET;
+        let end = match self
+            .optional_header
+            .data_directory(DirectoryEntry::Security)
+        {
+            Some(directory) => (directory.virtual_address as usize).min(self.data.len()),
+            None => self.data.len(),
+        };
+        let mut ranges = vec![self.data.get(..checksum)?];
+        if self.optional_header.data_directories.len() > DirectoryEntry::Security as usize {
+            let directories = if self.optional_header.is_64_bit() {
+                112
+            } else {
+                96
+            };
+            let security = optional_header + directories + 8 * DirectoryEntry::Security as usize;
+            ranges.push(self.data.get(checksum + 4..security)?);
+            ranges.push(self.data.get(security + 8..end)?);
+        } else {
+            ranges.push(self.data.get(checksum + 4..end)?);
+        }
+        Some(ranges)
+    }
+}
+
+fn hash<D: Digest>(ranges: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = D::new();
+    for range in ranges {
+        hasher.update(range);
+    }
+    hasher.finalize().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::image;
+    use super::*;
+
+    #[test]
+    fn leaves_the_checksum_and_certificates_out_of_the_hash() {
+        let data = image();
+        let pe = Pe::load(data.clone()).unwrap();
+        let ranges = pe.authenticode_ranges().unwrap();
+        assert_eq!(
+            ranges,
+            [&data[..0x98], &data[0x9c..0xe8], &data[0xf0..0x148]]
+        );
+
+        let hash = pe.authenticode_hash(&DigestAlgorithm::Sha256).unwrap();
+        let changed_hash = |offset: usize| {
+            let mut data = data.clone();
+            data[offset] ^= 0xff;
+            Pe::load(data)
+                .unwrap()
+                .authenticode_hash(&DigestAlgorithm::Sha256)
+                .unwrap()
+        };
+        // The checksum, the certificate table entry and the certificates themselves
+        for offset in [0x98, 0xec, 0x150] {
+            assert_eq!(changed_hash(offset), hash);
+        }
+        assert_ne!(changed_hash(0xa0), hash);
+    }
+
+    #[test]
+    fn hashes_images_without_a_certificate_table_entry() {
+        // Only four data directories, which stop short of the certificate table
+        let mut data = image();
+        data[0x58 + 108] = 4;
+        let pe = Pe::load(data.clone()).unwrap();
+        let ranges = pe.authenticode_ranges().unwrap();
+        assert_eq!(ranges, [&data[..0x98], &data[0x9c..]]);
+    }
+}
@@ -0,0 +1,283 @@
+//! Just enough of DER to walk the structures of a signature
+
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::combinator::{opt, peek};
+use nom::error::context;
+use nom::number::complete::u8 as byte;
+
+use crate::parse::{self, failure};
+
+pub const INTEGER: u8 = 0x02;
+pub const OCTET_STRING: u8 = 0x04;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const T61_STRING: u8 = 0x14;
+pub const UTC_TIME: u8 = 0x17;
+pub const GENERALIZED_TIME: u8 = 0x18;
+pub const UNIVERSAL_STRING: u8 = 0x1c;
+pub const BMP_STRING: u8 = 0x1e;
+pub const SEQUENCE: u8 = 0x30;
+pub const SET: u8 = 0x31;
+
+/// The tag of a constructed context specific field `[n]`
+pub const fn context_tag(n: u8) -> u8 {
+    0xa0 | n
+}
+
+/// The tag of a primitive context specific field `[n]`
+pub const fn context_primitive_tag(n: u8) -> u8 {
+    0x80 | n
+}
+
+/// A single encoded value
+#[derive(Debug, Clone, Copy)]
+pub struct Tlv<'a> {
+    pub tag: u8,
+    pub contents: parse::Input<'a>,
+    /// The whole value, with its tag and length
+    pub encoding: parse::Input<'a>,
+}
+
+impl<'a> Tlv<'a> {
+    pub fn parse(input: parse::Input<'a>) -> parse::ParseResult<'a, Self> {
+        let (rest, tag) = context("Parse DER Tag", byte)(input)?;
+        if tag & 0x1f == 0x1f {
+            return Err(failure(input, "Unsupported DER Tag Number"));
+        }
+        let (rest, first) = context("Parse DER Length", byte)(rest)?;
+        let (rest, length) = match first {
+            0..=0x7f => (rest, first as usize),
+            0x81..=0x84 => {
+                let (rest, bytes) = context("Parse DER Length", take(first & 0x7f))(rest)?;
+                let length = bytes
+                    .iter()
+                    .fold(0usize, |length, &b| length << 8 | b as usize);
+                (rest, length)
+            }
+            _ => return Err(failure(input, "Unsupported DER Length")),
+        };
+        let (rest, contents) = context("Parse DER Contents", take(length))(rest)?;
+        let encoding = &input[..input.len() - rest.len()];
+        Ok((
+            rest,
+            Self {
+                tag,
+                contents,
+                encoding,
+            },
+        ))
+    }
+}
+
+/// The contents of a value with the given tag
+pub fn expect<'a>(
+    tag: u8,
+    message: &'static str,
+) -> impl Fn(parse::Input<'a>) -> parse::ParseResult<'a, parse::Input<'a>> {
+    move |input| {
+        let (rest, tlv) = Tlv::parse(input)?;
+        match tlv.tag == tag {
+            true => Ok((rest, tlv.contents)),
+            false => Err(failure(input, message)),
+        }
+    }
+}
+
+/// The contents of a value with the given tag, if that's the next one
+pub fn optional<'a>(
+    tag: u8,
+) -> impl Fn(parse::Input<'a>) -> parse::ParseResult<'a, Option<parse::Input<'a>>> {
+    move |input| {
+        let (_, next) = opt(peek(byte))(input)?;
+        match next == Some(tag) {
+            true => Tlv::parse(input).map(|(rest, tlv)| (rest, Some(tlv.contents))),
+            false => Ok((input, None)),
+        }
+    }
+}
+
+/// Every value in the contents of a `SEQUENCE` or `SET`
+pub fn elements(mut input: parse::Input) -> parse::ParseResult<Vec<Tlv>> {
+    let mut elements = Vec::new();
+    while !input.is_empty() {
+        let (rest, tlv) = Tlv::parse(input)?;
+        elements.push(tlv);
+        input = rest;
+    }
+    Ok((input, elements))
+}
+
+pub fn oid(input: parse::Input) -> parse::ParseResult<String> {
+    let (rest, contents) = expect(OBJECT_IDENTIFIER, "Expected Object Identifier")(input)?;
+    Ok((rest, oid_string(contents)))
+}
+
+/// The dotted form of the contents of an `OBJECT IDENTIFIER`
+pub fn oid_string(contents: &[u8]) -> String {
+    let mut arcs = Vec::new();
+    let mut value = 0u64;
+    for &b in contents {
+        value = value << 7 | (b & 0x7f) as u64;
+        if b & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (value / 40).min(2);
+                arcs.push(first);
+                arcs.push(value - first * 40);
+            } else {
+                arcs.push(value);
+            }
+            value = 0;
+        }
+    }
+    arcs.iter()
+        .map(|arc| arc.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The contents of an `INTEGER`, which is big endian
+pub fn integer(input: parse::Input) -> parse::ParseResult<parse::Input> {
+    expect(INTEGER, "Expected Integer")(input)
+}
+
+/// An `INTEGER` that fits in a `u32`, like a version
+pub fn small_integer(input: parse::Input) -> parse::ParseResult<u32> {
+    let (rest, bytes) = integer(input)?;
+    if bytes.len() > 5 {
+        return Err(failure(input, "Integer Too Large"));
+    }
+    let value = bytes.iter().fold(0u64, |value, &b| value << 8 | b as u64);
+    Ok((rest, value as u32))
+}
+
+/// The value of a string, in any of the encodings a name uses
+pub fn string(tlv: &Tlv) -> String {
+    match tlv.tag {
+        BMP_STRING => char::decode_utf16(
+            tlv.contents
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]])),
+        )
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect(),
+        UNIVERSAL_STRING => tlv
+            .contents
+            .chunks_exact(4)
+            .map(|c| {
+                char::from_u32(u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+                    .unwrap_or(char::REPLACEMENT_CHARACTER)
+            })
+            .collect(),
+        // T61 strings are in practice Latin-1
+        T61_STRING => tlv.contents.iter().map(|&b| b as char).collect(),
+        _ => String::from_utf8_lossy(tlv.contents).into_owned(),
+    }
+}
+
+/// A `UTCTime` or `GeneralizedTime` as "YYYY-MM-DD HH:MM:SS UTC", or as it's encoded when it
+/// has some other form
+pub fn time(tlv: &Tlv) -> String {
+    let text = String::from_utf8_lossy(tlv.contents);
+    if !text.is_ascii() || !text.ends_with('Z') {
+        return text.into_owned();
+    }
+    let digits = text.trim_end_matches('Z');
+    let (year, rest) = match tlv.tag {
+        UTC_TIME if digits.len() >= 12 => match digits[..2].parse::<u32>() {
+            Ok(year) if year >= 50 => (format!("19{}", &digits[..2]), &digits[2..]),
+            Ok(_) => (format!("20{}", &digits[..2]), &digits[2..]),
+            Err(_) => return text.into_owned(),
+        },
+        GENERALIZED_TIME if digits.len() >= 14 => (digits[..4].to_string(), &digits[4..]),
+        _ => return text.into_owned(),
+    };
+    if rest.len() < 10 {
+        return text.into_owned();
+    }
+    format!(
+        "{}-{}-{} {}:{}:{}{} UTC",
+        year,
+        &rest[..2],
+        &rest[2..4],
+        &rest[4..6],
+        &rest[6..8],
+        &rest[8..10],
+        &rest[10..]
+    )
+}
+
+pub fn parse_time(input: parse::Input) -> parse::ParseResult<String> {
+    let (rest, tlv) = Tlv::parse(input)?;
+    match tlv.tag {
+        UTC_TIME | GENERALIZED_TIME => Ok((rest, time(&tlv))),
+        _ => Err(failure(input, "Expected Time")),
+    }
+}
+
+/// A `Name`, as the attributes of its relative distinguished names in the order they're encoded
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Name(pub Vec<(String, String)>);
+
+impl Name {
+    pub fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (rest, contents) = expect(SEQUENCE, "Expected Name")(input)?;
+        let (_, sets) = elements(contents)?;
+        let mut attributes = Vec::new();
+        for set in sets {
+            let (_, values) = elements(set.contents)?;
+            for value in values {
+                let (value, typ) = oid(value.contents)?;
+                let (_, value) = Tlv::parse(value)?;
+                attributes.push((attribute_name(&typ), string(&value)));
+            }
+        }
+        Ok((rest, Self(attributes)))
+    }
+
+    /// The value of the common name attribute
+    pub fn common_name(&self) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(typ, _)| typ == "CN")
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (typ, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}={}", typ, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// The short name of an attribute type, with the names OpenSSL uses
+fn attribute_name(oid: &str) -> String {
+    match oid {
+        "2.5.4.3" => "CN",
+        "2.5.4.4" => "SN",
+        "2.5.4.5" => "serialNumber",
+        "2.5.4.6" => "C",
+        "2.5.4.7" => "L",
+        "2.5.4.8" => "ST",
+        "2.5.4.9" => "street",
+        "2.5.4.10" => "O",
+        "2.5.4.11" => "OU",
+        "2.5.4.12" => "title",
+        "2.5.4.15" => "businessCategory",
+        "2.5.4.17" => "postalCode",
+        "2.5.4.42" => "GN",
+        "1.2.840.113549.1.9.1" => "emailAddress",
+        "0.9.2342.19200300.100.1.25" => "DC",
+        "1.3.6.1.4.1.311.60.2.1.1" => "jurisdictionL",
+        "1.3.6.1.4.1.311.60.2.1.2" => "jurisdictionST",
+        "1.3.6.1.4.1.311.60.2.1.3" => "jurisdictionC",
+        _ => return oid.to_string(),
+    }
+    .to_string()
+}
@@ -0,0 +1,549 @@
+//! The parts of PKCS #7 `SignedData` that Authenticode signatures use
+
+use std::fmt;
+
+use super::der::{self, Name, Tlv};
+use crate::error::BinDumpResult;
+use crate::parse::{self, failure, hex};
+
+const SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+const SPC_INDIRECT_DATA: &str = "1.3.6.1.4.1.311.2.1.4";
+const TST_INFO: &str = "1.2.840.113549.1.9.16.1.4";
+const MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+const SIGNING_TIME: &str = "1.2.840.113549.1.9.5";
+const COUNTERSIGNATURE: &str = "1.2.840.113549.1.9.6";
+const NESTED_SIGNATURE: &str = "1.3.6.1.4.1.311.2.4.1";
+const RFC3161_TIMESTAMP: &str = "1.3.6.1.4.1.311.3.3.1";
+
+/// How deep signatures and countersignatures can be nested in each other, to stop malformed
+/// ones from recursing without end
+const MAX_NESTING_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+    Other(String),
+}
+
+impl DigestAlgorithm {
+    fn from_oid(oid: String) -> Self {
+        match oid.as_str() {
+            "1.2.840.113549.2.5" => Self::Md5,
+            "1.3.14.3.2.26" => Self::Sha1,
+            "2.16.840.1.101.3.4.2.1" => Self::Sha256,
+            "2.16.840.1.101.3.4.2.2" => Self::Sha384,
+            "2.16.840.1.101.3.4.2.3" => Self::Sha512,
+            _ => Self::Other(oid),
+        }
+    }
+
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (rest, oid) = algorithm(input)?;
+        Ok((rest, Self::from_oid(oid)))
+    }
+}
+
+impl fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DigestAlgorithm::Md5 => write!(f, "MD5"),
+            DigestAlgorithm::Sha1 => write!(f, "SHA1"),
+            DigestAlgorithm::Sha256 => write!(f, "SHA256"),
+            DigestAlgorithm::Sha384 => write!(f, "SHA384"),
+            DigestAlgorithm::Sha512 => write!(f, "SHA512"),
+            DigestAlgorithm::Other(oid) => write!(f, "Unknown ({})", oid),
+        }
+    }
+}
+
+/// The object identifier of an `AlgorithmIdentifier`, without its parameters
+fn algorithm(input: parse::Input) -> parse::ParseResult<String> {
+    let (rest, contents) = der::expect(der::SEQUENCE, "Expected Algorithm Identifier")(input)?;
+    let (_, oid) = der::oid(contents)?;
+    Ok((rest, oid))
+}
+
+/// A `DigestInfo`, or a `MessageImprint` which has the same layout
+fn digest_info(input: parse::Input) -> parse::ParseResult<(DigestAlgorithm, Vec<u8>)> {
+    let (rest, contents) = der::expect(der::SEQUENCE, "Expected Digest Info")(input)?;
+    let (contents, algorithm) = DigestAlgorithm::parse(contents)?;
+    let (_, digest) = der::expect(der::OCTET_STRING, "Expected Digest")(contents)?;
+    Ok((rest, (algorithm, digest.to_vec())))
+}
+
+/// The parts of an X.509 certificate that identify it
+#[derive(Debug, Clone)]
+pub struct Certificate {
+    pub serial_number: Vec<u8>,
+    pub issuer: Name,
+    pub not_before: String,
+    pub not_after: String,
+    pub subject: Name,
+}
+
+impl Certificate {
+    pub fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (rest, certificate) = der::expect(der::SEQUENCE, "Expected Certificate")(input)?;
+        let (_, tbs) = der::expect(der::SEQUENCE, "Expected TBS Certificate")(certificate)?;
+        let (tbs, _version) = der::optional(der::context_tag(0))(tbs)?;
+        let (tbs, serial_number) = der::integer(tbs)?;
+        let (tbs, _signature) = algorithm(tbs)?;
+        let (tbs, issuer) = Name::parse(tbs)?;
+        let (tbs, validity) = der::expect(der::SEQUENCE, "Expected Validity")(tbs)?;
+        let (validity, not_before) = der::parse_time(validity)?;
+        let (_, not_after) = der::parse_time(validity)?;
+        let (_, subject) = Name::parse(tbs)?;
+        Ok((
+            rest,
+            Self {
+                serial_number: serial_number.to_vec(),
+                issuer,
+                not_before,
+                not_after,
+                subject,
+            },
+        ))
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = depth * 2;
+        writeln!(f, "{:indent$}Subject: {}", "", self.subject)?;
+        writeln!(f, "{:indent$}Issuer: {}", "", self.issuer)?;
+        writeln!(
+            f,
+            "{:indent$}Serial Number: {}",
+            "",
+            hex(&self.serial_number)
+        )?;
+        writeln!(
+            f,
+            "{:indent$}Validity: {} - {}",
+            "", self.not_before, self.not_after
+        )
+    }
+}
+
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Which certificate made a signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerIdentifier {
+    IssuerAndSerialNumber {
+        issuer: Name,
+        serial_number: Vec<u8>,
+    },
+    SubjectKeyIdentifier(Vec<u8>),
+}
+
+impl SignerIdentifier {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (rest, tlv) = Tlv::parse(input)?;
+        match tlv.tag {
+            der::SEQUENCE => {
+                let (contents, issuer) = Name::parse(tlv.contents)?;
+                let (_, serial_number) = der::integer(contents)?;
+                Ok((
+                    rest,
+                    Self::IssuerAndSerialNumber {
+                        issuer,
+                        serial_number: serial_number.to_vec(),
+                    },
+                ))
+            }
+            tag if tag == der::context_primitive_tag(0) => {
+                Ok((rest, Self::SubjectKeyIdentifier(tlv.contents.to_vec())))
+            }
+            _ => Err(failure(input, "Unknown Signer Identifier")),
+        }
+    }
+}
+
+/// A signature over the signature of a signer, which proves when it was made
+#[derive(Debug)]
+pub enum Countersignature {
+    /// A PKCS #9 countersignature, whose certificates are in the countersigned data
+    Pkcs9(SignerInfo),
+    /// An RFC 3161 timestamp token
+    Rfc3161(SignedData),
+}
+
+#[derive(Debug)]
+pub struct SignerInfo {
+    pub version: u32,
+    pub signer: SignerIdentifier,
+    pub digest_algorithm: DigestAlgorithm,
+    /// The time from the authenticated attributes, which timestamp signers have
+    pub signing_time: Option<String>,
+    /// The digest of the authenticated attributes' content, from the attributes
+    pub message_digest: Option<Vec<u8>>,
+    pub countersignatures: Vec<BinDumpResult<Countersignature>>,
+    /// Further signatures of the same image, usually with another digest algorithm
+    pub nested_signatures: Vec<BinDumpResult<SignedData>>,
+}
+
+impl SignerInfo {
+    fn parse(input: parse::Input, depth: usize) -> parse::ParseResult<Self> {
+        if depth > MAX_NESTING_DEPTH {
+            return Err(failure(input, "Signatures Nested Too Deep"));
+        }
+        let (rest, contents) = der::expect(der::SEQUENCE, "Expected Signer Info")(input)?;
+        let (contents, version) = der::small_integer(contents)?;
+        let (contents, signer) = SignerIdentifier::parse(contents)?;
+        let (contents, digest_algorithm) = DigestAlgorithm::parse(contents)?;
+        let (contents, authenticated) = der::optional(der::context_tag(0))(contents)?;
+        let (contents, _encryption_algorithm) = algorithm(contents)?;
+        let (contents, _encrypted_digest) =
+            der::expect(der::OCTET_STRING, "Expected Encrypted Digest")(contents)?;
+        let (_, unauthenticated) = der::optional(der::context_tag(1))(contents)?;
+
+        let mut info = Self {
+            version,
+            signer,
+            digest_algorithm,
+            signing_time: None,
+            message_digest: None,
+            countersignatures: Vec::new(),
+            nested_signatures: Vec::new(),
+        };
+        for (typ, value) in attributes(authenticated.unwrap_or_default())?.1 {
+            match typ.as_str() {
+                SIGNING_TIME => info.signing_time = Some(der::parse_time(value)?.1),
+                MESSAGE_DIGEST => {
+                    let (_, digest) =
+                        der::expect(der::OCTET_STRING, "Expected Message Digest")(value)?;
+                    info.message_digest = Some(digest.to_vec());
+                }
+                _ => {}
+            }
+        }
+        for (typ, value) in attributes(unauthenticated.unwrap_or_default())?.1 {
+            match typ.as_str() {
+                COUNTERSIGNATURE => info.countersignatures.push(
+                    Self::parse(value, depth + 1)
+                        .map(|(_, info)| Countersignature::Pkcs9(info))
+                        .map_err(Into::into),
+                ),
+                RFC3161_TIMESTAMP => info.countersignatures.push(
+                    SignedData::parse_nested(value, depth + 1)
+                        .map(|(_, data)| Countersignature::Rfc3161(data))
+                        .map_err(Into::into),
+                ),
+                NESTED_SIGNATURE => info.nested_signatures.push(
+                    SignedData::parse_nested(value, depth + 1)
+                        .map(|(_, data)| data)
+                        .map_err(Into::into),
+                ),
+                _ => {}
+            }
+        }
+        Ok((rest, info))
+    }
+
+    fn write(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        depth: usize,
+        certificates: &[Certificate],
+    ) -> fmt::Result {
+        let indent = depth * 2;
+        if let Some(certificate) = find_certificate(certificates, &self.signer) {
+            writeln!(f, "{:indent$}Subject: {}", "", certificate.subject)?;
+        }
+        match &self.signer {
+            SignerIdentifier::IssuerAndSerialNumber {
+                issuer,
+                serial_number,
+            } => {
+                writeln!(f, "{:indent$}Issuer: {}", "", issuer)?;
+                writeln!(f, "{:indent$}Serial Number: {}", "", hex(serial_number))?;
+            }
+            SignerIdentifier::SubjectKeyIdentifier(identifier) => {
+                writeln!(
+                    f,
+                    "{:indent$}Subject Key Identifier: {}",
+                    "",
+                    hex(identifier)
+                )?;
+            }
+        }
+        writeln!(
+            f,
+            "{:indent$}Digest Algorithm: {}",
+            "", self.digest_algorithm
+        )?;
+        if let Some(time) = &self.signing_time {
+            writeln!(f, "{:indent$}Signing Time: {}", "", time)?;
+        }
+        if let Some(digest) = &self.message_digest {
+            writeln!(f, "{:indent$}Message Digest: {}", "", hex(digest))?;
+        }
+        for countersignature in &self.countersignatures {
+            match countersignature {
+                Ok(Countersignature::Pkcs9(info)) => {
+                    writeln!(f, "{:indent$}Countersignature:", "")?;
+                    info.write(f, depth + 1, certificates)?;
+                }
+                Ok(Countersignature::Rfc3161(data)) => {
+                    writeln!(f, "{:indent$}Timestamp:", "")?;
+                    data.write(f, depth + 1)?;
+                }
+                Err(e) => writeln!(f, "{:indent$}Countersignature: Error {}", "", e)?,
+            }
+        }
+        for nested in &self.nested_signatures {
+            match nested {
+                Ok(data) => {
+                    writeln!(f, "{:indent$}Nested Signature:", "")?;
+                    data.write(f, depth + 1)?;
+                }
+                Err(e) => writeln!(f, "{:indent$}Nested Signature: Error {}", "", e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The type and value of every attribute in the contents of a `SET OF Attribute`
+fn attributes(input: parse::Input) -> parse::ParseResult<Vec<(String, parse::Input)>> {
+    let (rest, elements) = der::elements(input)?;
+    let mut attributes = Vec::new();
+    for attribute in elements {
+        let (values, typ) = der::oid(attribute.contents)?;
+        let (_, values) = der::expect(der::SET, "Expected Attribute Values")(values)?;
+        let (_, values) = der::elements(values)?;
+        // Every value gets its own entry, so that a set of countersignatures shows them all
+        for value in values {
+            attributes.push((typ.clone(), value.encoding));
+        }
+    }
+    Ok((rest, attributes))
+}
+
+fn find_certificate<'a>(
+    certificates: &'a [Certificate],
+    signer: &SignerIdentifier,
+) -> Option<&'a Certificate> {
+    match signer {
+        SignerIdentifier::IssuerAndSerialNumber {
+            issuer,
+            serial_number,
+        } => certificates.iter().find(|certificate| {
+            certificate.issuer == *issuer && certificate.serial_number == *serial_number
+        }),
+        SignerIdentifier::SubjectKeyIdentifier(_) => None,
+    }
+}
+
+/// What a `SignedData` signs
+#[derive(Debug)]
+pub enum Content {
+    /// An Authenticode `SpcIndirectDataContent`, with the digest of the image
+    IndirectData {
+        digest_algorithm: DigestAlgorithm,
+        digest: Vec<u8>,
+    },
+    /// The `TSTInfo` of a timestamp token
+    TstInfo {
+        policy: String,
+        digest_algorithm: DigestAlgorithm,
+        /// The digest of the signature that got timestamped
+        message_imprint: Vec<u8>,
+        serial_number: Vec<u8>,
+        time: String,
+    },
+    Other(String),
+}
+
+impl Content {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (rest, contents) = der::expect(der::SEQUENCE, "Expected Content Info")(input)?;
+        let (contents, typ) = der::oid(contents)?;
+        let (_, explicit) = der::optional(der::context_tag(0))(contents)?;
+        let Some(explicit) = explicit else {
+            return Ok((rest, Self::Other(typ)));
+        };
+        let (_, mut content) = Tlv::parse(explicit)?;
+        // Authenticode puts its content right in, where anything else has it in an OCTET STRING
+        if content.tag == der::OCTET_STRING {
+            content = Tlv::parse(content.contents)?.1;
+        }
+        let content = match typ.as_str() {
+            SPC_INDIRECT_DATA => {
+                let (contents, _data) = Tlv::parse(content.contents)?;
+                let (_, (digest_algorithm, digest)) = digest_info(contents)?;
+                Self::IndirectData {
+                    digest_algorithm,
+                    digest,
+                }
+            }
+            TST_INFO => {
+                let (contents, _version) = der::integer(content.contents)?;
+                let (contents, policy) = der::oid(contents)?;
+                let (contents, (digest_algorithm, message_imprint)) = digest_info(contents)?;
+                let (contents, serial_number) = der::integer(contents)?;
+                let (_, time) = der::parse_time(contents)?;
+                Self::TstInfo {
+                    policy,
+                    digest_algorithm,
+                    message_imprint,
+                    serial_number: serial_number.to_vec(),
+                    time,
+                }
+            }
+            _ => Self::Other(typ),
+        };
+        Ok((rest, content))
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = depth * 2;
+        match self {
+            Content::IndirectData {
+                digest_algorithm,
+                digest,
+            } => {
+                writeln!(f, "{:indent$}Content: Indirect Data", "")?;
+                writeln!(f, "{:indent$}  Digest Algorithm: {}", "", digest_algorithm)?;
+                writeln!(f, "{:indent$}  Digest: {}", "", hex(digest))
+            }
+            Content::TstInfo {
+                policy,
+                digest_algorithm,
+                message_imprint,
+                serial_number,
+                time,
+            } => {
+                writeln!(f, "{:indent$}Content: Timestamp Info", "")?;
+                writeln!(f, "{:indent$}  Policy: {}", "", policy)?;
+                writeln!(f, "{:indent$}  Digest Algorithm: {}", "", digest_algorithm)?;
+                writeln!(
+                    f,
+                    "{:indent$}  Message Imprint: {}",
+                    "",
+                    hex(message_imprint)
+                )?;
+                writeln!(f, "{:indent$}  Serial Number: {}", "", hex(serial_number))?;
+                writeln!(f, "{:indent$}  Time: {}", "", time)
+            }
+            Content::Other(typ) => writeln!(f, "{:indent$}Content: Unknown ({})", "", typ),
+        }
+    }
+}
+
+/// A PKCS #7 `SignedData`, read from the `ContentInfo` around it
+#[derive(Debug)]
+pub struct SignedData {
+    pub version: u32,
+    pub content: Content,
+    pub certificates: Vec<Certificate>,
+    pub signers: Vec<SignerInfo>,
+}
+
+impl SignedData {
+    pub fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        Self::parse_nested(input, 0)
+    }
+
+    fn parse_nested(input: parse::Input, depth: usize) -> parse::ParseResult<Self> {
+        let (rest, contents) = der::expect(der::SEQUENCE, "Expected Content Info")(input)?;
+        let (contents, typ) = der::oid(contents)?;
+        if typ != SIGNED_DATA {
+            return Err(failure(input, "Expected Signed Data"));
+        }
+        let (_, explicit) = der::expect(der::context_tag(0), "Expected Signed Data")(contents)?;
+        let (_, signed_data) = der::expect(der::SEQUENCE, "Expected Signed Data")(explicit)?;
+        let (signed_data, version) = der::small_integer(signed_data)?;
+        let (signed_data, _digest_algorithms) =
+            der::expect(der::SET, "Expected Digest Algorithms")(signed_data)?;
+        let (signed_data, content) = Content::parse(signed_data)?;
+        let (signed_data, certificate_set) = der::optional(der::context_tag(0))(signed_data)?;
+        let (signed_data, _crls) = der::optional(der::context_tag(1))(signed_data)?;
+        let (_, signer_set) = der::expect(der::SET, "Expected Signer Infos")(signed_data)?;
+
+        let mut certificates = Vec::new();
+        let mut input = certificate_set.unwrap_or_default();
+        while !input.is_empty() {
+            // The other choices are attribute certificates, which don't sign anything here
+            input = match input[0] {
+                der::SEQUENCE => {
+                    let (rest, certificate) = Certificate::parse(input)?;
+                    certificates.push(certificate);
+                    rest
+                }
+                _ => Tlv::parse(input)?.0,
+            };
+        }
+        let mut signers = Vec::new();
+        let mut input = signer_set;
+        while !input.is_empty() {
+            let (rest, signer) = SignerInfo::parse(input, depth)?;
+            signers.push(signer);
+            input = rest;
+        }
+        Ok((
+            rest,
+            Self {
+                version,
+                content,
+                certificates,
+                signers,
+            },
+        ))
+    }
+
+    /// The certificate of a signer, if the signature carries it
+    pub fn certificate(&self, signer: &SignerIdentifier) -> Option<&Certificate> {
+        find_certificate(&self.certificates, signer)
+    }
+
+    /// The image digests this and the nested signatures sign
+    pub fn image_digests(&self) -> Vec<(&DigestAlgorithm, &[u8])> {
+        let mut digests = Vec::new();
+        if let Content::IndirectData {
+            digest_algorithm,
+            digest,
+        } = &self.content
+        {
+            digests.push((digest_algorithm, digest.as_slice()));
+        }
+        for signer in &self.signers {
+            for nested in signer.nested_signatures.iter().flatten() {
+                digests.extend(nested.image_digests());
+            }
+        }
+        digests
+    }
+
+    pub(super) fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = depth * 2;
+        writeln!(f, "{:indent$}Version: {}", "", self.version)?;
+        self.content.write(f, depth)?;
+        if !self.certificates.is_empty() {
+            writeln!(f, "{:indent$}Certificates:", "")?;
+            for (i, certificate) in self.certificates.iter().enumerate() {
+                writeln!(f, "{:indent$}  Certificate {}", "", i)?;
+                certificate.write(f, depth + 2)?;
+            }
+        }
+        writeln!(f, "{:indent$}Signers:", "")?;
+        for (i, signer) in self.signers.iter().enumerate() {
+            writeln!(f, "{:indent$}  Signer {}", "", i)?;
+            signer.write(f, depth + 2, &self.certificates)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SignedData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
@@ -0,0 +1,692 @@
+pub mod tables;
+
+use std::fmt;
+
+use nom::bytes::complete::{tag, take};
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+use sha1::{Digest, Sha1};
+
+use super::header::{flag_names, DataDirectory, DirectoryEntry};
+use super::Pe;
+use crate::error::BinDumpResult;
+use crate::parse::{self, failure, hex, string_at, truncated};
+use tables::{Assembly, AssemblyRef, Tables, Token};
+
+const METADATA_SIGNATURE: &[u8] = b"BSJB";
+
+/// The assembly reference flag that says it has the whole public key rather than its token
+const ASSEMBLY_REF_PUBLIC_KEY: u32 = 0x1;
+
+// From corhdr.h
+const COMIMAGE_FLAGS: [(u32, &str); 6] = [
+    (0x00001, "ILONLY"),
+    (0x00002, "32BITREQUIRED"),
+    (0x00004, "IL_LIBRARY"),
+    (0x00008, "STRONGNAMESIGNED"),
+    (0x00010, "NATIVE_ENTRYPOINT"),
+    (0x10000, "TRACKDEBUGDATA"),
+];
+const COMIMAGE_FLAGS_NATIVE_ENTRYPOINT: u32 = 0x10;
+const COMIMAGE_FLAGS_32BITPREFERRED: u32 = 0x20000;
+
+fn data_directory(input: parse::Input) -> parse::ParseResult<DataDirectory> {
+    let (input, (virtual_address, size)) = tuple((le_u32, le_u32))(input)?;
+    Ok((
+        input,
+        DataDirectory {
+            virtual_address,
+            size,
+        },
+    ))
+}
+
+/// The `IMAGE_COR20_HEADER` of a managed image
+#[derive(Debug)]
+pub struct CliHeader {
+    pub size: u32,
+    pub major_runtime_version: u16,
+    pub minor_runtime_version: u16,
+    pub metadata: DataDirectory,
+    pub flags: u32,
+    /// The token of the entry point method, or its RVA with a native entry point
+    pub entry_point: u32,
+    pub resources: DataDirectory,
+    pub strong_name_signature: DataDirectory,
+    pub code_manager_table: DataDirectory,
+    pub vtable_fixups: DataDirectory,
+    pub export_address_table_jumps: DataDirectory,
+    pub managed_native_header: DataDirectory,
+}
+
+impl CliHeader {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (
+            input,
+            (size, major_runtime_version, minor_runtime_version, metadata, flags, entry_point),
+        ) = context(
+            "Parse CLI Header",
+            tuple((le_u32, le_u16, le_u16, data_directory, le_u32, le_u32)),
+        )(input)?;
+        let (input, directories) =
+            context("Parse CLI Header Directories", count(data_directory, 6))(input)?;
+        Ok((
+            input,
+            Self {
+                size,
+                major_runtime_version,
+                minor_runtime_version,
+                metadata,
+                flags,
+                entry_point,
+                resources: directories[0],
+                strong_name_signature: directories[1],
+                code_manager_table: directories[2],
+                vtable_fixups: directories[3],
+                export_address_table_jumps: directories[4],
+                managed_native_header: directories[5],
+            },
+        ))
+    }
+
+    pub fn flag_names(&self) -> Vec<&'static str> {
+        let mut names = flag_names(self.flags, &COMIMAGE_FLAGS);
+        if self.flags & COMIMAGE_FLAGS_32BITPREFERRED != 0 {
+            names.push("32BITPREFERRED");
+        }
+        names
+    }
+}
+
+impl fmt::Display for CliHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Size: {:#x}", self.size)?;
+        writeln!(
+            f,
+            "Runtime Version: {}.{}",
+            self.major_runtime_version, self.minor_runtime_version
+        )?;
+        writeln!(
+            f,
+            "Flags: {:#x} ({})",
+            self.flags,
+            self.flag_names().join(", ")
+        )?;
+        if self.flags & COMIMAGE_FLAGS_NATIVE_ENTRYPOINT != 0 {
+            writeln!(f, "Entry Point: {:#x}", self.entry_point)?;
+        } else {
+            writeln!(f, "Entry Point Token: {:#010x}", self.entry_point)?;
+        }
+        for (name, directory) in [
+            ("Metadata", self.metadata),
+            ("Resources", self.resources),
+            ("Strong Name Signature", self.strong_name_signature),
+            ("Code Manager Table", self.code_manager_table),
+            ("VTable Fixups", self.vtable_fixups),
+            (
+                "Export Address Table Jumps",
+                self.export_address_table_jumps,
+            ),
+            ("Managed Native Header", self.managed_native_header),
+        ] {
+            if directory.virtual_address != 0 {
+                writeln!(
+                    f,
+                    "{}: {:#x} (Size {:#x})",
+                    name, directory.virtual_address, directory.size
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A stream of the metadata, by its offset from the metadata root
+#[derive(Debug)]
+pub struct StreamHeader {
+    pub offset: u32,
+    pub size: u32,
+    pub name: String,
+}
+
+impl StreamHeader {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (rest, (offset, size)) =
+            context("Parse Stream Header", tuple((le_u32, le_u32)))(input)?;
+        let name = string_at(rest, 0);
+        // The name is NUL terminated and padded to 4 bytes
+        let padded = (name.len() + 4) & !3;
+        let (rest, _) = context("Parse Stream Name", take(padded))(rest)?;
+        Ok((rest, Self { offset, size, name }))
+    }
+}
+
+/// The heaps the tables point into
+struct Heaps<'a> {
+    strings: &'a [u8],
+    guids: &'a [u8],
+    blobs: &'a [u8],
+}
+
+impl<'a> Heaps<'a> {
+    fn string(&self, index: u32) -> String {
+        string_at(self.strings, index as usize)
+    }
+
+    /// A GUID by its index, which counts from 1
+    fn guid(&self, index: u32) -> Option<[u8; 16]> {
+        let start = (index as usize).checked_sub(1)? * 16;
+        self.guids.get(start..start + 16)?.try_into().ok()
+    }
+
+    fn blob(&self, index: u32) -> Vec<u8> {
+        heap_entry(self.blobs, index as usize)
+            .unwrap_or_default()
+            .to_vec()
+    }
+}
+
+/// An entry of the `#Blob` or `#US` heap, which starts with its compressed length
+fn heap_entry(heap: &[u8], index: usize) -> Option<&[u8]> {
+    let data = heap.get(index..)?;
+    let first = *data.first()? as usize;
+    let (length, header) = match first {
+        0x00..=0x7f => (first, 1),
+        0x80..=0xbf => ((first & 0x3f) << 8 | *data.get(1)? as usize, 2),
+        0xc0..=0xdf => {
+            let bytes = data.get(1..4)?;
+            (
+                (first & 0x1f) << 24
+                    | (bytes[0] as usize) << 16
+                    | (bytes[1] as usize) << 8
+                    | bytes[2] as usize,
+                4,
+            )
+        }
+        _ => return None,
+    };
+    data.get(header..header + length)
+}
+
+/// The metadata root and what its streams hold
+#[derive(Debug)]
+pub struct Metadata {
+    pub major_version: u16,
+    pub minor_version: u16,
+    /// The runtime version the image targets, like "v4.0.30319"
+    pub version: String,
+    pub flags: u16,
+    pub streams: Vec<StreamHeader>,
+    pub tables: BinDumpResult<Tables>,
+}
+
+impl Metadata {
+    fn parse(input: parse::Input) -> BinDumpResult<Self> {
+        let (rest, (_, major_version, minor_version, _, length)) = context(
+            "Parse Metadata Root",
+            tuple((tag(METADATA_SIGNATURE), le_u16, le_u16, le_u32, le_u32)),
+        )(input)?;
+        let (rest, version) = context("Parse Metadata Version", take(length))(rest)?;
+        let (rest, (flags, stream_count)) =
+            context("Parse Metadata Root", tuple((le_u16, le_u16)))(rest)?;
+        let (_, streams) = context(
+            "Parse Stream Headers",
+            count(StreamHeader::parse, stream_count as usize),
+        )(rest)?;
+
+        let stream = |name: &str| {
+            let header = streams.iter().find(|stream| stream.name == name)?;
+            let start = header.offset as usize;
+            input.get(start..start.checked_add(header.size as usize)?)
+        };
+        let heaps = Heaps {
+            strings: stream("#Strings").unwrap_or_default(),
+            guids: stream("#GUID").unwrap_or_default(),
+            blobs: stream("#Blob").unwrap_or_default(),
+        };
+        // "#-" is the uncompressed form of the tables that edit and continue leaves
+        let tables = match stream("#~").or_else(|| stream("#-")) {
+            Some(data) => Tables::parse(data, &heaps),
+            None => Err(failure(input, "Missing Metadata Tables").into()),
+        };
+        Ok(Self {
+            major_version,
+            minor_version,
+            version: string_at(version, 0),
+            flags,
+            streams,
+            tables,
+        })
+    }
+}
+
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Metadata Version: {}.{} ({})",
+            self.major_version, self.minor_version, self.version
+        )?;
+        writeln!(f, "Streams:")?;
+        for stream in &self.streams {
+            writeln!(
+                f,
+                "  {}: Offset {:#x} Size {:#x}",
+                stream.name, stream.offset, stream.size
+            )?;
+        }
+        let tables = match &self.tables {
+            Ok(tables) => tables,
+            Err(e) => return writeln!(f, "Tables: Error {}", e),
+        };
+        writeln!(
+            f,
+            "Tables Version: {}.{}",
+            tables.major_version, tables.minor_version
+        )?;
+        writeln!(f, "Rows:")?;
+        for (table, rows) in &tables.row_counts {
+            writeln!(f, "  {}: {}", table, rows)?;
+        }
+        if let Some(module) = &tables.module {
+            writeln!(f, "Module: {}", module.name)?;
+            if let Some(mvid) = &module.mvid {
+                writeln!(f, "MVID: {}", hex(mvid))?;
+            }
+        }
+        if let Some(assembly) = &tables.assembly {
+            writeln!(f, "Assembly: {}", assembly.display_name())?;
+        }
+        if !tables.assembly_refs.is_empty() {
+            writeln!(f, "Assembly References:")?;
+            for assembly_ref in &tables.assembly_refs {
+                writeln!(f, "  {}", assembly_ref.display_name())?;
+            }
+        }
+        if !tables.type_defs.is_empty() {
+            writeln!(f, "Types:")?;
+        }
+        for (i, typ) in tables.type_defs.iter().enumerate() {
+            let token = Token {
+                table: tables::Table::TypeDef,
+                row: i as u32 + 1,
+            };
+            write!(
+                f,
+                "  {} {} (Flags {:#x})",
+                token,
+                tables.type_name(token).unwrap_or_default(),
+                typ.flags
+            )?;
+            if let Some(base) = typ.extends {
+                match tables.type_name(base) {
+                    Some(name) => write!(f, " Extends {}", name)?,
+                    None => write!(f, " Extends {}", base)?,
+                }
+            }
+            writeln!(f)?;
+            for field in tables.type_fields(i) {
+                writeln!(f, "    Field {} (Flags {:#x})", field.name, field.flags)?;
+            }
+            for method in tables.type_methods(i) {
+                writeln!(
+                    f,
+                    "    Method {} (RVA {:#x} Flags {:#x} Impl Flags {:#x})",
+                    method.name, method.rva, method.flags, method.impl_flags
+                )?;
+            }
+        }
+        if !tables.member_refs.is_empty() {
+            writeln!(f, "Member References:")?;
+        }
+        for i in 0..tables.member_refs.len() {
+            let token = Token {
+                table: tables::Table::MemberRef,
+                row: i as u32 + 1,
+            };
+            let name = tables.member_name(token).unwrap_or_default();
+            writeln!(f, "  {} {}", token, name)?;
+        }
+        if !tables.custom_attributes.is_empty() {
+            writeln!(f, "Custom Attributes:")?;
+        }
+        for attribute in &tables.custom_attributes {
+            let parent = attribute
+                .parent
+                .map_or_else(|| "None".to_string(), |parent| parent.to_string());
+            let constructor = attribute
+                .constructor
+                .and_then(|constructor| tables.member_name(constructor))
+                .unwrap_or_default();
+            writeln!(
+                f,
+                "  {} {} Value {}",
+                parent,
+                constructor,
+                hex(&attribute.value)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The last 8 bytes of the SHA-1 hash of a public key, reversed
+fn public_key_token(public_key: &[u8]) -> Vec<u8> {
+    let hash = Sha1::digest(public_key);
+    hash[hash.len() - 8..].iter().rev().copied().collect()
+}
+
+/// The name of an assembly the way .NET shows it
+fn display_name(name: &str, version: tables::Version, culture: &str, token: &[u8]) -> String {
+    format!(
+        "{}, Version={}, Culture={}, PublicKeyToken={}",
+        name,
+        version,
+        if culture.is_empty() {
+            "neutral"
+        } else {
+            culture
+        },
+        if token.is_empty() {
+            "null".to_string()
+        } else {
+            hex(token)
+        }
+    )
+}
+
+impl Assembly {
+    pub fn public_key_token(&self) -> Vec<u8> {
+        match self.public_key.is_empty() {
+            true => Vec::new(),
+            false => public_key_token(&self.public_key),
+        }
+    }
+
+    pub fn display_name(&self) -> String {
+        display_name(
+            &self.name,
+            self.version,
+            &self.culture,
+            &self.public_key_token(),
+        )
+    }
+}
+
+impl AssemblyRef {
+    pub fn public_key_token(&self) -> Vec<u8> {
+        match self.flags & ASSEMBLY_REF_PUBLIC_KEY != 0 && !self.public_key_or_token.is_empty() {
+            true => public_key_token(&self.public_key_or_token),
+            false => self.public_key_or_token.clone(),
+        }
+    }
+
+    pub fn display_name(&self) -> String {
+        display_name(
+            &self.name,
+            self.version,
+            &self.culture,
+            &self.public_key_token(),
+        )
+    }
+}
+
+/// The managed parts of an image
+#[derive(Debug)]
+pub struct Clr {
+    pub header: CliHeader,
+    pub metadata: BinDumpResult<Metadata>,
+}
+
+impl fmt::Display for Clr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.header)?;
+        match &self.metadata {
+            Ok(metadata) => write!(f, "{}", metadata),
+            Err(e) => writeln!(f, "Metadata: Error {}", e),
+        }
+    }
+}
+
+impl Pe {
+    pub(super) fn parse_clr(&self) -> Option<BinDumpResult<Clr>> {
+        let input = self.directory_data(DirectoryEntry::ComDescriptor)?;
+        Some(self.parse_cli_header(input))
+    }
+
+    fn parse_cli_header(&self, input: parse::Input) -> BinDumpResult<Clr> {
+        let (_, header) = CliHeader::parse(input)?;
+        let metadata = match self.data_at_rva(header.metadata.virtual_address) {
+            Some(data) => Metadata::parse(&data[..(header.metadata.size as usize).min(data.len())]),
+            None => Err(truncated("metadata")),
+        };
+        Ok(Clr { header, metadata })
+    }
+
+    /// The CLI header and metadata of a managed image
+    pub fn clr(&self) -> Option<&Clr> {
+        self.clr.as_ref()?.as_ref().ok()
+    }
+
+    /// The metadata tables of a managed image
+    pub fn metadata_tables(&self) -> Option<&Tables> {
+        self.clr()?.metadata.as_ref().ok()?.tables.as_ref().ok()
+    }
+
+    /// The assembly a managed image defines, which modules that aren't the main one lack
+    pub fn assembly(&self) -> Option<&Assembly> {
+        self.metadata_tables()?.assembly.as_ref()
+    }
+
+    pub fn assembly_refs(&self) -> &[AssemblyRef] {
+        self.metadata_tables()
+            .map_or(&[], |tables| &tables.assembly_refs)
+    }
+
+    /// A string of the `#US` heap, which holds the string literals of the code
+    pub fn user_string(&self, index: u32) -> Option<String> {
+        let clr = self.clr()?;
+        let stream = clr
+            .metadata
+            .as_ref()
+            .ok()?
+            .streams
+            .iter()
+            .find(|stream| stream.name == "#US")?;
+        let rva = clr
+            .header
+            .metadata
+            .virtual_address
+            .checked_add(stream.offset)?;
+        let heap = self.data_at_rva(rva)?;
+        let heap = &heap[..(stream.size as usize).min(heap.len())];
+        // The UTF-16 string has a last byte that says whether it needs special handling
+        let entry = heap_entry(heap, index as usize)?;
+        let units = entry
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]));
+        Some(
+            char::decode_utf16(units)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::image;
+    use super::*;
+    use tables::Table;
+
+    const STRINGS: &[u8] =
+        b"\0test.dll\0Program\0App\0Main\0.ctor\0test\0System.Runtime\0mscorlib\0";
+
+    fn u16s(values: &[u16]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    /// A `#~` stream with a module, one type with two methods, an assembly and two references
+    fn tables_stream() -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 0, 2, 0, 0, 1];
+        let valid = 1u64 | 1 << 2 | 1 << 6 | 1 << 0x20 | 1 << 0x23;
+        data.extend_from_slice(&valid.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        for rows in [1u32, 1, 2, 1, 2] {
+            data.extend_from_slice(&rows.to_le_bytes());
+        }
+        // Module
+        data.extend(u16s(&[0, 1, 1, 0, 0]));
+        // TypeDef App.Program
+        data.extend(u16s(&[0, 0x10, 10, 18, 0, 1, 1]));
+        // MethodDef Main and .ctor
+        data.extend(u16s(&[0x2050, 0, 0, 0x96, 22, 0, 1]));
+        data.extend(u16s(&[0x2060, 0, 0, 0x1886, 27, 0, 1]));
+        // Assembly test 1.2.3.4
+        data.extend(u16s(&[0x8004, 0, 1, 2, 3, 4, 0, 0, 0, 33, 0]));
+        // AssemblyRef System.Runtime with a token and mscorlib with a full public key
+        data.extend(u16s(&[8, 0, 0, 0, 0, 0, 1, 38, 0, 0]));
+        data.extend(u16s(&[4, 0, 0, 0, 1, 0, 10, 53, 0, 0]));
+        data
+    }
+
+    /// A metadata root with its streams laid out after the stream headers
+    fn metadata() -> Vec<u8> {
+        let mut blobs = vec![0, 8, 0xb0, 0x3f, 0x5f, 0x7f, 0x11, 0xd5, 0x0a, 0x3a, 16];
+        // The ECMA standard public key
+        blobs.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0]);
+        let streams = [
+            ("#~", tables_stream()),
+            ("#Strings", STRINGS.to_vec()),
+            ("#US", vec![0, 5, b'H', 0, b'i', 0, 0]),
+            ("#GUID", vec![0x11; 16]),
+            ("#Blob", blobs),
+        ];
+        let mut data = METADATA_SIGNATURE.to_vec();
+        data.extend(u16s(&[1, 1, 0, 0, 12, 0]));
+        data.extend_from_slice(b"v4.0.30319\0\0");
+        data.extend(u16s(&[0, streams.len() as u16]));
+        let headers_size: usize = streams
+            .iter()
+            .map(|(name, _)| 8 + ((name.len() + 4) & !3))
+            .sum();
+        let mut offset = data.len() + headers_size;
+        for (name, stream) in &streams {
+            let size = (stream.len() + 3) & !3;
+            data.extend_from_slice(&(offset as u32).to_le_bytes());
+            data.extend_from_slice(&(size as u32).to_le_bytes());
+            data.extend_from_slice(name.as_bytes());
+            data.resize((data.len() + 4) & !3, 0);
+            offset += size;
+        }
+        for (_, stream) in &streams {
+            data.extend_from_slice(stream);
+            data.resize((data.len() + 3) & !3, 0);
+        }
+        data
+    }
+
+    /// The test image with its headers grown to 0x400 bytes to hold a CLI header at 0x160 and
+    /// the metadata at 0x1b0
+    fn managed_image() -> Pe {
+        let mut data = image();
+        data[0x94..0x98].copy_from_slice(&0x400u32.to_le_bytes());
+        // The COM descriptor entry of the optional header
+        data[0x138..0x140].copy_from_slice(&[0x60, 0x01, 0, 0, 72, 0, 0, 0]);
+        data.resize(0x160, 0);
+        let metadata = metadata();
+        for field in [
+            72,
+            2 | 5 << 16,
+            0x1b0,
+            metadata.len() as u32,
+            0x2_0003,
+            0x0600_0001,
+        ] {
+            data.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        data.resize(0x1b0, 0);
+        data.extend_from_slice(&metadata);
+        Pe::load(data).unwrap()
+    }
+
+    #[test]
+    fn parses_cli_header_and_streams() {
+        let pe = managed_image();
+        let clr = pe.clr().unwrap();
+        assert_eq!(clr.header.major_runtime_version, 2);
+        assert_eq!(clr.header.minor_runtime_version, 5);
+        assert_eq!(
+            clr.header.flag_names(),
+            ["ILONLY", "32BITREQUIRED", "32BITPREFERRED"]
+        );
+        assert!(clr
+            .header
+            .to_string()
+            .contains("Entry Point Token: 0x06000001"));
+
+        let metadata = clr.metadata.as_ref().unwrap();
+        assert_eq!(metadata.version, "v4.0.30319");
+        let names: Vec<&str> = metadata.streams.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["#~", "#Strings", "#US", "#GUID", "#Blob"]);
+        assert_eq!(pe.user_string(1).as_deref(), Some("Hi"));
+    }
+
+    #[test]
+    fn reads_assemblies_types_and_methods() {
+        let pe = managed_image();
+        let tables = pe.metadata_tables().unwrap();
+        let module = tables.module.as_ref().unwrap();
+        assert_eq!(module.name, "test.dll");
+        assert_eq!(module.mvid, Some([0x11; 16]));
+        assert_eq!(
+            pe.assembly().unwrap().display_name(),
+            "test, Version=1.2.3.4, Culture=neutral, PublicKeyToken=null"
+        );
+        let references: Vec<String> = pe
+            .assembly_refs()
+            .iter()
+            .map(AssemblyRef::display_name)
+            .collect();
+        assert_eq!(
+            references,
+            [
+                "System.Runtime, Version=8.0.0.0, Culture=neutral, PublicKeyToken=b03f5f7f11d50a3a",
+                "mscorlib, Version=4.0.0.0, Culture=neutral, PublicKeyToken=b77a5c561934e089",
+            ]
+        );
+        assert_eq!(tables.type_methods(0).len(), 2);
+        let constructor = Token {
+            table: Table::MethodDef,
+            row: 2,
+        };
+        assert_eq!(
+            tables.member_name(constructor).as_deref(),
+            Some("App.Program::.ctor")
+        );
+    }
+
+    #[test]
+    fn reads_heap_entry_lengths() {
+        assert_eq!(heap_entry(&[0, 2, 7, 8], 1), Some(&[7, 8][..]));
+        assert_eq!(heap_entry(&[0x80, 0x02, 7, 8], 0), Some(&[7, 8][..]));
+        assert_eq!(heap_entry(&[0xc0, 0, 0, 1, 7], 0), Some(&[7][..]));
+        assert_eq!(heap_entry(&[0x05, 7], 0), None);
+        assert_eq!(heap_entry(&[0xe0, 7], 0), None);
+    }
+
+    #[test]
+    fn reports_missing_tables() {
+        let mut data = metadata();
+        // Rename the "#~" stream
+        let name = data.windows(4).position(|w| w == b"#~\0\0").unwrap();
+        data[name + 1] = b'?';
+        let metadata = Metadata::parse(&data).unwrap();
+        assert!(metadata.tables.is_err());
+    }
+}
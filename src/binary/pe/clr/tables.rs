@@ -0,0 +1,764 @@
+//! The metadata tables of the `#~` stream, from ECMA-335 II.22
+
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_u32, le_u64, u8 as byte};
+use nom::sequence::tuple;
+
+use super::Heaps;
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse::{self, failure};
+
+const TABLE_COUNT: usize = 0x2d;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Table {
+    Module = 0x00,
+    TypeRef = 0x01,
+    TypeDef = 0x02,
+    FieldPtr = 0x03,
+    Field = 0x04,
+    MethodPtr = 0x05,
+    MethodDef = 0x06,
+    ParamPtr = 0x07,
+    Param = 0x08,
+    InterfaceImpl = 0x09,
+    MemberRef = 0x0a,
+    Constant = 0x0b,
+    CustomAttribute = 0x0c,
+    FieldMarshal = 0x0d,
+    DeclSecurity = 0x0e,
+    ClassLayout = 0x0f,
+    FieldLayout = 0x10,
+    StandAloneSig = 0x11,
+    EventMap = 0x12,
+    EventPtr = 0x13,
+    Event = 0x14,
+    PropertyMap = 0x15,
+    PropertyPtr = 0x16,
+    Property = 0x17,
+    MethodSemantics = 0x18,
+    MethodImpl = 0x19,
+    ModuleRef = 0x1a,
+    TypeSpec = 0x1b,
+    ImplMap = 0x1c,
+    FieldRva = 0x1d,
+    EncLog = 0x1e,
+    EncMap = 0x1f,
+    Assembly = 0x20,
+    AssemblyProcessor = 0x21,
+    AssemblyOs = 0x22,
+    AssemblyRef = 0x23,
+    AssemblyRefProcessor = 0x24,
+    AssemblyRefOs = 0x25,
+    File = 0x26,
+    ExportedType = 0x27,
+    ManifestResource = 0x28,
+    NestedClass = 0x29,
+    GenericParam = 0x2a,
+    MethodSpec = 0x2b,
+    GenericParamConstraint = 0x2c,
+}
+
+const TABLES: [Table; TABLE_COUNT] = [
+    Table::Module,
+    Table::TypeRef,
+    Table::TypeDef,
+    Table::FieldPtr,
+    Table::Field,
+    Table::MethodPtr,
+    Table::MethodDef,
+    Table::ParamPtr,
+    Table::Param,
+    Table::InterfaceImpl,
+    Table::MemberRef,
+    Table::Constant,
+    Table::CustomAttribute,
+    Table::FieldMarshal,
+    Table::DeclSecurity,
+    Table::ClassLayout,
+    Table::FieldLayout,
+    Table::StandAloneSig,
+    Table::EventMap,
+    Table::EventPtr,
+    Table::Event,
+    Table::PropertyMap,
+    Table::PropertyPtr,
+    Table::Property,
+    Table::MethodSemantics,
+    Table::MethodImpl,
+    Table::ModuleRef,
+    Table::TypeSpec,
+    Table::ImplMap,
+    Table::FieldRva,
+    Table::EncLog,
+    Table::EncMap,
+    Table::Assembly,
+    Table::AssemblyProcessor,
+    Table::AssemblyOs,
+    Table::AssemblyRef,
+    Table::AssemblyRefProcessor,
+    Table::AssemblyRefOs,
+    Table::File,
+    Table::ExportedType,
+    Table::ManifestResource,
+    Table::NestedClass,
+    Table::GenericParam,
+    Table::MethodSpec,
+    Table::GenericParamConstraint,
+];
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// An index that can point into one of several tables, with the table in its low bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodedIndex {
+    TypeDefOrRef,
+    HasConstant,
+    HasCustomAttribute,
+    HasFieldMarshal,
+    HasDeclSecurity,
+    MemberRefParent,
+    HasSemantics,
+    MethodDefOrRef,
+    MemberForwarded,
+    Implementation,
+    CustomAttributeType,
+    ResolutionScope,
+    TypeOrMethodDef,
+}
+
+impl CodedIndex {
+    /// The tables by tag, with `None` for the tags that aren't used
+    fn tables(self) -> &'static [Option<Table>] {
+        use Table::*;
+        match self {
+            CodedIndex::TypeDefOrRef => &[Some(TypeDef), Some(TypeRef), Some(TypeSpec)],
+            CodedIndex::HasConstant => &[Some(Field), Some(Param), Some(Property)],
+            CodedIndex::HasCustomAttribute => &[
+                Some(MethodDef),
+                Some(Field),
+                Some(TypeRef),
+                Some(TypeDef),
+                Some(Param),
+                Some(InterfaceImpl),
+                Some(MemberRef),
+                Some(Module),
+                Some(DeclSecurity),
+                Some(Property),
+                Some(Event),
+                Some(StandAloneSig),
+                Some(ModuleRef),
+                Some(TypeSpec),
+                Some(Assembly),
+                Some(AssemblyRef),
+                Some(File),
+                Some(ExportedType),
+                Some(ManifestResource),
+                Some(GenericParam),
+                Some(GenericParamConstraint),
+                Some(MethodSpec),
+            ],
+            CodedIndex::HasFieldMarshal => &[Some(Field), Some(Param)],
+            CodedIndex::HasDeclSecurity => &[Some(TypeDef), Some(MethodDef), Some(Assembly)],
+            CodedIndex::MemberRefParent => &[
+                Some(TypeDef),
+                Some(TypeRef),
+                Some(ModuleRef),
+                Some(MethodDef),
+                Some(TypeSpec),
+            ],
+            CodedIndex::HasSemantics => &[Some(Event), Some(Property)],
+            CodedIndex::MethodDefOrRef => &[Some(MethodDef), Some(MemberRef)],
+            CodedIndex::MemberForwarded => &[Some(Field), Some(MethodDef)],
+            CodedIndex::Implementation => &[Some(File), Some(AssemblyRef), Some(ExportedType)],
+            CodedIndex::CustomAttributeType => {
+                &[None, None, Some(MethodDef), Some(MemberRef), None]
+            }
+            CodedIndex::ResolutionScope => &[
+                Some(Module),
+                Some(ModuleRef),
+                Some(AssemblyRef),
+                Some(TypeRef),
+            ],
+            CodedIndex::TypeOrMethodDef => &[Some(TypeDef), Some(MethodDef)],
+        }
+    }
+
+    /// How many low bits hold the table
+    fn tag_bits(self) -> u32 {
+        usize::BITS - (self.tables().len() - 1).leading_zeros()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    U16,
+    U32,
+    String,
+    Guid,
+    Blob,
+    Index(Table),
+    Coded(CodedIndex),
+}
+
+/// The columns of every table
+const SCHEMAS: [&[Column]; TABLE_COUNT] = {
+    use CodedIndex::*;
+    use Column::*;
+    [
+        // Module
+        &[U16, String, Guid, Guid, Guid],
+        // TypeRef
+        &[Coded(ResolutionScope), String, String],
+        // TypeDef
+        &[
+            U32,
+            String,
+            String,
+            Coded(TypeDefOrRef),
+            Index(Table::Field),
+            Index(Table::MethodDef),
+        ],
+        // FieldPtr
+        &[Index(Table::Field)],
+        // Field
+        &[U16, String, Blob],
+        // MethodPtr
+        &[Index(Table::MethodDef)],
+        // MethodDef
+        &[U32, U16, U16, String, Blob, Index(Table::Param)],
+        // ParamPtr
+        &[Index(Table::Param)],
+        // Param
+        &[U16, U16, String],
+        // InterfaceImpl
+        &[Index(Table::TypeDef), Coded(TypeDefOrRef)],
+        // MemberRef
+        &[Coded(MemberRefParent), String, Blob],
+        // Constant, whose type is a byte and a padding byte
+        &[U16, Coded(HasConstant), Blob],
+        // CustomAttribute
+        &[Coded(HasCustomAttribute), Coded(CustomAttributeType), Blob],
+        // FieldMarshal
+        &[Coded(HasFieldMarshal), Blob],
+        // DeclSecurity
+        &[U16, Coded(HasDeclSecurity), Blob],
+        // ClassLayout
+        &[U16, U32, Index(Table::TypeDef)],
+        // FieldLayout
+        &[U32, Index(Table::Field)],
+        // StandAloneSig
+        &[Blob],
+        // EventMap
+        &[Index(Table::TypeDef), Index(Table::Event)],
+        // EventPtr
+        &[Index(Table::Event)],
+        // Event
+        &[U16, String, Coded(TypeDefOrRef)],
+        // PropertyMap
+        &[Index(Table::TypeDef), Index(Table::Property)],
+        // PropertyPtr
+        &[Index(Table::Property)],
+        // Property
+        &[U16, String, Blob],
+        // MethodSemantics
+        &[U16, Index(Table::MethodDef), Coded(HasSemantics)],
+        // MethodImpl
+        &[
+            Index(Table::TypeDef),
+            Coded(MethodDefOrRef),
+            Coded(MethodDefOrRef),
+        ],
+        // ModuleRef
+        &[String],
+        // TypeSpec
+        &[Blob],
+        // ImplMap
+        &[U16, Coded(MemberForwarded), String, Index(Table::ModuleRef)],
+        // FieldRVA
+        &[U32, Index(Table::Field)],
+        // EncLog
+        &[U32, U32],
+        // EncMap
+        &[U32],
+        // Assembly
+        &[U32, U16, U16, U16, U16, U32, Blob, String, String],
+        // AssemblyProcessor
+        &[U32],
+        // AssemblyOS
+        &[U32, U32, U32],
+        // AssemblyRef
+        &[U16, U16, U16, U16, U32, Blob, String, String, Blob],
+        // AssemblyRefProcessor
+        &[U32, Index(Table::AssemblyRef)],
+        // AssemblyRefOS
+        &[U32, U32, U32, Index(Table::AssemblyRef)],
+        // File
+        &[U32, String, Blob],
+        // ExportedType
+        &[U32, U32, String, String, Coded(Implementation)],
+        // ManifestResource
+        &[U32, U32, String, Coded(Implementation)],
+        // NestedClass
+        &[Index(Table::TypeDef), Index(Table::TypeDef)],
+        // GenericParam
+        &[U16, U16, Coded(TypeOrMethodDef), String],
+        // MethodSpec
+        &[Coded(MethodDefOrRef), Blob],
+        // GenericParamConstraint
+        &[Index(Table::GenericParam), Coded(TypeDefOrRef)],
+    ]
+};
+
+/// A row of a table, which makes up a metadata token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub table: Table,
+    /// The row, counting from 1
+    pub row: u32,
+}
+
+impl Token {
+    /// The token as IL refers to it, with the table in the top byte
+    pub fn value(&self) -> u32 {
+        (self.table as u32) << 24 | self.row
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#010x}", self.value())
+    }
+}
+
+/// The four parts of an assembly version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Version {
+    pub major: u16,
+    pub minor: u16,
+    pub build: u16,
+    pub revision: u16,
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.build, self.revision
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct Module {
+    pub generation: u16,
+    pub name: String,
+    pub mvid: Option<[u8; 16]>,
+}
+
+#[derive(Debug)]
+pub struct TypeRef {
+    pub resolution_scope: Option<Token>,
+    pub name: String,
+    pub namespace: String,
+}
+
+#[derive(Debug)]
+pub struct TypeDef {
+    pub flags: u32,
+    pub name: String,
+    pub namespace: String,
+    pub extends: Option<Token>,
+    /// The first row of the fields of the type, which run up to those of the next type
+    pub field_list: u32,
+    /// The first row of the methods of the type, which run up to those of the next type
+    pub method_list: u32,
+}
+
+#[derive(Debug)]
+pub struct Field {
+    pub flags: u16,
+    pub name: String,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct MethodDef {
+    pub rva: u32,
+    pub impl_flags: u16,
+    pub flags: u16,
+    pub name: String,
+    pub signature: Vec<u8>,
+    pub param_list: u32,
+}
+
+#[derive(Debug)]
+pub struct MemberRef {
+    pub class: Option<Token>,
+    pub name: String,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct CustomAttribute {
+    pub parent: Option<Token>,
+    /// The constructor of the attribute, a `MethodDef` or `MemberRef`
+    pub constructor: Option<Token>,
+    pub value: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct Assembly {
+    pub hash_algorithm: u32,
+    pub version: Version,
+    pub flags: u32,
+    pub public_key: Vec<u8>,
+    pub name: String,
+    pub culture: String,
+}
+
+#[derive(Debug)]
+pub struct AssemblyRef {
+    pub version: Version,
+    pub flags: u32,
+    /// The public key, or its token when the `PublicKey` flag is clear
+    pub public_key_or_token: Vec<u8>,
+    pub name: String,
+    pub culture: String,
+    pub hash_value: Vec<u8>,
+}
+
+/// The decoded `#~` stream
+#[derive(Debug)]
+pub struct Tables {
+    pub major_version: u8,
+    pub minor_version: u8,
+    pub heap_sizes: u8,
+    /// Which tables are present, by bit
+    pub valid: u64,
+    /// Which tables are sorted, by bit
+    pub sorted: u64,
+    pub row_counts: Vec<(Table, u32)>,
+    pub module: Option<Module>,
+    pub type_refs: Vec<TypeRef>,
+    pub type_defs: Vec<TypeDef>,
+    pub fields: Vec<Field>,
+    pub method_defs: Vec<MethodDef>,
+    pub member_refs: Vec<MemberRef>,
+    pub custom_attributes: Vec<CustomAttribute>,
+    pub assembly: Option<Assembly>,
+    pub assembly_refs: Vec<AssemblyRef>,
+}
+
+/// Where the rows of the tables are and how to read their columns
+struct Layout<'a> {
+    data: &'a [u8],
+    heap_sizes: u8,
+    rows: [u32; TABLE_COUNT],
+    offsets: [usize; TABLE_COUNT],
+    row_sizes: [usize; TABLE_COUNT],
+}
+
+impl<'a> Layout<'a> {
+    fn column_size(&self, column: Column) -> usize {
+        let wide = match column {
+            Column::U16 => false,
+            Column::U32 => true,
+            Column::String => self.heap_sizes & 0x01 != 0,
+            Column::Guid => self.heap_sizes & 0x02 != 0,
+            Column::Blob => self.heap_sizes & 0x04 != 0,
+            Column::Index(table) => self.rows[table as usize] > 0xffff,
+            Column::Coded(index) => {
+                let limit = 1u32 << (16 - index.tag_bits());
+                index
+                    .tables()
+                    .iter()
+                    .flatten()
+                    .any(|&table| self.rows[table as usize] >= limit)
+            }
+        };
+        if wide {
+            4
+        } else {
+            2
+        }
+    }
+
+    /// The columns of every row of a table
+    fn rows(&self, table: Table) -> parse::ParseResult<'a, Vec<Vec<u32>>> {
+        let schema = SCHEMAS[table as usize];
+        let start = self.offsets[table as usize];
+        let size = self.row_sizes[table as usize] * self.rows[table as usize] as usize;
+        let input = self.data.get(start..).unwrap_or_default();
+        let (rest, input) = context("Parse Metadata Table", take(size))(input)?;
+        let rows = input
+            .chunks_exact(self.row_sizes[table as usize].max(1))
+            .map(|mut row| {
+                schema
+                    .iter()
+                    .map(|&column| {
+                        let (value, rest) = match self.column_size(column) {
+                            2 => (u16::from_le_bytes([row[0], row[1]]) as u32, &row[2..]),
+                            _ => (
+                                u32::from_le_bytes([row[0], row[1], row[2], row[3]]),
+                                &row[4..],
+                            ),
+                        };
+                        row = rest;
+                        value
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok((rest, rows))
+    }
+}
+
+/// Splits a coded index into its table and row, unless it's null
+fn coded(index: CodedIndex, value: u32) -> Option<Token> {
+    let bits = index.tag_bits();
+    let table = (*index.tables().get((value & ((1 << bits) - 1)) as usize)?)?;
+    let row = value >> bits;
+    (row != 0).then_some(Token { table, row })
+}
+
+fn version(row: &[u32]) -> Version {
+    Version {
+        major: row[0] as u16,
+        minor: row[1] as u16,
+        build: row[2] as u16,
+        revision: row[3] as u16,
+    }
+}
+
+impl Tables {
+    pub(super) fn parse(input: parse::Input, heaps: &Heaps) -> BinDumpResult<Self> {
+        let (input, (_, major_version, minor_version, heap_sizes, _, valid, sorted)) =
+            context(
+                "Parse Metadata Tables Header",
+                tuple((le_u32, byte, byte, byte, byte, le_u64, le_u64)),
+            )(input)?;
+        if valid >> TABLE_COUNT != 0 {
+            return Err(BinDumpError::Unsupported {
+                format: format!("metadata tables {:#x}", valid & !((1 << TABLE_COUNT) - 1)),
+            });
+        }
+        let (input, counts) = context(
+            "Parse Metadata Row Counts",
+            count(le_u32, valid.count_ones() as usize),
+        )(input)?;
+        // Uncompressed streams can have an extra word after the row counts
+        let input = match heap_sizes & 0x40 {
+            0 => input,
+            _ => input
+                .get(4..)
+                .ok_or_else(|| failure(input, "Missing Extra Data"))?,
+        };
+
+        let mut layout = Layout {
+            data: input,
+            heap_sizes,
+            rows: [0; TABLE_COUNT],
+            offsets: [0; TABLE_COUNT],
+            row_sizes: [0; TABLE_COUNT],
+        };
+        let present = (0..TABLE_COUNT).filter(|i| valid & 1 << i != 0);
+        let mut row_counts = Vec::new();
+        for (i, rows) in present.zip(counts) {
+            layout.rows[i] = rows;
+            row_counts.push((TABLES[i], rows));
+        }
+        let mut offset = 0usize;
+        for (i, schema) in SCHEMAS.iter().enumerate() {
+            let size = schema
+                .iter()
+                .map(|&column| layout.column_size(column))
+                .sum::<usize>();
+            layout.offsets[i] = offset;
+            layout.row_sizes[i] = size;
+            offset = offset.saturating_add(size.saturating_mul(layout.rows[i] as usize));
+        }
+
+        let string = |index: u32| heaps.string(index);
+        let blob = |index: u32| heaps.blob(index);
+        let (_, modules) = layout.rows(Table::Module)?;
+        let (_, type_refs) = layout.rows(Table::TypeRef)?;
+        let (_, type_defs) = layout.rows(Table::TypeDef)?;
+        let (_, fields) = layout.rows(Table::Field)?;
+        let (_, method_defs) = layout.rows(Table::MethodDef)?;
+        let (_, member_refs) = layout.rows(Table::MemberRef)?;
+        let (_, custom_attributes) = layout.rows(Table::CustomAttribute)?;
+        let (_, assemblies) = layout.rows(Table::Assembly)?;
+        let (_, assembly_refs) = layout.rows(Table::AssemblyRef)?;
+        Ok(Self {
+            major_version,
+            minor_version,
+            heap_sizes,
+            valid,
+            sorted,
+            row_counts,
+            module: modules.first().map(|row| Module {
+                generation: row[0] as u16,
+                name: string(row[1]),
+                mvid: heaps.guid(row[2]),
+            }),
+            type_refs: type_refs
+                .iter()
+                .map(|row| TypeRef {
+                    resolution_scope: coded(CodedIndex::ResolutionScope, row[0]),
+                    name: string(row[1]),
+                    namespace: string(row[2]),
+                })
+                .collect(),
+            type_defs: type_defs
+                .iter()
+                .map(|row| TypeDef {
+                    flags: row[0],
+                    name: string(row[1]),
+                    namespace: string(row[2]),
+                    extends: coded(CodedIndex::TypeDefOrRef, row[3]),
+                    field_list: row[4],
+                    method_list: row[5],
+                })
+                .collect(),
+            fields: fields
+                .iter()
+                .map(|row| Field {
+                    flags: row[0] as u16,
+                    name: string(row[1]),
+                    signature: blob(row[2]),
+                })
+                .collect(),
+            method_defs: method_defs
+                .iter()
+                .map(|row| MethodDef {
+                    rva: row[0],
+                    impl_flags: row[1] as u16,
+                    flags: row[2] as u16,
+                    name: string(row[3]),
+                    signature: blob(row[4]),
+                    param_list: row[5],
+                })
+                .collect(),
+            member_refs: member_refs
+                .iter()
+                .map(|row| MemberRef {
+                    class: coded(CodedIndex::MemberRefParent, row[0]),
+                    name: string(row[1]),
+                    signature: blob(row[2]),
+                })
+                .collect(),
+            custom_attributes: custom_attributes
+                .iter()
+                .map(|row| CustomAttribute {
+                    parent: coded(CodedIndex::HasCustomAttribute, row[0]),
+                    constructor: coded(CodedIndex::CustomAttributeType, row[1]),
+                    value: blob(row[2]),
+                })
+                .collect(),
+            assembly: assemblies.first().map(|row| Assembly {
+                hash_algorithm: row[0],
+                version: version(&row[1..5]),
+                flags: row[5],
+                public_key: blob(row[6]),
+                name: string(row[7]),
+                culture: string(row[8]),
+            }),
+            assembly_refs: assembly_refs
+                .iter()
+                .map(|row| AssemblyRef {
+                    version: version(&row[..4]),
+                    flags: row[4],
+                    public_key_or_token: blob(row[5]),
+                    name: string(row[6]),
+                    culture: string(row[7]),
+                    hash_value: blob(row[8]),
+                })
+                .collect(),
+        })
+    }
+
+    /// The full name of a `TypeDef` or `TypeRef`
+    pub fn type_name(&self, token: Token) -> Option<String> {
+        let (namespace, name) = match token.table {
+            Table::TypeDef => {
+                let typ = self.type_defs.get(token.row.checked_sub(1)? as usize)?;
+                (&typ.namespace, &typ.name)
+            }
+            Table::TypeRef => {
+                let typ = self.type_refs.get(token.row.checked_sub(1)? as usize)?;
+                (&typ.namespace, &typ.name)
+            }
+            _ => return None,
+        };
+        Some(match namespace.is_empty() {
+            true => name.clone(),
+            false => format!("{}.{}", namespace, name),
+        })
+    }
+
+    /// The name of a `MethodDef` or `MemberRef`, with the type it belongs to
+    pub fn member_name(&self, token: Token) -> Option<String> {
+        match token.table {
+            Table::MethodDef => {
+                let method = self.method_defs.get(token.row.checked_sub(1)? as usize)?;
+                // The type with the last method list at or before the method owns it
+                let owner = self
+                    .type_defs
+                    .iter()
+                    .rposition(|typ| typ.method_list <= token.row)
+                    .and_then(|i| {
+                        self.type_name(Token {
+                            table: Table::TypeDef,
+                            row: i as u32 + 1,
+                        })
+                    });
+                Some(match owner {
+                    Some(owner) => format!("{}::{}", owner, method.name),
+                    None => method.name.clone(),
+                })
+            }
+            Table::MemberRef => {
+                let member = self.member_refs.get(token.row.checked_sub(1)? as usize)?;
+                let class = member.class.and_then(|class| self.type_name(class));
+                Some(match class {
+                    Some(class) => format!("{}::{}", class, member.name),
+                    None => member.name.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The rows of a list that belong to a type, given the list column of every type
+    fn type_range(&self, index: usize, list: fn(&TypeDef) -> u32, len: usize) -> (usize, usize) {
+        let start = self.type_defs.get(index).map_or(0, list) as usize;
+        let end = self
+            .type_defs
+            .get(index + 1)
+            .map_or(len + 1, |typ| list(typ) as usize);
+        let start = start.clamp(1, len + 1);
+        (start - 1, end.clamp(start, len + 1) - 1)
+    }
+
+    /// The fields of the type at an index of `type_defs`
+    pub fn type_fields(&self, index: usize) -> &[Field] {
+        let (start, end) = self.type_range(index, |typ| typ.field_list, self.fields.len());
+        &self.fields[start..end]
+    }
+
+    /// The methods of the type at an index of `type_defs`
+    pub fn type_methods(&self, index: usize) -> &[MethodDef] {
+        let (start, end) = self.type_range(index, |typ| typ.method_list, self.method_defs.len());
+        &self.method_defs[start..end]
+    }
+}
@@ -0,0 +1,605 @@
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+
+use super::header::{flag_names, DirectoryEntry};
+use super::Pe;
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse::{self, failure, hex, string_at};
+
+const RSDS_SIGNATURE: &[u8] = b"RSDS";
+const NB10_SIGNATURE: &[u8] = b"NB10";
+const MPDB_SIGNATURE: &[u8] = b"MPDB";
+/// The minor version of the CodeView entries of images with a portable PDB
+const PORTABLE_PDB_MINOR_VERSION: u16 = 0x504d;
+
+const EX_DLL_CHARACTERISTICS: [(u32, &str); 8] = [
+    (0x01, "CET_COMPAT"),
+    (0x02, "CET_COMPAT_STRICT_MODE"),
+    (0x04, "CET_SET_CONTEXT_IP_VALIDATION_RELAXED_MODE"),
+    (0x08, "CET_DYNAMIC_APIS_ALLOW_IN_PROC"),
+    (0x10, "CET_RESERVED_1"),
+    (0x20, "CET_RESERVED_2"),
+    (0x40, "FORWARD_CFI_COMPAT"),
+    (0x80, "HOTPATCH_COMPATIBLE"),
+];
+
+// From winnt.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugType {
+    Unknown,
+    Coff,
+    CodeView,
+    Fpo,
+    Misc,
+    Exception,
+    Fixup,
+    OmapToSrc,
+    OmapFromSrc,
+    Borland,
+    Reserved10,
+    Clsid,
+    VcFeature,
+    Pogo,
+    Iltcg,
+    Mpx,
+    Repro,
+    EmbeddedPortablePdb,
+    Spgo,
+    PdbChecksum,
+    ExDllCharacteristics,
+    Other(u32),
+}
+
+impl From<u32> for DebugType {
+    fn from(typ: u32) -> Self {
+        match typ {
+            0 => DebugType::Unknown,
+            1 => DebugType::Coff,
+            2 => DebugType::CodeView,
+            3 => DebugType::Fpo,
+            4 => DebugType::Misc,
+            5 => DebugType::Exception,
+            6 => DebugType::Fixup,
+            7 => DebugType::OmapToSrc,
+            8 => DebugType::OmapFromSrc,
+            9 => DebugType::Borland,
+            10 => DebugType::Reserved10,
+            11 => DebugType::Clsid,
+            12 => DebugType::VcFeature,
+            13 => DebugType::Pogo,
+            14 => DebugType::Iltcg,
+            15 => DebugType::Mpx,
+            16 => DebugType::Repro,
+            17 => DebugType::EmbeddedPortablePdb,
+            18 => DebugType::Spgo,
+            19 => DebugType::PdbChecksum,
+            20 => DebugType::ExDllCharacteristics,
+            typ => DebugType::Other(typ),
+        }
+    }
+}
+
+impl fmt::Display for DebugType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DebugType::Other(typ) => write!(f, "Unknown ({:#x})", typ),
+            typ => write!(f, "{:?}", typ),
+        }
+    }
+}
+
+/// Formats a GUID the way Windows does, with the first three fields stored little endian
+fn guid_string(guid: &[u8; 16], separator: &str) -> String {
+    let mut string = format!(
+        "{:08X}{sep}{:04X}{sep}{:04X}{sep}",
+        u32::from_le_bytes([guid[0], guid[1], guid[2], guid[3]]),
+        u16::from_le_bytes([guid[4], guid[5]]),
+        u16::from_le_bytes([guid[6], guid[7]]),
+        sep = separator
+    );
+    for (i, byte) in guid[8..].iter().enumerate() {
+        if i == 2 {
+            string.push_str(separator);
+        }
+        string.push_str(&format!("{:02X}", byte));
+    }
+    string
+}
+
+/// A CodeView record, which names the PDB file with the debug information of the image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeView {
+    /// `RSDS`, for PDB 7.0 files and portable PDBs
+    Pdb70 {
+        guid: [u8; 16],
+        age: u32,
+        path: String,
+    },
+    /// `NB10`, for PDB 2.0 files
+    Pdb20 {
+        signature: u32,
+        age: u32,
+        path: String,
+    },
+}
+
+impl CodeView {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (input, signature) = context("Parse CodeView Signature", take(4usize))(input)?;
+        match signature {
+            RSDS_SIGNATURE => {
+                let (input, (guid, age)) =
+                    context("Parse PDB 7.0 Info", tuple((take(16usize), le_u32)))(input)?;
+                Ok((
+                    &[],
+                    CodeView::Pdb70 {
+                        guid: guid.try_into().unwrap(),
+                        age,
+                        path: string_at(input, 0),
+                    },
+                ))
+            }
+            NB10_SIGNATURE => {
+                let (input, (_, signature, age)) =
+                    context("Parse PDB 2.0 Info", tuple((le_u32, le_u32, le_u32)))(input)?;
+                Ok((
+                    &[],
+                    CodeView::Pdb20 {
+                        signature,
+                        age,
+                        path: string_at(input, 0),
+                    },
+                ))
+            }
+            _ => Err(failure(signature, "Unknown CodeView Signature")),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            CodeView::Pdb70 { path, .. } | CodeView::Pdb20 { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for CodeView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeView::Pdb70 { guid, age, path } => write!(
+                f,
+                "PDB 7.0: GUID {}, Age {}, Path {}",
+                guid_string(guid, "-"),
+                age,
+                path
+            ),
+            CodeView::Pdb20 {
+                signature,
+                age,
+                path,
+            } => write!(
+                f,
+                "PDB 2.0: Signature {:#x}, Age {}, Path {}",
+                signature, age, path
+            ),
+        }
+    }
+}
+
+/// A part of the image that profile guided optimization laid out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PogoEntry {
+    pub rva: u32,
+    pub size: u32,
+    pub name: String,
+}
+
+/// The layout that profile guided optimization or link time code generation chose
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pogo {
+    /// Like `PGU` or `LTCG`, stored as a little endian word
+    pub signature: u32,
+    pub entries: Vec<PogoEntry>,
+}
+
+impl Pogo {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let start = input;
+        let (mut input, signature) = context("Parse POGO Signature", le_u32)(input)?;
+        let mut entries = Vec::new();
+        while input.len() >= 8 {
+            let (rest, (rva, size)) = context("Parse POGO Entry", tuple((le_u32, le_u32)))(input)?;
+            // The names are null terminated and padded to 4 bytes
+            let length = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+            let end = (start.len() - rest.len() + length + 1 + 3) & !3;
+            input = &start[end.min(start.len())..];
+            entries.push(PogoEntry {
+                rva,
+                size,
+                name: string_at(rest, 0),
+            });
+        }
+        Ok((input, Self { signature, entries }))
+    }
+
+    /// The signature as text, without the null that pads three letter signatures
+    pub fn signature_string(&self) -> String {
+        let bytes: Vec<u8> = self
+            .signature
+            .to_be_bytes()
+            .into_iter()
+            .filter(|&b| b != 0)
+            .collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+/// How many object files the linker saw from each compiler generation and with each
+/// security feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VcFeature {
+    pub pre_vc11: u32,
+    pub c_cpp: u32,
+    /// Compiled with `/GS`
+    pub gs: u32,
+    /// Compiled with `/sdl`
+    pub sdl: u32,
+    pub guard_n: u32,
+}
+
+/// The decoded data of a debug directory entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebugContents {
+    CodeView(CodeView),
+    Pogo(Pogo),
+    VcFeature(VcFeature),
+    /// The hash of a deterministic build, which the time stamps of the image hold instead of a
+    /// time
+    Repro(Vec<u8>),
+    ExDllCharacteristics(u32),
+    /// A portable PDB, deflate compressed
+    EmbeddedPortablePdb {
+        uncompressed_size: u32,
+        compressed: Vec<u8>,
+    },
+    /// The hash of the PDB file, by an algorithm like `SHA256`
+    PdbChecksum {
+        algorithm: String,
+        checksum: Vec<u8>,
+    },
+    /// Data of a type that isn't decoded
+    Other,
+}
+
+impl DebugContents {
+    fn parse(typ: DebugType, input: parse::Input) -> parse::ParseResult<Self> {
+        match typ {
+            DebugType::CodeView => {
+                let (input, codeview) = CodeView::parse(input)?;
+                Ok((input, DebugContents::CodeView(codeview)))
+            }
+            DebugType::Pogo => {
+                let (input, pogo) = Pogo::parse(input)?;
+                Ok((input, DebugContents::Pogo(pogo)))
+            }
+            DebugType::VcFeature => {
+                let (input, (pre_vc11, c_cpp, gs, sdl, guard_n)) = context(
+                    "Parse VC Feature",
+                    tuple((le_u32, le_u32, le_u32, le_u32, le_u32)),
+                )(input)?;
+                Ok((
+                    input,
+                    DebugContents::VcFeature(VcFeature {
+                        pre_vc11,
+                        c_cpp,
+                        gs,
+                        sdl,
+                        guard_n,
+                    }),
+                ))
+            }
+            // Images that use the hash of the build for their time stamps may leave it out
+            DebugType::Repro if input.is_empty() => Ok((input, DebugContents::Repro(Vec::new()))),
+            DebugType::Repro => {
+                let (input, length) = context("Parse Repro Hash Length", le_u32)(input)?;
+                let (input, hash) = context("Parse Repro Hash", take(length))(input)?;
+                Ok((input, DebugContents::Repro(hash.to_vec())))
+            }
+            DebugType::ExDllCharacteristics => {
+                let (input, characteristics) =
+                    context("Parse Extended DLL Characteristics", le_u32)(input)?;
+                Ok((input, DebugContents::ExDllCharacteristics(characteristics)))
+            }
+            DebugType::EmbeddedPortablePdb => {
+                let (input, (signature, uncompressed_size)) =
+                    context("Parse Embedded Portable PDB", tuple((take(4usize), le_u32)))(input)?;
+                if signature != MPDB_SIGNATURE {
+                    return Err(failure(
+                        signature,
+                        "Unknown Embedded Portable PDB Signature",
+                    ));
+                }
+                Ok((
+                    &[],
+                    DebugContents::EmbeddedPortablePdb {
+                        uncompressed_size,
+                        compressed: input.to_vec(),
+                    },
+                ))
+            }
+            DebugType::PdbChecksum => {
+                let algorithm = string_at(input, 0);
+                let checksum = input.get(algorithm.len() + 1..).unwrap_or_default();
+                Ok((
+                    &[],
+                    DebugContents::PdbChecksum {
+                        algorithm,
+                        checksum: checksum.to_vec(),
+                    },
+                ))
+            }
+            _ => Ok((input, DebugContents::Other)),
+        }
+    }
+}
+
+impl fmt::Display for DebugContents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DebugContents::CodeView(codeview) => writeln!(f, "{}", codeview),
+            DebugContents::Pogo(pogo) => {
+                writeln!(f, "POGO {}:", pogo.signature_string())?;
+                for entry in &pogo.entries {
+                    writeln!(
+                        f,
+                        "  {:#x} ({} bytes): {}",
+                        entry.rva, entry.size, entry.name
+                    )?;
+                }
+                Ok(())
+            }
+            DebugContents::VcFeature(feature) => writeln!(
+                f,
+                "VC Feature: Pre-VC++ 11.00 {}, C/C++ {}, /GS {}, /sdl {}, guardN {}",
+                feature.pre_vc11, feature.c_cpp, feature.gs, feature.sdl, feature.guard_n
+            ),
+            DebugContents::Repro(hash) if hash.is_empty() => writeln!(f, "Repro Hash: none"),
+            DebugContents::Repro(hash) => writeln!(f, "Repro Hash: {}", hex(hash)),
+            DebugContents::ExDllCharacteristics(characteristics) => writeln!(
+                f,
+                "Extended DLL Characteristics: {:#x} ({})",
+                characteristics,
+                flag_names(*characteristics, &EX_DLL_CHARACTERISTICS).join(", ")
+            ),
+            DebugContents::EmbeddedPortablePdb {
+                uncompressed_size,
+                compressed,
+            } => writeln!(
+                f,
+                "Embedded Portable PDB: {} bytes, {} compressed",
+                uncompressed_size,
+                compressed.len()
+            ),
+            DebugContents::PdbChecksum {
+                algorithm,
+                checksum,
+            } => writeln!(f, "PDB Checksum: {} {}", algorithm, hex(checksum)),
+            DebugContents::Other => Ok(()),
+        }
+    }
+}
+
+/// An `IMAGE_DEBUG_DIRECTORY` entry
+#[derive(Debug)]
+pub struct DebugEntry {
+    pub characteristics: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub typ: DebugType,
+    pub size_of_data: u32,
+    /// The RVA of the data, or 0 if it isn't loaded
+    pub address_of_raw_data: u32,
+    /// The file offset of the data
+    pub pointer_to_raw_data: u32,
+    pub contents: BinDumpResult<DebugContents>,
+}
+
+impl DebugEntry {
+    /// The key symbol servers store the PDB of this CodeView entry under
+    pub fn pdb_identifier(&self) -> Option<String> {
+        let DebugContents::CodeView(codeview) = self.contents.as_ref().ok()? else {
+            return None;
+        };
+        Some(match codeview {
+            // Portable PDBs don't have an age, and go by their GUID alone
+            CodeView::Pdb70 { guid, .. } if self.minor_version == PORTABLE_PDB_MINOR_VERSION => {
+                format!("{}FFFFFFFF", guid_string(guid, ""))
+            }
+            CodeView::Pdb70 { guid, age, .. } => format!("{}{:X}", guid_string(guid, ""), age),
+            CodeView::Pdb20 { signature, age, .. } => format!("{:08X}{:X}", signature, age),
+        })
+    }
+}
+
+impl fmt::Display for DebugEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Type: {}", self.typ)?;
+        writeln!(f, "Characteristics: {:#x}", self.characteristics)?;
+        writeln!(f, "Time Date Stamp: {:#x}", self.time_date_stamp)?;
+        writeln!(f, "Version: {}.{}", self.major_version, self.minor_version)?;
+        writeln!(f, "Size Of Data: {:#x}", self.size_of_data)?;
+        writeln!(f, "Address Of Raw Data: {:#x}", self.address_of_raw_data)?;
+        writeln!(f, "Pointer To Raw Data: {:#x}", self.pointer_to_raw_data)?;
+        match &self.contents {
+            Ok(contents) => write!(f, "{}", contents),
+            Err(e) => writeln!(f, "Error {}", e),
+        }
+    }
+}
+
+impl Pe {
+    pub(super) fn parse_debug_directory(&self) -> Option<BinDumpResult<Vec<DebugEntry>>> {
+        let input = self.directory_data(DirectoryEntry::Debug)?;
+        Some(self.parse_debug_entries(input))
+    }
+
+    fn parse_debug_entries(&self, input: parse::Input) -> BinDumpResult<Vec<DebugEntry>> {
+        let (_, entries) = context(
+            "Parse Debug Directory",
+            count(
+                tuple((
+                    le_u32, le_u32, le_u16, le_u16, le_u32, le_u32, le_u32, le_u32,
+                )),
+                input.len() / 28,
+            ),
+        )(input)?;
+        Ok(entries
+            .into_iter()
+            .map(
+                |(
+                    characteristics,
+                    time_date_stamp,
+                    major_version,
+                    minor_version,
+                    typ,
+                    size_of_data,
+                    address_of_raw_data,
+                    pointer_to_raw_data,
+                )| {
+                    let typ = DebugType::from(typ);
+                    let contents = self
+                        .debug_data(pointer_to_raw_data, address_of_raw_data, size_of_data)
+                        .and_then(|data| Ok(DebugContents::parse(typ, data)?.1));
+                    DebugEntry {
+                        characteristics,
+                        time_date_stamp,
+                        major_version,
+                        minor_version,
+                        typ,
+                        size_of_data,
+                        address_of_raw_data,
+                        pointer_to_raw_data,
+                        contents,
+                    }
+                },
+            )
+            .collect())
+    }
+
+    /// The data of a debug entry, which needn't be loaded and so is found by its file offset
+    fn debug_data(&self, offset: u32, rva: u32, size: u32) -> BinDumpResult<&[u8]> {
+        let data = match offset {
+            0 => self.data_at_rva(rva),
+            offset => self.data.get(offset as usize..),
+        };
+        data.and_then(|data| data.get(..size as usize))
+            .ok_or_else(|| BinDumpError::ParseError {
+                error: format!("Debug data at {:#x} is past the end of the file", offset),
+            })
+    }
+
+    pub fn debug_entries(&self) -> &[DebugEntry] {
+        match &self.debug {
+            Some(Ok(entries)) => entries,
+            _ => &[],
+        }
+    }
+
+    /// The CodeView record, which names the PDB file
+    pub fn codeview(&self) -> Option<&CodeView> {
+        self.debug_entries()
+            .iter()
+            .find_map(|entry| match &entry.contents {
+                Ok(DebugContents::CodeView(codeview)) => Some(codeview),
+                _ => None,
+            })
+    }
+
+    /// The GUID and age of the PDB file as one string, as symbol servers index it
+    pub fn pdb_identifier(&self) -> Option<String> {
+        self.debug_entries()
+            .iter()
+            .find_map(DebugEntry::pdb_identifier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::image;
+    use super::*;
+
+    /// Debug entries for the records at 0x160 and 0x190 of the test image
+    fn debug_entries(records: [(u16, &[u8]); 2]) -> Vec<DebugEntry> {
+        let mut data = image();
+        let mut directory = Vec::new();
+        for ((minor_version, record), offset) in records.into_iter().zip([0x160u32, 0x190]) {
+            data.resize(offset as usize, 0);
+            data.extend_from_slice(record);
+            directory.extend_from_slice(&[0; 10]);
+            directory.extend_from_slice(&minor_version.to_le_bytes());
+            for field in [2, record.len() as u32, 0, offset] {
+                directory.extend_from_slice(&field.to_le_bytes());
+            }
+        }
+        let pe = Pe::load(data).unwrap();
+        pe.parse_debug_entries(&directory).unwrap()
+    }
+
+    fn rsds() -> Vec<u8> {
+        let mut record = RSDS_SIGNATURE.to_vec();
+        record.extend(0..16);
+        record.extend_from_slice(&0x1au32.to_le_bytes());
+        record.extend_from_slice(b"test.pdb\0");
+        record
+    }
+
+    fn nb10() -> Vec<u8> {
+        let mut record = NB10_SIGNATURE.to_vec();
+        for field in [0, 0x5f3e_1a2b, 3] {
+            record.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        record.extend_from_slice(b"old.pdb\0");
+        record
+    }
+
+    #[test]
+    fn formats_pdb_identifiers() {
+        let entries = debug_entries([(0, &rsds()), (0, &nb10())]);
+        assert_eq!(
+            entries[0].pdb_identifier().as_deref(),
+            Some("030201000504070608090A0B0C0D0E0F1A")
+        );
+        assert_eq!(entries[1].pdb_identifier().as_deref(), Some("5F3E1A2B3"));
+        match &entries[0].contents {
+            Ok(DebugContents::CodeView(codeview)) => assert_eq!(
+                codeview.to_string(),
+                "PDB 7.0: GUID 03020100-0504-0706-0809-0A0B0C0D0E0F, Age 26, Path test.pdb"
+            ),
+            contents => panic!("{:?} isn't a CodeView record", contents),
+        }
+    }
+
+    #[test]
+    fn portable_pdbs_have_no_age() {
+        let entries = debug_entries([(PORTABLE_PDB_MINOR_VERSION, &rsds()), (0, &nb10())]);
+        assert_eq!(
+            entries[0].pdb_identifier().as_deref(),
+            Some("030201000504070608090A0B0C0D0E0FFFFFFFFF")
+        );
+    }
+
+    #[test]
+    fn broken_records_have_no_identifier() {
+        let mut unknown = rsds();
+        unknown[..4].copy_from_slice(MPDB_SIGNATURE);
+        let entries = debug_entries([(0, &unknown), (0, &rsds()[..12])]);
+        assert!(entries[0].contents.is_err());
+        assert_eq!(entries[0].pdb_identifier(), None);
+        assert_eq!(entries[1].pdb_identifier(), None);
+    }
+}
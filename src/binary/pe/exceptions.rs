@@ -0,0 +1,298 @@
+pub mod arm64;
+pub mod x64;
+
+use std::fmt;
+
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::le_u32;
+use nom::sequence::tuple;
+
+use super::header::{DirectoryEntry, Machine};
+use super::Pe;
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse;
+
+/// The language specific handler of a function, with the data it gets passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExceptionHandler {
+    pub rva: u32,
+    /// The handler data right after the handler RVA, whose layout only the handler knows
+    pub data_rva: u32,
+}
+
+impl fmt::Display for ExceptionHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x} (Data {:#x})", self.rva, self.data_rva)
+    }
+}
+
+/// How to unwind a function, in the format of the machine
+#[derive(Debug)]
+pub enum Unwind {
+    /// An `UNWIND_INFO` record
+    X64 {
+        rva: u32,
+        info: BinDumpResult<x64::UnwindInfo>,
+    },
+    /// Unwind data packed into the `.pdata` entry
+    Arm64Packed(arm64::PackedUnwind),
+    /// An `.xdata` record
+    Arm64 {
+        rva: u32,
+        data: BinDumpResult<arm64::UnwindData>,
+    },
+}
+
+impl fmt::Display for Unwind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unwind::X64 { rva, info } => {
+                writeln!(f, "  Unwind Info: {:#x}", rva)?;
+                match info {
+                    Ok(info) => write!(f, "{}", info),
+                    Err(e) => writeln!(f, "  Error {}", e),
+                }
+            }
+            Unwind::Arm64Packed(packed) => write!(f, "{}", packed),
+            Unwind::Arm64 { rva, data } => {
+                writeln!(f, "  Unwind Data: {:#x}", rva)?;
+                match data {
+                    Ok(data) => write!(f, "{}", data),
+                    Err(e) => writeln!(f, "  Error {}", e),
+                }
+            }
+        }
+    }
+}
+
+/// A `RUNTIME_FUNCTION` entry of the exception directory, which covers every function that
+/// isn't a leaf
+#[derive(Debug)]
+pub struct RuntimeFunction {
+    pub begin_rva: u32,
+    /// The end of the function, or its start if the unwind data that has its length is broken
+    pub end_rva: u32,
+    pub unwind: Unwind,
+}
+
+impl fmt::Display for RuntimeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Function {:#x}-{:#x}:", self.begin_rva, self.end_rva)?;
+        write!(f, "{}", self.unwind)
+    }
+}
+
+impl Pe {
+    pub(super) fn parse_exceptions(&self) -> Option<BinDumpResult<Vec<RuntimeFunction>>> {
+        let input = self.directory_data(DirectoryEntry::Exception)?;
+        Some(match self.coff_header.machine {
+            Machine::Amd64 => self.parse_x64_functions(input),
+            Machine::Arm64 => self.parse_arm64_functions(input),
+            machine => Err(BinDumpError::Unsupported {
+                format: format!("exception data of {} images", machine),
+            }),
+        })
+    }
+
+    fn parse_x64_functions(&self, input: parse::Input) -> BinDumpResult<Vec<RuntimeFunction>> {
+        let (_, entries) = context(
+            "Parse Runtime Functions",
+            count(tuple((le_u32, le_u32, le_u32)), input.len() / 12),
+        )(input)?;
+        Ok(entries
+            .into_iter()
+            .map(|(begin_rva, end_rva, unwind_rva)| {
+                // An odd unwind RVA points at the entry of the function to take the unwind info
+                // of instead
+                let rva = match unwind_rva & 1 {
+                    0 => unwind_rva,
+                    _ => self
+                        .data_at_rva(unwind_rva & !1)
+                        .and_then(|entry| entry.get(8..12))
+                        .map_or(unwind_rva, |rva| {
+                            u32::from_le_bytes([rva[0], rva[1], rva[2], rva[3]])
+                        }),
+                };
+                RuntimeFunction {
+                    begin_rva,
+                    end_rva,
+                    unwind: Unwind::X64 {
+                        rva,
+                        info: self.x64_unwind_info(rva, 0),
+                    },
+                }
+            })
+            .collect())
+    }
+
+    fn parse_arm64_functions(&self, input: parse::Input) -> BinDumpResult<Vec<RuntimeFunction>> {
+        let (_, entries) = context(
+            "Parse Runtime Functions",
+            count(tuple((le_u32, le_u32)), input.len() / 8),
+        )(input)?;
+        Ok(entries
+            .into_iter()
+            .map(|(begin_rva, unwind_data)| {
+                // The low two bits tell packed unwind data from the RVA of an `.xdata` record
+                let (function_length, unwind) = match unwind_data & 3 {
+                    0 => {
+                        let data = self.arm64_unwind_data(unwind_data);
+                        let length = data.as_ref().map_or(0, |data| data.function_length);
+                        (
+                            length,
+                            Unwind::Arm64 {
+                                rva: unwind_data,
+                                data,
+                            },
+                        )
+                    }
+                    _ => {
+                        let packed = arm64::PackedUnwind::from(unwind_data);
+                        (packed.function_length, Unwind::Arm64Packed(packed))
+                    }
+                };
+                RuntimeFunction {
+                    begin_rva,
+                    end_rva: begin_rva.wrapping_add(function_length),
+                    unwind,
+                }
+            })
+            .collect())
+    }
+
+    /// The `RUNTIME_FUNCTION` entries of the exception directory, sorted by address
+    pub fn runtime_functions(&self) -> &[RuntimeFunction] {
+        match &self.exceptions {
+            Some(Ok(functions)) => functions,
+            _ => &[],
+        }
+    }
+
+    /// The entry of the function containing an RVA, which leaf functions don't have
+    pub fn runtime_function(&self, rva: u32) -> Option<&RuntimeFunction> {
+        let functions = self.runtime_functions();
+        let position = functions.partition_point(|function| function.begin_rva <= rva);
+        let function = functions.get(position.checked_sub(1)?)?;
+        (rva < function.end_rva).then_some(function)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::image;
+    use super::*;
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    /// The test image with unwind info at 0x160, unwind info chaining to it at 0x180, a
+    /// runtime function entry at 0x1a0 and an exception directory at 0x1c0
+    fn x64_image() -> Vec<u8> {
+        let mut data = image();
+        // The exception directory entry of the optional header
+        data[0xe0..0xe8].copy_from_slice(&words(&[0x1c0, 36]));
+        data.resize(0x160, 0);
+        // Version 1 with an exception handler, and three codes ending 8 bytes into the prolog
+        data.extend_from_slice(&[0x09, 8, 3, 0]);
+        for slot in [0x4208u16, 0x3004, 0x5001, 0] {
+            data.extend_from_slice(&slot.to_le_bytes());
+        }
+        data.extend(words(&[0x3000]));
+        data.resize(0x180, 0);
+        data.extend_from_slice(&[0x21, 0, 0, 0]);
+        data.extend(words(&[0x1000, 0x1040, 0x160]));
+        data.resize(0x1a0, 0);
+        data.extend(words(&[0x1000, 0x1040, 0x160]));
+        data.resize(0x1c0, 0);
+        data.extend(words(&[0x1000, 0x1040, 0x160, 0x1040, 0x1080, 0x180]));
+        // An odd unwind RVA, which refers to the entry at 0x1a0
+        data.extend(words(&[0x1080, 0x10a0, 0x1a1]));
+        data
+    }
+
+    fn x64_info(function: &RuntimeFunction) -> &x64::UnwindInfo {
+        match &function.unwind {
+            Unwind::X64 { info, .. } => info.as_ref().unwrap(),
+            unwind => panic!("{:?} isn't x64 unwind info", unwind),
+        }
+    }
+
+    #[test]
+    fn parses_x64_runtime_functions() {
+        let pe = Pe::load(x64_image()).unwrap();
+        let functions = pe.runtime_functions();
+        assert_eq!(functions.len(), 3);
+
+        let info = x64_info(&functions[0]);
+        assert_eq!(info.flag_names(), ["EHANDLER"]);
+        let codes: Vec<String> = info
+            .codes
+            .iter()
+            .map(|code| code.operation.to_string())
+            .collect();
+        assert_eq!(codes, ["sub rsp, 0x28", "push rbx", "push rbp"]);
+        assert_eq!(
+            info.handler,
+            Some(ExceptionHandler {
+                rva: 0x3000,
+                data_rva: 0x170,
+            })
+        );
+
+        let chained = x64_info(&functions[1]).chained.as_ref().unwrap();
+        assert_eq!(chained.begin_rva, 0x1000);
+        assert_eq!(chained.info.codes.len(), 3);
+        assert!(matches!(
+            functions[2].unwind,
+            Unwind::X64 { rva: 0x160, .. }
+        ));
+    }
+
+    #[test]
+    fn finds_the_function_containing_an_rva() {
+        let pe = Pe::load(x64_image()).unwrap();
+        let begin = |rva| pe.runtime_function(rva).map(|function| function.begin_rva);
+        assert_eq!(begin(0xfff), None);
+        assert_eq!(begin(0x1000), Some(0x1000));
+        assert_eq!(begin(0x107f), Some(0x1040));
+        assert_eq!(begin(0x10a0), None);
+    }
+
+    #[test]
+    fn stops_on_unwind_info_chained_to_itself() {
+        let mut data = x64_image();
+        data[0x18c..0x190].copy_from_slice(&0x180u32.to_le_bytes());
+        let pe = Pe::load(data).unwrap();
+        assert!(pe.x64_unwind_info(0x180, 0).is_err());
+    }
+
+    #[test]
+    fn parses_arm64_packed_and_xdata_functions() {
+        let mut data = image();
+        data[0x44..0x46].copy_from_slice(&0xaa64u16.to_le_bytes());
+        data.resize(0x160, 0);
+        // 0x40 bytes of function, one code word and no epilog scopes
+        data.extend(words(&[0x10 | 1 << 21 | 1 << 27, 0xe3e3_e3e4]));
+        let pe = Pe::load(data).unwrap();
+        let packed = 1 | 0x10 << 2 | 2 << 16 | 3 << 21 | 2 << 23;
+        let functions = pe
+            .parse_arm64_functions(&words(&[0x1000, packed, 0x1100, 0x160]))
+            .unwrap();
+        assert_eq!(functions[0].end_rva, 0x1040);
+        assert!(matches!(functions[0].unwind, Unwind::Arm64Packed(_)));
+        assert_eq!(functions[1].end_rva, 0x1140);
+        match &functions[1].unwind {
+            Unwind::Arm64 { rva: 0x160, data } => {
+                let data = data.as_ref().unwrap();
+                assert_eq!(data.prolog(), [arm64::UnwindCode::End]);
+                assert_eq!(data.packed_epilog_index, Some(0));
+            }
+            unwind => panic!("{:?} isn't an xdata record", unwind),
+        }
+    }
+}
@@ -0,0 +1,680 @@
+//! The unwind data of ARM64 images, packed into `.pdata` entries or in `.xdata` records
+
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::combinator::cond;
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::le_u32;
+
+use super::ExceptionHandler;
+use crate::binary::pe::Pe;
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse::{self, failure};
+
+/// A prolog or epilog operation. Offsets are in bytes, and registers are numbered as in the
+/// instructions, so `SaveReg` of register 30 saves LR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnwindCode {
+    /// `alloc_s`, `alloc_m` and `alloc_l`
+    Alloc(u32),
+    /// `save_fplr`: `stp x29, lr, [sp, #offset]`
+    SaveFpLr {
+        offset: u32,
+    },
+    /// `save_fplr_x`: `stp x29, lr, [sp, #-offset]!`
+    SaveFpLrX {
+        offset: u32,
+    },
+    /// `save_r19r20_x`, `save_regp` and `save_regp_x`: saves a register and the next one
+    SaveRegPair {
+        register: u8,
+        offset: u32,
+        pre_index: bool,
+    },
+    /// `save_reg` and `save_reg_x`
+    SaveReg {
+        register: u8,
+        offset: u32,
+        pre_index: bool,
+    },
+    /// `save_lrpair`: `stp x<register>, lr, [sp, #offset]`
+    SaveLrPair {
+        register: u8,
+        offset: u32,
+    },
+    /// `save_fregp` and `save_fregp_x`: saves a D register and the next one
+    SaveFRegPair {
+        register: u8,
+        offset: u32,
+        pre_index: bool,
+    },
+    /// `save_freg` and `save_freg_x`
+    SaveFReg {
+        register: u8,
+        offset: u32,
+        pre_index: bool,
+    },
+    /// `alloc_z`: allocates a multiple of the SVE vector length
+    AllocZ(u8),
+    /// `set_fp`: `mov x29, sp`
+    SetFp,
+    /// `add_fp`: `add x29, sp, #offset`
+    AddFp(u32),
+    Nop,
+    End,
+    /// `end_c`: the end of the codes of this scope, with those of the chained scope following
+    EndC,
+    /// `save_next`: saves the next pair of the registers the previous code saved
+    SaveNext,
+    /// `save_any_reg`, with its operand bytes
+    SaveAnyReg([u8; 2]),
+    /// Operations for the frames of traps, machine frames and contexts
+    Custom(u8),
+    /// `pac_sign_lr`: `pacibsp`
+    PacSignLr,
+    Reserved(u8),
+}
+
+impl UnwindCode {
+    /// Decodes the code at the start of the input, with how many bytes it takes
+    fn decode(input: &[u8]) -> Option<(Self, usize)> {
+        let b = *input.first()?;
+        let length = match b {
+            0xc0..=0xdf | 0xe2 | 0xf8 => 2,
+            0xe7 | 0xf9 => 3,
+            0xe0 | 0xfa => 4,
+            0xfb => 5,
+            _ => 1,
+        };
+        let bytes = input.get(..length)?;
+        let w = bytes
+            .get(..2)
+            .map_or(0, |w| (w[0] as u32) << 8 | w[1] as u32);
+        // The offset and register fields of the two byte codes
+        let z6 = (w & 0x3f) * 8;
+        let z5 = ((w & 0x1f) + 1) * 8;
+        let x4 = ((w >> 6) & 0xf) as u8;
+        let x3 = ((w >> 6) & 0x7) as u8;
+        let code = match b {
+            0x00..=0x1f => UnwindCode::Alloc((b as u32 & 0x1f) * 16),
+            0x20..=0x3f => UnwindCode::SaveRegPair {
+                register: 19,
+                offset: (b as u32 & 0x1f) * 8,
+                pre_index: true,
+            },
+            0x40..=0x7f => UnwindCode::SaveFpLr {
+                offset: (b as u32 & 0x3f) * 8,
+            },
+            0x80..=0xbf => UnwindCode::SaveFpLrX {
+                offset: ((b as u32 & 0x3f) + 1) * 8,
+            },
+            0xc0..=0xc7 => UnwindCode::Alloc((w & 0x7ff) * 16),
+            0xc8..=0xcb => UnwindCode::SaveRegPair {
+                register: 19 + x4,
+                offset: z6,
+                pre_index: false,
+            },
+            0xcc..=0xcf => UnwindCode::SaveRegPair {
+                register: 19 + x4,
+                offset: z6 + 8,
+                pre_index: true,
+            },
+            0xd0..=0xd3 => UnwindCode::SaveReg {
+                register: 19 + x4,
+                offset: z6,
+                pre_index: false,
+            },
+            0xd4 | 0xd5 => UnwindCode::SaveReg {
+                register: 19 + ((w >> 5) & 0xf) as u8,
+                offset: z5,
+                pre_index: true,
+            },
+            0xd6 | 0xd7 => UnwindCode::SaveLrPair {
+                register: 19 + 2 * x3,
+                offset: z6,
+            },
+            0xd8 | 0xd9 => UnwindCode::SaveFRegPair {
+                register: 8 + x3,
+                offset: z6,
+                pre_index: false,
+            },
+            0xda | 0xdb => UnwindCode::SaveFRegPair {
+                register: 8 + x3,
+                offset: z6 + 8,
+                pre_index: true,
+            },
+            0xdc | 0xdd => UnwindCode::SaveFReg {
+                register: 8 + x3,
+                offset: z6,
+                pre_index: false,
+            },
+            0xde => UnwindCode::SaveFReg {
+                register: 8 + ((w >> 5) & 0x7) as u8,
+                offset: z5,
+                pre_index: true,
+            },
+            0xdf => UnwindCode::AllocZ(bytes[1]),
+            0xe0 => UnwindCode::Alloc(
+                ((bytes[1] as u32) << 16 | (bytes[2] as u32) << 8 | bytes[3] as u32) * 16,
+            ),
+            0xe1 => UnwindCode::SetFp,
+            0xe2 => UnwindCode::AddFp(bytes[1] as u32 * 8),
+            0xe3 => UnwindCode::Nop,
+            0xe4 => UnwindCode::End,
+            0xe5 => UnwindCode::EndC,
+            0xe6 => UnwindCode::SaveNext,
+            0xe7 => UnwindCode::SaveAnyReg([bytes[1], bytes[2]]),
+            0xe8..=0xec => UnwindCode::Custom(b),
+            0xfc => UnwindCode::PacSignLr,
+            _ => UnwindCode::Reserved(b),
+        };
+        Some((code, length))
+    }
+
+    /// Decodes the codes from an index of the code bytes up to the end of the scope
+    fn decode_scope(mut input: &[u8]) -> Vec<Self> {
+        let mut codes = Vec::new();
+        while let Some((code, length)) = Self::decode(input) {
+            codes.push(code);
+            if matches!(code, UnwindCode::End | UnwindCode::EndC) {
+                break;
+            }
+            input = &input[length..];
+        }
+        codes
+    }
+}
+
+fn register_name(register: u8) -> String {
+    match register {
+        30 => "lr".to_string(),
+        register => format!("x{}", register),
+    }
+}
+
+/// Formats the stack address of a save, which pre-indexed saves first move SP down to
+fn stack_address(offset: u32, pre_index: bool) -> String {
+    if pre_index {
+        format!("[sp, #-{}]!", offset)
+    } else {
+        format!("[sp, #{}]", offset)
+    }
+}
+
+impl fmt::Display for UnwindCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            UnwindCode::Alloc(size) => write!(f, "sub sp, sp, #{}", size),
+            UnwindCode::SaveFpLr { offset } => write!(f, "stp x29, lr, [sp, #{}]", offset),
+            UnwindCode::SaveFpLrX { offset } => write!(f, "stp x29, lr, [sp, #-{}]!", offset),
+            UnwindCode::SaveRegPair {
+                register,
+                offset,
+                pre_index,
+            } => write!(
+                f,
+                "stp {}, {}, {}",
+                register_name(register),
+                register_name(register + 1),
+                stack_address(offset, pre_index)
+            ),
+            UnwindCode::SaveReg {
+                register,
+                offset,
+                pre_index,
+            } => write!(
+                f,
+                "str {}, {}",
+                register_name(register),
+                stack_address(offset, pre_index)
+            ),
+            UnwindCode::SaveLrPair { register, offset } => {
+                write!(f, "stp x{}, lr, [sp, #{}]", register, offset)
+            }
+            UnwindCode::SaveFRegPair {
+                register,
+                offset,
+                pre_index,
+            } => write!(
+                f,
+                "stp d{}, d{}, {}",
+                register,
+                register + 1,
+                stack_address(offset, pre_index)
+            ),
+            UnwindCode::SaveFReg {
+                register,
+                offset,
+                pre_index,
+            } => write!(f, "str d{}, {}", register, stack_address(offset, pre_index)),
+            UnwindCode::AllocZ(size) => write!(f, "addvl sp, sp, #-{}", size),
+            UnwindCode::SetFp => write!(f, "mov x29, sp"),
+            UnwindCode::AddFp(offset) => write!(f, "add x29, sp, #{}", offset),
+            UnwindCode::Nop => write!(f, "nop"),
+            UnwindCode::End => write!(f, "end"),
+            UnwindCode::EndC => write!(f, "end_c"),
+            UnwindCode::SaveNext => write!(f, "save_next"),
+            UnwindCode::SaveAnyReg([first, second]) => {
+                write!(f, "save_any_reg {:#04x} {:#04x}", first, second)
+            }
+            UnwindCode::Custom(code) => match code {
+                0xe8 => write!(f, "trap_frame"),
+                0xe9 => write!(f, "machine_frame"),
+                0xea => write!(f, "context"),
+                0xeb => write!(f, "ec_context"),
+                0xec => write!(f, "clear_unwound_to_call"),
+                code => write!(f, "custom {:#04x}", code),
+            },
+            UnwindCode::PacSignLr => write!(f, "pacibsp"),
+            UnwindCode::Reserved(code) => write!(f, "reserved {:#04x}", code),
+        }
+    }
+}
+
+/// Unwind data packed into a `.pdata` entry, for functions with a canonical prolog and epilog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedUnwind {
+    /// Whether this is a fragment of a function, which has no prolog of its own
+    pub fragment: bool,
+    pub function_length: u32,
+    /// How many D registers from d8 were saved, minus one, if any were
+    pub reg_f: u8,
+    /// How many X registers from x19 were saved
+    pub reg_i: u8,
+    /// Whether the parameter registers x0 to x7 were stored on the stack
+    pub homes_parameters: bool,
+    /// 0 for no LR save, 1 for LR saved with the X registers, 2 for a PAC signed LR and 3 for
+    /// a frame record of x29 and LR
+    pub cr: u8,
+    /// The size of the whole frame in bytes
+    pub frame_size: u32,
+}
+
+impl From<u32> for PackedUnwind {
+    fn from(data: u32) -> Self {
+        Self {
+            fragment: data & 3 == 2,
+            function_length: ((data >> 2) & 0x7ff) * 4,
+            reg_f: ((data >> 13) & 0x7) as u8,
+            reg_i: ((data >> 16) & 0xf) as u8,
+            homes_parameters: (data >> 20) & 1 != 0,
+            cr: ((data >> 21) & 0x3) as u8,
+            frame_size: (data >> 23) * 16,
+        }
+    }
+}
+
+impl PackedUnwind {
+    /// The codes of the canonical prolog the fields describe, last first as in `.xdata`
+    pub fn prolog(&self) -> Vec<UnwindCode> {
+        let reg_i = self.reg_i as u32;
+        let mut int_size = 8 * reg_i;
+        if self.cr == 1 {
+            int_size += 8;
+        }
+        let float_registers = match self.reg_f {
+            0 => 0,
+            reg_f => reg_f as u32 + 1,
+        };
+        let float_size = 8 * float_registers;
+        let home_size = if self.homes_parameters { 64 } else { 0 };
+        let save_size = (int_size + float_size + home_size + 0xf) & !0xf;
+        let local_size = self.frame_size.saturating_sub(save_size);
+        let has_frame = self.cr == 2 || self.cr == 3;
+
+        let mut codes = Vec::new();
+        if has_frame {
+            codes.push(UnwindCode::SetFp);
+            if local_size <= 512 {
+                codes.push(UnwindCode::SaveFpLrX { offset: local_size });
+            } else {
+                codes.push(UnwindCode::SaveFpLr { offset: 0 });
+            }
+        }
+        // Large frames are allocated in two steps
+        if local_size > 4080 {
+            codes.push(UnwindCode::Alloc(local_size - 4080));
+            codes.push(UnwindCode::Alloc(4080));
+        } else if (!has_frame && local_size > 0) || local_size > 512 {
+            codes.push(UnwindCode::Alloc(local_size));
+        }
+        if self.homes_parameters {
+            for register in [6, 4, 2] {
+                codes.push(UnwindCode::SaveRegPair {
+                    register,
+                    offset: save_size - 64 + 8 * register as u32,
+                    pre_index: false,
+                });
+            }
+            let first_save = self.reg_i > 0 || self.reg_f > 0 || self.cr == 1;
+            codes.push(UnwindCode::SaveRegPair {
+                register: 0,
+                offset: if first_save {
+                    save_size - 64
+                } else {
+                    save_size
+                },
+                pre_index: !first_save,
+            });
+        }
+        let float_pairs = float_registers.div_ceil(2);
+        for i in (0..float_pairs).rev() {
+            let register = 8 + 2 * i as u8;
+            if i == float_pairs - 1 && float_registers % 2 == 1 {
+                codes.push(UnwindCode::SaveFReg {
+                    register,
+                    offset: int_size + 16 * i,
+                    pre_index: false,
+                });
+            } else if i == 0 && self.reg_i == 0 && self.cr != 1 {
+                codes.push(UnwindCode::SaveFRegPair {
+                    register,
+                    offset: save_size,
+                    pre_index: true,
+                });
+            } else {
+                codes.push(UnwindCode::SaveFRegPair {
+                    register,
+                    offset: int_size + 16 * i,
+                    pre_index: false,
+                });
+            }
+        }
+        if self.cr == 1 && reg_i.is_multiple_of(2) {
+            codes.push(UnwindCode::SaveReg {
+                register: 30,
+                offset: if reg_i == 0 { save_size } else { int_size - 8 },
+                pre_index: reg_i == 0,
+            });
+        }
+        let int_pairs = reg_i.div_ceil(2);
+        for i in (0..int_pairs).rev() {
+            let register = 19 + 2 * i as u8;
+            if i == int_pairs - 1 && reg_i % 2 == 1 {
+                // An odd register out, which LR pairs with if it's saved. LR can't pair with
+                // x19 though, so that combination has no prolog
+                if self.cr == 1 {
+                    if i > 0 {
+                        codes.push(UnwindCode::SaveLrPair {
+                            register,
+                            offset: 16 * i,
+                        });
+                    }
+                } else {
+                    codes.push(UnwindCode::SaveReg {
+                        register,
+                        offset: if i == 0 { save_size } else { 16 * i },
+                        pre_index: i == 0,
+                    });
+                }
+            } else {
+                codes.push(UnwindCode::SaveRegPair {
+                    register,
+                    offset: if i == 0 { save_size } else { 16 * i },
+                    pre_index: i == 0,
+                });
+            }
+        }
+        if self.cr == 2 {
+            codes.push(UnwindCode::PacSignLr);
+        }
+        codes.push(UnwindCode::End);
+        codes
+    }
+}
+
+impl fmt::Display for PackedUnwind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "  Packed: RegF {}, RegI {}, H {}, CR {}, Frame Size {}{}",
+            self.reg_f,
+            self.reg_i,
+            self.homes_parameters as u8,
+            self.cr,
+            self.frame_size,
+            if self.fragment { ", Fragment" } else { "" }
+        )?;
+        writeln!(f, "  Prolog:")?;
+        for code in self.prolog() {
+            writeln!(f, "    {}", code)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpilogScope {
+    /// The offset of the epilog from the start of the function
+    pub start_offset: u32,
+    /// The index of the epilog's first code in the code bytes
+    pub start_index: u16,
+}
+
+/// An `.xdata` record
+#[derive(Debug)]
+pub struct UnwindData {
+    pub function_length: u32,
+    pub version: u8,
+    pub epilog_scopes: Vec<EpilogScope>,
+    /// The index of the codes of the only epilog, which the header holds instead of the
+    /// number of epilog scopes when that epilog ends the function
+    pub packed_epilog_index: Option<u16>,
+    /// The unwind codes, which the prolog starts at and the epilog scopes index into
+    pub code_bytes: Vec<u8>,
+    pub handler: Option<ExceptionHandler>,
+}
+
+impl UnwindData {
+    fn parse(input: parse::Input, rva: u32) -> parse::ParseResult<Self> {
+        let start = input;
+        let (input, header) = context("Parse Unwind Data", le_u32)(input)?;
+        let version = ((header >> 18) & 0x3) as u8;
+        if version != 0 {
+            return Err(failure(start, "Unknown Unwind Data Version"));
+        }
+        let has_handler = (header >> 20) & 1 != 0;
+        let packed_epilog = (header >> 21) & 1 != 0;
+        // Records with more codes or epilogs than the header fits have a second header word
+        let (input, epilog_count, code_words) = match (header >> 22) & 0x1f {
+            0 if header >> 27 == 0 => {
+                let (input, extended) = context("Parse Extended Unwind Data", le_u32)(input)?;
+                (input, extended & 0xffff, (extended >> 16) & 0xff)
+            }
+            epilog_count => (input, epilog_count, header >> 27),
+        };
+        let (input, scopes) = context(
+            "Parse Epilog Scopes",
+            cond(!packed_epilog, count(le_u32, epilog_count as usize)),
+        )(input)?;
+        let (input, code_bytes) =
+            context("Parse Unwind Codes", take(code_words as usize * 4))(input)?;
+        let (input, handler_rva) =
+            context("Parse Exception Handler", cond(has_handler, le_u32))(input)?;
+        Ok((
+            input,
+            Self {
+                function_length: (header & 0x3ffff) * 4,
+                version,
+                epilog_scopes: scopes
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|scope| EpilogScope {
+                        start_offset: (scope & 0x3ffff) * 4,
+                        start_index: (scope >> 22) as u16,
+                    })
+                    .collect(),
+                packed_epilog_index: packed_epilog.then_some(epilog_count as u16),
+                code_bytes: code_bytes.to_vec(),
+                handler: handler_rva.map(|handler_rva| ExceptionHandler {
+                    rva: handler_rva,
+                    data_rva: rva.wrapping_add((start.len() - input.len()) as u32),
+                }),
+            },
+        ))
+    }
+
+    /// The codes of the prolog, last first
+    pub fn prolog(&self) -> Vec<UnwindCode> {
+        UnwindCode::decode_scope(&self.code_bytes)
+    }
+
+    /// The codes of the epilog whose codes start at an index of the code bytes
+    pub fn epilog(&self, start_index: u16) -> Vec<UnwindCode> {
+        UnwindCode::decode_scope(self.code_bytes.get(start_index as usize..).unwrap_or(&[]))
+    }
+}
+
+impl fmt::Display for UnwindData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Version: {}", self.version)?;
+        writeln!(f, "  Prolog:")?;
+        for code in self.prolog() {
+            writeln!(f, "    {}", code)?;
+        }
+        for scope in &self.epilog_scopes {
+            writeln!(f, "  Epilog At {:#x}:", scope.start_offset)?;
+            for code in self.epilog(scope.start_index) {
+                writeln!(f, "    {}", code)?;
+            }
+        }
+        if let Some(index) = self.packed_epilog_index {
+            writeln!(f, "  Epilog At End:")?;
+            for code in self.epilog(index) {
+                writeln!(f, "    {}", code)?;
+            }
+        }
+        if let Some(handler) = &self.handler {
+            writeln!(f, "  Handler: {}", handler)?;
+        }
+        Ok(())
+    }
+}
+
+impl Pe {
+    pub(super) fn arm64_unwind_data(&self, rva: u32) -> BinDumpResult<UnwindData> {
+        let input = self
+            .data_at_rva(rva)
+            .ok_or_else(|| BinDumpError::ParseError {
+                error: format!("Unwind data at {:#x} is not in the file", rva),
+            })?;
+        Ok(UnwindData::parse(input, rva)?.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(bytes: &[u8]) -> Vec<String> {
+        UnwindCode::decode_scope(bytes)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn decodes_codes_of_each_length() {
+        assert_eq!(
+            scope(&[0xc8, 0x02, 0xe1, 0x83, 0xe4, 0xe3]),
+            [
+                "stp x19, x20, [sp, #16]",
+                "mov x29, sp",
+                "stp x29, lr, [sp, #-32]!",
+                "end",
+            ]
+        );
+        assert_eq!(
+            scope(&[0xd5, 0x61, 0xe0, 0x00, 0x01, 0x00, 0xfc, 0xe5]),
+            [
+                "str lr, [sp, #-16]!",
+                "sub sp, sp, #4096",
+                "pacibsp",
+                "end_c"
+            ]
+        );
+        assert_eq!(UnwindCode::decode(&[0xc8]), None);
+        assert_eq!(scope(&[0xe0, 0x00]), Vec::<String>::new());
+    }
+
+    #[test]
+    fn expands_packed_unwind_data() {
+        let packed = PackedUnwind::from(1 | 0x10 << 2 | 2 << 16 | 3 << 21 | 2 << 23);
+        assert_eq!(packed.function_length, 0x40);
+        assert_eq!(packed.reg_i, 2);
+        assert_eq!(packed.cr, 3);
+        assert_eq!(packed.frame_size, 32);
+        assert!(!packed.fragment);
+        assert_eq!(
+            packed.prolog(),
+            [
+                UnwindCode::SetFp,
+                UnwindCode::SaveFpLrX { offset: 16 },
+                UnwindCode::SaveRegPair {
+                    register: 19,
+                    offset: 16,
+                    pre_index: true,
+                },
+                UnwindCode::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_epilog_scopes_and_handler() {
+        // 0x40 bytes of function with a handler, one epilog scope and three code words
+        let mut data = Vec::new();
+        for word in [0x10 | 1 << 20 | 1 << 22 | 3 << 27, 0x0c | 5 << 22] {
+            data.extend_from_slice(&u32::to_le_bytes(word));
+        }
+        data.extend_from_slice(&[0xc8, 0x02, 0xe1, 0x83, 0xe4, 0xc8, 0x02, 0x83]);
+        data.extend_from_slice(&[0xe4, 0xe3, 0xe3, 0xe3]);
+        data.extend_from_slice(&0x3000u32.to_le_bytes());
+        let (_, unwind) = UnwindData::parse(&data, 0x200).unwrap();
+        assert_eq!(unwind.function_length, 0x40);
+        assert_eq!(
+            unwind.epilog_scopes,
+            [EpilogScope {
+                start_offset: 0x30,
+                start_index: 5,
+            }]
+        );
+        assert_eq!(unwind.prolog().len(), 4);
+        assert_eq!(
+            unwind.epilog(5),
+            [
+                UnwindCode::SaveRegPair {
+                    register: 19,
+                    offset: 16,
+                    pre_index: false,
+                },
+                UnwindCode::SaveFpLrX { offset: 32 },
+                UnwindCode::End,
+            ]
+        );
+        assert_eq!(
+            unwind.handler,
+            Some(ExceptionHandler {
+                rva: 0x3000,
+                data_rva: 0x218,
+            })
+        );
+    }
+
+    #[test]
+    fn reads_the_extended_header() {
+        let mut data = Vec::new();
+        for word in [0x10, 1 | 1 << 16, 0x20] {
+            data.extend_from_slice(&u32::to_le_bytes(word));
+        }
+        data.extend_from_slice(&[0xe4, 0xe3, 0xe3, 0xe3]);
+        let (_, unwind) = UnwindData::parse(&data, 0).unwrap();
+        assert_eq!(unwind.epilog_scopes[0].start_offset, 0x80);
+        assert_eq!(unwind.prolog(), [UnwindCode::End]);
+
+        data[2] = 0x04;
+        assert!(UnwindData::parse(&data, 0).is_err());
+    }
+}
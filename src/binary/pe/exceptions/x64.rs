@@ -0,0 +1,409 @@
+//! The `UNWIND_INFO` records of x64 images
+
+use std::fmt;
+
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_u16, le_u32, u8};
+use nom::sequence::tuple;
+
+use super::ExceptionHandler;
+use crate::binary::pe::header::flag_names;
+use crate::binary::pe::Pe;
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse::{self, failure};
+
+const UNW_FLAG_EHANDLER: u8 = 0x1;
+const UNW_FLAG_UHANDLER: u8 = 0x2;
+const UNW_FLAG_CHAININFO: u8 = 0x4;
+const UNWIND_FLAGS: [(u8, &str); 3] = [
+    (UNW_FLAG_EHANDLER, "EHANDLER"),
+    (UNW_FLAG_UHANDLER, "UHANDLER"),
+    (UNW_FLAG_CHAININFO, "CHAININFO"),
+];
+
+const UWOP_PUSH_NONVOL: u16 = 0;
+const UWOP_ALLOC_LARGE: u16 = 1;
+const UWOP_ALLOC_SMALL: u16 = 2;
+const UWOP_SET_FPREG: u16 = 3;
+const UWOP_SAVE_NONVOL: u16 = 4;
+const UWOP_SAVE_NONVOL_FAR: u16 = 5;
+/// `UWOP_EPILOG` in version 2, and the retired `UWOP_SAVE_XMM` before
+const UWOP_EPILOG: u16 = 6;
+const UWOP_SPARE_CODE: u16 = 7;
+const UWOP_SAVE_XMM128: u16 = 8;
+const UWOP_SAVE_XMM128_FAR: u16 = 9;
+const UWOP_PUSH_MACHFRAME: u16 = 10;
+
+/// How deep chained unwind info may nest, to stop on cycles
+const MAX_CHAIN_DEPTH: usize = 32;
+
+/// The general purpose registers, by the 4 bit numbers unwind codes use
+const REGISTERS: [&str; 16] = [
+    "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12", "r13",
+    "r14", "r15",
+];
+
+/// What a prolog instruction did, for the unwinder to undo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnwindOperation {
+    /// `UWOP_PUSH_NONVOL`
+    PushNonVolatile(&'static str),
+    /// `UWOP_ALLOC_SMALL` and `UWOP_ALLOC_LARGE`, with the size in bytes
+    Alloc(u32),
+    /// `UWOP_SET_FPREG`: the frame register was set to RSP plus the frame offset
+    SetFramePointer,
+    /// `UWOP_SAVE_NONVOL` and `UWOP_SAVE_NONVOL_FAR`, with the offset from RSP
+    SaveNonVolatile {
+        register: &'static str,
+        offset: u32,
+    },
+    /// `UWOP_SAVE_XMM128` and `UWOP_SAVE_XMM128_FAR`, with the offset from RSP
+    SaveXmm128 {
+        register: u8,
+        offset: u32,
+    },
+    /// `UWOP_EPILOG`. The first one has the size of the epilogs in `offset` and sets bit 0 of
+    /// `info` if one ends the function, the others have the distance of an epilog from the end
+    /// of the function in `offset` and `info` as the low and high bits
+    Epilog {
+        offset: u8,
+        info: u8,
+    },
+    /// `UWOP_PUSH_MACHFRAME`: an interrupt or exception pushed a machine frame
+    PushMachineFrame {
+        error_code: bool,
+    },
+    Other {
+        operation: u8,
+        info: u8,
+    },
+}
+
+impl fmt::Display for UnwindOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnwindOperation::PushNonVolatile(register) => write!(f, "push {}", register),
+            UnwindOperation::Alloc(size) => write!(f, "sub rsp, {:#x}", size),
+            UnwindOperation::SetFramePointer => write!(f, "set frame pointer"),
+            UnwindOperation::SaveNonVolatile { register, offset } => {
+                write!(f, "mov [rsp + {:#x}], {}", offset, register)
+            }
+            UnwindOperation::SaveXmm128 { register, offset } => {
+                write!(f, "movaps [rsp + {:#x}], xmm{}", offset, register)
+            }
+            UnwindOperation::Epilog { offset, info } => {
+                write!(f, "epilog {:#x} ({:#x})", offset, info)
+            }
+            UnwindOperation::PushMachineFrame { error_code } => {
+                write!(f, "push machine frame")?;
+                if *error_code {
+                    write!(f, " with error code")?;
+                }
+                Ok(())
+            }
+            UnwindOperation::Other { operation, info } => {
+                write!(f, "unknown operation {} ({:#x})", operation, info)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnwindCode {
+    /// The offset of the end of the instruction from the start of the prolog
+    pub prolog_offset: u8,
+    pub operation: UnwindOperation,
+}
+
+/// The function whose unwind info continues where this one stops, as when a function's
+/// prolog is spread over several places
+#[derive(Debug)]
+pub struct ChainedFunction {
+    pub begin_rva: u32,
+    pub end_rva: u32,
+    pub unwind_info_rva: u32,
+    pub info: UnwindInfo,
+}
+
+/// An `UNWIND_INFO` record
+#[derive(Debug)]
+pub struct UnwindInfo {
+    pub version: u8,
+    pub flags: u8,
+    pub prolog_size: u8,
+    /// The register used as frame pointer, if any
+    pub frame_register: Option<&'static str>,
+    /// How far above RSP the frame register points
+    pub frame_offset: u32,
+    /// The prolog operations, last first as the unwinder undoes them
+    pub codes: Vec<UnwindCode>,
+    pub handler: Option<ExceptionHandler>,
+    pub chained: Option<Box<ChainedFunction>>,
+}
+
+impl UnwindInfo {
+    /// Parses the record at `rva`, and returns the entry of the chained function separately
+    fn parse(input: parse::Input, rva: u32) -> parse::ParseResult<(Self, Option<[u32; 3]>)> {
+        let start = input;
+        let (input, (version_flags, prolog_size, code_count, frame)) =
+            context("Parse Unwind Info", tuple((u8, u8, u8, u8)))(input)?;
+        let version = version_flags & 0x7;
+        let flags = version_flags >> 3;
+        if version != 1 && version != 2 {
+            return Err(failure(start, "Unknown Unwind Info Version"));
+        }
+        // The slots are padded to an even number
+        let slot_count = (code_count as usize + 1) & !1;
+        let (input, slots) = context("Parse Unwind Codes", count(le_u16, slot_count))(input)?;
+        let codes = decode_codes(&slots[..code_count as usize], version)
+            .ok_or_else(|| failure(start, "Truncated Unwind Code"))?;
+
+        let (input, chained, handler) = if flags & UNW_FLAG_CHAININFO != 0 {
+            let (input, (begin_rva, end_rva, unwind_info_rva)) =
+                context("Parse Chained Function", tuple((le_u32, le_u32, le_u32)))(input)?;
+            (input, Some([begin_rva, end_rva, unwind_info_rva]), None)
+        } else if flags & (UNW_FLAG_EHANDLER | UNW_FLAG_UHANDLER) != 0 {
+            let (input, handler_rva) = context("Parse Exception Handler", le_u32)(input)?;
+            let handler = ExceptionHandler {
+                rva: handler_rva,
+                data_rva: rva.wrapping_add((start.len() - input.len()) as u32),
+            };
+            (input, None, Some(handler))
+        } else {
+            (input, None, None)
+        };
+        let frame_register = frame & 0xf;
+        Ok((
+            input,
+            (
+                Self {
+                    version,
+                    flags,
+                    prolog_size,
+                    frame_register: (frame_register != 0)
+                        .then(|| REGISTERS[frame_register as usize]),
+                    frame_offset: (frame >> 4) as u32 * 16,
+                    codes,
+                    handler,
+                    chained: None,
+                },
+                chained,
+            ),
+        ))
+    }
+
+    pub fn flag_names(&self) -> Vec<&'static str> {
+        flag_names(self.flags, &UNWIND_FLAGS)
+    }
+}
+
+/// Decodes the unwind code slots, of which some operations take one or two extra
+fn decode_codes(slots: &[u16], version: u8) -> Option<Vec<UnwindCode>> {
+    let mut codes = Vec::new();
+    let mut i = 0;
+    while let Some(&slot) = slots.get(i) {
+        let prolog_offset = slot as u8;
+        let info = (slot >> 12) as u8;
+        let extra = |n: usize| slots.get(i + 1..i + 1 + n);
+        let long = || extra(2).map(|s| s[0] as u32 | (s[1] as u32) << 16);
+        let (operation, size) = match (slot >> 8) & 0xf {
+            UWOP_PUSH_NONVOL => (
+                UnwindOperation::PushNonVolatile(REGISTERS[info as usize]),
+                1,
+            ),
+            UWOP_ALLOC_LARGE if info == 0 => (UnwindOperation::Alloc(extra(1)?[0] as u32 * 8), 2),
+            UWOP_ALLOC_LARGE => (UnwindOperation::Alloc(long()?), 3),
+            UWOP_ALLOC_SMALL => (UnwindOperation::Alloc(info as u32 * 8 + 8), 1),
+            UWOP_SET_FPREG => (UnwindOperation::SetFramePointer, 1),
+            UWOP_SAVE_NONVOL => (
+                UnwindOperation::SaveNonVolatile {
+                    register: REGISTERS[info as usize],
+                    offset: extra(1)?[0] as u32 * 8,
+                },
+                2,
+            ),
+            UWOP_SAVE_NONVOL_FAR => (
+                UnwindOperation::SaveNonVolatile {
+                    register: REGISTERS[info as usize],
+                    offset: long()?,
+                },
+                3,
+            ),
+            UWOP_EPILOG if version >= 2 => (
+                UnwindOperation::Epilog {
+                    offset: prolog_offset,
+                    info,
+                },
+                1,
+            ),
+            operation @ (UWOP_EPILOG | UWOP_SPARE_CODE) => {
+                let size = if operation == UWOP_EPILOG { 2 } else { 3 };
+                extra(size - 1)?;
+                (
+                    UnwindOperation::Other {
+                        operation: operation as u8,
+                        info,
+                    },
+                    size,
+                )
+            }
+            UWOP_SAVE_XMM128 => (
+                UnwindOperation::SaveXmm128 {
+                    register: info,
+                    offset: extra(1)?[0] as u32 * 16,
+                },
+                2,
+            ),
+            UWOP_SAVE_XMM128_FAR => (
+                UnwindOperation::SaveXmm128 {
+                    register: info,
+                    offset: long()?,
+                },
+                3,
+            ),
+            UWOP_PUSH_MACHFRAME => (
+                UnwindOperation::PushMachineFrame {
+                    error_code: info != 0,
+                },
+                1,
+            ),
+            operation => (
+                UnwindOperation::Other {
+                    operation: operation as u8,
+                    info,
+                },
+                1,
+            ),
+        };
+        codes.push(UnwindCode {
+            prolog_offset,
+            operation,
+        });
+        i += size;
+    }
+    Some(codes)
+}
+
+impl fmt::Display for UnwindInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Version: {}", self.version)?;
+        writeln!(
+            f,
+            "  Flags: {:#x} ({})",
+            self.flags,
+            self.flag_names().join(", ")
+        )?;
+        writeln!(f, "  Prolog Size: {}", self.prolog_size)?;
+        if let Some(register) = self.frame_register {
+            writeln!(
+                f,
+                "  Frame Register: {} (Offset {:#x})",
+                register, self.frame_offset
+            )?;
+        }
+        writeln!(f, "  Codes:")?;
+        for code in &self.codes {
+            writeln!(f, "    {:#x}: {}", code.prolog_offset, code.operation)?;
+        }
+        if let Some(handler) = &self.handler {
+            writeln!(f, "  Handler: {}", handler)?;
+        }
+        if let Some(chained) = &self.chained {
+            writeln!(
+                f,
+                "  Chained To Function {:#x}-{:#x}, Unwind Info {:#x}",
+                chained.begin_rva, chained.end_rva, chained.unwind_info_rva
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Pe {
+    /// Reads the unwind info at an RVA, with the unwind info it chains to
+    pub(super) fn x64_unwind_info(&self, rva: u32, depth: usize) -> BinDumpResult<UnwindInfo> {
+        let input = self
+            .data_at_rva(rva)
+            .ok_or_else(|| BinDumpError::ParseError {
+                error: format!("Unwind info at {:#x} is not in the file", rva),
+            })?;
+        let (_, (mut info, chained)) = UnwindInfo::parse(input, rva)?;
+        if let Some([begin_rva, end_rva, unwind_info_rva]) = chained {
+            if depth == MAX_CHAIN_DEPTH {
+                return Err(failure(input, "Unwind Info Chain Too Long").into());
+            }
+            info.chained = Some(Box::new(ChainedFunction {
+                begin_rva,
+                end_rva,
+                unwind_info_rva,
+                info: self.x64_unwind_info(unwind_info_rva, depth + 1)?,
+            }));
+        }
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operations(slots: &[u16], version: u8) -> Option<Vec<String>> {
+        let codes = decode_codes(slots, version)?;
+        Some(
+            codes
+                .iter()
+                .map(|code| code.operation.to_string())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn decodes_codes_with_extra_slots() {
+        let slots = [
+            0x0110, 0x0020, // UWOP_ALLOC_LARGE with the size / 8
+            0x1108, 0, 1, // UWOP_ALLOC_LARGE with the whole size
+            0x6404, 2, // UWOP_SAVE_NONVOL rsi
+            0x6808, 1, // UWOP_SAVE_XMM128 xmm6
+            0x5502, 0x10, 0,      // UWOP_SAVE_NONVOL_FAR rbp
+            0x1a00, // UWOP_PUSH_MACHFRAME
+        ];
+        assert_eq!(
+            operations(&slots, 1).unwrap(),
+            [
+                "sub rsp, 0x100",
+                "sub rsp, 0x10000",
+                "mov [rsp + 0x10], rsi",
+                "movaps [rsp + 0x10], xmm6",
+                "mov [rsp + 0x10], rbp",
+                "push machine frame with error code",
+            ]
+        );
+    }
+
+    #[test]
+    fn epilog_codes_depend_on_version() {
+        assert_eq!(
+            decode_codes(&[0x1602, 0], 2).unwrap()[0].operation,
+            UnwindOperation::Epilog { offset: 2, info: 1 }
+        );
+        // Version 1 has the retired UWOP_SAVE_XMM in its place, which takes an extra slot
+        assert_eq!(
+            decode_codes(&[0x1602, 0], 1).unwrap(),
+            [UnwindCode {
+                prolog_offset: 2,
+                operation: UnwindOperation::Other {
+                    operation: 6,
+                    info: 1
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_codes() {
+        assert_eq!(operations(&[0x0110], 1), None);
+        assert_eq!(operations(&[0x1108, 0], 1), None);
+        assert!(UnwindInfo::parse(&[0x03, 0, 0, 0], 0).is_err());
+    }
+}
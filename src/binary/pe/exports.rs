@@ -0,0 +1,359 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+
+use super::header::DirectoryEntry;
+use super::Pe;
+use crate::error::BinDumpResult;
+use crate::parse;
+
+/// What an entry of the export address table points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportTarget {
+    /// The RVA of the exported code or data
+    Address(u32),
+    /// An export of another DLL, as `DLL.Function` or `DLL.#Ordinal`
+    Forwarder(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export {
+    pub ordinal: u32,
+    /// The names the export can be imported by, which is empty for exports by ordinal only
+    pub names: Vec<String>,
+    pub target: ExportTarget,
+}
+
+impl fmt::Display for Export {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:5} ", self.ordinal)?;
+        match &self.target {
+            ExportTarget::Address(rva) => write!(f, "{:#010x}", rva)?,
+            ExportTarget::Forwarder(forwarder) => write!(f, "-> {}", forwarder)?,
+        }
+        if self.names.is_empty() {
+            write!(f, " (ordinal only)")
+        } else {
+            write!(f, " {}", self.names.join(", "))
+        }
+    }
+}
+
+/// Something wrong with the tables of the export directory, which is skipped over
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportProblem {
+    /// A table is cut short by the end of its section
+    TruncatedTable { table: &'static str },
+    /// An entry of the name pointer table points outside of the file
+    UnmappedName { index: u32, rva: u32 },
+    /// An entry of the ordinal table is past the end of the export address table
+    OrdinalOutOfRange { index: u32, ordinal: u16 },
+    /// The name pointer table isn't sorted, so the loader's binary search can miss names
+    UnsortedName { index: u32 },
+}
+
+impl fmt::Display for ExportProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportProblem::TruncatedTable { table } => {
+                write!(f, "The {} is truncated", table)
+            }
+            ExportProblem::UnmappedName { index, rva } => {
+                write!(f, "Name {} at RVA {:#x} is outside of the file", index, rva)
+            }
+            ExportProblem::OrdinalOutOfRange { index, ordinal } => write!(
+                f,
+                "Name {} has ordinal index {}, past the export address table",
+                index, ordinal
+            ),
+            ExportProblem::UnsortedName { index } => {
+                write!(f, "Name {} is out of order", index)
+            }
+        }
+    }
+}
+
+/// `IMAGE_EXPORT_DIRECTORY` with the tables it points to
+#[derive(Debug)]
+pub struct ExportDirectory {
+    pub characteristics: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub name: String,
+    /// The ordinal of the first entry of the export address table
+    pub ordinal_base: u32,
+    pub number_of_functions: u32,
+    pub number_of_names: u32,
+    pub address_table_rva: u32,
+    pub name_pointer_rva: u32,
+    pub ordinal_table_rva: u32,
+    /// One entry for every used slot of the export address table, in ordinal order
+    pub exports: Vec<Export>,
+    pub problems: Vec<ExportProblem>,
+}
+
+impl ExportDirectory {
+    pub fn by_name(&self, name: &str) -> Option<&Export> {
+        self.exports
+            .iter()
+            .find(|export| export.names.iter().any(|n| n == name))
+    }
+
+    pub fn by_ordinal(&self, ordinal: u32) -> Option<&Export> {
+        self.exports.iter().find(|export| export.ordinal == ordinal)
+    }
+}
+
+impl fmt::Display for ExportDirectory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Name: {}", self.name)?;
+        writeln!(f, "Characteristics: {:#x}", self.characteristics)?;
+        writeln!(f, "Time Date Stamp: {:#x}", self.time_date_stamp)?;
+        writeln!(f, "Version: {}.{}", self.major_version, self.minor_version)?;
+        writeln!(f, "Ordinal Base: {}", self.ordinal_base)?;
+        writeln!(f, "Number Of Functions: {}", self.number_of_functions)?;
+        writeln!(f, "Number Of Names: {}", self.number_of_names)?;
+        writeln!(f, "Export Address Table: {:#x}", self.address_table_rva)?;
+        writeln!(f, "Name Pointer Table: {:#x}", self.name_pointer_rva)?;
+        writeln!(f, "Ordinal Table: {:#x}", self.ordinal_table_rva)?;
+        writeln!(f, "Exports:")?;
+        for export in &self.exports {
+            writeln!(f, "  {}", export)?;
+        }
+        if !self.problems.is_empty() {
+            writeln!(f, "Problems:")?;
+            for problem in &self.problems {
+                writeln!(f, "  {}", problem)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Pe {
+    /// Reads a table of `len` little endian entries at an RVA, keeping the entries that are in
+    /// the file
+    fn export_table<'a, T>(
+        &'a self,
+        rva: u32,
+        len: u32,
+        entry: impl FnMut(parse::Input<'a>) -> parse::ParseResult<'a, T>,
+        name: &'static str,
+        problems: &mut Vec<ExportProblem>,
+    ) -> Vec<T> {
+        if len == 0 {
+            return Vec::new();
+        }
+        let input = self.data_at_rva(rva).unwrap_or_default();
+        let size = std::mem::size_of::<T>();
+        let available = (input.len() / size).min(len as usize);
+        if available < len as usize {
+            problems.push(ExportProblem::TruncatedTable { table: name });
+        }
+        count(entry, available)(input)
+            .map(|(_, table)| table)
+            .unwrap_or_default()
+    }
+
+    pub(super) fn parse_exports(&self) -> Option<BinDumpResult<ExportDirectory>> {
+        let directory = self
+            .optional_header
+            .data_directory(DirectoryEntry::Export)?;
+        Some(self.parse_export_directory(directory.virtual_address, directory.size))
+    }
+
+    fn parse_export_directory(&self, rva: u32, size: u32) -> BinDumpResult<ExportDirectory> {
+        let input = self.data_at_rva(rva).unwrap_or_default();
+        let (
+            _,
+            (
+                characteristics,
+                time_date_stamp,
+                major_version,
+                minor_version,
+                name,
+                ordinal_base,
+                number_of_functions,
+                number_of_names,
+                address_table_rva,
+                name_pointer_rva,
+                ordinal_table_rva,
+            ),
+        ) = context(
+            "Parse Export Directory",
+            tuple((
+                le_u32, le_u32, le_u16, le_u16, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32,
+                le_u32,
+            )),
+        )(input)?;
+
+        let mut problems = Vec::new();
+        let addresses = self.export_table(
+            address_table_rva,
+            number_of_functions,
+            le_u32,
+            "export address table",
+            &mut problems,
+        );
+        let name_pointers = self.export_table(
+            name_pointer_rva,
+            number_of_names,
+            le_u32,
+            "name pointer table",
+            &mut problems,
+        );
+        let ordinals = self.export_table(
+            ordinal_table_rva,
+            number_of_names,
+            le_u16,
+            "ordinal table",
+            &mut problems,
+        );
+
+        let mut names = vec![Vec::new(); addresses.len()];
+        let mut previous: Option<String> = None;
+        for (index, (&name_rva, &ordinal)) in name_pointers.iter().zip(&ordinals).enumerate() {
+            let index = index as u32;
+            let name = match self.string_at_rva(name_rva) {
+                Some(name) => name,
+                None => {
+                    problems.push(ExportProblem::UnmappedName {
+                        index,
+                        rva: name_rva,
+                    });
+                    continue;
+                }
+            };
+            if previous.as_ref().is_some_and(|previous| *previous > name) {
+                problems.push(ExportProblem::UnsortedName { index });
+            }
+            match names.get_mut(ordinal as usize) {
+                Some(names) => names.push(name.clone()),
+                None => problems.push(ExportProblem::OrdinalOutOfRange { index, ordinal }),
+            }
+            previous = Some(name);
+        }
+
+        // Addresses inside the export directory are forwarder strings
+        let forwarded = |address: u32| address >= rva && address - rva < size;
+        let exports = addresses
+            .iter()
+            .zip(names)
+            .enumerate()
+            .filter(|(_, (&address, _))| address != 0)
+            .map(|(i, (&address, names))| Export {
+                ordinal: ordinal_base.wrapping_add(i as u32),
+                names,
+                target: if forwarded(address) {
+                    ExportTarget::Forwarder(self.string_at_rva(address).unwrap_or_default())
+                } else {
+                    ExportTarget::Address(address)
+                },
+            })
+            .collect();
+
+        Ok(ExportDirectory {
+            characteristics,
+            time_date_stamp,
+            major_version,
+            minor_version,
+            name: self.string_at_rva(name).unwrap_or_default(),
+            ordinal_base,
+            number_of_functions,
+            number_of_names,
+            address_table_rva,
+            name_pointer_rva,
+            ordinal_table_rva,
+            exports,
+            problems,
+        })
+    }
+
+    pub fn exports(&self) -> Option<&ExportDirectory> {
+        self.exports.as_ref()?.as_ref().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::image;
+    use super::*;
+
+    /// The test image with an export directory at 0x160 in its headers, which exports `beta`,
+    /// `alpha` as a forwarder and one function by ordinal only, starting from ordinal 5
+    fn exporting_image() -> Pe {
+        let mut data = image();
+        data.resize(0x160, 0);
+        // The third field is the major and minor version
+        for field in [0, 0, 0, 0x1b0, 5, 4, 2, 0x190, 0x1a0, 0x1a8] {
+            data.extend_from_slice(&u32::to_le_bytes(field));
+        }
+        // The export address table, then the name pointer table and the ordinal table
+        data.resize(0x190, 0);
+        for address in [0x1000u32, 0x1d0, 0, 0x2000, 0x1c0, 0x1c8] {
+            data.extend_from_slice(&address.to_le_bytes());
+        }
+        data.extend_from_slice(&[1, 0, 0, 0]);
+        for (offset, string) in [(0x1b0, "test.dll"), (0x1c0, "alpha"), (0x1c8, "beta")] {
+            data.resize(offset, 0);
+            data.extend_from_slice(string.as_bytes());
+        }
+        data.resize(0x1d0, 0);
+        data.extend_from_slice(b"OTHER.func\0");
+        Pe::load(data).unwrap()
+    }
+
+    #[test]
+    fn reads_forwarders_and_ordinal_only_exports() {
+        let pe = exporting_image();
+        let exports = pe.parse_export_directory(0x160, 0x80).unwrap();
+        assert_eq!(exports.name, "test.dll");
+        assert_eq!(exports.problems, []);
+        assert_eq!(
+            exports.exports,
+            [
+                Export {
+                    ordinal: 5,
+                    names: vec!["beta".to_string()],
+                    target: ExportTarget::Address(0x1000),
+                },
+                Export {
+                    ordinal: 6,
+                    names: vec!["alpha".to_string()],
+                    target: ExportTarget::Forwarder("OTHER.func".to_string()),
+                },
+                Export {
+                    ordinal: 8,
+                    names: Vec::new(),
+                    target: ExportTarget::Address(0x2000),
+                },
+            ]
+        );
+        assert_eq!(exports.by_name("alpha"), exports.by_ordinal(6));
+        assert_eq!(
+            exports.by_ordinal(8).unwrap().to_string(),
+            "    8 0x00002000 (ordinal only)"
+        );
+    }
+
+    #[test]
+    fn reports_ordinals_past_the_address_table() {
+        let pe = exporting_image();
+        let mut data = pe.data.clone();
+        data[0x1a8] = 4;
+        let pe = Pe::load(data).unwrap();
+        let exports = pe.parse_export_directory(0x160, 0x80).unwrap();
+        assert_eq!(
+            exports.problems,
+            [ExportProblem::OrdinalOutOfRange {
+                index: 0,
+                ordinal: 4
+            }]
+        );
+        assert!(exports.by_name("alpha").is_none());
+    }
+}
@@ -0,0 +1,561 @@
+use std::fmt;
+
+use nom::bytes::complete::{tag, take};
+use nom::combinator::map;
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_u16, le_u32, le_u64, u8};
+use nom::sequence::tuple;
+
+use crate::parse;
+
+/// The magic of the optional header of 32 bit images
+pub const PE32_MAGIC: u16 = 0x10b;
+/// The magic of the optional header of 64 bit images
+pub const PE32_PLUS_MAGIC: u16 = 0x20b;
+
+/// The part of the MS-DOS header that is still used
+#[derive(Debug)]
+pub struct DosHeader {
+    /// `e_lfanew`, the file offset of the PE signature
+    pub pe_header_offset: u32,
+}
+
+impl DosHeader {
+    pub(super) fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (input, (_, _, pe_header_offset)) = context(
+            "Parse DOS Header",
+            tuple((tag(b"MZ"), take(58usize), le_u32)),
+        )(input)?;
+        Ok((input, Self { pe_header_offset }))
+    }
+}
+
+// From winnt.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Machine {
+    Unknown,
+    I386,
+    R4000,
+    WceMipsV2,
+    Alpha,
+    Sh3,
+    Sh4,
+    Arm,
+    Thumb,
+    ArmNt,
+    PowerPc,
+    Ia64,
+    Mips16,
+    Alpha64,
+    Ebc,
+    RiscV32,
+    RiscV64,
+    LoongArch64,
+    Amd64,
+    Arm64Ec,
+    Arm64X,
+    Arm64,
+    Other(u16),
+}
+
+impl From<u16> for Machine {
+    fn from(machine: u16) -> Self {
+        match machine {
+            0 => Machine::Unknown,
+            0x14c => Machine::I386,
+            0x166 => Machine::R4000,
+            0x169 => Machine::WceMipsV2,
+            0x184 => Machine::Alpha,
+            0x1a2 => Machine::Sh3,
+            0x1a6 => Machine::Sh4,
+            0x1c0 => Machine::Arm,
+            0x1c2 => Machine::Thumb,
+            0x1c4 => Machine::ArmNt,
+            0x1f0 => Machine::PowerPc,
+            0x200 => Machine::Ia64,
+            0x266 => Machine::Mips16,
+            0x284 => Machine::Alpha64,
+            0xebc => Machine::Ebc,
+            0x5032 => Machine::RiscV32,
+            0x5064 => Machine::RiscV64,
+            0x6264 => Machine::LoongArch64,
+            0x8664 => Machine::Amd64,
+            0xa641 => Machine::Arm64Ec,
+            0xa64e => Machine::Arm64X,
+            0xaa64 => Machine::Arm64,
+            machine => Machine::Other(machine),
+        }
+    }
+}
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Machine::Other(machine) => write!(f, "Unknown ({:#x})", machine),
+            machine => write!(f, "{:?}", machine),
+        }
+    }
+}
+
+/// Names of the `Characteristics` bits of the file header
+pub(crate) const CHARACTERISTICS: [(u16, &str); 15] = [
+    (0x0001, "RELOCS_STRIPPED"),
+    (0x0002, "EXECUTABLE_IMAGE"),
+    (0x0004, "LINE_NUMS_STRIPPED"),
+    (0x0008, "LOCAL_SYMS_STRIPPED"),
+    (0x0010, "AGGRESSIVE_WS_TRIM"),
+    (0x0020, "LARGE_ADDRESS_AWARE"),
+    (0x0080, "BYTES_REVERSED_LO"),
+    (0x0100, "32BIT_MACHINE"),
+    (0x0200, "DEBUG_STRIPPED"),
+    (0x0400, "REMOVABLE_RUN_FROM_SWAP"),
+    (0x0800, "NET_RUN_FROM_SWAP"),
+    (0x1000, "SYSTEM"),
+    (0x2000, "DLL"),
+    (0x4000, "UP_SYSTEM_ONLY"),
+    (0x8000, "BYTES_REVERSED_HI"),
+];
+
+/// Names of the `DllCharacteristics` bits of the optional header
+const DLL_CHARACTERISTICS: [(u16, &str); 11] = [
+    (0x0020, "HIGH_ENTROPY_VA"),
+    (0x0040, "DYNAMIC_BASE"),
+    (0x0080, "FORCE_INTEGRITY"),
+    (0x0100, "NX_COMPAT"),
+    (0x0200, "NO_ISOLATION"),
+    (0x0400, "NO_SEH"),
+    (0x0800, "NO_BIND"),
+    (0x1000, "APPCONTAINER"),
+    (0x2000, "WDM_DRIVER"),
+    (0x4000, "GUARD_CF"),
+    (0x8000, "TERMINAL_SERVER_AWARE"),
+];
+
+/// The names of the bits set in `flags`
+pub(crate) fn flag_names<T>(flags: T, names: &[(T, &'static str)]) -> Vec<&'static str>
+where
+    T: Copy + std::ops::BitAnd<Output = T> + PartialEq + Default,
+{
+    names
+        .iter()
+        .filter(|(bit, _)| flags & *bit != T::default())
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// The COFF file header, which follows the `PE\0\0` signature in images and starts object files
+#[derive(Debug)]
+pub struct CoffHeader {
+    pub machine: Machine,
+    pub number_of_sections: u16,
+    pub time_date_stamp: u32,
+    pub pointer_to_symbol_table: u32,
+    pub number_of_symbols: u32,
+    pub size_of_optional_header: u16,
+    pub characteristics: u16,
+}
+
+impl CoffHeader {
+    pub(crate) fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (
+            input,
+            (
+                machine,
+                number_of_sections,
+                time_date_stamp,
+                pointer_to_symbol_table,
+                number_of_symbols,
+                size_of_optional_header,
+                characteristics,
+            ),
+        ) = context(
+            "Parse COFF Header",
+            tuple((
+                map(le_u16, Machine::from),
+                le_u16,
+                le_u32,
+                le_u32,
+                le_u32,
+                le_u16,
+                le_u16,
+            )),
+        )(input)?;
+        Ok((
+            input,
+            Self {
+                machine,
+                number_of_sections,
+                time_date_stamp,
+                pointer_to_symbol_table,
+                number_of_symbols,
+                size_of_optional_header,
+                characteristics,
+            },
+        ))
+    }
+
+    pub fn characteristic_names(&self) -> Vec<&'static str> {
+        flag_names(self.characteristics, &CHARACTERISTICS)
+    }
+}
+
+// From winnt.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Unknown,
+    Native,
+    WindowsGui,
+    WindowsCui,
+    Os2Cui,
+    PosixCui,
+    NativeWindows,
+    WindowsCeGui,
+    EfiApplication,
+    EfiBootServiceDriver,
+    EfiRuntimeDriver,
+    EfiRom,
+    Xbox,
+    WindowsBootApplication,
+    Other(u16),
+}
+
+impl From<u16> for Subsystem {
+    fn from(subsystem: u16) -> Self {
+        match subsystem {
+            0 => Subsystem::Unknown,
+            1 => Subsystem::Native,
+            2 => Subsystem::WindowsGui,
+            3 => Subsystem::WindowsCui,
+            5 => Subsystem::Os2Cui,
+            7 => Subsystem::PosixCui,
+            8 => Subsystem::NativeWindows,
+            9 => Subsystem::WindowsCeGui,
+            10 => Subsystem::EfiApplication,
+            11 => Subsystem::EfiBootServiceDriver,
+            12 => Subsystem::EfiRuntimeDriver,
+            13 => Subsystem::EfiRom,
+            14 => Subsystem::Xbox,
+            16 => Subsystem::WindowsBootApplication,
+            subsystem => Subsystem::Other(subsystem),
+        }
+    }
+}
+
+/// The index of each data directory in the optional header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectoryEntry {
+    Export = 0,
+    Import = 1,
+    Resource = 2,
+    Exception = 3,
+    Security = 4,
+    BaseRelocation = 5,
+    Debug = 6,
+    Architecture = 7,
+    GlobalPointer = 8,
+    Tls = 9,
+    LoadConfig = 10,
+    BoundImport = 11,
+    ImportAddressTable = 12,
+    DelayImport = 13,
+    ComDescriptor = 14,
+}
+
+const DIRECTORY_NAMES: [&str; 16] = [
+    "Export",
+    "Import",
+    "Resource",
+    "Exception",
+    "Security",
+    "Base Relocation",
+    "Debug",
+    "Architecture",
+    "Global Pointer",
+    "TLS",
+    "Load Config",
+    "Bound Import",
+    "Import Address Table",
+    "Delay Import",
+    "COM Descriptor",
+    "Reserved",
+];
+
+/// The location of a table, by RVA except for the security directory, which uses a file offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataDirectory {
+    pub virtual_address: u32,
+    pub size: u32,
+}
+
+/// Parses an address or size, which is 8 bytes in PE32+ images and 4 in PE32 ones
+pub(crate) fn word(is_64_bit: bool) -> impl Fn(parse::Input) -> parse::ParseResult<u64> + Copy {
+    move |input| {
+        if is_64_bit {
+            le_u64(input)
+        } else {
+            map(le_u32, u64::from)(input)
+        }
+    }
+}
+
+/// The optional header, which every image has
+#[derive(Debug)]
+pub struct OptionalHeader {
+    pub magic: u16,
+    pub major_linker_version: u8,
+    pub minor_linker_version: u8,
+    pub size_of_code: u32,
+    pub size_of_initialized_data: u32,
+    pub size_of_uninitialized_data: u32,
+    pub address_of_entry_point: u32,
+    pub base_of_code: u32,
+    /// Only present in PE32 images
+    pub base_of_data: Option<u32>,
+    pub image_base: u64,
+    pub section_alignment: u32,
+    pub file_alignment: u32,
+    pub major_operating_system_version: u16,
+    pub minor_operating_system_version: u16,
+    pub major_image_version: u16,
+    pub minor_image_version: u16,
+    pub major_subsystem_version: u16,
+    pub minor_subsystem_version: u16,
+    pub win32_version_value: u32,
+    pub size_of_image: u32,
+    pub size_of_headers: u32,
+    pub checksum: u32,
+    pub subsystem: Subsystem,
+    pub dll_characteristics: u16,
+    pub size_of_stack_reserve: u64,
+    pub size_of_stack_commit: u64,
+    pub size_of_heap_reserve: u64,
+    pub size_of_heap_commit: u64,
+    pub loader_flags: u32,
+    pub data_directories: Vec<DataDirectory>,
+}
+
+impl OptionalHeader {
+    pub(super) fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (input, magic) = context("Parse Optional Header Magic", le_u16)(input)?;
+        let is_64_bit = match magic {
+            PE32_MAGIC => false,
+            PE32_PLUS_MAGIC => true,
+            _ => return Err(parse::failure(input, "Invalid Optional Header Magic")),
+        };
+        let word = word(is_64_bit);
+        let (
+            input,
+            (
+                major_linker_version,
+                minor_linker_version,
+                size_of_code,
+                size_of_initialized_data,
+                size_of_uninitialized_data,
+                address_of_entry_point,
+                base_of_code,
+            ),
+        ) = context(
+            "Parse Optional Header Standard Fields",
+            tuple((u8, u8, le_u32, le_u32, le_u32, le_u32, le_u32)),
+        )(input)?;
+        let (input, base_of_data) = if is_64_bit {
+            (input, None)
+        } else {
+            map(le_u32, Some)(input)?
+        };
+        let (
+            input,
+            (
+                image_base,
+                section_alignment,
+                file_alignment,
+                major_operating_system_version,
+                minor_operating_system_version,
+                major_image_version,
+                minor_image_version,
+                major_subsystem_version,
+                minor_subsystem_version,
+                win32_version_value,
+                size_of_image,
+                size_of_headers,
+                checksum,
+            ),
+        ) = context(
+            "Parse Optional Header Windows Fields",
+            tuple((
+                word, le_u32, le_u32, le_u16, le_u16, le_u16, le_u16, le_u16, le_u16, le_u32,
+                le_u32, le_u32, le_u32,
+            )),
+        )(input)?;
+        let (
+            input,
+            (
+                subsystem,
+                dll_characteristics,
+                size_of_stack_reserve,
+                size_of_stack_commit,
+                size_of_heap_reserve,
+                size_of_heap_commit,
+                loader_flags,
+                number_of_rva_and_sizes,
+            ),
+        ) = context(
+            "Parse Optional Header Windows Fields",
+            tuple((
+                map(le_u16, Subsystem::from),
+                le_u16,
+                word,
+                word,
+                word,
+                word,
+                le_u32,
+                le_u32,
+            )),
+        )(input)?;
+        let (input, data_directories) = context(
+            "Parse Data Directories",
+            count(
+                map(tuple((le_u32, le_u32)), |(virtual_address, size)| {
+                    DataDirectory {
+                        virtual_address,
+                        size,
+                    }
+                }),
+                number_of_rva_and_sizes.min(16) as usize,
+            ),
+        )(input)?;
+        Ok((
+            input,
+            Self {
+                magic,
+                major_linker_version,
+                minor_linker_version,
+                size_of_code,
+                size_of_initialized_data,
+                size_of_uninitialized_data,
+                address_of_entry_point,
+                base_of_code,
+                base_of_data,
+                image_base,
+                section_alignment,
+                file_alignment,
+                major_operating_system_version,
+                minor_operating_system_version,
+                major_image_version,
+                minor_image_version,
+                major_subsystem_version,
+                minor_subsystem_version,
+                win32_version_value,
+                size_of_image,
+                size_of_headers,
+                checksum,
+                subsystem,
+                dll_characteristics,
+                size_of_stack_reserve,
+                size_of_stack_commit,
+                size_of_heap_reserve,
+                size_of_heap_commit,
+                loader_flags,
+                data_directories,
+            },
+        ))
+    }
+
+    pub fn is_64_bit(&self) -> bool {
+        self.magic == PE32_PLUS_MAGIC
+    }
+
+    /// A data directory, unless the image doesn't have it
+    pub fn data_directory(&self, entry: DirectoryEntry) -> Option<DataDirectory> {
+        self.data_directories
+            .get(entry as usize)
+            .copied()
+            .filter(|directory| directory.virtual_address != 0)
+    }
+
+    pub fn dll_characteristic_names(&self) -> Vec<&'static str> {
+        flag_names(self.dll_characteristics, &DLL_CHARACTERISTICS)
+    }
+}
+
+impl fmt::Display for OptionalHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Format: {}",
+            if self.is_64_bit() { "PE32+" } else { "PE32" }
+        )?;
+        writeln!(
+            f,
+            "Linker Version: {}.{}",
+            self.major_linker_version, self.minor_linker_version
+        )?;
+        writeln!(f, "Size Of Code: {:#x}", self.size_of_code)?;
+        writeln!(
+            f,
+            "Size Of Initialized Data: {:#x}",
+            self.size_of_initialized_data
+        )?;
+        writeln!(
+            f,
+            "Size Of Uninitialized Data: {:#x}",
+            self.size_of_uninitialized_data
+        )?;
+        writeln!(f, "Entry Point: {:#x}", self.address_of_entry_point)?;
+        writeln!(f, "Base Of Code: {:#x}", self.base_of_code)?;
+        if let Some(base_of_data) = self.base_of_data {
+            writeln!(f, "Base Of Data: {:#x}", base_of_data)?;
+        }
+        writeln!(f, "Image Base: {:#x}", self.image_base)?;
+        writeln!(f, "Section Alignment: {:#x}", self.section_alignment)?;
+        writeln!(f, "File Alignment: {:#x}", self.file_alignment)?;
+        writeln!(
+            f,
+            "Operating System Version: {}.{}",
+            self.major_operating_system_version, self.minor_operating_system_version
+        )?;
+        writeln!(
+            f,
+            "Image Version: {}.{}",
+            self.major_image_version, self.minor_image_version
+        )?;
+        writeln!(
+            f,
+            "Subsystem Version: {}.{}",
+            self.major_subsystem_version, self.minor_subsystem_version
+        )?;
+        writeln!(f, "Win32 Version Value: {}", self.win32_version_value)?;
+        writeln!(f, "Size Of Image: {:#x}", self.size_of_image)?;
+        writeln!(f, "Size Of Headers: {:#x}", self.size_of_headers)?;
+        writeln!(f, "Checksum: {:#x}", self.checksum)?;
+        writeln!(f, "Subsystem: {:?}", self.subsystem)?;
+        writeln!(
+            f,
+            "DLL Characteristics: {:#x} ({})",
+            self.dll_characteristics,
+            self.dll_characteristic_names().join(", ")
+        )?;
+        writeln!(
+            f,
+            "Size Of Stack Reserve: {:#x}",
+            self.size_of_stack_reserve
+        )?;
+        writeln!(f, "Size Of Stack Commit: {:#x}", self.size_of_stack_commit)?;
+        writeln!(f, "Size Of Heap Reserve: {:#x}", self.size_of_heap_reserve)?;
+        writeln!(f, "Size Of Heap Commit: {:#x}", self.size_of_heap_commit)?;
+        writeln!(f, "Loader Flags: {:#x}", self.loader_flags)?;
+        writeln!(f, "Data Directories:")?;
+        for (i, directory) in self.data_directories.iter().enumerate() {
+            if directory.virtual_address == 0 && directory.size == 0 {
+                continue;
+            }
+            writeln!(
+                f,
+                "  {}: {:#x} ({} bytes)",
+                DIRECTORY_NAMES[i], directory.virtual_address, directory.size
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+
+use super::header::{word, DirectoryEntry};
+use super::Pe;
+use crate::error::{BinDumpError, BinDumpResult};
+use crate::parse::string_at;
+
+/// Set in `Attributes` when a delay import descriptor holds RVAs instead of addresses
+const DELAY_ATTRIBUTE_RVA_BASED: u32 = 1;
+
+/// How an imported function is looked up in the exporting DLL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportName {
+    /// By name, with a hint for the index in the export name table to try first
+    Name {
+        hint: u16,
+        name: String,
+    },
+    Ordinal(u16),
+}
+
+impl fmt::Display for ImportName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportName::Name { hint, name } => write!(f, "{} (hint {})", name, hint),
+            ImportName::Ordinal(ordinal) => write!(f, "Ordinal {}", ordinal),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedFunction {
+    pub name: ImportName,
+    /// The RVA of the slot in the import address table the loader writes the address to
+    pub iat_rva: u32,
+}
+
+impl fmt::Display for ImportedFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}: {}", self.iat_rva, self.name)
+    }
+}
+
+/// A DLL from the import directory
+#[derive(Debug)]
+pub struct ImportedLibrary {
+    pub name: String,
+    /// The RVA of the import lookup table, which is 0 in some old images
+    pub lookup_table_rva: u32,
+    /// 0 unless the imports are bound, -1 for new style binding
+    pub time_date_stamp: u32,
+    pub forwarder_chain: u32,
+    pub iat_rva: u32,
+    pub functions: Vec<ImportedFunction>,
+}
+
+impl fmt::Display for ImportedLibrary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Library: {}", self.name)?;
+        writeln!(f, "Import Lookup Table: {:#x}", self.lookup_table_rva)?;
+        writeln!(f, "Time Date Stamp: {:#x}", self.time_date_stamp)?;
+        writeln!(f, "Forwarder Chain: {:#x}", self.forwarder_chain)?;
+        writeln!(f, "Import Address Table: {:#x}", self.iat_rva)?;
+        writeln!(f, "Functions:")?;
+        for function in &self.functions {
+            writeln!(f, "  {}", function)?;
+        }
+        Ok(())
+    }
+}
+
+/// A DLL from the delay-load import directory, which is loaded on the first call to one
+/// of its functions
+#[derive(Debug)]
+pub struct DelayImportedLibrary {
+    pub attributes: u32,
+    pub name: String,
+    /// The RVA of the variable the module handle is stored in once the DLL is loaded
+    pub module_handle_rva: u32,
+    pub iat_rva: u32,
+    pub name_table_rva: u32,
+    pub bound_iat_rva: u32,
+    pub unload_table_rva: u32,
+    pub time_date_stamp: u32,
+    pub functions: Vec<ImportedFunction>,
+}
+
+impl fmt::Display for DelayImportedLibrary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Library: {}", self.name)?;
+        writeln!(f, "Attributes: {:#x}", self.attributes)?;
+        writeln!(f, "Module Handle: {:#x}", self.module_handle_rva)?;
+        writeln!(f, "Import Address Table: {:#x}", self.iat_rva)?;
+        writeln!(f, "Import Name Table: {:#x}", self.name_table_rva)?;
+        writeln!(f, "Bound Import Address Table: {:#x}", self.bound_iat_rva)?;
+        writeln!(
+            f,
+            "Unload Import Address Table: {:#x}",
+            self.unload_table_rva
+        )?;
+        writeln!(f, "Time Date Stamp: {:#x}", self.time_date_stamp)?;
+        writeln!(f, "Functions:")?;
+        for function in &self.functions {
+            writeln!(f, "  {}", function)?;
+        }
+        Ok(())
+    }
+}
+
+/// A DLL whose import addresses were resolved ahead of time, and the version it was bound to
+#[derive(Debug)]
+pub struct BoundImport {
+    pub time_date_stamp: u32,
+    pub name: String,
+    /// The DLLs the bound DLL forwards some of the imported functions to
+    pub forwarder_refs: Vec<BoundForwarderRef>,
+}
+
+#[derive(Debug)]
+pub struct BoundForwarderRef {
+    pub time_date_stamp: u32,
+    pub name: String,
+}
+
+impl fmt::Display for BoundImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Library: {}", self.name)?;
+        writeln!(f, "Time Date Stamp: {:#x}", self.time_date_stamp)?;
+        for forwarder in &self.forwarder_refs {
+            writeln!(
+                f,
+                "  Forwarder: {} ({:#x})",
+                forwarder.name, forwarder.time_date_stamp
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn unmapped(what: &str, rva: u32) -> BinDumpError {
+    BinDumpError::ParseError {
+        error: format!("The {} at RVA {:#x} is outside of the file", what, rva),
+    }
+}
+
+impl Pe {
+    /// Reads the null terminated string at an RVA, failing when it isn't in the file
+    fn name_at(&self, rva: u32) -> BinDumpResult<String> {
+        self.string_at_rva(rva).ok_or_else(|| unmapped("name", rva))
+    }
+
+    /// Reads a table of thunks up to its null terminator. `to_rva` converts the non-ordinal
+    /// entries to the RVA of a hint/name entry
+    fn parse_thunks(
+        &self,
+        table_rva: u32,
+        iat_rva: u32,
+        to_rva: impl Fn(u64) -> u64,
+    ) -> BinDumpResult<Vec<ImportedFunction>> {
+        let is_64_bit = self.optional_header.is_64_bit();
+        let (size, ordinal_flag) = if is_64_bit {
+            (8, 1 << 63)
+        } else {
+            (4, 1 << 31)
+        };
+        let mut input = self
+            .data_at_rva(table_rva)
+            .ok_or_else(|| unmapped("import lookup table", table_rva))?;
+        let mut functions = Vec::new();
+        loop {
+            let (rest, thunk) = context("Parse Import Thunk", word(is_64_bit))(input)?;
+            input = rest;
+            if thunk == 0 {
+                break;
+            }
+            let name = if thunk & ordinal_flag != 0 {
+                ImportName::Ordinal(thunk as u16)
+            } else {
+                let rva = (to_rva(thunk) & 0x7fff_ffff) as u32;
+                let entry = self
+                    .data_at_rva(rva)
+                    .ok_or_else(|| unmapped("hint/name entry", rva))?;
+                let (name, hint) = context("Parse Hint", le_u16)(entry)?;
+                ImportName::Name {
+                    hint,
+                    name: string_at(name, 0),
+                }
+            };
+            functions.push(ImportedFunction {
+                name,
+                iat_rva: iat_rva.wrapping_add(functions.len() as u32 * size),
+            });
+        }
+        Ok(functions)
+    }
+
+    pub(super) fn parse_imports(&self) -> BinDumpResult<Vec<ImportedLibrary>> {
+        let mut input = match self.directory_start(DirectoryEntry::Import) {
+            Some(input) => input,
+            None => return Ok(Vec::new()),
+        };
+        let mut libraries = Vec::new();
+        loop {
+            let (rest, (lookup_table_rva, time_date_stamp, forwarder_chain, name, iat_rva)) =
+                context(
+                    "Parse Import Descriptor",
+                    tuple((le_u32, le_u32, le_u32, le_u32, le_u32)),
+                )(input)?;
+            input = rest;
+            if lookup_table_rva == 0 && name == 0 && iat_rva == 0 {
+                break;
+            }
+            // Without a lookup table, the names are only in the unbound import address table
+            let table_rva = if lookup_table_rva == 0 {
+                iat_rva
+            } else {
+                lookup_table_rva
+            };
+            libraries.push(ImportedLibrary {
+                name: self.name_at(name)?,
+                lookup_table_rva,
+                time_date_stamp,
+                forwarder_chain,
+                iat_rva,
+                functions: self.parse_thunks(table_rva, iat_rva, |thunk| thunk)?,
+            });
+        }
+        Ok(libraries)
+    }
+
+    pub(super) fn parse_delay_imports(&self) -> BinDumpResult<Vec<DelayImportedLibrary>> {
+        let mut input = match self.directory_start(DirectoryEntry::DelayImport) {
+            Some(input) => input,
+            None => return Ok(Vec::new()),
+        };
+        let image_base = self.optional_header.image_base;
+        let mut libraries = Vec::new();
+        loop {
+            let (
+                rest,
+                (
+                    attributes,
+                    name,
+                    module_handle,
+                    iat,
+                    name_table,
+                    bound_iat,
+                    unload_table,
+                    time_date_stamp,
+                ),
+            ) = context(
+                "Parse Delay Import Descriptor",
+                tuple((
+                    le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32,
+                )),
+            )(input)?;
+            input = rest;
+            if name == 0 && iat == 0 && name_table == 0 {
+                break;
+            }
+            // Descriptors from before Visual C++ 7 hold addresses instead of RVAs
+            let rva_based = attributes & DELAY_ATTRIBUTE_RVA_BASED != 0;
+            let to_rva = |value: u64| {
+                if rva_based || value == 0 {
+                    value
+                } else {
+                    value.wrapping_sub(image_base)
+                }
+            };
+            let rva = |value: u32| to_rva(value as u64) as u32;
+            libraries.push(DelayImportedLibrary {
+                attributes,
+                name: self.name_at(rva(name))?,
+                module_handle_rva: rva(module_handle),
+                iat_rva: rva(iat),
+                name_table_rva: rva(name_table),
+                bound_iat_rva: rva(bound_iat),
+                unload_table_rva: rva(unload_table),
+                time_date_stamp,
+                functions: self.parse_thunks(rva(name_table), rva(iat), to_rva)?,
+            });
+        }
+        Ok(libraries)
+    }
+
+    pub(super) fn parse_bound_imports(&self) -> BinDumpResult<Vec<BoundImport>> {
+        let directory = match self.directory_data(DirectoryEntry::BoundImport) {
+            Some(directory) => directory,
+            None => return Ok(Vec::new()),
+        };
+        let descriptor = |input| {
+            context(
+                "Parse Bound Import Descriptor",
+                tuple((le_u32, le_u16, le_u16)),
+            )(input)
+        };
+        // Names are offsets from the start of the directory
+        let mut input = directory;
+        let mut imports = Vec::new();
+        loop {
+            let (rest, (time_date_stamp, name, number_of_forwarder_refs)) = descriptor(input)?;
+            input = rest;
+            if time_date_stamp == 0 && name == 0 && number_of_forwarder_refs == 0 {
+                break;
+            }
+            let mut forwarder_refs = Vec::new();
+            for _ in 0..number_of_forwarder_refs {
+                let (rest, (time_date_stamp, name, _reserved)) = descriptor(input)?;
+                input = rest;
+                forwarder_refs.push(BoundForwarderRef {
+                    time_date_stamp,
+                    name: string_at(directory, name as usize),
+                });
+            }
+            imports.push(BoundImport {
+                time_date_stamp,
+                name: string_at(directory, name as usize),
+                forwarder_refs,
+            });
+        }
+        Ok(imports)
+    }
+}
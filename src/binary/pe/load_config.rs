@@ -0,0 +1,717 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+
+use super::header::{flag_names, word, DirectoryEntry, Machine};
+use super::Pe;
+use crate::error::BinDumpResult;
+use crate::parse;
+
+/// Room for every field of the newest layout, which older and shorter ones are padded to
+const MAX_LOAD_CONFIG_SIZE: usize = 0x200;
+/// The bits of `GuardFlags` with the number of extra bytes in each guard table entry
+const GUARD_CF_FUNCTION_TABLE_SIZE_MASK: u32 = 0xf000_0000;
+
+/// Names of the `GuardFlags` bits
+const GUARD_FLAGS: [(u32, &str); 17] = [
+    (0x0000_0100, "CF_INSTRUMENTED"),
+    (0x0000_0200, "CFW_INSTRUMENTED"),
+    (0x0000_0400, "CF_FUNCTION_TABLE_PRESENT"),
+    (0x0000_0800, "SECURITY_COOKIE_UNUSED"),
+    (0x0000_1000, "PROTECT_DELAYLOAD_IAT"),
+    (0x0000_2000, "DELAYLOAD_IAT_IN_ITS_OWN_SECTION"),
+    (0x0000_4000, "CF_EXPORT_SUPPRESSION_INFO_PRESENT"),
+    (0x0000_8000, "CF_ENABLE_EXPORT_SUPPRESSION"),
+    (0x0001_0000, "CF_LONGJUMP_TABLE_PRESENT"),
+    (0x0002_0000, "RF_INSTRUMENTED"),
+    (0x0004_0000, "RF_ENABLE"),
+    (0x0008_0000, "RF_STRICT"),
+    (0x0010_0000, "RETPOLINE_PRESENT"),
+    (0x0040_0000, "EH_CONTINUATION_TABLE_PRESENT"),
+    (0x0080_0000, "XFG_ENABLED"),
+    (0x0100_0000, "CASTGUARD_PRESENT"),
+    (0x0200_0000, "MEMCPY_PRESENT"),
+];
+
+/// Names of the flags in the extra byte of guard table entries
+const GUARD_FUNCTION_FLAGS: [(u8, &str); 4] = [
+    (0x1, "FID_SUPPRESSED"),
+    (0x2, "EXPORT_SUPPRESSED"),
+    (0x4, "FID_LANGEXCPTHANDLER"),
+    (0x8, "FID_XFG"),
+];
+
+/// An entry of one of the control flow guard tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuardFunction {
+    pub rva: u32,
+    /// The first extra byte of the entry, if the entries have one
+    pub flags: u8,
+}
+
+impl fmt::Display for GuardFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.rva)?;
+        if self.flags != 0 {
+            write!(
+                f,
+                " ({})",
+                flag_names(self.flags, &GUARD_FUNCTION_FLAGS).join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A range of the code map of hybrid images
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeRange {
+    pub start_rva: u32,
+    pub length: u32,
+    /// For ARM64EC 0 is ARM64, 1 ARM64EC and 2 x64 code. For x86 CHPE 1 is ARM64 code
+    pub kind: u8,
+}
+
+/// The start of `IMAGE_ARM64EC_METADATA` or `IMAGE_CHPE_METADATA_X86`, which share the layout
+/// of the version and the code map
+#[derive(Debug)]
+pub struct ChpeMetadata {
+    pub version: u32,
+    pub code_map_rva: u32,
+    pub code_map: Vec<CodeRange>,
+}
+
+/// `IMAGE_LOAD_CONFIG_DIRECTORY`, where fields past `size` aren't in the image and read as 0.
+/// Addresses are virtual addresses
+#[derive(Debug)]
+pub struct LoadConfig {
+    pub size: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub global_flags_clear: u32,
+    pub global_flags_set: u32,
+    pub critical_section_default_timeout: u32,
+    pub de_commit_free_block_threshold: u64,
+    pub de_commit_total_free_threshold: u64,
+    pub lock_prefix_table: u64,
+    pub maximum_allocation_size: u64,
+    pub virtual_memory_threshold: u64,
+    pub process_affinity_mask: u64,
+    pub process_heap_flags: u32,
+    pub csd_version: u16,
+    pub dependent_load_flags: u16,
+    pub edit_list: u64,
+    pub security_cookie: u64,
+    pub se_handler_table: u64,
+    pub se_handler_count: u64,
+    pub guard_cf_check_function_pointer: u64,
+    pub guard_cf_dispatch_function_pointer: u64,
+    pub guard_cf_function_table: u64,
+    pub guard_cf_function_count: u64,
+    pub guard_flags: u32,
+    pub code_integrity_flags: u16,
+    pub code_integrity_catalog: u16,
+    pub code_integrity_catalog_offset: u32,
+    pub guard_address_taken_iat_entry_table: u64,
+    pub guard_address_taken_iat_entry_count: u64,
+    pub guard_long_jump_target_table: u64,
+    pub guard_long_jump_target_count: u64,
+    pub dynamic_value_reloc_table: u64,
+    pub chpe_metadata_pointer: u64,
+    pub guard_rf_failure_routine: u64,
+    pub guard_rf_failure_routine_function_pointer: u64,
+    pub dynamic_value_reloc_table_offset: u32,
+    pub dynamic_value_reloc_table_section: u16,
+    pub guard_rf_verify_stack_pointer_function_pointer: u64,
+    pub hot_patch_table_offset: u32,
+    pub enclave_configuration_pointer: u64,
+    pub volatile_metadata_pointer: u64,
+    /// The valid targets of exception handler continuations, for CET shadow stacks
+    pub guard_eh_continuation_table: u64,
+    pub guard_eh_continuation_count: u64,
+    pub guard_xfg_check_function_pointer: u64,
+    pub guard_xfg_dispatch_function_pointer: u64,
+    pub guard_xfg_table_dispatch_function_pointer: u64,
+    pub cast_guard_os_determined_failure_mode: u64,
+    pub guard_memcpy_function_pointer: u64,
+    /// The RVAs of the safe exception handlers, in 32 bit x86 images
+    pub se_handlers: Vec<u32>,
+    pub guard_functions: Vec<GuardFunction>,
+    pub guard_address_taken_iat_entries: Vec<GuardFunction>,
+    pub guard_long_jump_targets: Vec<GuardFunction>,
+    pub guard_eh_continuations: Vec<GuardFunction>,
+    pub chpe_metadata: Option<ChpeMetadata>,
+}
+
+impl LoadConfig {
+    pub fn guard_flag_names(&self) -> Vec<&'static str> {
+        flag_names(self.guard_flags, &GUARD_FLAGS)
+    }
+}
+
+impl Pe {
+    pub(super) fn parse_load_config(&self) -> Option<BinDumpResult<LoadConfig>> {
+        let input = self.directory_start(DirectoryEntry::LoadConfig)?;
+        Some(self.parse_load_config_directory(input))
+    }
+
+    fn parse_load_config_directory(&self, input: &[u8]) -> BinDumpResult<LoadConfig> {
+        let is_64_bit = self.optional_header.is_64_bit();
+        let word = word(is_64_bit);
+        // The structure grew with each release, and its first field says how much of it there is
+        let (_, size) = context("Parse Load Config Size", le_u32)(input)?;
+        let mut padded = input[..(size as usize).min(input.len())].to_vec();
+        padded.resize(padded.len().max(MAX_LOAD_CONFIG_SIZE), 0);
+        let input = &padded[4..];
+
+        let (
+            input,
+            (
+                time_date_stamp,
+                major_version,
+                minor_version,
+                global_flags_clear,
+                global_flags_set,
+                critical_section_default_timeout,
+                de_commit_free_block_threshold,
+                de_commit_total_free_threshold,
+                lock_prefix_table,
+                maximum_allocation_size,
+                virtual_memory_threshold,
+            ),
+        ) = context(
+            "Parse Load Config",
+            tuple((
+                le_u32, le_u16, le_u16, le_u32, le_u32, le_u32, word, word, word, word, word,
+            )),
+        )(input)?;
+        // PE32+ swapped the order of these two fields
+        let (input, (process_affinity_mask, process_heap_flags)) = if is_64_bit {
+            context("Parse Load Config", tuple((word, le_u32)))(input)?
+        } else {
+            let (input, (process_heap_flags, process_affinity_mask)) =
+                context("Parse Load Config", tuple((le_u32, word)))(input)?;
+            (input, (process_affinity_mask, process_heap_flags))
+        };
+        let (
+            input,
+            (
+                csd_version,
+                dependent_load_flags,
+                edit_list,
+                security_cookie,
+                se_handler_table,
+                se_handler_count,
+                guard_cf_check_function_pointer,
+                guard_cf_dispatch_function_pointer,
+                guard_cf_function_table,
+                guard_cf_function_count,
+                guard_flags,
+                code_integrity_flags,
+                code_integrity_catalog,
+                code_integrity_catalog_offset,
+                _reserved,
+            ),
+        ) = context(
+            "Parse Load Config Guard Fields",
+            tuple((
+                le_u16, le_u16, word, word, word, word, word, word, word, word, le_u32, le_u16,
+                le_u16, le_u32, le_u32,
+            )),
+        )(input)?;
+        let (
+            input,
+            (
+                guard_address_taken_iat_entry_table,
+                guard_address_taken_iat_entry_count,
+                guard_long_jump_target_table,
+                guard_long_jump_target_count,
+                dynamic_value_reloc_table,
+                chpe_metadata_pointer,
+                guard_rf_failure_routine,
+                guard_rf_failure_routine_function_pointer,
+                dynamic_value_reloc_table_offset,
+                dynamic_value_reloc_table_section,
+                _reserved2,
+                guard_rf_verify_stack_pointer_function_pointer,
+                hot_patch_table_offset,
+                _reserved3,
+            ),
+        ) = context(
+            "Parse Load Config Guard Fields",
+            tuple((
+                word, word, word, word, word, word, word, word, le_u32, le_u16, le_u16, word,
+                le_u32, le_u32,
+            )),
+        )(input)?;
+        let (
+            _,
+            (
+                enclave_configuration_pointer,
+                volatile_metadata_pointer,
+                guard_eh_continuation_table,
+                guard_eh_continuation_count,
+                guard_xfg_check_function_pointer,
+                guard_xfg_dispatch_function_pointer,
+                guard_xfg_table_dispatch_function_pointer,
+                cast_guard_os_determined_failure_mode,
+                guard_memcpy_function_pointer,
+            ),
+        ) = context(
+            "Parse Load Config XFG Fields",
+            tuple((word, word, word, word, word, word, word, word, word)),
+        )(input)?;
+
+        let se_handlers = if self.coff_header.machine == Machine::I386 {
+            let table = self.data_at_va(se_handler_table).unwrap_or_default();
+            table
+                .chunks_exact(4)
+                .take(se_handler_count as usize)
+                .map(|rva| u32::from_le_bytes([rva[0], rva[1], rva[2], rva[3]]))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let extra = ((guard_flags & GUARD_CF_FUNCTION_TABLE_SIZE_MASK) >> 28) as usize;
+        let guard_table = |table: u64, count: u64| self.guard_table(table, count, extra);
+
+        Ok(LoadConfig {
+            size,
+            time_date_stamp,
+            major_version,
+            minor_version,
+            global_flags_clear,
+            global_flags_set,
+            critical_section_default_timeout,
+            de_commit_free_block_threshold,
+            de_commit_total_free_threshold,
+            lock_prefix_table,
+            maximum_allocation_size,
+            virtual_memory_threshold,
+            process_affinity_mask,
+            process_heap_flags,
+            csd_version,
+            dependent_load_flags,
+            edit_list,
+            security_cookie,
+            se_handler_table,
+            se_handler_count,
+            guard_cf_check_function_pointer,
+            guard_cf_dispatch_function_pointer,
+            guard_cf_function_table,
+            guard_cf_function_count,
+            guard_flags,
+            code_integrity_flags,
+            code_integrity_catalog,
+            code_integrity_catalog_offset,
+            guard_address_taken_iat_entry_table,
+            guard_address_taken_iat_entry_count,
+            guard_long_jump_target_table,
+            guard_long_jump_target_count,
+            dynamic_value_reloc_table,
+            chpe_metadata_pointer,
+            guard_rf_failure_routine,
+            guard_rf_failure_routine_function_pointer,
+            dynamic_value_reloc_table_offset,
+            dynamic_value_reloc_table_section,
+            guard_rf_verify_stack_pointer_function_pointer,
+            hot_patch_table_offset,
+            enclave_configuration_pointer,
+            volatile_metadata_pointer,
+            guard_eh_continuation_table,
+            guard_eh_continuation_count,
+            guard_xfg_check_function_pointer,
+            guard_xfg_dispatch_function_pointer,
+            guard_xfg_table_dispatch_function_pointer,
+            cast_guard_os_determined_failure_mode,
+            guard_memcpy_function_pointer,
+            se_handlers,
+            guard_functions: guard_table(guard_cf_function_table, guard_cf_function_count),
+            guard_address_taken_iat_entries: guard_table(
+                guard_address_taken_iat_entry_table,
+                guard_address_taken_iat_entry_count,
+            ),
+            guard_long_jump_targets: guard_table(
+                guard_long_jump_target_table,
+                guard_long_jump_target_count,
+            ),
+            guard_eh_continuations: guard_table(
+                guard_eh_continuation_table,
+                guard_eh_continuation_count,
+            ),
+            chpe_metadata: self.parse_chpe_metadata(chpe_metadata_pointer),
+        })
+    }
+
+    /// Reads a guard table, whose entries are an RVA followed by `extra` bytes of flags
+    fn guard_table(&self, table: u64, count: u64, extra: usize) -> Vec<GuardFunction> {
+        let table = self.data_at_va(table).unwrap_or_default();
+        table
+            .chunks_exact(4 + extra)
+            .take(count as usize)
+            .map(|entry| GuardFunction {
+                rva: u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]),
+                flags: entry.get(4).copied().unwrap_or(0),
+            })
+            .collect()
+    }
+
+    fn parse_chpe_metadata(&self, pointer: u64) -> Option<ChpeMetadata> {
+        let input = self.data_at_va(pointer)?;
+        let header: parse::ParseResult<_> =
+            context("Parse CHPE Metadata", tuple((le_u32, le_u32, le_u32)))(input);
+        let (_, (version, code_map_rva, code_map_count)) = header.ok()?;
+        let code_map = self
+            .data_at_rva(code_map_rva)
+            .unwrap_or_default()
+            .chunks_exact(8)
+            .take(code_map_count as usize)
+            .map(|entry| {
+                let start = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+                CodeRange {
+                    start_rva: start & !3,
+                    length: u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]),
+                    kind: (start & 3) as u8,
+                }
+            })
+            .collect();
+        Some(ChpeMetadata {
+            version,
+            code_map_rva,
+            code_map,
+        })
+    }
+
+    pub fn load_config(&self) -> Option<&LoadConfig> {
+        self.load_config.as_ref()?.as_ref().ok()
+    }
+}
+
+impl fmt::Display for LoadConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Size: {:#x}", self.size)?;
+        writeln!(f, "Time Date Stamp: {:#x}", self.time_date_stamp)?;
+        writeln!(f, "Version: {}.{}", self.major_version, self.minor_version)?;
+        writeln!(f, "Global Flags Clear: {:#x}", self.global_flags_clear)?;
+        writeln!(f, "Global Flags Set: {:#x}", self.global_flags_set)?;
+        writeln!(
+            f,
+            "Critical Section Default Timeout: {}",
+            self.critical_section_default_timeout
+        )?;
+        writeln!(
+            f,
+            "DeCommit Free Block Threshold: {:#x}",
+            self.de_commit_free_block_threshold
+        )?;
+        writeln!(
+            f,
+            "DeCommit Total Free Threshold: {:#x}",
+            self.de_commit_total_free_threshold
+        )?;
+        writeln!(f, "Lock Prefix Table: {:#x}", self.lock_prefix_table)?;
+        writeln!(
+            f,
+            "Maximum Allocation Size: {:#x}",
+            self.maximum_allocation_size
+        )?;
+        writeln!(
+            f,
+            "Virtual Memory Threshold: {:#x}",
+            self.virtual_memory_threshold
+        )?;
+        writeln!(
+            f,
+            "Process Affinity Mask: {:#x}",
+            self.process_affinity_mask
+        )?;
+        writeln!(f, "Process Heap Flags: {:#x}", self.process_heap_flags)?;
+        writeln!(f, "CSD Version: {:#x}", self.csd_version)?;
+        writeln!(f, "Dependent Load Flags: {:#x}", self.dependent_load_flags)?;
+        writeln!(f, "Edit List: {:#x}", self.edit_list)?;
+        writeln!(f, "Security Cookie: {:#x}", self.security_cookie)?;
+        writeln!(f, "SE Handler Table: {:#x}", self.se_handler_table)?;
+        writeln!(f, "SE Handler Count: {}", self.se_handler_count)?;
+        writeln!(
+            f,
+            "Guard CF Check Function Pointer: {:#x}",
+            self.guard_cf_check_function_pointer
+        )?;
+        writeln!(
+            f,
+            "Guard CF Dispatch Function Pointer: {:#x}",
+            self.guard_cf_dispatch_function_pointer
+        )?;
+        writeln!(
+            f,
+            "Guard CF Function Table: {:#x}",
+            self.guard_cf_function_table
+        )?;
+        writeln!(
+            f,
+            "Guard CF Function Count: {}",
+            self.guard_cf_function_count
+        )?;
+        writeln!(
+            f,
+            "Guard Flags: {:#x} ({})",
+            self.guard_flags,
+            self.guard_flag_names().join(", ")
+        )?;
+        writeln!(
+            f,
+            "Code Integrity: Flags {:#x}, Catalog {}, Catalog Offset {:#x}",
+            self.code_integrity_flags,
+            self.code_integrity_catalog,
+            self.code_integrity_catalog_offset
+        )?;
+        writeln!(
+            f,
+            "Guard Address Taken IAT Entry Table: {:#x}",
+            self.guard_address_taken_iat_entry_table
+        )?;
+        writeln!(
+            f,
+            "Guard Address Taken IAT Entry Count: {}",
+            self.guard_address_taken_iat_entry_count
+        )?;
+        writeln!(
+            f,
+            "Guard Long Jump Target Table: {:#x}",
+            self.guard_long_jump_target_table
+        )?;
+        writeln!(
+            f,
+            "Guard Long Jump Target Count: {}",
+            self.guard_long_jump_target_count
+        )?;
+        writeln!(
+            f,
+            "Dynamic Value Reloc Table: {:#x}",
+            self.dynamic_value_reloc_table
+        )?;
+        writeln!(
+            f,
+            "CHPE Metadata Pointer: {:#x}",
+            self.chpe_metadata_pointer
+        )?;
+        writeln!(
+            f,
+            "Guard RF Failure Routine: {:#x}",
+            self.guard_rf_failure_routine
+        )?;
+        writeln!(
+            f,
+            "Guard RF Failure Routine Function Pointer: {:#x}",
+            self.guard_rf_failure_routine_function_pointer
+        )?;
+        writeln!(
+            f,
+            "Dynamic Value Reloc Table Offset: {:#x}",
+            self.dynamic_value_reloc_table_offset
+        )?;
+        writeln!(
+            f,
+            "Dynamic Value Reloc Table Section: {}",
+            self.dynamic_value_reloc_table_section
+        )?;
+        writeln!(
+            f,
+            "Guard RF Verify Stack Pointer Function Pointer: {:#x}",
+            self.guard_rf_verify_stack_pointer_function_pointer
+        )?;
+        writeln!(
+            f,
+            "Hot Patch Table Offset: {:#x}",
+            self.hot_patch_table_offset
+        )?;
+        writeln!(
+            f,
+            "Enclave Configuration Pointer: {:#x}",
+            self.enclave_configuration_pointer
+        )?;
+        writeln!(
+            f,
+            "Volatile Metadata Pointer: {:#x}",
+            self.volatile_metadata_pointer
+        )?;
+        writeln!(
+            f,
+            "Guard EH Continuation Table: {:#x}",
+            self.guard_eh_continuation_table
+        )?;
+        writeln!(
+            f,
+            "Guard EH Continuation Count: {}",
+            self.guard_eh_continuation_count
+        )?;
+        writeln!(
+            f,
+            "Guard XFG Check Function Pointer: {:#x}",
+            self.guard_xfg_check_function_pointer
+        )?;
+        writeln!(
+            f,
+            "Guard XFG Dispatch Function Pointer: {:#x}",
+            self.guard_xfg_dispatch_function_pointer
+        )?;
+        writeln!(
+            f,
+            "Guard XFG Table Dispatch Function Pointer: {:#x}",
+            self.guard_xfg_table_dispatch_function_pointer
+        )?;
+        writeln!(
+            f,
+            "Cast Guard OS Determined Failure Mode: {:#x}",
+            self.cast_guard_os_determined_failure_mode
+        )?;
+        writeln!(
+            f,
+            "Guard Memcpy Function Pointer: {:#x}",
+            self.guard_memcpy_function_pointer
+        )?;
+        if !self.se_handlers.is_empty() {
+            writeln!(f, "SE Handlers:")?;
+            for handler in &self.se_handlers {
+                writeln!(f, "  {:#x}", handler)?;
+            }
+        }
+        let tables = [
+            ("Guard Functions", &self.guard_functions),
+            (
+                "Guard Address Taken IAT Entries",
+                &self.guard_address_taken_iat_entries,
+            ),
+            ("Guard Long Jump Targets", &self.guard_long_jump_targets),
+            ("Guard EH Continuations", &self.guard_eh_continuations),
+        ];
+        for (name, table) in tables {
+            if table.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}:", name)?;
+            for function in table {
+                writeln!(f, "  {}", function)?;
+            }
+        }
+        if let Some(chpe) = &self.chpe_metadata {
+            writeln!(f, "CHPE Metadata Version: {}", chpe.version)?;
+            writeln!(f, "Code Map: {:#x}", chpe.code_map_rva)?;
+            for range in &chpe.code_map {
+                writeln!(
+                    f,
+                    "  {:#x}-{:#x}: Kind {}",
+                    range.start_rva,
+                    range.start_rva.wrapping_add(range.length),
+                    range.kind
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::image;
+    use super::*;
+
+    const IMAGE_BASE: u64 = 0x1_4000_0000;
+
+    /// The test image with a guard table at 0x180 and CHPE metadata at 0x1a0 in its headers
+    fn guarded_image() -> Pe {
+        let mut data = image();
+        data.resize(0x180, 0);
+        data.extend_from_slice(&[0x00, 0x10, 0, 0, 0x1, 0x00, 0x11, 0, 0, 0]);
+        data.resize(0x1a0, 0);
+        for field in [1u32, 0x1b0, 2, 0] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [0x1001u32, 0x100, 0x2002, 0x80] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        Pe::load(data).unwrap()
+    }
+
+    /// A PE32+ load config of `size` bytes, with fields past it set to show they're ignored
+    fn load_config(size: u32) -> Vec<u8> {
+        let mut data = vec![0; 0x140];
+        let mut set =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        set(0, &size.to_le_bytes());
+        set(64, &0xffu64.to_le_bytes());
+        set(72, &0x4u32.to_le_bytes());
+        set(88, &(IMAGE_BASE + 0x3000).to_le_bytes());
+        set(128, &(IMAGE_BASE + 0x180).to_le_bytes());
+        set(136, &2u64.to_le_bytes());
+        set(144, &0x1000_0500u32.to_le_bytes());
+        set(176, &(IMAGE_BASE + 0x180).to_le_bytes());
+        set(184, &1u64.to_le_bytes());
+        set(200, &(IMAGE_BASE + 0x1a0).to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn reads_guard_tables_and_chpe_metadata() {
+        let pe = guarded_image();
+        let config = pe.parse_load_config_directory(&load_config(0x140)).unwrap();
+        assert_eq!(config.process_affinity_mask, 0xff);
+        assert_eq!(config.process_heap_flags, 4);
+        assert_eq!(config.security_cookie, IMAGE_BASE + 0x3000);
+        assert_eq!(
+            config.guard_flag_names(),
+            ["CF_INSTRUMENTED", "CF_FUNCTION_TABLE_PRESENT"]
+        );
+        // Each entry has one byte of flags after the RVA
+        assert_eq!(
+            config.guard_functions,
+            [
+                GuardFunction {
+                    rva: 0x1000,
+                    flags: 1
+                },
+                GuardFunction {
+                    rva: 0x1100,
+                    flags: 0
+                },
+            ]
+        );
+        assert_eq!(
+            config.guard_functions[0].to_string(),
+            "0x1000 (FID_SUPPRESSED)"
+        );
+        assert_eq!(config.guard_long_jump_targets.len(), 1);
+
+        let chpe = config.chpe_metadata.unwrap();
+        assert_eq!(chpe.version, 1);
+        assert_eq!(
+            chpe.code_map,
+            [
+                CodeRange {
+                    start_rva: 0x1000,
+                    length: 0x100,
+                    kind: 1
+                },
+                CodeRange {
+                    start_rva: 0x2000,
+                    length: 0x80,
+                    kind: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn fields_past_size_read_as_zero() {
+        let pe = guarded_image();
+        let config = pe.parse_load_config_directory(&load_config(0x94)).unwrap();
+        assert_eq!(config.size, 0x94);
+        assert_eq!(config.guard_flags, 0x1000_0500);
+        assert_eq!(config.guard_long_jump_target_table, 0);
+        assert!(config.guard_long_jump_targets.is_empty());
+        assert!(config.chpe_metadata.is_none());
+    }
+}
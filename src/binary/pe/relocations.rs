@@ -0,0 +1,214 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::number::complete::le_u32;
+use nom::sequence::tuple;
+
+use super::header::{DirectoryEntry, Machine};
+use super::Pe;
+use crate::error::BinDumpResult;
+use crate::parse::failure;
+
+/// The type of a base relocation. Types 5, 7 and 8 mean different things on each machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseRelocationType {
+    /// Padding that aligns blocks to 4 bytes
+    Absolute,
+    High,
+    Low,
+    HighLow,
+    /// The high 16 bits, with the low 16 bits in the following entry
+    HighAdj,
+    MipsJmpAddr,
+    ArmMov32,
+    RiscVHigh20,
+    ThumbMov32,
+    RiscVLow12I,
+    RiscVLow12S,
+    LoongArchMarkLa,
+    MipsJmpAddr16,
+    Dir64,
+    Other(u8),
+}
+
+impl BaseRelocationType {
+    pub fn new(typ: u8, machine: Machine) -> Self {
+        use BaseRelocationType::*;
+        match (typ, machine) {
+            (0, _) => Absolute,
+            (1, _) => High,
+            (2, _) => Low,
+            (3, _) => HighLow,
+            (4, _) => HighAdj,
+            (5, Machine::R4000 | Machine::WceMipsV2 | Machine::Mips16) => MipsJmpAddr,
+            (5, Machine::Arm | Machine::Thumb | Machine::ArmNt) => ArmMov32,
+            (5, Machine::RiscV32 | Machine::RiscV64) => RiscVHigh20,
+            (7, Machine::Arm | Machine::Thumb | Machine::ArmNt) => ThumbMov32,
+            (7, Machine::RiscV32 | Machine::RiscV64) => RiscVLow12I,
+            (8, Machine::RiscV32 | Machine::RiscV64) => RiscVLow12S,
+            (8, Machine::LoongArch64) => LoongArchMarkLa,
+            (9, Machine::R4000 | Machine::WceMipsV2 | Machine::Mips16) => MipsJmpAddr16,
+            (10, _) => Dir64,
+            (typ, _) => Other(typ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BaseRelocation {
+    pub rva: u32,
+    pub typ: BaseRelocationType,
+    /// The extra entry of `HighAdj` relocations
+    pub parameter: Option<u16>,
+}
+
+impl fmt::Display for BaseRelocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}: {:?}", self.rva, self.typ)?;
+        if let Some(parameter) = self.parameter {
+            write!(f, " ({:#x})", parameter)?;
+        }
+        Ok(())
+    }
+}
+
+/// The relocations of one 4K page
+#[derive(Debug)]
+pub struct BaseRelocationBlock {
+    pub page_rva: u32,
+    pub size: u32,
+    pub relocations: Vec<BaseRelocation>,
+}
+
+impl fmt::Display for BaseRelocationBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Page {:#x} ({} bytes, {} relocations):",
+            self.page_rva,
+            self.size,
+            self.relocations.len()
+        )?;
+        for relocation in &self.relocations {
+            writeln!(f, "  {}", relocation)?;
+        }
+        Ok(())
+    }
+}
+
+impl Pe {
+    pub(super) fn parse_base_relocations(&self) -> Option<BinDumpResult<Vec<BaseRelocationBlock>>> {
+        let input = self.directory_data(DirectoryEntry::BaseRelocation)?;
+        Some(self.parse_base_relocation_blocks(input))
+    }
+
+    fn parse_base_relocation_blocks(
+        &self,
+        mut input: &[u8],
+    ) -> BinDumpResult<Vec<BaseRelocationBlock>> {
+        let machine = self.coff_header.machine;
+        let mut blocks = Vec::new();
+        while input.len() >= 8 {
+            let (rest, (page_rva, size)) =
+                context("Parse Base Relocation Block", tuple((le_u32, le_u32)))(input)?;
+            if size < 8 || size as usize > input.len() {
+                return Err(failure(input, "Invalid Base Relocation Block Size").into());
+            }
+            let mut entries = rest[..size as usize - 8]
+                .chunks_exact(2)
+                .map(|entry| u16::from_le_bytes([entry[0], entry[1]]));
+            let mut relocations = Vec::new();
+            while let Some(entry) = entries.next() {
+                let typ = BaseRelocationType::new((entry >> 12) as u8, machine);
+                let parameter = match typ {
+                    BaseRelocationType::HighAdj => entries.next(),
+                    _ => None,
+                };
+                relocations.push(BaseRelocation {
+                    rva: page_rva.wrapping_add((entry & 0xfff) as u32),
+                    typ,
+                    parameter,
+                });
+            }
+            blocks.push(BaseRelocationBlock {
+                page_rva,
+                size,
+                relocations,
+            });
+            input = &input[size as usize..];
+        }
+        Ok(blocks)
+    }
+
+    /// The base relocation blocks, which are empty for images that can't be moved
+    pub fn base_relocations(&self) -> &[BaseRelocationBlock] {
+        match &self.base_relocations {
+            Some(Ok(blocks)) => blocks,
+            _ => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::image;
+    use super::*;
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn parses_blocks_with_padding_and_high_adj() {
+        let pe = Pe::load(image()).unwrap();
+        let mut data = words(&[0x1000, 16, 0x4030_a010, 0x0000_1234]);
+        data.extend(words(&[0x2000, 10]));
+        data.extend_from_slice(&0x3004u16.to_le_bytes());
+        let blocks = pe.parse_base_relocation_blocks(&data).unwrap();
+        assert_eq!(blocks.len(), 2);
+        let relocation = |rva, typ, parameter| BaseRelocation {
+            rva,
+            typ,
+            parameter,
+        };
+        assert_eq!(
+            blocks[0].relocations,
+            [
+                relocation(0x1010, BaseRelocationType::Dir64, None),
+                relocation(0x1030, BaseRelocationType::HighAdj, Some(0x1234)),
+                relocation(0x1000, BaseRelocationType::Absolute, None),
+            ]
+        );
+        assert_eq!(
+            blocks[1].relocations,
+            [relocation(0x2004, BaseRelocationType::HighLow, None)]
+        );
+        assert_eq!(
+            blocks[0].relocations[1].to_string(),
+            "0x1030: HighAdj (0x1234)"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_block_sizes() {
+        let pe = Pe::load(image()).unwrap();
+        assert!(pe
+            .parse_base_relocation_blocks(&words(&[0x1000, 4]))
+            .is_err());
+        assert!(pe
+            .parse_base_relocation_blocks(&words(&[0x1000, 12]))
+            .is_err());
+    }
+
+    #[test]
+    fn machine_decides_type() {
+        use BaseRelocationType::*;
+        assert_eq!(BaseRelocationType::new(5, Machine::Arm), ArmMov32);
+        assert_eq!(BaseRelocationType::new(5, Machine::RiscV64), RiscVHigh20);
+        assert_eq!(BaseRelocationType::new(5, Machine::Amd64), Other(5));
+        assert_eq!(BaseRelocationType::new(10, Machine::Amd64), Dir64);
+    }
+}
@@ -0,0 +1,655 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::{le_u16, le_u32, u8};
+use nom::sequence::tuple;
+
+use super::header::DirectoryEntry;
+use super::version_info::VersionInfo;
+use super::Pe;
+use crate::error::BinDumpResult;
+use crate::parse::{self, failure};
+
+pub const RT_ICON: u16 = 3;
+pub const RT_STRING: u16 = 6;
+pub const RT_GROUP_ICON: u16 = 14;
+pub const RT_VERSION: u16 = 16;
+pub const RT_MANIFEST: u16 = 24;
+
+/// Set in an entry's name when it's an offset to a string instead of an ID
+const NAME_IS_STRING: u32 = 0x8000_0000;
+/// Set in an entry's offset when it points to a subdirectory instead of a data entry
+const DATA_IS_DIRECTORY: u32 = 0x8000_0000;
+
+/// The name of a predefined resource type
+pub fn resource_type_name(id: u16) -> Option<&'static str> {
+    Some(match id {
+        1 => "CURSOR",
+        2 => "BITMAP",
+        RT_ICON => "ICON",
+        4 => "MENU",
+        5 => "DIALOG",
+        RT_STRING => "STRING",
+        7 => "FONTDIR",
+        8 => "FONT",
+        9 => "ACCELERATOR",
+        10 => "RCDATA",
+        11 => "MESSAGETABLE",
+        12 => "GROUP_CURSOR",
+        RT_GROUP_ICON => "GROUP_ICON",
+        RT_VERSION => "VERSION",
+        17 => "DLGINCLUDE",
+        19 => "PLUGPLAY",
+        20 => "VXD",
+        21 => "ANICURSOR",
+        22 => "ANIICON",
+        23 => "HTML",
+        RT_MANIFEST => "MANIFEST",
+        _ => return None,
+    })
+}
+
+/// Reads `length` UTF-16 code units
+pub(crate) fn utf16_string(length: usize) -> impl Fn(parse::Input) -> parse::ParseResult<String> {
+    move |input| {
+        let (input, units) = count(le_u16, length)(input)?;
+        Ok((input, String::from_utf16_lossy(&units)))
+    }
+}
+
+/// Reads a null terminated UTF-16 string, without the terminator
+pub(crate) fn utf16_string_nul(input: parse::Input) -> parse::ParseResult<String> {
+    let length = input
+        .chunks_exact(2)
+        .position(|unit| unit == [0, 0])
+        .ok_or_else(|| failure(input, "Unterminated UTF-16 String"))?;
+    let (input, string) = utf16_string(length)(input)?;
+    Ok((&input[2..], string))
+}
+
+/// The name of a directory entry, which is either a number or a string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceId {
+    Id(u16),
+    Name(String),
+}
+
+impl ResourceId {
+    pub fn id(&self) -> Option<u16> {
+        match self {
+            ResourceId::Id(id) => Some(*id),
+            ResourceId::Name(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ResourceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceId::Id(id) => write!(f, "{}", id),
+            ResourceId::Name(name) => write!(f, "\"{}\"", name),
+        }
+    }
+}
+
+/// A leaf of the tree, locating the contents of a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceData {
+    pub rva: u32,
+    pub size: u32,
+    pub code_page: u32,
+}
+
+#[derive(Debug)]
+pub enum ResourceNode {
+    Directory(ResourceDirectory),
+    Data(ResourceData),
+}
+
+#[derive(Debug)]
+pub struct ResourceEntry {
+    pub id: ResourceId,
+    pub node: ResourceNode,
+}
+
+/// A table of the resource tree, with the named entries first and then the ones with IDs
+#[derive(Debug)]
+pub struct ResourceDirectory {
+    pub characteristics: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub entries: Vec<ResourceEntry>,
+}
+
+/// A resource found by walking the type, name and language levels of the tree
+#[derive(Debug, Clone, Copy)]
+pub struct Resource<'a> {
+    pub typ: &'a ResourceId,
+    pub name: &'a ResourceId,
+    pub language: &'a ResourceId,
+    pub data: &'a ResourceData,
+}
+
+impl fmt::Display for Resource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.typ.id().and_then(resource_type_name) {
+            Some(name) => write!(f, "{}", name)?,
+            None => write!(f, "{}", self.typ)?,
+        }
+        write!(
+            f,
+            " {} Language {}: RVA {:#x}, {} bytes, Code Page {}",
+            self.name, self.language, self.data.rva, self.data.size, self.data.code_page
+        )
+    }
+}
+
+impl ResourceDirectory {
+    /// Parses the table at `offset` from the start of the resource section, and the tables below.
+    /// Each table can only be used once, which keeps malformed trees from looping
+    fn parse(section: &[u8], offset: usize, seen: &mut HashSet<usize>) -> BinDumpResult<Self> {
+        let input = section.get(offset..).unwrap_or_default();
+        if !seen.insert(offset) {
+            return Err(failure(input, "Resource Directory Used Twice").into());
+        }
+        let (
+            input,
+            (
+                characteristics,
+                time_date_stamp,
+                major_version,
+                minor_version,
+                number_of_named_entries,
+                number_of_id_entries,
+            ),
+        ) = context(
+            "Parse Resource Directory",
+            tuple((le_u32, le_u32, le_u16, le_u16, le_u16, le_u16)),
+        )(input)?;
+        let number_of_entries = number_of_named_entries as usize + number_of_id_entries as usize;
+        let (_, raw_entries) = context(
+            "Parse Resource Directory Entries",
+            count(tuple((le_u32, le_u32)), number_of_entries),
+        )(input)?;
+
+        let mut entries = Vec::with_capacity(raw_entries.len());
+        for (name, target) in raw_entries {
+            let id = if name & NAME_IS_STRING != 0 {
+                let input = section
+                    .get((name & !NAME_IS_STRING) as usize..)
+                    .unwrap_or_default();
+                let (input, length) = context("Parse Resource Name Length", le_u16)(input)?;
+                let (_, name) =
+                    context("Parse Resource Name", utf16_string(length as usize))(input)?;
+                ResourceId::Name(name)
+            } else {
+                ResourceId::Id(name as u16)
+            };
+            let offset = (target & !DATA_IS_DIRECTORY) as usize;
+            let node = if target & DATA_IS_DIRECTORY != 0 {
+                ResourceNode::Directory(Self::parse(section, offset, seen)?)
+            } else {
+                let input = section.get(offset..).unwrap_or_default();
+                let (_, (rva, size, code_page, _reserved)) = context(
+                    "Parse Resource Data Entry",
+                    tuple((le_u32, le_u32, le_u32, le_u32)),
+                )(input)?;
+                ResourceNode::Data(ResourceData {
+                    rva,
+                    size,
+                    code_page,
+                })
+            };
+            entries.push(ResourceEntry { id, node });
+        }
+        Ok(Self {
+            characteristics,
+            time_date_stamp,
+            major_version,
+            minor_version,
+            entries,
+        })
+    }
+
+    fn subdirectories(&self) -> impl Iterator<Item = (&ResourceId, &ResourceDirectory)> {
+        self.entries.iter().filter_map(|entry| match &entry.node {
+            ResourceNode::Directory(directory) => Some((&entry.id, directory)),
+            ResourceNode::Data(_) => None,
+        })
+    }
+
+    /// Every resource below this table, when it's the root of the tree
+    pub fn resources(&self) -> Vec<Resource<'_>> {
+        let mut resources = Vec::new();
+        for (typ, names) in self.subdirectories() {
+            for (name, languages) in names.subdirectories() {
+                for entry in &languages.entries {
+                    if let ResourceNode::Data(data) = &entry.node {
+                        resources.push(Resource {
+                            typ,
+                            name,
+                            language: &entry.id,
+                            data,
+                        });
+                    }
+                }
+            }
+        }
+        resources
+    }
+}
+
+#[derive(Debug)]
+pub struct Manifest {
+    pub name: ResourceId,
+    pub language: ResourceId,
+    pub text: String,
+}
+
+/// An entry of `GRPICONDIR`, describing one image of an icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IconEntry {
+    /// The width in pixels, where 0 means 256
+    pub width: u8,
+    pub height: u8,
+    pub color_count: u8,
+    pub planes: u16,
+    pub bit_count: u16,
+    pub bytes_in_resource: u32,
+    /// The name of the `RT_ICON` resource with the image
+    pub id: u16,
+}
+
+impl IconEntry {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (input, (width, height, color_count, _reserved, planes, bit_count, size, id)) =
+            context(
+                "Parse Group Icon Entry",
+                tuple((u8, u8, u8, u8, le_u16, le_u16, le_u32, le_u16)),
+            )(input)?;
+        Ok((
+            input,
+            Self {
+                width,
+                height,
+                color_count,
+                planes,
+                bit_count,
+                bytes_in_resource: size,
+                id,
+            },
+        ))
+    }
+}
+
+/// An `RT_GROUP_ICON` resource and the images it refers to, put back together as an `.ico` file
+#[derive(Debug)]
+pub struct IconGroup {
+    pub name: ResourceId,
+    pub language: ResourceId,
+    pub entries: Vec<IconEntry>,
+    pub ico: Vec<u8>,
+}
+
+/// A string from an `RT_STRING` table, each of which holds 16 consecutive string IDs
+#[derive(Debug)]
+pub struct ResourceString {
+    pub id: u32,
+    pub language: ResourceId,
+    pub value: String,
+}
+
+/// The resource tree with the resources of the common types decoded
+#[derive(Debug)]
+pub struct Resources {
+    pub root: ResourceDirectory,
+    pub version_info: Option<BinDumpResult<VersionInfo>>,
+    pub manifests: Vec<Manifest>,
+    pub icon_groups: Vec<IconGroup>,
+    pub strings: Vec<ResourceString>,
+}
+
+impl Resources {
+    pub fn version_info(&self) -> Option<&VersionInfo> {
+        self.version_info.as_ref()?.as_ref().ok()
+    }
+}
+
+impl fmt::Display for Resources {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for resource in self.root.resources() {
+            writeln!(f, "  {}", resource)?;
+        }
+        match &self.version_info {
+            Some(Ok(version_info)) => {
+                writeln!(f, "Version Info:")?;
+                write!(f, "{}", version_info)?;
+            }
+            Some(Err(e)) => writeln!(f, "Version Info: Error {}", e)?,
+            None => {}
+        }
+        for manifest in &self.manifests {
+            writeln!(
+                f,
+                "Manifest {} Language {}:",
+                manifest.name, manifest.language
+            )?;
+            writeln!(f, "{}", manifest.text.trim_end())?;
+        }
+        for group in &self.icon_groups {
+            writeln!(
+                f,
+                "Icon Group {} Language {}: {} bytes",
+                group.name,
+                group.language,
+                group.ico.len()
+            )?;
+            for entry in &group.entries {
+                writeln!(
+                    f,
+                    "  Icon {}: {}x{}, {} bit",
+                    entry.id,
+                    if entry.width == 0 {
+                        256
+                    } else {
+                        entry.width as u32
+                    },
+                    if entry.height == 0 {
+                        256
+                    } else {
+                        entry.height as u32
+                    },
+                    entry.bit_count
+                )?;
+            }
+        }
+        if !self.strings.is_empty() {
+            writeln!(f, "Strings:")?;
+            for string in &self.strings {
+                writeln!(
+                    f,
+                    "  {} Language {}: {:?}",
+                    string.id, string.language, string.value
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Pe {
+    pub(super) fn parse_resources(&self) -> Option<BinDumpResult<Resources>> {
+        let section = self.directory_data(DirectoryEntry::Resource)?;
+        Some(self.decode_resources(section))
+    }
+
+    fn decode_resources(&self, section: &[u8]) -> BinDumpResult<Resources> {
+        let root = ResourceDirectory::parse(section, 0, &mut HashSet::new())?;
+        let resources = root.resources();
+        let of_type = |typ: u16| {
+            resources
+                .iter()
+                .filter(move |resource| resource.typ.id() == Some(typ))
+        };
+
+        let version_info = of_type(RT_VERSION)
+            .next()
+            .map(|resource| VersionInfo::parse(self.resource_data(resource.data)));
+        let manifests = of_type(RT_MANIFEST)
+            .map(|resource| {
+                let data = self.resource_data(resource.data);
+                let data = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(data);
+                Manifest {
+                    name: resource.name.clone(),
+                    language: resource.language.clone(),
+                    text: String::from_utf8_lossy(data).into_owned(),
+                }
+            })
+            .collect();
+
+        let mut icon_groups = Vec::new();
+        for group in of_type(RT_GROUP_ICON) {
+            let input = self.resource_data(group.data);
+            let (input, (_reserved, _typ, number_of_entries)) =
+                context("Parse Group Icon Header", tuple((le_u16, le_u16, le_u16)))(input)?;
+            let (_, entries) = context(
+                "Parse Group Icon Entries",
+                count(IconEntry::parse, number_of_entries as usize),
+            )(input)?;
+            // The images are usually in the group's language, but not always
+            let images: Vec<&[u8]> = entries
+                .iter()
+                .map(|entry| {
+                    let icons: Vec<_> = of_type(RT_ICON)
+                        .filter(|icon| icon.name.id() == Some(entry.id))
+                        .collect();
+                    icons
+                        .iter()
+                        .find(|icon| icon.language == group.language)
+                        .or(icons.first())
+                        .map_or(&[][..], |icon| self.resource_data(icon.data))
+                })
+                .collect();
+            icon_groups.push(IconGroup {
+                name: group.name.clone(),
+                language: group.language.clone(),
+                ico: ico_file(&entries, &images),
+                entries,
+            });
+        }
+
+        let mut strings = Vec::new();
+        for table in of_type(RT_STRING) {
+            let block = match table.name.id() {
+                Some(block) if block > 0 => block as u32 - 1,
+                _ => continue,
+            };
+            let mut input = self.resource_data(table.data);
+            for i in 0..16 {
+                let (rest, length) = context("Parse String Length", le_u16)(input)?;
+                let (rest, value) = context("Parse String", utf16_string(length as usize))(rest)?;
+                input = rest;
+                if !value.is_empty() {
+                    strings.push(ResourceString {
+                        id: block * 16 + i,
+                        language: table.language.clone(),
+                        value,
+                    });
+                }
+            }
+        }
+
+        Ok(Resources {
+            root,
+            version_info,
+            manifests,
+            icon_groups,
+            strings,
+        })
+    }
+
+    /// The contents of a resource, cut short if it goes past the end of its section
+    pub fn resource_data(&self, data: &ResourceData) -> &[u8] {
+        let contents = self.data_at_rva(data.rva).unwrap_or_default();
+        &contents[..(data.size as usize).min(contents.len())]
+    }
+
+    pub fn resources(&self) -> Option<&Resources> {
+        self.resources.as_ref()?.as_ref().ok()
+    }
+
+    pub fn version_info(&self) -> Option<&VersionInfo> {
+        self.resources()?.version_info()
+    }
+}
+
+/// Lays out an `.ico` file, whose directory entries hold image offsets where the group's hold IDs
+fn ico_file(entries: &[IconEntry], images: &[&[u8]]) -> Vec<u8> {
+    let mut ico = Vec::new();
+    ico.extend_from_slice(&0u16.to_le_bytes());
+    ico.extend_from_slice(&1u16.to_le_bytes());
+    ico.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    let mut offset = 6 + 16 * entries.len() as u32;
+    for (entry, image) in entries.iter().zip(images) {
+        ico.extend_from_slice(&[entry.width, entry.height, entry.color_count, 0]);
+        ico.extend_from_slice(&entry.planes.to_le_bytes());
+        ico.extend_from_slice(&entry.bit_count.to_le_bytes());
+        ico.extend_from_slice(&(image.len() as u32).to_le_bytes());
+        ico.extend_from_slice(&offset.to_le_bytes());
+        offset += image.len() as u32;
+    }
+    for image in images {
+        ico.extend_from_slice(image);
+    }
+    ico
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::image;
+    use super::*;
+
+    enum Node {
+        Directory(Vec<(u32, Node)>),
+        Data(u32, u32),
+    }
+
+    /// Lays out a node and everything below it, returning its offset in the section
+    fn write(section: &mut Vec<u8>, node: Node) -> u32 {
+        let offset = section.len() as u32;
+        match node {
+            Node::Data(rva, size) => {
+                for field in [rva, size, 0, 0] {
+                    section.extend_from_slice(&field.to_le_bytes());
+                }
+            }
+            Node::Directory(entries) => {
+                let named = entries
+                    .iter()
+                    .filter(|(name, _)| name & NAME_IS_STRING != 0)
+                    .count() as u16;
+                section.extend_from_slice(&[0; 12]);
+                section.extend_from_slice(&named.to_le_bytes());
+                section.extend_from_slice(&(entries.len() as u16 - named).to_le_bytes());
+                let table = section.len();
+                section.resize(table + 8 * entries.len(), 0);
+                for (i, (name, child)) in entries.into_iter().enumerate() {
+                    let is_directory = matches!(child, Node::Directory(_));
+                    let mut target = write(section, child);
+                    if is_directory {
+                        target |= DATA_IS_DIRECTORY;
+                    }
+                    let entry = table + 8 * i;
+                    section[entry..entry + 4].copy_from_slice(&name.to_le_bytes());
+                    section[entry + 4..entry + 8].copy_from_slice(&target.to_le_bytes());
+                }
+            }
+        }
+        offset
+    }
+
+    fn leaf(name: u32, language: u32, rva: u32, size: u32) -> (u32, Node) {
+        let languages = Node::Directory(vec![(language, Node::Data(rva, size))]);
+        (name, languages)
+    }
+
+    /// The test image with resource contents at 0x160 in its headers, and a resource section
+    /// with two icon images, a group icon named "APP", a string table and a manifest
+    fn resource_image() -> (Pe, Vec<u8>) {
+        let mut data = image();
+        data.resize(0x160, 0);
+        data.extend_from_slice(b"\xef\xbb\xbf<assembly/>");
+        data.resize(0x170, 0);
+        data.extend_from_slice(&[1, 2, 3, 4, 9, 9, 9, 9]);
+        for field in [0u16, 1, 1, 0x1010, 0, 1, 32, 4, 0, 1] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.resize(0x190, 0);
+        data.extend_from_slice(&[0, 0, 5, 0]);
+        data.extend("Hello".encode_utf16().flat_map(u16::to_le_bytes));
+        data.resize(0x190 + 42, 0);
+
+        let icons = Node::Directory(vec![
+            (0, Node::Data(0x174, 4)),
+            (0x409, Node::Data(0x170, 4)),
+        ]);
+        let root = Node::Directory(vec![
+            (RT_ICON as u32, Node::Directory(vec![(1, icons)])),
+            (
+                RT_STRING as u32,
+                Node::Directory(vec![leaf(1, 0x409, 0x190, 42)]),
+            ),
+            (
+                RT_GROUP_ICON as u32,
+                Node::Directory(vec![leaf(NAME_IS_STRING | 0x200, 0x409, 0x178, 20)]),
+            ),
+            (
+                RT_MANIFEST as u32,
+                Node::Directory(vec![leaf(1, 0x409, 0x160, 14)]),
+            ),
+        ]);
+        let mut section = Vec::new();
+        write(&mut section, root);
+        section.resize(0x200, 0);
+        section.extend_from_slice(&[3, 0]);
+        section.extend("APP".encode_utf16().flat_map(u16::to_le_bytes));
+        (Pe::load(data).unwrap(), section)
+    }
+
+    #[test]
+    fn walks_the_resource_tree() {
+        let (pe, section) = resource_image();
+        let resources = pe.decode_resources(&section).unwrap();
+        let listed: Vec<String> = resources
+            .root
+            .resources()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                "ICON 1 Language 0: RVA 0x174, 4 bytes, Code Page 0",
+                "ICON 1 Language 1033: RVA 0x170, 4 bytes, Code Page 0",
+                "STRING 1 Language 1033: RVA 0x190, 42 bytes, Code Page 0",
+                "GROUP_ICON \"APP\" Language 1033: RVA 0x178, 20 bytes, Code Page 0",
+                "MANIFEST 1 Language 1033: RVA 0x160, 14 bytes, Code Page 0",
+            ]
+        );
+        assert!(resources.version_info.is_none());
+    }
+
+    #[test]
+    fn decodes_manifests_icons_and_strings() {
+        let (pe, section) = resource_image();
+        let resources = pe.decode_resources(&section).unwrap();
+        assert_eq!(resources.manifests.len(), 1);
+        assert_eq!(resources.manifests[0].text, "<assembly/>");
+
+        let group = &resources.icon_groups[0];
+        assert_eq!(group.name, ResourceId::Name("APP".to_string()));
+        assert_eq!(group.entries[0].id, 1);
+        assert_eq!(group.entries[0].bit_count, 32);
+        // The image in the group's language is picked over the neutral one
+        let mut ico = vec![0, 0, 1, 0, 1, 0];
+        ico.extend_from_slice(&[16, 16, 0, 0, 1, 0, 32, 0, 4, 0, 0, 0, 22, 0, 0, 0]);
+        ico.extend_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(group.ico, ico);
+
+        assert_eq!(resources.strings.len(), 1);
+        assert_eq!(resources.strings[0].id, 1);
+        assert_eq!(resources.strings[0].value, "Hello");
+    }
+
+    #[test]
+    fn rejects_directories_used_twice() {
+        let mut section = Vec::new();
+        write(&mut section, Node::Directory(vec![(1, Node::Data(0, 0))]));
+        // Point the only entry back at the root
+        section[20..24].copy_from_slice(&DATA_IS_DIRECTORY.to_le_bytes());
+        assert!(ResourceDirectory::parse(&section, 0, &mut HashSet::new()).is_err());
+    }
+}
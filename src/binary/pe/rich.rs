@@ -0,0 +1,490 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::multi::count;
+use nom::number::complete::le_u32;
+use nom::sequence::tuple;
+
+use super::Pe;
+use crate::error::{BinDumpError, BinDumpResult};
+
+const RICH_SIGNATURE: &[u8] = b"Rich";
+/// "DanS", which starts the header once decoded
+const DANS_SIGNATURE: u32 = 0x536e6144;
+/// The offset of `e_lfanew`, which the checksum of the header leaves out
+const PE_HEADER_OFFSET_FIELD: usize = 0x3c;
+
+/// The names of the product IDs, as the linker calls them
+const PRODUCT_NAMES: [&str; 0x10f] = [
+    "Unknown",
+    "Import0",
+    "Linker510",
+    "Cvtomf510",
+    "Linker600",
+    "Cvtomf600",
+    "Cvtres500",
+    "Utc11_Basic",
+    "Utc11_C",
+    "Utc12_Basic",
+    "Utc12_C",
+    "Utc12_CPP",
+    "AliasObj60",
+    "VisualBasic60",
+    "Masm613",
+    "Masm710",
+    "Linker511",
+    "Cvtomf511",
+    "Masm614",
+    "Linker512",
+    "Cvtomf512",
+    "Utc12_C_Std",
+    "Utc12_CPP_Std",
+    "Utc12_C_Book",
+    "Utc12_CPP_Book",
+    "Implib700",
+    "Cvtomf700",
+    "Utc13_Basic",
+    "Utc13_C",
+    "Utc13_CPP",
+    "Linker610",
+    "Cvtomf610",
+    "Linker601",
+    "Cvtomf601",
+    "Utc12_1_Basic",
+    "Utc12_1_C",
+    "Utc12_1_CPP",
+    "Linker620",
+    "Cvtomf620",
+    "AliasObj70",
+    "Linker621",
+    "Cvtomf621",
+    "Masm615",
+    "Utc13_LTCG_C",
+    "Utc13_LTCG_CPP",
+    "Masm620",
+    "ILAsm100",
+    "Utc12_2_Basic",
+    "Utc12_2_C",
+    "Utc12_2_CPP",
+    "Utc12_2_C_Std",
+    "Utc12_2_CPP_Std",
+    "Utc12_2_C_Book",
+    "Utc12_2_CPP_Book",
+    "Implib622",
+    "Cvtomf622",
+    "Cvtres501",
+    "Utc13_C_Std",
+    "Utc13_CPP_Std",
+    "Cvtpgd1300",
+    "Linker622",
+    "Linker700",
+    "Export622",
+    "Export700",
+    "Masm700",
+    "Utc13_POGO_I_C",
+    "Utc13_POGO_I_CPP",
+    "Utc13_POGO_O_C",
+    "Utc13_POGO_O_CPP",
+    "Cvtres700",
+    "Cvtres710p",
+    "Linker710p",
+    "Cvtomf710p",
+    "Export710p",
+    "Implib710p",
+    "Masm710p",
+    "Utc1310p_C",
+    "Utc1310p_CPP",
+    "Utc1310p_C_Std",
+    "Utc1310p_CPP_Std",
+    "Utc1310p_LTCG_C",
+    "Utc1310p_LTCG_CPP",
+    "Utc1310p_POGO_I_C",
+    "Utc1310p_POGO_I_CPP",
+    "Utc1310p_POGO_O_C",
+    "Utc1310p_POGO_O_CPP",
+    "Linker624",
+    "Cvtomf624",
+    "Export624",
+    "Implib624",
+    "Linker710",
+    "Cvtomf710",
+    "Export710",
+    "Implib710",
+    "Cvtres710",
+    "Utc1310_C",
+    "Utc1310_CPP",
+    "Utc1310_C_Std",
+    "Utc1310_CPP_Std",
+    "Utc1310_LTCG_C",
+    "Utc1310_LTCG_CPP",
+    "Utc1310_POGO_I_C",
+    "Utc1310_POGO_I_CPP",
+    "Utc1310_POGO_O_C",
+    "Utc1310_POGO_O_CPP",
+    "AliasObj710",
+    "AliasObj710p",
+    "Cvtpgd1310",
+    "Cvtpgd1310p",
+    "Utc1400_C",
+    "Utc1400_CPP",
+    "Utc1400_C_Std",
+    "Utc1400_CPP_Std",
+    "Utc1400_LTCG_C",
+    "Utc1400_LTCG_CPP",
+    "Utc1400_POGO_I_C",
+    "Utc1400_POGO_I_CPP",
+    "Utc1400_POGO_O_C",
+    "Utc1400_POGO_O_CPP",
+    "Cvtpgd1400",
+    "Linker800",
+    "Cvtomf800",
+    "Export800",
+    "Implib800",
+    "Cvtres800",
+    "Masm800",
+    "AliasObj800",
+    "PhoenixPrerelease",
+    "Utc1400_CVTCIL_C",
+    "Utc1400_CVTCIL_CPP",
+    "Utc1400_LTCG_MSIL",
+    "Utc1500_C",
+    "Utc1500_CPP",
+    "Utc1500_C_Std",
+    "Utc1500_CPP_Std",
+    "Utc1500_CVTCIL_C",
+    "Utc1500_CVTCIL_CPP",
+    "Utc1500_LTCG_C",
+    "Utc1500_LTCG_CPP",
+    "Utc1500_LTCG_MSIL",
+    "Utc1500_POGO_I_C",
+    "Utc1500_POGO_I_CPP",
+    "Utc1500_POGO_O_C",
+    "Utc1500_POGO_O_CPP",
+    "Cvtpgd1500",
+    "Linker900",
+    "Export900",
+    "Implib900",
+    "Cvtres900",
+    "Masm900",
+    "AliasObj900",
+    "Resource",
+    "AliasObj1000",
+    "Cvtpgd1600",
+    "Cvtres1000",
+    "Export1000",
+    "Implib1000",
+    "Linker1000",
+    "Masm1000",
+    "Phx1600_C",
+    "Phx1600_CPP",
+    "Phx1600_CVTCIL_C",
+    "Phx1600_CVTCIL_CPP",
+    "Phx1600_LTCG_C",
+    "Phx1600_LTCG_CPP",
+    "Phx1600_LTCG_MSIL",
+    "Phx1600_POGO_I_C",
+    "Phx1600_POGO_I_CPP",
+    "Phx1600_POGO_O_C",
+    "Phx1600_POGO_O_CPP",
+    "Utc1600_C",
+    "Utc1600_CPP",
+    "Utc1600_CVTCIL_C",
+    "Utc1600_CVTCIL_CPP",
+    "Utc1600_LTCG_C",
+    "Utc1600_LTCG_CPP",
+    "Utc1600_LTCG_MSIL",
+    "Utc1600_POGO_I_C",
+    "Utc1600_POGO_I_CPP",
+    "Utc1600_POGO_O_C",
+    "Utc1600_POGO_O_CPP",
+    "AliasObj1010",
+    "Cvtpgd1610",
+    "Cvtres1010",
+    "Export1010",
+    "Implib1010",
+    "Linker1010",
+    "Masm1010",
+    "Utc1610_C",
+    "Utc1610_CPP",
+    "Utc1610_CVTCIL_C",
+    "Utc1610_CVTCIL_CPP",
+    "Utc1610_LTCG_C",
+    "Utc1610_LTCG_CPP",
+    "Utc1610_LTCG_MSIL",
+    "Utc1610_POGO_I_C",
+    "Utc1610_POGO_I_CPP",
+    "Utc1610_POGO_O_C",
+    "Utc1610_POGO_O_CPP",
+    "AliasObj1100",
+    "Cvtpgd1700",
+    "Cvtres1100",
+    "Export1100",
+    "Implib1100",
+    "Linker1100",
+    "Masm1100",
+    "Utc1700_C",
+    "Utc1700_CPP",
+    "Utc1700_CVTCIL_C",
+    "Utc1700_CVTCIL_CPP",
+    "Utc1700_LTCG_C",
+    "Utc1700_LTCG_CPP",
+    "Utc1700_LTCG_MSIL",
+    "Utc1700_POGO_I_C",
+    "Utc1700_POGO_I_CPP",
+    "Utc1700_POGO_O_C",
+    "Utc1700_POGO_O_CPP",
+    "AliasObj1200",
+    "Cvtpgd1800",
+    "Cvtres1200",
+    "Export1200",
+    "Implib1200",
+    "Linker1200",
+    "Masm1200",
+    "Utc1800_C",
+    "Utc1800_CPP",
+    "Utc1800_CVTCIL_C",
+    "Utc1800_CVTCIL_CPP",
+    "Utc1800_LTCG_C",
+    "Utc1800_LTCG_CPP",
+    "Utc1800_LTCG_MSIL",
+    "Utc1800_POGO_I_C",
+    "Utc1800_POGO_I_CPP",
+    "Utc1800_POGO_O_C",
+    "Utc1800_POGO_O_CPP",
+    "AliasObj1210",
+    "Cvtpgd1810",
+    "Cvtres1210",
+    "Export1210",
+    "Implib1210",
+    "Linker1210",
+    "Masm1210",
+    "Utc1810_C",
+    "Utc1810_CPP",
+    "Utc1810_CVTCIL_C",
+    "Utc1810_CVTCIL_CPP",
+    "Utc1810_LTCG_C",
+    "Utc1810_LTCG_CPP",
+    "Utc1810_LTCG_MSIL",
+    "Utc1810_POGO_I_C",
+    "Utc1810_POGO_I_CPP",
+    "Utc1810_POGO_O_C",
+    "Utc1810_POGO_O_CPP",
+    "AliasObj1400",
+    "Cvtpgd1900",
+    "Cvtres1400",
+    "Export1400",
+    "Implib1400",
+    "Linker1400",
+    "Masm1400",
+    "Utc1900_C",
+    "Utc1900_CPP",
+    "Utc1900_CVTCIL_C",
+    "Utc1900_CVTCIL_CPP",
+    "Utc1900_LTCG_C",
+    "Utc1900_LTCG_CPP",
+    "Utc1900_LTCG_MSIL",
+    "Utc1900_POGO_I_C",
+    "Utc1900_POGO_I_CPP",
+    "Utc1900_POGO_O_C",
+    "Utc1900_POGO_O_CPP",
+];
+
+/// The Visual Studio releases of the product IDs before Visual Studio 2015, which had their
+/// own IDs for every toolchain
+const PRODUCT_RELEASES: [(u16, u16, &str); 7] = [
+    (0x005a, 0x006c, "Visual Studio .NET 2003"),
+    (0x006d, 0x0082, "Visual Studio 2005"),
+    (0x0083, 0x0096, "Visual Studio 2008"),
+    (0x0098, 0x00b4, "Visual Studio 2010"),
+    (0x00c7, 0x00d8, "Visual Studio 2012"),
+    (0x00d9, 0x00ea, "Visual Studio 2013"),
+    (0x00eb, 0x00fc, "Visual Studio 2013"),
+];
+
+/// The first product ID of the toolchains since Visual Studio 2015, which share the IDs and
+/// tell releases apart by build number
+const VS2015_PRODUCT_ID: u16 = 0x00fd;
+
+/// The first build number of every release since Visual Studio 2015
+const BUILD_RELEASES: [(u16, &str); 39] = [
+    (23026, "Visual Studio 2015"),
+    (23506, "Visual Studio 2015 Update 1"),
+    (23918, "Visual Studio 2015 Update 2"),
+    (24210, "Visual Studio 2015 Update 3"),
+    (25017, "Visual Studio 2017 15.0"),
+    (25506, "Visual Studio 2017 15.3"),
+    (25830, "Visual Studio 2017 15.5"),
+    (26128, "Visual Studio 2017 15.6"),
+    (26428, "Visual Studio 2017 15.7"),
+    (26726, "Visual Studio 2017 15.8"),
+    (27023, "Visual Studio 2017 15.9"),
+    (27508, "Visual Studio 2019 16.0"),
+    (27702, "Visual Studio 2019 16.1"),
+    (27905, "Visual Studio 2019 16.2"),
+    (28105, "Visual Studio 2019 16.3"),
+    (28314, "Visual Studio 2019 16.4"),
+    (28610, "Visual Studio 2019 16.5"),
+    (28805, "Visual Studio 2019 16.6"),
+    (29110, "Visual Studio 2019 16.7"),
+    (29333, "Visual Studio 2019 16.8"),
+    (29910, "Visual Studio 2019 16.9"),
+    (30037, "Visual Studio 2019 16.10"),
+    (30133, "Visual Studio 2019 16.11"),
+    (30705, "Visual Studio 2022 17.0"),
+    (31104, "Visual Studio 2022 17.1"),
+    (31328, "Visual Studio 2022 17.2"),
+    (31629, "Visual Studio 2022 17.3"),
+    (31933, "Visual Studio 2022 17.4"),
+    (32215, "Visual Studio 2022 17.5"),
+    (32532, "Visual Studio 2022 17.6"),
+    (32822, "Visual Studio 2022 17.7"),
+    (33130, "Visual Studio 2022 17.8"),
+    (33519, "Visual Studio 2022 17.9"),
+    (33808, "Visual Studio 2022 17.10"),
+    (34120, "Visual Studio 2022 17.11"),
+    (34433, "Visual Studio 2022 17.12"),
+    (34808, "Visual Studio 2022 17.13"),
+    (35207, "Visual Studio 2022 17.14"),
+    (35717, "Visual Studio 2026 18.0"),
+];
+
+/// A tool that made some of the objects of the image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RichEntry {
+    pub product_id: u16,
+    pub build: u16,
+    /// How many objects the tool made, or for `Import0` how many functions the image imports
+    pub count: u32,
+}
+
+impl RichEntry {
+    pub fn product_name(&self) -> Option<&'static str> {
+        PRODUCT_NAMES.get(self.product_id as usize).copied()
+    }
+
+    /// The Visual Studio release of the tool, if it's a known one
+    pub fn visual_studio(&self) -> Option<&'static str> {
+        if self.product_id >= VS2015_PRODUCT_ID {
+            if self.product_id as usize >= PRODUCT_NAMES.len() {
+                return None;
+            }
+            let position = BUILD_RELEASES.partition_point(|(build, _)| *build <= self.build);
+            return BUILD_RELEASES[..position]
+                .last()
+                .map(|(_, release)| *release);
+        }
+        PRODUCT_RELEASES
+            .iter()
+            .find(|(first, last, _)| (*first..=*last).contains(&self.product_id))
+            .map(|(_, _, release)| *release)
+    }
+}
+
+impl fmt::Display for RichEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.product_name() {
+            Some(name) => write!(f, "{}", name)?,
+            None => write!(f, "Unknown ({:#x})", self.product_id)?,
+        }
+        write!(f, " Build {} Count {}", self.build, self.count)?;
+        if let Some(release) = self.visual_studio() {
+            write!(f, " ({})", release)?;
+        }
+        Ok(())
+    }
+}
+
+/// The Rich header the Microsoft linker puts between the DOS stub and the PE headers
+#[derive(Debug)]
+pub struct RichHeader {
+    /// The file offset of the header
+    pub offset: usize,
+    /// The key the header is XORed with, which is also its checksum
+    pub key: u32,
+    /// The checksum of the DOS header and the entries, which should be the key
+    pub computed_key: u32,
+    pub entries: Vec<RichEntry>,
+}
+
+impl fmt::Display for RichHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Offset: {:#x}", self.offset)?;
+        writeln!(
+            f,
+            "Key: {:#x} ({})",
+            self.key,
+            if self.key == self.computed_key {
+                "Matches Checksum".to_string()
+            } else {
+                format!("Checksum Is {:#x}", self.computed_key)
+            }
+        )?;
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+impl Pe {
+    pub(super) fn parse_rich_header(&self) -> Option<BinDumpResult<RichHeader>> {
+        let stub = self.data.get(..self.dos_header.pe_header_offset as usize)?;
+        // The header ends with "Rich" and the key, on a 4 byte boundary
+        let end = (0..stub.len().saturating_sub(7))
+            .step_by(4)
+            .find(|&i| &stub[i..i + 4] == RICH_SIGNATURE)?;
+        let key = u32::from_le_bytes([stub[end + 4], stub[end + 5], stub[end + 6], stub[end + 7]]);
+        let start = (0..end).step_by(4).rev().find(|&i| {
+            u32::from_le_bytes([stub[i], stub[i + 1], stub[i + 2], stub[i + 3]]) ^ key
+                == DANS_SIGNATURE
+        });
+        let Some(start) = start else {
+            return Some(Err(BinDumpError::ParseError {
+                error: format!("The Rich header at {:#x} has no start", end),
+            }));
+        };
+        // Three zeroed padding words follow the signature
+        let input = stub.get(start + 16..end).unwrap_or_default();
+        let entries = context(
+            "Parse Rich Header",
+            count(tuple((le_u32, le_u32)), input.len() / 8),
+        )(input)
+        .map(|(_, entries)| entries);
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => return Some(Err(e.into())),
+        };
+        let entries = entries
+            .into_iter()
+            .map(|(id, count)| {
+                let id = id ^ key;
+                RichEntry {
+                    product_id: (id >> 16) as u16,
+                    build: id as u16,
+                    count: count ^ key,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut computed_key = start as u32;
+        for (i, &b) in stub[..start].iter().enumerate() {
+            if !(PE_HEADER_OFFSET_FIELD..PE_HEADER_OFFSET_FIELD + 4).contains(&i) {
+                computed_key = computed_key.wrapping_add((b as u32).rotate_left(i as u32));
+            }
+        }
+        for entry in &entries {
+            let id = (entry.product_id as u32) << 16 | entry.build as u32;
+            computed_key = computed_key.wrapping_add(id.rotate_left(entry.count));
+        }
+        Some(Ok(RichHeader {
+            offset: start,
+            key,
+            computed_key,
+            entries,
+        }))
+    }
+
+    pub fn rich_header(&self) -> Option<&RichHeader> {
+        self.rich_header.as_ref()?.as_ref().ok()
+    }
+}
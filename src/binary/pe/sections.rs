@@ -0,0 +1,151 @@
+use std::fmt;
+
+use nom::bytes::complete::take;
+use nom::error::context;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+
+use super::header::flag_names;
+use crate::parse;
+
+/// Names of the `Characteristics` bits of a section header, leaving out the alignment field
+const CHARACTERISTICS: [(u32, &str); 15] = [
+    (0x0000_0008, "TYPE_NO_PAD"),
+    (0x0000_0020, "CNT_CODE"),
+    (0x0000_0040, "CNT_INITIALIZED_DATA"),
+    (0x0000_0080, "CNT_UNINITIALIZED_DATA"),
+    (0x0000_0200, "LNK_INFO"),
+    (0x0000_0800, "LNK_REMOVE"),
+    (0x0000_1000, "LNK_COMDAT"),
+    (0x0000_8000, "GPREL"),
+    (0x0100_0000, "LNK_NRELOC_OVFL"),
+    (0x0200_0000, "MEM_DISCARDABLE"),
+    (0x0400_0000, "MEM_NOT_CACHED"),
+    (0x0800_0000, "MEM_NOT_PAGED"),
+    (0x1000_0000, "MEM_SHARED"),
+    (0x2000_0000, "MEM_EXECUTE"),
+    (0x4000_0000, "MEM_READ"),
+];
+const IMAGE_SCN_MEM_WRITE: u32 = 0x8000_0000;
+/// The `IMAGE_SCN_ALIGN_*` field, which only object files use
+const IMAGE_SCN_ALIGN_MASK: u32 = 0x00f0_0000;
+
+/// An entry of the section table
+#[derive(Debug)]
+pub struct SectionHeader {
+    /// The name, which in object files may be a `/offset` into the string table
+    pub name: String,
+    pub virtual_size: u32,
+    pub virtual_address: u32,
+    pub size_of_raw_data: u32,
+    pub pointer_to_raw_data: u32,
+    pub pointer_to_relocations: u32,
+    pub pointer_to_line_numbers: u32,
+    pub number_of_relocations: u16,
+    pub number_of_line_numbers: u16,
+    pub characteristics: u32,
+}
+
+impl SectionHeader {
+    pub(crate) fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (
+            input,
+            (
+                name,
+                virtual_size,
+                virtual_address,
+                size_of_raw_data,
+                pointer_to_raw_data,
+                pointer_to_relocations,
+                pointer_to_line_numbers,
+                number_of_relocations,
+                number_of_line_numbers,
+                characteristics,
+            ),
+        ) = context(
+            "Parse Section Header",
+            tuple((
+                take(8usize),
+                le_u32,
+                le_u32,
+                le_u32,
+                le_u32,
+                le_u32,
+                le_u32,
+                le_u16,
+                le_u16,
+                le_u32,
+            )),
+        )(input)?;
+        let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+        Ok((
+            input,
+            Self {
+                name: String::from_utf8_lossy(&name[..end]).into_owned(),
+                virtual_size,
+                virtual_address,
+                size_of_raw_data,
+                pointer_to_raw_data,
+                pointer_to_relocations,
+                pointer_to_line_numbers,
+                number_of_relocations,
+                number_of_line_numbers,
+                characteristics,
+            },
+        ))
+    }
+
+    /// Whether an RVA falls in the memory the section takes up
+    pub fn contains_rva(&self, rva: u32) -> bool {
+        let size = if self.virtual_size == 0 {
+            self.size_of_raw_data
+        } else {
+            self.virtual_size
+        };
+        self.virtual_address <= rva && rva - self.virtual_address < size
+    }
+
+    /// The alignment of the section in bytes, if the characteristics give one
+    pub fn alignment(&self) -> Option<u32> {
+        match (self.characteristics & IMAGE_SCN_ALIGN_MASK) >> 20 {
+            0 => None,
+            align => Some(1 << (align - 1)),
+        }
+    }
+
+    pub fn characteristic_names(&self) -> Vec<&'static str> {
+        let mut names = flag_names(self.characteristics, &CHARACTERISTICS);
+        if self.characteristics & IMAGE_SCN_MEM_WRITE != 0 {
+            names.push("MEM_WRITE");
+        }
+        names
+    }
+}
+
+impl fmt::Display for SectionHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Name: {}", self.name)?;
+        writeln!(f, "Virtual Size: {:#x}", self.virtual_size)?;
+        writeln!(f, "Virtual Address: {:#x}", self.virtual_address)?;
+        writeln!(f, "Size Of Raw Data: {:#x}", self.size_of_raw_data)?;
+        writeln!(f, "Pointer To Raw Data: {:#x}", self.pointer_to_raw_data)?;
+        writeln!(
+            f,
+            "Pointer To Relocations: {:#x}",
+            self.pointer_to_relocations
+        )?;
+        writeln!(
+            f,
+            "Pointer To Line Numbers: {:#x}",
+            self.pointer_to_line_numbers
+        )?;
+        writeln!(f, "Number Of Relocations: {}", self.number_of_relocations)?;
+        writeln!(f, "Number Of Line Numbers: {}", self.number_of_line_numbers)?;
+        writeln!(
+            f,
+            "Characteristics: {:#x} ({})",
+            self.characteristics,
+            self.characteristic_names().join(", ")
+        )
+    }
+}
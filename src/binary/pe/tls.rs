@@ -0,0 +1,134 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::number::complete::le_u32;
+use nom::sequence::tuple;
+
+use super::header::{word, DirectoryEntry};
+use super::Pe;
+use crate::error::BinDumpResult;
+
+/// `IMAGE_TLS_DIRECTORY`, whose addresses are virtual addresses and not RVAs
+#[derive(Debug)]
+pub struct TlsDirectory {
+    pub start_of_raw_data: u64,
+    pub end_of_raw_data: u64,
+    pub address_of_index: u64,
+    pub address_of_callbacks: u64,
+    pub size_of_zero_fill: u32,
+    pub characteristics: u32,
+    /// The functions the loader calls on process and thread attach and detach, before the
+    /// entry point runs
+    pub callbacks: Vec<u64>,
+}
+
+impl TlsDirectory {
+    /// The alignment of the template, from the `IMAGE_SCN_ALIGN_*` bits of the characteristics
+    pub fn alignment(&self) -> Option<u32> {
+        match (self.characteristics >> 20) & 0xf {
+            0 => None,
+            n => Some(1 << (n - 1)),
+        }
+    }
+}
+
+impl fmt::Display for TlsDirectory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Start Of Raw Data: {:#x}", self.start_of_raw_data)?;
+        writeln!(f, "End Of Raw Data: {:#x}", self.end_of_raw_data)?;
+        writeln!(f, "Address Of Index: {:#x}", self.address_of_index)?;
+        writeln!(f, "Address Of Callbacks: {:#x}", self.address_of_callbacks)?;
+        writeln!(f, "Size Of Zero Fill: {:#x}", self.size_of_zero_fill)?;
+        write!(f, "Characteristics: {:#x}", self.characteristics)?;
+        match self.alignment() {
+            Some(alignment) => writeln!(f, " (Align {})", alignment)?,
+            None => writeln!(f)?,
+        }
+        writeln!(f, "Callbacks:")?;
+        for callback in &self.callbacks {
+            writeln!(f, "  {:#x}", callback)?;
+        }
+        Ok(())
+    }
+}
+
+impl Pe {
+    pub(super) fn parse_tls(&self) -> Option<BinDumpResult<TlsDirectory>> {
+        let input = self.directory_data(DirectoryEntry::Tls)?;
+        Some(self.parse_tls_directory(input))
+    }
+
+    fn parse_tls_directory(&self, input: &[u8]) -> BinDumpResult<TlsDirectory> {
+        let word = word(self.optional_header.is_64_bit());
+        let (
+            _,
+            (
+                start_of_raw_data,
+                end_of_raw_data,
+                address_of_index,
+                address_of_callbacks,
+                size_of_zero_fill,
+                characteristics,
+            ),
+        ) = context(
+            "Parse TLS Directory",
+            tuple((word, word, word, word, le_u32, le_u32)),
+        )(input)?;
+        // The callback array ends with a null pointer
+        let mut callbacks = Vec::new();
+        let mut input = self.data_at_va(address_of_callbacks).unwrap_or_default();
+        while let Ok((rest, callback)) = word(input) {
+            if callback == 0 {
+                break;
+            }
+            callbacks.push(callback);
+            input = rest;
+        }
+        Ok(TlsDirectory {
+            start_of_raw_data,
+            end_of_raw_data,
+            address_of_index,
+            address_of_callbacks,
+            size_of_zero_fill,
+            characteristics,
+            callbacks,
+        })
+    }
+
+    pub fn tls(&self) -> Option<&TlsDirectory> {
+        self.tls.as_ref()?.as_ref().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::image;
+    use super::*;
+
+    #[test]
+    fn reads_callbacks_until_null() {
+        let mut data = image();
+        data.resize(0x160, 0);
+        for callback in [0x1_4000_1000u64, 0x1_4000_1100, 0, 0x1_4000_1200] {
+            data.extend_from_slice(&callback.to_le_bytes());
+        }
+        let pe = Pe::load(data).unwrap();
+
+        let mut directory = Vec::new();
+        for address in [
+            0x1_4000_2000u64,
+            0x1_4000_2010,
+            0x1_4000_3000,
+            0x1_4000_0160,
+        ] {
+            directory.extend_from_slice(&address.to_le_bytes());
+        }
+        directory.extend_from_slice(&0x20u32.to_le_bytes());
+        directory.extend_from_slice(&0x0030_0000u32.to_le_bytes());
+        let tls = pe.parse_tls_directory(&directory).unwrap();
+        assert_eq!(tls.end_of_raw_data, 0x1_4000_2010);
+        assert_eq!(tls.size_of_zero_fill, 0x20);
+        assert_eq!(tls.alignment(), Some(4));
+        assert_eq!(tls.callbacks, [0x1_4000_1000, 0x1_4000_1100]);
+    }
+}
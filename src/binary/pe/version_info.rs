@@ -0,0 +1,417 @@
+use std::fmt;
+
+use nom::error::context;
+use nom::number::complete::{le_u16, le_u32};
+use nom::sequence::tuple;
+
+use super::resources::utf16_string_nul;
+use crate::error::BinDumpResult;
+use crate::parse::{self, failure};
+
+const VS_FFI_SIGNATURE: u32 = 0xfeef_04bd;
+/// `wType` of blocks whose value is text, with the length counted in UTF-16 code units
+const TEXT_VALUE: u16 = 1;
+
+/// `VS_FIXEDFILEINFO`, the language independent part of the version resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedFileInfo {
+    pub struct_version: u32,
+    pub file_version: u64,
+    pub product_version: u64,
+    pub file_flags_mask: u32,
+    pub file_flags: u32,
+    pub file_os: u32,
+    pub file_type: u32,
+    pub file_subtype: u32,
+    pub file_date: u64,
+}
+
+/// Formats a version kept as four 16 bit parts, most significant first
+fn version_string(version: u64) -> String {
+    format!(
+        "{}.{}.{}.{}",
+        version >> 48,
+        (version >> 32) & 0xffff,
+        (version >> 16) & 0xffff,
+        version & 0xffff
+    )
+}
+
+impl FixedFileInfo {
+    fn parse(input: parse::Input) -> parse::ParseResult<Self> {
+        let (
+            input,
+            (
+                signature,
+                struct_version,
+                file_version_ms,
+                file_version_ls,
+                product_version_ms,
+                product_version_ls,
+                file_flags_mask,
+                file_flags,
+                file_os,
+                file_type,
+                file_subtype,
+                file_date_ms,
+                file_date_ls,
+            ),
+        ) = context(
+            "Parse Fixed File Info",
+            tuple((
+                le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32,
+                le_u32, le_u32, le_u32,
+            )),
+        )(input)?;
+        if signature != VS_FFI_SIGNATURE {
+            return Err(failure(input, "Invalid Fixed File Info Signature"));
+        }
+        let join = |ms: u32, ls: u32| (ms as u64) << 32 | ls as u64;
+        Ok((
+            input,
+            Self {
+                struct_version,
+                file_version: join(file_version_ms, file_version_ls),
+                product_version: join(product_version_ms, product_version_ls),
+                file_flags_mask,
+                file_flags,
+                file_os,
+                file_type,
+                file_subtype,
+                file_date: join(file_date_ms, file_date_ls),
+            },
+        ))
+    }
+
+    pub fn file_version_string(&self) -> String {
+        version_string(self.file_version)
+    }
+
+    pub fn product_version_string(&self) -> String {
+        version_string(self.product_version)
+    }
+}
+
+impl fmt::Display for FixedFileInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "File Version: {}", self.file_version_string())?;
+        writeln!(f, "Product Version: {}", self.product_version_string())?;
+        writeln!(
+            f,
+            "File Flags: {:#x} (Mask {:#x})",
+            self.file_flags, self.file_flags_mask
+        )?;
+        writeln!(f, "File OS: {:#x}", self.file_os)?;
+        writeln!(f, "File Type: {:#x}", self.file_type)?;
+        writeln!(f, "File Subtype: {:#x}", self.file_subtype)?;
+        writeln!(f, "File Date: {:#x}", self.file_date)
+    }
+}
+
+/// The strings of a `StringFileInfo` for one language and code page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringTable {
+    /// The language and code page as 8 hex digits, like `040904b0`
+    pub key: String,
+    pub strings: Vec<(String, String)>,
+}
+
+impl StringTable {
+    pub fn language(&self) -> Option<u16> {
+        u16::from_str_radix(self.key.get(..4)?, 16).ok()
+    }
+
+    pub fn code_page(&self) -> Option<u16> {
+        u16::from_str_radix(self.key.get(4..)?, 16).ok()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A decoded `VS_VERSIONINFO` resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+    pub fixed: Option<FixedFileInfo>,
+    pub string_tables: Vec<StringTable>,
+    /// The language and code page pairs from `VarFileInfo`
+    pub translations: Vec<(u16, u16)>,
+}
+
+/// A node of the version resource: a key, a value, and child nodes laid out the same way
+struct Block<'a> {
+    key: String,
+    typ: u16,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+impl<'a> Block<'a> {
+    /// Parses a block, which starts 4 byte aligned like each part inside it
+    fn parse(input: parse::Input<'a>) -> parse::ParseResult<'a, Self> {
+        let (_, (length, value_length, typ)) =
+            context("Parse Version Block", tuple((le_u16, le_u16, le_u16)))(input)?;
+        if (length as usize) < 6 || length as usize > input.len() {
+            return Err(failure(input, "Invalid Version Block Length"));
+        }
+        let block = &input[..length as usize];
+        let (after_key, key) = context("Parse Version Block Key", utf16_string_nul)(&block[6..])?;
+        let value_start = align4(block.len() - after_key.len()).min(block.len());
+        let value_size = if typ == TEXT_VALUE {
+            value_length as usize * 2
+        } else {
+            value_length as usize
+        };
+        let value_end = (value_start + value_size).min(block.len());
+        let children = &block[align4(value_end).min(block.len())..];
+        let next = &input[align4(length as usize).min(input.len())..];
+        Ok((
+            next,
+            Self {
+                key,
+                typ,
+                value: &block[value_start..value_end],
+                children,
+            },
+        ))
+    }
+
+    /// The blocks in the children area
+    fn children(&self) -> BinDumpResult<Vec<Block<'a>>> {
+        let mut input = self.children;
+        let mut children = Vec::new();
+        while input.len() >= 6 {
+            let (rest, child) = Block::parse(input)?;
+            input = rest;
+            children.push(child);
+        }
+        Ok(children)
+    }
+
+    fn text(&self) -> String {
+        let units: Vec<u16> = self
+            .value
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .take_while(|&unit| unit != 0)
+            .collect();
+        String::from_utf16_lossy(&units)
+    }
+}
+
+impl VersionInfo {
+    pub(super) fn parse(input: parse::Input) -> BinDumpResult<Self> {
+        let (_, root) = Block::parse(input)?;
+        if root.key != "VS_VERSION_INFO" {
+            return Err(failure(input, "Invalid Version Info Key").into());
+        }
+        let fixed = if root.value.is_empty() {
+            None
+        } else {
+            Some(FixedFileInfo::parse(root.value)?.1)
+        };
+
+        let mut string_tables = Vec::new();
+        let mut translations = Vec::new();
+        for child in root.children()? {
+            match child.key.as_str() {
+                "StringFileInfo" => {
+                    for table in child.children()? {
+                        let strings = table
+                            .children()?
+                            .iter()
+                            .map(|string| (string.key.clone(), string.text()))
+                            .collect();
+                        string_tables.push(StringTable {
+                            key: table.key,
+                            strings,
+                        });
+                    }
+                }
+                "VarFileInfo" => {
+                    for var in child.children()? {
+                        if var.key != "Translation" || var.typ == TEXT_VALUE {
+                            continue;
+                        }
+                        translations.extend(var.value.chunks_exact(4).map(|pair| {
+                            (
+                                u16::from_le_bytes([pair[0], pair[1]]),
+                                u16::from_le_bytes([pair[2], pair[3]]),
+                            )
+                        }));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(Self {
+            fixed,
+            string_tables,
+            translations,
+        })
+    }
+
+    /// A string like `FileVersion` or `CompanyName`, from the first table that has it
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.string_tables.iter().find_map(|table| table.get(key))
+    }
+
+    /// The file version from the fixed info, or else from the `FileVersion` string
+    pub fn file_version(&self) -> Option<String> {
+        match &self.fixed {
+            Some(fixed) => Some(fixed.file_version_string()),
+            None => self.get("FileVersion").map(str::to_string),
+        }
+    }
+}
+
+impl fmt::Display for VersionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(fixed) = &self.fixed {
+            write!(f, "{}", fixed)?;
+        }
+        for table in &self.string_tables {
+            writeln!(f, "String Table {}:", table.key)?;
+            for (key, value) in &table.strings {
+                writeln!(f, "  {}: {}", key, value)?;
+            }
+        }
+        for (language, code_page) in &self.translations {
+            writeln!(
+                f,
+                "Translation: Language {:#06x}, Code Page {}",
+                language, code_page
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pad(data: &mut Vec<u8>) {
+        data.resize(align4(data.len()), 0);
+    }
+
+    /// Lays out a block, padded so the next one starts aligned
+    fn block(
+        key: &str,
+        typ: u16,
+        value: &[u8],
+        value_length: u16,
+        children: &[Vec<u8>],
+    ) -> Vec<u8> {
+        let mut data = vec![0; 2];
+        data.extend_from_slice(&value_length.to_le_bytes());
+        data.extend_from_slice(&typ.to_le_bytes());
+        data.extend(key.encode_utf16().chain([0]).flat_map(u16::to_le_bytes));
+        pad(&mut data);
+        data.extend_from_slice(value);
+        pad(&mut data);
+        for child in children {
+            data.extend_from_slice(child);
+        }
+        let length = data.len() as u16;
+        data[..2].copy_from_slice(&length.to_le_bytes());
+        pad(&mut data);
+        data
+    }
+
+    fn text(key: &str, value: &str) -> Vec<u8> {
+        let units: Vec<u16> = value.encode_utf16().chain([0]).collect();
+        let value: Vec<u8> = units.iter().flat_map(|unit| unit.to_le_bytes()).collect();
+        block(key, TEXT_VALUE, &value, units.len() as u16, &[])
+    }
+
+    fn version_resource(fixed: &[u8]) -> Vec<u8> {
+        let strings = block(
+            "StringFileInfo",
+            TEXT_VALUE,
+            &[],
+            0,
+            &[block(
+                "040904b0",
+                TEXT_VALUE,
+                &[],
+                0,
+                &[text("CompanyName", "Example"), text("FileVersion", "5.6")],
+            )],
+        );
+        let translation = block("Translation", 0, &[0x09, 0x04, 0xb0, 0x04], 4, &[]);
+        let vars = block("VarFileInfo", TEXT_VALUE, &[], 0, &[translation]);
+        block(
+            "VS_VERSION_INFO",
+            0,
+            fixed,
+            fixed.len() as u16,
+            &[strings, vars],
+        )
+    }
+
+    fn fixed_file_info() -> Vec<u8> {
+        [
+            VS_FFI_SIGNATURE,
+            0x1_0000,
+            0x0001_0002,
+            0x0003_0004,
+            0x0001_0002,
+            0,
+            0x3f,
+            0,
+            0x4_0004,
+            1,
+            0,
+            0,
+            0,
+        ]
+        .iter()
+        .flat_map(|field| field.to_le_bytes())
+        .collect()
+    }
+
+    #[test]
+    fn parses_fixed_info_strings_and_translations() {
+        let info = VersionInfo::parse(&version_resource(&fixed_file_info())).unwrap();
+        let fixed = info.fixed.unwrap();
+        assert_eq!(fixed.file_version_string(), "1.2.3.4");
+        assert_eq!(fixed.product_version_string(), "1.2.0.0");
+        assert_eq!(fixed.file_type, 1);
+        assert_eq!(info.file_version().as_deref(), Some("1.2.3.4"));
+
+        let table = &info.string_tables[0];
+        assert_eq!(table.language(), Some(0x0409));
+        assert_eq!(table.code_page(), Some(1200));
+        assert_eq!(info.get("CompanyName"), Some("Example"));
+        assert_eq!(info.get("ProductName"), None);
+        assert_eq!(info.translations, [(0x0409, 1200)]);
+    }
+
+    #[test]
+    fn falls_back_to_the_file_version_string() {
+        let info = VersionInfo::parse(&version_resource(&[])).unwrap();
+        assert!(info.fixed.is_none());
+        assert_eq!(info.file_version().as_deref(), Some("5.6"));
+    }
+
+    #[test]
+    fn rejects_bad_key_and_signature() {
+        let mut data = version_resource(&fixed_file_info());
+        // The first character of the key
+        data[6] = b'X';
+        assert!(VersionInfo::parse(&data).is_err());
+
+        let mut fixed = fixed_file_info();
+        fixed[0] = 0;
+        assert!(VersionInfo::parse(&version_resource(&fixed)).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+
+use super::dwarf::frames::Frame;
+use super::elf::program_headers::ProgramType;
+use super::elf::symbols::SymbolType;
+use super::elf::{Elf, FileType};
+use super::mach::load_commands::Command;
+use super::mach::machine::CpuType;
+use super::mach::MachODetails;
+use super::Object;
+use crate::error::{BinDumpError, BinDumpResult};
+
+/// What is known about an address: the nearest symbol and the source frames from DWARF
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbolication {
+    /// The address that was looked up, as given
+    pub address: u64,
+    /// The name of the symbol containing the address and the offset into it
+    pub symbol: Option<(String, u64)>,
+    /// The functions active at the address, innermost first
+    pub frames: Vec<Frame>,
+}
+
+impl fmt::Display for Symbolication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.address)?;
+        match &self.symbol {
+            Some((name, 0)) => writeln!(f, ": {}", name)?,
+            Some((name, offset)) => writeln!(f, ": {}+{:#x}", name, offset)?,
+            None => writeln!(f)?,
+        }
+        for frame in &self.frames {
+            writeln!(f, "  {}", frame)?;
+        }
+        Ok(())
+    }
+}
+
+/// An image that addresses can be looked up in
+enum Image<'a> {
+    Elf(&'a Elf),
+    MachO(&'a MachODetails),
+}
+
+impl Object {
+    /// The image to symbolicate, with `arch` picking the slice of a universal binary and
+    /// defaulting to the host's
+    fn symbolication_image(&self, arch: Option<CpuType>) -> BinDumpResult<Image<'_>> {
+        match self {
+            Object::Elf(elf) => Ok(Image::Elf(elf)),
+            Object::Mach(mach) => {
+                mach.image(arch)
+                    .map(Image::MachO)
+                    .ok_or_else(|| BinDumpError::Unsupported {
+                        format: match arch.or_else(CpuType::host) {
+                            Some(arch) => {
+                                format!("symbolication of Mach-O files without a {} image", arch)
+                            }
+                            None => "symbolication of universal binaries on this host".to_string(),
+                        },
+                    })
+            }
+            _ => Err(BinDumpError::Unsupported {
+                format: "symbolication of files other than ELF and Mach-O".to_string(),
+            }),
+        }
+    }
+
+    /// The address the image is linked at, which a load address is relative to: the start of the
+    /// first `PT_LOAD` segment of an ELF file or the `__TEXT` segment of a Mach-O image
+    pub fn image_base(&self, arch: Option<CpuType>) -> BinDumpResult<u64> {
+        Ok(match self.symbolication_image(arch)? {
+            Image::Elf(elf) => elf_image_base(elf),
+            Image::MachO(details) => mach_image_base(details),
+        })
+    }
+
+    /// Looks up an address as it appears in the file, before any slide or load bias
+    pub fn symbolicate(&self, address: u64, arch: Option<CpuType>) -> BinDumpResult<Symbolication> {
+        let (symbol, frames) = match self.symbolication_image(arch)? {
+            Image::Elf(elf) => (
+                elf_symbol(elf, address),
+                elf.dwarf()
+                    .map(|dwarf| dwarf.find_frames(address))
+                    .unwrap_or_default(),
+            ),
+            Image::MachO(details) => (
+                mach_symbol(details, address),
+                details
+                    .dwarf()
+                    .map(|dwarf| dwarf.find_frames(address))
+                    .unwrap_or_default(),
+            ),
+        };
+        Ok(Symbolication {
+            address,
+            symbol,
+            frames,
+        })
+    }
+
+    /// Looks up a runtime address of the image loaded at `load_address`, which undoes the ELF
+    /// load bias or the Mach-O slide
+    pub fn symbolicate_loaded(
+        &self,
+        address: u64,
+        load_address: u64,
+        arch: Option<CpuType>,
+    ) -> BinDumpResult<Symbolication> {
+        let file_address = address
+            .wrapping_sub(load_address)
+            .wrapping_add(self.image_base(arch)?);
+        Ok(Symbolication {
+            address,
+            ..self.symbolicate(file_address, arch)?
+        })
+    }
+}
+
+fn elf_image_base(elf: &Elf) -> u64 {
+    if elf.header().file_type == FileType::Relocatable {
+        return 0;
+    }
+    elf.program_headers()
+        .iter()
+        .filter(|header| header.typ == ProgramType::Load)
+        .map(|header| match header.align {
+            0 | 1 => header.virtual_address,
+            align => header.virtual_address & !(align - 1),
+        })
+        .min()
+        .unwrap_or(0)
+}
+
+/// The function symbol containing an address, from the full symbol table if it is still there
+fn elf_symbol(elf: &Elf, address: u64) -> Option<(String, u64)> {
+    elf.symbols()
+        .iter()
+        .chain(elf.dynamic_symbols())
+        .filter(|symbol| {
+            matches!(
+                symbol.typ,
+                SymbolType::Function | SymbolType::GnuIndirectFunction
+            )
+        })
+        .find(|symbol| {
+            symbol.value <= address
+                && (address < symbol.value.saturating_add(symbol.size)
+                    || symbol.size == 0 && address == symbol.value)
+        })
+        .map(|symbol| (symbol.name.clone(), address - symbol.value))
+}
+
+fn mach_image_base(details: &MachODetails) -> u64 {
+    details
+        .load_commands()
+        .iter()
+        .find_map(|command| match &command.command {
+            Command::Segment64(segment) if segment.name == "__TEXT" => Some(segment.vm_addr),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+/// The closest symbol at or before an address, as `nlist` entries have no size
+fn mach_symbol(details: &MachODetails, address: u64) -> Option<(String, u64)> {
+    details
+        .symbols()
+        .iter()
+        .filter(|symbol| symbol.is_defined_in_section() && symbol.value <= address)
+        .max_by_key(|symbol| symbol.value)
+        .map(|symbol| (symbol.name.clone(), address - symbol.value))
+}
@@ -19,6 +19,19 @@ pub enum BinDumpError {
     UnknownMagic { magic: Vec<u8> },
     #[error("Parse Error: {error}")]
     ParseError { error: String },
+    #[error("Unsupported Format Error: {format}")]
+    Unsupported { format: String },
+}
+
+impl<'a> From<nom::Err<nom::error::VerboseError<Input<'a>>>> for BinDumpError {
+    fn from(error: nom::Err<nom::error::VerboseError<Input<'a>>>) -> Self {
+        match error {
+            nom::Err::Incomplete(_) => Self::ParseError {
+                error: "Parsing failed: incomplete input".to_string(),
+            },
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.into(),
+        }
+    }
 }
 
 impl<'a> From<nom::error::VerboseError<Input<'a>>> for BinDumpError {
@@ -34,7 +47,8 @@ impl<'a> From<nom::error::VerboseError<Input<'a>>> for BinDumpError {
         writeln!(&mut error_string, "Parsing failed:").expect("Couldn't write to string");
         for (input, err) in error.errors {
             writeln!(&mut error_string, "{:?} at:", err).expect("Couldn't write to string");
-            writeln!(&mut error_string, "{:02x?}", &input[..20]).expect("Couldn't write to string");
+            writeln!(&mut error_string, "{:02x?}", &input[..input.len().min(20)])
+                .expect("Couldn't write to string");
         }
         Self::ParseError {
             error: error_string,
@@ -42,12 +56,13 @@ impl<'a> From<nom::error::VerboseError<Input<'a>>> for BinDumpError {
     }
 }
 /// Useful functions to calculate the offset between slices and show a hexdump of a slice
+#[allow(dead_code)]
 trait Offset {
     /// Offset between the first byte of self and the first byte of the argument
     fn offset(&self, second: &Self) -> usize;
 }
 
-impl<'a> Offset for &'a [u8] {
+impl Offset for &[u8] {
     fn offset(&self, second: &Self) -> usize {
         let fst = self.as_ptr();
         let snd = second.as_ptr();
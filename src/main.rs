@@ -1,9 +1,114 @@
+use std::io::BufRead;
+
+use bindumprs::binary::mach::dyld_cache::DyldCache;
+use bindumprs::binary::mach::machine::CpuType;
 use bindumprs::binary::Object;
+
+/// Parses an address written in hex, with or without a `0x` prefix
+fn parse_address(text: &str) -> Option<u64> {
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    u64::from_str_radix(digits, 16).ok()
+}
+
+/// Symbolicates every address read from stdin, optionally relative to the address the image was
+/// loaded at. `arch` picks the slice of a universal binary, which defaults to the host's.
+fn symbolicate(path: String, load_address: Option<String>, arch: Option<CpuType>) {
+    let obj = match Object::load(path) {
+        Ok(obj) => obj,
+        Err(e) => return println!("Error {}", e),
+    };
+    // Checks up front that there is an image to look addresses up in
+    if let Err(e) = obj.image_base(arch) {
+        return println!("Error {}", e);
+    }
+    let load_address = load_address
+        .map(|text| parse_address(&text).expect("Requires a hex load address after the path"));
+    for line in std::io::stdin().lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return println!("Error {}", e),
+        };
+        for text in line.split_whitespace() {
+            let symbolication = match (parse_address(text), load_address) {
+                (Some(address), Some(load_address)) => {
+                    obj.symbolicate_loaded(address, load_address, arch)
+                }
+                (Some(address), None) => obj.symbolicate(address, arch),
+                (None, _) => {
+                    println!("Error {} is not an address", text);
+                    continue;
+                }
+            };
+            match symbolication {
+                Ok(symbolication) => print!("{}", symbolication),
+                Err(e) => println!("Error {}", e),
+            }
+        }
+    }
+}
+
+/// Prints the image of a dyld shared cache named `name`, along with its local symbols
+fn dump_cache_image(cache: &DyldCache, name: &str) {
+    let Some(image) = cache.find_image(name) else {
+        return println!("Error no image named {} in the cache", name);
+    };
+    match cache.image(image) {
+        Ok(mach) => println!("{}", mach),
+        Err(e) => println!("Error {}", e),
+    }
+    let local_symbols = cache.image_local_symbols(image);
+    if !local_symbols.is_empty() {
+        println!("Local Symbols:");
+    }
+    for symbol in local_symbols {
+        println!("{:#x} {}", symbol.value, symbol.name);
+    }
+}
+
 fn main() {
-    let path = std::env::args().nth(1).expect("Requires one arg");
+    let mut args = std::env::args().skip(1);
+    let first = args.next().expect("Requires one arg");
+    if first == "--symbolicate" {
+        let mut path = args.next().expect("Requires a path after --symbolicate");
+        let mut arch = None;
+        if path == "--arch" {
+            let name = args.next().expect("Requires an arch name after --arch");
+            arch = Some(CpuType::from_arch_name(&name).expect("Requires a known arch name"));
+            path = args.next().expect("Requires a path after the arch name");
+        }
+        return symbolicate(path, args.next(), arch);
+    }
+    let (class_dump, image, path) = if first == "--class-dump" {
+        (
+            true,
+            None,
+            args.next().expect("Requires a path after --class-dump"),
+        )
+    } else if first == "--image" {
+        (
+            false,
+            Some(args.next().expect("Requires an image name after --image")),
+            args.next().expect("Requires a path after the image name"),
+        )
+    } else {
+        (false, None, first)
+    };
     let obj = Object::load(path);
-    match obj {
-        Ok(obj) => println!("{}", obj),
-        Err(e) => println!("Error {}", e),
+    match (obj, image) {
+        (Ok(Object::DyldCache(cache)), Some(name)) => dump_cache_image(&cache, &name),
+        (Ok(_), Some(_)) => println!("Error images can only be extracted from dyld shared caches"),
+        (Ok(Object::Mach(mach)), None) if class_dump => {
+            for objc in mach.objc_metadata() {
+                print!("{}", objc.class_dump());
+            }
+        }
+        (Ok(_), None) if class_dump => {
+            println!("Error class dumps are only supported for Mach-O files")
+        }
+        (Ok(obj), None) => println!("{}", obj),
+        (Err(e), _) => println!("Error {}", e),
     }
 }
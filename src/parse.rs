@@ -1,3 +1,105 @@
+use crate::error::BinDumpError;
+
 pub(crate) type Input<'a> = &'a [u8];
 pub(crate) type ParseResult<'a, O> =
     nom::IResult<Input<'a>, O, nom::error::VerboseError<Input<'a>>>;
+
+/// A parse error that stops parsing, for a value that was read but isn't valid
+pub(crate) fn failure<'a>(
+    input: Input<'a>,
+    message: &'static str,
+) -> nom::Err<nom::error::VerboseError<Input<'a>>> {
+    nom::Err::Failure(nom::error::VerboseError {
+        errors: vec![(input, nom::error::VerboseErrorKind::Context(message))],
+    })
+}
+
+/// The error for a table or header that runs past the end of the file
+pub(crate) fn truncated(what: &str) -> BinDumpError {
+    BinDumpError::ParseError {
+        error: format!("The {} are past the end of the file", what),
+    }
+}
+
+/// Parses an unsigned LEB128 encoded integer
+pub(crate) fn uleb128(input: Input) -> ParseResult<u64> {
+    let mut result = 0u64;
+    let mut shift = 0;
+    for (i, &byte) in input.iter().enumerate() {
+        if shift < 64 {
+            result |= ((byte & 0x7f) as u64) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Ok((&input[i + 1..], result));
+        }
+    }
+    Err(nom::Err::Error(nom::error::VerboseError {
+        errors: vec![(
+            input,
+            nom::error::VerboseErrorKind::Context("Unterminated ULEB128"),
+        )],
+    }))
+}
+
+/// Parses a signed LEB128 encoded integer
+pub(crate) fn sleb128(input: Input) -> ParseResult<i64> {
+    let mut result = 0i64;
+    let mut shift = 0;
+    for (i, &byte) in input.iter().enumerate() {
+        if shift < 64 {
+            result |= ((byte & 0x7f) as i64) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                result |= -1i64 << shift;
+            }
+            return Ok((&input[i + 1..], result));
+        }
+    }
+    Err(nom::Err::Error(nom::error::VerboseError {
+        errors: vec![(
+            input,
+            nom::error::VerboseErrorKind::Context("Unterminated SLEB128"),
+        )],
+    }))
+}
+
+/// Reads the null terminated string at `offset` in a string table
+pub(crate) fn string_at(table: &[u8], offset: usize) -> String {
+    let bytes = table.get(offset..).unwrap_or_default();
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Formats bytes as lowercase hex, without separators
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_uleb128() {
+        assert_eq!(uleb128(&[0x02, 0xaa]), Ok((&[0xaa][..], 2)));
+        assert_eq!(uleb128(&[0x80, 0x01]), Ok((&[][..], 128)));
+        assert_eq!(uleb128(&[0xe5, 0x8e, 0x26]), Ok((&[][..], 624485)));
+        let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        assert_eq!(uleb128(&max), Ok((&[][..], u64::MAX)));
+        assert!(uleb128(&[0x80, 0x80]).is_err());
+    }
+
+    #[test]
+    fn decodes_sleb128() {
+        assert_eq!(sleb128(&[0x02]), Ok((&[][..], 2)));
+        assert_eq!(sleb128(&[0x7f]), Ok((&[][..], -1)));
+        assert_eq!(sleb128(&[0x80, 0x7f]), Ok((&[][..], -128)));
+        assert_eq!(sleb128(&[0xc0, 0xbb, 0x78]), Ok((&[][..], -123456)));
+        let min = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f];
+        assert_eq!(sleb128(&min), Ok((&[][..], i64::MIN)));
+        assert!(sleb128(&[]).is_err());
+    }
+}